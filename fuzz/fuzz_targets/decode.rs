@@ -0,0 +1,45 @@
+#![no_main]
+
+//! Feeds raw bytes to `from_ssz_bytes` for a representative set of types.
+//! Decoding may fail, but it must never panic.
+use alloy_primitives::{Address, U256};
+use bytes::buf::{Buf, BufMut};
+use ethereum_types::H256;
+use libfuzzer_sys::fuzz_target;
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U1024, U16, U2048, U32, U4, U64, U8};
+
+#[derive(Debug, SszbEncode, SszbDecode)]
+struct Container {
+    a: u16,
+    b: VariableList<u64, U16>,
+    c: H256,
+    d: BitList<U64>,
+    e: VariableList<VariableList<u8, U8>, U4>,
+}
+
+fn decode<T: SszbDecode>(data: &[u8]) {
+    let _ = <T as SszbDecode>::from_ssz_bytes(data);
+}
+
+fuzz_target!(|data: &[u8]| {
+    decode::<u8>(data);
+    decode::<u16>(data);
+    decode::<u32>(data);
+    decode::<u64>(data);
+    decode::<bool>(data);
+    decode::<[u8; 32]>(data);
+    decode::<Address>(data);
+    decode::<H256>(data);
+    decode::<U256>(data);
+    decode::<BitVector<U64>>(data);
+    decode::<BitList<U2048>>(data);
+    decode::<VariableList<u64, U1024>>(data);
+    decode::<VariableList<VariableList<u8, U32>, U16>>(data);
+    decode::<FixedVector<VariableList<u8, U8>, U4>>(data);
+    decode::<milhouse::List<u64, U1024>>(data);
+    decode::<milhouse::Vector<u32, U16>>(data);
+    decode::<Container>(data);
+});
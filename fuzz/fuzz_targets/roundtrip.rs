@@ -0,0 +1,69 @@
+#![no_main]
+
+//! Builds structured values with `arbitrary` and checks that encoding agrees with
+//! `sszb_bytes_len` and that decoding the encoding gives back the original value.
+use alloy_primitives::{Address, U256};
+use arbitrary::Arbitrary;
+use bytes::buf::{Buf, BufMut};
+use ethereum_types::H256;
+use libfuzzer_sys::fuzz_target;
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U16, U2048, U32, U4, U64, U8};
+
+#[derive(Debug, PartialEq, SszbEncode, SszbDecode)]
+#[ssz(arbitrary)]
+struct Container {
+    a: u16,
+    b: VariableList<u64, U16>,
+    c: H256,
+    d: BitList<U64>,
+    e: VariableList<VariableList<u8, U8>, U4>,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Input {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    Bool(bool),
+    Bytes32([u8; 32]),
+    Address(Address),
+    H256(H256),
+    U256(U256),
+    BitVector(BitVector<U64>),
+    BitList(BitList<U2048>),
+    List(VariableList<u64, U32>),
+    NestedList(VariableList<VariableList<u8, U32>, U16>),
+    Vector(FixedVector<VariableList<u8, U8>, U4>),
+    Container(Container),
+}
+
+fn roundtrip<T: SszbEncode + SszbDecode + PartialEq + Debug>(item: T) {
+    let bytes = item.to_ssz();
+    assert_eq!(bytes.len(), item.sszb_bytes_len());
+    assert_eq!(<T as SszbDecode>::from_ssz_bytes(&bytes).unwrap(), item);
+}
+
+fuzz_target!(|input: Input| {
+    match input {
+        Input::U8(item) => roundtrip(item),
+        Input::U16(item) => roundtrip(item),
+        Input::U32(item) => roundtrip(item),
+        Input::U64(item) => roundtrip(item),
+        Input::Bool(item) => roundtrip(item),
+        Input::Bytes32(item) => roundtrip(item),
+        Input::Address(item) => roundtrip(item),
+        Input::H256(item) => roundtrip(item),
+        Input::U256(item) => roundtrip(item),
+        Input::BitVector(item) => roundtrip(item),
+        Input::BitList(item) => roundtrip(item),
+        Input::List(item) => roundtrip(item),
+        Input::NestedList(item) => roundtrip(item),
+        Input::Vector(item) => roundtrip(item),
+        Input::Container(item) => roundtrip(item),
+    }
+});
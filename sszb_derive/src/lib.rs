@@ -1,6 +1,6 @@
-use darling::FromMeta;
+use darling::{util::Override, FromMeta};
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, DeriveInput, Ident};
 
 /// Field-level configuration.
@@ -10,354 +10,2965 @@ struct FieldOpts {
     skip_encode: bool,
     #[darling(default)]
     skip_decode: bool,
+    #[darling(default)]
+    allow_asymmetric_skip: bool,
+    #[darling(default, rename = "encode_as")]
+    as_type: Option<String>,
+    #[darling(default)]
+    validate: Option<String>,
+    #[darling(default)]
+    ctx_max: Option<String>,
+    #[darling(default)]
+    placeholder: Option<Override<String>>,
+    #[darling(default)]
+    parallel: bool,
+}
+
+/// Container-level configuration.
+#[derive(Debug, Default, FromMeta)]
+struct ContainerOpts {
+    #[darling(default)]
+    arbitrary: bool,
+    #[darling(default)]
+    parallel: bool,
+    #[darling(default)]
+    lenient_decode: bool,
+    #[darling(default)]
+    enum_behaviour: Option<String>,
+    #[darling(default)]
+    compat: Option<String>,
+    #[darling(default)]
+    pod: bool,
+    #[darling(default)]
+    monomorphize: Option<String>,
+    #[darling(default)]
+    validate: Option<String>,
+    #[darling(default)]
+    zero: bool,
+}
+
+fn parse_container_opts(attrs: &[syn::Attribute]) -> Vec<ContainerOpts> {
+    // possible container options include arbitrary, parallel, lenient_decode, enum_behaviour,
+    // compat, pod, monomorphize, validate and zero
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ssz"))
+        .map(|attr| ContainerOpts::from_meta(&attr.meta).expect("checked by check_ssz_attrs"))
+        .collect()
+}
+
+// the options #[ssz(...)] takes on a container, and on a field
+const CONTAINER_OPTIONS: &[&str] = &[
+    "arbitrary",
+    "parallel",
+    "lenient_decode",
+    "enum_behaviour",
+    "compat",
+    "pod",
+    "monomorphize",
+    "validate",
+    "zero",
+];
+const FIELD_OPTIONS: &[&str] = &[
+    "skip_encode",
+    "skip_decode",
+    "allow_asymmetric_skip",
+    "encode_as",
+    "validate",
+    "ctx_max",
+    "placeholder",
+    "parallel",
+];
+
+// checks every #[ssz(...)] attribute of the input before anything is derived from it, so that an
+// unknown option or a combination that can't work is a compile error pointing at it, rather than
+// a panic or an option that's silently ignored. Each derive runs it first
+fn check_ssz_attrs(derive_input: &DeriveInput) -> syn::Result<()> {
+    let mut errors = vec![];
+    let mut metas = vec![];
+    for attr in ssz_attrs(&derive_input.attrs) {
+        match check_container_attr(attr) {
+            Ok(attr_metas) => metas.extend(attr_metas),
+            Err(e) => errors.push(e),
+        }
+    }
+    // the rest of the checks read the container's options, so they need all of them to be valid
+    if !errors.is_empty() {
+        return Err(combine_errors(errors));
+    }
+    check_container_opts(derive_input, &metas)?;
+    let parallel = metas.iter().any(|meta| meta.path().is_ident("parallel"));
+
+    match &derive_input.data {
+        syn::Data::Struct(struct_data) => {
+            if let syn::Fields::Unnamed(fields) = &struct_data.fields {
+                errors.push(syn::Error::new_spanned(
+                    fields,
+                    "SSZ containers need named fields",
+                ));
+            }
+            for field in &struct_data.fields {
+                if let Err(e) = check_field_attrs(field, parallel) {
+                    errors.push(e);
+                }
+            }
+        }
+        syn::Data::Enum(enum_data) => {
+            let variant_attrs = enum_data.variants.iter().flat_map(|variant| {
+                let field_attrs = variant.fields.iter().flat_map(|field| &field.attrs);
+                variant.attrs.iter().chain(field_attrs)
+            });
+            for attr in ssz_attrs(variant_attrs) {
+                errors.push(syn::Error::new_spanned(
+                    attr,
+                    "#[ssz(...)] options go on the enum itself, not on its variants or their fields",
+                ));
+            }
+            // each kind of enum has its own rules for its variants
+            let variants = match enum_behaviour(&derive_input.attrs) {
+                EnumBehaviour::Union => parse_union_variants(enum_data).map(drop),
+                EnumBehaviour::Transparent => parse_transparent_variants(enum_data).map(drop),
+                EnumBehaviour::Tag => parse_tag_variants(enum_data).map(drop),
+            };
+            if let Err(e) = variants {
+                errors.push(e);
+            }
+        }
+        syn::Data::Union(union_data) => {
+            errors.push(syn::Error::new_spanned(
+                union_data.union_token,
+                "the SSZ derives support structs and enums, not unions",
+            ));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(combine_errors(errors))
+    }
+}
+
+fn combine_errors(errors: Vec<syn::Error>) -> syn::Error {
+    let mut errors = errors.into_iter();
+    let mut first = errors
+        .next()
+        .expect("there's at least one error to combine");
+    errors.for_each(|e| first.combine(e));
+    first
+}
+
+fn ssz_attrs<'a>(
+    attrs: impl IntoIterator<Item = &'a syn::Attribute>,
+) -> impl Iterator<Item = &'a syn::Attribute> {
+    attrs.into_iter().filter(|attr| attr.path().is_ident("ssz"))
+}
+
+// the options of one #[ssz(...)] attribute, each of which has to be one of `valid`
+fn check_options(attr: &syn::Attribute, valid: &[&str], kind: &str) -> syn::Result<Vec<syn::Meta>> {
+    let metas = attr.parse_args_with(
+        syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+    )?;
+    for meta in &metas {
+        let path = meta.path();
+        if !valid.iter().any(|option| path.is_ident(option)) {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!(
+                    "unknown {} option `{}`, expected one of: {}",
+                    kind,
+                    quote!(#path),
+                    valid.join(", ")
+                ),
+            ));
+        }
+    }
+    Ok(metas.into_iter().collect())
+}
+
+// the options of one #[ssz(...)] attribute on the container, which have to be known and parse
+fn check_container_attr(attr: &syn::Attribute) -> syn::Result<Vec<syn::Meta>> {
+    let metas = check_options(attr, CONTAINER_OPTIONS, "container")?;
+    ContainerOpts::from_meta(&attr.meta)
+        .map_err(|e| syn::Error::new_spanned(&attr.meta, e.to_string()))?;
+    Ok(metas)
+}
+
+// a container's options have to fit together and fit the input: `enum_behaviour`, `compat` and
+// `monomorphize` take one of a few values, `validate` names a function, a `pod` container can't
+// be monomorphized and has to be a repr(C) struct with every field encoded as itself, and the
+// options that are about a container's fields don't go on enums
+fn check_container_opts(derive_input: &DeriveInput, metas: &[syn::Meta]) -> syn::Result<()> {
+    let option = |name: &str| metas.iter().rev().find(|meta| meta.path().is_ident(name));
+    let values = |name: &'static str| {
+        metas
+            .iter()
+            .filter(move |meta| meta.path().is_ident(name))
+            .filter_map(string_value)
+    };
+
+    for (name, valid) in [
+        ("enum_behaviour", &["union", "transparent", "tag"][..]),
+        ("compat", &["ethereum_ssz"][..]),
+        ("monomorphize", &["slices"][..]),
+    ] {
+        for value in values(name) {
+            if !valid.contains(&value.value().as_str()) {
+                let valid = valid.iter().map(|value| format!("\"{}\"", value));
+                return Err(syn::Error::new_spanned(
+                    value,
+                    format!(
+                        "`{}` must be one of: {}",
+                        name,
+                        valid.collect::<Vec<_>>().join(", ")
+                    ),
+                ));
+            }
+        }
+    }
+    for value in values("validate") {
+        check_value::<syn::Path>(value, "`validate` must name a function")?;
+    }
+    if let (Some(monomorphize), Some(_)) = (option("monomorphize"), option("pod")) {
+        return Err(syn::Error::new_spanned(
+            monomorphize.path(),
+            "`monomorphize` and `pod` don't go together, a pod container is a single copy \
+             already",
+        ));
+    }
+
+    match &derive_input.data {
+        syn::Data::Struct(struct_data) => match option("pod") {
+            Some(pod) => check_pod(&derive_input.attrs, struct_data, pod),
+            None => Ok(()),
+        },
+        syn::Data::Enum(_) => {
+            for name in [
+                "arbitrary",
+                "parallel",
+                "lenient_decode",
+                "compat",
+                "pod",
+                "monomorphize",
+                "validate",
+                "zero",
+            ] {
+                if let Some(meta) = option(name) {
+                    return Err(syn::Error::new_spanned(
+                        meta.path(),
+                        format!("`{}` only supports structs", name),
+                    ));
+                }
+            }
+            Ok(())
+        }
+        syn::Data::Union(_) => Ok(()),
+    }
+}
+
+// a `pod` struct is copied to and from its bytes as it is in memory, so it has to be laid out
+// as written, and every field has to be in the encoding as itself
+fn check_pod(
+    attrs: &[syn::Attribute],
+    struct_data: &syn::DataStruct,
+    pod: &syn::Meta,
+) -> syn::Result<()> {
+    if !is_repr_c(attrs) {
+        return Err(syn::Error::new_spanned(
+            pod.path(),
+            "`pod` needs the struct to be #[repr(C)]",
+        ));
+    }
+    let field_attrs = struct_data.fields.iter().flat_map(|field| &field.attrs);
+    for attr in ssz_attrs(field_attrs) {
+        for meta in check_options(attr, FIELD_OPTIONS, "field")? {
+            let path = meta.path();
+            if ["skip_encode", "skip_decode", "encode_as", "placeholder"]
+                .iter()
+                .any(|option| path.is_ident(option))
+            {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    format!(
+                        "a `pod` container's fields are all encoded as themselves, so they can't \
+                         have `{}`",
+                        quote!(#path)
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_repr_c(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && attr
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+                )
+                .is_ok_and(|reprs| reprs.iter().any(|repr| repr.path().is_ident("C")))
+    })
+}
+
+// the string an option like `validate = "path"` is set to
+fn string_value(meta: &syn::Meta) -> Option<&syn::LitStr> {
+    match meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(value),
+                    ..
+                }),
+            ..
+        }) => Some(value),
+        _ => None,
+    }
+}
+
+// an option whose string names a path or a type has to parse as one
+fn check_value<T: syn::parse::Parse>(value: &syn::LitStr, message: &str) -> syn::Result<()> {
+    value
+        .parse::<T>()
+        .map(drop)
+        .map_err(|_| syn::Error::new_spanned(value, message))
+}
+
+// a field's options have to be known and parse, and fit together: skipping a field on one side
+// only has to be acknowledged, since the encoding then can't be decoded back, `encode_as` needs
+// the field to be encoded while `validate` and `ctx_max` need it to be decoded, a placeholder
+// takes the place of the field's value on both sides, and `parallel` fields are only split up
+// within a `parallel` container
+fn check_field_attrs(field: &syn::Field, container_parallel: bool) -> syn::Result<()> {
+    let mut metas = vec![];
+    let mut opts = FieldOpts::default();
+    for attr in ssz_attrs(&field.attrs) {
+        metas.extend(check_options(attr, FIELD_OPTIONS, "field")?);
+        let parsed = FieldOpts::from_meta(&attr.meta)
+            .map_err(|e| syn::Error::new_spanned(&attr.meta, e.to_string()))?;
+        opts.skip_encode |= parsed.skip_encode;
+        opts.skip_decode |= parsed.skip_decode;
+        opts.allow_asymmetric_skip |= parsed.allow_asymmetric_skip;
+        opts.as_type = parsed.as_type.or(opts.as_type);
+        opts.validate = parsed.validate.or(opts.validate);
+        opts.ctx_max = parsed.ctx_max.or(opts.ctx_max);
+        opts.placeholder = parsed.placeholder.or(opts.placeholder);
+        opts.parallel |= parsed.parallel;
+    }
+    let option = |name: &str| {
+        metas
+            .iter()
+            .rev()
+            .find(|meta| meta.path().is_ident(name))
+            .map(syn::Meta::path)
+    };
+    let error = |name: &str, message: &str| Err(syn::Error::new_spanned(option(name), message));
+
+    for meta in &metas {
+        let Some(value) = string_value(meta) else {
+            continue;
+        };
+        if meta.path().is_ident("encode_as") {
+            check_value::<syn::Type>(value, "`encode_as` must name a type")?;
+        } else if meta.path().is_ident("validate") {
+            check_value::<syn::Path>(value, "`validate` must name a function")?;
+        } else if meta.path().is_ident("placeholder") {
+            check_value::<syn::Path>(value, "`placeholder` must name a function")?;
+        }
+    }
+
+    if opts.skip_encode != opts.skip_decode && !opts.allow_asymmetric_skip {
+        let (name, message) = if opts.skip_encode {
+            (
+                "skip_encode",
+                "`skip_encode` without `skip_decode` leaves the field out of the encoding but not \
+                 out of decoding, so values don't round-trip; skip both, or add \
+                 `allow_asymmetric_skip` if that's intended",
+            )
+        } else {
+            (
+                "skip_decode",
+                "`skip_decode` without `skip_encode` leaves the field out of decoding but not out \
+                 of the encoding, so values don't round-trip; skip both, or add \
+                 `allow_asymmetric_skip` if that's intended",
+            )
+        };
+        return error(name, message);
+    }
+    if opts.allow_asymmetric_skip && opts.skip_encode == opts.skip_decode {
+        return error(
+            "allow_asymmetric_skip",
+            "`allow_asymmetric_skip` goes with exactly one of `skip_encode` and `skip_decode`",
+        );
+    }
+    if opts.as_type.is_some() && opts.skip_encode && opts.skip_decode {
+        return error(
+            "encode_as",
+            "a field with `skip_encode` and `skip_decode` is never encoded, so `encode_as` has \
+             nothing to convert",
+        );
+    }
+    if opts.validate.is_some() && opts.skip_decode {
+        return error(
+            "validate",
+            "a field with `skip_decode` is never decoded, so `validate` has nothing to check",
+        );
+    }
+    if opts.ctx_max.is_some() && opts.skip_decode {
+        return error(
+            "ctx_max",
+            "a field with `skip_decode` is never decoded, so `ctx_max` has nothing to check",
+        );
+    }
+    if opts.parallel && !container_parallel {
+        return error(
+            "parallel",
+            "a `parallel` field is only encoded and decoded in parallel within a container marked \
+             `#[ssz(parallel)]`",
+        );
+    }
+    if opts.parallel && opts.skip_encode && opts.skip_decode {
+        return error(
+            "parallel",
+            "a field with `skip_encode` and `skip_decode` is never encoded or decoded, so \
+             `parallel` has nothing to split up",
+        );
+    }
+    if opts.placeholder.is_some() {
+        if opts.skip_encode || opts.skip_decode {
+            return error(
+                "placeholder",
+                "a `placeholder` keeps the field's place in the encoding, which `skip_encode` and \
+                 `skip_decode` take it out of",
+            );
+        }
+        for (name, what) in [
+            ("encode_as", "convert"),
+            ("validate", "check"),
+            ("ctx_max", "check"),
+            ("parallel", "split up"),
+        ] {
+            if option(name).is_some() {
+                return error(
+                    name,
+                    &format!(
+                        "a `placeholder` field's value is never encoded or decoded, so `{}` has \
+                         nothing to {}",
+                        name, what
+                    ),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+enum EnumBehaviour {
+    Union,
+    Transparent,
+    Tag,
+}
+
+// enums are unions unless they're marked #[ssz(enum_behaviour = "transparent")] or "tag"
+fn enum_behaviour(attrs: &[syn::Attribute]) -> EnumBehaviour {
+    parse_container_opts(attrs)
+        .iter()
+        .filter_map(|opt| opt.enum_behaviour.as_deref())
+        .map(|behaviour| match behaviour {
+            "union" => EnumBehaviour::Union,
+            "transparent" => EnumBehaviour::Transparent,
+            "tag" => EnumBehaviour::Tag,
+            _ => unreachable!("checked by check_ssz_attrs"),
+        })
+        .next_back()
+        .unwrap_or(EnumBehaviour::Union)
+}
+
+// whether the container is marked #[ssz(compat = "ethereum_ssz")], to also implement the
+// `ethereum_ssz` traits by delegating to the sszb ones
+fn ethereum_ssz_compat(attrs: &[syn::Attribute]) -> bool {
+    parse_container_opts(attrs)
+        .iter()
+        .filter_map(|opt| opt.compat.as_deref())
+        .map(|compat| match compat {
+            "ethereum_ssz" => true,
+            _ => unreachable!("checked by check_ssz_attrs"),
+        })
+        .next_back()
+        .unwrap_or(false)
+}
+
+// whether the container is marked #[ssz(monomorphize = "slices")], to have the bulk of its
+// encoding instantiated only for a `Vec<u8>`, behind thin generic shims, rather than once for
+// every buffer type it's used with. Decoding always goes through the slice-based SszReader
+fn monomorphize_slices(attrs: &[syn::Attribute]) -> bool {
+    parse_container_opts(attrs)
+        .iter()
+        .filter_map(|opt| opt.monomorphize.as_deref())
+        .map(|monomorphize| match monomorphize {
+            "slices" => true,
+            _ => unreachable!("checked by check_ssz_attrs"),
+        })
+        .next_back()
+        .unwrap_or(false)
+}
+
+fn parse_ssz_fields(
+    struct_data: &syn::DataStruct,
+) -> impl Iterator<Item = (&syn::Type, Option<&Ident>, Vec<FieldOpts>)> {
+    struct_data.fields.iter().map(|field| {
+        let ty = &field.ty;
+        let ident = field.ident.as_ref();
+
+        // possible field options include skip_encode, skip_decode, allow_asymmetric_skip,
+        // encode_as, validate, ctx_max, placeholder and parallel
+        let field_opts = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("ssz"))
+            .map(|attr| FieldOpts::from_meta(&attr.meta).expect("checked by check_ssz_attrs"))
+            .collect::<Vec<_>>();
+
+        (ty, ident, field_opts)
+    })
+}
+
+// the checks of #[ssz(validate = "path")] on a decoded `value`: those of the fields, in order,
+// then the container's own. Each hook takes a reference and returns a Result<(), E> with E: Display
+fn validate_stmts(
+    attrs: &[syn::Attribute],
+    struct_data: &syn::DataStruct,
+    name: &Ident,
+) -> Vec<proc_macro2::TokenStream> {
+    let hook =
+        |path: &str| -> syn::Path { syn::parse_str(path).expect("checked by check_ssz_attrs") };
+
+    let mut stmts = vec![];
+    for (_, ident, field_opts) in parse_ssz_fields(struct_data) {
+        for path in field_opts.iter().filter_map(|opt| opt.validate.as_deref()) {
+            let path = hook(path);
+            let what = format!("field `{}` of `{}`", quote!(#ident), name);
+            stmts.push(quote! {
+                sszb::check_decoded(#path(&value.#ident), #what)?;
+            });
+        }
+    }
+    for path in parse_container_opts(attrs)
+        .iter()
+        .filter_map(|opt| opt.validate.as_deref())
+    {
+        let path = hook(path);
+        let what = format!("`{}`", name);
+        stmts.push(quote! {
+            sszb::check_decoded(#path(value), #what)?;
+        });
+    }
+    stmts
+}
+
+// the #[ssz(ctx_max = "name")] check of a field's serialized `bytes` against the installed decode
+// context, made before they're decoded, like the bound of the field's own type
+fn ctx_max_check(
+    field_opts: &[FieldOpts],
+    ty: &syn::Type,
+    bytes: proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    field_opts
+        .iter()
+        .filter_map(|opt| opt.ctx_max.as_deref())
+        .next_back()
+        .map(|limit| quote! { sszb::context::check_context_max::<#ty>(#bytes, #limit) })
+}
+
+// the type named by #[ssz(encode_as = "...")], which the field is encoded as in place of its own
+fn as_type(field_opts: &[FieldOpts]) -> Option<syn::Type> {
+    field_opts
+        .iter()
+        .filter_map(|opt| opt.as_type.as_deref())
+        .next_back()
+        .map(|ty| syn::parse_str(ty).expect("checked by check_ssz_attrs"))
+}
+
+// whether the field is marked #[ssz(parallel)], to be encoded and decoded through
+// SszbEncodeParallel and SszbDecodeParallel within a parallel container
+fn is_parallel(field_opts: &[FieldOpts]) -> bool {
+    field_opts.iter().any(|opt| opt.parallel)
+}
+
+// a field marked #[ssz(placeholder)] keeps its place in the encoding, but its value is neither
+// written nor read: it's encoded as the SSZ zero value of its type, and decoded (the bytes in its
+// place still have to decode as its type) as the type's Default. With
+// #[ssz(placeholder = "path")], it's encoded and decoded as what the function returns instead
+enum Placeholder {
+    Zero,
+    Value(syn::Path),
+}
+
+impl Placeholder {
+    fn encoded(&self, ty: &syn::Type) -> proc_macro2::TokenStream {
+        match self {
+            Placeholder::Zero => quote! { <#ty as sszb::SszZero>::ssz_zero() },
+            Placeholder::Value(path) => quote! { #path() },
+        }
+    }
+
+    fn decoded(&self, ty: &syn::Type) -> proc_macro2::TokenStream {
+        match self {
+            Placeholder::Zero => quote! { <#ty as core::default::Default>::default() },
+            Placeholder::Value(path) => quote! { #path() },
+        }
+    }
+}
+
+fn placeholder(field_opts: &[FieldOpts]) -> Option<Placeholder> {
+    field_opts
+        .iter()
+        .filter_map(|opt| opt.placeholder.as_ref())
+        .next_back()
+        .map(|placeholder| match placeholder {
+            Override::Inherit => Placeholder::Zero,
+            Override::Explicit(path) => {
+                Placeholder::Value(syn::parse_str(path).expect("checked by check_ssz_attrs"))
+            }
+        })
+}
+
+// the field's value converted to its #[ssz(encode_as = "...")] type for encoding, its
+// placeholder, or the field itself
+fn encoded_value(
+    ty: &syn::Type,
+    ident: Option<&Ident>,
+    as_type: Option<&syn::Type>,
+    placeholder: Option<&Placeholder>,
+) -> proc_macro2::TokenStream {
+    if let Some(placeholder) = placeholder {
+        let value = placeholder.encoded(ty);
+        return quote! { &#value };
+    }
+    match as_type {
+        Some(as_type) => quote! {
+            &<#as_type as core::convert::From<#ty>>::from(core::clone::Clone::clone(&self.#ident))
+        },
+        None => quote! { &self.#ident },
+    }
+}
+
+// appended to a Result of the #[ssz(encode_as = "...")] type to convert the decoded value back
+// into the field's own type, or to replace it with the field's placeholder, and empty for fields
+// that are decoded as themselves
+fn decoded_conversion(
+    ty: &syn::Type,
+    ident: &Ident,
+    as_type: Option<&syn::Type>,
+    placeholder: Option<&Placeholder>,
+) -> proc_macro2::TokenStream {
+    if let Some(placeholder) = placeholder {
+        let value = placeholder.decoded(ty);
+        return quote! { .map(|_| #value) };
+    }
+    match as_type {
+        Some(as_type) => {
+            let message = format!(
+                "field `{}` can't be converted from its `{}` encoding",
+                ident,
+                quote!(#as_type).to_string().replace(' ', "")
+            );
+            quote! {
+                .and_then(|value| {
+                    <#ty as core::convert::TryFrom<#as_type>>::try_from(value)
+                        .map_err(|_| sszb::invalid_value(#message))
+                })
+            }
+        }
+        None => quote! {},
+    }
+}
+
+// with #[ssz(pod)], the value of SSZ_POD under `ssz_trait`: whether the container's memory is
+// its SSZ encoding, for it to be copied to and from its bytes in one go. The container has to be
+// repr(C) and bytemuck::Pod, and is then plain old data if its fields are and there's no padding
+// between them. Otherwise, as on big-endian targets, SSZ_POD is false and the fields are written
+// and read one by one as usual
+fn pod_layout(
+    attrs: &[syn::Attribute],
+    struct_data: &syn::DataStruct,
+    ssz_trait: proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    if !parse_container_opts(attrs).iter().any(|opt| opt.pod) {
+        return None;
+    }
+
+    // the struct is repr(C), and its fields are all encoded as themselves (see check_pod)
+    let tys = parse_ssz_fields(struct_data)
+        .map(|(ty, _, _)| ty)
+        .collect::<Vec<_>>();
+    Some(quote! {
+        core::mem::size_of::<Self>() == 0 #(+ core::mem::size_of::<#tys>())*
+            #(&& <#tys as #ssz_trait>::SSZ_POD)*
+    })
+}
+
+// whether a field's type refers back to the type being derived, like `VariableList<Box<Self>, U16>`:
+// `Self` or the derived type's bare name as a type argument, as a type can only contain itself
+// through a pointer or a collection. A same-named type from elsewhere, like
+// `alloy_primitives::Address` in a struct `Address`, is reached through a path, and isn't one.
+// Asking such a field for its lengths while they're being computed would never finish, so a
+// recursive type is always variable-size, with an unbounded (saturated) maximum length.
+fn is_self_referential(ty: &syn::Type, name: &Ident) -> bool {
+    fn refers(ty: &syn::Type, name: &Ident, is_argument: bool) -> bool {
+        match ty {
+            syn::Type::Path(syn::TypePath { qself: None, path }) => {
+                let names_self = is_argument
+                    && path.leading_colon.is_none()
+                    && path.segments.len() == 1
+                    && path.segments[0].arguments.is_empty()
+                    && (path.segments[0].ident == *name || path.segments[0].ident == "Self");
+                names_self
+                    || path
+                        .segments
+                        .iter()
+                        .any(|segment| match &segment.arguments {
+                            syn::PathArguments::AngleBracketed(args) => {
+                                args.args.iter().any(|arg| match arg {
+                                    syn::GenericArgument::Type(ty) => refers(ty, name, true),
+                                    _ => false,
+                                })
+                            }
+                            _ => false,
+                        })
+            }
+            syn::Type::Array(array) => refers(&array.elem, name, is_argument),
+            syn::Type::Slice(slice) => refers(&slice.elem, name, is_argument),
+            syn::Type::Paren(paren) => refers(&paren.elem, name, is_argument),
+            syn::Type::Group(group) => refers(&group.elem, name, is_argument),
+            syn::Type::Tuple(tuple) => tuple.elems.iter().any(|ty| refers(ty, name, is_argument)),
+            _ => false,
+        }
+    }
+    refers(ty, name, false)
+}
+
+// how a self-referential field is described in the schema fingerprint in place of its type's,
+// which is the one being computed: the type as written, with the derived type as `Self`
+fn self_referential_schema(ty: &syn::Type, name: &Ident) -> proc_macro2::TokenStream {
+    let name = name.to_string();
+    let description = quote!(#ty)
+        .to_string()
+        .split_whitespace()
+        .map(|token| if token == name { "Self" } else { token })
+        .collect::<String>();
+    quote! { sszb::schema::recursive(#description.as_bytes()) }
+}
+
+/// How the body of a union variant is serialized after its selector byte.
+enum VariantBody<'a> {
+    /// Nothing, only allowed for the first variant.
+    None,
+    /// The single field of a tuple variant, serialized as itself.
+    Value(&'a syn::Type),
+    /// The named fields of a struct variant, serialized as a container.
+    Container(Vec<(&'a syn::Type, &'a Ident)>),
+}
+
+fn parse_union_variants(
+    enum_data: &syn::DataEnum,
+) -> syn::Result<Vec<(u8, &Ident, VariantBody<'_>)>> {
+    if enum_data.variants.is_empty() || enum_data.variants.len() > 128 {
+        return Err(syn::Error::new(
+            enum_data.brace_token.span.join(),
+            "a union needs between 1 and 128 variants",
+        ));
+    }
+
+    enum_data
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(selector, variant)| {
+            let body = match &variant.fields {
+                syn::Fields::Unit if selector == 0 => VariantBody::None,
+                syn::Fields::Unit => {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "only the first variant of a union can be empty",
+                    ))
+                }
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    VariantBody::Value(&fields.unnamed[0].ty)
+                }
+                syn::Fields::Unnamed(fields) => {
+                    return Err(syn::Error::new_spanned(
+                        fields,
+                        "tuple variants of a union must have exactly one field",
+                    ))
+                }
+                syn::Fields::Named(fields) => VariantBody::Container(
+                    fields
+                        .named
+                        .iter()
+                        .map(|field| (&field.ty, field.ident.as_ref().unwrap()))
+                        .collect(),
+                ),
+            };
+            Ok((selector as u8, &variant.ident, body))
+        })
+        .collect()
+}
+
+// the variants of a transparent enum each wrap one value, which is encoded as itself
+fn parse_transparent_variants(enum_data: &syn::DataEnum) -> syn::Result<Vec<(&Ident, &syn::Type)>> {
+    if enum_data.variants.is_empty() {
+        return Err(syn::Error::new(
+            enum_data.brace_token.span.join(),
+            "a transparent enum needs at least one variant",
+        ));
+    }
+
+    enum_data
+        .variants
+        .iter()
+        .map(|variant| match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                Ok((&variant.ident, &fields.unnamed[0].ty))
+            }
+            _ => Err(syn::Error::new_spanned(
+                variant,
+                "variants of a transparent enum must have exactly one unnamed field",
+            )),
+        })
+        .collect()
+}
+
+// a tag enum's variants are all unit variants, each encoded as its discriminant, which is either
+// an integer literal or one more than the previous variant's, as in Rust
+fn parse_tag_variants(enum_data: &syn::DataEnum) -> syn::Result<Vec<(&Ident, u8)>> {
+    if enum_data.variants.is_empty() || enum_data.variants.len() > 256 {
+        return Err(syn::Error::new(
+            enum_data.brace_token.span.join(),
+            "a tag enum needs between 1 and 256 variants",
+        ));
+    }
+
+    let mut next: u16 = 0;
+    enum_data
+        .variants
+        .iter()
+        .map(|variant| {
+            if !matches!(variant.fields, syn::Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    &variant.fields,
+                    "variants of a tag enum must be unit variants",
+                ));
+            }
+            let tag = match &variant.discriminant {
+                Some((
+                    _,
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(int),
+                        ..
+                    }),
+                )) => int.base10_parse::<u16>().unwrap_or(u16::MAX),
+                Some((_, discriminant)) => {
+                    return Err(syn::Error::new_spanned(
+                        discriminant,
+                        "discriminants of a tag enum must be integer literals",
+                    ))
+                }
+                None => next,
+            };
+            let tag = u8::try_from(tag).map_err(|_| {
+                syn::Error::new_spanned(variant, "discriminants of a tag enum must fit in a u8")
+            })?;
+            next = u16::from(tag) + 1;
+            Ok((&variant.ident, tag))
+        })
+        .collect()
+}
+
+#[proc_macro_derive(SszbEncode, attributes(ssz))]
+pub fn derive_encode(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    if let Err(e) = check_ssz_attrs(&derive_input) {
+        return e.to_compile_error().into();
+    }
+    let struct_data = match derive_input.data {
+        syn::Data::Struct(data) => data,
+        syn::Data::Enum(ref data) => {
+            return match enum_behaviour(&derive_input.attrs) {
+                EnumBehaviour::Union => derive_encode_union(&derive_input, data),
+                EnumBehaviour::Transparent => derive_encode_transparent(&derive_input, data),
+                EnumBehaviour::Tag => derive_encode_tag(&derive_input, data),
+            }
+        }
+        syn::Data::Union(_) => unreachable!("checked by check_ssz_attrs"),
+    };
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let fixed_len_stmts = &mut vec![];
+    let static_stmts = &mut vec![];
+    let bytes_len_stmts = &mut vec![];
+    let check_bounds_stmts = &mut vec![];
+    let max_len_stmts = &mut vec![];
+    let ssz_write_fixed_stmts = &mut vec![];
+    let write_fixed_stmts = &mut vec![];
+    let write_variable_stmts = &mut vec![];
+    let write_variable_vec_stmts = &mut vec![];
+    let split_stmts = &mut vec![];
+    let write_parallel_stmts = &mut vec![];
+    let record_lens_stmts = &mut vec![];
+    let write_cached_fixed_stmts = &mut vec![];
+    let write_cached_variable_stmts = &mut vec![];
+    let write_cached_variable_vec_stmts = &mut vec![];
+    let write_vectored_fixed_stmts = &mut vec![];
+    let write_vectored_variable_stmts = &mut vec![];
+    let write_slice_stmts = &mut vec![];
+    let breakdown_stmts = &mut vec![];
+
+    for (i, (ty, ident, field_opts)) in parse_ssz_fields(&struct_data).enumerate() {
+        if field_opts.iter().any(|opt| opt.skip_encode) {
+            continue;
+        }
+
+        // a field with #[ssz(encode_as = "...")] is converted, and then encoded as that type
+        // throughout, and one with #[ssz(placeholder)] has its placeholder encoded in its place
+        let as_type = as_type(&field_opts);
+        let placeholder = placeholder(&field_opts);
+        let value = encoded_value(ty, ident, as_type.as_ref(), placeholder.as_ref());
+        let ty = as_type.as_ref().unwrap_or(ty);
+
+        // static fields get their slot in the fixed portion of the buffer, variable fields
+        // write their offset there and get their own range of the variable portion
+        let part = format_ident!("part_{}", i);
+        split_stmts.push(quote! {
+            let (slot, rest) = core::mem::take(&mut fixed).split_at_mut(<#ty as sszb::SszbEncode>::SSZ_FIXED_LEN);
+            fixed = rest;
+            let #part = if <#ty as sszb::SszbEncode>::SSZ_STATIC {
+                slot
+            } else {
+                let len = <#ty as sszb::SszbEncode>::sszb_bytes_len(#value);
+                slot.copy_from_slice(&sszb::offset_bytes(offset));
+                offset += len;
+                let (part, rest) = core::mem::take(&mut variable).split_at_mut(len);
+                variable = rest;
+                part
+            };
+        });
+        // within a parallel container, fields marked #[ssz(parallel)] split up their own items too
+        if is_parallel(&field_opts) {
+            write_parallel_stmts.push(quote! {
+                <#ty as sszb::parallel::SszbEncodeParallel>::ssz_write_parallel(#value, #part)
+            });
+        } else {
+            write_parallel_stmts.push(quote! {
+                <#ty as sszb::SszbEncode>::ssz_write_slice(#value, #part);
+            });
+        }
+
+        // the cached write takes the lengths of variable fields from the record made by
+        // ssz_record_lens rather than measuring each field again
+        record_lens_stmts.push(quote! {
+            if !<#ty as sszb::SszbEncode>::SSZ_STATIC {
+                // len is where the field starts, so its offset is checked before anything is
+                // written
+                sszb::check_offset(len);
+                let field_len = <#ty as sszb::SszbEncode>::ssz_record_lens(#value, lens);
+                lens[slot] = field_len;
+                slot += 1;
+                len = sszb::expect_len(
+                    len.checked_add(field_len).ok_or(sszb::EncodeError::LengthOverflow),
+                    core::stringify!(#name),
+                );
+            }
+        });
+        write_cached_fixed_stmts.push(quote! {
+            if <#ty as sszb::SszbEncode>::SSZ_STATIC {
+                <#ty as sszb::SszbEncode>::ssz_write(#value, buf);
+            } else {
+                sszb::expect_offset(sszb::write_offset(offset, buf));
+                offset += field_lens.next().expect("a length is recorded for every variable field");
+            }
+        });
+        write_cached_variable_stmts.push(quote! {
+            if !<#ty as sszb::SszbEncode>::SSZ_STATIC {
+                <#ty as sszb::SszbEncode>::ssz_write_cached(#value, lens, buf);
+            }
+        });
+        write_cached_variable_vec_stmts.push(quote! {
+            if !<#ty as sszb::SszbEncode>::SSZ_STATIC {
+                <#ty as sszb::SszbEncode>::ssz_write_cached_vec(#value, lens, buf);
+            }
+        });
+
+        // each variable field is written where the previous one ended, and its offset is filled
+        // in from where that was, so nothing has to be measured beforehand
+        write_slice_stmts.push(quote! {
+            if <#ty as sszb::SszbEncode>::SSZ_STATIC {
+                position += <#ty as sszb::SszbEncode>::ssz_write_slice(#value, &mut buf[position..]);
+            } else {
+                buf[position..(position + sszb::BYTES_PER_LENGTH_OFFSET)]
+                    .copy_from_slice(&sszb::offset_bytes(offset));
+                position += sszb::BYTES_PER_LENGTH_OFFSET;
+                offset += <#ty as sszb::SszbEncode>::ssz_write_slice(#value, &mut buf[offset..]);
+            }
+        });
+
+        // static fields may hold long byte vectors too, so they're written vectored as well,
+        // but converted values and placeholders don't outlive the call and are copied into the
+        // scratch buffer
+        if as_type.is_some() || placeholder.is_some() {
+            write_vectored_fixed_stmts.push(quote! {
+                <#ty as sszb::SszbEncode>::ssz_write_fixed(#value, &mut offset, segments.scratch());
+            });
+            write_vectored_variable_stmts.push(quote! {
+                <#ty as sszb::SszbEncode>::ssz_write_variable(#value, segments.scratch());
+            });
+        } else {
+            write_vectored_fixed_stmts.push(quote! {
+                if <#ty as sszb::SszbEncode>::SSZ_STATIC {
+                    <#ty as sszb::SszbEncode>::ssz_write_vectored(#value, segments);
+                } else {
+                    <#ty as sszb::SszbEncode>::ssz_write_fixed(#value, &mut offset, segments.scratch());
+                }
+            });
+            write_vectored_variable_stmts.push(quote! {
+                if !<#ty as sszb::SszbEncode>::SSZ_STATIC {
+                    <#ty as sszb::SszbEncode>::ssz_write_vectored(#value, segments);
+                }
+            });
+        }
+
+        if is_self_referential(ty, name) {
+            static_stmts.push(quote! { false });
+            fixed_len_stmts.push(quote! { sszb::BYTES_PER_LENGTH_OFFSET });
+            max_len_stmts.push(quote! { usize::MAX });
+        } else {
+            static_stmts.push(quote! { <#ty as sszb::SszbEncode>::SSZ_STATIC });
+            fixed_len_stmts.push(quote! { <#ty as sszb::SszbEncode>::SSZ_FIXED_LEN });
+            max_len_stmts.push(quote! {
+                sszb::enclosed_max_len(
+                    <#ty as sszb::SszbEncode>::SSZ_STATIC,
+                    <#ty as sszb::SszbEncode>::SSZ_FIXED_LEN,
+                    <#ty as sszb::SszbEncode>::SSZ_MAX_LEN,
+                )
+            });
+        }
+        bytes_len_stmts.push(quote! { <#ty as sszb::SszbEncode>::try_sszb_bytes_len(#value)? });
+        check_bounds_stmts.push(quote! { sszb::check_part_bounds(#value, &mut offset)? });
+
+        // a field's share of the container is its slot in the fixed part, plus its own bytes in
+        // the variable part if it has any, the same sum try_sszb_bytes_len makes
+        let is_static = static_stmts.last();
+        let fixed_len = fixed_len_stmts.last();
+        breakdown_stmts.push(quote! {
+            (
+                core::stringify!(#ident),
+                #fixed_len + if #is_static {
+                    0
+                } else {
+                    <#ty as sszb::SszbEncode>::sszb_bytes_len(#value)
+                },
+            )
+        });
+        ssz_write_fixed_stmts
+            .push(quote! { sszb::debug_check_write_fixed::<#ty>(#value, offset, buf) });
+        write_fixed_stmts
+            .push(quote! { sszb::debug_check_write_fixed::<#ty>(#value, &mut offset, buf) });
+        write_variable_stmts
+            .push(quote! { <#ty as sszb::SszbEncode>::ssz_write_variable(#value, buf) });
+        write_variable_vec_stmts.push(quote! {
+            if !<#ty as sszb::SszbEncode>::SSZ_STATIC {
+                <#ty as sszb::SszbEncode>::ssz_write_vec(#value, buf);
+            }
+        });
+    }
+
+    // with #[ssz(parallel)], the container implements SszbEncodeParallel: every field is written
+    // into its own part of the buffer, all of them concurrently. The closures only capture self
+    // and their parts, so the container has to be Sync, as its type parameters are required to be
+    let container_opts = parse_container_opts(&derive_input.attrs);
+    let write_parallel = write_parallel_stmts
+        .iter()
+        .rev()
+        .map(|stmt| quote! { { #stmt } })
+        .reduce(|rest, stmt| quote! { sszb::join(move || #stmt, move || #rest) });
+    let parallel_impl = if container_opts.iter().any(|opt| opt.parallel) {
+        let mut parallel_generics = derive_input.generics.clone();
+        for param in parallel_generics.type_params_mut() {
+            param.bounds.push(syn::parse_quote!(Sync));
+        }
+        let (parallel_impl_generics, _, _) = parallel_generics.split_for_impl();
+
+        quote! {
+            impl #parallel_impl_generics sszb::parallel::SszbEncodeParallel for #name #ty_generics #where_clause {
+                fn ssz_write_parallel(&self, buf: &mut [u8]) {
+                    let mut offset: usize = const { 0 #(+ #fixed_len_stmts)* };
+                    let (mut fixed, mut variable) = buf.split_at_mut(offset);
+
+                    #(
+                        #split_stmts
+                    )*
+
+                    #write_parallel;
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // with #[ssz(pod)], a container that's plain old data is written as its memory, as are runs
+    // of it in lists and vectors
+    let (pod_const, pod_write, pod_write_slice, pod_write_packed) = match pod_layout(
+        &derive_input.attrs,
+        &struct_data,
+        quote! { sszb::SszbEncode },
+    ) {
+        Some(pod_layout) => (
+            quote! { const SSZ_POD: bool = #pod_layout; },
+            quote! {
+                if <Self as sszb::SszbEncode>::SSZ_POD {
+                    return buf.put_slice(sszb::pod::bytes_of(self));
+                }
+            },
+            quote! {
+                if <Self as sszb::SszbEncode>::SSZ_POD {
+                    let bytes = sszb::pod::bytes_of(self);
+                    buf[..bytes.len()].copy_from_slice(bytes);
+                    return bytes.len();
+                }
+            },
+            quote! {
+                fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+                    if <Self as sszb::SszbEncode>::SSZ_POD {
+                        buf.put_slice(sszb::pod::slice_bytes(items));
+                    } else {
+                        for item in items {
+                            <Self as sszb::SszbEncode>::ssz_write(item, buf);
+                        }
+                    }
+                }
+            },
+        ),
+        None => (quote! {}, quote! {}, quote! {}, quote! {}),
+    };
+
+    // the variable fields are written with ssz_write_variable and ssz_write_cached, or with
+    // their Vec counterparts when the buffer is known to be a Vec
+    let write_body = |write_variable_stmts: &[proc_macro2::TokenStream]| {
+        quote! {
+            sszb::enter_trace_span!("ssz_encode", ty = core::stringify!(#name));
+
+            // offset is the length of all fixed size items
+            // this lets us point dynamic length items to the area *after* the fixed size items
+            let mut offset: usize = const { 0 #(+ #fixed_len_stmts)* };
+
+            // first we write the fixed portion of each field in self
+            // offset is passed to the write_fixed call in case we point to variable-sized data
+            #(
+                #write_fixed_stmts;
+            )*
+
+            // then we write the variable portion into the buffer
+            #(
+                #write_variable_stmts;
+            )*
+        }
+    };
+    let write_cached_body = |write_cached_variable_stmts: &[proc_macro2::TokenStream]| {
+        quote! {
+            sszb::enter_trace_span!("ssz_encode", ty = core::stringify!(#name));
+
+            let (field_lens, rest) = lens.split_at(const { 0 #(+ (!#static_stmts) as usize)* });
+            *lens = rest;
+            let mut field_lens = field_lens.iter();
+            let mut offset: usize = const { 0 #(+ #fixed_len_stmts)* };
+            #(
+                #write_cached_fixed_stmts
+            )*
+            #(
+                #write_cached_variable_stmts
+            )*
+        }
+    };
+
+    // with #[ssz(monomorphize = "slices")], the fields are only ever written into a Vec, by
+    // functions of the container's own that every buffer type shares. to_ssz, to_ssz_with_vec and
+    // monomorphized containers and lists holding this one write straight into their Vec, while
+    // writing into anything else goes through a scratch Vec, at the cost of a copy of the
+    // container's encoding. A static container is written in a single pass of its fields already,
+    // so it can't be monomorphized
+    let (write_fn_body, write_cached_fn_body, monomorphized_fns, write_vec_fns) =
+        if monomorphize_slices(&derive_input.attrs) {
+            let not_static = quote! {
+                assert!(
+                    !<#name #ty_generics as sszb::SszbEncode>::SSZ_STATIC,
+                    concat!(
+                        "`",
+                        stringify!(#name),
+                        "` is static, so it can't be monomorphized: it's written in a single \
+                         pass of its fields already",
+                    ),
+                )
+            };
+            // a container without type parameters is checked when it's defined, a generic one
+            // when it's written
+            let not_static_item = if derive_input.generics.params.is_empty() {
+                quote! { const _: () = #not_static; }
+            } else {
+                quote! {}
+            };
+            let write_vec_body = write_body(write_variable_vec_stmts);
+            let write_cached_vec_body = write_cached_body(write_cached_variable_vec_stmts);
+            (
+                quote! {
+                    let mut scratch =
+                        sszb::Vec::with_capacity(<Self as sszb::SszbEncode>::sszb_bytes_len(self));
+                    Self::__sszb_write_vec(self, &mut scratch);
+                    buf.put_slice(&scratch);
+                },
+                quote! {
+                    // the container's length is its fixed part plus the recorded lengths of its
+                    // variable fields, which come first in lens, so it isn't measured again
+                    let num_variable = const { 0 #(+ (!#static_stmts) as usize)* };
+                    let mut scratch = sszb::Vec::with_capacity(
+                        const { 0 #(+ #fixed_len_stmts)* }
+                            + lens[..num_variable].iter().sum::<usize>(),
+                    );
+                    Self::__sszb_write_cached_vec(self, lens, &mut scratch);
+                    buf.put_slice(&scratch);
+                },
+                quote! {
+                    #not_static_item
+
+                    impl #impl_generics #name #ty_generics #where_clause {
+                        fn __sszb_write_vec(&self, buf: &mut sszb::Vec<u8>) {
+                            const { #not_static };
+                            #write_vec_body
+                        }
+
+                        fn __sszb_write_cached_vec(&self, lens: &mut &[usize], buf: &mut sszb::Vec<u8>) {
+                            const { #not_static };
+                            #write_cached_vec_body
+                        }
+                    }
+                },
+                quote! {
+                    fn ssz_write_vec(&self, buf: &mut sszb::Vec<u8>) {
+                        sszb::debug_check_written(
+                            buf,
+                            core::stringify!(#name),
+                            "ssz_write_vec",
+                            || <Self as sszb::SszbEncode>::sszb_bytes_len(self),
+                            |buf| Self::__sszb_write_vec(self, buf),
+                        );
+                    }
+
+                    fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut sszb::Vec<u8>) {
+                        sszb::debug_check_written(
+                            buf,
+                            core::stringify!(#name),
+                            "ssz_write_cached_vec",
+                            || <Self as sszb::SszbEncode>::sszb_bytes_len(self),
+                            |buf| Self::__sszb_write_cached_vec(self, lens, buf),
+                        );
+                    }
+                },
+            )
+        } else {
+            (
+                write_body(write_variable_stmts),
+                write_cached_body(write_cached_variable_stmts),
+                quote! {},
+                quote! {},
+            )
+        };
+
+    // only expanded with sszb's `introspection` feature
+    let breakdown_fn = quote! {
+        sszb::if_introspection! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Each encoded field with its share of `sszb_bytes_len()`, which the shares sum
+                /// to: its own bytes, plus the offset a variable field takes in the fixed part.
+                /// Skipped fields aren't encoded, so they aren't listed.
+                pub fn ssz_size_breakdown(&self) -> sszb::Vec<(&'static str, usize)> {
+                    sszb::Vec::from([#(
+                        #breakdown_stmts,
+                    )*])
+                }
+            }
+        }
+    };
+
+    let output = quote! {
+        #monomorphized_fns
+
+        #breakdown_fn
+
+        impl #impl_generics sszb::SszbEncode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = #(
+                #static_stmts &&
+            )*
+                true;
+
+            const SSZ_FIXED_LEN: usize = if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                0 #(
+                    + #fixed_len_stmts
+                )*
+            } else {
+                sszb::BYTES_PER_LENGTH_OFFSET
+            };
+
+            // saturates rather than overflowing for very large (or unbounded) types
+            const SSZ_MAX_LEN: usize = 0usize #(
+                .saturating_add(#max_len_stmts)
+            )*;
+
+            #pod_const
+
+            fn sszb_bytes_len(&self) -> usize {
+                sszb::expect_len(
+                    <Self as sszb::SszbEncode>::try_sszb_bytes_len(self),
+                    core::stringify!(#name),
+                )
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, sszb::EncodeError> {
+                if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                    Ok(<Self as sszb::SszbEncode>::SSZ_FIXED_LEN)
+                } else {
+                    // the fixed portion, offsets included, is known at compile time, so only
+                    // the variable fields need to be measured
+                    let mut len: usize = const { 0 #(+ #fixed_len_stmts)* };
+                    #(
+                        if !#static_stmts {
+                            len = len
+                                .checked_add(#bytes_len_stmts)
+                                .ok_or(sszb::EncodeError::LengthOverflow)?;
+                        }
+                    )*
+
+                    Ok(len)
+                }
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), sszb::EncodeError> {
+                // static fields can't hold a list, or an offset. the variable ones start after
+                // the fixed part, each where the one before it ends
+                let mut offset: usize = const { 0 #(+ #fixed_len_stmts)* };
+                #(
+                    if !#static_stmts {
+                        #check_bounds_stmts;
+                    }
+                )*
+                Ok(())
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                // if self is fixed-sized then write the data outright
+                // or else we write the offset to the buffer and point to the end of the buffer
+                sszb::debug_check_written(
+                    buf,
+                    core::stringify!(#name),
+                    "ssz_write_fixed",
+                    || <Self as sszb::SszbEncode>::SSZ_FIXED_LEN,
+                    |buf| {
+                        if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                            #pod_write
+                            #(
+                                #ssz_write_fixed_stmts;
+                            )*
+                        } else {
+                            sszb::expect_offset(sszb::write_offset(*offset, buf));
+                            *offset += <Self as sszb::SszbEncode>::sszb_bytes_len(self);
+                        }
+                    },
+                );
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                if !<Self as sszb::SszbEncode>::SSZ_STATIC {
+                    <Self as sszb::SszbEncode>::ssz_write(self, buf);
+                }
+            }
+
+            // using this function direct is most efficient with a &mut [u8]
+            // the slice must have enough capacity, otherwise it will panic on failure
+            // if used with a Vec<u8>, capacity may be increased automatically if needed
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                // debug builds check that the lengths the offsets were worked out from are the
+                // lengths actually written
+                sszb::debug_check_written(
+                    buf,
+                    core::stringify!(#name),
+                    "ssz_write",
+                    || <Self as sszb::SszbEncode>::sszb_bytes_len(self),
+                    |buf| {
+                        #pod_write
+                        #write_fn_body
+                    },
+                );
+            }
+
+            fn ssz_write_vectored<'segments>(&'segments self, segments: &mut sszb::vectored::Segments<'segments>) {
+                let mut offset: usize = const { 0 #(+ #fixed_len_stmts)* };
+                #(
+                    #write_vectored_fixed_stmts
+                )*
+                #(
+                    #write_vectored_variable_stmts
+                )*
+            }
+
+            fn ssz_record_lens(&self, lens: &mut sszb::Vec<usize>) -> usize {
+                if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                    return <Self as sszb::SszbEncode>::SSZ_FIXED_LEN;
+                }
+
+                // a slot for the length of each variable field, followed by the fields' records
+                let mut slot = lens.len();
+                lens.resize(slot + const { 0 #(+ (!#static_stmts) as usize)* }, 0);
+                let mut len: usize = const { 0 #(+ #fixed_len_stmts)* };
+                #(
+                    #record_lens_stmts
+                )*
+
+                len
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+                if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                    return <Self as sszb::SszbEncode>::ssz_write(self, buf);
+                }
+                sszb::debug_check_written(
+                    buf,
+                    core::stringify!(#name),
+                    "ssz_write_cached",
+                    || <Self as sszb::SszbEncode>::sszb_bytes_len(self),
+                    |buf| {
+                        #write_cached_fn_body
+                    },
+                );
+            }
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                #pod_write_slice
+                let mut position: usize = 0;
+                let mut offset: usize = const { 0 #(+ #fixed_len_stmts)* };
+                #(
+                    #write_slice_stmts
+                )*
+
+                offset
+            }
+
+            #pod_write_packed
+
+            #write_vec_fns
+        }
+
+        #parallel_impl
+    };
+
+    if !ethereum_ssz_compat(&derive_input.attrs) {
+        return output.into();
+    }
+
+    let output = quote! {
+        #output
+
+        impl #impl_generics sszb::ethereum_ssz::Encode for #name #ty_generics #where_clause {
+            fn is_ssz_fixed_len() -> bool {
+                <Self as sszb::SszbEncode>::SSZ_STATIC
+            }
+
+            fn ssz_fixed_len() -> usize {
+                <Self as sszb::SszbEncode>::SSZ_FIXED_LEN
+            }
+
+            fn ssz_append(&self, buf: &mut sszb::Vec<u8>) {
+                <Self as sszb::SszbEncode>::ssz_write(self, buf);
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                <Self as sszb::SszbEncode>::sszb_bytes_len(self)
+            }
+        }
+    };
+    output.into()
+}
+
+// a union is its variant's selector byte followed by the variant's body, where struct variants
+// are laid out like a container of their fields
+fn derive_encode_union(derive_input: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let max_len_stmts = &mut vec![];
+    let bytes_len_arms = &mut vec![];
+    let check_bounds_arms = &mut vec![];
+    let write_arms = &mut vec![];
+
+    for (selector, variant, body) in
+        parse_union_variants(enum_data).expect("checked by check_ssz_attrs")
+    {
+        match body {
+            VariantBody::None => {
+                max_len_stmts.push(quote! { 0 });
+                bytes_len_arms.push(quote! { Self::#variant => Ok(1) });
+                check_bounds_arms.push(quote! { Self::#variant => Ok(()) });
+                write_arms.push(quote! { Self::#variant => buf.put_u8(#selector) });
+            }
+            VariantBody::Value(ty) => {
+                max_len_stmts.push(if is_self_referential(ty, name) {
+                    quote! { usize::MAX }
+                } else {
+                    quote! { <#ty as sszb::SszbEncode>::SSZ_MAX_LEN }
+                });
+                bytes_len_arms.push(quote! {
+                    Self::#variant(value) => <#ty as sszb::SszbEncode>::try_sszb_bytes_len(value)?
+                        .checked_add(1)
+                        .ok_or(sszb::EncodeError::LengthOverflow)
+                });
+                check_bounds_arms.push(quote! {
+                    Self::#variant(value) => <#ty as sszb::SszbEncode>::ssz_check_bounds(value)
+                });
+                write_arms.push(quote! {
+                    Self::#variant(value) => {
+                        buf.put_u8(#selector);
+                        <#ty as sszb::SszbEncode>::ssz_write(value, buf);
+                    }
+                });
+            }
+            VariantBody::Container(fields) => {
+                // fields are bound to names of our own, so they can't shadow the locals below
+                let idents = fields.iter().map(|(_, ident)| ident).collect::<Vec<_>>();
+                let tys = fields.iter().map(|(ty, _)| ty).collect::<Vec<_>>();
+                let bindings = (0..fields.len())
+                    .map(|i| format_ident!("field_{}", i))
+                    .collect::<Vec<_>>();
+
+                max_len_stmts.push(if tys.iter().any(|ty| is_self_referential(ty, name)) {
+                    quote! { usize::MAX }
+                } else {
+                    quote! {
+                        0usize #(
+                            .saturating_add(sszb::enclosed_max_len(
+                                <#tys as sszb::SszbEncode>::SSZ_STATIC,
+                                <#tys as sszb::SszbEncode>::SSZ_FIXED_LEN,
+                                <#tys as sszb::SszbEncode>::SSZ_MAX_LEN,
+                            ))
+                        )*
+                    }
+                });
+                bytes_len_arms.push(quote! {
+                    Self::#variant { #(#idents: #bindings),* } => {
+                        let mut len: usize = const { 1 #(+ <#tys as sszb::SszbEncode>::SSZ_FIXED_LEN)* };
+                        #(
+                            if !<#tys as sszb::SszbEncode>::SSZ_STATIC {
+                                len = len
+                                    .checked_add(<#tys as sszb::SszbEncode>::try_sszb_bytes_len(#bindings)?)
+                                    .ok_or(sszb::EncodeError::LengthOverflow)?;
+                            }
+                        )*
+                        Ok(len)
+                    }
+                });
+                check_bounds_arms.push(quote! {
+                    Self::#variant { #(#idents: #bindings),* } => {
+                        let mut offset: usize = const { 0 #(+ <#tys as sszb::SszbEncode>::SSZ_FIXED_LEN)* };
+                        #(
+                            if !<#tys as sszb::SszbEncode>::SSZ_STATIC {
+                                sszb::check_part_bounds(#bindings, &mut offset)?;
+                            }
+                        )*
+                        Ok(())
+                    }
+                });
+                write_arms.push(quote! {
+                    Self::#variant { #(#idents: #bindings),* } => {
+                        buf.put_u8(#selector);
+                        let mut offset: usize = const { 0 #(+ <#tys as sszb::SszbEncode>::SSZ_FIXED_LEN)* };
+                        #(
+                            sszb::debug_check_write_fixed::<#tys>(#bindings, &mut offset, buf);
+                        )*
+                        #(
+                            <#tys as sszb::SszbEncode>::ssz_write_variable(#bindings, buf);
+                        )*
+                    }
+                });
+            }
+        }
+    }
+
+    let output = quote! {
+        impl #impl_generics sszb::SszbEncode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = false;
+
+            const SSZ_FIXED_LEN: usize = sszb::BYTES_PER_LENGTH_OFFSET;
+
+            // the selector, followed by the largest variant
+            const SSZ_MAX_LEN: usize = {
+                let mut max_len: usize = 0;
+                #(
+                    let len = #max_len_stmts;
+                    if len > max_len {
+                        max_len = len;
+                    }
+                )*
+                max_len.saturating_add(1)
+            };
+
+            fn sszb_bytes_len(&self) -> usize {
+                sszb::expect_len(
+                    <Self as sszb::SszbEncode>::try_sszb_bytes_len(self),
+                    core::stringify!(#name),
+                )
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, sszb::EncodeError> {
+                match self {
+                    #(
+                        #bytes_len_arms,
+                    )*
+                }
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), sszb::EncodeError> {
+                match self {
+                    #(
+                        #check_bounds_arms,
+                    )*
+                }
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                sszb::expect_offset(sszb::write_offset(*offset, buf));
+                *offset += <Self as sszb::SszbEncode>::sszb_bytes_len(self);
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                <Self as sszb::SszbEncode>::ssz_write(self, buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                sszb::debug_check_written(
+                    buf,
+                    core::stringify!(#name),
+                    "ssz_write",
+                    || <Self as sszb::SszbEncode>::sszb_bytes_len(self),
+                    |buf| match self {
+                        #(
+                            #write_arms,
+                        )*
+                    },
+                );
+            }
+        }
+    };
+
+    output.into()
+}
+
+// a transparent enum is encoded as its active variant, without a selector, so decoding needs to
+// be told which variant to expect
+fn derive_encode_transparent(derive_input: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let variants = parse_transparent_variants(enum_data).expect("checked by check_ssz_attrs");
+    let idents = variants.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let tys = variants.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+    let first_ty = tys[0];
+    // static only if every variant takes up the same number of bytes
+    let static_stmts = tys.iter().map(|ty| {
+        quote! {
+            <#ty as sszb::SszbEncode>::SSZ_STATIC
+                && <#ty as sszb::SszbEncode>::SSZ_FIXED_LEN == <#first_ty as sszb::SszbEncode>::SSZ_FIXED_LEN
+        }
+    });
+
+    let output = quote! {
+        impl #impl_generics sszb::SszbEncode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = #(
+                #static_stmts &&
+            )*
+                true;
+
+            const SSZ_FIXED_LEN: usize = if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                <#first_ty as sszb::SszbEncode>::SSZ_FIXED_LEN
+            } else {
+                sszb::BYTES_PER_LENGTH_OFFSET
+            };
+
+            const SSZ_MAX_LEN: usize = {
+                let mut max_len: usize = 0;
+                #(
+                    if <#tys as sszb::SszbEncode>::SSZ_MAX_LEN > max_len {
+                        max_len = <#tys as sszb::SszbEncode>::SSZ_MAX_LEN;
+                    }
+                )*
+                max_len
+            };
+
+            fn sszb_bytes_len(&self) -> usize {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::sszb_bytes_len(value),
+                    )*
+                }
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, sszb::EncodeError> {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::try_sszb_bytes_len(value),
+                    )*
+                }
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), sszb::EncodeError> {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::ssz_check_bounds(value),
+                    )*
+                }
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                if <Self as sszb::SszbEncode>::SSZ_STATIC {
+                    <Self as sszb::SszbEncode>::ssz_write(self, buf);
+                } else {
+                    sszb::expect_offset(sszb::write_offset(*offset, buf));
+                    *offset += <Self as sszb::SszbEncode>::sszb_bytes_len(self);
+                }
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                if !<Self as sszb::SszbEncode>::SSZ_STATIC {
+                    <Self as sszb::SszbEncode>::ssz_write(self, buf);
+                }
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::ssz_write(value, buf),
+                    )*
+                }
+            }
+
+            fn ssz_write_vectored<'segments>(&'segments self, segments: &mut sszb::vectored::Segments<'segments>) {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::ssz_write_vectored(value, segments),
+                    )*
+                }
+            }
+
+            fn ssz_record_lens(&self, lens: &mut sszb::Vec<usize>) -> usize {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::ssz_record_lens(value, lens),
+                    )*
+                }
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+                match self {
+                    #(
+                        Self::#idents(value) => <#tys as sszb::SszbEncode>::ssz_write_cached(value, lens, buf),
+                    )*
+                }
+            }
+        }
+    };
+
+    output.into()
+}
+
+// a tag enum is encoded as a single byte, its variant's discriminant
+fn derive_encode_tag(derive_input: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let (idents, tags): (Vec<_>, Vec<_>) = parse_tag_variants(enum_data)
+        .expect("checked by check_ssz_attrs")
+        .into_iter()
+        .unzip();
+
+    let output = quote! {
+        impl #impl_generics sszb::SszbEncode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = true;
+
+            const SSZ_FIXED_LEN: usize = 1;
+
+            const SSZ_MAX_LEN: usize = 1;
+
+            fn sszb_bytes_len(&self) -> usize {
+                1
+            }
+
+            fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+                <Self as sszb::SszbEncode>::ssz_write(self, buf);
+            }
+
+            fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                buf.put_u8(match self {
+                    #(
+                        Self::#idents => #tags,
+                    )*
+                });
+            }
+        }
+    };
+
+    output.into()
 }
 
-fn parse_ssz_fields(
-    struct_data: &syn::DataStruct,
-) -> impl Iterator<Item = (&syn::Type, Option<&Ident>, Vec<FieldOpts>)> {
-    struct_data.fields.iter().map(|field| {
-        let ty = &field.ty;
-        let ident = field.ident.as_ref();
+#[proc_macro_derive(SszbDecode, attributes(ssz))]
+pub fn derive_decode(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    if let Err(e) = check_ssz_attrs(&derive_input) {
+        return e.to_compile_error().into();
+    }
+    let struct_data = match derive_input.data {
+        syn::Data::Struct(data) => data,
+        syn::Data::Enum(ref data) => {
+            return match enum_behaviour(&derive_input.attrs) {
+                EnumBehaviour::Union => derive_decode_union(&derive_input, data),
+                EnumBehaviour::Transparent => derive_decode_transparent(&derive_input, data),
+                EnumBehaviour::Tag => derive_decode_tag(&derive_input, data),
+            }
+        }
+        syn::Data::Union(_) => unreachable!("checked by check_ssz_attrs"),
+    };
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let fixed_len_stmts = &mut vec![];
+    let static_stmts = &mut vec![];
+    let max_len_stmts = &mut vec![];
+    let schema_stmts = &mut vec![];
+    let read_stmts = &mut vec![];
+    let read_fixed_stmts = &mut vec![];
+    let read_variable_stmts = &mut vec![];
+    let decode_field_fns = &mut vec![];
+    let read_trusted_stmts = &mut vec![];
+    let read_into_stmts = &mut vec![];
+    let read_lenient_stmts = &mut vec![];
+    let read_fixed_parallel_stmts = &mut vec![];
+    let read_variable_parallel_stmts = &mut vec![];
+    let validate_arms = &mut vec![];
+
+    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
+        let ident = ident.expect("checked by check_ssz_attrs");
+
+        if field_opts.iter().any(|opt| opt.skip_decode) {
+            // should deserialize default
+            read_stmts.push(quote! { #ident: <_>::default() });
+            read_trusted_stmts.push(quote! { #ident: <_>::default() });
+            read_into_stmts.push(quote! {
+                self.#ident = <_>::default();
+            });
+            read_lenient_stmts.push(quote! { #ident: <_>::default() });
+
+            continue;
+        }
+
+        // a field with #[ssz(encode_as = "...")] is decoded as that type, then converted back, and
+        // one with #[ssz(placeholder)] is decoded and then replaced with its placeholder
+        let as_type = as_type(&field_opts);
+        let placeholder = placeholder(&field_opts);
+        let convert = decoded_conversion(ty, ident, as_type.as_ref(), placeholder.as_ref());
+        let field_ty = ty;
+        let ty = as_type.as_ref().unwrap_or(ty);
+
+        let index = static_stmts.len();
+        // a field with #[ssz(ctx_max = "...")] has its length checked before it's decoded
+        let check_ctx_max = |bytes, at_byte| {
+            ctx_max_check(&field_opts, ty, bytes)
+                .map(|check| quote! { #check.map_err(|e| e.at_byte(#at_byte))?; })
+        };
+        read_into_stmts.extend(check_ctx_max(
+            quote! { &bytes[ranges[#index].clone()] },
+            quote! { ranges[#index].start },
+        ));
+        let validate_field = if as_type.is_some() || placeholder.is_some() {
+            read_into_stmts.push(quote! {
+                self.#ident = <#ty as sszb::SszbDecode>::from_ssz_bytes(&bytes[ranges[#index].clone()])
+                    #convert
+                    .map_err(|e| e.at_byte(ranges[#index].start))?;
+            });
+            // the conversion can fail too, so validation has to make the value
+            quote! { <#ty as sszb::SszbDecode>::from_ssz_bytes(field) #convert .map(drop) }
+        } else {
+            read_into_stmts.push(quote! {
+                <#ty as sszb::SszbDecode>::decode_into(&mut self.#ident, &bytes[ranges[#index].clone()])
+                    .map_err(|e| e.at_byte(ranges[#index].start))?;
+            });
+            quote! { <#ty as sszb::SszbDecode>::validate_ssz_bytes(field) }
+        };
+        validate_arms.push(match ctx_max_check(&field_opts, ty, quote! { field }) {
+            Some(check) => quote! { #index => #check.and_then(|()| #validate_field) },
+            None => quote! { #index => #validate_field },
+        });
+        let check_trusted = check_ctx_max(quote! { &bytes[range.clone()] }, quote! { range.start });
+        read_trusted_stmts.push(quote! {
+            #ident: {
+                let range = sszb::container_field_range_trusted(bytes, fields, #index)?;
+                #check_trusted
+                <#ty as sszb::SszbDecode>::ssz_read_trusted(&bytes[range.clone()])
+                    #convert
+                    .map_err(|e| e.at_byte(range.start))?
+            }
+        });
+        let check_lenient = check_ctx_max(
+            quote! { &bytes[ranges[#index].clone()] },
+            quote! { ranges[#index].start },
+        );
+        read_lenient_stmts.push(quote! {
+            #ident: {
+                #check_lenient
+                <#ty as sszb::SszbDecode>::from_ssz_bytes(&bytes[ranges[#index].clone()])
+                    #convert
+                    .map_err(|e| e.at_byte(ranges[#index].start))?
+            }
+        });
+        let decode_field = format_ident!("decode_field_{}", ident);
+        decode_field_fns.push(quote! {
+            /// Decodes only this field out of the serialized container, see `field_range`.
+            pub fn #decode_field(bytes: &[u8]) -> Result<#field_ty, sszb::DecodeError> {
+                let range = Self::field_range(bytes, #index)?;
+                <#ty as sszb::SszbDecode>::from_ssz_bytes(&bytes[range.clone()])
+                    #convert
+                    .map_err(|e| e.at_byte(range.start))
+            }
+        });
+
+        if is_self_referential(ty, name) {
+            static_stmts.push(quote! { false });
+            fixed_len_stmts.push(quote! { sszb::BYTES_PER_LENGTH_OFFSET });
+            max_len_stmts.push(quote! { usize::MAX });
+            schema_stmts.push(self_referential_schema(ty, name));
+        } else {
+            static_stmts.push(quote! { <#ty as sszb::SszbDecode>::SSZ_STATIC });
+            fixed_len_stmts.push(quote! { <#ty as sszb::SszbDecode>::SSZ_FIXED_LEN });
+            max_len_stmts.push(quote! {
+                sszb::enclosed_max_len(
+                    <#ty as sszb::SszbDecode>::SSZ_STATIC,
+                    <#ty as sszb::SszbDecode>::SSZ_FIXED_LEN,
+                    <#ty as sszb::SszbDecode>::SSZ_MAX_LEN,
+                )
+            });
+            schema_stmts.push(quote! { <#ty as sszb::SszbDecode>::SSZ_SCHEMA });
+        }
+        let var = format_ident!("__sszb_{}", ident);
+        read_stmts.push(quote! { #ident: #var });
+    }
+
+    // fields are read in two passes over the SszReader: the fixed section in order, decoding the
+    // static fields and reading the offsets of the variable ones, then the variable fields
+    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
+        let ident = ident.expect("checked by check_ssz_attrs");
+
+        if field_opts.iter().any(|opt| opt.skip_decode) {
+            continue;
+        }
+
+        // a field with #[ssz(encode_as = "...")] is converted once it's read, and conversion errors
+        // are reported at the start of the field. A placeholder replaces the value read
+        let as_type = as_type(&field_opts);
+        let placeholder = placeholder(&field_opts);
+        let (fixed_at, variable_at, convert) = if as_type.is_some() || placeholder.is_some() {
+            let convert = decoded_conversion(ty, ident, as_type.as_ref(), placeholder.as_ref());
+            let ty = as_type.as_ref().unwrap_or(ty);
+            (
+                quote! { let at_byte = reader.fixed_position(); },
+                quote! { let at_byte = reader.variable_position(); },
+                quote! {
+                    let value = Ok::<#ty, sszb::DecodeError>(value)
+                        #convert
+                        .map_err(|e| e.at_byte(at_byte))?;
+                },
+            )
+        } else {
+            (quote! {}, quote! {}, quote! {})
+        };
+        let ty = as_type.as_ref().unwrap_or(ty);
+        let var = format_ident!("__sszb_{}", ident);
+        // and one with #[ssz(ctx_max = "...")] has the length of its bytes checked before
+        // they're decoded
+        let check_ctx_max = ctx_max_check(&field_opts, ty, quote! { reader.peek_variable()? })
+            .map(|check| quote! { #check.map_err(|e| e.at_byte(reader.variable_position()))?; });
+        // SszbDecodeParallel walks the reader the same way, so that it reports the same errors,
+        // but reads the fields marked #[ssz(parallel)] through ssz_read_parallel
+        let (read_fixed, read_variable) = if is_parallel(&field_opts) {
+            (
+                quote! {
+                    reader.read_fixed_with(<#ty as sszb::parallel::SszbDecodeParallel>::ssz_read_parallel)
+                },
+                quote! {
+                    reader.read_variable_with(<#ty as sszb::parallel::SszbDecodeParallel>::ssz_read_parallel)
+                },
+            )
+        } else {
+            (
+                quote! { reader.read_fixed::<#ty>() },
+                quote! { reader.read_variable::<#ty>() },
+            )
+        };
+        for (read_fixed_stmts, read_variable_stmts, read_fixed, read_variable) in [
+            (
+                &mut *read_fixed_stmts,
+                &mut *read_variable_stmts,
+                quote! { reader.read_fixed::<#ty>() },
+                quote! { reader.read_variable::<#ty>() },
+            ),
+            (
+                &mut *read_fixed_parallel_stmts,
+                &mut *read_variable_parallel_stmts,
+                read_fixed,
+                read_variable,
+            ),
+        ] {
+            read_fixed_stmts.push(quote! {
+                let #var = if <#ty as sszb::SszbDecode>::SSZ_STATIC {
+                    #fixed_at
+                    let value = #read_fixed?;
+                    #convert
+                    Some(value)
+                } else {
+                    reader.read_offset()?;
+                    None
+                };
+            });
+            read_variable_stmts.push(quote! {
+                let #var = match #var {
+                    Some(value) => value,
+                    None => {
+                        #check_ctx_max
+                        #variable_at
+                        let value = #read_variable?;
+                        #convert
+                        value
+                    }
+                };
+            });
+        }
+    }
+
+    // with #[ssz(validate = "path")], every decode runs the hooks on the value it built, and fails
+    // with their error. Validating has to build the value too, for the hooks to look at
+    let validate_stmts = validate_stmts(&derive_input.attrs, &struct_data, name);
+    let (validate_value, validate_self, validate_items, validate_bytes, validate_fn) =
+        if validate_stmts.is_empty() {
+            (quote! {}, quote! {}, quote! {}, quote! {}, quote! {})
+        } else {
+            (
+                quote! { Self::__sszb_validate(&value)?; },
+                quote! { Self::__sszb_validate(self)?; },
+                quote! {
+                    for (i, value) in items.iter().enumerate() {
+                        Self::__sszb_validate(value).map_err(|e| e.at_byte(i * len))?;
+                    }
+                },
+                quote! {
+                    .and_then(|()| <Self as sszb::SszbDecode>::from_ssz_bytes(bytes).map(drop))
+                },
+                quote! {
+                    fn __sszb_validate(value: &Self) -> Result<(), sszb::DecodeError> {
+                        #(
+                            #validate_stmts
+                        )*
+                        Ok(())
+                    }
+                },
+            )
+        };
+
+    let container_opts = parse_container_opts(&derive_input.attrs);
+    // kept out of the SszbDecode impl, so that nothing reaches it without asking for it by name
+    let lenient_fn = if container_opts.iter().any(|opt| opt.lenient_decode) {
+        quote! {
+            /// Decodes the fields this type declares out of a container that a newer schema
+            /// extended with more fields at the end, ignoring the extra fields' bytes. The offsets
+            /// of the declared fields are still checked, see `sszb::container_field_ranges_lenient`
+            /// for how loose the rest is. Use `from_ssz_bytes` for anything but inspection.
+            pub fn from_ssz_bytes_lenient(bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
+                let ranges = sszb::container_field_ranges_lenient(bytes, &[#(
+                    (#static_stmts, #fixed_len_stmts),
+                )*])?;
+                let value = Self {
+                    #(
+                        #read_lenient_stmts,
+                    )*
+                };
+                #validate_value
+                Ok(value)
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // with #[ssz(pod)], a container that's plain old data is read as its memory, as are runs
+    // of it in lists and vectors
+    let (pod_const, pod_read, pod_read_packed) = match pod_layout(
+        &derive_input.attrs,
+        &struct_data,
+        quote! { sszb::SszbDecode },
+    ) {
+        Some(pod_layout) => (
+            quote! { const SSZ_POD: bool = #pod_layout; },
+            quote! {
+                if <Self as sszb::SszbDecode>::SSZ_POD {
+                    let len = <Self as sszb::SszbDecode>::SSZ_FIXED_LEN;
+                    let value = sszb::pod::read(reader.read_fixed_bytes(len)?);
+                    reader.finish()?;
+                    #validate_value
+                    return Ok(value);
+                }
+            },
+            quote! {
+                fn ssz_read_packed(bytes: &[u8]) -> Result<sszb::Vec<Self>, sszb::DecodeError> {
+                    let len = <Self as sszb::SszbDecode>::SSZ_FIXED_LEN;
+                    let num_items = sszb::raw::peek_list_len_static(bytes, len)?;
+                    if <Self as sszb::SszbDecode>::SSZ_POD {
+                        let items: sszb::Vec<Self> = sszb::pod::read_slice(bytes);
+                        #validate_items
+                        return Ok(items);
+                    }
+
+                    let mut items = sszb::Vec::with_capacity(num_items);
+                    for (i, chunk) in bytes.chunks_exact(len).enumerate() {
+                        items.push(
+                            <Self as sszb::SszbDecode>::from_ssz_bytes(chunk)
+                                .map_err(|e| e.at_byte(i * len))?,
+                        );
+                    }
+                    Ok(items)
+                }
+            },
+        ),
+        None => (quote! {}, quote! {}, quote! {}),
+    };
+
+    // every buffer is read through the SszReader, which only ever sees a slice, so the fields
+    // are decoded by code compiled once per container, behind ssz_read's thin generic shim
+    let read_body = |read_fixed_stmts: &[proc_macro2::TokenStream],
+                     read_variable_stmts: &[proc_macro2::TokenStream]| {
+        quote! {
+            sszb::enter_trace_span!(
+                "ssz_decode",
+                ty = core::stringify!(#name),
+                len = reader.bytes().len()
+            );
+
+            // a static container is all fixed section, while a variable one is followed by the
+            // bytes of its variable fields
+            #[allow(unused_mut)]
+            let mut reader = reader.with_fixed_len(const { 0 #(+ #fixed_len_stmts)* })?;
+
+            #pod_read
+
+            #(
+                #read_fixed_stmts
+            )*
+            #(
+                #read_variable_stmts
+            )*
+            reader.finish()?;
+
+            let value = Self {
+                #(
+                    #read_stmts,
+                )*
+            };
+            #validate_value
+            Ok(value)
+        }
+    };
+
+    // with #[ssz(parallel)], the container implements SszbDecodeParallel, whose walk over the
+    // fields is the same as ssz_read_from's but for the fields marked #[ssz(parallel)]
+    let parallel_impl = if container_opts.iter().any(|opt| opt.parallel) {
+        let mut parallel_generics = derive_input.generics.clone();
+        for param in parallel_generics.type_params_mut() {
+            param.bounds.push(syn::parse_quote!(Send));
+        }
+        let (parallel_impl_generics, _, _) = parallel_generics.split_for_impl();
+        let read_parallel_body = read_body(read_fixed_parallel_stmts, read_variable_parallel_stmts);
+
+        quote! {
+            impl #parallel_impl_generics sszb::parallel::SszbDecodeParallel for #name #ty_generics #where_clause {
+                fn ssz_read_parallel(bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
+                    let reader = sszb::SszReader::new(bytes);
+                    #read_parallel_body
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let read_body = read_body(read_fixed_stmts, read_variable_stmts);
+
+    let output = quote! {
+        impl #impl_generics sszb::SszbDecode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = #(
+                #static_stmts &&
+            )*
+                true;
+
+            const SSZ_FIXED_LEN: usize = if <Self as sszb::SszbDecode>::SSZ_STATIC {
+                0 #(
+                    + #fixed_len_stmts
+                )*
+            } else {
+                sszb::BYTES_PER_LENGTH_OFFSET
+            };
+
+            // saturates rather than overflowing for very large (or unbounded) types
+            const SSZ_MAX_LEN: usize = 0usize #(
+                .saturating_add(#max_len_stmts)
+            )*;
+
+            // only the layout of the fields counts, not their names
+            const SSZ_SCHEMA: [u8; 32] = sszb::schema::container(&[#(
+                #schema_stmts,
+            )*]);
+
+            #pod_const
+
+            #pod_read_packed
+
+            fn ssz_read(fixed_bytes: &mut impl Buf, variable_bytes: &mut impl Buf) -> Result<Self, sszb::DecodeError>  {
+                sszb::read_through_reader::<Self>(fixed_bytes, variable_bytes)
+            }
+
+            fn ssz_read_from(reader: sszb::SszReader<'_>) -> Result<Self, sszb::DecodeError> {
+                #read_body
+            }
+
+            fn ssz_read_into(&mut self, fixed_bytes: &mut impl Buf, variable_bytes: &mut impl Buf) -> Result<(), sszb::DecodeError> {
+                // a static container is read from the front of the fixed bytes, and can still own
+                // allocations to reuse, such as a FixedVector of public keys
+                let is_static = <Self as sszb::SszbDecode>::SSZ_STATIC;
+                let len = if is_static {
+                    <Self as sszb::SszbDecode>::SSZ_FIXED_LEN
+                } else {
+                    variable_bytes.remaining()
+                };
+                if is_static && fixed_bytes.remaining() < len {
+                    // ssz_read reports the missing bytes
+                    *self = <Self as sszb::SszbDecode>::ssz_read(fixed_bytes, variable_bytes)?;
+                    return Ok(());
+                }
+
+                // every offset is checked before any field is overwritten. the container's bytes
+                // are gathered first if a fragmented buffer splits them
+                let read_into = |bytes: &[u8]| -> Result<(), sszb::DecodeError> {
+                    let ranges = sszb::container_field_ranges(bytes, &[#(
+                        (#static_stmts, #fixed_len_stmts),
+                    )*])?;
+                    #(
+                        #read_into_stmts
+                    )*
+                    #validate_self
+                    Ok(())
+                };
+                if is_static {
+                    sszb::read_contiguous(fixed_bytes, len, read_into)
+                } else {
+                    sszb::read_contiguous(variable_bytes, len, read_into)
+                }
+            }
+
+            fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), sszb::DecodeError> {
+                // the same walk as ssz_read, with each field validated in place instead of built
+                sszb::visit_container_fields(bytes, &[#(
+                    (#static_stmts, #fixed_len_stmts),
+                )*], |index, range| {
+                    let field = &bytes[range.clone()];
+                    match index {
+                        #(
+                            #validate_arms,
+                        )*
+                        _ => Ok(()),
+                    }
+                    .map_err(|e| e.at_byte(range.start))
+                })
+                #validate_bytes
+            }
+
+            fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
+                // static containers have no offsets to skip validating
+                if <Self as sszb::SszbDecode>::SSZ_STATIC {
+                    return <Self as sszb::SszbDecode>::from_ssz_bytes(bytes);
+                }
+
+                let fields: &[(bool, usize)] = &[#(
+                    (#static_stmts, #fixed_len_stmts),
+                )*];
+                let value = Self {
+                    #(
+                        #read_trusted_stmts,
+                    )*
+                };
+                #validate_value
+                Ok(value)
+            }
+        }
+
+        // not every container needs partial decoding
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Fingerprint of the container's SSZ layout, to tell bytes written with another
+            /// definition of it apart. See `sszb::schema`.
+            pub const SSZ_SCHEMA_FINGERPRINT: [u8; 32] = <Self as sszb::SszbDecode>::SSZ_SCHEMA;
+
+            /// Byte range of the field with the given index (skipped fields aren't counted)
+            /// within the serialized container. Only the offsets needed to find the field are
+            /// validated, so the rest of `bytes` may well fail to decode.
+            pub fn field_range(bytes: &[u8], index: usize) -> Result<core::ops::Range<usize>, sszb::DecodeError> {
+                sszb::container_field_range(bytes, &[#(
+                    (#static_stmts, #fixed_len_stmts),
+                )*], index)
+            }
+
+            #(
+                #decode_field_fns
+            )*
+
+            #lenient_fn
+
+            #validate_fn
+        }
+
+        #parallel_impl
+    };
+
+    let output = if ethereum_ssz_compat(&derive_input.attrs) {
+        quote! {
+            #output
+
+            impl #impl_generics sszb::ethereum_ssz::Decode for #name #ty_generics #where_clause {
+                fn is_ssz_fixed_len() -> bool {
+                    <Self as sszb::SszbDecode>::SSZ_STATIC
+                }
+
+                fn ssz_fixed_len() -> usize {
+                    <Self as sszb::SszbDecode>::SSZ_FIXED_LEN
+                }
+
+                fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, sszb::ethereum_ssz::DecodeError> {
+                    <Self as sszb::SszbDecode>::from_ssz_bytes(bytes).map_err(Into::into)
+                }
+            }
+        }
+    } else {
+        output
+    };
+
+    // with #[ssz(zero)], the container's SSZ zero value is made of its fields'. Skipped fields
+    // aren't part of the encoding, so they're left as default like a decoded value leaves them,
+    // and placeholder fields hold the placeholder a decoded value gets
+    let output = if container_opts.iter().any(|opt| opt.zero) {
+        let zero_stmts = parse_ssz_fields(&struct_data).map(|(ty, ident, field_opts)| {
+            if field_opts
+                .iter()
+                .any(|opt| opt.skip_encode || opt.skip_decode)
+            {
+                return quote! { #ident: <_>::default() };
+            }
+            if let Some(placeholder) = placeholder(&field_opts) {
+                let value = placeholder.decoded(ty);
+                return quote! { #ident: #value };
+            }
+            match as_type(&field_opts) {
+                Some(as_type) => {
+                    let message = format!(
+                        "field `{}` can't be converted from the zero value of its `{}` encoding",
+                        ident.unwrap(),
+                        quote!(#as_type).to_string().replace(' ', "")
+                    );
+                    quote! {
+                        #ident: <#ty as core::convert::TryFrom<#as_type>>::try_from(
+                            <#as_type as sszb::SszZero>::ssz_zero(),
+                        )
+                        .unwrap_or_else(|_| panic!(#message))
+                    }
+                }
+                None => quote! { #ident: <#ty as sszb::SszZero>::ssz_zero() },
+            }
+        });
 
-        // possible field options include skip_encode, skip_decode, skip_hash
-        let field_opts = field
-            .attrs
-            .iter()
-            .filter(|attr| {
-                attr.path()
-                    .get_ident()
-                    .map_or(false, |ident| *ident == "ssz")
-            })
-            .map(|attr| FieldOpts::from_meta(&attr.meta).unwrap())
-            .collect::<Vec<_>>();
+        let mut zero_generics = derive_input.generics.clone();
+        for param in zero_generics.type_params_mut() {
+            param.bounds.push(syn::parse_quote!(sszb::SszZero));
+        }
+        let (zero_impl_generics, _, _) = zero_generics.split_for_impl();
 
-        (ty, ident, field_opts)
-    })
-}
+        quote! {
+            #output
 
-#[proc_macro_derive(SszbEncode)]
-pub fn derive_encode(input: TokenStream) -> TokenStream {
-    let derive_input = parse_macro_input!(input as DeriveInput);
-    let struct_data = match derive_input.data {
-        syn::Data::Struct(data) => data,
-        _ => panic!(), // TODO: fix
+            impl #zero_impl_generics #name #ty_generics #where_clause {
+                /// The SSZ zero value of this container, each field holding its own zero value,
+                /// whose encoding is the canonical all-zero one. It needn't be `Default`.
+                pub fn ssz_zero() -> Self {
+                    <Self as sszb::SszZero>::ssz_zero()
+                }
+            }
+
+            impl #zero_impl_generics sszb::SszZero for #name #ty_generics #where_clause {
+                fn ssz_zero() -> Self {
+                    Self {
+                        #(
+                            #zero_stmts,
+                        )*
+                    }
+                }
+            }
+        }
+    } else {
+        output
     };
-    let name = &derive_input.ident;
-    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
-    let fixed_len_stmts = &mut vec![];
-    let static_stmts = &mut vec![];
-    let bytes_len_stmts = &mut vec![];
-    let max_len_stmts = &mut vec![];
-    let ssz_write_fixed_stmts = &mut vec![];
-    let write_fixed_stmts = &mut vec![];
-    let write_variable_stmts = &mut vec![];
+    if !container_opts.iter().any(|opt| opt.arbitrary) {
+        return output.into();
+    }
 
-    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
-        if field_opts.iter().any(|opt| opt.skip_encode) {
-            continue;
+    // skipped fields never make it through a round trip, so they are always left as default, and
+    // placeholder fields always hold their placeholder
+    let arbitrary_stmts = parse_ssz_fields(&struct_data).map(|(ty, ident, field_opts)| {
+        if field_opts
+            .iter()
+            .any(|opt| opt.skip_encode || opt.skip_decode)
+        {
+            quote! { #ident: <_>::default() }
+        } else if let Some(placeholder) = placeholder(&field_opts) {
+            let value = placeholder.decoded(ty);
+            quote! { #ident: #value }
+        } else {
+            quote! { #ident: u.arbitrary()? }
         }
+    });
 
-        static_stmts.push(quote! { <#ty as sszb::SszbEncode>::is_ssz_static() });
-        fixed_len_stmts.push(quote! { <#ty as sszb::SszbEncode>::ssz_fixed_len() });
-        bytes_len_stmts.push(quote! { self.#ident.sszb_bytes_len() });
-        max_len_stmts.push(quote! { <#ty as sszb::SszbEncode>::ssz_max_len() });
-        ssz_write_fixed_stmts.push(quote! { self.#ident.ssz_write_fixed(offset, buf) });
-        write_fixed_stmts.push(quote! { self.#ident.ssz_write_fixed(&mut offset, buf) });
-        write_variable_stmts.push(quote! { self.#ident.ssz_write_variable(buf) });
+    let mut arbitrary_generics = derive_input.generics.clone();
+    for param in arbitrary_generics.type_params_mut() {
+        param
+            .bounds
+            .push(syn::parse_quote!(sszb::arbitrary::Arbitrary<'arbitrary>));
     }
+    arbitrary_generics
+        .params
+        .insert(0, syn::parse_quote!('arbitrary));
+    let (arbitrary_impl_generics, _, _) = arbitrary_generics.split_for_impl();
 
     let output = quote! {
-        impl #impl_generics sszb::SszbEncode for #name #ty_generics #where_clause {
-            fn is_ssz_static() -> bool {
-                #(
-                    #static_stmts &&
-                )*
-                    true
-            }
+        #output
 
-            fn ssz_fixed_len() -> usize {
-                if <Self as sszb::SszbEncode>::is_ssz_static() {
-                    let mut len: usize = 0;
+        impl #arbitrary_impl_generics sszb::arbitrary::Arbitrary<'arbitrary> for #name #ty_generics #where_clause {
+            fn arbitrary(u: &mut sszb::arbitrary::Unstructured<'arbitrary>) -> sszb::arbitrary::Result<Self> {
+                Ok(Self {
                     #(
-                        len = len
-                            .checked_add(#fixed_len_stmts)
-                            .expect("encode ssz_fixed_len length overflow");
+                        #arbitrary_stmts,
                     )*
-                    len
+                })
+            }
+        }
+    };
+    output.into()
+}
+
+fn derive_decode_union(derive_input: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+    let max_len_stmts = &mut vec![];
+    let read_arms = &mut vec![];
+
+    for (selector, variant, body) in
+        parse_union_variants(enum_data).expect("checked by check_ssz_attrs")
+    {
+        match body {
+            VariantBody::None => {
+                max_len_stmts.push(quote! { 0 });
+                read_arms.push(quote! {
+                    #selector => {
+                        if !body.is_empty() {
+                            return Err(sszb::DecodeError::InvalidByteLength {
+                                len: body.len(),
+                                expected: 0,
+                            }
+                            .at_byte(1));
+                        }
+                        Self::#variant
+                    }
+                });
+            }
+            VariantBody::Value(ty) => {
+                max_len_stmts.push(if is_self_referential(ty, name) {
+                    quote! { usize::MAX }
                 } else {
-                    sszb::BYTES_PER_LENGTH_OFFSET
-                }
+                    quote! { <#ty as sszb::SszbDecode>::SSZ_MAX_LEN }
+                });
+                read_arms.push(quote! {
+                    #selector => Self::#variant(
+                        <#ty as sszb::SszbDecode>::from_ssz_bytes(body).map_err(|e| e.at_byte(1))?
+                    )
+                });
             }
+            VariantBody::Container(fields) => {
+                let idents = fields.iter().map(|(_, ident)| ident);
+                let tys = fields.iter().map(|(ty, _)| ty).collect::<Vec<_>>();
+                let indices = 0..fields.len();
 
-            fn sszb_bytes_len(&self) -> usize {
-                if <Self as sszb::SszbEncode>::is_ssz_static() {
-                    <Self as sszb::SszbEncode>::ssz_fixed_len()
+                max_len_stmts.push(if tys.iter().any(|ty| is_self_referential(ty, name)) {
+                    quote! { usize::MAX }
                 } else {
-                    let mut len: usize = 0;
-                    #(
-                        if #static_stmts {
-                            len = len
-                                .checked_add(#fixed_len_stmts)
-                                .expect("encode sszb_bytes_len length overflow");
-                        } else {
-                            len = len
-                                .checked_add(sszb::BYTES_PER_LENGTH_OFFSET)
-                                .expect("encode sszb_bytes_len length overflow for offset");
-                            len = len
-                                .checked_add(#bytes_len_stmts)
-                                .expect("encode ssbz_bytes_len length overflow for bytes");
+                    quote! {
+                        0usize #(
+                            .saturating_add(sszb::enclosed_max_len(
+                                <#tys as sszb::SszbDecode>::SSZ_STATIC,
+                                <#tys as sszb::SszbDecode>::SSZ_FIXED_LEN,
+                                <#tys as sszb::SszbDecode>::SSZ_MAX_LEN,
+                            ))
+                        )*
+                    }
+                });
+                // the body's offsets are validated exactly like a derived container's
+                read_arms.push(quote! {
+                    #selector => {
+                        let ranges = sszb::container_field_ranges(body, &[#(
+                            (<#tys as sszb::SszbDecode>::SSZ_STATIC, <#tys as sszb::SszbDecode>::SSZ_FIXED_LEN),
+                        )*])
+                        .map_err(|e| e.at_byte(1))?;
+                        Self::#variant {
+                            #(
+                                #idents: {
+                                    let range = ranges[#indices].clone();
+                                    <#tys as sszb::SszbDecode>::from_ssz_bytes(&body[range.clone()])
+                                        .map_err(|e| e.at_byte(1 + range.start))?
+                                },
+                            )*
                         }
-                    )*
-
-                    len
-                }
+                    }
+                });
             }
+        }
+    }
+
+    let output = quote! {
+        impl #impl_generics sszb::SszbDecode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = false;
 
-            fn ssz_max_len() -> usize {
-                let mut len: usize = 0;
+            const SSZ_FIXED_LEN: usize = sszb::BYTES_PER_LENGTH_OFFSET;
+
+            // the selector, followed by the largest variant
+            const SSZ_MAX_LEN: usize = {
+                let mut max_len: usize = 0;
                 #(
-                    len = len
-                        .checked_add(#max_len_stmts)
-                        .expect("encode ssz_max_len length overflow");
+                    let len = #max_len_stmts;
+                    if len > max_len {
+                        max_len = len;
+                    }
                 )*
-                len
+                max_len.saturating_add(1)
+            };
+
+            fn ssz_read(_fixed_bytes: &mut impl Buf, variable_bytes: &mut impl Buf) -> Result<Self, sszb::DecodeError> {
+                let len = variable_bytes.remaining();
+                sszb::read_contiguous(variable_bytes, len, |bytes| {
+                    let (selector, body) = bytes
+                        .split_first()
+                        .ok_or(sszb::DecodeError::InvalidByteLength { len: 0, expected: 1 })?;
+
+                    Ok(match *selector {
+                        #(
+                            #read_arms,
+                        )*
+                        selector => return Err(sszb::DecodeError::UnionSelectorInvalid(selector)),
+                    })
+                })
             }
+        }
+    };
 
-            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-                // if self is fixed-sized then write the data outright
-                // or else we write the offset to the buffer and point to the end of the buffer
-                if <Self as sszb::SszbEncode>::is_ssz_static() {
+    output.into()
+}
+
+// without a selector there's nothing to decide the variant from, so rather than implementing
+// SszbDecode, transparent enums get functions that take the variant from the caller
+fn derive_decode_transparent(derive_input: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+
+    let variants = parse_transparent_variants(enum_data).expect("checked by check_ssz_attrs");
+    let idents = variants.iter().map(|(ident, _)| ident).collect::<Vec<_>>();
+    let tys = variants.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+    let names = idents.iter().map(|ident| ident.to_string());
+    let indices = 0..variants.len();
+
+    let output = quote! {
+        // not every transparent enum is decoded by name
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Decodes `bytes` as the variant with the given index, in declaration order.
+            pub fn from_ssz_bytes_for_variant(variant_index: usize, bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
+                match variant_index {
                     #(
-                        #ssz_write_fixed_stmts;
+                        #indices => <#tys as sszb::SszbDecode>::from_ssz_bytes(bytes).map(Self::#idents),
                     )*
-                } else {
-                    buf.put_slice(&offset.to_le_bytes()[0..sszb::BYTES_PER_LENGTH_OFFSET]);
-                    *offset += self.sszb_bytes_len();
+                    _ => Err(sszb::DecodeError::InvalidVariantIndex(variant_index)),
                 }
             }
 
-            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-                if !<Self as sszb::SszbEncode>::is_ssz_static() {
-                    self.ssz_write(buf);
+            /// Decodes `bytes` as the variant with the given name.
+            pub fn from_ssz_bytes_for_variant_name(variant_name: &str, bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
+                match variant_name {
+                    #(
+                        #names => <#tys as sszb::SszbDecode>::from_ssz_bytes(bytes).map(Self::#idents),
+                    )*
+                    _ => Err(sszb::DecodeError::InvalidVariantName),
                 }
             }
+        }
+    };
 
-            // using this function direct is most efficient with a &mut [u8]
-            // the slice must have enough capacity, otherwise it will panic on failure
-            // if used with a Vec<u8>, capacity may be increased automatically if needed
-            fn ssz_write(&self, buf: &mut impl BufMut) {
-                // offset is the length of all fixed size items
-                // this lets us point dynamic length items to the area *after* the fixed size items
-                let mut offset: usize = 0;
-                #(
-                    offset = offset
-                        .checked_add(#fixed_len_stmts)
-                        .expect("encode ssz_fixed_len length overflow");
-                )*
+    output.into()
+}
 
-                // first we write the fixed portion of each field in self
-                // offset is passed to the write_fixed call in case we point to variable-sized data
-                #(
-                    #write_fixed_stmts;
-                )*
+fn derive_decode_tag(derive_input: &DeriveInput, enum_data: &syn::DataEnum) -> TokenStream {
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
+    let (idents, tags): (Vec<_>, Vec<_>) = parse_tag_variants(enum_data)
+        .expect("checked by check_ssz_attrs")
+        .into_iter()
+        .unzip();
 
-                // then we write the variable portion into the buffer
-                #(
-                    #write_variable_stmts;
-                )*
+    let output = quote! {
+        impl #impl_generics sszb::SszbDecode for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = true;
+
+            const SSZ_FIXED_LEN: usize = 1;
+
+            const SSZ_MAX_LEN: usize = 1;
+
+            // with 256 variants, every tag is taken
+            #[allow(unreachable_patterns)]
+            fn ssz_read(fixed_bytes: &mut impl Buf, _variable_bytes: &mut impl Buf) -> Result<Self, sszb::DecodeError> {
+                if !fixed_bytes.has_remaining() {
+                    return Err(sszb::DecodeError::InvalidByteLength { len: 0, expected: 1 });
+                }
+
+                match fixed_bytes.get_u8() {
+                    #(
+                        #tags => Ok(Self::#idents),
+                    )*
+                    tag => Err(sszb::DecodeError::InvalidEnumTag(tag)),
+                }
             }
         }
     };
+
     output.into()
 }
 
-#[proc_macro_derive(SszbDecode)]
-pub fn derive_decode(input: TokenStream) -> TokenStream {
+#[proc_macro_derive(SszbDecodeIn, attributes(ssz))]
+pub fn derive_decode_in(input: TokenStream) -> TokenStream {
     let derive_input = parse_macro_input!(input as DeriveInput);
+    if let Err(e) = check_ssz_attrs(&derive_input) {
+        return e.to_compile_error().into();
+    }
     let struct_data = match derive_input.data {
         syn::Data::Struct(data) => data,
-        _ => panic!(), // TODO: fix
+        _ => {
+            return syn::Error::new_spanned(
+                &derive_input.ident,
+                "#[derive(SszbDecodeIn)] only supports structs",
+            )
+            .to_compile_error()
+            .into()
+        }
     };
     let name = &derive_input.ident;
     let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
-    let fixed_len_stmts = &mut vec![];
+    // fields keep their lists in the arena for the struct's lifetime
+    let lifetime = match derive_input.generics.lifetimes().next() {
+        Some(param) => &param.lifetime,
+        None => {
+            return syn::Error::new_spanned(
+                &derive_input.ident,
+                "#[derive(SszbDecodeIn)] needs a lifetime parameter, use #[derive(SszbDecode)] for owned containers",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
     let static_stmts = &mut vec![];
-    let max_len_stmts = &mut vec![];
-    let read_stmts = &mut vec![];
-    let read_stmts_var = &mut vec![];
+    let fixed_len_stmts = &mut vec![];
+    let field_stmts = &mut vec![];
 
     for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
-        let ident = match ident {
-            Some(ref ident) => ident,
-            _ => panic!(
-                "#[ssz(struct_behaviour = \"container\")] only supports named struct fields."
-            ),
-        };
+        let ident = ident.expect("checked by check_ssz_attrs");
 
         if field_opts.iter().any(|opt| opt.skip_decode) {
-            // should deserialize default
-            read_stmts.push(quote! {
-                #ident = <_>::default();
-            });
-            read_stmts_var.push(quote! {
-                #ident = <_>::default();
-            });
-
+            field_stmts.push(quote! { #ident: <_>::default() });
             continue;
         }
 
-        static_stmts.push(quote! { <#ty as sszb::SszbDecode>::is_ssz_static() });
-        fixed_len_stmts.push(quote! { <#ty as sszb::SszbDecode>::ssz_fixed_len() });
-        max_len_stmts.push(quote! { <#ty as sszb::SszbDecode>::ssz_max_len() });
-        read_stmts.push(quote! {
-            #ident: <#ty as sszb::SszbDecode>::ssz_read(fixed_bytes, variable_bytes)?
+        let as_type = as_type(&field_opts);
+        let placeholder = placeholder(&field_opts);
+        let convert = decoded_conversion(ty, ident, as_type.as_ref(), placeholder.as_ref());
+        let ty = as_type.as_ref().unwrap_or(ty);
+
+        let index = static_stmts.len();
+        static_stmts.push(quote! { <#ty as sszb::arena::SszbDecodeIn<#lifetime>>::SSZ_STATIC });
+        fixed_len_stmts
+            .push(quote! { <#ty as sszb::arena::SszbDecodeIn<#lifetime>>::SSZ_FIXED_LEN });
+        field_stmts.push(quote! {
+            #ident: {
+                let range = ranges[#index].clone();
+                <#ty as sszb::arena::SszbDecodeIn<#lifetime>>::from_ssz_bytes_in(&bytes[range.clone()], arena)
+                    #convert
+                    .map_err(|e| e.at_byte(range.start))?
+            }
         });
     }
+    let num_fields = static_stmts.len();
 
-    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
-        let ident = match ident {
-            Some(ref ident) => ident,
-            _ => panic!(
-                "#[ssz(struct_behaviour = \"container\")] only supports named struct fields."
-            ),
-        };
+    let output = quote! {
+        impl #impl_generics sszb::arena::SszbDecodeIn<#lifetime> for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = #(
+                #static_stmts &&
+            )*
+                true;
 
-        if field_opts.iter().any(|opt| opt.skip_decode) {
-            read_stmts_var.push(quote! {
-                #ident = <_>::default();
-            });
+            const SSZ_FIXED_LEN: usize = if <Self as sszb::arena::SszbDecodeIn<#lifetime>>::SSZ_STATIC {
+                0 #(
+                    + #fixed_len_stmts
+                )*
+            } else {
+                sszb::BYTES_PER_LENGTH_OFFSET
+            };
 
-            continue;
+            fn from_ssz_bytes_in(bytes: &[u8], arena: &#lifetime sszb::arena::Bump) -> Result<Self, sszb::DecodeError> {
+                // the field ranges go on the stack, so that only the fields allocate, and only
+                // in the arena
+                let mut ranges: [core::ops::Range<usize>; #num_fields] =
+                    core::array::from_fn(|_| 0..0);
+                sszb::visit_container_fields(bytes, &[#(
+                    (#static_stmts, #fixed_len_stmts),
+                )*], |index, range| {
+                    ranges[index] = range;
+                    Ok(())
+                })?;
+
+                Ok(Self {
+                    #(
+                        #field_stmts,
+                    )*
+                })
+            }
         }
+    };
+    output.into()
+}
 
-        read_stmts_var.push(quote! {
-            #ident: if <#ty as sszb::SszbDecode>::is_ssz_static() {
-                fixed_cursor = fixed_cursor.checked_add(<#ty as sszb::SszbDecode>::ssz_fixed_len()).expect("overflow");
-                <#ty as sszb::SszbDecode>::ssz_read(fixed_bytes, variable_bytes)?
-            } else {
-                fixed_cursor = fixed_cursor.checked_add(sszb::BYTES_PER_LENGTH_OFFSET).expect("overflow");
-                let begin = sszb::read_offset_from_buf(fixed_bytes)?;
+#[proc_macro_derive(SszbDecodeRef, attributes(ssz))]
+pub fn derive_decode_ref(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    if let Err(e) = check_ssz_attrs(&derive_input) {
+        return e.to_compile_error().into();
+    }
+    let struct_data = match derive_input.data {
+        syn::Data::Struct(data) => data,
+        _ => {
+            return syn::Error::new_spanned(
+                &derive_input.ident,
+                "#[derive(SszbDecodeRef)] only supports structs",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
-                let mut end = None;
-                let mut start: usize = 0;
-                #(
-                    if #static_stmts {
-                        start = start
-                            .checked_add(#fixed_len_stmts)
-                            .expect("ssz fixed length overflow");
-                    } else {
-                        if start >= fixed_cursor && end.is_none() {
-                            let index = start - fixed_cursor;
-                            end = Some(sszb::read_offset_from_slice(&fixed_bytes.chunk()[index..(index + sszb::BYTES_PER_LENGTH_OFFSET)])?);
-                        } else {
-                            start = start
-                                .checked_add(sszb::BYTES_PER_LENGTH_OFFSET)
-                                .expect("ssz fixed length overflow");
-                        }
-                    }
-                )*
+    // fields borrow from the input for the struct's lifetime
+    let lifetime = match derive_input.generics.lifetimes().next() {
+        Some(param) => &param.lifetime,
+        None => {
+            return syn::Error::new_spanned(
+                &derive_input.ident,
+                "#[derive(SszbDecodeRef)] needs a lifetime parameter, use #[derive(SszbDecode)] for owned containers",
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
 
-                let field_len = end.unwrap_or(end_of_buffer) - begin;
-                if field_len > variable_bytes.remaining() {
-                    return Err(sszb::DecodeError::InvalidByteLength {
-                        len: field_len,
-                        expected: variable_bytes.remaining(),
-                    })
-                } else {
+    let static_stmts = &mut vec![];
+    let fixed_len_stmts = &mut vec![];
+    let field_stmts = &mut vec![];
 
-                    // let bytes = variable_bytes.copy_to_bytes(field_len);
-                    let bytes = variable_bytes.chunk();
-                    let bytes = &bytes[..field_len];
-                    // both the fixed and variable buffers are advanced at this point
-                    // even though we don't make a call to ssz_read with them
-                    let res = <#ty as sszb::SszbDecode>::from_ssz_bytes(&bytes)?;
-                    variable_bytes.advance(field_len);
-                    res
-                }
+    for (ty, ident, field_opts) in parse_ssz_fields(&struct_data) {
+        let ident = ident.expect("checked by check_ssz_attrs");
+
+        if field_opts.iter().any(|opt| opt.skip_decode) {
+            field_stmts.push(quote! { #ident: <_>::default() });
+            continue;
+        }
+
+        let as_type = as_type(&field_opts);
+        let placeholder = placeholder(&field_opts);
+        let convert = decoded_conversion(ty, ident, as_type.as_ref(), placeholder.as_ref());
+        let ty = as_type.as_ref().unwrap_or(ty);
+
+        let index = static_stmts.len();
+        static_stmts.push(quote! { <#ty as sszb::SszbDecodeRef<#lifetime>>::SSZ_STATIC });
+        fixed_len_stmts.push(quote! { <#ty as sszb::SszbDecodeRef<#lifetime>>::SSZ_FIXED_LEN });
+        field_stmts.push(quote! {
+            #ident: {
+                let range = ranges[#index].clone();
+                <#ty as sszb::SszbDecodeRef<#lifetime>>::from_ssz_bytes_ref(&bytes[range.clone()])
+                    #convert
+                    .map_err(|e| e.at_byte(range.start))?
             }
         });
     }
 
     let output = quote! {
-        impl #impl_generics sszb::SszbDecode for #name #ty_generics #where_clause {
-            fn is_ssz_static() -> bool {
-                #(
-                    #static_stmts &&
+        impl #impl_generics sszb::SszbDecodeRef<#lifetime> for #name #ty_generics #where_clause {
+            const SSZ_STATIC: bool = #(
+                #static_stmts &&
+            )*
+                true;
+
+            const SSZ_FIXED_LEN: usize = if <Self as sszb::SszbDecodeRef<#lifetime>>::SSZ_STATIC {
+                0 #(
+                    + #fixed_len_stmts
                 )*
-                    true
-            }
+            } else {
+                sszb::BYTES_PER_LENGTH_OFFSET
+            };
 
-            fn ssz_fixed_len() -> usize {
-                if <Self as sszb::SszbEncode>::is_ssz_static() {
-                    let mut len: usize = 0;
+            fn from_ssz_bytes_ref(bytes: &#lifetime [u8]) -> Result<Self, sszb::DecodeError> {
+                let ranges = sszb::container_field_ranges(bytes, &[#(
+                    (#static_stmts, #fixed_len_stmts),
+                )*])?;
+
+                Ok(Self {
                     #(
-                        len = len
-                            .checked_add(#fixed_len_stmts)
-                            .expect("encode ssz_fixed_len length overflow");
+                        #field_stmts,
                     )*
-                    len
-                } else {
-                    sszb::BYTES_PER_LENGTH_OFFSET
-                }
+                })
             }
+        }
+    };
+    output.into()
+}
 
-            fn ssz_max_len() -> usize {
-                let mut len: usize = 0;
-                #(
-                    len = len
-                        .checked_add(#max_len_stmts)
-                        .expect("encode ssz_max_len length overflow");
-                )*
-                len
-            }
+#[proc_macro_derive(SszbTypeInfo, attributes(ssz))]
+pub fn derive_type_info(input: TokenStream) -> TokenStream {
+    let derive_input = parse_macro_input!(input as DeriveInput);
+    if let Err(e) = check_ssz_attrs(&derive_input) {
+        return e.to_compile_error().into();
+    }
+    let name = &derive_input.ident;
+    let (impl_generics, ty_generics, where_clause) = &derive_input.generics.split_for_impl();
 
-            fn ssz_read(fixed_bytes: &mut impl Buf, variable_bytes: &mut impl Buf) -> Result<Self, sszb::DecodeError>  {
-                if <Self as sszb::SszbDecode>::is_ssz_static() {
-                    if fixed_bytes.remaining() < <Self as sszb::SszbDecode>::ssz_fixed_len() {
-                        return Err(sszb::DecodeError::InvalidByteLength {
-                            len: fixed_bytes.remaining(),
-                            expected: <Self as sszb::SszbDecode>::ssz_fixed_len(),
-                        });
-                    }
+    let info = match type_info(&derive_input) {
+        Ok(info) => info,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-                    Ok(Self {
-                        #(
-                            #read_stmts,
-                        )*
-                    })
-                } else {
+    let output = quote! {
+        impl #impl_generics sszb::dynamic::SszbTypeInfo for #name #ty_generics #where_clause {
+            fn ssz_type_info() -> sszb::dynamic::SszTypeInfo {
+                #info
+            }
+        }
+    };
+    output.into()
+}
 
-                    let end_of_buffer: usize = fixed_bytes.remaining() + variable_bytes.remaining();
+// the layout SszbTypeInfo describes: that of a struct's decoded fields, a union's variants or a
+// tag enum's byte
+fn type_info(derive_input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &derive_input.ident;
 
-                    let mut fixed_cursor: usize = 0;
-                    Ok(Self {
-                        #(
-                            #read_stmts_var,
-                        )*
-                    })
-                }
-            }
+    // a field's layout, or an error for a field that contains the type itself, whose layout
+    // would never finish describing itself
+    let field_info = |ty: &syn::Type| {
+        if is_self_referential(ty, name) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "#[derive(SszbTypeInfo)] doesn't support recursive types, which have no finite layout",
+            ));
+        }
+        Ok(quote! { <#ty as sszb::dynamic::SszbTypeInfo>::ssz_type_info() })
+    };
+    let container_info = |fields: Vec<(&syn::Type, &Ident)>| {
+        let names = fields.iter().map(|(_, ident)| ident.to_string());
+        let infos = fields
+            .iter()
+            .map(|(ty, _)| field_info(ty))
+            .collect::<syn::Result<Vec<_>>>()?;
+        Ok(quote! {
+            sszb::dynamic::SszTypeInfo::Container(sszb::Vec::from([#(
+                (core::convert::Into::into(#names), #infos),
+            )*]))
+        })
+    };
 
-            fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, sszb::DecodeError> {
-                let mut len: usize = 0;
-                #(
-                    len = len
-                        .checked_add(#fixed_len_stmts)
-                        .expect("decode ssz_fixed_len length overflow");
-                )*
-                if len > bytes.len() {
-                    return Err(sszb::DecodeError::InvalidByteLength {
-                        len: bytes.len(),
-                        expected: len,
-                    })
-                } else {
-                    let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(len);
-                    <Self as SszbDecode>::ssz_read(&mut fixed_bytes, &mut variable_bytes)
+    match &derive_input.data {
+        syn::Data::Struct(struct_data) => {
+            // the layout that decoding reads, without skipped fields and with converted ones
+            // as the type they're encoded as
+            let mut as_types = vec![];
+            for (ty, ident, field_opts) in parse_ssz_fields(struct_data) {
+                let ident = ident.expect("checked by check_ssz_attrs");
+                if field_opts.iter().any(|opt| opt.skip_decode) {
+                    continue;
                 }
+                as_types.push((as_type(&field_opts).unwrap_or_else(|| ty.clone()), ident));
             }
+            container_info(as_types.iter().map(|(ty, ident)| (ty, *ident)).collect())
         }
-    };
-    output.into()
+        syn::Data::Enum(enum_data) => match enum_behaviour(&derive_input.attrs) {
+            EnumBehaviour::Union => {
+                let variants = parse_union_variants(enum_data)
+                    .expect("checked by check_ssz_attrs")
+                    .into_iter()
+                    .map(|(_, _, body)| match body {
+                        VariantBody::None => Ok(quote! { None }),
+                        VariantBody::Value(ty) => {
+                            let info = field_info(ty)?;
+                            Ok(quote! { Some(#info) })
+                        }
+                        VariantBody::Container(fields) => {
+                            let info = container_info(fields)?;
+                            Ok(quote! { Some(#info) })
+                        }
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                Ok(quote! {
+                    sszb::dynamic::SszTypeInfo::Union(sszb::Vec::from([#(#variants),*]))
+                })
+            }
+            // a tag enum is its tag byte
+            EnumBehaviour::Tag => Ok(quote! { sszb::dynamic::SszTypeInfo::Uint(1) }),
+            EnumBehaviour::Transparent => Err(syn::Error::new_spanned(
+                name,
+                "#[derive(SszbTypeInfo)] doesn't support transparent enums, whose layout depends on the variant",
+            )),
+        },
+        syn::Data::Union(_) => unreachable!("checked by check_ssz_attrs"),
+    }
 }
@@ -125,3 +125,15 @@ fn test_invalid_length_var_b() {
         true
     );
 }
+
+#[test]
+fn test_associated_constants() {
+    assert!(<VariableA as SszEncode>::SSZ_STATIC);
+    assert_eq!(<VariableA as SszEncode>::SSZ_FIXED_LEN, 6);
+    assert_eq!(<VariableA as SszDecode>::SSZ_FIXED_LEN, 6);
+
+    assert!(!<VariableB as SszEncode>::SSZ_STATIC);
+    assert!(!<VariableB as SszDecode>::SSZ_STATIC);
+    assert_eq!(<VariableB as SszEncode>::SSZ_FIXED_LEN, 4);
+    assert_eq!(<VariableB as SszEncode>::SSZ_MAX_LEN, 22);
+}
@@ -1,11 +1,16 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use sszb::{SszbDecode, SszbEncode};
 
-pub mod beacon_block;
-pub use beacon_block::SignedBeaconBlock;
+use sszb::test_utils::{self, BeaconState, BlockConfig, SignedBeaconBlock, StateConfig};
 
-pub mod beacon_state;
-pub use beacon_state::BeaconState;
+// the value in the file of mainnet data at `path` if there is one, and otherwise a generated one
+// of about the same shape, along with its serialization
+fn fixture<T: SszbDecode>(path: &str, generate: impl FnOnce() -> (T, Vec<u8>)) -> (T, Vec<u8>) {
+    match std::fs::read(path) {
+        Ok(bytes) => (T::from_ssz_bytes(&bytes).unwrap(), bytes),
+        Err(_) => generate(),
+    }
+}
 
 fn basic_types(c: &mut Criterion) {
     use milhouse::List;
@@ -28,6 +33,14 @@ fn basic_types(c: &mut Criterion) {
         |b, bytes| b.iter(|| <List<u64, C> as SszbDecode>::from_ssz_bytes(bytes).unwrap()),
     );
 
+    // decoding builds the tree a run of items at a time, compared with decoding every item into
+    // a vector first and building the tree out of that
+    group.bench_with_input(
+        BenchmarkId::new("Milhouse", "decode through a Vec"),
+        &list_bytes,
+        |b, bytes| b.iter(|| List::<u64, C>::new(u64::ssz_read_packed(bytes).unwrap()).unwrap()),
+    );
+
     group.bench_with_input(BenchmarkId::new("Milhouse", "to_ssz"), &list, |b, list| {
         b.iter(|| list.to_ssz())
     });
@@ -42,14 +55,306 @@ fn basic_types(c: &mut Criterion) {
         },
     );
 
+    // the integers of a contiguous list are copied in one go, compared with writing them one
+    // at a time
+    let list = ssz_types::VariableList::<u64, C>::new((0..size).collect()).unwrap();
+    group.bench_with_input(
+        BenchmarkId::new("VariableList", "ssz_write to slice"),
+        &list,
+        |b, list| {
+            let mut buf: Vec<u8> = vec![0u8; list.sszb_bytes_len()];
+            b.iter(|| list.ssz_write(&mut buf.as_mut_slice()))
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("VariableList", "item by item"),
+        &list,
+        |b, list| {
+            let mut buf: Vec<u8> = vec![0u8; list.sszb_bytes_len()];
+            b.iter(|| {
+                let mut slice = buf.as_mut_slice();
+                for item in list.iter() {
+                    item.ssz_write(&mut slice);
+                }
+            })
+        },
+    );
+
+    group.finish();
+}
+
+fn byte_lists(c: &mut Criterion) {
+    use milhouse::List;
+    use ssz_types::VariableList;
+
+    type C = typenum::U1073741824;
+    const N: usize = 16 * 1024 * 1024;
+
+    let mut group = c.benchmark_group("ByteList");
+
+    let bytes: Vec<u8> = (0..N).map(|i| i as u8).collect();
+    let list = VariableList::<u8, C>::new(bytes).unwrap();
+    let list_bytes = list.to_ssz();
+
+    group.throughput(Throughput::Bytes(list_bytes.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("VariableList", "decode"),
+        &list_bytes,
+        |b, bytes| b.iter(|| <VariableList<u8, C> as SszbDecode>::from_ssz_bytes(bytes).unwrap()),
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("VariableList", "ssz_write to slice"),
+        &list,
+        |b, list| {
+            let len = list.sszb_bytes_len();
+            let mut buf: Vec<u8> = vec![0u8; len];
+            b.iter(|| list.ssz_write(&mut buf.as_mut_slice()))
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Milhouse", "decode"),
+        &list_bytes,
+        |b, bytes| b.iter(|| <List<u8, C> as SszbDecode>::from_ssz_bytes(bytes).unwrap()),
+    );
+
+    group.finish();
+}
+
+fn roots(c: &mut Criterion) {
+    use alloy_primitives::B256;
+    use milhouse::Vector;
+    use ssz_types::FixedVector;
+    use typenum::Unsigned;
+
+    // the length of the block and state root vectors of a state
+    type C = typenum::U8192;
+
+    let mut group = c.benchmark_group("Roots");
+
+    let roots: Vec<B256> = (0..C::USIZE as u64)
+        .map(|i| B256::left_padding_from(&i.to_le_bytes()))
+        .collect();
+    let vector = FixedVector::<B256, C>::new(roots.clone()).unwrap();
+    let vector_bytes = vector.to_ssz();
+
+    group.throughput(Throughput::Bytes(vector_bytes.len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("FixedVector", "decode"),
+        &vector_bytes,
+        |b, bytes| b.iter(|| <FixedVector<B256, C> as SszbDecode>::from_ssz_bytes(bytes).unwrap()),
+    );
+
+    // the roots are copied in one go, compared with decoding them one at a time
+    group.bench_with_input(
+        BenchmarkId::new("FixedVector", "decode item by item"),
+        &vector_bytes,
+        |b, bytes| {
+            b.iter(|| {
+                bytes
+                    .chunks_exact(32)
+                    .map(|chunk| B256::from_ssz_bytes(chunk).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("FixedVector", "ssz_write to slice"),
+        &vector,
+        |b, vector| {
+            let mut buf: Vec<u8> = vec![0u8; vector.sszb_bytes_len()];
+            b.iter(|| vector.ssz_write(&mut buf.as_mut_slice()))
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("FixedVector", "item by item"),
+        &vector,
+        |b, vector| {
+            let mut buf: Vec<u8> = vec![0u8; vector.sszb_bytes_len()];
+            b.iter(|| {
+                let mut slice = buf.as_mut_slice();
+                for item in vector.iter() {
+                    item.ssz_write(&mut slice);
+                }
+            })
+        },
+    );
+
+    // the leaves of a tree are gathered into runs
+    let vector = Vector::<B256, C>::new(roots).unwrap();
+    group.bench_with_input(
+        BenchmarkId::new("Milhouse", "to_ssz"),
+        &vector,
+        |b, vector| b.iter(|| vector.to_ssz()),
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Milhouse", "decode"),
+        &vector_bytes,
+        |b, bytes| b.iter(|| <Vector<B256, C> as SszbDecode>::from_ssz_bytes(bytes).unwrap()),
+    );
+
+    group.finish();
+}
+
+fn bitfields(c: &mut Criterion) {
+    use ssz_types::{BitList, VariableList};
+
+    type C = typenum::U128;
+
+    let mut group = c.benchmark_group("BitLists");
+
+    // attestation-sized aggregation bits, on and off byte boundaries
+    let lists = (0..128)
+        .map(|i| {
+            let mut bits = BitList::<typenum::U2048>::with_capacity(2048 - i).unwrap();
+            for j in (0..bits.len()).step_by(3) {
+                bits.set(j, true).unwrap();
+            }
+            bits
+        })
+        .collect::<Vec<_>>();
+    let list = VariableList::<_, C>::new(lists).unwrap();
+    group.throughput(Throughput::Bytes(list.sszb_bytes_len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("VariableList", "ssz_write to slice"),
+        &list,
+        |b, list| {
+            let len = list.sszb_bytes_len();
+            let mut buf: Vec<u8> = vec![0u8; len];
+            b.iter(|| list.ssz_write(&mut buf.as_mut_slice()))
+        },
+    );
+
+    group.finish();
+}
+
+fn transactions(c: &mut Criterion) {
+    use milhouse::List;
+    use ssz_types::VariableList;
+
+    type Transaction = VariableList<u8, typenum::U1073741824>;
+
+    let mut group = c.benchmark_group("Transactions");
+
+    // a block's worth of transactions, mostly small transfers with some large contract calls
+    let transactions = (0..1000)
+        .map(|i| {
+            let len = if i % 10 == 0 {
+                4096 + i * 7
+            } else {
+                110 + i % 200
+            };
+            Transaction::new(vec![i as u8; len]).unwrap()
+        })
+        .collect::<Vec<_>>();
+    let list = List::<Transaction, typenum::U1048576>::new(transactions).unwrap();
+    group.throughput(Throughput::Bytes(list.sszb_bytes_len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("Milhouse", "ssz_write to slice"),
+        &list,
+        |b, list| {
+            let mut buf: Vec<u8> = vec![0u8; list.sszb_bytes_len()];
+            b.iter(|| list.ssz_write(&mut buf.as_mut_slice()))
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Milhouse", "ssz_write_slice"),
+        &list,
+        |b, list| {
+            let mut buf: Vec<u8> = vec![0u8; list.sszb_bytes_len()];
+            b.iter(|| list.ssz_write_slice(&mut buf))
+        },
+    );
+
+    group.finish();
+}
+
+fn small_containers(c: &mut Criterion) {
+    use bytes::buf::{Buf, BufMut};
+    use ssz_types::VariableList;
+    use sszb_derive::{SszbDecode, SszbEncode};
+
+    #[derive(SszbEncode, SszbDecode)]
+    struct Attestation {
+        aggregation_bits: VariableList<u8, typenum::U256>,
+        slot: u64,
+        index: u64,
+        committees: VariableList<VariableList<u64, typenum::U16>, typenum::U8>,
+        signature: [u8; 96],
+    }
+
+    let mut group = c.benchmark_group("Attestations");
+
+    // a batch of small containers, each with a few lists of its own
+    let batch = (0..10_000u64)
+        .map(|i| {
+            Attestation {
+                aggregation_bits: VariableList::new(vec![i as u8; 64]).unwrap(),
+                slot: i,
+                index: i % 64,
+                committees: VariableList::new(vec![VariableList::new(vec![i; 4]).unwrap(); 2])
+                    .unwrap(),
+                signature: [i as u8; 96],
+            }
+            .to_ssz()
+        })
+        .collect::<Vec<_>>();
+    group.throughput(Throughput::Elements(batch.len() as u64));
+
+    group.bench_with_input(BenchmarkId::new("Sszb", "decode"), &batch, |b, batch| {
+        b.iter(|| {
+            for bytes in batch {
+                criterion::black_box(Attestation::from_ssz_bytes(bytes).unwrap());
+            }
+        })
+    });
+
+    // the same batch with the lists in an arena, reset after every batch
+    #[cfg(feature = "bumpalo")]
+    {
+        use sszb::arena::{ArenaList, Bump, SszbDecodeIn};
+        use sszb_derive::SszbDecodeIn;
+
+        #[derive(SszbDecodeIn)]
+        struct ArenaAttestation<'a> {
+            aggregation_bits: ArenaList<'a, u8, 256>,
+            slot: u64,
+            index: u64,
+            committees: ArenaList<'a, ArenaList<'a, u64, 16>, 8>,
+            signature: [u8; 96],
+        }
+
+        let mut arena = Bump::new();
+        group.bench_with_input(BenchmarkId::new("Sszb", "decode_in"), &batch, |b, batch| {
+            b.iter(|| {
+                for bytes in batch {
+                    criterion::black_box(
+                        ArenaAttestation::from_ssz_bytes_in(bytes, &arena).unwrap(),
+                    );
+                }
+                arena.reset();
+            })
+        });
+    }
+
     group.finish();
 }
 
 fn beacon_block(c: &mut Criterion) {
     let mut group = c.benchmark_group("SignedBeaconBlock");
-    let block_bytes: Vec<u8> = std::fs::read("beacon-block.ssz").unwrap();
-    let beacon_block =
-        <SignedBeaconBlock as SszbDecode>::from_ssz_bytes(block_bytes.as_slice()).unwrap();
+    let (beacon_block, block_bytes) = fixture::<SignedBeaconBlock>("beacon-block.ssz", || {
+        test_utils::signed_beacon_block(1, &BlockConfig::default())
+    });
     group.throughput(Throughput::Bytes(block_bytes.len() as u64));
 
     group.bench_with_input(
@@ -75,12 +380,44 @@ fn beacon_block(c: &mut Criterion) {
     );
 
     group.finish();
+
+    // the same block, with a few megabytes of transactions that a vectored write only borrows
+    let mut group = c.benchmark_group("SignedBeaconBlock with large transactions");
+    let mut heavy_block = beacon_block.clone();
+    heavy_block.message.body.execution_payload.transactions = ssz_types::VariableList::new(
+        (0..32)
+            .map(|i| ssz_types::VariableList::new(vec![i as u8; 128 * 1024]).unwrap())
+            .collect(),
+    )
+    .unwrap();
+    group.throughput(Throughput::Bytes(heavy_block.sszb_bytes_len() as u64));
+
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "encode naive"),
+        &heavy_block,
+        |b, block| b.iter(|| block.to_ssz()),
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "ssz_write_vectored"),
+        &heavy_block,
+        |b, block| {
+            b.iter(|| {
+                let mut segments = sszb::vectored::Segments::new();
+                block.ssz_write_vectored(&mut segments);
+                segments.copied_len()
+            })
+        },
+    );
+
+    group.finish();
 }
 
 fn beacon_state(c: &mut Criterion) {
     let mut group = c.benchmark_group("BeaconState");
-    let state_bytes: Vec<u8> = std::fs::read("beacon-state.ssz").unwrap();
-    let beacon_state = <BeaconState as SszbDecode>::from_ssz_bytes(state_bytes.as_slice()).unwrap();
+    let (beacon_state, state_bytes) = fixture::<BeaconState>("beacon-state.ssz", || {
+        test_utils::beacon_state(1, &StateConfig::default())
+    });
     group.throughput(Throughput::Bytes(state_bytes.len() as u64));
     group.sample_size(10);
 
@@ -90,6 +427,33 @@ fn beacon_state(c: &mut Criterion) {
         |b, bytes| b.iter(|| <BeaconState as SszbDecode>::from_ssz_bytes(bytes).unwrap()),
     );
 
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "decode trusted"),
+        state_bytes.as_slice(),
+        |b, bytes| b.iter(|| <BeaconState as SszbDecode>::from_ssz_bytes_trusted(bytes).unwrap()),
+    );
+
+    // checks the same layout as the decode, without building the state
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "validate"),
+        state_bytes.as_slice(),
+        |b, bytes| b.iter(|| sszb::validate_ssz::<BeaconState>(bytes).unwrap()),
+    );
+
+    // the same decode, with the large lists and vectors of the state split up between the
+    // threads of the global pool
+    #[cfg(feature = "rayon")]
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "decode parallel"),
+        state_bytes.as_slice(),
+        |b, bytes| {
+            b.iter(|| {
+                <BeaconState as sszb::parallel::SszbDecodeParallel>::from_ssz_bytes_parallel(bytes)
+                    .unwrap()
+            })
+        },
+    );
+
     group.bench_with_input(
         BenchmarkId::new("Sszb", "encode naive"),
         &beacon_state,
@@ -106,8 +470,63 @@ fn beacon_state(c: &mut Criterion) {
         },
     );
 
+    // a fresh buffer per encode, zeroed first and then not
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "ssz_write to zeroed Vec"),
+        &beacon_state,
+        |b, state| {
+            b.iter(|| {
+                let mut buf: Vec<u8> = vec![0u8; state.sszb_bytes_len()];
+                state.ssz_write(&mut buf.as_mut_slice());
+                buf
+            })
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "encode_to_uninit"),
+        &beacon_state,
+        |b, state| {
+            b.iter(|| {
+                let mut buf: Vec<u8> = Vec::with_capacity(state.sszb_bytes_len());
+                let len = state
+                    .encode_to_uninit(buf.spare_capacity_mut())
+                    .unwrap()
+                    .len();
+                unsafe { buf.set_len(len) };
+                buf
+            })
+        },
+    );
+
+    #[cfg(feature = "rayon")]
+    group.bench_with_input(
+        BenchmarkId::new("Sszb", "ssz_write_parallel to slice"),
+        &beacon_state,
+        |b, state| {
+            let len = state.sszb_bytes_len();
+            let mut buf: Vec<u8> = vec![0u8; len];
+            b.iter(|| {
+                <BeaconState as sszb::parallel::SszbEncodeParallel>::ssz_write_parallel(
+                    state,
+                    buf.as_mut_slice(),
+                )
+            })
+        },
+    );
+
     group.finish();
 }
 
-criterion_group!(benches, basic_types, beacon_block, beacon_state);
+criterion_group!(
+    benches,
+    basic_types,
+    byte_lists,
+    roots,
+    bitfields,
+    transactions,
+    small_containers,
+    beacon_block,
+    beacon_state
+);
 criterion_main!(benches);
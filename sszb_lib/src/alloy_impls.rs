@@ -0,0 +1,287 @@
+use crate::decode::{decode_impls::read_byte_arrays, read_contiguous};
+use crate::dynamic::{SszTypeInfo, SszbTypeInfo};
+use crate::encode::encode_impls::{byte_arrays, write_byte_run_vectored};
+use crate::vectored::Segments;
+use crate::{DecodeError, SszZero, SszbDecode, SszbEncode};
+use alloc::vec::Vec;
+use alloy_primitives::{Address, Bloom, FixedBytes, Uint};
+use bytes::buf::{Buf, BufMut};
+
+impl SszbEncode for Address {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 20;
+    const SSZ_MAX_LEN: usize = 20;
+    const SSZ_POD: bool = true;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.as_slice());
+    }
+
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        // SAFETY: Address and FixedBytes are repr(transparent) wrappers around their bytes
+        buf.put_slice(unsafe { byte_arrays::<Self, 20>(items) });
+    }
+
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        // SAFETY: see ssz_write_packed
+        write_byte_run_vectored(unsafe { byte_arrays::<Self, 20>(items) }, segments);
+    }
+}
+
+impl SszbDecode for Address {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 20;
+    const SSZ_MAX_LEN: usize = 20;
+    const SSZ_POD: bool = true;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let mut bytes = [0u8; 20];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(Self::from(bytes))
+        }
+    }
+
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut items = Vec::new();
+        read_byte_arrays::<Self, 20>(bytes, &mut items)?;
+        Ok(items)
+    }
+
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        read_byte_arrays::<Self, 20>(bytes, items)
+    }
+}
+
+impl<const N: usize> SszbEncode for FixedBytes<N> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+    const SSZ_MAX_LEN: usize = N;
+    const SSZ_POD: bool = true;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&self.0)
+    }
+
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        // SAFETY: Address and FixedBytes are repr(transparent) wrappers around their bytes
+        buf.put_slice(unsafe { byte_arrays::<Self, N>(items) });
+    }
+
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        // SAFETY: see ssz_write_packed
+        write_byte_run_vectored(unsafe { byte_arrays::<Self, N>(items) }, segments);
+    }
+}
+
+impl<const N: usize> SszbDecode for FixedBytes<N> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+    const SSZ_MAX_LEN: usize = N;
+    const SSZ_POD: bool = true;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if N == 0 {
+            Err(DecodeError::ZeroLengthVector)
+        } else if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let mut bytes = [0u8; N];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(Self(bytes))
+        }
+    }
+
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut items = Vec::new();
+        read_byte_arrays::<Self, N>(bytes, &mut items)?;
+        Ok(items)
+    }
+
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        read_byte_arrays::<Self, N>(bytes, items)
+    }
+}
+
+impl SszbEncode for Bloom {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 256;
+    const SSZ_MAX_LEN: usize = 256;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&self.0 .0)
+    }
+}
+
+impl SszbDecode for Bloom {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 256;
+    const SSZ_MAX_LEN: usize = 256;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let mut bytes = [0u8; 256];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(Self::from_slice(&bytes))
+        }
+    }
+}
+
+// the byte length of a Uint, which is only defined for widths that are a whole number of bytes:
+// using any other width fails to compile, rather than silently padding the value
+const fn uint_bytes_len<const BITS: usize>() -> usize {
+    assert!(
+        BITS.is_multiple_of(8),
+        "only byte-aligned Uint widths have an SSZ encoding"
+    );
+    BITS / 8
+}
+
+// U256, U128 and the rest of the alloy integers, which are all ruint's Uint
+impl<const BITS: usize, const LIMBS: usize> SszbEncode for Uint<BITS, LIMBS> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = uint_bytes_len::<BITS>();
+    const SSZ_MAX_LEN: usize = uint_bytes_len::<BITS>();
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        // the limbs may hold more bytes than the width, which are always zero
+        buf.put_slice(&self.as_le_slice()[..<Self as SszbEncode>::SSZ_FIXED_LEN]);
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> SszbDecode for Uint<BITS, LIMBS> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = uint_bytes_len::<BITS>();
+    const SSZ_MAX_LEN: usize = uint_bytes_len::<BITS>();
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            // BITS / 8 bytes always fit, so from_le_slice can't panic
+            Ok(read_contiguous(fixed_bytes, expected, Self::from_le_slice))
+        }
+    }
+}
+
+impl SszbTypeInfo for Address {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 20)
+    }
+}
+
+impl<const N: usize> SszbTypeInfo for FixedBytes<N> {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), N)
+    }
+}
+
+impl SszbTypeInfo for Bloom {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 256)
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> SszbTypeInfo for Uint<BITS, LIMBS> {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::Uint(uint_bytes_len::<BITS>())
+    }
+}
+
+impl SszZero for Address {
+    fn ssz_zero() -> Self {
+        Address::ZERO
+    }
+}
+
+impl<const N: usize> SszZero for FixedBytes<N> {
+    fn ssz_zero() -> Self {
+        FixedBytes::ZERO
+    }
+}
+
+impl SszZero for Bloom {
+    fn ssz_zero() -> Self {
+        Bloom::ZERO
+    }
+}
+
+impl<const BITS: usize, const LIMBS: usize> SszZero for Uint<BITS, LIMBS> {
+    fn ssz_zero() -> Self {
+        Uint::ZERO
+    }
+}
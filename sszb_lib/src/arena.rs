@@ -0,0 +1,237 @@
+//! Decoding into an arena, for workloads that decode many small containers and drop them soon
+//! after, attestations off the wire being the usual example.
+//!
+//! `SszbDecodeIn<'a>` mirrors `SszbDecodeRef<'a>`, but instead of borrowing from the input, the
+//! result keeps its lists in a `bumpalo` arena that outlives it. It is implemented for
+//! `ArenaList<'a, T, N>`, every `SszbDecode` type (which allocates as usual) and containers with
+//! `#[derive(SszbDecodeIn)]`:
+//!
+//! ```ignore
+//! #[derive(SszbEncode, SszbDecodeIn)]
+//! struct Attestation<'a> {
+//!     aggregation_bits: ArenaList<'a, u8, 256>,
+//!     data: AttestationData,
+//!     signature: [u8; 96],
+//! }
+//!
+//! let mut arena = Bump::new();
+//! for batch in batches {
+//!     for message in batch {
+//!         let attestation: Attestation = from_ssz_bytes_in(&message, &arena)?;
+//!         // ...
+//!     }
+//!     arena.reset();
+//! }
+//! ```
+//!
+//! Resetting the arena frees everything decoded into it at once, and keeps its memory for the
+//! next batch. It takes `&mut Bump`, so nothing decoded before can still be around to point into
+//! it. Offsets and lengths are validated exactly as `SszbDecode` does, with the same errors.
+
+use crate::decode::decode_impls::VariableItemRanges;
+use crate::encode::{record_item_lens, write_items_cached, write_items_slice, write_to_slice};
+use crate::raw::{peek_list_len_static, peek_list_len_variable, split_offset_table};
+use crate::vectored::Segments;
+use crate::{
+    check_items_bounds, enclosed_max_len, expect_len, expect_offset, try_items_bytes_len,
+    write_offset, DecodeError, EncodeError, SszbDecode, SszbEncode, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use bytes::buf::BufMut;
+use core::ops::{Deref, DerefMut};
+
+pub use bumpalo::Bump;
+
+pub trait SszbDecodeIn<'a>: Sized {
+    const SSZ_STATIC: bool;
+    const SSZ_FIXED_LEN: usize;
+
+    fn from_ssz_bytes_in(bytes: &[u8], arena: &'a Bump) -> Result<Self, DecodeError>;
+}
+
+/// Decodes `bytes` as a `T` whose lists are allocated in `arena`.
+pub fn from_ssz_bytes_in<'a, T: SszbDecodeIn<'a>>(
+    bytes: &[u8],
+    arena: &'a Bump,
+) -> Result<T, DecodeError> {
+    T::from_ssz_bytes_in(bytes, arena)
+}
+
+impl<'a, T: SszbDecode> SszbDecodeIn<'a> for T {
+    const SSZ_STATIC: bool = <T as SszbDecode>::SSZ_STATIC;
+    const SSZ_FIXED_LEN: usize = <T as SszbDecode>::SSZ_FIXED_LEN;
+
+    fn from_ssz_bytes_in(bytes: &[u8], _arena: &'a Bump) -> Result<Self, DecodeError> {
+        T::from_ssz_bytes(bytes)
+    }
+}
+
+/// An SSZ list of at most `N` items, stored in an arena. It encodes exactly like any other list
+/// with the same maximum length, and dereferences to a slice of its items.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ArenaList<'a, T, const N: usize> {
+    items: bumpalo::collections::Vec<'a, T>,
+}
+
+impl<'a, T, const N: usize> ArenaList<'a, T, N> {
+    pub fn new_in(arena: &'a Bump) -> Self {
+        Self {
+            items: bumpalo::collections::Vec::new_in(arena),
+        }
+    }
+
+    /// Appends `item`, or hands it back if the list already holds `N` items.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.items.len() >= N {
+            return Err(item);
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    /// The items, for as long as the arena lives rather than as long as the list does.
+    pub fn into_bump_slice(self) -> &'a [T] {
+        self.items.into_bump_slice()
+    }
+}
+
+impl<T, const N: usize> Deref for ArenaList<'_, T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArenaList<'_, T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+}
+
+impl<T: SszbEncode, const N: usize> SszbEncode for ArenaList<'_, T, N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize =
+        enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN).saturating_mul(N);
+
+    fn sszb_bytes_len(&self) -> usize {
+        expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        try_items_bytes_len::<T>(self.len(), self.iter())
+    }
+
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        check_items_bounds::<T>(self.len(), N, self.iter())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        if T::SSZ_STATIC {
+            T::ssz_write_packed(self, buf);
+        } else {
+            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+            for item in self.iter() {
+                item.ssz_write_fixed(offset, buf);
+            }
+            for item in self.iter() {
+                item.ssz_write(buf);
+            }
+        }
+    }
+
+    fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+        if T::SSZ_STATIC {
+            T::ssz_write_packed_vectored(self, segments);
+        } else {
+            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+            for item in self.iter() {
+                item.ssz_write_fixed(offset, segments.scratch());
+            }
+            for item in self.iter() {
+                item.ssz_write_vectored(segments);
+            }
+        }
+    }
+
+    fn ssz_record_lens(&self, lens: &mut Vec<usize>) -> usize {
+        if T::SSZ_STATIC {
+            self.sszb_bytes_len()
+        } else {
+            record_item_lens(self.len(), self.iter(), lens)
+        }
+    }
+
+    fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+        if T::SSZ_STATIC {
+            self.ssz_write(buf);
+        } else {
+            write_items_cached(self.len(), self.iter(), lens, buf);
+        }
+    }
+
+    fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+        if T::SSZ_STATIC {
+            write_to_slice(buf, |rest| self.ssz_write(rest))
+        } else {
+            write_items_slice(self.len(), self.iter(), buf)
+        }
+    }
+}
+
+// like the heapless impl, fails before decoding any item if the list holds more than N of them,
+// and then reserves room for all of them in one go
+impl<'a, T: SszbDecodeIn<'a>, const N: usize> SszbDecodeIn<'a> for ArenaList<'a, T, N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+
+    fn from_ssz_bytes_in(bytes: &[u8], arena: &'a Bump) -> Result<Self, DecodeError> {
+        let mut list = Self::new_in(arena);
+        if bytes.is_empty() {
+            return Ok(list);
+        }
+
+        let num_items = if T::SSZ_STATIC {
+            peek_list_len_static(bytes, T::SSZ_FIXED_LEN)?
+        } else {
+            peek_list_len_variable(bytes)?
+        };
+        crate::enter_trace_span!("ssz_decode_list", items = num_items, len = bytes.len());
+        if num_items > N {
+            return Err(DecodeError::ListTooLong {
+                len: num_items,
+                max: N,
+            });
+        }
+        list.items.reserve_exact(num_items);
+
+        if T::SSZ_STATIC {
+            let item_len = T::SSZ_FIXED_LEN;
+            for (i, chunk) in bytes.chunks_exact(item_len).enumerate() {
+                let item =
+                    T::from_ssz_bytes_in(chunk, arena).map_err(|e| e.at_byte(i * item_len))?;
+                list.items.push(item);
+            }
+        } else {
+            // peek_list_len_variable has already checked the first offset
+            let (offsets, _) = split_offset_table(bytes, num_items)?;
+            for range in VariableItemRanges::checked(offsets, bytes.len())? {
+                let range = range?;
+                let item = T::from_ssz_bytes_in(&bytes[range.clone()], arena)
+                    .map_err(|e| e.at_byte(range.start))?;
+                list.items.push(item);
+            }
+        }
+        Ok(list)
+    }
+}
@@ -0,0 +1,51 @@
+/// Fails to compile unless the type is static, e.g. `assert_ssz_static!(Checkpoint);`.
+///
+/// Useful next to types that other code relies on staying fixed-size, so that a refactor that
+/// adds a variable field breaks the build rather than something at runtime.
+#[macro_export]
+macro_rules! assert_ssz_static {
+    ($ty: ty) => {
+        const _: () = assert!(
+            <$ty as $crate::SszbEncode>::SSZ_STATIC,
+            concat!("`", stringify!($ty), "` is not SSZ static")
+        );
+    };
+}
+
+/// Fails to compile unless the type is static and encodes to exactly the given number of bytes,
+/// e.g. `assert_ssz_fixed_len!(Checkpoint, 40);`.
+#[macro_export]
+macro_rules! assert_ssz_fixed_len {
+    ($ty: ty, $len: expr) => {
+        const _: () = assert!(
+            <$ty as $crate::SszbEncode>::SSZ_STATIC
+                && <$ty as $crate::SszbEncode>::SSZ_FIXED_LEN == $len,
+            concat!(
+                "`",
+                stringify!($ty),
+                "` is not SSZ static with a fixed length of ",
+                stringify!($len)
+            )
+        );
+    };
+}
+
+/// Panics unless the type's `SSZ_SCHEMA` fingerprint is the given `0x`-prefixed hex string,
+/// e.g. `assert_schema!(BeaconBlock, "0x…");` in a test.
+///
+/// Pin the fingerprint of every type whose bytes outlive the process that wrote them, so that a
+/// change to its layout fails CI instead of decoding old bytes with the new definition. The
+/// panic message has the new fingerprint, for when the change is intended.
+#[macro_export]
+macro_rules! assert_schema {
+    ($ty: ty, $fingerprint: expr) => {{
+        let actual = $crate::schema::to_hex(&<$ty as $crate::SszbDecode>::SSZ_SCHEMA);
+        assert!(
+            actual == $fingerprint,
+            "the SSZ layout of `{}` changed: its fingerprint is {} instead of {}",
+            stringify!($ty),
+            actual,
+            $fingerprint
+        );
+    }};
+}
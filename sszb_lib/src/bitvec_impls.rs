@@ -0,0 +1,127 @@
+use crate::{
+    expect_offset, read_contiguous, write_offset, DecodeError, SszZero, SszbDecode, SszbEncode,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use bitvec::{array::BitArray, field::BitField, order::Lsb0, vec::BitVec};
+use bytes::buf::{Buf, BufMut};
+
+// a BitVec is encoded as an SSZ bitlist, bit i being bit i % 8 of byte i / 8 followed by the
+// length bit, exactly like ssz_types' BitList. it has no maximum length of its own
+impl SszbEncode for BitVec<u8, Lsb0> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        // the bits, plus the length bit that marks where they end
+        self.len() / 8 + 1
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        // the bits past the end of a BitVec aren't guaranteed to be zero, and it needn't start
+        // at the beginning of its first byte, so its bytes are loaded rather than copied
+        let chunks = self.chunks_exact(8);
+        let rest = chunks.remainder();
+        for chunk in chunks {
+            buf.put_u8(chunk.load_le::<u8>());
+        }
+        let last = if rest.is_empty() {
+            0
+        } else {
+            rest.load_le::<u8>()
+        };
+        buf.put_u8(last | (1 << (self.len() % 8)));
+    }
+}
+
+impl SszbDecode for BitVec<u8, Lsb0> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let remaining = variable_bytes.remaining();
+        read_contiguous(variable_bytes, remaining, |bytes| {
+            // the highest set bit of the last byte is the length bit
+            let len = match bytes.last() {
+                Some(&last) if last != 0 => {
+                    (bytes.len() - 1) * 8 + (7 - last.leading_zeros() as usize)
+                }
+                _ => return Err(DecodeError::BitfieldError("its length bit is missing")),
+            };
+
+            let mut bits = BitVec::from_slice(bytes);
+            bits.truncate(len);
+            Ok(bits)
+        })
+    }
+}
+
+// a BitArray of N bytes is encoded as an SSZ bitvector of 8 * N bits, with the same bit order as
+// ssz_types' BitVector. every bit of its bytes is used, so there are no excess bits to check
+impl<const N: usize> SszbEncode for BitArray<[u8; N], Lsb0> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+    const SSZ_MAX_LEN: usize = N;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.as_raw_slice());
+    }
+}
+
+impl<const N: usize> SszbDecode for BitArray<[u8; N], Lsb0> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+    const SSZ_MAX_LEN: usize = N;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        if N == 0 {
+            return Err(DecodeError::ZeroLengthVector);
+        }
+        if len < N {
+            return Err(DecodeError::InvalidByteLength { len, expected: N });
+        }
+
+        let mut bytes = [0u8; N];
+        fixed_bytes.copy_to_slice(&mut bytes);
+        Ok(BitArray::new(bytes))
+    }
+}
+
+impl SszZero for BitVec<u8, Lsb0> {
+    fn ssz_zero() -> Self {
+        BitVec::new()
+    }
+}
+
+impl<const N: usize> SszZero for BitArray<[u8; N], Lsb0> {
+    fn ssz_zero() -> Self {
+        BitArray::ZERO
+    }
+}
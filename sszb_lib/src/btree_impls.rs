@@ -0,0 +1,282 @@
+//! `BTreeSet` and `BTreeMap`, which aren't consensus types: SSZ has no sets or maps, so these are
+//! an encoding of our own. A set is a list of its elements, and a map is a list of its entries,
+//! each a container of the key and the value, both in increasing order. To keep the encoding
+//! canonical, decoding rejects lists that aren't strictly increasing.
+
+use crate::decode::{invalid_value, read_contiguous};
+use crate::{
+    check_items_bounds, check_part_bounds, container_field_ranges, decode_list_into,
+    enclosed_max_len, expect_len, expect_offset, try_items_bytes_len, write_offset, DecodeError,
+    EncodeError, SszZero, SszbDecode, SszbEncode, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+use core::borrow::Borrow;
+
+// a map entry, encoded as the container { key, value }
+struct Entry<K, V> {
+    key: K,
+    value: V,
+}
+
+impl<'a, K, V> From<(&'a K, &'a V)> for Entry<&'a K, &'a V> {
+    fn from((key, value): (&'a K, &'a V)) -> Self {
+        Entry { key, value }
+    }
+}
+
+impl<K: SszbEncode, V: SszbEncode> SszbEncode for Entry<&K, &V> {
+    const SSZ_STATIC: bool = K::SSZ_STATIC && V::SSZ_STATIC;
+    const SSZ_FIXED_LEN: usize = if <Self as SszbEncode>::SSZ_STATIC {
+        K::SSZ_FIXED_LEN + V::SSZ_FIXED_LEN
+    } else {
+        BYTES_PER_LENGTH_OFFSET
+    };
+    const SSZ_MAX_LEN: usize =
+        enclosed_max_len(K::SSZ_STATIC, K::SSZ_FIXED_LEN, K::SSZ_MAX_LEN).saturating_add(
+            enclosed_max_len(V::SSZ_STATIC, V::SSZ_FIXED_LEN, V::SSZ_MAX_LEN),
+        );
+
+    fn sszb_bytes_len(&self) -> usize {
+        expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        let mut len = K::SSZ_FIXED_LEN + V::SSZ_FIXED_LEN;
+        if !K::SSZ_STATIC {
+            len = len
+                .checked_add(self.key.try_sszb_bytes_len()?)
+                .ok_or(EncodeError::LengthOverflow)?;
+        }
+        if !V::SSZ_STATIC {
+            len = len
+                .checked_add(self.value.try_sszb_bytes_len()?)
+                .ok_or(EncodeError::LengthOverflow)?;
+        }
+        Ok(len)
+    }
+
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        // static parts can't hold a list, or an offset
+        let mut offset = K::SSZ_FIXED_LEN + V::SSZ_FIXED_LEN;
+        if !K::SSZ_STATIC {
+            check_part_bounds(self.key, &mut offset)?;
+        }
+        if !V::SSZ_STATIC {
+            check_part_bounds(self.value, &mut offset)?;
+        }
+        Ok(())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        if <Self as SszbEncode>::SSZ_STATIC {
+            self.ssz_write(buf);
+        } else {
+            expect_offset(write_offset(*offset, buf));
+            *offset += self.sszb_bytes_len();
+        }
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        if !<Self as SszbEncode>::SSZ_STATIC {
+            self.ssz_write(buf);
+        }
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        let mut offset = K::SSZ_FIXED_LEN + V::SSZ_FIXED_LEN;
+        self.key.ssz_write_fixed(&mut offset, buf);
+        self.value.ssz_write_fixed(&mut offset, buf);
+        self.key.ssz_write_variable(buf);
+        self.value.ssz_write_variable(buf);
+    }
+}
+
+impl<K: SszbDecode, V: SszbDecode> SszbDecode for Entry<K, V> {
+    const SSZ_STATIC: bool = K::SSZ_STATIC && V::SSZ_STATIC;
+    const SSZ_FIXED_LEN: usize = if <Self as SszbDecode>::SSZ_STATIC {
+        K::SSZ_FIXED_LEN + V::SSZ_FIXED_LEN
+    } else {
+        BYTES_PER_LENGTH_OFFSET
+    };
+    const SSZ_MAX_LEN: usize =
+        enclosed_max_len(K::SSZ_STATIC, K::SSZ_FIXED_LEN, K::SSZ_MAX_LEN).saturating_add(
+            enclosed_max_len(V::SSZ_STATIC, V::SSZ_FIXED_LEN, V::SSZ_MAX_LEN),
+        );
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        if <Self as SszbDecode>::SSZ_STATIC {
+            let key = K::ssz_read(fixed_bytes, variable_bytes)?;
+            let value = V::ssz_read(fixed_bytes, variable_bytes)
+                .map_err(|e| e.at_byte(K::SSZ_FIXED_LEN))?;
+            return Ok(Entry { key, value });
+        }
+
+        let len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, len, |bytes| {
+            let ranges = container_field_ranges(
+                bytes,
+                &[
+                    (K::SSZ_STATIC, K::SSZ_FIXED_LEN),
+                    (V::SSZ_STATIC, V::SSZ_FIXED_LEN),
+                ],
+            )?;
+            Ok(Entry {
+                key: K::from_ssz_bytes(&bytes[ranges[0].clone()])
+                    .map_err(|e| e.at_byte(ranges[0].start))?,
+                value: V::from_ssz_bytes(&bytes[ranges[1].clone()])
+                    .map_err(|e| e.at_byte(ranges[1].start))?,
+            })
+        })
+    }
+}
+
+fn write_list<T: SszbEncode>(
+    num_items: usize,
+    items: impl Iterator<Item = impl Borrow<T>> + Clone,
+    buf: &mut impl BufMut,
+) {
+    let mut offset = num_items * T::SSZ_FIXED_LEN;
+    for item in items.clone() {
+        item.borrow().ssz_write_fixed(&mut offset, buf);
+    }
+    for item in items {
+        item.borrow().ssz_write_variable(buf);
+    }
+}
+
+// decodes a list of any length, all of whose bytes are in variable_bytes, and checks that its
+// items are in strictly increasing order
+fn read_sorted_list<T: SszbDecode>(
+    variable_bytes: &mut impl Buf,
+    is_less: impl Fn(&T, &T) -> bool,
+    unsorted: &'static str,
+) -> Result<Vec<T>, DecodeError> {
+    let len = variable_bytes.remaining();
+    let items = read_contiguous(variable_bytes, len, |bytes| {
+        let mut items = Vec::new();
+        decode_list_into(bytes, usize::MAX, &mut items).map(|()| items)
+    })?;
+
+    if items.windows(2).any(|pair| !is_less(&pair[0], &pair[1])) {
+        return Err(invalid_value(unsorted));
+    }
+    Ok(items)
+}
+
+impl<T: SszbEncode> SszbEncode for BTreeSet<T> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        try_items_bytes_len::<T>(self.len(), self.iter())
+    }
+
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        check_items_bounds::<T>(self.len(), usize::MAX, self.iter())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        write_list::<T>(self.len(), self.iter(), buf);
+    }
+}
+
+impl<T: SszbDecode + Ord> SszbDecode for BTreeSet<T> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let items = read_sorted_list(
+            variable_bytes,
+            |a: &T, b| a < b,
+            "BTreeSet elements are not in strictly increasing order",
+        )?;
+        Ok(items.into_iter().collect())
+    }
+}
+
+impl<K: SszbEncode, V: SszbEncode> SszbEncode for BTreeMap<K, V> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        try_items_bytes_len::<Entry<&K, &V>>(self.len(), self.iter().map(Entry::from))
+    }
+
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        check_items_bounds::<Entry<&K, &V>>(self.len(), usize::MAX, self.iter().map(Entry::from))
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        write_list::<Entry<&K, &V>>(self.len(), self.iter().map(Entry::from), buf);
+    }
+}
+
+impl<K: SszbDecode + Ord, V: SszbDecode> SszbDecode for BTreeMap<K, V> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let entries = read_sorted_list(
+            variable_bytes,
+            |a: &Entry<K, V>, b| a.key < b.key,
+            "BTreeMap keys are not in strictly increasing order",
+        )?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect())
+    }
+}
+
+impl<T> SszZero for BTreeSet<T> {
+    fn ssz_zero() -> Self {
+        BTreeSet::new()
+    }
+}
+
+impl<K, V> SszZero for BTreeMap<K, V> {
+    fn ssz_zero() -> Self {
+        BTreeMap::new()
+    }
+}
@@ -0,0 +1,210 @@
+//! `bytes::Bytes` and [`ByteList`], byte lists that can share the allocation they're decoded from.
+//!
+//! `Bytes` is encoded as an SSZ list of bytes with no maximum length, and `ByteList<N>` as one of
+//! at most `N` bytes, the `ByteList[N]` of the consensus specs (`extra_data`, transactions). Both
+//! are copied out of a plain slice, but decoded with `from_ssz_bytes_shared`, or read with
+//! `ssz_read` out of a `Bytes` buffer, they're views into the input, so fields of a message
+//! received into a `Bytes` don't each need an allocation of their own.
+
+use crate::vectored::Segments;
+use crate::{
+    expect_offset, write_offset, DecodeError, EncodeError, SszReader, SszZero, SszbDecode,
+    SszbEncode, BYTES_PER_LENGTH_OFFSET,
+};
+use bytes::{
+    buf::{Buf, BufMut},
+    Bytes,
+};
+use core::{marker::PhantomData, ops::Deref};
+use typenum::Unsigned;
+
+impl SszbEncode for Bytes {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        self.len()
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        Ok(self.len())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self);
+    }
+
+    fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+        u8::ssz_write_packed_vectored(self, segments);
+    }
+}
+
+impl SszbDecode for Bytes {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+    const SSZ_SCHEMA: [u8; 32] = crate::schema::list(<u8 as SszbDecode>::SSZ_SCHEMA, usize::MAX);
+
+    // copy_to_bytes is a refcount bump when the buffer is a Bytes, and a copy otherwise
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        Ok(variable_bytes.copy_to_bytes(variable_bytes.remaining()))
+    }
+
+    fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        Ok(reader.to_bytes())
+    }
+
+    fn validate_ssz_bytes(_bytes: &[u8]) -> Result<(), DecodeError> {
+        Ok(())
+    }
+}
+
+/// An SSZ list of at most `N` bytes, held in a `Bytes`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct ByteList<N> {
+    bytes: Bytes,
+    _max_len: PhantomData<N>,
+}
+
+impl<N: Unsigned> ByteList<N> {
+    /// Fails with `ListTooLong` if there are more than `N` bytes.
+    pub fn new(bytes: impl Into<Bytes>) -> Result<Self, DecodeError> {
+        let bytes = bytes.into();
+        Self::check_len(bytes.len())?;
+        Ok(ByteList {
+            bytes,
+            _max_len: PhantomData,
+        })
+    }
+
+    pub fn empty() -> Self {
+        ByteList {
+            bytes: Bytes::new(),
+            _max_len: PhantomData,
+        }
+    }
+
+    pub fn max_len() -> usize {
+        N::USIZE
+    }
+
+    pub fn into_inner(self) -> Bytes {
+        self.bytes
+    }
+
+    fn check_len(len: usize) -> Result<(), DecodeError> {
+        if len > N::USIZE {
+            return Err(DecodeError::ListTooLong { len, max: N::USIZE });
+        }
+        Ok(())
+    }
+}
+
+impl<N> Deref for ByteList<N> {
+    type Target = Bytes;
+
+    fn deref(&self) -> &Bytes {
+        &self.bytes
+    }
+}
+
+impl<N: Unsigned> SszbEncode for ByteList<N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = N::USIZE;
+
+    fn sszb_bytes_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        Ok(self.bytes.len())
+    }
+
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        if self.bytes.len() > N::USIZE {
+            return Err(EncodeError::ListTooLong {
+                len: self.bytes.len(),
+                max: N::USIZE,
+            });
+        }
+        Ok(())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        self.bytes.ssz_write_fixed(offset, buf);
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.bytes.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        self.bytes.ssz_write(buf);
+    }
+
+    fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+        self.bytes.ssz_write_vectored(segments);
+    }
+}
+
+impl<N: Unsigned> SszbDecode for ByteList<N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = N::USIZE;
+    const SSZ_SCHEMA: [u8; 32] = crate::schema::list(<u8 as SszbDecode>::SSZ_SCHEMA, N::USIZE);
+
+    // the length is checked before anything is copied or shared
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        Self::check_len(variable_bytes.remaining())?;
+        Self::new(variable_bytes.copy_to_bytes(variable_bytes.remaining()))
+    }
+
+    fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        Self::check_len(reader.bytes().len())?;
+        Self::new(reader.to_bytes())
+    }
+
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        Self::check_len(bytes.len())
+    }
+}
+
+impl SszZero for Bytes {
+    fn ssz_zero() -> Self {
+        Bytes::new()
+    }
+}
+
+impl<N: Unsigned> SszZero for ByteList<N> {
+    fn ssz_zero() -> Self {
+        ByteList::empty()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<N> crate::context::ContextLen for ByteList<N> {
+    fn context_len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn peek_context_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+        Ok(bytes.len())
+    }
+}
@@ -0,0 +1,174 @@
+//! `tokio_util::codec` framing for streams of SSZ messages.
+//!
+//! Each frame is a `u32` little-endian length followed by that many bytes of SSZ.
+
+use crate::{DecodeError, EncodeError, SszbDecode, SszbEncode};
+use bytes::{Buf, BufMut, BytesMut};
+use core::marker::PhantomData;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Length of the prefix in front of every frame.
+pub const FRAME_PREFIX_LEN: usize = 4;
+
+/// Upper bound on the default maximum frame length, for types whose `SSZ_MAX_LEN` is larger.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum CodecError {
+    Io(std::io::Error),
+    /// A frame held bytes that don't decode as the message type. The frame is consumed, so
+    /// the stream can carry on with the next one.
+    Decode(DecodeError),
+    /// A message couldn't be encoded, nothing is written.
+    Encode(EncodeError),
+    /// A frame was longer than the codec's maximum. When decoding, the frame is skipped, so the
+    /// stream can carry on with the next one; when encoding, nothing is written.
+    FrameTooLarge {
+        len: usize,
+        max: usize,
+    },
+}
+
+impl core::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CodecError::Io(error) => write!(f, "{}", error),
+            CodecError::Decode(error) => write!(f, "Invalid frame: {}", error),
+            CodecError::Encode(error) => write!(f, "Invalid message: {}", error),
+            CodecError::FrameTooLarge { len, max } => {
+                write!(f, "Frame of {} bytes exceeds maximum of {}", len, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodecError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodecError::Io(error) => Some(error),
+            CodecError::Decode(error) => Some(error),
+            CodecError::Encode(error) => Some(error),
+            CodecError::FrameTooLarge { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CodecError {
+    fn from(error: std::io::Error) -> Self {
+        CodecError::Io(error)
+    }
+}
+
+impl From<DecodeError> for CodecError {
+    fn from(error: DecodeError) -> Self {
+        CodecError::Decode(error)
+    }
+}
+
+impl From<EncodeError> for CodecError {
+    fn from(error: EncodeError) -> Self {
+        CodecError::Encode(error)
+    }
+}
+
+/// Encodes and decodes length-prefixed SSZ frames of `T`, for use with `FramedRead`,
+/// `FramedWrite` or `Framed`.
+#[derive(Debug)]
+pub struct SszCodec<T> {
+    max_frame_len: usize,
+    // bytes of an over-long frame that are yet to be skipped
+    skip: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: SszbDecode> SszCodec<T> {
+    /// A codec whose maximum frame length is `T::SSZ_MAX_LEN`, capped at
+    /// `DEFAULT_MAX_FRAME_LEN`.
+    pub fn new() -> Self {
+        Self::with_max_frame_len(<T as SszbDecode>::SSZ_MAX_LEN.min(DEFAULT_MAX_FRAME_LEN))
+    }
+}
+
+impl<T> SszCodec<T> {
+    /// A codec that rejects frames longer than `max_frame_len` bytes (not counting the prefix).
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        Self {
+            // nothing longer fits in the prefix anyway
+            max_frame_len: max_frame_len.min(u32::MAX as usize),
+            skip: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+}
+
+impl<T: SszbDecode> Default for SszCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: SszbEncode> Encoder<T> for SszCodec<T> {
+    type Error = CodecError;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.ssz_check_bounds()?;
+        let len = item.try_sszb_bytes_len()?;
+        if len > self.max_frame_len {
+            return Err(CodecError::FrameTooLarge {
+                len,
+                max: self.max_frame_len,
+            });
+        }
+
+        dst.reserve(FRAME_PREFIX_LEN + len);
+        dst.put_u32_le(len as u32);
+        item.ssz_write(dst);
+        Ok(())
+    }
+}
+
+impl<T: SszbDecode> Decoder for SszCodec<T> {
+    type Item = T;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>, Self::Error> {
+        if self.skip > 0 {
+            let skipped = self.skip.min(src.len());
+            src.advance(skipped);
+            self.skip -= skipped;
+            if self.skip > 0 {
+                return Ok(None);
+            }
+        }
+
+        if src.len() < FRAME_PREFIX_LEN {
+            return Ok(None);
+        }
+        let len = u32::from_le_bytes([src[0], src[1], src[2], src[3]]) as usize;
+
+        if len > self.max_frame_len {
+            // skip over the frame as it arrives rather than buffering it
+            src.advance(FRAME_PREFIX_LEN);
+            let skipped = len.min(src.len());
+            src.advance(skipped);
+            self.skip = len - skipped;
+            return Err(CodecError::FrameTooLarge {
+                len,
+                max: self.max_frame_len,
+            });
+        }
+
+        if src.len() < FRAME_PREFIX_LEN + len {
+            src.reserve(FRAME_PREFIX_LEN + len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(FRAME_PREFIX_LEN);
+        let frame = src.split_to(len);
+        Ok(Some(T::from_ssz_bytes(&frame)?))
+    }
+}
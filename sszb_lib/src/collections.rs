@@ -0,0 +1,1151 @@
+//! The SSZ impls of lists and vectors, shared by `ssz_types`, `milhouse` and `ghilhouse`.
+//!
+//! Each backing crate only implements `SszCollection` for its list and vector types, which is
+//! the plumbing needed to iterate over and build them (the tree crates share theirs, from
+//! `impl_tree_collections!`), and instantiates `impl_ssz_list!` or `impl_ssz_vector!`. Every
+//! check, error and fast path lives in the functions below, so all the backends encode and
+//! decode alike.
+use crate::{
+    check_items_bounds, debug_check_write_fixed,
+    decode::decode_impls::decode_variable_length_items,
+    decode::decode_impls::{validate_vector, visit_vector_items},
+    decode::{check_static_len, read_contiguous},
+    decode_list_into,
+    encode::{
+        record_item_lens, write_items_cached, write_items_cached_vec, write_items_slice,
+        write_to_slice,
+    },
+    expect_len,
+    raw::{peek_list_len_static, peek_list_len_variable, split_offset_table},
+    ssz_decode_variable_length_items_trusted, try_items_bytes_len,
+    vectored::Segments,
+    DecodeError, EncodeError, SszReader, SszbDecode, SszbEncode, TryFromIter,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+use itertools::process_results;
+
+// what the shared impls need from a list or vector type
+pub(crate) trait SszCollection<T>: Sized {
+    // the maximum length of a list, or the length of a vector
+    const LIMIT: usize;
+
+    fn num_items(&self) -> usize;
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+
+    // the items as a slice, if they're stored contiguously, so that static ones are written in
+    // bulk and variable ones can be borrowed by vectored writes
+    fn as_slice(&self) -> Option<&[T]> {
+        None
+    }
+
+    // writes the items of a static type, for collections that can't hand them out as a slice
+    fn write_static_items(&self, buf: &mut impl BufMut)
+    where
+        T: SszbEncode,
+    {
+        for item in self.items() {
+            item.ssz_write(buf);
+        }
+    }
+
+    // writes the items into buf on the rayon pool if the collection can be split up, returning
+    // whether it did
+    #[cfg(feature = "rayon")]
+    fn write_parallel(&self, _buf: &mut [u8]) -> bool
+    where
+        T: SszbEncode + Sync,
+    {
+        false
+    }
+
+    // builds the collection out of decoded items, whose number is already checked against LIMIT
+    fn from_items(items: Vec<T>) -> Result<Self, DecodeError>;
+
+    // builds the collection out of the encoding of its static items, back to back, whose number
+    // is already checked against LIMIT. They're decoded into a vector for from_items, unless the
+    // collection has a builder to decode them straight into (see read_packed_runs)
+    fn from_packed(bytes: &[u8]) -> Result<Self, DecodeError>
+    where
+        T: SszbDecode,
+    {
+        Self::from_items(T::ssz_read_packed(bytes)?)
+    }
+
+    // takes the items out as a vector, leaving the list empty, if the collection is backed by one
+    // that decode_into can refill
+    fn take_items(&mut self) -> Option<Vec<T>> {
+        None
+    }
+
+    // the items as a mutable slice, if the collection is a vector that stores them contiguously,
+    // so that decode_into can decode over them in place
+    fn items_mut(&mut self) -> Option<&mut [T]> {
+        None
+    }
+}
+
+/// A list of at most `N` items of type `T` from one of the crates the impls here are shared
+/// between: an `ssz_types::VariableList`, a `milhouse::List` or a `ghilhouse::List`. They all
+/// encode alike, so `convert_list` moves the items from one to another without changing a byte
+/// of their SSZ.
+pub trait SszList<T, N>: Sized {
+    #[doc(hidden)]
+    fn list_items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+
+    #[doc(hidden)]
+    fn from_list_items(items: Vec<T>) -> Result<Self, DecodeError>;
+
+    /// Copies the items into another kind of list of the same `T` and `N`, e.g. a `VariableList`
+    /// into the milhouse `List` of a state. Only building a tree can fail, with its crate's error.
+    fn convert_list<L: SszList<T, N>>(&self) -> Result<L, DecodeError>
+    where
+        T: Clone,
+    {
+        L::from_list_items(self.list_items().cloned().collect())
+    }
+}
+
+/// A vector of exactly `N` items of type `T` from one of the crates the impls here are shared
+/// between: an `ssz_types::FixedVector`, a `milhouse::Vector` or a `ghilhouse::Vector`. See
+/// `SszList`.
+pub trait SszVector<T, N>: Sized {
+    #[doc(hidden)]
+    fn vector_items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+
+    #[doc(hidden)]
+    fn from_vector_items(items: Vec<T>) -> Result<Self, DecodeError>;
+
+    /// Copies the items into another kind of vector of the same `T` and `N`. Only building a tree
+    /// can fail, with its crate's error.
+    fn convert_vector<V: SszVector<T, N>>(&self) -> Result<V, DecodeError>
+    where
+        T: Clone,
+    {
+        V::from_vector_items(self.vector_items().cloned().collect())
+    }
+}
+
+pub(crate) fn try_collection_bytes_len<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+) -> Result<usize, EncodeError> {
+    try_items_bytes_len::<T>(items.num_items(), items.items())
+}
+
+pub(crate) fn check_collection_bounds<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    max: usize,
+) -> Result<(), EncodeError> {
+    check_items_bounds::<T>(items.num_items(), max, items.items())
+}
+
+// the infallible encoders don't check the maximum of a list, which ssz_check_bounds does, but
+// debug builds assert it in ssz_write and in ssz_record_lens, which to_ssz starts with
+#[inline]
+pub(crate) fn debug_check_list_len<T, C: SszCollection<T>>(items: &C, max: usize) {
+    debug_assert!(
+        items.num_items() <= max,
+        "{} holds {} items, more than its maximum of {}",
+        core::any::type_name::<C>(),
+        items.num_items(),
+        max
+    );
+}
+
+pub(crate) fn write_collection<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    buf: &mut impl BufMut,
+) {
+    if T::SSZ_STATIC {
+        match items.as_slice() {
+            Some(slice) => T::ssz_write_packed(slice, buf),
+            None => items.write_static_items(buf),
+        }
+    } else {
+        let offset = &mut (items.num_items() * BYTES_PER_LENGTH_OFFSET);
+        for item in items.items() {
+            debug_check_write_fixed(item, offset, buf);
+        }
+        for item in items.items() {
+            item.ssz_write(buf);
+        }
+    }
+}
+
+// write_collection for a Vec, passing it on to each variable item's ssz_write_vec
+pub(crate) fn write_collection_vec<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    buf: &mut Vec<u8>,
+) {
+    if T::SSZ_STATIC {
+        write_collection(items, buf);
+    } else {
+        let offset = &mut (items.num_items() * BYTES_PER_LENGTH_OFFSET);
+        for item in items.items() {
+            debug_check_write_fixed(item, offset, buf);
+        }
+        for item in items.items() {
+            item.ssz_write_vec(buf);
+        }
+    }
+}
+
+// how many bytes of items write_gathered copies before writing them out, and read_packed_runs
+// decodes before handing them over, a few pages' worth
+const GATHER_LEN: usize = 4096;
+
+// builds a collection that takes its items one at a time anyway, like a tree that packs them into
+// leaves as they come, out of the encoding of its static items. They're decoded a run of a few
+// kilobytes at a time, so that integers still convert in bulk without a vector of all of them
+// being allocated first, and pushed into the tree's builder, which packs them into leaves and
+// builds the tree bottom-up. The first run that doesn't decode stops the build, with its error
+pub(crate) fn read_packed_runs<T: SszbDecode, C: TryFromIter<T>>(
+    bytes: &[u8],
+) -> Result<C, DecodeError> {
+    // items of no bytes can't be split into runs
+    let run_len = (GATHER_LEN / T::SSZ_FIXED_LEN.max(1)).max(1) * T::SSZ_FIXED_LEN;
+    if run_len == 0 {
+        let items = T::ssz_read_packed(bytes)?;
+        return C::try_from_iter(items.into_iter()).map_err(Into::into);
+    }
+
+    let runs = bytes
+        .chunks(run_len)
+        .enumerate()
+        .map(|(i, run)| T::ssz_read_packed(run).map_err(|e| e.at_byte(i * run_len)));
+    process_results(runs, |runs| C::try_from_iter(runs.flatten()))?.map_err(Into::into)
+}
+
+// writes static items that aren't contiguous in memory, like the packed leaves of a tree. plain
+// old data items are gathered into runs that are written in one go, which gives the same bytes
+// as writing them one by one, without going through the buffer for every item
+pub(crate) fn write_gathered<'a, T: SszbEncode + Clone + 'a>(
+    items: impl Iterator<Item = &'a T>,
+    buf: &mut impl BufMut,
+) {
+    if !T::SSZ_POD {
+        for item in items {
+            item.ssz_write(buf);
+        }
+        return;
+    }
+
+    // items of no bytes, like zero-length vectors, write nothing however many there are
+    let run_len = (GATHER_LEN / T::SSZ_FIXED_LEN.max(1)).max(1);
+    let mut run = Vec::with_capacity(run_len);
+    for item in items {
+        run.push(item.clone());
+        if run.len() == run_len {
+            T::ssz_write_packed(&run, buf);
+            run.clear();
+        }
+    }
+    T::ssz_write_packed(&run, buf);
+}
+
+pub(crate) fn write_collection_parallel<T: SszbEncode + Sync, C: SszCollection<T>>(
+    items: &C,
+    buf: &mut [u8],
+) {
+    #[cfg(feature = "rayon")]
+    if buf.len() >= crate::parallel::PARALLEL_THRESHOLD && items.write_parallel(buf) {
+        return;
+    }
+
+    write_collection_slice(items, buf);
+}
+
+pub(crate) fn write_collection_vectored<'segments, T, C>(
+    items: &'segments C,
+    segments: &mut Segments<'segments>,
+) where
+    T: SszbEncode + 'segments,
+    C: SszCollection<T>,
+{
+    if T::SSZ_STATIC {
+        match items.as_slice() {
+            Some(slice) => T::ssz_write_packed_vectored(slice, segments),
+            // the items of a tree aren't contiguous, so only variable ones can borrow anything
+            None => write_collection(items, segments.scratch()),
+        }
+    } else {
+        let offset = &mut (items.num_items() * BYTES_PER_LENGTH_OFFSET);
+        for item in items.items() {
+            item.ssz_write_fixed(offset, segments.scratch());
+        }
+        for item in items.items() {
+            item.ssz_write_vectored(segments);
+        }
+    }
+}
+
+pub(crate) fn record_collection_lens<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    lens: &mut Vec<usize>,
+) -> usize {
+    if T::SSZ_STATIC {
+        expect_len(try_collection_bytes_len(items), core::any::type_name::<C>())
+    } else {
+        record_item_lens(items.num_items(), items.items(), lens)
+    }
+}
+
+pub(crate) fn write_collection_cached<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    lens: &mut &[usize],
+    buf: &mut impl BufMut,
+) {
+    if T::SSZ_STATIC {
+        write_collection(items, buf);
+    } else {
+        write_items_cached(items.num_items(), items.items(), lens, buf);
+    }
+}
+
+pub(crate) fn write_collection_cached_vec<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    lens: &mut &[usize],
+    buf: &mut Vec<u8>,
+) {
+    if T::SSZ_STATIC {
+        write_collection(items, buf);
+    } else {
+        write_items_cached_vec(items.num_items(), items.items(), lens, buf);
+    }
+}
+
+pub(crate) fn write_collection_slice<T: SszbEncode, C: SszCollection<T>>(
+    items: &C,
+    buf: &mut [u8],
+) -> usize {
+    if T::SSZ_STATIC {
+        write_to_slice(buf, |rest| write_collection(items, rest))
+    } else {
+        write_items_slice(items.num_items(), items.items(), buf)
+    }
+}
+
+fn check_list_len<T, C: SszCollection<T>>(len: usize) -> Result<(), DecodeError> {
+    if len > C::LIMIT {
+        return Err(DecodeError::ListTooLong { len, max: C::LIMIT });
+    }
+    Ok(())
+}
+
+// vectors of no items are illegal, so no bytes decode as one
+fn check_vector_limit<T, C: SszCollection<T>>() -> Result<(), DecodeError> {
+    if C::LIMIT == 0 {
+        return Err(DecodeError::ZeroLengthVector);
+    }
+    Ok(())
+}
+
+fn check_vector_len<T, C: SszCollection<T>>(found: usize) -> Result<(), DecodeError> {
+    if found != C::LIMIT {
+        return Err(DecodeError::VectorLengthMismatch {
+            found,
+            expected: C::LIMIT,
+        });
+    }
+    Ok(())
+}
+
+// how read_list and read_vector decode the items once they've checked the length: one after the
+// other for SszbDecode, and with Parallel for SszbDecodeParallel
+pub(crate) trait ReadItems<T> {
+    // the list or vector of static values whose items are in bytes, back to back
+    fn read_packed<C: SszCollection<T>>(bytes: &[u8]) -> Result<C, DecodeError>;
+
+    // the items of a list or vector of variable-size values, after their offset table
+    fn read_variable<C: TryFromIter<T>>(
+        var_offsets: &[u8],
+        var_items: &[u8],
+    ) -> Result<C, DecodeError>;
+}
+
+pub(crate) enum Serial {}
+
+impl<T: SszbDecode> ReadItems<T> for Serial {
+    fn read_packed<C: SszCollection<T>>(bytes: &[u8]) -> Result<C, DecodeError> {
+        C::from_packed(bytes)
+    }
+
+    fn read_variable<C: TryFromIter<T>>(
+        var_offsets: &[u8],
+        var_items: &[u8],
+    ) -> Result<C, DecodeError> {
+        decode_variable_length_items(var_offsets, var_items)
+    }
+}
+
+// without the rayon feature, SszbDecodeParallel decodes the items serially too
+#[cfg(feature = "rayon")]
+pub(crate) use crate::parallel::pool::Parallel;
+#[cfg(not(feature = "rayon"))]
+pub(crate) use Serial as Parallel;
+
+// a list is always stored in the variable section, and spans the rest of it
+pub(crate) fn read_list<T, C, R>(variable_bytes: &mut impl Buf) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T>,
+    R: ReadItems<T>,
+{
+    if !variable_bytes.has_remaining() {
+        return C::from_items(Vec::new());
+    }
+
+    // the bytes are borrowed in place, unless they're split across chunks
+    let len = variable_bytes.remaining();
+    read_contiguous(variable_bytes, len, |bytes| {
+        if T::SSZ_STATIC {
+            let num_items = peek_list_len_static(bytes, T::SSZ_FIXED_LEN)?;
+            crate::enter_trace_span!("ssz_decode_list", items = num_items, len = bytes.len());
+            check_list_len::<T, C>(num_items)?;
+
+            // the items are decoded in bulk, or a run at a time into the collection's builder
+            R::read_packed::<C>(bytes)
+        } else {
+            // the first offset is the length of the offset table, which is the only way to tell
+            // how many items there are
+            let num_items = peek_list_len_variable(bytes)?;
+            crate::enter_trace_span!("ssz_decode_list", items = num_items, len = bytes.len());
+            check_list_len::<T, C>(num_items)?;
+
+            let (var_offsets, var_items) = split_offset_table(bytes, num_items)?;
+            R::read_variable::<C>(var_offsets, var_items)
+        }
+    })
+}
+
+// ssz_read_from for a list, which spans all of the reader's bytes
+pub(crate) fn read_list_from<T, C>(reader: SszReader<'_>) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T>,
+{
+    read_list::<T, C, Serial>(&mut reader.bytes())
+}
+
+// ssz_read_into for a list, refilling its vector when it has one
+pub(crate) fn read_list_into<T, C>(
+    list: &mut C,
+    variable_bytes: &mut impl Buf,
+) -> Result<(), DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T>,
+{
+    let mut items = match list.take_items() {
+        Some(items) => items,
+        None => {
+            *list = read_list::<T, C, Serial>(variable_bytes)?;
+            return Ok(());
+        }
+    };
+
+    // the list was taken apart, and is left empty if decoding into its items fails
+    let len = variable_bytes.remaining();
+    let result = read_contiguous(variable_bytes, len, |bytes| {
+        decode_list_into(bytes, C::LIMIT, &mut items)
+    });
+    *list = C::from_items(items)?;
+    result
+}
+
+// a vector of static items is stored in the fixed section, taking only its own bytes from it,
+// and a vector of variable ones spans the rest of the variable section
+pub(crate) fn read_vector<T, C, R>(
+    fixed_bytes: &mut impl Buf,
+    variable_bytes: &mut impl Buf,
+) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T>,
+    R: ReadItems<T>,
+{
+    let len = C::LIMIT;
+    crate::enter_trace_span!("ssz_decode_vector", items = len);
+    check_vector_limit::<T, C>()?;
+
+    if !(fixed_bytes.has_remaining() || variable_bytes.has_remaining()) {
+        check_vector_len::<T, C>(0)?;
+        C::from_items(Vec::new())
+    } else if T::SSZ_STATIC {
+        let vector_len = len * T::SSZ_FIXED_LEN;
+        if fixed_bytes.remaining() < vector_len {
+            return Err(DecodeError::VectorLengthMismatch {
+                found: fixed_bytes
+                    .remaining()
+                    .checked_div(T::SSZ_FIXED_LEN)
+                    .unwrap_or(0),
+                expected: len,
+            });
+        }
+
+        read_contiguous(fixed_bytes, vector_len, |bytes| R::read_packed::<C>(bytes))
+    } else {
+        if variable_bytes.remaining() < len * BYTES_PER_LENGTH_OFFSET {
+            return Err(DecodeError::InvalidByteLength {
+                len: variable_bytes.remaining(),
+                expected: len * BYTES_PER_LENGTH_OFFSET,
+            });
+        }
+
+        let vector_len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, vector_len, |bytes| {
+            let (var_offsets, var_items) = bytes.split_at(len * BYTES_PER_LENGTH_OFFSET);
+            R::read_variable::<C>(var_offsets, var_items)
+        })
+    }
+}
+
+// ssz_read_from for a vector, whose reader spans exactly its items, wherever they're stored
+pub(crate) fn read_vector_from<T, C>(reader: SszReader<'_>) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T>,
+{
+    let mut bytes = reader.bytes();
+    if T::SSZ_STATIC {
+        let vector = read_vector::<T, C, Serial>(&mut bytes, &mut &[][..])?;
+        if !bytes.is_empty() {
+            return Err(DecodeError::InvalidByteLength {
+                len: reader.bytes().len(),
+                expected: reader.bytes().len() - bytes.len(),
+            });
+        }
+        Ok(vector)
+    } else {
+        read_vector::<T, C, Serial>(&mut &[][..], &mut bytes)
+    }
+}
+
+// from_ssz_bytes for a vector, with the same checks, for the items to be read by R
+pub(crate) fn decode_vector<T, C, R>(bytes: &[u8]) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T> + SszbDecode,
+    R: ReadItems<T>,
+{
+    if T::SSZ_STATIC {
+        check_static_len::<C>(bytes)?;
+        read_vector::<T, C, R>(&mut &bytes[..], &mut &[][..])
+    } else {
+        read_vector::<T, C, R>(&mut &[][..], &mut &bytes[..])
+    }
+}
+
+// ssz_read_into for a vector, decoding over its items when it holds them in a slice. Every item
+// is checked before any is overwritten, so on error the vector keeps its old items
+pub(crate) fn read_vector_into<T, C>(
+    vector: &mut C,
+    fixed_bytes: &mut impl Buf,
+    variable_bytes: &mut impl Buf,
+) -> Result<(), DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T> + TryFromIter<T>,
+{
+    let items = match vector.items_mut() {
+        Some(items) if items.len() == C::LIMIT => items,
+        _ => {
+            *vector = read_vector::<T, C, Serial>(fixed_bytes, variable_bytes)?;
+            return Ok(());
+        }
+    };
+
+    if T::SSZ_STATIC {
+        let vector_len = C::LIMIT * T::SSZ_FIXED_LEN;
+        if fixed_bytes.remaining() < vector_len {
+            // read_vector reports the missing bytes
+            return read_vector::<T, C, Serial>(fixed_bytes, variable_bytes).map(drop);
+        }
+        read_contiguous(fixed_bytes, vector_len, |bytes| {
+            decode_vector_items_into(bytes, items)
+        })
+    } else {
+        let vector_len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, vector_len, |bytes| {
+            decode_vector_items_into(bytes, items)
+        })
+    }
+}
+
+fn decode_vector_items_into<T: SszbDecode>(
+    bytes: &[u8],
+    items: &mut [T],
+) -> Result<(), DecodeError> {
+    let len = items.len();
+    validate_vector::<T>(bytes, len)?;
+
+    let mut items = items.iter_mut();
+    visit_vector_items(bytes, (T::SSZ_STATIC, T::SSZ_FIXED_LEN), len, |range| {
+        let item = items.next().expect("the vector has one item per range");
+        item.decode_into(&bytes[range.clone()])
+            .map_err(|e| e.at_byte(range.start))
+    })
+}
+
+fn read_items_trusted<T: SszbDecode>(bytes: &[u8]) -> Result<Vec<T>, DecodeError> {
+    if T::SSZ_STATIC {
+        T::ssz_read_packed(bytes)
+    } else {
+        ssz_decode_variable_length_items_trusted(bytes)
+    }
+}
+
+pub(crate) fn read_list_trusted<T, C>(bytes: &[u8]) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T>,
+{
+    let items = read_items_trusted(bytes)?;
+    check_list_len::<T, C>(items.len())?;
+    C::from_items(items)
+}
+
+pub(crate) fn read_vector_trusted<T, C>(bytes: &[u8]) -> Result<C, DecodeError>
+where
+    T: SszbDecode,
+    C: SszCollection<T>,
+{
+    check_vector_limit::<T, C>()?;
+    let items = read_items_trusted(bytes)?;
+    check_vector_len::<T, C>(items.len())?;
+    C::from_items(items)
+}
+
+// implements SszbEncode, SszbDecode, their parallel counterparts, SszbTypeInfo, SszList, SszZero
+// and ContextLen for a list type `$list<T, N>` with `SszCollection`. Every backend's items need
+// the same SSZ traits, plus `Sync` to encode and `Send` to decode in parallel. `item` is whatever
+// the backing type itself requires of its items, which all the impls have to repeat, and should
+// be nothing more
+macro_rules! impl_ssz_list {
+    ($list: ident, item: [$($item_bound: tt)*]) => {
+        impl<T, N> $crate::SszbEncode for $list<T, N>
+        where
+            T: $crate::SszbEncode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = $crate::BYTES_PER_LENGTH_OFFSET;
+            const SSZ_MAX_LEN: usize =
+                $crate::enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN)
+                    .saturating_mul(N::USIZE);
+
+            fn sszb_bytes_len(&self) -> usize {
+                $crate::expect_len(self.try_sszb_bytes_len(), ::core::any::type_name::<Self>())
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, $crate::EncodeError> {
+                $crate::collections::try_collection_bytes_len::<T, Self>(self)
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), $crate::EncodeError> {
+                $crate::collections::check_collection_bounds::<T, Self>(self, N::USIZE)
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl ::bytes::buf::BufMut) {
+                $crate::expect_offset($crate::write_offset(*offset, buf));
+                *offset += self.sszb_bytes_len();
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl ::bytes::buf::BufMut) {
+                self.ssz_write(buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl ::bytes::buf::BufMut) {
+                $crate::collections::debug_check_list_len::<T, Self>(self, N::USIZE);
+                $crate::debug_check_written(
+                    buf,
+                    ::core::any::type_name::<Self>(),
+                    "ssz_write",
+                    || self.sszb_bytes_len(),
+                    |buf| $crate::collections::write_collection::<T, Self>(self, buf),
+                );
+            }
+
+            fn ssz_write_vectored<'segments>(
+                &'segments self,
+                segments: &mut $crate::vectored::Segments<'segments>,
+            ) {
+                $crate::collections::write_collection_vectored::<T, Self>(self, segments);
+            }
+
+            fn ssz_record_lens(&self, lens: &mut $crate::Vec<usize>) -> usize {
+                $crate::collections::debug_check_list_len::<T, Self>(self, N::USIZE);
+                $crate::collections::record_collection_lens::<T, Self>(self, lens)
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl ::bytes::buf::BufMut) {
+                $crate::collections::write_collection_cached::<T, Self>(self, lens, buf);
+            }
+
+            fn ssz_write_vec(&self, buf: &mut $crate::Vec<u8>) {
+                $crate::collections::write_collection_vec::<T, Self>(self, buf);
+            }
+
+            fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut $crate::Vec<u8>) {
+                $crate::collections::write_collection_cached_vec::<T, Self>(self, lens, buf);
+            }
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                $crate::collections::write_collection_slice::<T, Self>(self, buf)
+            }
+        }
+
+        impl<T, N> $crate::SszbDecode for $list<T, N>
+        where
+            T: $crate::SszbDecode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = $crate::BYTES_PER_LENGTH_OFFSET;
+            const SSZ_MAX_LEN: usize =
+                $crate::enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN)
+                    .saturating_mul(N::USIZE);
+            const SSZ_SCHEMA: [u8; 32] = $crate::schema::list(T::SSZ_SCHEMA, N::USIZE);
+
+            fn ssz_read(
+                _fixed_bytes: &mut impl ::bytes::buf::Buf,
+                variable_bytes: &mut impl ::bytes::buf::Buf,
+            ) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_list::<T, Self, $crate::collections::Serial>(
+                    variable_bytes,
+                )
+            }
+
+            fn ssz_read_from(reader: $crate::SszReader<'_>) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_list_from::<T, Self>(reader)
+            }
+
+            fn ssz_read_into(
+                &mut self,
+                _fixed_bytes: &mut impl ::bytes::buf::Buf,
+                variable_bytes: &mut impl ::bytes::buf::Buf,
+            ) -> Result<(), $crate::DecodeError> {
+                $crate::collections::read_list_into::<T, Self>(self, variable_bytes)
+            }
+
+            fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), $crate::DecodeError> {
+                $crate::decode::decode_impls::validate_list::<T>(bytes, N::USIZE)
+            }
+
+            fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_list_trusted::<T, Self>(bytes)
+            }
+        }
+
+        impl<T, N> $crate::parallel::SszbEncodeParallel for $list<T, N>
+        where
+            T: $crate::SszbEncode + Sync + $($item_bound)*,
+            N: ::typenum::Unsigned,
+            Self: Sync,
+        {
+            fn ssz_write_parallel(&self, buf: &mut [u8]) {
+                $crate::collections::write_collection_parallel::<T, Self>(self, buf);
+            }
+        }
+
+        impl<T, N> $crate::parallel::SszbDecodeParallel for $list<T, N>
+        where
+            T: $crate::SszbDecode + Send + $($item_bound)*,
+            N: ::typenum::Unsigned,
+            Self: Send,
+        {
+            fn ssz_read_parallel(bytes: &[u8]) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_list::<T, Self, $crate::collections::Parallel>(
+                    &mut &bytes[..],
+                )
+            }
+        }
+
+        impl<T, N> $crate::dynamic::SszbTypeInfo for $list<T, N>
+        where
+            T: $crate::dynamic::SszbTypeInfo + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn ssz_type_info() -> $crate::dynamic::SszTypeInfo {
+                $crate::dynamic::SszTypeInfo::list(T::ssz_type_info(), N::USIZE)
+            }
+        }
+
+        impl<T, N> $crate::SszList<T, N> for $list<T, N>
+        where
+            T: $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn list_items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+            where
+                T: 'a,
+            {
+                $crate::collections::SszCollection::items(self)
+            }
+
+            fn from_list_items(items: $crate::Vec<T>) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::SszCollection::from_items(items)
+            }
+        }
+
+        impl<T, N> $crate::SszZero for $list<T, N>
+        where
+            T: $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn ssz_zero() -> Self {
+                $crate::collections::SszCollection::from_items($crate::Vec::new())
+                    .expect("an empty list is never too long")
+            }
+        }
+
+        impl<T, N> $crate::context::ContextLen for $list<T, N>
+        where
+            T: $crate::SszbDecode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn context_len(&self) -> usize {
+                $crate::collections::SszCollection::num_items(self)
+            }
+
+            fn peek_context_len(bytes: &[u8]) -> Result<usize, $crate::DecodeError> {
+                $crate::raw::peek_list_len::<T>(bytes)
+            }
+        }
+    };
+}
+
+// implements SszbEncode, SszbDecode, their parallel counterparts, SszbTypeInfo, SszVector and
+// SszZero for a vector type `$vector<T, N>` with `SszCollection`. Every backend's items need the
+// same SSZ traits, plus `Sync` to encode and `Send` to decode in parallel. `item` is whatever the
+// backing type itself requires of its items, which all the impls have to repeat, and should be
+// nothing more
+macro_rules! impl_ssz_vector {
+    ($vector: ident, item: [$($item_bound: tt)*]) => {
+        impl<T, N> $crate::SszbEncode for $vector<T, N>
+        where
+            T: $crate::SszbEncode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            const SSZ_STATIC: bool = T::SSZ_STATIC;
+            const SSZ_FIXED_LEN: usize = if T::SSZ_STATIC {
+                T::SSZ_FIXED_LEN * N::USIZE
+            } else {
+                $crate::BYTES_PER_LENGTH_OFFSET
+            };
+            const SSZ_MAX_LEN: usize =
+                $crate::enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN)
+                    .saturating_mul(N::USIZE);
+
+            fn sszb_bytes_len(&self) -> usize {
+                $crate::expect_len(self.try_sszb_bytes_len(), ::core::any::type_name::<Self>())
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, $crate::EncodeError> {
+                // a vector of static items always takes up its fixed length, however many items
+                // the value holds, so that it can't shift the offsets of the container around it
+                if T::SSZ_STATIC {
+                    Ok(<Self as $crate::SszbEncode>::SSZ_FIXED_LEN)
+                } else {
+                    $crate::collections::try_collection_bytes_len::<T, Self>(self)
+                }
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), $crate::EncodeError> {
+                $crate::collections::check_collection_bounds::<T, Self>(self, usize::MAX)
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl ::bytes::buf::BufMut) {
+                if T::SSZ_STATIC {
+                    self.ssz_write(buf);
+                } else {
+                    $crate::expect_offset($crate::write_offset(*offset, buf));
+                    *offset += self.sszb_bytes_len();
+                }
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl ::bytes::buf::BufMut) {
+                if !T::SSZ_STATIC {
+                    self.ssz_write(buf);
+                }
+            }
+
+            fn ssz_write(&self, buf: &mut impl ::bytes::buf::BufMut) {
+                $crate::debug_check_written(
+                    buf,
+                    ::core::any::type_name::<Self>(),
+                    "ssz_write",
+                    || self.sszb_bytes_len(),
+                    |buf| $crate::collections::write_collection::<T, Self>(self, buf),
+                );
+            }
+
+            fn ssz_write_vectored<'segments>(
+                &'segments self,
+                segments: &mut $crate::vectored::Segments<'segments>,
+            ) {
+                $crate::collections::write_collection_vectored::<T, Self>(self, segments);
+            }
+
+            fn ssz_record_lens(&self, lens: &mut $crate::Vec<usize>) -> usize {
+                $crate::collections::record_collection_lens::<T, Self>(self, lens)
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl ::bytes::buf::BufMut) {
+                $crate::collections::write_collection_cached::<T, Self>(self, lens, buf);
+            }
+
+            fn ssz_write_vec(&self, buf: &mut $crate::Vec<u8>) {
+                $crate::collections::write_collection_vec::<T, Self>(self, buf);
+            }
+
+            fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut $crate::Vec<u8>) {
+                $crate::collections::write_collection_cached_vec::<T, Self>(self, lens, buf);
+            }
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                $crate::collections::write_collection_slice::<T, Self>(self, buf)
+            }
+        }
+
+        impl<T, N> $crate::SszbDecode for $vector<T, N>
+        where
+            T: $crate::SszbDecode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            const SSZ_STATIC: bool = T::SSZ_STATIC;
+            const SSZ_FIXED_LEN: usize = if T::SSZ_STATIC {
+                T::SSZ_FIXED_LEN * N::USIZE
+            } else {
+                $crate::BYTES_PER_LENGTH_OFFSET
+            };
+            const SSZ_MAX_LEN: usize =
+                $crate::enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN)
+                    .saturating_mul(N::USIZE);
+            const SSZ_SCHEMA: [u8; 32] = $crate::schema::vector(T::SSZ_SCHEMA, N::USIZE);
+
+            fn ssz_read(
+                fixed_bytes: &mut impl ::bytes::buf::Buf,
+                variable_bytes: &mut impl ::bytes::buf::Buf,
+            ) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_vector::<T, Self, $crate::collections::Serial>(
+                    fixed_bytes,
+                    variable_bytes,
+                )
+            }
+
+            fn ssz_read_from(reader: $crate::SszReader<'_>) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_vector_from::<T, Self>(reader)
+            }
+
+            fn ssz_read_into(
+                &mut self,
+                fixed_bytes: &mut impl ::bytes::buf::Buf,
+                variable_bytes: &mut impl ::bytes::buf::Buf,
+            ) -> Result<(), $crate::DecodeError> {
+                $crate::collections::read_vector_into::<T, Self>(self, fixed_bytes, variable_bytes)
+            }
+
+            fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), $crate::DecodeError> {
+                $crate::decode::decode_impls::validate_vector::<T>(bytes, N::USIZE)
+            }
+
+            fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::read_vector_trusted::<T, Self>(bytes)
+            }
+        }
+
+        impl<T, N> $crate::parallel::SszbEncodeParallel for $vector<T, N>
+        where
+            T: $crate::SszbEncode + Sync + $($item_bound)*,
+            N: ::typenum::Unsigned,
+            Self: Sync,
+        {
+            fn ssz_write_parallel(&self, buf: &mut [u8]) {
+                $crate::collections::write_collection_parallel::<T, Self>(self, buf);
+            }
+        }
+
+        impl<T, N> $crate::parallel::SszbDecodeParallel for $vector<T, N>
+        where
+            T: $crate::SszbDecode + Send + $($item_bound)*,
+            N: ::typenum::Unsigned,
+            Self: Send,
+        {
+            fn ssz_read_parallel(bytes: &[u8]) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::decode_vector::<T, Self, $crate::collections::Parallel>(bytes)
+            }
+        }
+
+        impl<T, N> $crate::dynamic::SszbTypeInfo for $vector<T, N>
+        where
+            T: $crate::dynamic::SszbTypeInfo + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn ssz_type_info() -> $crate::dynamic::SszTypeInfo {
+                $crate::dynamic::SszTypeInfo::vector(T::ssz_type_info(), N::USIZE)
+            }
+        }
+
+        impl<T, N> $crate::SszVector<T, N> for $vector<T, N>
+        where
+            T: $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn vector_items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+            where
+                T: 'a,
+            {
+                $crate::collections::SszCollection::items(self)
+            }
+
+            fn from_vector_items(items: $crate::Vec<T>) -> Result<Self, $crate::DecodeError> {
+                $crate::collections::SszCollection::from_items(items)
+            }
+        }
+
+        impl<T, N> $crate::SszZero for $vector<T, N>
+        where
+            T: $crate::SszZero + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            fn ssz_zero() -> Self {
+                let items = (0..N::USIZE).map(|_| T::ssz_zero()).collect();
+                $crate::collections::SszCollection::from_items(items)
+                    .expect("a vector holds exactly its length of items")
+            }
+        }
+    };
+}
+
+// implements TryFromIter and SszCollection for the list `$list<T, N>` and vector `$vector<T, N>`
+// of a crate of persistent trees, milhouse or a fork of it, whose items need `item`. It also
+// needs a `From` impl of the crate's error for DecodeError
+#[cfg(any(feature = "milhouse", feature = "ghilhouse"))]
+macro_rules! impl_tree_collections {
+    ($list: ident, $vector: ident, item: [$($item_bound: tt)*]) => {
+        impl<T, N> $crate::TryFromIter<T> for $list<T, N>
+        where
+            T: $crate::SszbDecode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            type Error = $crate::DecodeError;
+
+            fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+                Ok($list::try_from_iter(iter)?)
+            }
+        }
+
+        impl<T, N> $crate::TryFromIter<T> for $vector<T, N>
+        where
+            T: $crate::SszbDecode + $($item_bound)*,
+            N: ::typenum::Unsigned,
+        {
+            type Error = $crate::DecodeError;
+
+            fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+                Ok($vector::try_from_iter(iter)?)
+            }
+        }
+
+        impl<T: $($item_bound)*, N: ::typenum::Unsigned> $crate::collections::SszCollection<T>
+            for $list<T, N>
+        {
+            const LIMIT: usize = N::USIZE;
+
+            fn num_items(&self) -> usize {
+                self.len()
+            }
+
+            fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+            where
+                T: 'a,
+            {
+                self.iter()
+            }
+
+            // the tree keeps its packed leaves to itself, but iterating over it hands out their
+            // items one leaf after another, to be gathered back into runs
+            fn write_static_items(&self, buf: &mut impl ::bytes::buf::BufMut)
+            where
+                T: $crate::SszbEncode,
+            {
+                $crate::collections::write_gathered(self.iter(), buf);
+            }
+
+            #[cfg(feature = "rayon")]
+            fn write_parallel(&self, buf: &mut [u8]) -> bool
+            where
+                T: $crate::SszbEncode + Sync,
+            {
+                // the items of a tree aren't contiguous, but runs of static ones each start at a
+                // known index
+                if T::SSZ_STATIC {
+                    $crate::parallel::pool::write_static_items_from(buf, |i| {
+                        self.iter_from(i).expect("runs start within the list")
+                    });
+                }
+                T::SSZ_STATIC
+            }
+
+            fn from_items(items: $crate::Vec<T>) -> Result<Self, $crate::DecodeError> {
+                Ok($list::new(items)?)
+            }
+
+            fn from_packed(bytes: &[u8]) -> Result<Self, $crate::DecodeError>
+            where
+                T: $crate::SszbDecode,
+            {
+                $crate::collections::read_packed_runs(bytes)
+            }
+        }
+
+        impl<T: $($item_bound)*, N: ::typenum::Unsigned> $crate::collections::SszCollection<T>
+            for $vector<T, N>
+        {
+            const LIMIT: usize = N::USIZE;
+
+            fn num_items(&self) -> usize {
+                self.len()
+            }
+
+            fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+            where
+                T: 'a,
+            {
+                self.iter()
+            }
+
+            fn write_static_items(&self, buf: &mut impl ::bytes::buf::BufMut)
+            where
+                T: $crate::SszbEncode,
+            {
+                $crate::collections::write_gathered(self.iter(), buf);
+            }
+
+            fn from_items(items: $crate::Vec<T>) -> Result<Self, $crate::DecodeError> {
+                Ok($vector::new(items)?)
+            }
+
+            fn from_packed(bytes: &[u8]) -> Result<Self, $crate::DecodeError>
+            where
+                T: $crate::SszbDecode,
+            {
+                $crate::collections::read_packed_runs(bytes)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_ssz_list;
+pub(crate) use impl_ssz_vector;
+#[cfg(any(feature = "milhouse", feature = "ghilhouse"))]
+pub(crate) use impl_tree_collections;
@@ -0,0 +1,123 @@
+//! Named limits that decoding checks at runtime, for codebases that pick a spec preset (mainnet
+//! or minimal) at startup instead of threading a typenum parameter through every type.
+//!
+//! A `DecodeContext` maps names such as `"max_validators"` to limits. It's installed on the
+//! current thread with `install` or `scope`, or for a single call with `from_ssz_bytes_with_ctx`.
+//! A field of a derived container marked `#[ssz(ctx_max = "max_validators")]` checks its length
+//! against the installed limit of that name before it's decoded, from the item count of its
+//! bytes, as the bound of its own type is. A field whose limit isn't in the installed context, or
+//! that's decoded without any, is a `DecodeError::MissingContextLimit` rather than a check that
+//! silently passes. Encoding never looks at the context.
+//!
+//! Contexts are installed per thread, so threads decoding under different presets don't see each
+//! other's, and large lists decoded on the rayon pool carry the context of the thread decoding
+//! them to the pool's threads.
+use crate::{DecodeError, SszbDecode};
+use alloc::{collections::BTreeMap, string::String, sync::Arc};
+use core::{cell::RefCell, marker::PhantomData};
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<DecodeContext>> = const { RefCell::new(None) };
+}
+
+/// Named runtime limits for `#[ssz(ctx_max = "...")]` fields. Cloning one is cheap, the limits
+/// are shared.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodeContext {
+    limits: Arc<BTreeMap<String, usize>>,
+}
+
+impl DecodeContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limit called `name`, replacing any earlier one.
+    pub fn with_limit(mut self, name: impl Into<String>, max: usize) -> Self {
+        Arc::make_mut(&mut self.limits).insert(name.into(), max);
+        self
+    }
+
+    pub fn limit(&self, name: &str) -> Option<usize> {
+        self.limits.get(name).copied()
+    }
+
+    /// Installs the context on the current thread until the guard is dropped, which puts back
+    /// the context it replaced, if any.
+    pub fn install(&self) -> ContextGuard {
+        install(Some(self.clone()))
+    }
+
+    /// Runs `f` with the context installed on the current thread.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.install();
+        f()
+    }
+
+    /// The context installed on the current thread, if any.
+    pub fn current() -> Option<DecodeContext> {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+}
+
+/// Keeps a context installed on the thread that installed it, until it's dropped.
+#[must_use = "the context is uninstalled as soon as the guard is dropped"]
+pub struct ContextGuard {
+    previous: Option<DecodeContext>,
+    // installs are undone on the thread that made them
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+// replaces the current thread's context, which `None` uninstalls
+pub(crate) fn install(context: Option<DecodeContext>) -> ContextGuard {
+    let previous = CURRENT.with(|current| current.replace(context));
+    ContextGuard {
+        previous,
+        _not_send: PhantomData,
+    }
+}
+
+/// Decoding under a `DecodeContext` given for the one call, implemented for every `SszbDecode`
+/// type.
+pub trait DecodeWithContext: SszbDecode {
+    /// `from_ssz_bytes` with `ctx` installed on the current thread for the duration of the call.
+    fn from_ssz_bytes_with_ctx(bytes: &[u8], ctx: &DecodeContext) -> Result<Self, DecodeError> {
+        ctx.scope(|| Self::from_ssz_bytes(bytes))
+    }
+}
+
+impl<T: SszbDecode> DecodeWithContext for T {}
+
+/// Collections that a `#[ssz(ctx_max = "...")]` limit applies to, with their length: the number
+/// of items of a list, the bits of a bitlist or the bytes of a byte list.
+pub trait ContextLen {
+    fn context_len(&self) -> usize;
+
+    /// The length of the value serialized in `bytes`, read off them without decoding it, such
+    /// as the item count `peek_list_len` gives for a list.
+    fn peek_context_len(bytes: &[u8]) -> Result<usize, DecodeError>;
+}
+
+/// Checks the serialized `#[ssz(ctx_max = "...")]` field in `bytes` against the installed limit
+/// called `name`, before it's decoded.
+#[doc(hidden)]
+pub fn check_context_max<T: ContextLen + ?Sized>(
+    bytes: &[u8],
+    name: &'static str,
+) -> Result<(), DecodeError> {
+    let max = CURRENT
+        .with(|current| current.borrow().as_ref().and_then(|ctx| ctx.limit(name)))
+        .ok_or(DecodeError::MissingContextLimit(name))?;
+    let len = T::peek_context_len(bytes)?;
+    if len > max {
+        return Err(DecodeError::ListTooLong { len, max });
+    }
+    Ok(())
+}
@@ -1,11 +1,26 @@
-use crate::BYTES_PER_LENGTH_OFFSET;
-use bytes::buf::Buf;
+#[cfg(feature = "metrics")]
+pub(crate) use crate::metrics::observe;
+use crate::raw::peek_list_len_static;
+use alloc::vec::Vec;
+#[cfg(not(feature = "tiny-errors"))]
+use alloc::{boxed::Box, string::String};
+use bytes::{buf::Buf, Bytes};
 
 pub mod decode_impls;
+mod list_iter;
+mod reader;
+
+pub use list_iter::{iter_ssz_list, SszListIter};
+pub use reader::{read_through_reader, SszReader};
 
 // error types and offset decoding code borrowed from the sigma prime team:
 // https://github.com/sigp/ethereum_ssz/blob/main/ssz/src/decode.rs#L12
+//
+// with the `tiny-errors` feature, every variant only holds integers and static strings so that
+// the error is `Copy`: the variants holding a `String`, a `Box` or a collection crate's error
+// hold a static string or a `DecodeErrorKind` instead, so matching on them keeps compiling
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "tiny-errors", derive(Copy))]
 pub enum DecodeError {
     /// The bytes supplied were too short to be decoded into the specified type.
     InvalidByteLength { len: usize, expected: usize },
@@ -17,7 +32,7 @@ pub enum DecodeError {
     ///
     /// - It is `>= bytes.len()`.
     /// - When decoding variable length items, the 1st offset points "backwards" into the fixed
-    /// length items (i.e., `length[0] < BYTES_PER_LENGTH_OFFSET`).
+    ///   length items (i.e., `length[0] < BYTES_PER_LENGTH_OFFSET`).
     /// - When decoding variable-length items, the `n`'th offset was less than the `n-1`'th offset.
     OutOfBoundsByte { i: usize },
     /// An offset points “backwards” into the fixed-bytes portion of the message, essentially
@@ -44,90 +59,400 @@ pub enum DecodeError {
     InvalidListFixedBytesLen(usize),
     /// Some item has a `ssz_fixed_len` of zero. This is illegal.
     ZeroLengthItem,
-    /// The given bytes were invalid for some application-level reason.
+    /// The type being decoded is a vector or bit vector of length zero, which the consensus
+    /// specs make illegal, so no bytes are a valid encoding of it.
+    ZeroLengthVector,
+    /// The selector byte of a union doesn't correspond to any of its variants.
+    UnionSelectorInvalid(u8),
+    /// The byte of a `#[ssz(enum_behaviour = "tag")]` enum isn't the tag of any of its variants.
+    InvalidEnumTag(u8),
+    /// A transparent enum was asked to decode a variant index it doesn't have.
+    InvalidVariantIndex(usize),
+    /// A transparent enum was asked to decode a variant name it doesn't have.
+    InvalidVariantName,
+    /// A list has more items than its maximum length allows.
+    ListTooLong { len: usize, max: usize },
+    /// A vector doesn't have exactly as many items as its length.
+    VectorLengthMismatch { found: usize, expected: usize },
+    /// A boolean was encoded as something other than 0 or 1.
+    InvalidBool(u8),
+    /// The bytes of a bitfield are invalid for the reason given, e.g. a bit list is missing its
+    /// length bit.
+    BitfieldError(&'static str),
+    /// Building the named collection type out of its decoded items failed.
+    CollectionBuildError(&'static str),
+    /// The named BLS type rejected its bytes.
+    BlsError(&'static str),
+    /// `container_field_range` was asked for a field the container doesn't have.
+    FieldIndexOutOfRange { index: usize, num_fields: usize },
+    /// A field marked `#[ssz(ctx_max = "...")]` was decoded without a `DecodeContext` that has
+    /// the limit of that name.
+    MissingContextLimit(&'static str),
+    /// `milhouse` failed to build a list or vector out of the decoded items. With `tiny-errors`
+    /// it only names the collection.
+    #[cfg(all(feature = "milhouse", not(feature = "tiny-errors")))]
+    Milhouse(milhouse::Error),
+    #[cfg(all(feature = "milhouse", feature = "tiny-errors"))]
+    Milhouse(&'static str),
+    /// The `ghilhouse` fork of `milhouse` failed to build a list or vector out of the decoded
+    /// items. With `tiny-errors` it only names the collection.
+    #[cfg(all(feature = "ghilhouse", not(feature = "tiny-errors")))]
+    Ghilhouse(ghilhouse::Error),
+    #[cfg(all(feature = "ghilhouse", feature = "tiny-errors"))]
+    Ghilhouse(&'static str),
+    /// `ssz_types` failed to build a list or vector out of the decoded items. With `tiny-errors`
+    /// it only names the collection.
+    #[cfg(all(feature = "ssz-types", not(feature = "tiny-errors")))]
+    SszTypes(ssz_types::Error),
+    #[cfg(all(feature = "ssz-types", feature = "tiny-errors"))]
+    SszTypes(&'static str),
+    /// The given bytes were invalid for some application-level reason: the decoded value was
+    /// rejected by a `#[ssz(validate = "...")]` hook, couldn't be converted into its own type,
+    /// or a sorted collection wasn't sorted. With `tiny-errors` it names what was rejected,
+    /// without the reason.
+    #[cfg(not(feature = "tiny-errors"))]
+    #[deprecated(note = "kept as a catch-all, decoders return the structured variants instead")]
     BytesInvalid(String),
+    #[cfg(feature = "tiny-errors")]
+    #[deprecated(note = "kept as a catch-all, decoders return the structured variants instead")]
+    BytesInvalid(&'static str),
+    /// `error` was raised while decoding the element, field or offset starting at `at_byte`.
+    ///
+    /// Collections and derived containers wrap the errors of their items in this variant,
+    /// shifting `at_byte` by the item's position as the error travels up. By the time it leaves
+    /// `from_ssz_bytes`, `at_byte` is an absolute index into the input.
+    #[cfg(not(feature = "tiny-errors"))]
+    AtByte {
+        at_byte: usize,
+        error: Box<DecodeError>,
+    },
+    /// With `tiny-errors`, the position of an error keeps only the `kind` of the error raised
+    /// there, not its fields.
+    #[cfg(feature = "tiny-errors")]
+    AtByte {
+        at_byte: usize,
+        kind: DecodeErrorKind,
+    },
 }
 
-/// Reads a `BYTES_PER_LENGTH_OFFSET`-byte length from `bytes`, where `bytes.len() >=
-/// BYTES_PER_LENGTH_OFFSET`.
-pub fn read_offset_from_buf(buf: &mut impl Buf) -> Result<usize, DecodeError> {
-    let len = buf.remaining();
-    let expected = BYTES_PER_LENGTH_OFFSET;
+/// Which `DecodeError` was raised, without its fields, for the `AtByte` errors of `tiny-errors`.
+#[cfg(feature = "tiny-errors")]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DecodeErrorKind {
+    InvalidByteLength,
+    InvalidLengthPrefix,
+    OutOfBoundsByte,
+    OffsetIntoFixedPortion,
+    OffsetSkipsVariableBytes,
+    OffsetsAreDecreasing,
+    OffsetOutOfBounds,
+    InvalidListFixedBytesLen,
+    ZeroLengthItem,
+    ZeroLengthVector,
+    UnionSelectorInvalid,
+    InvalidEnumTag,
+    InvalidVariantIndex,
+    InvalidVariantName,
+    ListTooLong,
+    VectorLengthMismatch,
+    InvalidBool,
+    BitfieldError,
+    CollectionBuildError,
+    BlsError,
+    FieldIndexOutOfRange,
+    MissingContextLimit,
+    #[cfg(feature = "milhouse")]
+    Milhouse,
+    #[cfg(feature = "ghilhouse")]
+    Ghilhouse,
+    #[cfg(feature = "ssz-types")]
+    SszTypes,
+    BytesInvalid,
+}
 
-    if len < expected {
-        Err(DecodeError::InvalidLengthPrefix { len, expected })
-    } else {
-        Ok(buf.get_u32_le() as usize)
+impl DecodeError {
+    /// Records that this error was raised `at_byte` bytes into the item being decoded. Errors
+    /// that already carry a position (relative to a nested item) have it shifted instead.
+    #[cfg(not(feature = "tiny-errors"))]
+    pub fn at_byte(self, at_byte: usize) -> Self {
+        match self {
+            DecodeError::AtByte {
+                at_byte: inner,
+                error,
+            } => DecodeError::AtByte {
+                at_byte: at_byte + inner,
+                error,
+            },
+            error => DecodeError::AtByte {
+                at_byte,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Records that this error was raised `at_byte` bytes into the item being decoded, keeping
+    /// only its kind. Errors that already carry a position have it shifted instead.
+    #[cfg(feature = "tiny-errors")]
+    pub fn at_byte(self, at_byte: usize) -> Self {
+        match self {
+            DecodeError::AtByte {
+                at_byte: inner,
+                kind,
+            } => DecodeError::AtByte {
+                at_byte: at_byte + inner,
+                kind,
+            },
+            error => DecodeError::AtByte {
+                at_byte,
+                kind: error.kind(),
+            },
+        }
+    }
+
+    /// Which error this is, or was at its position, without its fields.
+    #[cfg(feature = "tiny-errors")]
+    #[allow(deprecated)]
+    pub fn kind(&self) -> DecodeErrorKind {
+        match self {
+            DecodeError::InvalidByteLength { .. } => DecodeErrorKind::InvalidByteLength,
+            DecodeError::InvalidLengthPrefix { .. } => DecodeErrorKind::InvalidLengthPrefix,
+            DecodeError::OutOfBoundsByte { .. } => DecodeErrorKind::OutOfBoundsByte,
+            DecodeError::OffsetIntoFixedPortion(_) => DecodeErrorKind::OffsetIntoFixedPortion,
+            DecodeError::OffsetSkipsVariableBytes(_) => DecodeErrorKind::OffsetSkipsVariableBytes,
+            DecodeError::OffsetsAreDecreasing(_) => DecodeErrorKind::OffsetsAreDecreasing,
+            DecodeError::OffsetOutOfBounds(_) => DecodeErrorKind::OffsetOutOfBounds,
+            DecodeError::InvalidListFixedBytesLen(_) => DecodeErrorKind::InvalidListFixedBytesLen,
+            DecodeError::ZeroLengthItem => DecodeErrorKind::ZeroLengthItem,
+            DecodeError::ZeroLengthVector => DecodeErrorKind::ZeroLengthVector,
+            DecodeError::UnionSelectorInvalid(_) => DecodeErrorKind::UnionSelectorInvalid,
+            DecodeError::InvalidEnumTag(_) => DecodeErrorKind::InvalidEnumTag,
+            DecodeError::InvalidVariantIndex(_) => DecodeErrorKind::InvalidVariantIndex,
+            DecodeError::InvalidVariantName => DecodeErrorKind::InvalidVariantName,
+            DecodeError::ListTooLong { .. } => DecodeErrorKind::ListTooLong,
+            DecodeError::VectorLengthMismatch { .. } => DecodeErrorKind::VectorLengthMismatch,
+            DecodeError::InvalidBool(_) => DecodeErrorKind::InvalidBool,
+            DecodeError::BitfieldError(_) => DecodeErrorKind::BitfieldError,
+            DecodeError::CollectionBuildError(_) => DecodeErrorKind::CollectionBuildError,
+            DecodeError::BlsError(_) => DecodeErrorKind::BlsError,
+            DecodeError::FieldIndexOutOfRange { .. } => DecodeErrorKind::FieldIndexOutOfRange,
+            DecodeError::MissingContextLimit(_) => DecodeErrorKind::MissingContextLimit,
+            #[cfg(feature = "milhouse")]
+            DecodeError::Milhouse(_) => DecodeErrorKind::Milhouse,
+            #[cfg(feature = "ghilhouse")]
+            DecodeError::Ghilhouse(_) => DecodeErrorKind::Ghilhouse,
+            #[cfg(feature = "ssz-types")]
+            DecodeError::SszTypes(_) => DecodeErrorKind::SszTypes,
+            DecodeError::BytesInvalid(_) => DecodeErrorKind::BytesInvalid,
+            DecodeError::AtByte { kind, .. } => *kind,
+        }
+    }
+
+    /// The position of the error within the input, if it is known.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            DecodeError::AtByte { at_byte, .. } => Some(*at_byte),
+            _ => None,
+        }
+    }
+
+    /// The underlying error, stripped of its position. With `tiny-errors`, an error with a
+    /// position is its own root cause, as only its `kind` is kept.
+    pub fn root_cause(&self) -> &DecodeError {
+        match self {
+            #[cfg(not(feature = "tiny-errors"))]
+            DecodeError::AtByte { error, .. } => error.root_cause(),
+            error => error,
+        }
     }
 }
 
-pub fn read_offset_from_slice(bytes: &[u8]) -> Result<usize, DecodeError> {
-    decode_offset(bytes.get(0..BYTES_PER_LENGTH_OFFSET).ok_or(
-        DecodeError::InvalidLengthPrefix {
-            len: bytes.len(),
-            expected: BYTES_PER_LENGTH_OFFSET,
-        },
-    )?)
+#[cfg(not(feature = "tiny-errors"))]
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::AtByte { at_byte, error } => write!(f, "{} at byte {}", error, at_byte),
+            DecodeError::ListTooLong { len, max } => {
+                write!(f, "List of {} items exceeds maximum of {}", len, max)
+            }
+            DecodeError::VectorLengthMismatch { found, expected } => {
+                write!(
+                    f,
+                    "Vector of {} items not equal to length {}",
+                    found, expected
+                )
+            }
+            DecodeError::UnionSelectorInvalid(selector) => {
+                write!(f, "Invalid union selector {}", selector)
+            }
+            DecodeError::InvalidEnumTag(tag) => write!(f, "Unknown enum tag {}", tag),
+            DecodeError::InvalidBool(byte) => write!(f, "Out-of-range for boolean: {}", byte),
+            DecodeError::BitfieldError(reason) => write!(f, "Invalid bitfield: {}", reason),
+            DecodeError::CollectionBuildError(name) => write!(f, "Error building {}", name),
+            DecodeError::BlsError(name) => write!(f, "Invalid {}", name),
+            // the collection's own error is the source
+            #[cfg(feature = "milhouse")]
+            DecodeError::Milhouse(_) => write!(f, "Error building a milhouse collection"),
+            #[cfg(feature = "ghilhouse")]
+            DecodeError::Ghilhouse(_) => write!(f, "Error building a ghilhouse collection"),
+            #[cfg(feature = "ssz-types")]
+            DecodeError::SszTypes(_) => write!(f, "Error building an ssz_types collection"),
+            DecodeError::FieldIndexOutOfRange { index, num_fields } => write!(
+                f,
+                "Field index {} out of range for a container with {} fields",
+                index, num_fields
+            ),
+            DecodeError::MissingContextLimit(name) => {
+                write!(f, "No decode context limit named {}", name)
+            }
+            error => write!(f, "{:?}", error),
+        }
+    }
 }
 
-/// Decode bytes as a little-endian usize, returning an `Err` if `bytes.len() !=
-/// BYTES_PER_LENGTH_OFFSET`.
-fn decode_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
-    let len = bytes.len();
-    let expected = BYTES_PER_LENGTH_OFFSET;
+// none of the messages above, just the variant and its fields
+#[cfg(feature = "tiny-errors")]
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
 
-    if len != expected {
-        Err(DecodeError::InvalidLengthPrefix { len, expected })
-    } else {
-        let mut array: [u8; BYTES_PER_LENGTH_OFFSET] = std::default::Default::default();
-        array.clone_from_slice(bytes);
+// an error with a position has the error at that position as its source, down to the error
+// of the collection crate that failed to build a list
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(not(feature = "tiny-errors"))]
+            DecodeError::AtByte { error, .. } => Some(error.as_ref()),
+            #[cfg(all(feature = "milhouse", not(feature = "tiny-errors")))]
+            DecodeError::Milhouse(error) => Some(error),
+            #[cfg(all(feature = "ghilhouse", not(feature = "tiny-errors")))]
+            DecodeError::Ghilhouse(error) => Some(error),
+            #[cfg(all(feature = "ssz-types", not(feature = "tiny-errors")))]
+            DecodeError::SszTypes(error) => Some(error),
+            _ => None,
+        }
+    }
+}
 
-        Ok(u32::from_le_bytes(array) as usize)
+// without the `metrics` feature there's nothing to report failed decodes to. T, the type being
+// decoded, is what metrics::observe reports them under, so it's kept for the callers' sake
+#[cfg(not(feature = "metrics"))]
+#[allow(clippy::extra_unused_type_parameters)]
+#[inline(always)]
+pub(crate) fn observe<T, R>(
+    decode: impl FnOnce() -> Result<R, DecodeError>,
+) -> Result<R, DecodeError> {
+    decode()
+}
+
+// a static value spans exactly its fixed length, so trailing bytes are an error
+pub(crate) fn check_static_len<T: SszbDecode>(bytes: &[u8]) -> Result<(), DecodeError> {
+    if bytes.len() != T::SSZ_FIXED_LEN {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: T::SSZ_FIXED_LEN,
+        });
     }
+    Ok(())
 }
 
-/// Performs checks on the `offset` based upon the other parameters provided.
-///
-/// ## Detail
-///
-/// - `offset`: the offset bytes (e.g., result of `read_offset(..)`).
-/// - `previous_offset`: unless this is the first offset in the SSZ object, the value of the
-/// previously-read offset. Used to ensure offsets are not decreasing.
-/// - `num_bytes`: the total number of bytes in the SSZ object. Used to ensure the offset is not
-/// out of bounds.
-/// - `num_fixed_bytes`: the number of fixed-bytes in the struct, if it is known. Used to ensure
-/// that the first offset doesn't skip any variable bytes.
-///
-/// ## References
+/// Hands the next `len` bytes of `buf` to `read` as one slice and consumes them. They're borrowed
+/// in place when `buf`'s first chunk holds them all, and only copied when `buf` is fragmented.
+/// `buf` must have at least `len` bytes remaining.
 ///
-/// The checks here are derived from this document:
-///
-/// https://notes.ethereum.org/ruKvDXl6QOW3gnqVYb8ezA?view
-pub fn sanitize_offset(
-    offset: usize,
-    previous_offset: Option<usize>,
-    num_bytes: usize,
-    num_fixed_bytes: Option<usize>,
-) -> Result<usize, DecodeError> {
-    if num_fixed_bytes.map_or(false, |fixed_bytes| offset < fixed_bytes) {
-        Err(DecodeError::OffsetIntoFixedPortion(offset))
-    } else if previous_offset.is_none()
-        && num_fixed_bytes.map_or(false, |fixed_bytes| offset != fixed_bytes)
-    {
-        Err(DecodeError::OffsetSkipsVariableBytes(offset))
-    } else if offset > num_bytes {
-        Err(DecodeError::OffsetOutOfBounds(offset))
-    } else if previous_offset.map_or(false, |prev| prev > offset) {
-        Err(DecodeError::OffsetsAreDecreasing(offset))
+/// Impls that read a `Buf` go through this, or `copy_to_slice` and the `get_*` methods, rather
+/// than indexing `buf.chunk()`, which a `Chain` may split anywhere.
+#[doc(hidden)]
+pub fn read_contiguous<R>(buf: &mut impl Buf, len: usize, read: impl FnOnce(&[u8]) -> R) -> R {
+    if buf.chunk().len() >= len {
+        let result = read(&buf.chunk()[..len]);
+        buf.advance(len);
+        result
     } else {
-        Ok(offset)
+        read(&buf.copy_to_bytes(len))
     }
 }
 
+/// Checks that `bytes` is well-formed SSZ for `T`, e.g. before spending time on decoding a gossip
+/// message, without building a value or allocating along the way.
+///
+/// The layout is walked just like `from_ssz_bytes` walks it: fixed lengths, offsets, list lengths
+/// against their maximums, bitfield length bits and the values of booleans. An input passes if
+/// and only if `from_ssz_bytes` would decode it, in which case it usually fails with the same
+/// error. Types without a validator of their own (BLS keys, say) are decoded and dropped.
+pub fn validate_ssz<T: SszbDecode>(bytes: &[u8]) -> Result<(), DecodeError> {
+    T::validate_ssz_bytes(bytes)
+}
+
+/// Fails a derived decode whose `#[ssz(validate = "...")]` hook rejected the value, as a
+/// `DecodeError::BytesInvalid` naming `what` was checked and carrying the hook's own message
+/// (with `tiny-errors`, only naming `what`).
+#[doc(hidden)]
+pub fn check_decoded<E: core::fmt::Display>(
+    result: Result<(), E>,
+    what: &'static str,
+) -> Result<(), DecodeError> {
+    result.map_err(|error| {
+        #[cfg(not(feature = "tiny-errors"))]
+        #[allow(deprecated)]
+        let error = DecodeError::BytesInvalid(alloc::format!("{} is invalid: {}", what, error));
+        #[cfg(feature = "tiny-errors")]
+        #[allow(deprecated)]
+        let error = {
+            let _ = error;
+            DecodeError::BytesInvalid(what)
+        };
+        error
+    })
+}
+
+/// The error for a decoded value that was rejected for the reason given, such as a field that
+/// can't be converted from its `#[ssz(encode_as = "...")]` encoding: `DecodeError::BytesInvalid`
+/// with the reason.
+#[doc(hidden)]
+pub fn invalid_value(reason: &'static str) -> DecodeError {
+    #[cfg(not(feature = "tiny-errors"))]
+    #[allow(deprecated)]
+    let error = DecodeError::BytesInvalid(reason.into());
+    #[cfg(feature = "tiny-errors")]
+    #[allow(deprecated)]
+    let error = DecodeError::BytesInvalid(reason);
+    error
+}
+
 pub trait SszbDecode: Sized {
-    fn is_ssz_static() -> bool;
-    fn ssz_fixed_len() -> usize;
-    fn ssz_max_len() -> usize;
+    const SSZ_STATIC: bool;
+    const SSZ_FIXED_LEN: usize;
+    /// The length of the longest valid serialization of the value itself, not counting the offset
+    /// pointing to it from an enclosing type. The same as `SszbEncode::SSZ_MAX_LEN`.
+    const SSZ_MAX_LEN: usize;
+
+    /// Whether any `SSZ_FIXED_LEN` bytes are the memory of a valid value, so that values and
+    /// runs of them can be read by copying: unsigned integers on little-endian targets, byte
+    /// arrays, and `#[ssz(pod)]` containers of such fields.
+    const SSZ_POD: bool = false;
+
+    /// A fingerprint of the type's layout, see `sszb::schema`. Containers, lists and vectors
+    /// describe the types they're made of, everything else defaults to its lengths.
+    const SSZ_SCHEMA: [u8; 32] =
+        crate::schema::basic(Self::SSZ_STATIC, Self::SSZ_FIXED_LEN, Self::SSZ_MAX_LEN);
+
+    #[deprecated(note = "use the `SSZ_STATIC` associated constant instead")]
+    fn is_ssz_static() -> bool {
+        Self::SSZ_STATIC
+    }
+
+    #[deprecated(note = "use the `SSZ_FIXED_LEN` associated constant instead")]
+    fn ssz_fixed_len() -> usize {
+        Self::SSZ_FIXED_LEN
+    }
+
+    #[deprecated(note = "use the `SSZ_MAX_LEN` associated constant instead")]
+    fn ssz_max_len() -> usize {
+        Self::SSZ_MAX_LEN
+    }
 
     // Decoding happens in lockstep, where either:
     // - the static type is decoded from the fixed portion at the beginning of the data (fixed_bytes)
@@ -138,19 +463,174 @@ pub trait SszbDecode: Sized {
     // inputs depending on the type.
     //
     // We accept any input that implements Buf, which takes care of advancing the buffer for us.
+    //
+    // New impls should do their decoding in ssz_read_from, and implement this as a shim over it
+    // with `sszb::read_through_reader`, as derived containers do.
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError>;
 
-    // dev facing helper function for decoding a (static or variable) type from a slice
+    /// Decodes a value from `reader`, which spans exactly the value's bytes. See `SszReader`
+    /// for how it walks the fixed and variable sections, checking offsets and positioning
+    /// errors along the way.
+    ///
+    /// Derived containers, lists and vectors decode through this, and read their fields and
+    /// items with the reader's `read_fixed` and `read_variable`. The default decodes the
+    /// reader's bytes with `from_ssz_bytes`.
+    fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        Self::from_ssz_bytes(reader.bytes())
+    }
+
+    /// Decodes a run of static values stored back to back, as in the body of a list or vector.
+    /// `bytes` has to be a whole number of values long.
+    ///
+    /// Primitive integers override this to convert the whole run at once instead of value by value.
+    #[doc(hidden)]
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let len = Self::SSZ_FIXED_LEN;
+        let mut items = Vec::with_capacity(peek_list_len_static(bytes, len)?);
+        for (i, chunk) in bytes.chunks_exact(len).enumerate() {
+            items.push(Self::from_ssz_bytes(chunk).map_err(|e| e.at_byte(i * len))?);
+        }
+        Ok(items)
+    }
+
+    /// `ssz_read_packed`, appending the values to `items` instead of allocating a new vector.
+    #[doc(hidden)]
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        let len = Self::SSZ_FIXED_LEN;
+        items.reserve(peek_list_len_static(bytes, len)?);
+        for (i, chunk) in bytes.chunks_exact(len).enumerate() {
+            items.push(Self::from_ssz_bytes(chunk).map_err(|e| e.at_byte(i * len))?);
+        }
+        Ok(())
+    }
+
+    // ssz_read, overwriting self instead of returning a new value, so that types owning
+    // allocations can reuse them. the default just assigns the result of ssz_read, which leaves
+    // self untouched on error. overrides may leave self cleared on error, but never holding a mix
+    // of old and new items
+    fn ssz_read_into(
+        &mut self,
+        fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<(), DecodeError> {
+        *self = Self::ssz_read(fixed_bytes, variable_bytes)?;
+        Ok(())
+    }
+
+    // dev facing helper function for decoding a (static or variable) type from a slice.
+    // impls shouldn't need to override it: a static type gets its bytes in fixed_bytes, and a
+    // variable one gets all of them in variable_bytes, splitting off its own fixed portion
+    //
+    // with the `metrics` feature, a failed decode that isn't part of another is reported to the
+    // observer installed with sszb::set_decode_observer
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
-        if Self::is_ssz_static() {
-            let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(bytes.len());
-            Self::ssz_read(&mut fixed_bytes, &mut variable_bytes)
+        observe::<Self, _>(|| {
+            if Self::SSZ_STATIC {
+                check_static_len::<Self>(bytes)?;
+
+                let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(bytes.len());
+                Self::ssz_read(&mut fixed_bytes, &mut variable_bytes)
+            } else {
+                let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(0);
+                Self::ssz_read(&mut fixed_bytes, &mut variable_bytes)
+            }
+        })
+    }
+
+    /// Decodes a value from the front of `buf`, which may be fragmented (a `Chain`, say).
+    ///
+    /// On success, the value's bytes are consumed and the rest of `buf` is left for whatever
+    /// follows. A static value spans its fixed length, but nothing in the encoding says where
+    /// a variable one ends, so it spans the rest of `buf`. The bytes are only copied when they
+    /// aren't all in `buf`'s first chunk.
+    fn decode_from_buf(buf: &mut impl Buf) -> Result<Self, DecodeError> {
+        let len = if Self::SSZ_STATIC {
+            Self::SSZ_FIXED_LEN
         } else {
-            let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(0);
-            Self::ssz_read(&mut fixed_bytes, &mut variable_bytes)
+            buf.remaining()
+        };
+        if buf.remaining() < len {
+            return Err(DecodeError::InvalidByteLength {
+                len: buf.remaining(),
+                expected: len,
+            });
         }
+
+        read_contiguous(buf, len, Self::from_ssz_bytes)
+    }
+
+    /// `from_ssz_bytes` for input that's already in a refcounted `Bytes`, as it is when it comes
+    /// off the network. `Bytes` and `ByteList` values, whether they're the whole input or fields
+    /// of derived containers, are decoded as views sharing the input's allocation instead of
+    /// copies, and stay valid once the input itself is dropped. Everything else is decoded just
+    /// as `from_ssz_bytes` decodes it, with the same checks and errors.
+    ///
+    /// The input is only passed on through `ssz_read_from`, so values inside lists and unions,
+    /// which don't decode their items through it, get copies. A type of your own can share the
+    /// input too, by building itself from `SszReader::to_bytes` in its `ssz_read_from`.
+    fn from_ssz_bytes_shared(bytes: Bytes) -> Result<Self, DecodeError> {
+        observe::<Self, _>(|| {
+            if Self::SSZ_STATIC {
+                check_static_len::<Self>(&bytes)?;
+            }
+            Self::ssz_read_from(SszReader::shared(&bytes))
+        })
+    }
+
+    /// Decodes `bytes` into `self`, reusing the allocations `self` already owns, which saves
+    /// reallocating the same lists over and over when decoding many similar values in a loop.
+    ///
+    /// Lists keep their capacity, and lists of variable-size values decode into the values they
+    /// already hold. Vectors backed by a `Vec`, such as a `FixedVector`, decode over their items
+    /// in place. Derived containers decode each field in place, and every other type is simply
+    /// overwritten. The checks and errors are the same as `from_ssz_bytes`'s.
+    ///
+    /// On error, `self` is left valid but unspecified: a list may be left empty and a vector
+    /// keeps its old items, but neither ever holds a mix of old and new ones, while a container
+    /// may have decoded some of its fields and not the others.
+    fn decode_into(&mut self, bytes: &[u8]) -> Result<(), DecodeError> {
+        observe::<Self, _>(|| {
+            if Self::SSZ_STATIC {
+                check_static_len::<Self>(bytes)?;
+
+                let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(bytes.len());
+                self.ssz_read_into(&mut fixed_bytes, &mut variable_bytes)
+            } else {
+                let (mut fixed_bytes, mut variable_bytes) = bytes.split_at(0);
+                self.ssz_read_into(&mut fixed_bytes, &mut variable_bytes)
+            }
+        })
+    }
+
+    /// Checks that `bytes` is a valid encoding of `Self`, accepting exactly the inputs
+    /// `from_ssz_bytes` accepts, without building the value. See `validate_ssz`.
+    ///
+    /// The default decodes and drops the value. Primitives, bitfields, lists, vectors and
+    /// derived containers override it to walk the encoding without allocating.
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        Self::from_ssz_bytes(bytes).map(drop)
+    }
+
+    /// Decodes bytes that are known to be a valid encoding of `Self`, such as ones this process
+    /// encoded itself and read back from its own storage.
+    ///
+    /// **Never use this on untrusted input.** Collections and derived containers skip offset
+    /// sanitization and the separate maximum length checks, and only check offsets as far as
+    /// needed to slice the input without panicking. Structurally impossible inputs still return
+    /// an error, but other invalid encodings may decode to values that `from_ssz_bytes` would
+    /// have rejected, and which don't encode back to the same bytes.
+    fn from_ssz_bytes_trusted(bytes: &[u8]) -> Result<Self, DecodeError> {
+        observe::<Self, _>(|| Self::ssz_read_trusted(bytes))
+    }
+
+    // decodes the value the way from_ssz_bytes_trusted does, without reporting a failure to the
+    // decode observer (see sszb::metrics). Impls with a cheaper trusted decode override this
+    // rather than from_ssz_bytes_trusted, and the default decodes as from_ssz_bytes does
+    #[doc(hidden)]
+    fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_ssz_bytes(bytes)
     }
 }
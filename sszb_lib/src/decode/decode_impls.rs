@@ -1,73 +1,91 @@
 use crate::{
-    read_offset_from_slice, sanitize_offset, DecodeError, SszbDecode, BYTES_PER_LENGTH_OFFSET,
+    decode::{check_static_len, read_contiguous},
+    offsets::{
+        check_first_offset, check_list_first_offset, check_next_offset, read_offset, OffsetTable,
+    },
+    raw::{peek_list_len_static, peek_list_len_variable, split_offset_table},
+    DecodeError, SszReader, SszbDecode, BYTES_PER_LENGTH_OFFSET,
 };
-use alloy_primitives::{Address, Bloom, FixedBytes, U128, U256};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use bytes::buf::Buf;
-use ethereum_types::{H160, H256, H32};
-use itertools::{process_results, Itertools as _};
-use milhouse::{Error as MilhouseError, List as PersistentList, Value, Vector as PersistentVector};
+use core::ops::Range;
+use itertools::process_results;
 use paste::paste;
-use smallvec::ToSmallVec;
-use ssz_types::{BitList, BitVector, Error as SszTypeError, FixedVector, VariableList};
-use typenum::Unsigned;
 
 macro_rules! uint_ssz_decode {
     ($type: ident, $bit_size: expr) => {
         impl SszbDecode for $type {
-            fn is_ssz_static() -> bool {
-                true
-            }
-
-            fn ssz_fixed_len() -> usize {
-                $bit_size / 8
-            }
-
-            fn ssz_max_len() -> usize {
-                $bit_size / 8
-            }
+            const SSZ_STATIC: bool = true;
+            const SSZ_FIXED_LEN: usize = $bit_size / 8;
+            const SSZ_MAX_LEN: usize = $bit_size / 8;
+            const SSZ_POD: bool = cfg!(target_endian = "little");
 
             fn ssz_read(
                 fixed_bytes: &mut impl Buf,
                 _variable_bytes: &mut impl Buf,
             ) -> Result<Self, DecodeError> {
                 let len = fixed_bytes.remaining();
-                let expected = <Self as SszbDecode>::ssz_fixed_len();
+                let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
 
                 if len < expected {
                     Err(DecodeError::InvalidByteLength { len, expected })
                 } else {
-                    let bytes: [u8; ($bit_size / 8)] =
-                        <[u8; ($bit_size / 8)]>::try_from(&fixed_bytes.chunk()[0..($bit_size / 8)])
-                            .unwrap();
-                    let number = paste! { [<$type>]::from_le_bytes(bytes) };
-                    fixed_bytes.advance(($bit_size / 8));
-                    //Ok(paste! { fixed_bytes.[<get_ $type _le>]() })
-                    Ok(number)
+                    // reads across the chunks of a fragmented buffer
+                    Ok(paste! { fixed_bytes.[<get_ $type _le>]() })
+                }
+            }
+
+            fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+                peek_list_len_static(bytes, <Self as SszbDecode>::SSZ_FIXED_LEN)?;
+
+                if cfg!(target_endian = "little") {
+                    // the wire format is the in-memory layout, so this is a plain copy
+                    Ok(bytemuck::pod_collect_to_vec(bytes))
+                } else {
+                    Ok(bytes
+                        .chunks_exact($bit_size / 8)
+                        .map(|chunk| {
+                            let mut array = [0u8; $bit_size / 8];
+                            array.copy_from_slice(chunk);
+                            <$type>::from_le_bytes(array)
+                        })
+                        .collect())
                 }
             }
+
+            fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+                check_static_len::<Self>(bytes)
+            }
+
+            fn ssz_read_packed_into(
+                bytes: &[u8],
+                items: &mut Vec<Self>,
+            ) -> Result<(), DecodeError> {
+                peek_list_len_static(bytes, <Self as SszbDecode>::SSZ_FIXED_LEN)?;
+
+                items.extend(bytes.chunks_exact($bit_size / 8).map(|chunk| {
+                    let mut array = [0u8; $bit_size / 8];
+                    array.copy_from_slice(chunk);
+                    <$type>::from_le_bytes(array)
+                }));
+                Ok(())
+            }
         }
     };
 }
 
 impl SszbDecode for u8 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        1
-    }
-
-    fn ssz_max_len() -> usize {
-        1
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 1;
+    const SSZ_MAX_LEN: usize = 1;
+    const SSZ_POD: bool = true;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -75,6 +93,19 @@ impl SszbDecode for u8 {
             Ok(fixed_bytes.get_u8())
         }
     }
+
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        Ok(bytes.to_vec())
+    }
+
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        items.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        check_static_len::<Self>(bytes)
+    }
 }
 
 uint_ssz_decode!(u16, 16);
@@ -83,24 +114,16 @@ uint_ssz_decode!(u64, 64);
 uint_ssz_decode!(u128, 128);
 
 impl SszbDecode for bool {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        1
-    }
-
-    fn ssz_max_len() -> usize {
-        1
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 1;
+    const SSZ_MAX_LEN: usize = 1;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
@@ -108,841 +131,506 @@ impl SszbDecode for bool {
             match fixed_bytes.get_u8() {
                 0 => Ok(false),
                 1 => Ok(true),
-                num => Err(DecodeError::BytesInvalid(format!(
-                    "Out-of-range for boolean: {}",
-                    num
-                ))),
+                num => Err(DecodeError::InvalidBool(num)),
             }
         }
     }
-}
-
-impl<const N: usize> SszbDecode for [u8; N] {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        N
-    }
-
-    fn ssz_max_len() -> usize {
-        N
-    }
 
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        //[0u8; N];
-        let bytes: [u8; N] = <[u8; N]>::try_from(&fixed_bytes.chunk()[0..N]).unwrap();
-        fixed_bytes.advance(N);
-        // bytes[..] = fixed_bytes.chunk()[..N];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(bytes)
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        check_static_len::<Self>(bytes)?;
+        match bytes[0] {
+            0 | 1 => Ok(()),
+            num => Err(DecodeError::InvalidBool(num)),
         }
     }
 }
 
-impl SszbDecode for Address {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        20
-    }
-
-    fn ssz_max_len() -> usize {
-        20
-    }
+impl<const N: usize> SszbDecode for [u8; N] {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+    const SSZ_MAX_LEN: usize = N;
+    const SSZ_POD: bool = true;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 20] = [0u8; 20];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 20] = <[u8; 20]>::try_from(&fixed_bytes.chunk()[0..20]).unwrap();
-        fixed_bytes.advance(20);
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
 
-        if len < expected {
+        if N == 0 {
+            Err(DecodeError::ZeroLengthVector)
+        } else if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
         } else {
-            Ok(Self::from(bytes))
+            let mut bytes = [0u8; N];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(bytes)
         }
     }
-}
-
-impl<const N: usize> SszbDecode for FixedBytes<N> {
-    fn is_ssz_static() -> bool {
-        true
-    }
 
-    fn ssz_fixed_len() -> usize {
-        N
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut items = Vec::new();
+        read_byte_arrays(bytes, &mut items)?;
+        Ok(items)
     }
 
-    fn ssz_max_len() -> usize {
-        N
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        read_byte_arrays(bytes, items)
     }
 
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; N] = [0u8; N];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; N] = <[u8; N]>::try_from(&fixed_bytes.chunk()[0..N]).unwrap();
-        fixed_bytes.advance(N);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self(bytes))
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        if N == 0 {
+            return Err(DecodeError::ZeroLengthVector);
         }
+        check_static_len::<Self>(bytes)
     }
 }
 
-impl SszbDecode for Bloom {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        256
-    }
-
-    fn ssz_max_len() -> usize {
-        256
-    }
-
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 256] = [0u8; 256];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 256] = <[u8; 256]>::try_from(&fixed_bytes.chunk()[0..256]).unwrap();
-        fixed_bytes.advance(256);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
-        }
-    }
+/// `ssz_read_packed_into` for byte array newtypes, such as `FixedBytes<N>`, `Address` and
+/// `H256`, and byte arrays themselves. Any `N` bytes are a valid item, so the length of the run
+/// is checked once, and the items are built straight from their chunks without being decoded
+/// one by one. A run that isn't a whole number of items fails like the generic path.
+pub(crate) fn read_byte_arrays<T: From<[u8; N]>, const N: usize>(
+    bytes: &[u8],
+    items: &mut Vec<T>,
+) -> Result<(), DecodeError> {
+    items.reserve(peek_list_len_static(bytes, N)?);
+    // the conversion can't fail, and compiles down to a copy
+    items.extend(
+        bytes
+            .chunks_exact(N)
+            .map(|chunk| T::from(<[u8; N]>::try_from(chunk).expect("chunks are N bytes"))),
+    );
+    Ok(())
 }
 
-impl SszbDecode for U256 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        32
-    }
-    fn ssz_max_len() -> usize {
-        32
-    }
+// smart pointers are decoded as the value they point to, which is how recursive types, such as
+// a tree whose nodes hold a list of their children, get decoded. every path goes through T's own,
+// so a pointer in a field or a union variant reads exactly like the value it points to. an Arc is
+// always decoded into a new allocation, since other owners may be holding the old one
+impl<T: SszbDecode> SszbDecode for Box<T> {
+    const SSZ_STATIC: bool = T::SSZ_STATIC;
+    const SSZ_FIXED_LEN: usize = T::SSZ_FIXED_LEN;
+    const SSZ_MAX_LEN: usize = T::SSZ_MAX_LEN;
+    const SSZ_SCHEMA: [u8; 32] = T::SSZ_SCHEMA;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 32] = [0u8; 32];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 32] = <[u8; 32]>::try_from(&fixed_bytes.chunk()[0..32]).unwrap();
-        fixed_bytes.advance(32);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_le_slice(&bytes))
-        }
+        T::ssz_read(fixed_bytes, variable_bytes).map(Box::new)
     }
-}
 
-impl SszbDecode for U128 {
-    fn is_ssz_static() -> bool {
-        true
+    fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        T::ssz_read_from(reader).map(Box::new)
     }
 
-    fn ssz_fixed_len() -> usize {
-        16
-    }
-    fn ssz_max_len() -> usize {
-        16
-    }
-
-    fn ssz_read(
+    fn ssz_read_into(
+        &mut self,
         fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 16] = [0u8; 16];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 16] = <[u8; 16]>::try_from(&fixed_bytes.chunk()[0..16]).unwrap();
-        fixed_bytes.advance(16);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_le_slice(&bytes))
-        }
+        variable_bytes: &mut impl Buf,
+    ) -> Result<(), DecodeError> {
+        (**self).ssz_read_into(fixed_bytes, variable_bytes)
     }
-}
 
-impl SszbDecode for H32 {
-    fn is_ssz_static() -> bool {
-        true
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        T::validate_ssz_bytes(bytes)
     }
 
-    fn ssz_fixed_len() -> usize {
-        4
-    }
-    fn ssz_max_len() -> usize {
-        4
-    }
-
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 4] = [0u8; 4];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 4] = <[u8; 4]>::try_from(&fixed_bytes.chunk()[0..4]).unwrap();
-        fixed_bytes.advance(4);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
-        }
+    fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, DecodeError> {
+        T::ssz_read_trusted(bytes).map(Box::new)
     }
 }
 
-impl SszbDecode for H160 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        20
-    }
-    fn ssz_max_len() -> usize {
-        20
-    }
+impl<T: SszbDecode> SszbDecode for Arc<T> {
+    const SSZ_STATIC: bool = T::SSZ_STATIC;
+    const SSZ_FIXED_LEN: usize = T::SSZ_FIXED_LEN;
+    const SSZ_MAX_LEN: usize = T::SSZ_MAX_LEN;
+    const SSZ_SCHEMA: [u8; 32] = T::SSZ_SCHEMA;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 20] = [0u8; 20];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 20] = <[u8; 20]>::try_from(&fixed_bytes.chunk()[0..20]).unwrap();
-        fixed_bytes.advance(20);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
-        }
+        T::ssz_read(fixed_bytes, variable_bytes).map(Arc::new)
     }
-}
 
-impl SszbDecode for H256 {
-    fn is_ssz_static() -> bool {
-        true
+    fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        T::ssz_read_from(reader).map(Arc::new)
     }
 
-    fn ssz_fixed_len() -> usize {
-        32
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        T::validate_ssz_bytes(bytes)
     }
 
-    fn ssz_max_len() -> usize {
-        32
-    }
-
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        // let mut bytes: [u8; 32] = [0u8; 32];
-        // fixed_bytes.copy_to_slice(&mut bytes[..]);
-
-        let bytes: [u8; 32] = <[u8; 32]>::try_from(&fixed_bytes.chunk()[0..32]).unwrap();
-        fixed_bytes.advance(32);
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            Ok(Self::from_slice(&bytes))
-        }
+    fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, DecodeError> {
+        T::ssz_read_trusted(bytes).map(Arc::new)
     }
 }
 
-impl<N: Unsigned + Clone> SszbDecode for BitVector<N> {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
-    fn ssz_max_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        _variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
-
-        if len < expected {
-            Err(DecodeError::InvalidByteLength { len, expected })
-        } else {
-            let bytes = &fixed_bytes.chunk()[..expected]; // .copy_to_bytes(expected);
+pub trait TryFromIter<T>: Sized {
+    type Error: Into<DecodeError>;
 
-            let bitvec = Self::from_bytes(bytes.to_smallvec()).map_err(|e| {
-                DecodeError::BytesInvalid(format!("BitVector failed to decode: {:?}", e))
-            });
-            fixed_bytes.advance(expected);
-            bitvec
-        }
-    }
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error>;
 }
 
-impl<N: Unsigned + Clone> SszbDecode for BitList<N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
+pub fn ssz_decode_variable_length_items<T: SszbDecode, L: TryFromIter<T>>(
+    mut var_offsets: impl Buf,
+    var_items: &mut impl Buf,
+) -> Result<L, DecodeError> {
+    // offsets and items are read as slices, gathered first if a fragmented buffer splits them
+    let offsets_len = var_offsets.remaining();
+    let items_len = var_items.remaining();
+    read_contiguous(&mut var_offsets, offsets_len, |var_offsets| {
+        read_contiguous(var_items, items_len, |var_items| {
+            decode_variable_length_items(var_offsets, var_items)
+        })
+    })
+}
 
-    fn ssz_max_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET + std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
+pub(crate) fn decode_variable_length_items<T: SszbDecode, L: TryFromIter<T>>(
+    var_offsets: &[u8],
+    mut var_items: &[u8],
+) -> Result<L, DecodeError> {
+    let Some(ranges) = variable_item_ranges(var_offsets, var_items)? else {
+        return L::try_from_iter(core::iter::empty()).map_err(Into::into);
+    };
 
-    fn ssz_read(
-        _fixed_bytes: &mut impl Buf,
-        variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        // let bytes = variable_bytes.copy_to_bytes(variable_bytes.remaining());
+    process_results(
+        ranges.map(move |range| {
+            let range = range?;
+            let bytes = var_items
+                .get(..range.len())
+                .ok_or(DecodeError::OffsetOutOfBounds(range.end))?;
+            let res = <T as SszbDecode>::from_ssz_bytes(bytes).map_err(|e| e.at_byte(range.start));
+            var_items = &var_items[range.len()..];
+            res
+        }),
+        |iter| L::try_from_iter(iter),
+    )?
+    .map_err(Into::into)
+}
 
-        let bytes = &variable_bytes.chunk()[..]; // .copy_to_bytes(expected);
+/// The ranges of the items of a list or vector of variable-size values, with the whole offset
+/// table checked, or `None` if it has no items. The serial and parallel decoders both start here,
+/// so they reject the same tables with the same errors.
+pub(crate) fn variable_item_ranges<'a>(
+    var_offsets: &'a [u8],
+    var_items: &[u8],
+) -> Result<Option<VariableItemRanges<'a>>, DecodeError> {
+    if var_offsets.is_empty() && var_items.is_empty() {
+        return Ok(None);
+    }
+
+    // the first offset has to point right past the offset table,
+    // otherwise items would be read starting from the wrong position
+    if let Some(first_offset) = var_offsets.get(..BYTES_PER_LENGTH_OFFSET) {
+        check_first_offset(
+            read_offset(first_offset)?,
+            var_offsets.len(),
+            var_offsets.len() + var_items.len(),
+        )
+        .map_err(|e| e.at_byte(0))?;
+    }
+
+    // the whole offset table is checked before any item is decoded, so a bad offset is reported
+    // wherever it is, rather than the errors of the items before it
+    let end_of_list = var_offsets.len() + var_items.len();
+    VariableItemRanges::checked(var_offsets, end_of_list).map(Some)
+}
 
-        Self::from_bytes(bytes.to_smallvec())
-            .map_err(|e| DecodeError::BytesInvalid(format!("BitVector failed to decode: {:?}", e)))
-    }
+/// Decodes the body of a list of at most `max_len` values into `items`, keeping its allocation
+/// and, for variable-size values, decoding into the values it already holds. This is what
+/// `decode_into` does for `VariableList`s, and can be used directly for a plain `Vec`.
+///
+/// The list is always decoded serially. On error `items` is left empty, rather than holding a
+/// mix of old and new values.
+pub fn decode_list_into<T: SszbDecode>(
+    bytes: &[u8],
+    max_len: usize,
+    items: &mut Vec<T>,
+) -> Result<(), DecodeError> {
+    let result = read_list_into(bytes, max_len, items);
+    if result.is_err() {
+        items.clear();
+    }
+    result
 }
 
-impl<T: SszbDecode + Value, N: Unsigned> SszbDecode for PersistentList<T, N> {
-    fn is_ssz_static() -> bool {
-        false
+fn read_list_into<T: SszbDecode>(
+    bytes: &[u8],
+    max_len: usize,
+    items: &mut Vec<T>,
+) -> Result<(), DecodeError> {
+    let num_items = if T::SSZ_STATIC {
+        peek_list_len_static(bytes, T::SSZ_FIXED_LEN)?
+    } else {
+        peek_list_len_variable(bytes)?
+    };
+    if num_items > max_len {
+        return Err(DecodeError::ListTooLong {
+            len: num_items,
+            max: max_len,
+        });
     }
 
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
+    if T::SSZ_STATIC {
+        items.clear();
+        return T::ssz_read_packed_into(bytes, items);
     }
 
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszbDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
+    // the items beyond the new length are dropped, the rest are decoded over
+    items.truncate(num_items);
+    let (offsets, _) = split_offset_table(bytes, num_items)?;
+    for (i, range) in VariableItemRanges::checked(offsets, bytes.len())?.enumerate() {
+        let range = range?;
+        let item_bytes = &bytes[range.clone()];
+        match items.get_mut(i) {
+            Some(item) => item.decode_into(item_bytes),
+            None => T::from_ssz_bytes(item_bytes).map(|item| items.push(item)),
         }
+        .map_err(|e| e.at_byte(range.start))?;
     }
+    Ok(())
+}
 
-    fn ssz_read(
-        _fixed_bytes: &mut impl Buf,
-        variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let max_len = N::to_usize();
-
-        // Lists are always stored in the dynamic section at the end
-        // So we only check if the variable bytes are empty
-        if !variable_bytes.has_remaining() {
-            Ok(Self::empty())
-        } else if T::is_ssz_static() {
-            let num_items = variable_bytes
-                .remaining()
-                .checked_div(<T as SszbDecode>::ssz_fixed_len())
-                .ok_or(DecodeError::ZeroLengthItem)?;
-
-            if num_items > max_len {
-                return Err(DecodeError::BytesInvalid(format!(
-                    "List of {} items exceeds maximum of {}",
-                    num_items, max_len
-                )));
-            }
-
-            // let bytes = variable_bytes.copy_to_bytes(num_items * <T as SszbDecode>::ssz_fixed_len());
-
-            process_results(
-                variable_bytes
-                    .chunk()
-                    .chunks_exact(<T as SszbDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszbDecode>::from_ssz_bytes(chunk)),
-                |iter| PersistentList::try_from_iter(iter),
-            )?
-            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
-        } else {
-            // we move over variable_bytes to var_offsets (of type Bytes) since it has more methods for us to work with
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            let var_offsets = variable_bytes.chunk();
-
-            let first_offset = read_offset_from_slice(&var_offsets[0..BYTES_PER_LENGTH_OFFSET])?;
-            sanitize_offset(
-                first_offset,
-                None,
-                var_offsets[BYTES_PER_LENGTH_OFFSET..].len(),
-                Some(first_offset),
-            )?;
-            if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET
-            {
-                return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
-            }
-
-            // get how many items are in the list by reading the offset (only way to deduce in variable lists)
-            let num_items = first_offset / BYTES_PER_LENGTH_OFFSET;
-
-            // if length exceeds expected max_len then revert
-            if num_items > max_len {
-                return Err(DecodeError::BytesInvalid(format!(
-                    "Variable length list of {} items exceeds maximum of {:?}",
-                    num_items, max_len
-                )));
-            }
-
-            // var_offsets now only contains the offsets, and var_items contains the list items (bytes)
-            // let mut var_items = var_offsets.split_off(num_items * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
+/// Checks the body of a list of at most `max_len` values the way decoding it would, without
+/// building any of them.
+pub(crate) fn validate_list<T: SszbDecode>(
+    bytes: &[u8],
+    max_len: usize,
+) -> Result<(), DecodeError> {
+    visit_list_items(bytes, (T::SSZ_STATIC, T::SSZ_FIXED_LEN), max_len, |range| {
+        T::validate_ssz_bytes(&bytes[range.clone()]).map_err(|e| e.at_byte(range.start))
+    })
+}
 
-            let mut var_items = &var_offsets[(num_items * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(
-                &var_offsets[..(num_items * BYTES_PER_LENGTH_OFFSET)],
-                &mut var_items,
-            )
-        }
-    }
+/// Checks the body of a vector of `len` values the way decoding it would, without building any
+/// of them.
+pub(crate) fn validate_vector<T: SszbDecode>(bytes: &[u8], len: usize) -> Result<(), DecodeError> {
+    visit_vector_items(bytes, (T::SSZ_STATIC, T::SSZ_FIXED_LEN), len, |range| {
+        T::validate_ssz_bytes(&bytes[range.clone()]).map_err(|e| e.at_byte(range.start))
+    })
 }
 
-impl<T: SszbDecode + Value, N: Unsigned> SszbDecode for PersistentVector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
+/// Hands the byte range of each item of a list of at most `max_len` items to `visit`, in order,
+/// given the items' `(SSZ_STATIC, SSZ_FIXED_LEN)`. The length and offsets are checked exactly as
+/// decoding the list checks them, so `validate_list` and the schema-driven decoder in
+/// `sszb::dynamic` report the same errors.
+pub(crate) fn visit_list_items(
+    bytes: &[u8],
+    (is_static, item_len): (bool, usize),
+    max_len: usize,
+    visit: impl FnMut(Range<usize>) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+
+    let num_items = if is_static {
+        peek_list_len_static(bytes, item_len)?
+    } else {
+        peek_list_len_variable(bytes)?
+    };
+    if num_items > max_len {
+        return Err(DecodeError::ListTooLong {
+            len: num_items,
+            max: max_len,
+        });
     }
 
-    fn ssz_fixed_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszbDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
+    if is_static {
+        visit_packed_items(bytes, item_len, visit)
+    } else {
+        visit_variable_items(bytes, num_items, visit)
     }
+}
 
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszbDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
+/// Like `visit_list_items`, for a vector of `len` items. A vector of no items is illegal, so it
+/// rejects every input.
+pub(crate) fn visit_vector_items(
+    bytes: &[u8],
+    (is_static, item_len): (bool, usize),
+    len: usize,
+    visit: impl FnMut(Range<usize>) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    if len == 0 {
+        return Err(DecodeError::ZeroLengthVector);
+    }
+    if is_static {
+        // the vector is static too, so from_ssz_bytes checks its whole length first
+        if bytes.len() != len * item_len {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: len * item_len,
+            });
         }
-    }
-
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = N::to_usize();
-
-        // Vectors are either static, in which case the data is in the fixed bytes section
-        // or it's dynamic and the data is in variable bytes.
-        // The vector is empty if both sections are empty.
-        if !(fixed_bytes.has_remaining() || variable_bytes.has_remaining()) {
-            Ok(Self::try_from(PersistentList::empty()).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Error decoding empty vector: {:?}", e))
-            })?)
-        } else if T::is_ssz_static() {
-            // T is static, so data resides in fixed_bytes
-            if fixed_bytes.remaining() < len * <T as SszbDecode>::ssz_fixed_len() {
-                return Err(DecodeError::BytesInvalid(format!(
-                    "Vector of {} items not equal to length {}",
-                    fixed_bytes
-                        .remaining()
-                        .checked_div(<T as SszbDecode>::ssz_fixed_len())
-                        .unwrap(),
-                    len
-                )));
-            }
-
-            // create slice of length `len * T::ssz_fixed_len`
-            // let bytes = fixed_bytes.copy_to_bytes(len * <T as SszbDecode>::ssz_fixed_len());
-            let bytes = &fixed_bytes.chunk()[..(len * <T as SszbDecode>::ssz_fixed_len())];
-
-            let res = process_results(
-                bytes
-                    .chunks_exact(<T as SszbDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszbDecode>::from_ssz_bytes(chunk)),
-                |iter| PersistentVector::try_from_iter(iter),
-            )?
-            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)));
-
-            fixed_bytes.advance(len * <T as SszbDecode>::ssz_fixed_len());
-            res
-        } else {
-            // T is not static so data resides in variable_bytes
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            // let mut var_items = var_offsets.split_off(len * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
-
-            let var_offsets = &variable_bytes.chunk()[..(len * BYTES_PER_LENGTH_OFFSET)];
-            let mut var_items = &variable_bytes.chunk()[(len * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(var_offsets, &mut var_items)
+        // an empty body is only left by items of no bytes, which visiting rejects
+        visit_packed_items(bytes, item_len, visit)
+    } else if bytes.is_empty() {
+        Err(DecodeError::VectorLengthMismatch {
+            found: 0,
+            expected: len,
+        })
+    } else {
+        if bytes.len() < len * BYTES_PER_LENGTH_OFFSET {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: len * BYTES_PER_LENGTH_OFFSET,
+            });
         }
+        visit_variable_items(bytes, len, visit)
     }
 }
 
-impl<T: SszbDecode, N: Unsigned> SszbDecode for VariableList<T, N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
+// for types whose every encoding is valid, such as integers, visiting is just a loop over length
+// checks that the optimizer can drop
+fn visit_packed_items(
+    bytes: &[u8],
+    item_len: usize,
+    mut visit: impl FnMut(Range<usize>) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    peek_list_len_static(bytes, item_len)?;
+    for i in 0..(bytes.len() / item_len) {
+        visit((i * item_len)..((i + 1) * item_len))?;
+    }
+    Ok(())
+}
 
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszbDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
+// the checks of ssz_decode_variable_length_items, with every item visited in place
+fn visit_variable_items(
+    bytes: &[u8],
+    num_items: usize,
+    mut visit: impl FnMut(Range<usize>) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    let (offsets, _) = split_offset_table(bytes, num_items)?;
+    if let Some(first_offset) = offsets.get(..BYTES_PER_LENGTH_OFFSET) {
+        check_first_offset(read_offset(first_offset)?, offsets.len(), bytes.len())
+            .map_err(|e| e.at_byte(0))?;
     }
 
-    fn ssz_read(
-        _fixed_bytes: &mut impl Buf,
-        variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let max_len = N::to_usize();
-
-        if !variable_bytes.has_remaining() {
-            Ok(Self::empty())
-        } else if T::is_ssz_static() {
-            let num_items = variable_bytes
-                .remaining()
-                .checked_div(<T as SszbDecode>::ssz_fixed_len())
-                .ok_or(DecodeError::ZeroLengthItem)?;
-
-            if num_items > max_len {
-                return Err(DecodeError::BytesInvalid(format!(
-                    "List of {} items exceeds maximum of {}",
-                    num_items, max_len
-                )));
-            }
-
-            // let bytes = variable_bytes.copy_to_bytes(num_items * <T as SszbDecode>::ssz_fixed_len());
-
-            process_results(
-                variable_bytes
-                    .chunk()
-                    .chunks_exact(<T as SszbDecode>::ssz_fixed_len())
-                    .map(|chunk| <T as SszbDecode>::from_ssz_bytes(chunk)),
-                |iter| VariableList::try_from_iter(iter),
-            )?
-            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
-        } else {
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            let var_offsets = variable_bytes.chunk();
-
-            let first_offset = read_offset_from_slice(&var_offsets[0..BYTES_PER_LENGTH_OFFSET])?;
-            sanitize_offset(
-                first_offset,
-                None,
-                var_offsets[BYTES_PER_LENGTH_OFFSET..].len(),
-                Some(first_offset),
-            )?;
-            if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset < BYTES_PER_LENGTH_OFFSET
-            {
-                return Err(DecodeError::InvalidListFixedBytesLen(first_offset));
-            }
-
-            // get how many items are in the list by reading the offset (only way to deduce in variable lists)
-            let num_items = first_offset / BYTES_PER_LENGTH_OFFSET;
-
-            // if length exceeds expected max_len then revert
-            if num_items > max_len {
-                return Err(DecodeError::BytesInvalid(format!(
-                    "Variable length list of {} items exceeds maximum of {:?}",
-                    num_items, max_len
-                )));
-            }
-
-            let mut var_items = &var_offsets[(num_items * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(
-                &var_offsets[..(num_items * BYTES_PER_LENGTH_OFFSET)],
-                &mut var_items,
-            )
-        }
+    for range in VariableItemRanges::checked(offsets, bytes.len())? {
+        visit(range?)?;
     }
+    Ok(())
 }
 
-impl<T: SszbDecode, N: Unsigned> SszbDecode for FixedVector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
+pub(crate) const EXCESS_BITS: &str = "bits are set past its length";
+pub(crate) const MISSING_LENGTH_BIT: &str = "its length bit is missing";
+pub(crate) const TOO_LONG: &str = "it exceeds its maximum length";
+pub(crate) const WRONG_BYTE_COUNT: &str = "it has the wrong number of bytes";
 
-    fn ssz_fixed_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszbDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
+/// Checks that no bit is set past the end of a bit vector of `bits` bits, whose bytes have
+/// already been checked to be `bitfield_bytes_len(bits)` long.
+/// A bit vector of no bits is rejected whatever its bytes.
+pub(crate) fn validate_bitvector_bits(bytes: &[u8], bits: usize) -> Result<(), DecodeError> {
+    if bits == 0 {
+        return Err(DecodeError::ZeroLengthVector);
     }
-
-    fn ssz_max_len() -> usize {
-        if T::is_ssz_static() {
-            <T as SszbDecode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = T::ssz_max_len() * N::to_usize();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
+    // the bits of the last byte past the vector's length have to be clear
+    let used_bits = bits % 8;
+    if used_bits == 0 {
+        return Ok(());
     }
-
-    fn ssz_read(
-        fixed_bytes: &mut impl Buf,
-        variable_bytes: &mut impl Buf,
-    ) -> Result<Self, DecodeError> {
-        let len = N::to_usize();
-
-        if !(fixed_bytes.has_remaining() || variable_bytes.has_remaining()) {
-            Ok(Self::new(Vec::new()).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Error deocoding empty vector: {:?}", e))
-            })?)
-        } else if T::is_ssz_static() {
-            if fixed_bytes.remaining() < len * <T as SszbDecode>::ssz_fixed_len() {
-                return Err(DecodeError::BytesInvalid(format!(
-                    "Vector of {} items not equal to length {}",
-                    fixed_bytes
-                        .remaining()
-                        .checked_div(<T as SszbDecode>::ssz_fixed_len())
-                        .unwrap(),
-                    len
-                )));
-            }
-
-            // create slice of length `len * T::ssz_fixed_len`
-            // let bytes = fixed_bytes.copy_to_bytes(len * <T as SszbDecode>::ssz_fixed_len());
-            let bytes = &fixed_bytes.chunk()[..(len * <T as SszbDecode>::ssz_fixed_len())];
-
-            // let res = process_results(
-            //     bytes
-            //         .chunks_exact(<T as SszbDecode>::ssz_fixed_len())
-            //         .map(|chunk| <T as SszbDecode>::from_ssz_bytes(chunk)),
-            //     |iter| FixedVector::try_from_iter(iter),
-            // )?
-            // .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)));
-
-            // fixed_bytes.advance(len * <T as SszbDecode>::ssz_fixed_len());
-            // res
-
-            let vec = bytes
-                .chunks_exact(<T as SszbDecode>::ssz_fixed_len())
-                .try_fold(Vec::with_capacity(len), |mut vec, chunk| {
-                    vec.push(<T as SszbDecode>::from_ssz_bytes(chunk)?);
-                    Ok(vec)
-                })?;
-            fixed_bytes.advance(len * <T as SszbDecode>::ssz_fixed_len());
-            Self::new(vec).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Wrong number of FixedVector elements: {:?}", e))
-            })
-        } else {
-            // let mut var_offsets = variable_bytes.copy_to_bytes(variable_bytes.remaining());
-            // let mut var_items = var_offsets.split_off(len * BYTES_PER_LENGTH_OFFSET);
-            // ssz_decode_variable_length_items(var_offsets, &mut var_items)
-
-            let var_offsets = &variable_bytes.chunk()[..(len * BYTES_PER_LENGTH_OFFSET)];
-            let mut var_items = &variable_bytes.chunk()[(len * BYTES_PER_LENGTH_OFFSET)..];
-            ssz_decode_variable_length_items(var_offsets, &mut var_items)
-        }
+    if bytes[bytes.len() - 1] >> used_bits != 0 {
+        return Err(DecodeError::BitfieldError(EXCESS_BITS));
     }
+    Ok(())
 }
 
-pub trait TryFromIter<T>: Sized {
-    type Error: std::fmt::Debug;
-
-    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error>;
+/// Returns the number of bits in a bit list of at most `max_bits` bits, checking its length bit.
+pub(crate) fn bitlist_len(bytes: &[u8], max_bits: usize) -> Result<usize, DecodeError> {
+    // the highest set bit is the length bit, and it has to be in the last byte
+    let last = bytes.last().copied().unwrap_or(0);
+    if last == 0 {
+        // a length bit in an earlier byte means there are too many bytes
+        return Err(DecodeError::BitfieldError(
+            if bytes.iter().any(|&byte| byte != 0) {
+                WRONG_BYTE_COUNT
+            } else {
+                MISSING_LENGTH_BIT
+            },
+        ));
+    }
+    let len = (bytes.len() - 1) * 8 + (7 - last.leading_zeros() as usize);
+    if len > max_bits {
+        return Err(DecodeError::BitfieldError(TOO_LONG));
+    }
+    Ok(len)
 }
 
-impl<T, N> TryFromIter<T> for PersistentList<T, N>
-where
-    T: Value + SszbDecode,
-    N: Unsigned,
-{
-    type Error = MilhouseError;
-
-    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
-        PersistentList::try_from_iter(iter)
-    }
+/// The byte range of each item of a list of variable-size values, given its offset table (whose
+/// first offset has already been checked) and the length of the whole list.
+///
+/// Each pair of offsets is checked as the range between them is reached, stopping at the first
+/// bad one: an offset has to be at or past the one before it, which also keeps it past the table,
+/// and within the list. The serial, parallel and lazy list decoders all go through this, so they
+/// agree on which offset is to blame.
+#[derive(Clone)]
+pub(crate) struct VariableItemRanges<'a> {
+    table: OffsetTable<'a>,
+    end_of_list: usize,
 }
 
-impl<T, N> TryFromIter<T> for VariableList<T, N>
-where
-    T: SszbDecode,
-    N: Unsigned,
-{
-    type Error = DecodeError;
-
-    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
-        let n = N::to_usize();
-        let iterator = iter.into_iter();
-
-        // Pre-allocate up to `N` elements based on the iterator size hint.
-        let (_, opt_max_len) = iterator.size_hint();
-        let mut l = Self::new(Vec::with_capacity(
-            opt_max_len.map_or(n, |max_len| std::cmp::min(n, max_len)),
-        ))
-        .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))?;
-        for item in iterator {
-            l.push(item).map_err(|e| {
-                DecodeError::BytesInvalid(format!("Error processing results: {:?}", e))
-            })?
+impl<'a> VariableItemRanges<'a> {
+    pub(crate) fn new(offsets: &'a [u8], end_of_list: usize) -> Self {
+        Self {
+            table: OffsetTable::new(offsets, end_of_list),
+            end_of_list,
         }
-        Ok(l)
     }
-}
-
-impl<T, N> TryFromIter<T> for PersistentVector<T, N>
-where
-    T: Value + SszbDecode,
-    N: Unsigned,
-{
-    type Error = MilhouseError;
 
-    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
-        PersistentVector::try_from_iter(iter)
+    /// Like `new`, with every offset in the table checked up front, so that nothing is sliced out
+    /// of the list, or decoded, before a bad offset anywhere in it is found. The error is the one
+    /// iterating would have stopped at.
+    pub(crate) fn checked(offsets: &'a [u8], end_of_list: usize) -> Result<Self, DecodeError> {
+        let ranges = Self::new(offsets, end_of_list);
+        ranges.clone().try_for_each(|range| range.map(drop))?;
+        Ok(ranges)
     }
 }
 
-impl<T, N> TryFromIter<T> for FixedVector<T, N>
-where
-    T: SszbDecode,
-    N: Unsigned,
-{
-    type Error = DecodeError;
-
-    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
-        let n = N::to_usize();
-        let iterator = iter.into_iter();
-
-        let (_, opt_max_len) = iterator.size_hint();
-        let mut vec =
-            Vec::with_capacity(opt_max_len.map_or(n, |max_len| std::cmp::min(n, max_len)));
-
-        for item in iterator {
-            // Bail out as soon as the length tries to exceed the limit. This guards against
-            // memory denial-of-service attacks.
-            if vec.len() >= n {
-                return Err(SszTypeError::OutOfBounds {
-                    i: vec.len(),
-                    len: n,
-                })
-                .map_err(|e| {
-                    DecodeError::BytesInvalid(format!("Error processing results: {:?}", e))
-                });
-            }
-            vec.push(item);
+impl Iterator for VariableItemRanges<'_> {
+    type Item = Result<Range<usize>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (range, end_at) = self.table.next()?;
+        // the start offset has already been checked, as the first one or the end of the range
+        // before, so it's within the list. Errors in an offset pair are reported at the end offset
+        let range = check_next_offset(range.end, range.start, self.end_of_list)
+            .map(|_| range)
+            .map_err(|e| e.at_byte(end_at));
+        // nothing after a bad offset can be located
+        if range.is_err() {
+            self.table = OffsetTable::new(&[], self.end_of_list);
         }
-        Self::new(vec)
-            .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
+        Some(range)
     }
 }
 
-pub fn ssz_decode_variable_length_items<T: SszbDecode, L: TryFromIter<T>>(
-    var_offsets: impl Buf,
-    var_items: &mut impl Buf,
-) -> Result<L, DecodeError> {
-    if !var_offsets.has_remaining() && !var_items.has_remaining() {
-        return L::try_from_iter(std::iter::empty()).map_err(|e| {
-            DecodeError::BytesInvalid(format!("Error trying to collect empty items: {:?}", e))
-        });
-    }
-
-    // h/t the grandine team for this iter def:
-    // https://github.com/grandinetech/grandine/blob/develop/ssz/src/shared.rs#L174
-    // This iterator splits offset sections into offset sized chunks (of length BYTES_PER_LENGTH_OFFSET),
-    // reads them (maps to Result<usize, Error>), slides over them in windows of size 2,
-    // and calculates the length between offsets before decoding an item of that length (the last map).
-    // The .chain call is so we don't forget an offset at the end since it stops iterating
-    // when the window hits the the last chunk.
-
-    process_results(
-        var_offsets
-            .chunk()
-            .chunks_exact(BYTES_PER_LENGTH_OFFSET)
-            .map(read_offset_from_slice)
-            .chain(core::iter::once(Ok(
-                var_offsets.remaining() + var_items.remaining()
-            )))
-            .tuple_windows()
-            .map(move |(start_result, end_result)| {
-                let start = start_result?;
-                let end = end_result?;
-                let len = end - start;
-                let bytes = &var_items.chunk()[..len];
-                let res = <T as SszbDecode>::from_ssz_bytes(bytes);
-                var_items.advance(len);
-                res
-            }),
-        |iter| L::try_from_iter(iter),
-    )?
-    .map_err(|e| DecodeError::BytesInvalid(format!("Error processing results: {:?}", e)))
+/// Decodes the body of a list or vector of variable-size values for `from_ssz_bytes_trusted`.
+///
+/// Only the length of the offset table is checked up front, every other offset is only checked
+/// as far as needed to slice out its item.
+pub fn ssz_decode_variable_length_items_trusted<T: SszbDecode>(
+    bytes: &[u8],
+) -> Result<Vec<T>, DecodeError> {
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // the first offset is the length of the offset table
+    let first_offset = read_offset(bytes)?;
+    let num_items = check_list_first_offset(first_offset, bytes.len())
+        .map_err(|_| DecodeError::InvalidListFixedBytesLen(first_offset))?;
+
+    let mut items = Vec::with_capacity(num_items);
+    for (range, _) in OffsetTable::new(&bytes[..first_offset], bytes.len()) {
+        let item = bytes
+            .get(range.clone())
+            .ok_or(DecodeError::OffsetOutOfBounds(range.end))?;
+        items.push(T::ssz_read_trusted(item).map_err(|e| e.at_byte(range.start))?);
+    }
+    Ok(items)
 }
@@ -0,0 +1,77 @@
+use crate::{
+    decode::decode_impls::VariableItemRanges,
+    raw::{peek_list_len_static, peek_list_len_variable, split_offset_table},
+    DecodeError, SszbDecode,
+};
+use core::{marker::PhantomData, slice::ChunksExact};
+
+/// Iterates over the elements of a serialized list of up to `max_len` `T`s, decoding each one
+/// as it's reached instead of building the collection.
+///
+/// The list's length is checked up front, as when decoding it: for static elements, that `bytes`
+/// is a whole number of them, and for variable ones, the first offset. The other offsets are
+/// only checked once their element is reached, and a bad one is yielded as that element's error,
+/// after which iteration stops. Errors carry the same positions as a full decode's.
+pub fn iter_ssz_list<T: SszbDecode>(
+    bytes: &[u8],
+    max_len: usize,
+) -> Result<SszListIter<'_, T>, DecodeError> {
+    let (num_items, items) = if T::SSZ_STATIC {
+        let num_items = peek_list_len_static(bytes, <T as SszbDecode>::SSZ_FIXED_LEN)?;
+        let chunks = bytes.chunks_exact(<T as SszbDecode>::SSZ_FIXED_LEN);
+        (num_items, Items::Static { chunks, start: 0 })
+    } else {
+        let num_items = peek_list_len_variable(bytes)?;
+        let (offsets, _) = split_offset_table(bytes, num_items)?;
+        let ranges = VariableItemRanges::new(offsets, bytes.len());
+        (num_items, Items::Variable { bytes, ranges })
+    };
+
+    if num_items > max_len {
+        return Err(DecodeError::ListTooLong {
+            len: num_items,
+            max: max_len,
+        });
+    }
+
+    Ok(SszListIter {
+        items,
+        _marker: PhantomData,
+    })
+}
+
+/// The iterator returned by `iter_ssz_list`, borrowing the serialized list.
+pub struct SszListIter<'a, T> {
+    items: Items<'a>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+enum Items<'a> {
+    Static {
+        chunks: ChunksExact<'a, u8>,
+        // where the next element starts
+        start: usize,
+    },
+    Variable {
+        bytes: &'a [u8],
+        ranges: VariableItemRanges<'a>,
+    },
+}
+
+impl<T: SszbDecode> Iterator for SszListIter<'_, T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.items {
+            Items::Static { chunks, start } => {
+                let chunk = chunks.next()?;
+                let at_byte = *start;
+                *start += chunk.len();
+                Some(T::from_ssz_bytes(chunk).map_err(|e| e.at_byte(at_byte)))
+            }
+            Items::Variable { bytes, ranges } => Some(ranges.next()?.and_then(|range| {
+                T::from_ssz_bytes(&bytes[range.clone()]).map_err(|e| e.at_byte(range.start))
+            })),
+        }
+    }
+}
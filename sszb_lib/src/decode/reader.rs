@@ -0,0 +1,328 @@
+use crate::{
+    decode::read_contiguous,
+    offsets::{check_first_offset, offset_span, read_offset},
+    DecodeError, SszbDecode, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use bytes::{buf::Buf, Bytes};
+
+/// A cursor over the bytes of one serialized value, for `SszbDecode::ssz_read_from`.
+///
+/// The bytes are split into a fixed section, holding static values and the offsets of variable
+/// ones, and the variable section after it. Values are read from the front of the fixed section
+/// in order with `read_fixed` and `read_offset`, and then the variable values in the order of
+/// their offsets with `read_variable`. The reader checks every offset the way the derived
+/// decoders always have, and wraps errors in the position of whatever they were raised in, so
+/// that impls built on it don't keep track of either. `finish` then checks that nothing was
+/// left over.
+///
+/// Positions in errors are relative to the start of the reader, and shifted as they travel up
+/// like any other. `base` is the reader's own position within the outermost input, for hooks and
+/// diagnostics that want to know where they are.
+///
+/// A reader made by `shared` also holds the refcounted input its bytes were cut from, and passes
+/// it on to the readers of the values inside it. Types that keep their bytes as `Bytes`, such as
+/// `Bytes` and `ByteList`, take views of the input from `to_bytes` instead of copies, and every
+/// other type decodes just like it does from a plain slice.
+#[derive(Debug)]
+pub struct SszReader<'a> {
+    bytes: &'a [u8],
+    base: usize,
+    // the whole input of `from_ssz_bytes_shared`, which `bytes` is a part of
+    shared: Option<&'a Bytes>,
+    // where the variable section starts
+    fixed_len: usize,
+    // the next byte of the fixed section to read
+    fixed: usize,
+    // the offsets read so far, each with where it was read, to find where variable values end
+    offsets: Vec<(usize, usize)>,
+    // the offset of the next variable value to read
+    next_offset: usize,
+    // the next byte of the variable section to read
+    variable: usize,
+}
+
+impl<'a> SszReader<'a> {
+    /// A reader over `bytes`, which are all taken to be the fixed section until
+    /// `with_fixed_len` says otherwise, as they are for a static value.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        SszReader {
+            bytes,
+            base: 0,
+            shared: None,
+            fixed_len: bytes.len(),
+            fixed: 0,
+            offsets: Vec::new(),
+            next_offset: 0,
+            variable: bytes.len(),
+        }
+    }
+
+    /// A reader over all of `input`, whose values can share its allocation through `to_bytes`.
+    pub fn shared(input: &'a Bytes) -> Self {
+        SszReader {
+            shared: Some(input),
+            ..SszReader::new(input)
+        }
+    }
+
+    // a reader over some of this reader's bytes, starting at_byte into them
+    fn nested(&self, bytes: &'a [u8], at_byte: usize) -> SszReader<'a> {
+        SszReader {
+            shared: self.shared,
+            ..SszReader::new(bytes).with_base(self.base + at_byte)
+        }
+    }
+
+    /// Sets the position of the reader's first byte within the outermost input.
+    pub fn with_base(mut self, base: usize) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Ends the fixed section after `fixed_len` bytes, which fails if there aren't that many.
+    /// Meant to be called before anything is read.
+    pub fn with_fixed_len(mut self, fixed_len: usize) -> Result<Self, DecodeError> {
+        debug_assert_eq!(
+            self.fixed, 0,
+            "the fixed section is set before reading from it"
+        );
+        let len = self.bytes.len();
+        if fixed_len > len {
+            return Err(DecodeError::InvalidByteLength {
+                len,
+                expected: fixed_len,
+            });
+        }
+        self.fixed_len = fixed_len;
+        self.variable = fixed_len;
+        Ok(self)
+    }
+
+    /// All of the value's bytes, however much of them has been read.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// All of the value's bytes as `Bytes`: a view sharing the input's allocation if the reader
+    /// came from `shared`, and a copy otherwise.
+    pub fn to_bytes(&self) -> Bytes {
+        match self.shared {
+            Some(input) => input.slice_ref(self.bytes),
+            None => Bytes::copy_from_slice(self.bytes),
+        }
+    }
+
+    /// Whether `to_bytes` shares the input's allocation rather than copying.
+    pub fn is_shared(&self) -> bool {
+        self.shared.is_some()
+    }
+
+    /// The position of the reader's first byte within the outermost input.
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// The position of the next byte of the fixed section, relative to the reader.
+    pub fn fixed_position(&self) -> usize {
+        self.fixed
+    }
+
+    /// The position of the next byte of the variable section, relative to the reader.
+    pub fn variable_position(&self) -> usize {
+        self.variable
+    }
+
+    /// Takes the next `len` bytes of the fixed section.
+    pub fn read_fixed_bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let available = self.fixed_len - self.fixed;
+        if len > available {
+            return Err(DecodeError::InvalidByteLength {
+                len: available,
+                expected: len,
+            }
+            .at_byte(self.fixed));
+        }
+        let bytes = &self.bytes[self.fixed..(self.fixed + len)];
+        self.fixed += len;
+        Ok(bytes)
+    }
+
+    /// Decodes a static `T` from the front of the fixed section.
+    ///
+    /// # Panics
+    ///
+    /// If `T` is variable, whose values are found through their offsets instead.
+    pub fn read_fixed<T: SszbDecode>(&mut self) -> Result<T, DecodeError> {
+        let (at_byte, bytes) = self.next_fixed::<T>()?;
+        T::ssz_read_from(self.nested(bytes, at_byte)).map_err(|e| e.at_byte(at_byte))
+    }
+
+    /// Like `read_fixed`, but decodes the value's bytes with `read`, such as
+    /// `SszbDecodeParallel::ssz_read_parallel`, rather than with `T::ssz_read_from`.
+    pub fn read_fixed_with<T: SszbDecode>(
+        &mut self,
+        read: impl FnOnce(&'a [u8]) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        let (at_byte, bytes) = self.next_fixed::<T>()?;
+        read(bytes).map_err(|e| e.at_byte(at_byte))
+    }
+
+    // the bytes of the static T at the front of the fixed section, and where they start
+    fn next_fixed<T: SszbDecode>(&mut self) -> Result<(usize, &'a [u8]), DecodeError> {
+        assert!(
+            T::SSZ_STATIC,
+            "variable values are read with read_offset and read_variable"
+        );
+        let at_byte = self.fixed;
+        let bytes = self.read_fixed_bytes(T::SSZ_FIXED_LEN)?;
+        Ok((at_byte, bytes))
+    }
+
+    /// Reads the offset of the next variable value from the front of the fixed section.
+    ///
+    /// The first offset has to point right at the end of the fixed section. The ones after it
+    /// are checked by `read_variable`, against the offset before them, when the value they end
+    /// is read, and a bad one is blamed for that value's length.
+    pub fn read_offset(&mut self) -> Result<usize, DecodeError> {
+        let at_byte = self.fixed;
+        let offset =
+            read_offset(&self.bytes[self.fixed..self.fixed_len]).map_err(|e| e.at_byte(at_byte))?;
+        if self.offsets.is_empty() {
+            check_first_offset(offset, self.fixed_len, self.bytes.len())
+                .map_err(|e| e.at_byte(at_byte))?;
+        }
+        self.fixed += BYTES_PER_LENGTH_OFFSET;
+        self.offsets.push((offset, at_byte));
+        Ok(offset)
+    }
+
+    /// Takes the next `len` bytes of the variable section.
+    pub fn take_variable(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let available = self.bytes.len() - self.variable;
+        if len > available {
+            return Err(DecodeError::InvalidByteLength {
+                len: available,
+                expected: len,
+            }
+            .at_byte(self.variable));
+        }
+        let bytes = &self.bytes[self.variable..(self.variable + len)];
+        self.variable += len;
+        Ok(bytes)
+    }
+
+    /// Decodes the variable `T` whose offset is next, which spans up to the offset after it,
+    /// or the end of the bytes for the last one.
+    ///
+    /// # Panics
+    ///
+    /// If the fixed section hasn't been read yet, which leaves the end of the value unknown,
+    /// or there's no offset left for the value.
+    pub fn read_variable<T: SszbDecode>(&mut self) -> Result<T, DecodeError> {
+        let (begin, bytes) = self.next_variable()?;
+        T::ssz_read_from(self.nested(bytes, begin)).map_err(|e| e.at_byte(begin))
+    }
+
+    /// Like `read_variable`, but decodes the value's bytes with `read`, such as
+    /// `SszbDecodeParallel::ssz_read_parallel`, rather than with `T::ssz_read_from`.
+    pub fn read_variable_with<T: SszbDecode>(
+        &mut self,
+        read: impl FnOnce(&'a [u8]) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        let (begin, bytes) = self.next_variable()?;
+        read(bytes).map_err(|e| e.at_byte(begin))
+    }
+
+    /// The bytes of the variable value whose offset is next, without reading them, such as to
+    /// check the length of a list before decoding it. Offsets are checked as by `read_variable`.
+    ///
+    /// # Panics
+    ///
+    /// Like `read_variable`.
+    pub fn peek_variable(&self) -> Result<&'a [u8], DecodeError> {
+        let (begin, len) = self.variable_span()?;
+        Ok(&self.bytes[begin..(begin + len)])
+    }
+
+    // the bytes of the variable value whose offset is next, and where they start
+    fn next_variable(&mut self) -> Result<(usize, &'a [u8]), DecodeError> {
+        let (begin, len) = self.variable_span()?;
+        let bytes = self.take_variable(len)?;
+        self.next_offset += 1;
+        Ok((begin, bytes))
+    }
+
+    // where the variable value whose offset is next starts, and its length
+    fn variable_span(&self) -> Result<(usize, usize), DecodeError> {
+        assert_eq!(
+            self.fixed, self.fixed_len,
+            "the fixed section has to be read before the variable one"
+        );
+        let (_, begin_at) = *self
+            .offsets
+            .get(self.next_offset)
+            .expect("every variable value has an offset");
+        let (end, end_at) = match self.offsets.get(self.next_offset + 1) {
+            Some(&(end, end_at)) => (end, end_at),
+            None => (self.bytes.len(), begin_at),
+        };
+
+        // the offsets are checked in order, and each one against where the value before it
+        // starts. Bad lengths are blamed on the offset that ends the value
+        let begin = self.variable;
+        let len = offset_span(begin, end).map_err(|e| e.at_byte(end_at))?;
+        let available = self.bytes.len() - begin;
+        if len > available {
+            return Err(DecodeError::InvalidByteLength {
+                len,
+                expected: available,
+            }
+            .at_byte(end_at));
+        }
+        Ok((begin, len))
+    }
+
+    /// Checks that every byte was read, the fixed section first.
+    pub fn finish(self) -> Result<(), DecodeError> {
+        let read = if self.fixed < self.fixed_len {
+            self.fixed
+        } else {
+            self.variable
+        };
+        if read != self.bytes.len() {
+            return Err(DecodeError::InvalidByteLength {
+                len: self.bytes.len(),
+                expected: read,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Implements the two-buffer `ssz_read` of a type on top of its `ssz_read_from`, which is all
+/// that's left of `ssz_read` in the derived containers: a static value is read from the front of
+/// `fixed_bytes`, and a variable one spans all of `variable_bytes`.
+#[doc(hidden)]
+pub fn read_through_reader<T: SszbDecode>(
+    fixed_bytes: &mut impl Buf,
+    variable_bytes: &mut impl Buf,
+) -> Result<T, DecodeError> {
+    if T::SSZ_STATIC {
+        let len = T::SSZ_FIXED_LEN;
+        if fixed_bytes.remaining() < len {
+            return Err(DecodeError::InvalidByteLength {
+                len: fixed_bytes.remaining(),
+                expected: len,
+            });
+        }
+        read_contiguous(fixed_bytes, len, |bytes| {
+            T::ssz_read_from(SszReader::new(bytes))
+        })
+    } else {
+        let len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, len, |bytes| {
+            T::ssz_read_from(SszReader::new(bytes))
+        })
+    }
+}
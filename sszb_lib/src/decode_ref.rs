@@ -0,0 +1,352 @@
+//! Decoding into values that borrow from the input instead of copying out of it.
+//!
+//! `SszbDecodeRef<'a>` mirrors `SszbDecode`, but decodes from a `&'a [u8]` that the result may
+//! point into. It is implemented for `&'a [u8]` (a byte list), `&'a [u8; N]`, every `SszbDecode`
+//! type (which copies as usual) and containers with `#[derive(SszbDecodeRef)]`:
+//!
+//! ```ignore
+//! #[derive(SszbDecodeRef)]
+//! struct Envelope<'a> {
+//!     slot: u64,
+//!     signature: &'a [u8; 96],
+//!     extra_data: &'a [u8],
+//! }
+//! ```
+//!
+//! Offsets and lengths are validated exactly as `SszbDecode` does, with the same errors.
+//!
+//! The same field layout also drives `container_field_range`, which the derived
+//! `field_range` and `decode_field_*` functions use to find a single field without decoding
+//! (or validating) the rest of the container.
+use crate::{
+    offsets::{
+        check_first_offset, check_next_offset, check_offset_past_fixed, offset_span, read_offset,
+    },
+    DecodeError, SszbDecode,
+};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+pub trait SszbDecodeRef<'a>: Sized {
+    const SSZ_STATIC: bool;
+    const SSZ_FIXED_LEN: usize;
+
+    fn from_ssz_bytes_ref(bytes: &'a [u8]) -> Result<Self, DecodeError>;
+}
+
+impl<'a, T: SszbDecode> SszbDecodeRef<'a> for T {
+    const SSZ_STATIC: bool = <T as SszbDecode>::SSZ_STATIC;
+    const SSZ_FIXED_LEN: usize = <T as SszbDecode>::SSZ_FIXED_LEN;
+
+    fn from_ssz_bytes_ref(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        T::from_ssz_bytes(bytes)
+    }
+}
+
+// a list of bytes without a maximum length
+impl<'a> SszbDecodeRef<'a> for &'a [u8] {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = crate::BYTES_PER_LENGTH_OFFSET;
+
+    fn from_ssz_bytes_ref(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        Ok(bytes)
+    }
+}
+
+impl<'a, const N: usize> SszbDecodeRef<'a> for &'a [u8; N] {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+
+    fn from_ssz_bytes_ref(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        if N == 0 {
+            return Err(DecodeError::ZeroLengthVector);
+        }
+        bytes
+            .try_into()
+            .map_err(|_| DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: N,
+            })
+    }
+}
+
+/// Computes the byte range of every field of a serialized container, given each field's
+/// `(SSZ_STATIC, SSZ_FIXED_LEN)` in order.
+///
+/// The offsets are checked exactly like the derived `SszbDecode` checks them, and errors carry
+/// the position of the offending offset.
+pub fn container_field_ranges(
+    bytes: &[u8],
+    fields: &[(bool, usize)],
+) -> Result<Vec<Range<usize>>, DecodeError> {
+    let mut ranges = Vec::with_capacity(fields.len());
+    visit_container_fields(bytes, fields, |_, range| {
+        ranges.push(range);
+        Ok(())
+    })?;
+    Ok(ranges)
+}
+
+/// Like `container_field_ranges`, but hands each field's index and byte range to `visit` as soon
+/// as it's been checked, in order, instead of collecting them. Nothing is allocated, which is what
+/// the derived `validate_ssz_bytes` needs. The first error, from the offsets or from `visit`,
+/// stops the walk.
+pub fn visit_container_fields(
+    bytes: &[u8],
+    fields: &[(bool, usize)],
+    mut visit: impl FnMut(usize, Range<usize>) -> Result<(), DecodeError>,
+) -> Result<(), DecodeError> {
+    let fixed_len = fixed_portion_len(bytes, fields)?;
+
+    let mut position = 0;
+    let mut variable_end = fixed_len;
+    for (index, &(is_static, field_len)) in fields.iter().enumerate() {
+        if is_static {
+            visit(index, position..(position + field_len))?;
+        } else {
+            let begin_at = position;
+            let begin = read_offset(&bytes[begin_at..]).map_err(|e| e.at_byte(begin_at))?;
+            // the offset has to point right where the variable data read so far ends
+            check_first_offset(begin, variable_end, bytes.len())
+                .map_err(|e| e.at_byte(begin_at))?;
+
+            // the field ends where the next variable one begins, or at the end of the input
+            let (end_at, end) = match next_variable_field_at(fields, index, begin_at) {
+                Some(end_at) => (end_at, read_offset(&bytes[end_at..])?),
+                None => (begin_at, bytes.len()),
+            };
+            let len = offset_span(begin, end).map_err(|e| e.at_byte(end_at))?;
+            if len > bytes.len() - variable_end {
+                return Err(DecodeError::InvalidByteLength {
+                    len,
+                    expected: bytes.len() - variable_end,
+                }
+                .at_byte(end_at));
+            }
+
+            visit(index, begin..end)?;
+            variable_end = end;
+        }
+        position += field_len;
+    }
+
+    Ok(())
+}
+
+// where the offset of the first variable field after fields[index], which starts at position,
+// is stored
+fn next_variable_field_at(
+    fields: &[(bool, usize)],
+    index: usize,
+    position: usize,
+) -> Option<usize> {
+    fields[index..]
+        .iter()
+        .scan(position, |position, &(is_static, len)| {
+            let at = *position;
+            *position += len;
+            Some((at, is_static))
+        })
+        .skip(1)
+        .find(|(_, is_static)| !is_static)
+        .map(|(at, _)| at)
+}
+
+/// Computes the byte range of every field like `container_field_ranges`, for a container that a
+/// newer schema may have extended by appending fields after the given ones. The appended fields
+/// are ignored, but the offsets of the given ones are still checked.
+///
+/// The first offset may point past the given fixed fields, over whatever fixed parts were
+/// appended. The last given variable field ends where the first appended variable field begins,
+/// read from the four bytes right after the given fixed fields when they hold an offset that
+/// makes sense there, and otherwise at the end of the input. An appended static field can look
+/// like such an offset, so this is meant for inspecting data, not for anything that has to be
+/// exact.
+pub fn container_field_ranges_lenient(
+    bytes: &[u8],
+    fields: &[(bool, usize)],
+) -> Result<Vec<Range<usize>>, DecodeError> {
+    let known_fixed_len = fields
+        .iter()
+        .try_fold(0usize, |len, (_, field_len)| len.checked_add(*field_len))
+        .expect("decode ssz_fixed_len length overflow");
+    if known_fixed_len > bytes.len() {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: known_fixed_len,
+        });
+    }
+
+    // the variable data starts wherever the first offset says, as long as it doesn't overlap the
+    // known fixed fields
+    let mut offset_positions = Vec::new();
+    let mut position = 0;
+    for &(is_static, field_len) in fields {
+        if !is_static {
+            offset_positions.push(position);
+        }
+        position += field_len;
+    }
+    let fixed_len = match offset_positions.first() {
+        Some(&first_at) => {
+            let first = read_offset(&bytes[first_at..]).map_err(|e| e.at_byte(first_at))?;
+            // appended fields may make the fixed portion longer than the known one
+            check_offset_past_fixed(first, known_fixed_len, bytes.len())
+                .map_err(|e| e.at_byte(first_at))?
+        }
+        None => known_fixed_len,
+    };
+
+    let mut ranges = Vec::with_capacity(fields.len());
+    let mut position = 0;
+    let mut variable_end = fixed_len;
+    let mut offset_positions = offset_positions.iter().skip(1);
+    for &(is_static, field_len) in fields {
+        if is_static {
+            ranges.push(position..(position + field_len));
+        } else {
+            let begin_at = position;
+            let begin = read_offset(&bytes[begin_at..]).map_err(|e| e.at_byte(begin_at))?;
+            check_first_offset(begin, variable_end, bytes.len())
+                .map_err(|e| e.at_byte(begin_at))?;
+
+            let (end_at, end) = match offset_positions.next() {
+                Some(&end_at) => (end_at, read_offset(&bytes[end_at..])?),
+                None => (
+                    begin_at,
+                    appended_variable_start(bytes, known_fixed_len, fixed_len, begin),
+                ),
+            };
+            let len = offset_span(begin, end).map_err(|e| e.at_byte(end_at))?;
+            if len > bytes.len() - variable_end {
+                return Err(DecodeError::InvalidByteLength {
+                    len,
+                    expected: bytes.len() - variable_end,
+                }
+                .at_byte(end_at));
+            }
+
+            ranges.push(begin..end);
+            variable_end = end;
+        }
+        position += field_len;
+    }
+
+    Ok(ranges)
+}
+
+// where the variable data of appended fields starts, if the appended fixed parts begin with an
+// offset between the start of the last known variable field and the end of the input
+fn appended_variable_start(
+    bytes: &[u8],
+    known_fixed_len: usize,
+    fixed_len: usize,
+    begin: usize,
+) -> usize {
+    if fixed_len < known_fixed_len + crate::BYTES_PER_LENGTH_OFFSET {
+        return bytes.len();
+    }
+    match read_offset(&bytes[known_fixed_len..]) {
+        Ok(offset) if (begin..=bytes.len()).contains(&offset) => offset,
+        _ => bytes.len(),
+    }
+}
+
+/// Computes the byte range of the field with the given index (counting only the fields that are
+/// serialized), given each field's `(SSZ_STATIC, SSZ_FIXED_LEN)` in order.
+///
+/// Only what's needed to locate the field is checked: the length of the fixed portion and, for
+/// a variable field, the offsets it starts and ends at. Errors match `container_field_ranges`.
+pub fn container_field_range(
+    bytes: &[u8],
+    fields: &[(bool, usize)],
+    index: usize,
+) -> Result<Range<usize>, DecodeError> {
+    let (is_static, field_len) = *fields.get(index).ok_or(DecodeError::FieldIndexOutOfRange {
+        index,
+        num_fields: fields.len(),
+    })?;
+    let fixed_len = fixed_portion_len(bytes, fields)?;
+
+    let begin_at: usize = fields[..index].iter().map(|(_, len)| len).sum();
+    if is_static {
+        return Ok(begin_at..(begin_at + field_len));
+    }
+
+    let begin = read_offset(&bytes[begin_at..]).map_err(|e| e.at_byte(begin_at))?;
+    // the first variable field has to start right after the fixed portion, the others only have
+    // to start somewhere after it, as checking them against the previous field means reading it
+    if fields[..index].iter().all(|(is_static, _)| *is_static) {
+        check_first_offset(begin, fixed_len, bytes.len())
+    } else {
+        check_offset_past_fixed(begin, fixed_len, bytes.len())
+    }
+    .map_err(|e| e.at_byte(begin_at))?;
+
+    // the field ends where the next variable one begins, or at the end of the input
+    let (end_at, end) = match next_variable_field_at(fields, index, begin_at) {
+        Some(end_at) => (end_at, read_offset(&bytes[end_at..])?),
+        None => (begin_at, bytes.len()),
+    };
+    let len = offset_span(begin, end).map_err(|e| e.at_byte(end_at))?;
+    if len > bytes.len() - begin {
+        return Err(DecodeError::InvalidByteLength {
+            len,
+            expected: bytes.len() - begin,
+        }
+        .at_byte(end_at));
+    }
+
+    Ok(begin..end)
+}
+
+/// Computes the byte range of a field like `container_field_range`, but for
+/// `from_ssz_bytes_trusted`: offsets are only checked as far as needed to slice the input
+/// without panicking.
+pub fn container_field_range_trusted(
+    bytes: &[u8],
+    fields: &[(bool, usize)],
+    index: usize,
+) -> Result<Range<usize>, DecodeError> {
+    let fixed_len = fixed_portion_len(bytes, fields)?;
+    let begin_at: usize = fields[..index].iter().map(|(_, len)| len).sum();
+    let (is_static, field_len) = fields[index];
+    if is_static {
+        return Ok(begin_at..(begin_at + field_len));
+    }
+
+    let begin = read_offset(&bytes[begin_at..])?;
+    // the field ends where the next variable one begins, or at the end of the input
+    let mut end = bytes.len();
+    let mut position = begin_at + field_len;
+    for &(is_static, len) in &fields[(index + 1)..] {
+        if !is_static {
+            end = read_offset(&bytes[position..])?;
+            break;
+        }
+        position += len;
+    }
+
+    // any bad offset is only blamed on the one the field starts at
+    check_offset_past_fixed(begin, fixed_len, end)
+        .and_then(|_| check_next_offset(end, begin, bytes.len()))
+        .map_err(|_| DecodeError::OffsetOutOfBounds(begin))?;
+    Ok(begin..end)
+}
+
+// checks that `bytes` holds at least the fixed portion of the container (exactly, if it's
+// static) and returns the fixed portion's length
+fn fixed_portion_len(bytes: &[u8], fields: &[(bool, usize)]) -> Result<usize, DecodeError> {
+    let fixed_len = fields
+        .iter()
+        .try_fold(0usize, |len, (_, field_len)| len.checked_add(*field_len))
+        .expect("decode ssz_fixed_len length overflow");
+    let is_static = fields.iter().all(|(is_static, _)| *is_static);
+    if fixed_len > bytes.len() || (is_static && fixed_len != bytes.len()) {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: fixed_len,
+        });
+    }
+    Ok(fixed_len)
+}
@@ -0,0 +1,584 @@
+//! Decoding and encoding SSZ without the Rust types, for tooling that only learns the layout of
+//! the bytes it inspects at runtime.
+//!
+//! The layout is an `SszTypeInfo`, either built by hand (e.g. from a JSON or YAML description)
+//! or taken from a type with `SszbTypeInfo`, which `#[derive(SszbTypeInfo)]` implements for
+//! containers and unions:
+//!
+//! ```ignore
+//! #[derive(SszbEncode, SszbDecode, SszbTypeInfo)]
+//! struct Checkpoint {
+//!     epoch: u64,
+//!     root: [u8; 32],
+//! }
+//!
+//! let schema = Checkpoint::ssz_type_info();
+//! let value = decode_dynamic(&schema, &bytes)?;
+//! assert_eq!(value.field("epoch"), Some(&SszValue::U64(7)));
+//!
+//! let mut reencoded = vec![];
+//! encode_dynamic(&schema, &value, &mut reencoded)?;
+//! assert_eq!(reencoded, bytes);
+//! ```
+//!
+//! Offsets and lengths are checked by the same functions the typed decoders use, so
+//! `decode_dynamic` accepts exactly the bytes that decoding the matching type accepts, and fails
+//! with the same errors at the same positions.
+//!
+//! A recursive type has no finite `SszTypeInfo`, so `SszbTypeInfo` isn't implemented for `Box`
+//! and `Arc`, and deriving it for a container that contains itself doesn't compile.
+
+use crate::decode::decode_impls::{
+    bitlist_len, validate_bitvector_bits, visit_list_items, visit_vector_items,
+};
+use crate::{
+    bitfield_bytes_len, expect_offset, visit_container_fields, write_offset, DecodeError,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use bytes::buf::BufMut;
+
+/// The layout of an SSZ type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SszTypeInfo {
+    /// An unsigned integer of the given number of bytes.
+    Uint(usize),
+    Bool,
+    /// A vector of exactly `len` items.
+    Vector {
+        item: Box<SszTypeInfo>,
+        len: usize,
+    },
+    /// A list of at most `max_len` items.
+    List {
+        item: Box<SszTypeInfo>,
+        max_len: usize,
+    },
+    /// A bit vector of the given number of bits.
+    Bitvector(usize),
+    /// A bit list of at most the given number of bits.
+    Bitlist(usize),
+    /// A container's fields, by name and in order.
+    Container(Vec<(String, SszTypeInfo)>),
+    /// A union's variants, by selector. Only the first may be `None`, for a variant without a
+    /// value.
+    Union(Vec<Option<SszTypeInfo>>),
+}
+
+impl SszTypeInfo {
+    /// A vector of `len` items of type `item`.
+    pub fn vector(item: SszTypeInfo, len: usize) -> Self {
+        SszTypeInfo::Vector {
+            item: Box::new(item),
+            len,
+        }
+    }
+
+    /// A list of at most `max_len` items of type `item`.
+    pub fn list(item: SszTypeInfo, max_len: usize) -> Self {
+        SszTypeInfo::List {
+            item: Box::new(item),
+            max_len,
+        }
+    }
+
+    /// Whether every value of the type has the same length, its `SSZ_STATIC`.
+    pub fn is_static(&self) -> bool {
+        match self {
+            SszTypeInfo::Uint(_) | SszTypeInfo::Bool | SszTypeInfo::Bitvector(_) => true,
+            SszTypeInfo::Vector { item, .. } => item.is_static(),
+            SszTypeInfo::Container(fields) => fields.iter().all(|(_, field)| field.is_static()),
+            SszTypeInfo::List { .. } | SszTypeInfo::Bitlist(_) | SszTypeInfo::Union(_) => false,
+        }
+    }
+
+    /// The length of the type in the fixed portion of a container, its `SSZ_FIXED_LEN`.
+    pub fn fixed_len(&self) -> usize {
+        if !self.is_static() {
+            return BYTES_PER_LENGTH_OFFSET;
+        }
+        match self {
+            SszTypeInfo::Uint(len) => *len,
+            SszTypeInfo::Bool => 1,
+            SszTypeInfo::Bitvector(bits) => bitfield_bytes_len(*bits),
+            SszTypeInfo::Vector { item, len } => item.fixed_len() * len,
+            SszTypeInfo::Container(fields) => {
+                fields.iter().map(|(_, field)| field.fixed_len()).sum()
+            }
+            SszTypeInfo::List { .. } | SszTypeInfo::Bitlist(_) | SszTypeInfo::Union(_) => {
+                unreachable!("variable-size types are offsets in the fixed portion")
+            }
+        }
+    }
+
+    // the item of a vector or list of bytes, which are decoded as `SszValue::Bytes`
+    fn is_byte(&self) -> bool {
+        *self == SszTypeInfo::Uint(1)
+    }
+
+    // the (SSZ_STATIC, SSZ_FIXED_LEN) pair the layout helpers take
+    fn layout(&self) -> (bool, usize) {
+        (self.is_static(), self.fixed_len())
+    }
+}
+
+/// Types that can describe their own SSZ layout.
+pub trait SszbTypeInfo {
+    fn ssz_type_info() -> SszTypeInfo;
+}
+
+macro_rules! uint_type_info {
+    ($($type: ident),+) => {
+        $(
+            impl SszbTypeInfo for $type {
+                fn ssz_type_info() -> SszTypeInfo {
+                    SszTypeInfo::Uint(core::mem::size_of::<$type>())
+                }
+            }
+        )+
+    };
+}
+
+uint_type_info!(u8, u16, u32, u64, u128);
+
+impl SszbTypeInfo for bool {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::Bool
+    }
+}
+
+impl<const N: usize> SszbTypeInfo for [u8; N] {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), N)
+    }
+}
+
+macro_rules! union_type_info {
+    ($name: ident { $($variant: ident),+ }) => {
+        impl<$($variant: SszbTypeInfo),+> SszbTypeInfo for crate::$name<$($variant),+> {
+            fn ssz_type_info() -> SszTypeInfo {
+                SszTypeInfo::Union(vec![$(Some($variant::ssz_type_info())),+])
+            }
+        }
+    };
+}
+
+union_type_info!(Union2 { A, B });
+union_type_info!(Union3 { A, B, C });
+union_type_info!(Union4 { A, B, C, D });
+
+/// A value of any SSZ type, as `decode_dynamic` builds it from its `SszTypeInfo`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SszValue {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    /// An integer of any other width, such as a uint256, as its little-endian bytes.
+    Uint(Vec<u8>),
+    Bool(bool),
+    /// A vector or list of bytes.
+    Bytes(Vec<u8>),
+    Vector(Vec<SszValue>),
+    List(Vec<SszValue>),
+    Bitvector(Vec<bool>),
+    Bitlist(Vec<bool>),
+    /// A container's fields, by name and in order.
+    Container(Vec<(String, SszValue)>),
+    /// The selector of a union's variant, and its value unless the variant has none.
+    Union {
+        selector: u8,
+        value: Option<Box<SszValue>>,
+    },
+}
+
+impl SszValue {
+    /// The value of the container's field with the given name, if this is a container that has
+    /// one.
+    pub fn field(&self, name: &str) -> Option<&SszValue> {
+        match self {
+            SszValue::Container(fields) => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes `bytes` as a value of the type `schema` describes.
+pub fn decode_dynamic(schema: &SszTypeInfo, bytes: &[u8]) -> Result<SszValue, DecodeError> {
+    if schema.is_static() && bytes.len() != schema.fixed_len() {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: schema.fixed_len(),
+        });
+    }
+
+    match schema {
+        SszTypeInfo::Uint(len) => Ok(decode_uint(*len, bytes)),
+        SszTypeInfo::Bool => match bytes[0] {
+            0 => Ok(SszValue::Bool(false)),
+            1 => Ok(SszValue::Bool(true)),
+            byte => Err(DecodeError::InvalidBool(byte)),
+        },
+        SszTypeInfo::Vector { item, len } => {
+            // visiting rejects vectors of no items, byte vectors included
+            if item.is_byte() && *len > 0 {
+                return Ok(SszValue::Bytes(bytes.to_vec()));
+            }
+            let mut items = Vec::with_capacity(*len);
+            visit_vector_items(bytes, item.layout(), *len, |range| {
+                items.push(decode_at(item, bytes, range)?);
+                Ok(())
+            })?;
+            Ok(SszValue::Vector(items))
+        }
+        SszTypeInfo::List { item, max_len } => {
+            if item.is_byte() {
+                if bytes.len() > *max_len {
+                    return Err(DecodeError::ListTooLong {
+                        len: bytes.len(),
+                        max: *max_len,
+                    });
+                }
+                return Ok(SszValue::Bytes(bytes.to_vec()));
+            }
+            let mut items = vec![];
+            visit_list_items(bytes, item.layout(), *max_len, |range| {
+                items.push(decode_at(item, bytes, range)?);
+                Ok(())
+            })?;
+            Ok(SszValue::List(items))
+        }
+        SszTypeInfo::Bitvector(len) => {
+            validate_bitvector_bits(bytes, *len)?;
+            Ok(SszValue::Bitvector(bits(bytes, *len)))
+        }
+        SszTypeInfo::Bitlist(max_len) => {
+            let len = bitlist_len(bytes, *max_len)?;
+            Ok(SszValue::Bitlist(bits(bytes, len)))
+        }
+        SszTypeInfo::Container(fields) => {
+            let layout = fields
+                .iter()
+                .map(|(_, field)| field.layout())
+                .collect::<Vec<_>>();
+            let mut values = Vec::with_capacity(fields.len());
+            visit_container_fields(bytes, &layout, |index, range| {
+                let (name, field) = &fields[index];
+                values.push((name.clone(), decode_at(field, bytes, range)?));
+                Ok(())
+            })?;
+            Ok(SszValue::Container(values))
+        }
+        SszTypeInfo::Union(variants) => {
+            let (&selector, body) = bytes.split_first().ok_or(DecodeError::InvalidByteLength {
+                len: 0,
+                expected: 1,
+            })?;
+            let value = match variants.get(usize::from(selector)) {
+                Some(Some(variant)) => Some(Box::new(
+                    decode_dynamic(variant, body).map_err(|e| e.at_byte(1))?,
+                )),
+                Some(None) if body.is_empty() => None,
+                Some(None) => {
+                    return Err(DecodeError::InvalidByteLength {
+                        len: body.len(),
+                        expected: 0,
+                    }
+                    .at_byte(1))
+                }
+                None => return Err(DecodeError::UnionSelectorInvalid(selector)),
+            };
+            Ok(SszValue::Union { selector, value })
+        }
+    }
+}
+
+// decodes the item or field at `range` of `bytes`, with errors relative to `bytes`
+fn decode_at(
+    schema: &SszTypeInfo,
+    bytes: &[u8],
+    range: core::ops::Range<usize>,
+) -> Result<SszValue, DecodeError> {
+    decode_dynamic(schema, &bytes[range.clone()]).map_err(|e| e.at_byte(range.start))
+}
+
+fn decode_uint(len: usize, bytes: &[u8]) -> SszValue {
+    let mut le = [0; 16];
+    if len <= 16 {
+        le[..len].copy_from_slice(bytes);
+    }
+    match len {
+        1 => SszValue::U8(bytes[0]),
+        2 => SszValue::U16(u16::from_le_bytes([le[0], le[1]])),
+        4 => SszValue::U32(u32::from_le_bytes([le[0], le[1], le[2], le[3]])),
+        8 => SszValue::U64(u64::from_le_bytes(le[..8].try_into().unwrap())),
+        16 => SszValue::U128(u128::from_le_bytes(le)),
+        _ => SszValue::Uint(bytes.to_vec()),
+    }
+}
+
+// the first `len` bits of `bytes`, least significant bit first
+fn bits(bytes: &[u8], len: usize) -> Vec<bool> {
+    (0..len)
+        .map(|i| bytes[i / 8] & (1 << (i % 8)) != 0)
+        .collect()
+}
+
+/// A value that doesn't have the type of the schema it was encoded with.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SchemaMismatch {
+    /// What the schema expected where the value didn't fit, e.g. "a list of at most 16 items".
+    pub expected: String,
+}
+
+impl core::fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "the value doesn't fit its schema, expected {}",
+            self.expected
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchemaMismatch {}
+
+fn mismatch(expected: impl Into<String>) -> SchemaMismatch {
+    SchemaMismatch {
+        expected: expected.into(),
+    }
+}
+
+/// Encodes `value` as the type `schema` describes, after checking that it has that type.
+pub fn encode_dynamic(
+    schema: &SszTypeInfo,
+    value: &SszValue,
+    buf: &mut impl BufMut,
+) -> Result<(), SchemaMismatch> {
+    check(schema, value)?;
+    write(schema, value, buf);
+    Ok(())
+}
+
+// whether value has the type of schema, which is all that the lengths and writes below assume
+fn check(schema: &SszTypeInfo, value: &SszValue) -> Result<(), SchemaMismatch> {
+    match (schema, value) {
+        (SszTypeInfo::Uint(1), SszValue::U8(_))
+        | (SszTypeInfo::Uint(2), SszValue::U16(_))
+        | (SszTypeInfo::Uint(4), SszValue::U32(_))
+        | (SszTypeInfo::Uint(8), SszValue::U64(_))
+        | (SszTypeInfo::Uint(16), SszValue::U128(_))
+        | (SszTypeInfo::Bool, SszValue::Bool(_)) => Ok(()),
+        (SszTypeInfo::Uint(len), SszValue::Uint(bytes))
+            if !matches!(*len, 1 | 2 | 4 | 8 | 16) && bytes.len() == *len =>
+        {
+            Ok(())
+        }
+        (SszTypeInfo::Uint(len), _) => Err(mismatch(alloc::format!("a {}-byte integer", len))),
+        (SszTypeInfo::Bool, _) => Err(mismatch("a boolean")),
+        (SszTypeInfo::Vector { item, len }, SszValue::Bytes(bytes)) if item.is_byte() => {
+            if bytes.len() == *len {
+                Ok(())
+            } else {
+                Err(mismatch(alloc::format!("a vector of {} bytes", len)))
+            }
+        }
+        (SszTypeInfo::Vector { item, len }, SszValue::Vector(items)) if !item.is_byte() => {
+            if items.len() != *len {
+                return Err(mismatch(alloc::format!("a vector of {} items", len)));
+            }
+            items.iter().try_for_each(|value| check(item, value))
+        }
+        (SszTypeInfo::Vector { len, .. }, _) => {
+            Err(mismatch(alloc::format!("a vector of {} items", len)))
+        }
+        (SszTypeInfo::List { item, max_len }, SszValue::Bytes(bytes)) if item.is_byte() => {
+            if bytes.len() <= *max_len {
+                Ok(())
+            } else {
+                Err(mismatch(alloc::format!(
+                    "a list of at most {} bytes",
+                    max_len
+                )))
+            }
+        }
+        (SszTypeInfo::List { item, max_len }, SszValue::List(items)) if !item.is_byte() => {
+            if items.len() > *max_len {
+                return Err(mismatch(alloc::format!(
+                    "a list of at most {} items",
+                    max_len
+                )));
+            }
+            items.iter().try_for_each(|value| check(item, value))
+        }
+        (SszTypeInfo::List { max_len, .. }, _) => Err(mismatch(alloc::format!(
+            "a list of at most {} items",
+            max_len
+        ))),
+        (SszTypeInfo::Bitvector(len), SszValue::Bitvector(bits)) if bits.len() == *len => Ok(()),
+        (SszTypeInfo::Bitvector(len), _) => {
+            Err(mismatch(alloc::format!("a bit vector of {} bits", len)))
+        }
+        (SszTypeInfo::Bitlist(max_len), SszValue::Bitlist(bits)) if bits.len() <= *max_len => {
+            Ok(())
+        }
+        (SszTypeInfo::Bitlist(max_len), _) => Err(mismatch(alloc::format!(
+            "a bit list of at most {} bits",
+            max_len
+        ))),
+        (SszTypeInfo::Container(fields), SszValue::Container(values))
+            if fields.len() == values.len()
+                && fields
+                    .iter()
+                    .zip(values)
+                    .all(|((name, _), (value_name, _))| name == value_name) =>
+        {
+            fields
+                .iter()
+                .zip(values)
+                .try_for_each(|((_, field), (_, value))| check(field, value))
+        }
+        (SszTypeInfo::Container(fields), _) => {
+            let names = fields
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>();
+            Err(mismatch(alloc::format!(
+                "a container with the fields {}",
+                names.join(", ")
+            )))
+        }
+        (SszTypeInfo::Union(variants), SszValue::Union { selector, value }) => {
+            match (variants.get(usize::from(*selector)), value) {
+                (Some(Some(variant)), Some(value)) => check(variant, value),
+                (Some(None), None) => Ok(()),
+                _ => Err(mismatch(alloc::format!(
+                    "a union with the variant {}",
+                    selector
+                ))),
+            }
+        }
+        (SszTypeInfo::Union(variants), _) => Err(mismatch(alloc::format!(
+            "a union of {} variants",
+            variants.len()
+        ))),
+    }
+}
+
+// the length of the encoding of a value that has been checked against schema
+fn encoded_len(schema: &SszTypeInfo, value: &SszValue) -> usize {
+    if schema.is_static() {
+        return schema.fixed_len();
+    }
+    match (schema, value) {
+        (SszTypeInfo::List { .. }, SszValue::Bytes(bytes)) => bytes.len(),
+        (
+            SszTypeInfo::Vector { item, .. } | SszTypeInfo::List { item, .. },
+            SszValue::Vector(items) | SszValue::List(items),
+        ) => items
+            .iter()
+            .map(|value| item.fixed_len() + variable_len(item, value))
+            .sum(),
+        (SszTypeInfo::Bitlist(_), SszValue::Bitlist(bits)) => bits.len() / 8 + 1,
+        (SszTypeInfo::Container(fields), SszValue::Container(values)) => fields
+            .iter()
+            .zip(values)
+            .map(|((_, field), (_, value))| field.fixed_len() + variable_len(field, value))
+            .sum(),
+        (SszTypeInfo::Union(variants), SszValue::Union { selector, value }) => {
+            1 + match (&variants[usize::from(*selector)], value) {
+                (Some(variant), Some(value)) => encoded_len(variant, value),
+                _ => 0,
+            }
+        }
+        _ => unreachable!("the value was checked against its schema"),
+    }
+}
+
+// the length of a value's encoding past its part in the fixed portion
+fn variable_len(schema: &SszTypeInfo, value: &SszValue) -> usize {
+    if schema.is_static() {
+        0
+    } else {
+        encoded_len(schema, value)
+    }
+}
+
+// writes a value that has been checked against schema
+fn write(schema: &SszTypeInfo, value: &SszValue, buf: &mut impl BufMut) {
+    match (schema, value) {
+        (_, SszValue::U8(int)) => buf.put_u8(*int),
+        (_, SszValue::U16(int)) => buf.put_u16_le(*int),
+        (_, SszValue::U32(int)) => buf.put_u32_le(*int),
+        (_, SszValue::U64(int)) => buf.put_u64_le(*int),
+        (_, SszValue::U128(int)) => buf.put_u128_le(*int),
+        (_, SszValue::Uint(bytes) | SszValue::Bytes(bytes)) => buf.put_slice(bytes),
+        (_, SszValue::Bool(value)) => buf.put_u8(u8::from(*value)),
+        (
+            SszTypeInfo::Vector { item, .. } | SszTypeInfo::List { item, .. },
+            SszValue::Vector(items) | SszValue::List(items),
+        ) => {
+            let values = items
+                .iter()
+                .map(|value| (&**item, value))
+                .collect::<Vec<_>>();
+            write_parts(&values, buf);
+        }
+        (_, SszValue::Bitvector(bits)) => buf.put_slice(&pack_bits(bits, false)),
+        (_, SszValue::Bitlist(bits)) => buf.put_slice(&pack_bits(bits, true)),
+        (SszTypeInfo::Container(fields), SszValue::Container(values)) => {
+            let values = fields
+                .iter()
+                .zip(values)
+                .map(|((_, field), (_, value))| (field, value))
+                .collect::<Vec<_>>();
+            write_parts(&values, buf);
+        }
+        (SszTypeInfo::Union(variants), SszValue::Union { selector, value }) => {
+            buf.put_u8(*selector);
+            if let (Some(variant), Some(value)) = (&variants[usize::from(*selector)], value) {
+                write(variant, value, buf);
+            }
+        }
+        _ => unreachable!("the value was checked against its schema"),
+    }
+}
+
+// writes the fixed portion of the items or fields, with offsets for the variable ones, followed
+// by the variable ones
+fn write_parts(parts: &[(&SszTypeInfo, &SszValue)], buf: &mut impl BufMut) {
+    let mut offset: usize = parts.iter().map(|(schema, _)| schema.fixed_len()).sum();
+    for (schema, value) in parts {
+        if schema.is_static() {
+            write(schema, value, buf);
+        } else {
+            expect_offset(write_offset(offset, buf));
+            offset += encoded_len(schema, value);
+        }
+    }
+    for (schema, value) in parts {
+        if !schema.is_static() {
+            write(schema, value, buf);
+        }
+    }
+}
+
+// the bytes of a bitfield, with the length bit after the last bit of a bit list
+fn pack_bits(bits: &[bool], length_bit: bool) -> Vec<u8> {
+    let len = bits.len() + usize::from(length_bit);
+    let mut bytes = vec![0; bitfield_bytes_len(len)];
+    for (i, _) in bits
+        .iter()
+        .chain(length_bit.then_some(&true))
+        .enumerate()
+        .filter(|(_, bit)| **bit)
+    {
+        bytes[i / 8] |= 1 << (i % 8);
+    }
+    bytes
+}
@@ -1,21 +1,97 @@
-use bytes::buf::BufMut;
+use crate::{vectored::Segments, BYTES_PER_LENGTH_OFFSET};
+use alloc::vec::Vec;
+use bytes::buf::{BufMut, UninitSlice};
+use core::{borrow::Borrow, mem::MaybeUninit};
 
 pub mod encode_impls;
+mod list_iter;
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+mod stream;
+
+pub use list_iter::{encode_list_iter, list_iter_to_ssz};
+#[cfg(feature = "std")]
+pub use pool::EncoderPool;
+#[cfg(feature = "std")]
+pub use stream::stream_ssz_to;
 
 // Most of the complexity in implementing ssz macros arises from offset accounting.
 // Using the BufMut trait means that moving the buffer cursor is taken care of for us.
 pub trait SszbEncode {
-    fn is_ssz_static() -> bool;
+    // these are pure functions of the type, so they're associated constants
+    // rather than functions that have to be re-evaluated in hot loops
+    const SSZ_STATIC: bool;
 
     // all lengths are in number of bytes
-    fn ssz_fixed_len() -> usize;
+    const SSZ_FIXED_LEN: usize;
+
+    // use when preallocating the max bytes needed to encode this type. It's the length of the
+    // value's own serialization, not counting the offset pointing to it from an enclosing type,
+    // and is the same as the decode impl's
+    const SSZ_MAX_LEN: usize;
+
+    // whether the type's memory is its encoding, so that values and runs of them can be copied
+    // as bytes: unsigned integers on little-endian targets, byte arrays, and #[ssz(pod)]
+    // containers of such fields
+    const SSZ_POD: bool = false;
+
+    // a static type's is its SSZ_FIXED_LEN, from the type rather than whatever the value holds
+    // in memory, since the fixed part of an enclosing container is laid out with that constant
     fn sszb_bytes_len(&self) -> usize;
 
-    // helper function, use when preallocating the max bytes needed to encode this type
-    fn ssz_max_len() -> usize;
+    // sszb_bytes_len, or LengthOverflow if the length doesn't fit in a usize. types whose
+    // length is the sum of their parts' (containers, unions, lists and vectors) add them up here
+    // with checked arithmetic and implement sszb_bytes_len as expect_len(self.try_sszb_bytes_len())
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        Ok(self.sszb_bytes_len())
+    }
+
+    // ListTooLong if a list in self holds more items than its maximum, which would encode to
+    // bytes no client decodes, and OffsetOverflow if a variable part in self would start past
+    // the u32::MAX bytes an offset can reach. bounded lists check their own length, and types
+    // holding other values (containers, vectors, unions and pointers) check those in turn, the
+    // variable ones through check_part_bounds. the fallible encoders check before writing
+    // anything, while ssz_write only debug-asserts the length and panics on the offset
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    #[deprecated(note = "use the `SSZ_STATIC` associated constant instead")]
+    fn is_ssz_static() -> bool {
+        Self::SSZ_STATIC
+    }
+
+    #[deprecated(note = "use the `SSZ_FIXED_LEN` associated constant instead")]
+    fn ssz_fixed_len() -> usize {
+        Self::SSZ_FIXED_LEN
+    }
+
+    #[deprecated(note = "use the `SSZ_MAX_LEN` associated constant instead")]
+    fn ssz_max_len() -> usize {
+        Self::SSZ_MAX_LEN
+    }
+
+    // SSZ_MAX_LEN, or LengthOverflow if the type has no maximum length that fits in a usize.
+    // the constant saturates at usize::MAX, both for unbounded types and for ones whose bounds
+    // multiply past it, so it can't be used to size a buffer then
+    fn try_ssz_max_len() -> Result<usize, EncodeError> {
+        if Self::SSZ_MAX_LEN == usize::MAX {
+            Err(EncodeError::LengthOverflow)
+        } else {
+            Ok(Self::SSZ_MAX_LEN)
+        }
+    }
 
-    // ssz_write_fixed either writes fixed types to the buffer,
-    // or writes the offset to the buffer and increases the offset by self.sszb_bytes_len()
+    // writes self's part of the fixed portion of an enclosing container, list or vector. a static
+    // value writes all of its SSZ_FIXED_LEN bytes, the same as ssz_write, and leaves offset alone:
+    // the enclosing type counts static parts into its fixed length up front. a variable value
+    // writes offset (where its bytes are going to start) as a BYTES_PER_LENGTH_OFFSET-byte
+    // offset, and advances it by self.sszb_bytes_len() to where the next variable part starts.
+    // getting either wrong shifts every offset written after it, so debug builds of the derived
+    // encoders check each field against this, and sszb::testing::verify_encode_contract checks
+    // a whole impl. this can't fail, so offsets are written with expect_offset(write_offset(..)),
+    // which panics on one past u32::MAX (see check_offset)
     fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut);
     // write self to the buffer if the type is dynamic (variable-sized)
     fn ssz_write_variable(&self, buf: &mut impl BufMut);
@@ -23,21 +99,600 @@ pub trait SszbEncode {
     // this may create an offset and make calls to ssz_write_fixed and ssz_write_variable
     fn ssz_write(&self, buf: &mut impl BufMut);
 
+    // writes static values back to back, as in the body of a list or vector
+    // u8, and the other unsigned integers on little-endian targets, override this to copy the
+    // whole run with a single put_slice
+    #[doc(hidden)]
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut)
+    where
+        Self: Sized,
+    {
+        for item in items {
+            item.ssz_write(buf);
+        }
+    }
+
+    // writes self into segments, borrowing byte lists and vectors of at least
+    // VECTORED_THRESHOLD bytes instead of copying them. flattened, the output is the same as
+    // ssz_write's
+    fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+        self.ssz_write(segments.scratch());
+    }
+
+    // ssz_write_packed for ssz_write_vectored, u8 overrides this to borrow long runs
+    #[doc(hidden)]
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) where
+        Self: Sized,
+    {
+        Self::ssz_write_packed(items, segments.scratch());
+    }
+
+    // walks self once, appending the lengths ssz_write_cached needs to lens, and returns
+    // sszb_bytes_len(). types that write offsets to variable parts (containers and lists of
+    // variable items) record the length of each such part, followed by each part's own record,
+    // and have to override this together with ssz_write_cached
+    #[doc(hidden)]
+    fn ssz_record_lens(&self, _lens: &mut Vec<usize>) -> usize {
+        self.sszb_bytes_len()
+    }
+
+    // writes the same bytes as ssz_write, but takes the lengths of variable parts from the front
+    // of lens (as recorded by ssz_record_lens) instead of walking each part again to measure it
+    #[doc(hidden)]
+    fn ssz_write_cached(&self, _lens: &mut &[usize], buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    // ssz_write and ssz_write_cached for a Vec, which is what to_ssz and to_ssz_with_vec write
+    // into. containers derived with #[ssz(monomorphize = "slices")] write straight into it with
+    // the encoder they compile once, instead of going through the scratch Vec their generic
+    // ssz_write needs, and lists and pointers pass these on to their items
+    #[doc(hidden)]
+    fn ssz_write_vec(&self, buf: &mut Vec<u8>) {
+        self.ssz_write(buf);
+    }
+
+    #[doc(hidden)]
+    fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut Vec<u8>) {
+        self.ssz_write_cached(lens, buf);
+    }
+
+    // writes self to the front of buf in a single pass, returning the number of bytes written.
+    // lists of variable items and containers write each variable part straight to where it
+    // belongs and back-patch its offset once it's written, instead of measuring every part before
+    // writing it as ssz_write does. buf has to be at least sszb_bytes_len() bytes long, and ends
+    // up with the same bytes as ssz_write would write. an append-only BufMut can't be patched,
+    // so that's what ssz_write is for
+    fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+        write_to_slice(buf, |rest| self.ssz_write(rest))
+    }
+
     // dev facing helper function for when a buffer is not already allocated
     // ssz_write should be used if there's a spare buffer around to write into
     fn to_ssz(&self) -> Vec<u8> {
-        // buf must be appropriately sized
-        let mut buf = Vec::with_capacity(self.sszb_bytes_len());
-        self.ssz_write(&mut buf);
+        // every length is measured once up front, which also sizes buf
+        let mut lens = Vec::new();
+        let mut buf = Vec::with_capacity(self.ssz_record_lens(&mut lens));
+        self.ssz_write_cached_vec(&mut lens.as_slice(), &mut buf);
 
         buf
     }
 
+    // to_ssz for values that come off the network, returning LengthOverflow instead of
+    // panicking when the length of self doesn't fit in a usize, OffsetOverflow instead of
+    // panicking on an offset past u32::MAX, and ListTooLong instead of encoding a list past its
+    // maximum
+    fn try_to_ssz(&self) -> Result<Vec<u8>, EncodeError> {
+        self.ssz_check_bounds()?;
+        self.try_sszb_bytes_len()?;
+        Ok(self.to_ssz())
+    }
+
+    // ssz_write for values that come off the network, returning LengthOverflow instead of
+    // panicking when the length of self doesn't fit in a usize, OffsetOverflow instead of
+    // panicking on an offset past u32::MAX, ListTooLong instead of encoding a list past its
+    // maximum, and BufferTooSmall instead of writing part of it when buf has less room left
+    // than that
+    fn try_ssz_write(&self, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+        self.ssz_check_bounds()?;
+        let len = self.try_sszb_bytes_len()?;
+        if buf.remaining_mut() < len {
+            return Err(EncodeError::BufferTooSmall {
+                len: buf.remaining_mut(),
+                expected: len,
+            });
+        }
+        self.ssz_write(buf);
+        Ok(())
+    }
+
+    /// Encodes `self` into the front of `buf` without zeroing it first, and returns the written
+    /// bytes, e.g. the spare capacity of a `Vec` that's about to hold a large state:
+    ///
+    /// ```ignore
+    /// let mut buf = Vec::with_capacity(state.sszb_bytes_len());
+    /// let len = state.encode_to_uninit(buf.spare_capacity_mut())?.len();
+    /// unsafe { buf.set_len(len) };
+    /// ```
+    ///
+    /// `buf` has to be at least `sszb_bytes_len()` bytes long, otherwise nothing is written and
+    /// `BufferTooSmall` is returned. Encoding goes through the append-only `BufMut` that
+    /// `ssz_write` takes, so the bytes are written strictly in order and the returned slice is
+    /// exactly the ones that were.
+    fn encode_to_uninit<'a>(
+        &self,
+        buf: &'a mut [MaybeUninit<u8>],
+    ) -> Result<&'a mut [u8], EncodeError> {
+        let mut lens = Vec::new();
+        let len = self.ssz_record_lens(&mut lens);
+        if buf.len() < len {
+            return Err(EncodeError::BufferTooSmall {
+                len: buf.len(),
+                expected: len,
+            });
+        }
+
+        let mut writer = UninitWriter {
+            buf: &mut buf[..len],
+            written: 0,
+        };
+        self.ssz_write_cached(&mut lens.as_slice(), &mut writer);
+        let written = writer.written;
+        debug_assert_eq!(
+            written, len,
+            "ssz_write didn't write sszb_bytes_len() bytes"
+        );
+
+        // SAFETY: UninitWriter only ever appends, so its first `written` bytes are initialized
+        Ok(unsafe { &mut *(&mut buf[..written] as *mut [MaybeUninit<u8>] as *mut [u8]) })
+    }
+
     // dev facing helper function for when a buffer is already allocated
     fn to_ssz_with_vec(&self, buf: &mut Vec<u8>) {
         // buf must be appropriately sized before writing to it
         // .reserve_exact reserves the required additional capacity if not already allocated
-        buf.reserve_exact(self.sszb_bytes_len());
-        self.ssz_write(buf);
+        let mut lens = Vec::new();
+        buf.reserve_exact(self.ssz_record_lens(&mut lens));
+        self.ssz_write_cached_vec(&mut lens.as_slice(), buf);
+    }
+
+    /// Encodes a static value into an array on the stack, e.g.
+    /// `let bytes: [u8; 32] = root.to_ssz_array();`.
+    ///
+    /// `N` has to be the type's fixed length, which is checked at compile time:
+    ///
+    /// ```compile_fail
+    /// use sszb::SszbEncode;
+    /// let bytes: [u8; 4] = 7u64.to_ssz_array();
+    /// ```
+    fn to_ssz_array<const N: usize>(&self) -> [u8; N] {
+        const {
+            assert!(
+                Self::SSZ_STATIC && Self::SSZ_FIXED_LEN == N,
+                "to_ssz_array needs a static type whose fixed length is N"
+            )
+        };
+        let mut array = [0u8; N];
+        self.ssz_write(&mut array.as_mut_slice());
+        array
+    }
+
+    // to_ssz_array for when the length is only known at runtime (variable values included),
+    // returning LengthMismatch unless the encoding is exactly N bytes
+    fn try_to_ssz_array<const N: usize>(&self) -> Result<[u8; N], EncodeError> {
+        self.ssz_check_bounds()?;
+        let len = self.try_sszb_bytes_len()?;
+        if len != N {
+            return Err(EncodeError::LengthMismatch { len, expected: N });
+        }
+        let mut array = [0u8; N];
+        self.ssz_write(&mut array.as_mut_slice());
+        Ok(array)
+    }
+}
+
+/// Returned when a value can't be encoded.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum EncodeError {
+    /// The length of the encoding doesn't fit in a `usize`.
+    LengthOverflow,
+    /// The buffer has room for `len` bytes, but the encoding is `expected` bytes long.
+    BufferTooSmall { len: usize, expected: usize },
+    /// The encoding is `len` bytes long, but exactly `expected` bytes were asked for.
+    LengthMismatch { len: usize, expected: usize },
+    /// A variable-size part starts `offset` bytes in, past the `u32::MAX` bytes an offset can
+    /// reach.
+    OffsetOverflow { offset: usize },
+    /// A list holds `len` items, more than its maximum of `max`.
+    ListTooLong { len: usize, max: usize },
+}
+
+impl core::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EncodeError::LengthOverflow => {
+                write!(f, "the length of the encoding doesn't fit in a usize")
+            }
+            EncodeError::BufferTooSmall { len, expected } => write!(
+                f,
+                "the buffer has room for {} bytes, but the encoding is {} bytes long",
+                len, expected
+            ),
+            EncodeError::LengthMismatch { len, expected } => write!(
+                f,
+                "the encoding is {} bytes long, but exactly {} bytes were asked for",
+                len, expected
+            ),
+            EncodeError::OffsetOverflow { offset } => write!(
+                f,
+                "a part starts {} bytes in, past the u32::MAX bytes an offset can reach",
+                offset
+            ),
+            EncodeError::ListTooLong { len, max } => {
+                write!(f, "a list of {} items exceeds its maximum of {}", len, max)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EncodeError {}
+
+/// The length in `len`, for implementing `sszb_bytes_len` on top of `try_sszb_bytes_len`.
+///
+/// # Panics
+///
+/// If `len` is `LengthOverflow`, naming the type `ty` whose encoding is too long.
+#[inline]
+pub fn expect_len(len: Result<usize, EncodeError>, ty: &'static str) -> usize {
+    match len {
+        Ok(len) => len,
+        Err(_) => length_overflow(ty),
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn length_overflow(ty: &str) -> ! {
+    panic!(
+        "SSZ length overflow: the encoding of {} is longer than usize::MAX bytes",
+        ty
+    )
+}
+
+/// The length of `num_items` items encoded back to back as the body of a list or vector, with
+/// offsets in front of them if they're variable, or `LengthOverflow` if it doesn't fit in a
+/// `usize`. Static items are all the same length, so `items` is only visited for variable ones.
+pub fn try_items_bytes_len<T: SszbEncode>(
+    num_items: usize,
+    mut items: impl Iterator<Item = impl Borrow<T>>,
+) -> Result<usize, EncodeError> {
+    if T::SSZ_STATIC {
+        num_items
+            .checked_mul(T::SSZ_FIXED_LEN)
+            .ok_or(EncodeError::LengthOverflow)
+    } else {
+        let offsets = num_items
+            .checked_mul(BYTES_PER_LENGTH_OFFSET)
+            .ok_or(EncodeError::LengthOverflow)?;
+        items.try_fold(offsets, |len, item| {
+            len.checked_add(item.borrow().try_sszb_bytes_len()?)
+                .ok_or(EncodeError::LengthOverflow)
+        })
+    }
+}
+
+/// `ssz_check_bounds` for a list of `num_items` items with a maximum of `max` (`usize::MAX` for
+/// unbounded lists and for vectors), followed by that of each item. Static items hold no lists,
+/// so `items` is only visited for variable ones.
+pub fn check_items_bounds<T: SszbEncode>(
+    num_items: usize,
+    max: usize,
+    items: impl Iterator<Item = impl Borrow<T>>,
+) -> Result<(), EncodeError> {
+    if num_items > max {
+        return Err(EncodeError::ListTooLong {
+            len: num_items,
+            max,
+        });
+    }
+    if !T::SSZ_STATIC {
+        // the items start after their offsets, each where the one before it ends
+        let mut offset = num_items
+            .checked_mul(BYTES_PER_LENGTH_OFFSET)
+            .ok_or(EncodeError::LengthOverflow)?;
+        for item in items {
+            check_part_bounds(item.borrow(), &mut offset)?;
+        }
+    }
+    Ok(())
+}
+
+/// `ssz_check_bounds` for a variable-size `part` that starts `offset` bytes into the
+/// serialization holding it: `OffsetOverflow` if its offset is past `u32::MAX`, followed by the
+/// part's own. `offset` is moved past the part, to where the next one starts, or the length of
+/// the part is `LengthOverflow` if that doesn't fit in a `usize`.
+pub fn check_part_bounds<T: SszbEncode>(part: &T, offset: &mut usize) -> Result<(), EncodeError> {
+    if u32::try_from(*offset).is_err() {
+        return Err(EncodeError::OffsetOverflow { offset: *offset });
+    }
+    *offset = offset
+        .checked_add(part.try_sszb_bytes_len()?)
+        .ok_or(EncodeError::LengthOverflow)?;
+    part.ssz_check_bounds()
+}
+
+/// The offset of a variable-size part that starts `offset` bytes into the serialization holding
+/// it, as a `u32`.
+///
+/// # Panics
+///
+/// Offsets are 4 bytes, so no part can start more than `u32::MAX` bytes in. Truncating a larger
+/// offset would quietly point it somewhere else, so once a serialization grows that large this
+/// panics instead.
+#[inline]
+pub fn check_offset(offset: usize) -> u32 {
+    match u32::try_from(offset) {
+        Ok(offset) => offset,
+        Err(_) => offset_overflow(offset),
+    }
+}
+
+/// The 4 little-endian bytes of an offset, see `check_offset`.
+#[inline]
+pub fn offset_bytes(offset: usize) -> [u8; BYTES_PER_LENGTH_OFFSET] {
+    check_offset(offset).to_le_bytes()
+}
+
+/// Writes `offset` to `buf` as the 4 little-endian bytes of a `u32`, or returns
+/// `OffsetOverflow` without writing anything if it's past `u32::MAX`. Every impl writes its
+/// offsets through this, so a truncated offset can't slip into an encoding anywhere.
+#[inline]
+pub fn write_offset(offset: usize, buf: &mut impl BufMut) -> Result<(), EncodeError> {
+    let offset = u32::try_from(offset).map_err(|_| EncodeError::OffsetOverflow { offset })?;
+    buf.put_slice(&offset.to_le_bytes());
+    Ok(())
+}
+
+/// The result of `write_offset`, for writers that can't return an error, such as
+/// `ssz_write_fixed`.
+///
+/// # Panics
+///
+/// If it's `OffsetOverflow`, see `check_offset`.
+#[inline]
+pub fn expect_offset(result: Result<(), EncodeError>) {
+    if let Err(EncodeError::OffsetOverflow { offset }) = result {
+        offset_overflow(offset)
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn offset_overflow(offset: usize) -> ! {
+    panic!(
+        "SSZ offset overflow: a part starts {} bytes in, past the u32::MAX bytes an offset can reach",
+        offset
+    )
+}
+
+/// Runs `write` on `buf` and, in debug builds, panics unless it wrote exactly `expected()`
+/// bytes, naming `ty` and the `method` that got its lengths wrong. The derived encoders wrap
+/// their writes in it, so that a length that drifted from what's written, in the derive or in a
+/// field's impl, fails the first test that encodes the type. Release builds just call `write`.
+///
+/// The bytes written are told apart by how much `remaining_mut` went down, so buffers that
+/// don't track it, such as a stream's (which reports `usize::MAX`), aren't checked.
+#[doc(hidden)]
+#[inline(always)]
+pub fn debug_check_written<B: BufMut>(
+    buf: &mut B,
+    ty: &'static str,
+    method: &'static str,
+    expected: impl FnOnce() -> usize,
+    write: impl FnOnce(&mut B),
+) {
+    #[cfg(debug_assertions)]
+    let remaining = buf.remaining_mut();
+    write(buf);
+    #[cfg(debug_assertions)]
+    {
+        let written = remaining.checked_sub(buf.remaining_mut());
+        match written {
+            Some(written) if remaining != usize::MAX => {
+                let expected = expected();
+                if written != expected {
+                    wrong_written_len(ty, method, written, expected)
+                }
+            }
+            _ => {}
+        }
+    }
+    #[cfg(not(debug_assertions))]
+    let _ = (ty, method, expected);
+}
+
+/// Calls `value.ssz_write_fixed(offset, buf)` and, in debug builds, panics unless it kept to
+/// the contract of `ssz_write_fixed`: a static value writes its `SSZ_FIXED_LEN` bytes and leaves
+/// `offset` alone, a variable one writes `BYTES_PER_LENGTH_OFFSET` bytes and advances `offset`
+/// by its `sszb_bytes_len()`. The derived encoders write the fixed part of each field through
+/// it, so a field impl that breaks the contract fails where it's used instead of shifting every
+/// offset after it. Release builds just call `ssz_write_fixed`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn debug_check_write_fixed<T: SszbEncode>(
+    value: &T,
+    offset: &mut usize,
+    buf: &mut impl BufMut,
+) {
+    #[cfg(debug_assertions)]
+    let (remaining, start) = (buf.remaining_mut(), *offset);
+    value.ssz_write_fixed(offset, buf);
+    #[cfg(debug_assertions)]
+    {
+        let (expected_written, expected_offset) = if T::SSZ_STATIC {
+            (T::SSZ_FIXED_LEN, start)
+        } else {
+            (
+                BYTES_PER_LENGTH_OFFSET,
+                start.wrapping_add(value.sszb_bytes_len()),
+            )
+        };
+        match remaining.checked_sub(buf.remaining_mut()) {
+            Some(written) if remaining != usize::MAX && written != expected_written => {
+                broken_write_fixed::<T>("wrote", written, expected_written)
+            }
+            _ => {}
+        }
+        if *offset != expected_offset {
+            broken_write_fixed::<T>("moved the offset to", *offset, expected_offset)
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[cold]
+#[inline(never)]
+fn broken_write_fixed<T>(what: &str, found: usize, expected: usize) -> ! {
+    panic!(
+        "SSZ offset accounting: {}::ssz_write_fixed {} {}, instead of {}",
+        core::any::type_name::<T>(),
+        what,
+        found,
+        expected
+    )
+}
+
+#[cfg(debug_assertions)]
+#[cold]
+#[inline(never)]
+fn wrong_written_len(ty: &str, method: &str, written: usize, expected: usize) -> ! {
+    panic!(
+        "SSZ length mismatch: {}::{} wrote {} bytes, but its lengths add up to {}",
+        ty, method, written, expected
+    )
+}
+
+// ssz_record_lens for a list or vector of num_items variable items
+pub(crate) fn record_item_lens<'a, T: SszbEncode + 'a>(
+    num_items: usize,
+    items: impl Iterator<Item = &'a T>,
+    lens: &mut Vec<usize>,
+) -> usize {
+    let start = lens.len();
+    lens.resize(start + num_items, 0);
+    let mut len = BYTES_PER_LENGTH_OFFSET * num_items;
+    for (i, item) in items.enumerate() {
+        // len is where the item starts, so its offset is checked before any byte is written
+        check_offset(len);
+        let item_len = item.ssz_record_lens(lens);
+        lens[start + i] = item_len;
+        len += item_len;
+    }
+    len
+}
+
+// ssz_write_cached for a list or vector of num_items variable items
+pub(crate) fn write_items_cached<'a, T: SszbEncode + 'a>(
+    num_items: usize,
+    items: impl Iterator<Item = &'a T>,
+    lens: &mut &[usize],
+    buf: &mut impl BufMut,
+) {
+    write_item_offsets(num_items, lens, buf);
+    for item in items {
+        item.ssz_write_cached(lens, buf);
+    }
+}
+
+// write_items_cached for a Vec, passing it on to each item's ssz_write_cached_vec
+pub(crate) fn write_items_cached_vec<'a, T: SszbEncode + 'a>(
+    num_items: usize,
+    items: impl Iterator<Item = &'a T>,
+    lens: &mut &[usize],
+    buf: &mut Vec<u8>,
+) {
+    write_item_offsets(num_items, lens, buf);
+    for item in items {
+        item.ssz_write_cached_vec(lens, buf);
+    }
+}
+
+// the offsets of num_items variable items, from the lengths recorded at the front of lens
+fn write_item_offsets(num_items: usize, lens: &mut &[usize], buf: &mut impl BufMut) {
+    let (item_lens, rest) = lens.split_at(num_items);
+    *lens = rest;
+    let mut offset = BYTES_PER_LENGTH_OFFSET * num_items;
+    for item_len in item_lens {
+        expect_offset(write_offset(offset, buf));
+        offset += item_len;
+    }
+}
+
+// ssz_write_slice for a list or vector of num_items variable items, filling in each offset just
+// before writing the item it points to
+pub(crate) fn write_items_slice<'a, T: SszbEncode + 'a>(
+    num_items: usize,
+    items: impl Iterator<Item = &'a T>,
+    buf: &mut [u8],
+) -> usize {
+    let mut offset = BYTES_PER_LENGTH_OFFSET * num_items;
+    for (i, item) in items.enumerate() {
+        let at = i * BYTES_PER_LENGTH_OFFSET;
+        buf[at..(at + BYTES_PER_LENGTH_OFFSET)].copy_from_slice(&offset_bytes(offset));
+        offset += item.ssz_write_slice(&mut buf[offset..]);
     }
+    offset
+}
+
+// runs write with a cursor over buf, returning how far it advanced
+pub(crate) fn write_to_slice(buf: &mut [u8], write: impl FnOnce(&mut &mut [u8])) -> usize {
+    let len = buf.len();
+    let mut rest = buf;
+    write(&mut rest);
+    len - rest.len()
+}
+
+// a BufMut over uninitialized bytes for encode_to_uninit. It can only be appended to, so
+// everything before `written` has been written
+struct UninitWriter<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    written: usize,
+}
+
+unsafe impl BufMut for UninitWriter<'_> {
+    fn remaining_mut(&self) -> usize {
+        self.buf.len() - self.written
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        // the caller has initialized the next cnt bytes of chunk_mut()
+        assert!(
+            cnt <= self.remaining_mut(),
+            "advance_mut past the end of the buffer"
+        );
+        self.written += cnt;
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        UninitSlice::uninit(&mut self.buf[self.written..])
+    }
+}
+
+// runs both closures, concurrently with the rayon feature. this is what the code generated for
+// #[ssz(parallel)] uses, so that it builds either way, and with the same bounds either way
+#[doc(hidden)]
+#[cfg(feature = "rayon")]
+pub fn join(a: impl FnOnce() + Send, b: impl FnOnce() + Send) {
+    rayon::join(a, b);
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "rayon"))]
+pub fn join(a: impl FnOnce() + Send, b: impl FnOnce() + Send) {
+    a();
+    b();
 }
@@ -1,30 +1,21 @@
-use crate::{SszbEncode, BYTES_PER_LENGTH_OFFSET};
-use alloy_primitives::{Address, Bloom, FixedBytes, U128, U256};
+use crate::{
+    vectored::{Segments, VECTORED_THRESHOLD},
+    EncodeError, SszbEncode,
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use bytes::buf::BufMut;
-use ethereum_types::{H160, H256, H32};
-use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
 use paste::paste;
-use ssz_types::{BitList, BitVector, FixedVector, VariableList};
-use std::sync::Arc;
-use typenum::Unsigned;
 
 macro_rules! uint_sszb_encode {
     ($type: ident, $bit_size: expr) => {
         impl SszbEncode for $type {
-            fn is_ssz_static() -> bool {
-                true
-            }
-
-            fn ssz_fixed_len() -> usize {
-                $bit_size / 8
-            }
+            const SSZ_STATIC: bool = true;
+            const SSZ_FIXED_LEN: usize = $bit_size / 8;
+            const SSZ_MAX_LEN: usize = $bit_size / 8;
+            const SSZ_POD: bool = cfg!(target_endian = "little");
 
             fn sszb_bytes_len(&self) -> usize {
-                $bit_size / 8
-            }
-
-            fn ssz_max_len() -> usize {
-                $bit_size / 8
+                <Self as SszbEncode>::SSZ_FIXED_LEN
             }
 
             fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -36,25 +27,29 @@ macro_rules! uint_sszb_encode {
             fn ssz_write(&self, buf: &mut impl BufMut) {
                 paste! { buf.[<put_ $type _le>](*self) }
             }
+
+            fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+                if cfg!(target_endian = "little") {
+                    // the in-memory layout is the wire format, so this is a plain copy
+                    buf.put_slice(bytemuck::cast_slice(items));
+                } else {
+                    for item in items {
+                        item.ssz_write(buf);
+                    }
+                }
+            }
         }
     };
 }
 
 impl SszbEncode for u8 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        1
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 1;
+    const SSZ_MAX_LEN: usize = 1;
+    const SSZ_POD: bool = true;
 
     fn sszb_bytes_len(&self) -> usize {
-        1
-    }
-
-    fn ssz_max_len() -> usize {
-        1
+        <Self as SszbEncode>::SSZ_FIXED_LEN
     }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -66,6 +61,17 @@ impl SszbEncode for u8 {
     fn ssz_write(&self, buf: &mut impl BufMut) {
         buf.put_u8(*self);
     }
+
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        buf.put_slice(items);
+    }
+
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        write_byte_run_vectored(items, segments);
+    }
 }
 
 uint_sszb_encode!(u16, 16);
@@ -74,20 +80,12 @@ uint_sszb_encode!(u64, 64);
 uint_sszb_encode!(u128, 128);
 
 impl SszbEncode for bool {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        1
-    }
-
-    fn ssz_max_len() -> usize {
-        1
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 1;
+    const SSZ_MAX_LEN: usize = 1;
 
     fn sszb_bytes_len(&self) -> usize {
-        1
+        <Self as SszbEncode>::SSZ_FIXED_LEN
     }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -102,20 +100,13 @@ impl SszbEncode for bool {
 }
 
 impl<const N: usize> SszbEncode for [u8; N] {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        N
-    }
-
-    fn ssz_max_len() -> usize {
-        N
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = N;
+    const SSZ_MAX_LEN: usize = N;
+    const SSZ_POD: bool = true;
 
     fn sszb_bytes_len(&self) -> usize {
-        N
+        <Self as SszbEncode>::SSZ_FIXED_LEN
     }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -127,529 +118,109 @@ impl<const N: usize> SszbEncode for [u8; N] {
     fn ssz_write(&self, buf: &mut impl BufMut) {
         buf.put_slice(self.as_slice());
     }
-}
-
-impl SszbEncode for Address {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        20
-    }
-
-    fn ssz_max_len() -> usize {
-        20
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        20
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_slice());
-    }
-}
-
-impl<const N: usize> SszbEncode for FixedBytes<N> {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        N
-    }
-
-    fn ssz_max_len() -> usize {
-        N
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        N
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(&self.0)
-    }
-}
-
-impl SszbEncode for Bloom {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        256
-    }
-
-    fn ssz_fixed_len() -> usize {
-        256
-    }
-
-    fn ssz_max_len() -> usize {
-        256
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(&self.0 .0)
-    }
-}
-
-impl SszbEncode for U256 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        32
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        32
-    }
-
-    fn ssz_max_len() -> usize {
-        32
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_le_slice());
-    }
-}
-
-impl SszbEncode for U128 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        16
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        16
-    }
-
-    fn ssz_max_len() -> usize {
-        16
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_le_slice());
-    }
-}
-
-impl SszbEncode for H32 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        4
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        4
-    }
-
-    fn ssz_max_len() -> usize {
-        4
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_bytes());
-    }
-}
-
-impl SszbEncode for H160 {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        20
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        20
-    }
-
-    fn ssz_max_len() -> usize {
-        20
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_bytes());
-    }
-}
-
-impl SszbEncode for H256 {
-    fn is_ssz_static() -> bool {
-        true
-    }
 
-    fn ssz_fixed_len() -> usize {
-        32
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        buf.put_slice(items.as_flattened());
     }
 
-    fn sszb_bytes_len(&self) -> usize {
-        32
-    }
-
-    fn ssz_max_len() -> usize {
-        32
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(self.as_bytes());
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        write_byte_run_vectored(items.as_flattened(), segments);
     }
 }
 
-impl<N: Unsigned + Clone> SszbEncode for BitVector<N> {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        self.as_slice().len()
-    }
-
-    fn ssz_max_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
-    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(&self.clone().into_bytes());
+// ssz_write_packed_vectored for items whose memory is their encoding, borrowing long runs
+// instead of copying them
+pub(crate) fn write_byte_run_vectored<'segments>(
+    bytes: &'segments [u8],
+    segments: &mut Segments<'segments>,
+) {
+    if bytes.len() >= VECTORED_THRESHOLD {
+        segments.push_borrowed(bytes);
+    } else {
+        segments.scratch().put_slice(bytes);
     }
 }
 
-impl<N: Unsigned + Clone> SszbEncode for BitList<N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        self.clone().into_bytes().len()
-    }
-
-    fn ssz_max_len() -> usize {
-        std::cmp::max(1, (N::to_usize() + 7) / 8)
-    }
-
-    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-        // usize can be u32 or u64 depending on the system
-        // so we restrict offset to the first BYTES_PER_LENGTH_OFFSET bytes
-        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
-        *offset += self.sszb_bytes_len();
-    }
-
-    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        buf.put_slice(&self.clone().into_bytes());
-    }
-}
-
-impl<T: SszbEncode> SszbEncode for Arc<T> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
-
-    fn ssz_fixed_len() -> usize {
-        T::ssz_fixed_len()
-    }
-
-    fn ssz_max_len() -> usize {
-        T::ssz_max_len()
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        self.as_ref().sszb_bytes_len()
-    }
-
-    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-        self.as_ref().ssz_write_fixed(offset, buf);
-    }
-
-    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-        self.as_ref().ssz_write_variable(buf);
-    }
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        self.as_ref().ssz_write(buf);
+/// The memory of a run of byte array newtypes, such as `FixedBytes<N>`, `Address` and `H256`,
+/// which is their encoding: the lists of roots and addresses that make up much of a state are
+/// written with one `put_slice` per run, rather than one per item.
+///
+/// # Safety
+///
+/// `T` has to be a `#[repr(transparent)]` or `#[repr(C)]` wrapper around a `[u8; N]` and
+/// nothing else. Its size and alignment are checked at compile time, its fields can't be.
+#[cfg(any(feature = "alloy", feature = "ethereum-types"))]
+pub(crate) unsafe fn byte_arrays<T, const N: usize>(items: &[T]) -> &[u8] {
+    const {
+        assert!(core::mem::size_of::<T>() == N && core::mem::align_of::<T>() == 1);
     }
+    // SAFETY: the items are laid out back to back as [u8; N]s, per the caller, with no padding
+    // between them as the alignment is 1, so the slice covers exactly their initialized bytes
+    unsafe { core::slice::from_raw_parts(items.as_ptr().cast::<u8>(), items.len() * N) }
 }
 
-impl<T: SszbEncode + Value, N: Unsigned> SszbEncode for PersistentList<T, N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
+// smart pointers are encoded as the value they point to. Boxes are what recursive types, such as
+// a tree whose nodes hold a list of their children, need to be encodable at all
+macro_rules! pointer_ssz_encode {
+    ($pointer:ident) => {
+        impl<T: SszbEncode> SszbEncode for $pointer<T> {
+            const SSZ_STATIC: bool = T::SSZ_STATIC;
+            const SSZ_FIXED_LEN: usize = T::SSZ_FIXED_LEN;
+            const SSZ_MAX_LEN: usize = T::SSZ_MAX_LEN;
 
-    fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        if <T as SszbEncode>::is_ssz_static() {
-            <T as SszbEncode>::ssz_fixed_len() * self.len()
-        } else {
-            let mut len = self.iter().map(|item| SszbEncode::sszb_bytes_len(item)).sum();
-            len += BYTES_PER_LENGTH_OFFSET * self.len();
-            len
-        }
-    }
-
-    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
-        *offset += self.sszb_bytes_len();
-    }
-
-    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        if T::is_ssz_static() {
-            for item in self {
-                item.ssz_write(buf);
-            }
-        } else {
-            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
-            for item in self {
-                item.ssz_write_fixed(offset, buf);
-            }
-            for item in self {
-                item.ssz_write(buf);
+            fn sszb_bytes_len(&self) -> usize {
+                self.as_ref().sszb_bytes_len()
             }
-        }
-    }
-}
-
-impl<T: SszbEncode + Value, N: Unsigned> SszbEncode for PersistentVector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
-
-    fn ssz_fixed_len() -> usize {
-        if <T as SszbEncode>::is_ssz_static() {
-            <T as SszbEncode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
-    }
 
-    fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
-    }
-
-    fn sszb_bytes_len(&self) -> usize {
-        if <T as SszbEncode>::is_ssz_static() {
-            <T as SszbEncode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = self.iter().map(|item| SszbEncode::sszb_bytes_len(item)).sum();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
-
-    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-        if T::is_ssz_static() {
-            self.ssz_write(buf);
-        } else {
-            buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
-            *offset += self.sszb_bytes_len();
-        }
-    }
-
-    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-        if !T::is_ssz_static() {
-            self.ssz_write(buf);
-        }
-    }
-
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        if T::is_ssz_static() {
-            for item in self {
-                item.ssz_write(buf);
-            }
-        } else {
-            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
-            for item in self {
-                item.ssz_write_fixed(offset, buf);
-            }
-            for item in self {
-                item.ssz_write(buf);
+            fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+                self.as_ref().try_sszb_bytes_len()
             }
-        }
-    }
-}
 
-impl<T: SszbEncode, N: Unsigned> SszbEncode for VariableList<T, N> {
-    fn is_ssz_static() -> bool {
-        false
-    }
-    fn ssz_fixed_len() -> usize {
-        BYTES_PER_LENGTH_OFFSET
-    }
-    fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
-    }
-    fn sszb_bytes_len(&self) -> usize {
-        if <T as SszbEncode>::is_ssz_static() {
-            <T as SszbEncode>::ssz_fixed_len() * self.len()
-        } else {
-            let mut len = self.iter().map(|item| SszbEncode::sszb_bytes_len(item)).sum();
-            len += BYTES_PER_LENGTH_OFFSET * self.len();
-            len
-        }
-    }
-    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-        buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
-        *offset += self.sszb_bytes_len();
-    }
-    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-        self.ssz_write(buf);
-    }
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        if T::is_ssz_static() {
-            for item in self {
-                item.ssz_write(buf);
-            }
-        } else {
-            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
-            for item in self {
-                item.ssz_write_fixed(offset, buf);
+            fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+                self.as_ref().ssz_check_bounds()
             }
-            for item in self {
-                item.ssz_write(buf);
-            }
-        }
-    }
-}
 
-impl<T: SszbEncode, N: Unsigned> SszbEncode for FixedVector<T, N> {
-    fn is_ssz_static() -> bool {
-        T::is_ssz_static()
-    }
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                self.as_ref().ssz_write_fixed(offset, buf);
+            }
 
-    fn ssz_fixed_len() -> usize {
-        if <T as SszbEncode>::is_ssz_static() {
-            <T as SszbEncode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            BYTES_PER_LENGTH_OFFSET
-        }
-    }
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                self.as_ref().ssz_write_variable(buf);
+            }
 
-    fn ssz_max_len() -> usize {
-        T::ssz_max_len() * N::to_usize()
-    }
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                self.as_ref().ssz_write(buf);
+            }
 
-    fn sszb_bytes_len(&self) -> usize {
-        if <T as SszbEncode>::is_ssz_static() {
-            <T as SszbEncode>::ssz_fixed_len() * N::to_usize()
-        } else {
-            let mut len = self.iter().map(|item| SszbEncode::sszb_bytes_len(item)).sum();
-            len += BYTES_PER_LENGTH_OFFSET * N::to_usize();
-            len
-        }
-    }
+            fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+                self.as_ref().ssz_write_vectored(segments);
+            }
 
-    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
-        if T::is_ssz_static() {
-            self.ssz_write(buf);
-        } else {
-            buf.put_slice(&offset.to_le_bytes()[0..BYTES_PER_LENGTH_OFFSET]);
-            *offset += self.sszb_bytes_len();
-        }
-    }
+            fn ssz_record_lens(&self, lens: &mut Vec<usize>) -> usize {
+                self.as_ref().ssz_record_lens(lens)
+            }
 
-    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
-        if !T::is_ssz_static() {
-            self.ssz_write(buf);
-        }
-    }
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+                self.as_ref().ssz_write_cached(lens, buf);
+            }
 
-    fn ssz_write(&self, buf: &mut impl BufMut) {
-        if T::is_ssz_static() {
-            for item in self {
-                item.ssz_write(buf);
+            fn ssz_write_vec(&self, buf: &mut Vec<u8>) {
+                self.as_ref().ssz_write_vec(buf);
             }
-        } else {
-            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
-            for item in self {
-                item.ssz_write_fixed(offset, buf);
+
+            fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut Vec<u8>) {
+                self.as_ref().ssz_write_cached_vec(lens, buf);
             }
-            for item in self {
-                item.ssz_write(buf);
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                self.as_ref().ssz_write_slice(buf)
             }
         }
-    }
+    };
 }
+
+pointer_ssz_encode!(Box);
+pointer_ssz_encode!(Arc);
@@ -0,0 +1,101 @@
+use crate::{check_offset, expect_offset, write_offset, SszbEncode, BYTES_PER_LENGTH_OFFSET};
+use alloc::{vec, vec::Vec};
+use bytes::buf::BufMut;
+use core::borrow::Borrow;
+
+/// Writes the elements of `items` to `buf` as an SSZ list of `T`s, the same bytes as encoding a
+/// `VariableList` holding them, without collecting them into one first. The items can be `T`s or
+/// anything that borrows as one, such as the `&T`s of a map's `values()`, in which case `T` has to
+/// be named: `encode_list_iter::<T>(map.values(), &mut buf)`.
+///
+/// Static elements are written as they come. For variable ones, a clone of `items` is walked
+/// first to measure each element and write the offset table, and then `items` itself to write the
+/// elements, so the iterator should be cheap to clone and yield the same elements both times.
+///
+/// # Panics
+///
+/// If a list of variable elements yields a different number of them than its `len()`, which
+/// would leave the offsets pointing at the wrong bytes, or if an offset overflows, see
+/// `check_offset`.
+pub fn encode_list_iter<T: SszbEncode>(
+    items: impl ExactSizeIterator<Item = impl Borrow<T>> + Clone,
+    buf: &mut impl BufMut,
+) {
+    if T::SSZ_STATIC {
+        for item in items {
+            item.borrow().ssz_write(buf);
+        }
+        return;
+    }
+
+    let num_items = items.len();
+    let mut offset = BYTES_PER_LENGTH_OFFSET * num_items;
+    let mut count = 0;
+    for item in items.clone() {
+        if count == num_items {
+            wrong_item_count(num_items);
+        }
+        expect_offset(write_offset(offset, buf));
+        offset += item.borrow().sszb_bytes_len();
+        count += 1;
+    }
+    if count != num_items {
+        wrong_item_count(num_items);
+    }
+    for item in items {
+        item.borrow().ssz_write(buf);
+    }
+}
+
+/// `encode_list_iter` into a new `Vec`, which is allocated at its final size. As with `to_ssz`,
+/// every variable element is measured once, before anything is written.
+pub fn list_iter_to_ssz<T: SszbEncode>(
+    items: impl ExactSizeIterator<Item = impl Borrow<T>> + Clone,
+) -> Vec<u8> {
+    if T::SSZ_STATIC {
+        let mut buf = Vec::with_capacity(T::SSZ_FIXED_LEN * items.len());
+        encode_list_iter::<T>(items, &mut buf);
+        return buf;
+    }
+
+    // the lengths of the elements come first, followed by whatever their own parts recorded
+    let num_items = items.len();
+    let mut lens = vec![0; num_items];
+    let mut len = BYTES_PER_LENGTH_OFFSET * num_items;
+    let mut count = 0;
+    for item in items.clone() {
+        if count == num_items {
+            wrong_item_count(num_items);
+        }
+        // len is where the element starts, so its offset is checked before any byte is written
+        check_offset(len);
+        let item_len = item.borrow().ssz_record_lens(&mut lens);
+        lens[count] = item_len;
+        len += item_len;
+        count += 1;
+    }
+    if count != num_items {
+        wrong_item_count(num_items);
+    }
+
+    let mut buf = Vec::with_capacity(len);
+    let (item_lens, mut rest) = lens.split_at(num_items);
+    let mut offset = BYTES_PER_LENGTH_OFFSET * num_items;
+    for item_len in item_lens {
+        expect_offset(write_offset(offset, &mut buf));
+        offset += item_len;
+    }
+    for item in items {
+        item.borrow().ssz_write_cached(&mut rest, &mut buf);
+    }
+    buf
+}
+
+#[cold]
+#[inline(never)]
+fn wrong_item_count(len: usize) -> ! {
+    panic!(
+        "encode_list_iter: the iterator yielded a different number of elements than its len() of {}",
+        len
+    )
+}
@@ -0,0 +1,42 @@
+use crate::SszbEncode;
+use alloc::{sync::Arc, vec::Vec};
+use bytes::{Bytes, BytesMut};
+use std::sync::Mutex;
+
+/// Encodes values into `Bytes`, reusing buffers across calls instead of allocating one per
+/// encode.
+///
+/// Each encode takes a buffer from the pool, writes the value into its spare capacity and splits
+/// the result off as `Bytes`. The buffer then goes back to the pool with whatever capacity is
+/// left, and once every `Bytes` split off an allocation has been dropped, that allocation is
+/// reused from the start. Clones share the same buffers, so a pool can be handed to every thread
+/// that encodes; encodes running at the same time each get their own buffer.
+#[derive(Clone, Debug, Default)]
+pub struct EncoderPool {
+    buffers: Arc<Mutex<Vec<BytesMut>>>,
+}
+
+impl EncoderPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn encode<T: SszbEncode + ?Sized>(&self, value: &T) -> Bytes {
+        let mut buf = self
+            .buffers
+            .lock()
+            .expect("encoder pool lock poisoned")
+            .pop()
+            .unwrap_or_default();
+
+        buf.reserve(value.sszb_bytes_len());
+        value.ssz_write(&mut buf);
+        let bytes = buf.split().freeze();
+
+        self.buffers
+            .lock()
+            .expect("encoder pool lock poisoned")
+            .push(buf);
+        bytes
+    }
+}
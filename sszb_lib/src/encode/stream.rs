@@ -0,0 +1,96 @@
+use crate::SszbEncode;
+use alloc::vec::Vec;
+use bytes::buf::{BufMut, UninitSlice};
+use std::io::{self, Write};
+
+/// Encodes `value` straight into `writer`, through a scratch buffer of `scratch_capacity` bytes,
+/// instead of building the whole encoding in memory first. Returns the number of bytes written.
+///
+/// The lengths of the value's variable parts are measured in a first pass, so the second pass can
+/// write every byte in order. Slices longer than the scratch buffer (byte lists, say) are written
+/// directly rather than copied through it. The output is the same as `to_ssz()`.
+pub fn stream_ssz_to<T: SszbEncode + ?Sized, W: Write>(
+    value: &T,
+    writer: W,
+    scratch_capacity: usize,
+) -> io::Result<u64> {
+    let mut lens = Vec::new();
+    let len = value.ssz_record_lens(&mut lens);
+
+    let mut buf = WriterBuf {
+        writer,
+        scratch: Vec::with_capacity(scratch_capacity.clamp(1, len.max(1))),
+        written: 0,
+        error: None,
+    };
+    value.ssz_write_cached(&mut lens.as_slice(), &mut buf);
+    buf.flush()?;
+    Ok(buf.written)
+}
+
+// a BufMut can't fail, so the first write error is kept and returned once encoding is done.
+// whatever is written after it is dropped
+struct WriterBuf<W> {
+    writer: W,
+    scratch: Vec<u8>,
+    written: u64,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> WriterBuf<W> {
+    fn write(&mut self, bytes: &[u8]) {
+        if self.error.is_none() {
+            match self.writer.write_all(bytes) {
+                Ok(()) => self.written += bytes.len() as u64,
+                Err(error) => self.error = Some(error),
+            }
+        }
+    }
+
+    fn write_scratch(&mut self) {
+        let scratch = core::mem::take(&mut self.scratch);
+        self.write(&scratch);
+        self.scratch = scratch;
+        self.scratch.clear();
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_scratch();
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.writer.flush(),
+        }
+    }
+}
+
+unsafe impl<W: Write> BufMut for WriterBuf<W> {
+    fn remaining_mut(&self) -> usize {
+        usize::MAX
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        let len = self.scratch.len() + cnt;
+        debug_assert!(len <= self.scratch.capacity());
+        self.scratch.set_len(len);
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        if self.scratch.len() == self.scratch.capacity() {
+            self.write_scratch();
+        }
+        UninitSlice::uninit(self.scratch.spare_capacity_mut())
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        if self.scratch.len() + src.len() <= self.scratch.capacity() {
+            self.scratch.extend_from_slice(src);
+        } else {
+            self.write_scratch();
+            if src.len() < self.scratch.capacity() {
+                self.scratch.extend_from_slice(src);
+            } else {
+                self.write(src);
+            }
+        }
+    }
+}
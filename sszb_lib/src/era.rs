@@ -0,0 +1,286 @@
+//! Reading and writing e2store files, the framing used by era files of archived chain history.
+//!
+//! An e2store file is a sequence of entries, each an 8 byte header (a 2 byte type tag, a `u32`
+//! little-endian length and 2 reserved zero bytes) followed by that many bytes of data. The file
+//! starts with an empty version entry. Era files store blocks and states as snappy-framed SSZ,
+//! which `read_compressed_entry` and `write_compressed_entry` take care of.
+//!
+//! Anything that implements `Read` can be read from, including the bytes of a memory-mapped
+//! `sszb::fs::SszFile` (with the `mmap` feature) as a `&[u8]`.
+
+use crate::{DecodeError, EncodeError, SszbDecode, SszbEncode};
+use alloc::vec::Vec;
+use std::io::{Read, Write};
+
+/// Length of the header in front of every entry.
+pub const HEADER_LEN: usize = 8;
+
+/// The type tag of an entry.
+pub type TypeTag = [u8; 2];
+
+/// The entry every e2store file starts with.
+pub const VERSION: TypeTag = [0x65, 0x32];
+pub const EMPTY: TypeTag = [0x00, 0x00];
+pub const COMPRESSED_SIGNED_BEACON_BLOCK: TypeTag = [0x01, 0x00];
+pub const COMPRESSED_BEACON_STATE: TypeTag = [0x02, 0x00];
+pub const SLOT_INDEX: TypeTag = [0x69, 0x32];
+
+#[derive(Debug)]
+pub enum EraError {
+    Io(std::io::Error),
+    Decode(DecodeError),
+    /// A value couldn't be encoded, nothing is written.
+    Encode(EncodeError),
+    /// The file doesn't start with a version entry, so it isn't an e2store file.
+    MissingVersion(TypeTag),
+    /// The file ended `len` bytes into an entry's header.
+    TruncatedHeader {
+        len: usize,
+    },
+    /// The file ended `len` bytes into an entry's data, which should have been `expected` bytes.
+    TruncatedEntry {
+        len: usize,
+        expected: usize,
+    },
+    /// The reserved bytes of an entry's header weren't zero.
+    ReservedBytesSet([u8; 2]),
+    /// An entry had another type than the one asked for.
+    UnexpectedType {
+        found: TypeTag,
+        expected: TypeTag,
+    },
+    /// An entry was longer than its length field can hold when writing, or decompressed to more
+    /// than the maximum length of its type when reading, in which case decompression stopped
+    /// after `len` bytes.
+    EntryTooLarge {
+        len: usize,
+        max: usize,
+    },
+    /// An entry's data isn't valid snappy framing.
+    InvalidCompression(std::io::Error),
+}
+
+impl core::fmt::Display for EraError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EraError::Io(error) => write!(f, "{}", error),
+            EraError::Decode(error) => write!(f, "Invalid entry: {}", error),
+            EraError::Encode(error) => write!(f, "Invalid value: {}", error),
+            EraError::MissingVersion(tag) => {
+                write!(
+                    f,
+                    "File starts with an entry of type {:02x?} instead of a version",
+                    tag
+                )
+            }
+            EraError::TruncatedHeader { len } => {
+                write!(f, "File ends {} bytes into an entry header", len)
+            }
+            EraError::TruncatedEntry { len, expected } => {
+                write!(f, "Entry of {} bytes ends after {}", expected, len)
+            }
+            EraError::ReservedBytesSet(reserved) => {
+                write!(f, "Reserved header bytes {:02x?} aren't zero", reserved)
+            }
+            EraError::UnexpectedType { found, expected } => {
+                write!(
+                    f,
+                    "Entry of type {:02x?}, expected {:02x?}",
+                    found, expected
+                )
+            }
+            EraError::EntryTooLarge { len, max } => {
+                write!(f, "Entry of {} bytes exceeds maximum of {}", len, max)
+            }
+            EraError::InvalidCompression(error) => write!(f, "Invalid snappy framing: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for EraError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EraError::Io(error) | EraError::InvalidCompression(error) => Some(error),
+            EraError::Decode(error) => Some(error),
+            EraError::Encode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for EraError {
+    fn from(error: std::io::Error) -> Self {
+        EraError::Io(error)
+    }
+}
+
+impl From<DecodeError> for EraError {
+    fn from(error: DecodeError) -> Self {
+        EraError::Decode(error)
+    }
+}
+
+impl From<EncodeError> for EraError {
+    fn from(error: EncodeError) -> Self {
+        EraError::Encode(error)
+    }
+}
+
+/// Reads the entries of an e2store file, after checking that it starts with a version entry.
+/// Later version entries, which start each group of a file holding several, are read like any
+/// other entry.
+#[derive(Debug)]
+pub struct E2StoreReader<R> {
+    reader: R,
+}
+
+impl<R: Read> E2StoreReader<R> {
+    pub fn new(reader: R) -> Result<Self, EraError> {
+        let mut reader = Self { reader };
+        match reader.next_entry()? {
+            Some((VERSION, _)) => Ok(reader),
+            Some((tag, _)) => Err(EraError::MissingVersion(tag)),
+            None => Err(EraError::TruncatedHeader { len: 0 }),
+        }
+    }
+
+    /// The type tag and data of the next entry, or `None` at the end of the file.
+    pub fn next_entry(&mut self) -> Result<Option<(TypeTag, Vec<u8>)>, EraError> {
+        let mut header = [0u8; HEADER_LEN];
+        let mut filled = 0;
+        while filled < HEADER_LEN {
+            match self.reader.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err(EraError::TruncatedHeader { len: filled }),
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        let tag = [header[0], header[1]];
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        if header[6..8] != [0, 0] {
+            return Err(EraError::ReservedBytesSet([header[6], header[7]]));
+        }
+
+        // read through take, so a corrupt length can't make us allocate more than the file holds
+        let mut data = Vec::new();
+        (&mut self.reader).take(len as u64).read_to_end(&mut data)?;
+        if data.len() < len {
+            return Err(EraError::TruncatedEntry {
+                len: data.len(),
+                expected: len,
+            });
+        }
+        Ok(Some((tag, data)))
+    }
+
+    /// Decodes the next entry, which has to be of type `expected`, as uncompressed SSZ.
+    pub fn read_entry<T: SszbDecode>(&mut self, expected: TypeTag) -> Result<Option<T>, EraError> {
+        self.next_entry_of(expected)?
+            .map(|data| Ok(T::from_ssz_bytes(&data)?))
+            .transpose()
+    }
+
+    /// Decodes the next entry, which has to be of type `expected`, as snappy-framed SSZ. The
+    /// entry may decompress to at most `T::SSZ_MAX_LEN` bytes.
+    pub fn read_compressed_entry<T: SszbDecode>(
+        &mut self,
+        expected: TypeTag,
+    ) -> Result<Option<T>, EraError> {
+        let Some(data) = self.next_entry_of(expected)? else {
+            return Ok(None);
+        };
+
+        let max = <T as SszbDecode>::SSZ_MAX_LEN;
+        let mut bytes = Vec::new();
+        snap::read::FrameDecoder::new(data.as_slice())
+            .take((max as u64).saturating_add(1))
+            .read_to_end(&mut bytes)
+            .map_err(EraError::InvalidCompression)?;
+        if bytes.len() > max {
+            return Err(EraError::EntryTooLarge {
+                len: bytes.len(),
+                max,
+            });
+        }
+        Ok(Some(T::from_ssz_bytes(&bytes)?))
+    }
+
+    fn next_entry_of(&mut self, expected: TypeTag) -> Result<Option<Vec<u8>>, EraError> {
+        match self.next_entry()? {
+            Some((tag, data)) if tag == expected => Ok(Some(data)),
+            Some((found, _)) => Err(EraError::UnexpectedType { found, expected }),
+            None => Ok(None),
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for E2StoreReader<R> {
+    type Item = Result<(TypeTag, Vec<u8>), EraError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().transpose()
+    }
+}
+
+/// Writes an e2store file, starting with its version entry.
+#[derive(Debug)]
+pub struct E2StoreWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    pub fn new(writer: W) -> Result<Self, EraError> {
+        let mut writer = Self { writer };
+        writer.write_raw_entry(VERSION, &[])?;
+        Ok(writer)
+    }
+
+    /// Writes an entry of the given type holding `data` as is.
+    pub fn write_raw_entry(&mut self, tag: TypeTag, data: &[u8]) -> Result<(), EraError> {
+        let len = u32::try_from(data.len()).map_err(|_| EraError::EntryTooLarge {
+            len: data.len(),
+            max: u32::MAX as usize,
+        })?;
+
+        let mut header = [0u8; HEADER_LEN];
+        header[0..2].copy_from_slice(&tag);
+        header[2..6].copy_from_slice(&len.to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(data)?;
+        Ok(())
+    }
+
+    /// Writes `value` as an entry of uncompressed SSZ.
+    pub fn write_entry<T: SszbEncode>(&mut self, tag: TypeTag, value: &T) -> Result<(), EraError> {
+        self.write_raw_entry(tag, &value.try_to_ssz()?)
+    }
+
+    /// Writes `value` as an entry of snappy-framed SSZ, like the blocks and states of era files.
+    pub fn write_compressed_entry<T: SszbEncode>(
+        &mut self,
+        tag: TypeTag,
+        value: &T,
+    ) -> Result<(), EraError> {
+        let mut data = Vec::new();
+        let mut encoder = snap::write::FrameEncoder::new(&mut data);
+        encoder.write_all(&value.try_to_ssz()?)?;
+        encoder.flush()?;
+        drop(encoder);
+        self.write_raw_entry(tag, &data)
+    }
+
+    pub fn flush(&mut self) -> Result<(), EraError> {
+        Ok(self.writer.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,39 @@
+//! What the `ethereum_ssz` impls generated for `#[ssz(compat = "ethereum_ssz")]` containers need
+//! beyond the re-exported crate: their errors as `ssz::DecodeError`s.
+
+use crate::DecodeError;
+use alloc::string::ToString;
+
+// errors ethereum_ssz has a variant for keep it, without their position, and the rest are
+// described in full
+impl From<DecodeError> for ssz::DecodeError {
+    fn from(error: DecodeError) -> Self {
+        match *error.root_cause() {
+            DecodeError::InvalidByteLength { len, expected } => {
+                ssz::DecodeError::InvalidByteLength { len, expected }
+            }
+            DecodeError::InvalidLengthPrefix { len, expected } => {
+                ssz::DecodeError::InvalidLengthPrefix { len, expected }
+            }
+            DecodeError::OutOfBoundsByte { i } => ssz::DecodeError::OutOfBoundsByte { i },
+            DecodeError::OffsetIntoFixedPortion(offset) => {
+                ssz::DecodeError::OffsetIntoFixedPortion(offset)
+            }
+            DecodeError::OffsetSkipsVariableBytes(offset) => {
+                ssz::DecodeError::OffsetSkipsVariableBytes(offset)
+            }
+            DecodeError::OffsetsAreDecreasing(offset) => {
+                ssz::DecodeError::OffsetsAreDecreasing(offset)
+            }
+            DecodeError::OffsetOutOfBounds(offset) => ssz::DecodeError::OffsetOutOfBounds(offset),
+            DecodeError::InvalidListFixedBytesLen(len) => {
+                ssz::DecodeError::InvalidListFixedBytesLen(len)
+            }
+            DecodeError::ZeroLengthItem => ssz::DecodeError::ZeroLengthItem,
+            DecodeError::UnionSelectorInvalid(selector) => {
+                ssz::DecodeError::UnionSelectorInvalid(selector)
+            }
+            _ => ssz::DecodeError::BytesInvalid(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use crate::decode::decode_impls::read_byte_arrays;
+use crate::dynamic::{SszTypeInfo, SszbTypeInfo};
+use crate::encode::encode_impls::{byte_arrays, write_byte_run_vectored};
+use crate::vectored::Segments;
+use crate::{DecodeError, SszZero, SszbDecode, SszbEncode};
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+use ethereum_types::{H160, H256, H32};
+
+impl SszbEncode for H32 {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 4;
+    const SSZ_MAX_LEN: usize = 4;
+    const SSZ_POD: bool = true;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.as_bytes());
+    }
+
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        // SAFETY: the fixed-hash types are repr(C) wrappers around their bytes
+        buf.put_slice(unsafe { byte_arrays::<Self, 4>(items) });
+    }
+
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        // SAFETY: see ssz_write_packed
+        write_byte_run_vectored(unsafe { byte_arrays::<Self, 4>(items) }, segments);
+    }
+}
+
+impl SszbDecode for H32 {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 4;
+    const SSZ_MAX_LEN: usize = 4;
+    const SSZ_POD: bool = true;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let mut bytes = [0u8; 4];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(Self::from_slice(&bytes))
+        }
+    }
+
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut items = Vec::new();
+        read_byte_arrays::<Self, 4>(bytes, &mut items)?;
+        Ok(items)
+    }
+
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        read_byte_arrays::<Self, 4>(bytes, items)
+    }
+}
+
+impl SszbEncode for H160 {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 20;
+    const SSZ_MAX_LEN: usize = 20;
+    const SSZ_POD: bool = true;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.as_bytes());
+    }
+
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        // SAFETY: the fixed-hash types are repr(C) wrappers around their bytes
+        buf.put_slice(unsafe { byte_arrays::<Self, 20>(items) });
+    }
+
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        // SAFETY: see ssz_write_packed
+        write_byte_run_vectored(unsafe { byte_arrays::<Self, 20>(items) }, segments);
+    }
+}
+
+impl SszbDecode for H160 {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 20;
+    const SSZ_MAX_LEN: usize = 20;
+    const SSZ_POD: bool = true;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let mut bytes = [0u8; 20];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(Self::from_slice(&bytes))
+        }
+    }
+
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut items = Vec::new();
+        read_byte_arrays::<Self, 20>(bytes, &mut items)?;
+        Ok(items)
+    }
+
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        read_byte_arrays::<Self, 20>(bytes, items)
+    }
+}
+
+impl SszbEncode for H256 {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 32;
+    const SSZ_MAX_LEN: usize = 32;
+    const SSZ_POD: bool = true;
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.as_bytes());
+    }
+
+    fn ssz_write_packed(items: &[Self], buf: &mut impl BufMut) {
+        // SAFETY: the fixed-hash types are repr(C) wrappers around their bytes
+        buf.put_slice(unsafe { byte_arrays::<Self, 32>(items) });
+    }
+
+    fn ssz_write_packed_vectored<'segments>(
+        items: &'segments [Self],
+        segments: &mut Segments<'segments>,
+    ) {
+        // SAFETY: see ssz_write_packed
+        write_byte_run_vectored(unsafe { byte_arrays::<Self, 32>(items) }, segments);
+    }
+}
+
+impl SszbDecode for H256 {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 32;
+    const SSZ_MAX_LEN: usize = 32;
+    const SSZ_POD: bool = true;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            let mut bytes = [0u8; 32];
+            fixed_bytes.copy_to_slice(&mut bytes);
+            Ok(Self::from_slice(&bytes))
+        }
+    }
+
+    fn ssz_read_packed(bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut items = Vec::new();
+        read_byte_arrays::<Self, 32>(bytes, &mut items)?;
+        Ok(items)
+    }
+
+    fn ssz_read_packed_into(bytes: &[u8], items: &mut Vec<Self>) -> Result<(), DecodeError> {
+        read_byte_arrays::<Self, 32>(bytes, items)
+    }
+}
+
+impl SszbTypeInfo for H32 {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 4)
+    }
+}
+
+impl SszbTypeInfo for H160 {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 20)
+    }
+}
+
+impl SszbTypeInfo for H256 {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 32)
+    }
+}
+
+impl SszZero for H32 {
+    fn ssz_zero() -> Self {
+        H32::zero()
+    }
+}
+
+impl SszZero for H160 {
+    fn ssz_zero() -> Self {
+        H160::zero()
+    }
+}
+
+impl SszZero for H256 {
+    fn ssz_zero() -> Self {
+        H256::zero()
+    }
+}
@@ -0,0 +1,82 @@
+//! Decoding SSZ files through a memory map, without reading them into memory first.
+
+use crate::{DecodeError, SszbDecode, SszbDecodeRef};
+use memmap2::Mmap;
+use std::{fs::File, path::Path};
+
+#[derive(Debug)]
+pub enum FileError {
+    /// The file couldn't be opened or mapped.
+    Io(std::io::Error),
+    Decode(DecodeError),
+}
+
+impl core::fmt::Display for FileError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FileError::Io(error) => write!(f, "{}", error),
+            FileError::Decode(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileError::Io(error) => Some(error),
+            FileError::Decode(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for FileError {
+    fn from(error: std::io::Error) -> Self {
+        FileError::Io(error)
+    }
+}
+
+impl From<DecodeError> for FileError {
+    fn from(error: DecodeError) -> Self {
+        FileError::Decode(error)
+    }
+}
+
+/// A memory-mapped SSZ file, whose bytes can be decoded (or borrowed from, see `decode_ref`)
+/// without copying the file into memory.
+///
+/// The file must not be modified while it's mapped: the bytes would change under the decoder,
+/// which is undefined behaviour.
+#[derive(Debug)]
+pub struct SszFile {
+    mmap: Mmap,
+}
+
+impl SszFile {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        let file = File::open(path)?;
+        // SAFETY: the caller promises not to modify the file while it's mapped, see above
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self { mmap })
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    pub fn decode<T: SszbDecode>(&self) -> Result<T, DecodeError> {
+        T::from_ssz_bytes(self.bytes())
+    }
+
+    /// Decodes a type whose fields may borrow from the mapped bytes, which stay mapped for as
+    /// long as it lives.
+    pub fn decode_ref<'a, T: SszbDecodeRef<'a>>(&'a self) -> Result<T, DecodeError> {
+        T::from_ssz_bytes_ref(self.bytes())
+    }
+}
+
+/// Decodes the file at `path` through a memory map, so large files (archived states, say)
+/// aren't read into memory before decoding. The same caveat as for `SszFile` applies: the file
+/// must not be modified while it's being decoded.
+pub fn from_ssz_file<T: SszbDecode>(path: impl AsRef<Path>) -> Result<T, FileError> {
+    Ok(SszFile::open(path)?.decode()?)
+}
@@ -0,0 +1,167 @@
+//! `heapless::Vec<T, N>` as an SSZ list with a maximum length of `N`, for consumers without a
+//! heap to spare. Decoding pushes each item straight into the vector once the length has been
+//! checked against `N`, so apart from what `T` itself allocates, nothing is allocated unless the
+//! bytes are split across chunks of a `Buf` and have to be gathered first.
+
+use crate::decode::decode_impls::{validate_list, VariableItemRanges};
+use crate::decode::read_contiguous;
+use crate::encode::{record_item_lens, write_items_cached, write_items_slice, write_to_slice};
+use crate::raw::{peek_list_len_static, peek_list_len_variable, split_offset_table};
+use crate::vectored::Segments;
+use crate::{
+    check_items_bounds, enclosed_max_len, expect_len, expect_offset, try_items_bytes_len,
+    write_offset, DecodeError, EncodeError, SszZero, SszbDecode, SszbEncode,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+
+impl<T: SszbEncode, const N: usize> SszbEncode for heapless::Vec<T, N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize =
+        enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN).saturating_mul(N);
+
+    fn sszb_bytes_len(&self) -> usize {
+        expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+    }
+
+    fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+        try_items_bytes_len::<T>(self.len(), self.iter())
+    }
+
+    // the capacity is the maximum, so only the items are checked
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        check_items_bounds::<T>(self.len(), N, self.iter())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        if T::SSZ_STATIC {
+            T::ssz_write_packed(self, buf);
+        } else {
+            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+            for item in self {
+                item.ssz_write_fixed(offset, buf);
+            }
+            for item in self {
+                item.ssz_write(buf);
+            }
+        }
+    }
+
+    fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+        if T::SSZ_STATIC {
+            T::ssz_write_packed_vectored(self, segments);
+        } else {
+            let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+            for item in self {
+                item.ssz_write_fixed(offset, segments.scratch());
+            }
+            for item in self {
+                item.ssz_write_vectored(segments);
+            }
+        }
+    }
+
+    fn ssz_record_lens(&self, lens: &mut Vec<usize>) -> usize {
+        if T::SSZ_STATIC {
+            self.sszb_bytes_len()
+        } else {
+            record_item_lens(self.len(), self.iter(), lens)
+        }
+    }
+
+    fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+        if T::SSZ_STATIC {
+            self.ssz_write(buf);
+        } else {
+            write_items_cached(self.len(), self.iter(), lens, buf);
+        }
+    }
+
+    fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+        if T::SSZ_STATIC {
+            write_to_slice(buf, |rest| self.ssz_write(rest))
+        } else {
+            write_items_slice(self.len(), self.iter(), buf)
+        }
+    }
+}
+
+// decodes the body of a list into a heapless vector, failing before decoding any item if the
+// list holds more than N of them
+fn read_list<T: SszbDecode, const N: usize>(
+    bytes: &[u8],
+) -> Result<heapless::Vec<T, N>, DecodeError> {
+    let mut items = heapless::Vec::new();
+    if bytes.is_empty() {
+        return Ok(items);
+    }
+
+    let num_items = if T::SSZ_STATIC {
+        peek_list_len_static(bytes, T::SSZ_FIXED_LEN)?
+    } else {
+        peek_list_len_variable(bytes)?
+    };
+    crate::enter_trace_span!("ssz_decode_list", items = num_items, len = bytes.len());
+    let too_long = || DecodeError::ListTooLong {
+        len: num_items,
+        max: N,
+    };
+    if num_items > N {
+        return Err(too_long());
+    }
+
+    if T::SSZ_STATIC {
+        let item_len = T::SSZ_FIXED_LEN;
+        for (i, chunk) in bytes.chunks_exact(item_len).enumerate() {
+            let item = T::from_ssz_bytes(chunk).map_err(|e| e.at_byte(i * item_len))?;
+            items.push(item).map_err(|_| too_long())?;
+        }
+    } else {
+        // peek_list_len_variable has already checked the first offset
+        let (offsets, _) = split_offset_table(bytes, num_items)?;
+        for range in VariableItemRanges::checked(offsets, bytes.len())? {
+            let range = range?;
+            let item =
+                T::from_ssz_bytes(&bytes[range.clone()]).map_err(|e| e.at_byte(range.start))?;
+            items.push(item).map_err(|_| too_long())?;
+        }
+    }
+    Ok(items)
+}
+
+impl<T: SszbDecode, const N: usize> SszbDecode for heapless::Vec<T, N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize =
+        enclosed_max_len(T::SSZ_STATIC, T::SSZ_FIXED_LEN, T::SSZ_MAX_LEN).saturating_mul(N);
+    const SSZ_SCHEMA: [u8; 32] = crate::schema::list(T::SSZ_SCHEMA, N);
+
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, len, read_list::<T, N>)
+    }
+
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        validate_list::<T>(bytes, N)
+    }
+}
+
+impl<T, const N: usize> SszZero for heapless::Vec<T, N> {
+    fn ssz_zero() -> Self {
+        heapless::Vec::new()
+    }
+}
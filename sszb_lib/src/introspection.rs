@@ -0,0 +1,56 @@
+//! Where a container's encoded bytes go, enabled by the `introspection` feature.
+//!
+//! With it, every derived container gets a `ssz_size_breakdown()` listing each encoded field with
+//! its share of `sszb_bytes_len()`: the field's own encoding, plus the offset a variable field
+//! takes up in the container's fixed part. The shares always sum to `sszb_bytes_len()`, so e.g.
+//! the validators, balances and inactivity scores of a `BeaconState` can be weighed against its
+//! whole encoding. `size_breakdown_table` renders one for a log or a terminal.
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+/// Renders a `ssz_size_breakdown()` as a table of the fields, largest first, with their bytes and
+/// share of the total, followed by the total itself. Fields of the same size keep their order.
+pub fn size_breakdown_table(breakdown: &[(&str, usize)]) -> String {
+    let mut rows: Vec<_> = breakdown.to_vec();
+    rows.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let total: usize = rows.iter().map(|(_, len)| len).sum();
+
+    let name_width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(["field".len(), "total".len()])
+        .max()
+        .unwrap_or(0);
+    let share = |len: usize| {
+        if total == 0 {
+            0.0
+        } else {
+            len as f64 * 100.0 / total as f64
+        }
+    };
+
+    let mut table = String::new();
+    // writing into a String can't fail
+    let _ = writeln!(
+        table,
+        "{:<name_width$} {:>12} {:>7}",
+        "field", "bytes", "share"
+    );
+    for (name, len) in &rows {
+        let _ = writeln!(
+            table,
+            "{:<name_width$} {:>12} {:>6.1}%",
+            name,
+            len,
+            share(*len)
+        );
+    }
+    let _ = writeln!(
+        table,
+        "{:<name_width$} {:>12} {:>6.1}%",
+        "total",
+        total,
+        share(total)
+    );
+    table
+}
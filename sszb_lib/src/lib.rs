@@ -1,18 +1,197 @@
+#![no_std]
+
+// the crate itself only needs `core` and `alloc`, the collection and BLS
+// impls are gated behind features that pull in `std`
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+// the fixtures in `test_utils` are derived, and the derived code names the crate `sszb`
+#[cfg(feature = "test-utils")]
+extern crate self as sszb;
+
+#[cfg(feature = "alloy")]
+mod alloy_impls;
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+mod assertions;
+#[cfg(feature = "bitvec")]
+mod bitvec_impls;
+#[cfg(feature = "collections")]
+mod btree_impls;
+mod bytes_impls;
+#[cfg(feature = "codec")]
+pub mod codec;
+#[cfg(any(feature = "ssz-types", feature = "milhouse", feature = "ghilhouse"))]
+mod collections;
+#[cfg(feature = "std")]
+pub mod context;
 mod decode;
+mod decode_ref;
+pub mod dynamic;
 mod encode;
+#[cfg(feature = "era")]
+pub mod era;
+#[cfg(feature = "ethereum-ssz")]
+mod ethereum_ssz_impls;
+#[cfg(feature = "ethereum-types")]
+mod ethereum_types_impls;
+#[cfg(feature = "mmap")]
+pub mod fs;
+#[cfg(feature = "ghilhouse")]
 mod ghilhouse_impls;
+#[cfg(feature = "ethereum-types")]
 mod hash;
+#[cfg(feature = "heapless")]
+mod heapless_impls;
+#[cfg(feature = "introspection")]
+pub mod introspection;
+pub mod merkle;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "milhouse")]
+mod milhouse_impls;
+pub mod offsets;
+pub mod parallel;
+pub mod path;
+#[cfg(feature = "pod")]
+#[doc(hidden)]
+pub mod pod;
+pub mod prelude;
+pub mod raw;
+pub mod runtime;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde_utils;
+#[cfg(feature = "bls")]
 mod sig;
+#[cfg(any(feature = "ssz-types", feature = "milhouse", feature = "ghilhouse"))]
+mod slice_impls;
+#[cfg(feature = "ssz-types")]
+mod ssz_types_impls;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod union;
+pub mod vectored;
+mod wrapper;
+mod zero;
 
 pub const BYTES_PER_LENGTH_OFFSET: usize = 4;
 pub const N: usize = 1_000;
 
+// number of bytes used to serialize a bit vector of `bits` bits, which is none for the zero-length
+// bit vectors that only exist to be rejected by decoding
+pub(crate) const fn bitfield_bytes_len(bits: usize) -> usize {
+    bits.div_ceil(8)
+}
+
+// number of bytes used to serialize a bitlist of `bits` bits, with the bit that marks its end
+#[cfg(feature = "ssz-types")]
+pub(crate) const fn bitlist_bytes_len(bits: usize) -> usize {
+    bits / 8 + 1
+}
+
+/// The most bytes a field of a container, or an item of a list or vector, takes up: all of it
+/// if it's static, otherwise its offset as well as its `SSZ_MAX_LEN`. Both the encode and decode
+/// impls of composite types add up their `SSZ_MAX_LEN` with this, so the two always agree.
+#[doc(hidden)]
+pub const fn enclosed_max_len(ssz_static: bool, fixed_len: usize, max_len: usize) -> usize {
+    if ssz_static {
+        fixed_len
+    } else {
+        max_len.saturating_add(BYTES_PER_LENGTH_OFFSET)
+    }
+}
+
+pub use bytes_impls::ByteList;
+#[cfg(any(feature = "ssz-types", feature = "milhouse", feature = "ghilhouse"))]
+pub use collections::{SszList, SszVector};
+#[cfg(feature = "tiny-errors")]
+pub use decode::DecodeErrorKind;
 pub use decode::{
-    decode_impls::*, read_offset_from_buf, read_offset_from_slice, sanitize_offset, DecodeError,
-    SszbDecode,
+    check_decoded, decode_impls::*, invalid_value, iter_ssz_list, read_contiguous,
+    read_through_reader, validate_ssz, DecodeError, SszListIter, SszReader, SszbDecode,
+};
+pub use decode_ref::{
+    container_field_range, container_field_range_trusted, container_field_ranges,
+    container_field_ranges_lenient, visit_container_fields, SszbDecodeRef,
 };
 pub use encode::*;
+#[cfg(feature = "metrics")]
+pub use metrics::set_decode_observer;
+pub use offsets::read_offset_from_buf;
+#[allow(deprecated)]
+pub use offsets::{read_offset_from_slice, sanitize_offset};
+
+/// The earlier name of [`SszbDecode`]. Deprecated: `SszbDecode` is the trait the derive
+/// implements, use it instead.
+pub use decode::SszbDecode as SszDecode;
+/// The earlier name of [`SszbEncode`]. Deprecated: `SszbEncode` is the trait the derive
+/// implements, use it instead.
+pub use encode::SszbEncode as SszEncode;
+#[cfg(feature = "ethereum-types")]
 pub use hash::SszHash;
+pub use union::{Union2, Union3, Union4};
+#[doc(hidden)]
+pub use wrapper::wrap_decoded;
+pub use zero::SszZero;
 
+#[cfg(feature = "ghilhouse")]
 pub use ghilhouse_impls::*;
+#[cfg(feature = "bls")]
 pub use sig::*;
+
+// re-exported so derived code can name it in `#![no_std]` crates too
+#[doc(hidden)]
+pub use alloc::vec::Vec;
+
+// re-exported so code generated by `#[ssz(arbitrary)]` doesn't need its own dependency
+#[cfg(feature = "arbitrary")]
+pub use arbitrary;
+
+// re-exported so code generated by `#[ssz(compat = "ethereum_ssz")]` doesn't need its own
+// dependency
+#[doc(hidden)]
+#[cfg(feature = "ethereum-ssz")]
+pub use ssz as ethereum_ssz;
+
+#[doc(hidden)]
+#[cfg(feature = "tracing")]
+pub use tracing;
+
+/// Enters a `trace_span!` until the end of the enclosing block, with the `tracing` feature.
+/// Without it nothing is expanded, so not even the span's fields are evaluated.
+#[doc(hidden)]
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! enter_trace_span {
+    ($($span: tt)*) => {
+        let _span = $crate::tracing::trace_span!($($span)*).entered();
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! enter_trace_span {
+    ($($span: tt)*) => {};
+}
+
+/// Expands to the items it's given with the `introspection` feature, and to nothing without it,
+/// so derived code can emit them without knowing which features the crate was built with.
+#[doc(hidden)]
+#[cfg(feature = "introspection")]
+#[macro_export]
+macro_rules! if_introspection {
+    ($($item: tt)*) => {
+        $($item)*
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "introspection"))]
+#[macro_export]
+macro_rules! if_introspection {
+    ($($item: tt)*) => {};
+}
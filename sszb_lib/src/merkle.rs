@@ -0,0 +1,189 @@
+//! Generalized indices into the hash tree of an SSZ type, for building and checking Merkle proofs
+//! such as those of light clients.
+//!
+//! A generalized index numbers the nodes of the tree breadth first, the root being 1 and the
+//! children of node `i` being `2i` and `2i + 1`. It only depends on the layout, so it's computed
+//! from an `SszTypeInfo` and a path of field names, item indices and `__len__`, following the
+//! chunk counts and tree depths of the consensus specs' `get_generalized_index`:
+//!
+//! ```ignore
+//! let schema = BeaconState::ssz_type_info();
+//! assert_eq!(gindex(&schema, &["finalized_checkpoint", "root"]), Ok(105));
+//! gindex(&schema, &["validators", "12", "pubkey"])?;
+//! gindex(&schema, &["validators", "__len__"])?;
+//! ```
+//!
+//! Items of lists and vectors, and bits of bitfields, are named by their index. Basic items
+//! share chunks, so the index of one is that of the chunk holding it. A list's or bitlist's
+//! `__len__` is the length mixed into its root. In a union, `__selector__` is the selector mixed
+//! into its root, and the selector of a variant names the root of its value.
+
+use crate::dynamic::SszTypeInfo;
+use alloc::string::String;
+
+const BYTES_PER_CHUNK: usize = 32;
+
+/// A path that doesn't lead anywhere in the tree of its type.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GindexError {
+    /// The container has no field by that name.
+    UnknownField(String),
+    /// A list, vector or bitfield item, or a union variant, wasn't named by a number.
+    InvalidIndex(String),
+    /// The item is past the length (or maximum length) of the vector or list, or the variant
+    /// past the union's last.
+    IndexOutOfRange { index: usize, len: usize },
+    /// The path goes on past an integer, boolean or bit, which are leaves.
+    NotComposite(String),
+    /// `__len__` was asked of something other than a list or bitlist, or `__selector__` of
+    /// something other than a union.
+    NoSuchNode(String),
+    /// The index doesn't fit in a `u64`, the tree being deeper than 63 levels.
+    Overflow,
+}
+
+impl core::fmt::Display for GindexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GindexError::UnknownField(field) => write!(f, "no field named `{}`", field),
+            GindexError::InvalidIndex(index) => write!(f, "`{}` isn't an index", index),
+            GindexError::IndexOutOfRange { index, len } => {
+                write!(f, "index {} is out of range for length {}", index, len)
+            }
+            GindexError::NotComposite(step) => {
+                write!(f, "`{}` goes past a leaf of the tree", step)
+            }
+            GindexError::NoSuchNode(step) => write!(f, "`{}` doesn't apply here", step),
+            GindexError::Overflow => write!(f, "the generalized index overflows a u64"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GindexError {}
+
+/// The generalized index of the node that `path` leads to in the hash tree of `schema`.
+pub fn gindex(schema: &SszTypeInfo, path: &[&str]) -> Result<u64, GindexError> {
+    let mut typ = Some(schema);
+    let mut root: u64 = 1;
+    for &step in path {
+        // integers, booleans and bits are leaves, with nothing inside to name
+        let Some(composite) = typ else {
+            return Err(GindexError::NotComposite(step.into()));
+        };
+        let (depth, position, next) = descend(composite, step)?;
+        root = root
+            .checked_shl(depth)
+            .filter(|shifted| shifted >> depth == root)
+            .and_then(|shifted| shifted.checked_add(position))
+            .ok_or(GindexError::Overflow)?;
+        typ = next;
+    }
+    Ok(root)
+}
+
+// the number of levels below `typ`'s root to the node `step` names, that node's position among
+// those at its level, and its type, or None for a leaf that the path can't go into
+fn descend<'a>(
+    typ: &'a SszTypeInfo,
+    step: &str,
+) -> Result<(u32, u64, Option<&'a SszTypeInfo>), GindexError> {
+    match typ {
+        SszTypeInfo::Uint(_) | SszTypeInfo::Bool => Err(GindexError::NotComposite(step.into())),
+        SszTypeInfo::Container(_) if step == "__len__" || step == "__selector__" => {
+            Err(GindexError::NoSuchNode(step.into()))
+        }
+        SszTypeInfo::Container(fields) => {
+            let index = fields
+                .iter()
+                .position(|(name, _)| name == step)
+                .ok_or_else(|| GindexError::UnknownField(step.into()))?;
+            Ok((depth(fields.len()), index as u64, Some(&fields[index].1)))
+        }
+        SszTypeInfo::Vector { item, len } => item_node(item, *len, step),
+        SszTypeInfo::List { item, max_len } => {
+            if step == "__len__" {
+                return Ok((1, 1, None));
+            }
+            // the items' tree is the left child, next to the length
+            let (depth, position, next) = item_node(item, *max_len, step)?;
+            Ok((depth + 1, position, next))
+        }
+        SszTypeInfo::Bitvector(bits) => {
+            let (depth, position) = bit_node(*bits, step)?;
+            Ok((depth, position, None))
+        }
+        SszTypeInfo::Bitlist(max_bits) => {
+            if step == "__len__" {
+                return Ok((1, 1, None));
+            }
+            let (depth, position) = bit_node(*max_bits, step)?;
+            Ok((depth + 1, position, None))
+        }
+        SszTypeInfo::Union(variants) => {
+            // the value's root is the left child, next to the selector
+            if step == "__selector__" {
+                return Ok((1, 1, None));
+            }
+            let selector = parse_index(step, variants.len())?;
+            match &variants[selector] {
+                Some(variant) => Ok((1, 0, Some(variant))),
+                // an empty variant's value is a zero chunk
+                None => Ok((1, 0, None)),
+            }
+        }
+    }
+}
+
+// the node of item `step` of a vector or list of `len` (or at most `len`) items, in the tree of
+// the items alone. Basic items are packed into chunks, several to a chunk
+fn item_node<'a>(
+    item: &'a SszTypeInfo,
+    len: usize,
+    step: &str,
+) -> Result<(u32, u64, Option<&'a SszTypeInfo>), GindexError> {
+    let index = parse_index(step, len)?;
+    match basic_len(item) {
+        Some(item_len) => {
+            let chunks = (len * item_len).div_ceil(BYTES_PER_CHUNK);
+            let chunk = index * item_len / BYTES_PER_CHUNK;
+            Ok((depth(chunks), chunk as u64, None))
+        }
+        None => Ok((depth(len), index as u64, Some(item))),
+    }
+}
+
+// the node of the chunk holding bit `step` of a bitfield of `bits` (or at most `bits`) bits
+fn bit_node(bits: usize, step: &str) -> Result<(u32, u64), GindexError> {
+    let index = parse_index(step, bits)?;
+    let chunks = bits.div_ceil(BYTES_PER_CHUNK * 8);
+    Ok((depth(chunks), (index / (BYTES_PER_CHUNK * 8)) as u64))
+}
+
+// the index of an item, bit or variant, out of `len`
+fn parse_index(step: &str, len: usize) -> Result<usize, GindexError> {
+    if step == "__len__" || step == "__selector__" {
+        return Err(GindexError::NoSuchNode(step.into()));
+    }
+    let index = step
+        .parse()
+        .map_err(|_| GindexError::InvalidIndex(step.into()))?;
+    if index >= len {
+        return Err(GindexError::IndexOutOfRange { index, len });
+    }
+    Ok(index)
+}
+
+// the length of a basic type, which is packed with others into chunks
+fn basic_len(typ: &SszTypeInfo) -> Option<usize> {
+    match typ {
+        SszTypeInfo::Uint(len) => Some(*len),
+        SszTypeInfo::Bool => Some(1),
+        _ => None,
+    }
+}
+
+// the depth of a tree of `chunks` leaves, padded to the next power of two
+fn depth(chunks: usize) -> u32 {
+    chunks.max(1).next_power_of_two().trailing_zeros()
+}
@@ -0,0 +1,73 @@
+//! Telemetry for inputs that fail to decode, enabled by the `metrics` feature.
+//!
+//! An endpoint taking SSZ from the network can count and classify what it rejects (truncated
+//! inputs, bad offsets, lists past their maximum) by installing an observer with
+//! `set_decode_observer` and matching on the `DecodeError` it's handed, along with the name of
+//! the type that failed to decode. The observer is called once for every top-level decode that
+//! fails (`from_ssz_bytes`, `from_ssz_bytes_shared`, `decode_into`, `from_ssz_bytes_trusted` or
+//! `from_ssz_bytes_parallel`), with the error it returns, and not for the elements and fields that
+//! are decoded along the way, even on the rayon pool. Until one is installed, decoding only checks
+//! that there's none.
+use crate::DecodeError;
+use core::cell::Cell;
+use std::sync::OnceLock;
+
+/// Called with the error of a failed decode and the name of the type being decoded.
+pub type DecodeObserver = fn(&DecodeError, &'static str);
+
+static OBSERVER: OnceLock<DecodeObserver> = OnceLock::new();
+
+std::thread_local! {
+    // how many decodes the current thread is in the middle of, only the outermost reports
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Installs the observer of failed decodes for the whole process. There's only ever one, so
+/// installing another fails, handing it back.
+pub fn set_decode_observer(observer: DecodeObserver) -> Result<(), DecodeObserver> {
+    OBSERVER.set(observer)
+}
+
+// undoes the depth a decode added, even if it panics
+struct Nested;
+
+impl Nested {
+    fn enter() -> (Self, usize) {
+        let depth = DEPTH.with(|depth| depth.replace(depth.get() + 1));
+        (Nested, depth)
+    }
+}
+
+impl Drop for Nested {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+// runs a decode of T, and reports its error to the observer if it isn't part of another decode
+#[inline]
+pub(crate) fn observe<T, R>(
+    decode: impl FnOnce() -> Result<R, DecodeError>,
+) -> Result<R, DecodeError> {
+    let Some(observer) = OBSERVER.get() else {
+        return decode();
+    };
+    let (nested, depth) = Nested::enter();
+    let result = decode();
+    drop(nested);
+    if let (0, Err(error)) = (depth, &result) {
+        observer(error, core::any::type_name::<T>());
+    }
+    result
+}
+
+// runs part of a decode that's under way on another thread, i.e. the elements of a list decoded
+// on the rayon pool, so that their decodes aren't mistaken for outermost ones
+#[cfg(all(
+    feature = "rayon",
+    any(feature = "ssz-types", feature = "milhouse", feature = "ghilhouse")
+))]
+pub(crate) fn nested<R>(part: impl FnOnce() -> R) -> R {
+    let _nested = Nested::enter();
+    part()
+}
@@ -0,0 +1,25 @@
+use crate::{
+    collections::{impl_ssz_list, impl_ssz_vector, impl_tree_collections},
+    DecodeError,
+};
+use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
+
+impl From<milhouse::Error> for DecodeError {
+    fn from(error: milhouse::Error) -> Self {
+        #[cfg(not(feature = "tiny-errors"))]
+        let error = DecodeError::Milhouse(error);
+        #[cfg(feature = "tiny-errors")]
+        let error = {
+            let _ = error;
+            DecodeError::Milhouse("milhouse collection")
+        };
+        error
+    }
+}
+
+// milhouse's `List` and `Vector` only hold items that implement its `Value` trait, so the impls
+// for them have to ask for it too. Nothing else is required of the items beyond what a
+// `VariableList`'s need
+impl_tree_collections!(PersistentList, PersistentVector, item: [Value]);
+impl_ssz_list!(PersistentList, item: [Value]);
+impl_ssz_vector!(PersistentVector, item: [Value]);
@@ -0,0 +1,214 @@
+//! Reading and checking the offsets of variable-size values.
+//!
+//! Containers and lists of variable-size values start with a fixed portion, where each variable
+//! value is a `BYTES_PER_LENGTH_OFFSET`-byte offset to where its bytes start, counted from the
+//! start of the container or list. Every decoder, validator and lazy reader in this crate goes
+//! through these functions, so they agree on which offsets are accepted and on the error each
+//! bad one gets.
+//!
+//! The checks are derived from this document:
+//!
+//! https://notes.ethereum.org/ruKvDXl6QOW3gnqVYb8ezA?view
+use crate::{DecodeError, BYTES_PER_LENGTH_OFFSET};
+use bytes::buf::Buf;
+use core::{ops::Range, slice::ChunksExact};
+
+/// Reads the offset at the start of `bytes`, which can go on past it.
+///
+/// Errors with `InvalidLengthPrefix` if `bytes` is shorter than `BYTES_PER_LENGTH_OFFSET`.
+pub fn read_offset(bytes: &[u8]) -> Result<usize, DecodeError> {
+    let offset = bytes
+        .get(..BYTES_PER_LENGTH_OFFSET)
+        .ok_or(DecodeError::InvalidLengthPrefix {
+            len: bytes.len(),
+            expected: BYTES_PER_LENGTH_OFFSET,
+        })?;
+    Ok(offset_from_bytes(offset))
+}
+
+// the offset in exactly BYTES_PER_LENGTH_OFFSET bytes
+fn offset_from_bytes(bytes: &[u8]) -> usize {
+    let mut array = [0; BYTES_PER_LENGTH_OFFSET];
+    array.copy_from_slice(bytes);
+    u32::from_le_bytes(array) as usize
+}
+
+/// Reads the offset at the front of `buf` and advances past it, or leaves `buf` as it was if it
+/// holds less than `BYTES_PER_LENGTH_OFFSET` bytes.
+pub fn read_offset_from_buf(buf: &mut impl Buf) -> Result<usize, DecodeError> {
+    let len = buf.remaining();
+    let expected = BYTES_PER_LENGTH_OFFSET;
+
+    if len < expected {
+        Err(DecodeError::InvalidLengthPrefix { len, expected })
+    } else {
+        Ok(buf.get_u32_le() as usize)
+    }
+}
+
+/// Checks the offset of the first variable value of a container, which has to point right past
+/// the `fixed_len` bytes of its fixed portion, within its `len` bytes.
+///
+/// Errors with `OffsetIntoFixedPortion` if it points before `fixed_len`,
+/// `OffsetSkipsVariableBytes` if it points after it, and `OffsetOutOfBounds` if the fixed portion
+/// itself doesn't fit in `len`.
+pub fn check_first_offset(
+    offset: usize,
+    fixed_len: usize,
+    len: usize,
+) -> Result<usize, DecodeError> {
+    if offset < fixed_len {
+        Err(DecodeError::OffsetIntoFixedPortion(offset))
+    } else if offset != fixed_len {
+        Err(DecodeError::OffsetSkipsVariableBytes(offset))
+    } else if offset > len {
+        Err(DecodeError::OffsetOutOfBounds(offset))
+    } else {
+        Ok(offset)
+    }
+}
+
+/// Checks an offset that has to point somewhere after the `fixed_len` bytes of the fixed
+/// portion, within the `len` bytes of the container, but not necessarily right after it: the
+/// first offset of a container that may have fields appended to it, or one whose preceding
+/// variable values aren't read.
+///
+/// Errors with `OffsetIntoFixedPortion` if it points before `fixed_len`, and `OffsetOutOfBounds`
+/// if it points past `len`.
+pub fn check_offset_past_fixed(
+    offset: usize,
+    fixed_len: usize,
+    len: usize,
+) -> Result<usize, DecodeError> {
+    if offset < fixed_len {
+        Err(DecodeError::OffsetIntoFixedPortion(offset))
+    } else if offset > len {
+        Err(DecodeError::OffsetOutOfBounds(offset))
+    } else {
+        Ok(offset)
+    }
+}
+
+/// Checks an offset after the first, which can't point before the `previous` one or past the
+/// `len` bytes of the container or list.
+///
+/// Errors with `OffsetOutOfBounds` if it points past `len`, and `OffsetsAreDecreasing` if it
+/// points before `previous`.
+pub fn check_next_offset(offset: usize, previous: usize, len: usize) -> Result<usize, DecodeError> {
+    if offset > len {
+        Err(DecodeError::OffsetOutOfBounds(offset))
+    } else if offset < previous {
+        Err(DecodeError::OffsetsAreDecreasing(offset))
+    } else {
+        Ok(offset)
+    }
+}
+
+/// Checks the first offset of a list of variable-size values of `len` bytes, and returns the
+/// number of values, which is how many offsets it leaves room for before it.
+///
+/// Errors with `OffsetOutOfBounds` if it points past `len`, and `InvalidListFixedBytesLen` if it
+/// is zero or not a multiple of `BYTES_PER_LENGTH_OFFSET`.
+pub fn check_list_first_offset(offset: usize, len: usize) -> Result<usize, DecodeError> {
+    if offset > len {
+        Err(DecodeError::OffsetOutOfBounds(offset))
+    } else if offset == 0 || !offset.is_multiple_of(BYTES_PER_LENGTH_OFFSET) {
+        Err(DecodeError::InvalidListFixedBytesLen(offset))
+    } else {
+        Ok(offset / BYTES_PER_LENGTH_OFFSET)
+    }
+}
+
+/// Walks the offset table of a list of variable-size values, yielding the range of each item
+/// between its offset and the next, with where that next offset was read. The last item ends at
+/// `end_of_list`, and the position yielded with it is that of its own offset, which is to blame
+/// if the list ends before the item starts.
+///
+/// The offsets are yielded as they're read, unchecked, so a range may be empty, backwards or out
+/// of bounds. The list decoders, serial, parallel, lazy and trusted, all walk their tables with
+/// this, checking each range as they need to.
+#[derive(Clone, Debug)]
+pub struct OffsetTable<'a> {
+    offsets: ChunksExact<'a, u8>,
+    // the offset of the next item, and where it was read
+    next: Option<(usize, usize)>,
+    end_of_list: usize,
+}
+
+impl<'a> OffsetTable<'a> {
+    /// The items of the offset `table`, whose length is a multiple of `BYTES_PER_LENGTH_OFFSET`
+    /// (any trailing bytes are ignored), in a list of `end_of_list` bytes.
+    pub fn new(table: &'a [u8], end_of_list: usize) -> Self {
+        let mut offsets = table.chunks_exact(BYTES_PER_LENGTH_OFFSET);
+        let next = offsets.next().map(|offset| (offset_from_bytes(offset), 0));
+        OffsetTable {
+            offsets,
+            next,
+            end_of_list,
+        }
+    }
+}
+
+impl Iterator for OffsetTable<'_> {
+    type Item = (Range<usize>, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, start_at) = self.next?;
+        self.next = self.offsets.next().map(|offset| {
+            (
+                offset_from_bytes(offset),
+                start_at + BYTES_PER_LENGTH_OFFSET,
+            )
+        });
+        let (end, end_at) = self.next.unwrap_or((self.end_of_list, start_at));
+        Some((start..end, end_at))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.offsets.len() + usize::from(self.next.is_some());
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for OffsetTable<'_> {}
+
+/// The length of a value that starts at the `begin` offset and ends at the `end` one.
+///
+/// Errors with `OffsetsAreDecreasing` if `end` comes before `begin`.
+pub fn offset_span(begin: usize, end: usize) -> Result<usize, DecodeError> {
+    end.checked_sub(begin)
+        .ok_or(DecodeError::OffsetsAreDecreasing(end))
+}
+
+/// Reads the first `BYTES_PER_LENGTH_OFFSET` bytes of `bytes` as an offset.
+#[deprecated(note = "use `sszb::offsets::read_offset` instead")]
+pub fn read_offset_from_slice(bytes: &[u8]) -> Result<usize, DecodeError> {
+    read_offset(bytes)
+}
+
+/// Performs checks on the `offset` based upon the other parameters provided.
+///
+/// - `previous_offset`: unless this is the first offset in the SSZ object, the value of the
+///   previously-read offset. Used to ensure offsets are not decreasing.
+/// - `num_bytes`: the total number of bytes in the SSZ object. Used to ensure the offset is not
+///   out of bounds.
+/// - `num_fixed_bytes`: the number of fixed-bytes in the struct, if it is known. Used to ensure
+///   that the first offset doesn't skip any variable bytes.
+#[deprecated(
+    note = "use `sszb::offsets::check_first_offset` or `sszb::offsets::check_next_offset` instead"
+)]
+pub fn sanitize_offset(
+    offset: usize,
+    previous_offset: Option<usize>,
+    num_bytes: usize,
+    num_fixed_bytes: Option<usize>,
+) -> Result<usize, DecodeError> {
+    match (previous_offset, num_fixed_bytes) {
+        (None, Some(fixed_len)) => check_first_offset(offset, fixed_len, num_bytes),
+        (Some(previous), Some(fixed_len)) => {
+            check_offset_past_fixed(offset, fixed_len, num_bytes)?;
+            check_next_offset(offset, previous, num_bytes)
+        }
+        (previous, None) => check_next_offset(offset, previous.unwrap_or(0), num_bytes),
+    }
+}
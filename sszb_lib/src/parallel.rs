@@ -0,0 +1,80 @@
+//! Parallel decoding and encoding of large collections, on the rayon thread pool with the `rayon`
+//! feature.
+//!
+//! `SszbDecode` and `SszbEncode` always run on the calling thread, and ask nothing more of the
+//! elements of lists and vectors than the SSZ traits. The parallel paths are asked for by name,
+//! through `from_ssz_bytes_parallel` and `ssz_write_parallel`, which `SszbDecodeParallel` and
+//! `SszbEncodeParallel` implement for lists and vectors of `Send` (to decode) and `Sync` (to
+//! encode) elements, and for containers marked `#[ssz(parallel)]`. Within those, a list or
+//! vector whose encoding is at least `PARALLEL_THRESHOLD` bytes has its elements decoded on the
+//! pool and then assembled in order, so both the decoded value and the error reported for bad
+//! input are the same as `from_ssz_bytes`'s. Encoding in parallel writes every element (or run of
+//! static elements) into its own, precomputed part of the output buffer.
+//!
+//! The traits are there, with the same bounds, whether or not the `rayon` feature is enabled;
+//! without it they do the same work on the calling thread.
+use crate::{decode::observe, DecodeError, SszbDecode, SszbEncode};
+use alloc::{boxed::Box, sync::Arc};
+
+#[cfg(all(
+    feature = "rayon",
+    any(feature = "ssz-types", feature = "milhouse", feature = "ghilhouse")
+))]
+pub(crate) mod pool;
+
+/// Encodings of at least this many bytes are decoded in parallel.
+pub const PARALLEL_THRESHOLD: usize = 1 << 16;
+
+/// Decoding that splits large lists and vectors up between the threads of the rayon pool.
+///
+/// Implemented by lists and vectors whose elements are `Send`, by boxed and shared slices, and by
+/// containers marked `#[ssz(parallel)]`, whose fields marked `#[ssz(parallel)]` are decoded
+/// through this trait and the rest through `SszbDecode`.
+pub trait SszbDecodeParallel: SszbDecode + Send {
+    // decodes the value the way from_ssz_bytes_parallel does, without reporting a failure to the
+    // decode observer (see sszb::metrics)
+    #[doc(hidden)]
+    fn ssz_read_parallel(bytes: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Decodes a value the same way as `from_ssz_bytes`, with the same result and the same error
+    /// for bad input, but with the elements of large lists and vectors decoded in parallel.
+    fn from_ssz_bytes_parallel(bytes: &[u8]) -> Result<Self, DecodeError> {
+        observe::<Self, _>(|| Self::ssz_read_parallel(bytes))
+    }
+}
+
+/// Encoding that splits large lists and vectors up between the threads of the rayon pool.
+///
+/// Implemented by lists and vectors whose elements are `Sync`, by boxed and shared slices, and by
+/// containers marked `#[ssz(parallel)]`, which write all of their fields concurrently: the fields
+/// marked `#[ssz(parallel)]` through this trait, and the rest with `ssz_write_slice`.
+pub trait SszbEncodeParallel: SszbEncode + Sync {
+    /// Writes self into `buf`, which must be exactly `sszb_bytes_len()` bytes long, with the same
+    /// output as `ssz_write`.
+    fn ssz_write_parallel(&self, buf: &mut [u8]);
+}
+
+macro_rules! pointer_ssz_parallel {
+    ($pointer:ident) => {
+        impl<T: SszbDecodeParallel> SszbDecodeParallel for $pointer<T>
+        where
+            Self: SszbDecode + Send,
+        {
+            fn ssz_read_parallel(bytes: &[u8]) -> Result<Self, DecodeError> {
+                T::ssz_read_parallel(bytes).map($pointer::new)
+            }
+        }
+
+        impl<T: SszbEncodeParallel> SszbEncodeParallel for $pointer<T>
+        where
+            Self: SszbEncode + Sync,
+        {
+            fn ssz_write_parallel(&self, buf: &mut [u8]) {
+                self.as_ref().ssz_write_parallel(buf);
+            }
+        }
+    };
+}
+
+pointer_ssz_parallel!(Box);
+pointer_ssz_parallel!(Arc);
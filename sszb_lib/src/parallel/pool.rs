@@ -0,0 +1,141 @@
+//! The rayon side of `SszbDecodeParallel` and `SszbEncodeParallel` for lists and vectors.
+use super::PARALLEL_THRESHOLD;
+use crate::{
+    collections::{ReadItems, SszCollection},
+    context::{install, DecodeContext},
+    decode::decode_impls::{
+        decode_variable_length_items, variable_item_ranges, VariableItemRanges,
+    },
+    expect_offset,
+    raw::peek_list_len_static,
+    write_offset, DecodeError, SszbDecode, SszbEncode, TryFromIter, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use rayon::prelude::*;
+
+// the elements decoded on the pool are part of the decode of their list
+#[cfg(feature = "metrics")]
+use crate::metrics::nested;
+#[cfg(not(feature = "metrics"))]
+fn nested<R>(part: impl FnOnce() -> R) -> R {
+    part()
+}
+
+// how SszbDecodeParallel decodes the items of a list or vector: on the pool once their encoding
+// is at least PARALLEL_THRESHOLD bytes long, and like SszbDecode below that
+pub(crate) enum Parallel {}
+
+impl<T: SszbDecode + Send> ReadItems<T> for Parallel {
+    fn read_packed<C: SszCollection<T>>(bytes: &[u8]) -> Result<C, DecodeError> {
+        if bytes.len() < PARALLEL_THRESHOLD {
+            return C::from_packed(bytes);
+        }
+        C::from_items(read_packed(bytes)?)
+    }
+
+    fn read_variable<C: TryFromIter<T>>(
+        var_offsets: &[u8],
+        var_items: &[u8],
+    ) -> Result<C, DecodeError> {
+        if var_offsets.len() + var_items.len() < PARALLEL_THRESHOLD {
+            return decode_variable_length_items(var_offsets, var_items);
+        }
+        let items = match variable_item_ranges(var_offsets, var_items)? {
+            Some(ranges) => decode_variable_items(ranges, var_offsets, var_items)?,
+            None => Vec::new(),
+        };
+        C::try_from_iter(items.into_iter()).map_err(Into::into)
+    }
+}
+
+/// Parallel counterpart of `SszbDecode::ssz_read_packed`: `bytes` is split into runs of whole
+/// values, which are decoded on the pool and concatenated.
+fn read_packed<T: SszbDecode + Send>(bytes: &[u8]) -> Result<Vec<T>, DecodeError> {
+    let elem_len = <T as SszbDecode>::SSZ_FIXED_LEN;
+    let num_items = peek_list_len_static(bytes, elem_len)?;
+    let run_len = (PARALLEL_THRESHOLD / elem_len).max(1) * elem_len;
+
+    let runs: Vec<_> = bytes
+        .par_chunks(run_len)
+        .enumerate()
+        .map(|(i, run)| nested(|| T::ssz_read_packed(run)).map_err(|e| e.at_byte(i * run_len)))
+        .collect();
+
+    // the runs are in order, so the first error here is the one a serial decode would hit
+    let mut items = Vec::with_capacity(num_items);
+    for run in runs {
+        items.extend(run?);
+    }
+    Ok(items)
+}
+
+/// Parallel counterpart of `ssz_decode_variable_length_items`, for the `ranges` of an offset
+/// table that has already been checked in full. The items they delimit are decoded on the pool.
+fn decode_variable_items<T: SszbDecode + Send>(
+    ranges: VariableItemRanges<'_>,
+    var_offsets: &[u8],
+    var_items: &[u8],
+) -> Result<Vec<T>, DecodeError> {
+    let offsets_len = var_offsets.len();
+    let ranges = ranges.collect::<Result<Vec<_>, _>>()?;
+
+    // the items are decoded under the decode context of the thread decoding the list
+    let context = DecodeContext::current();
+    let items: Vec<_> = ranges
+        .into_par_iter()
+        .map(|range| {
+            let _context = install(context.clone());
+            let bytes = &var_items[(range.start - offsets_len)..(range.end - offsets_len)];
+            nested(|| <T as SszbDecode>::from_ssz_bytes(bytes)).map_err(|e| e.at_byte(range.start))
+        })
+        .collect();
+    items.into_iter().collect()
+}
+
+/// Writes `items` into `buf`, which has to be exactly as long as their encoding. Static values
+/// are written in runs of about `PARALLEL_THRESHOLD` bytes, variable ones each into their own
+/// part of the buffer after the offset table.
+pub(crate) fn write_items<T: SszbEncode + Sync>(items: &[T], buf: &mut [u8]) {
+    if T::SSZ_STATIC {
+        let run_len = (PARALLEL_THRESHOLD / T::SSZ_FIXED_LEN).max(1);
+        buf.par_chunks_mut(run_len * T::SSZ_FIXED_LEN)
+            .zip(items.par_chunks(run_len))
+            .for_each(|(mut chunk, run)| T::ssz_write_packed(run, &mut chunk));
+    } else {
+        let (mut offsets, mut rest) = buf.split_at_mut(items.len() * BYTES_PER_LENGTH_OFFSET);
+        let mut offset = items.len() * BYTES_PER_LENGTH_OFFSET;
+        let mut parts = Vec::with_capacity(items.len());
+        for item in items {
+            let len = item.sszb_bytes_len();
+            expect_offset(write_offset(offset, &mut offsets));
+            offset += len;
+            let (part, remaining) = core::mem::take(&mut rest).split_at_mut(len);
+            rest = remaining;
+            parts.push(part);
+        }
+
+        items.par_iter().zip(parts).for_each(|(item, part)| {
+            item.ssz_write_slice(part);
+        });
+    }
+}
+
+/// Like `write_items`, for static values that aren't stored contiguously (e.g. in persistent
+/// lists). `items_from(i)` has to iterate over the values from index `i` on.
+#[cfg(any(feature = "milhouse", feature = "ghilhouse"))]
+pub(crate) fn write_static_items_from<'a, T, I>(
+    buf: &mut [u8],
+    items_from: impl Fn(usize) -> I + Sync,
+) where
+    T: SszbEncode + 'a,
+    I: Iterator<Item = &'a T>,
+{
+    let run_len = (PARALLEL_THRESHOLD / T::SSZ_FIXED_LEN).max(1);
+    buf.par_chunks_mut(run_len * T::SSZ_FIXED_LEN)
+        .enumerate()
+        .for_each(|(i, mut chunk)| {
+            for item in items_from(i * run_len).take(run_len) {
+                item.ssz_write(&mut chunk);
+            }
+        });
+}
@@ -0,0 +1,276 @@
+//! Pulling one nested value out of a serialized object by a path of field names and indices,
+//! without decoding the rest of it or working out the offsets by hand:
+//!
+//! ```ignore
+//! let raw = get_raw::<BeaconBlock>(&bytes, "body.execution_payload.transactions[3]")?;
+//! let slot: u64 = get_typed::<BeaconBlock, _>(&bytes, "slot")?;
+//! ```
+//!
+//! Steps are separated by dots, and list and vector items are named by their index in brackets,
+//! which may follow one another (`committees[2][5]`) or start the path (`[0].root`). A union is
+//! stepped through into whichever variant it holds.
+//!
+//! Only the containers, lists and vectors the path goes through are checked, with the same
+//! checks and errors as decoding them, so a path can succeed on bytes that a full decode would
+//! reject somewhere else.
+
+use crate::decode::decode_impls::{visit_list_items, visit_vector_items};
+use crate::dynamic::{SszTypeInfo, SszbTypeInfo};
+use crate::{visit_container_fields, DecodeError, SszbDecode};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A path that couldn't be followed.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PathError {
+    /// The path isn't made of field names and bracketed indices.
+    Syntax(String),
+    /// The path up to and including this step doesn't exist in the schema: there's no such
+    /// field, the step goes into something that isn't a container, list or vector, or the index
+    /// is past the length of the vector or the maximum of the list.
+    NotInSchema(String),
+    /// The list at this step exists in the schema, but holds only `len` items.
+    IndexOutOfRange {
+        path: String,
+        index: usize,
+        len: usize,
+    },
+    /// The union at this step holds its empty variant, so there's nothing to step into.
+    EmptyVariant(String),
+    /// The bytes are malformed at this step. The error's position is in the whole input.
+    Malformed { path: String, error: DecodeError },
+}
+
+impl core::fmt::Display for PathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PathError::Syntax(path) => write!(f, "`{}` isn't a valid path", path),
+            PathError::NotInSchema(path) => write!(f, "`{}` isn't in the schema", path),
+            PathError::IndexOutOfRange { path, index, len } => write!(
+                f,
+                "`{}` is out of range: index {} of a list of {} items",
+                path, index, len
+            ),
+            PathError::EmptyVariant(path) => {
+                write!(f, "the union at `{}` holds its empty variant", path)
+            }
+            PathError::Malformed { path, error } => {
+                write!(f, "malformed bytes at `{}`: {}", path, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PathError::Malformed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// one step of a path
+enum Step<'p> {
+    Field(&'p str),
+    Index(usize),
+}
+
+/// The bytes of the value `path` leads to in `bytes`, a serialized `Root`.
+pub fn get_raw<'a, Root: SszbTypeInfo>(bytes: &'a [u8], path: &str) -> Result<&'a [u8], PathError> {
+    get_raw_with_schema(&Root::ssz_type_info(), bytes, path)
+}
+
+/// Decodes the value `path` leads to in `bytes`, a serialized `Root`, as a `T`.
+pub fn get_typed<Root: SszbTypeInfo, T: SszbDecode>(
+    bytes: &[u8],
+    path: &str,
+) -> Result<T, PathError> {
+    let schema = Root::ssz_type_info();
+    let range = locate(&schema, bytes, path)?;
+    T::from_ssz_bytes(&bytes[range.clone()]).map_err(|error| PathError::Malformed {
+        path: path.into(),
+        error: error.at_byte(range.start),
+    })
+}
+
+/// `get_raw` for a layout only known at runtime.
+pub fn get_raw_with_schema<'a>(
+    schema: &SszTypeInfo,
+    bytes: &'a [u8],
+    path: &str,
+) -> Result<&'a [u8], PathError> {
+    locate(schema, bytes, path).map(|range| &bytes[range])
+}
+
+// the range of `bytes` that `path` leads to
+fn locate(schema: &SszTypeInfo, bytes: &[u8], path: &str) -> Result<Range<usize>, PathError> {
+    let steps = parse(path)?;
+    if schema.is_static() && bytes.len() != schema.fixed_len() {
+        return Err(PathError::Malformed {
+            path: String::new(),
+            error: DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: schema.fixed_len(),
+            },
+        });
+    }
+
+    let mut typ = schema;
+    let mut range = 0..bytes.len();
+    for (i, (step, walked)) in steps.iter().enumerate() {
+        // a union is stepped through into the variant it holds
+        while let SszTypeInfo::Union(variants) = typ {
+            let previous = i.checked_sub(1).map_or("", |i| steps[i].1);
+            let (variant, body) = active_variant(variants, &bytes[range.clone()])
+                .map_err(|error| malformed(previous, error, range.start))?;
+            typ = variant.ok_or_else(|| PathError::EmptyVariant(previous.into()))?;
+            range = (range.start + body.start)..(range.start + body.end);
+        }
+
+        let value = &bytes[range.clone()];
+        let (next, found) = match (typ, step) {
+            (SszTypeInfo::Container(fields), Step::Field(name)) => {
+                let index = fields
+                    .iter()
+                    .position(|(field, _)| field == name)
+                    .ok_or_else(|| PathError::NotInSchema((*walked).into()))?;
+                let layout = fields
+                    .iter()
+                    .map(|(_, field)| (field.is_static(), field.fixed_len()))
+                    .collect::<Vec<_>>();
+                let mut found = 0..0;
+                visit_container_fields(value, &layout, |field_index, field| {
+                    if field_index == index {
+                        found = field;
+                    }
+                    Ok(())
+                })
+                .map_err(|error| malformed(walked, error, range.start))?;
+                (&fields[index].1, found)
+            }
+            (SszTypeInfo::Vector { item, len }, Step::Index(index)) => {
+                if index >= len {
+                    return Err(PathError::NotInSchema((*walked).into()));
+                }
+                let layout = (item.is_static(), item.fixed_len());
+                let found = nth_item(*index, |visit| {
+                    visit_vector_items(value, layout, *len, visit)
+                })
+                .map_err(|error| malformed(walked, error, range.start))?;
+                (&**item, found.expect("a vector has all its items"))
+            }
+            (SszTypeInfo::List { item, max_len }, Step::Index(index)) => {
+                if index >= max_len {
+                    return Err(PathError::NotInSchema((*walked).into()));
+                }
+                let layout = (item.is_static(), item.fixed_len());
+                let mut len = 0;
+                let found = nth_item(*index, |visit| {
+                    visit_list_items(value, layout, *max_len, |item| {
+                        len += 1;
+                        visit(item)
+                    })
+                })
+                .map_err(|error| malformed(walked, error, range.start))?;
+                let found = found.ok_or_else(|| PathError::IndexOutOfRange {
+                    path: (*walked).into(),
+                    index: *index,
+                    len,
+                })?;
+                (&**item, found)
+            }
+            _ => return Err(PathError::NotInSchema((*walked).into())),
+        };
+        typ = next;
+        range = (range.start + found.start)..(range.start + found.end);
+    }
+    Ok(range)
+}
+
+// the range of item `index` among those `visit_items` walks, which checks every item's range as
+// decoding would, or None if there are fewer items
+fn nth_item(
+    index: usize,
+    visit_items: impl FnOnce(
+        &mut dyn FnMut(Range<usize>) -> Result<(), DecodeError>,
+    ) -> Result<(), DecodeError>,
+) -> Result<Option<Range<usize>>, DecodeError> {
+    let mut found = None;
+    let mut i = 0;
+    visit_items(&mut |item| {
+        if i == index {
+            found = Some(item);
+        }
+        i += 1;
+        Ok(())
+    })?;
+    Ok(found)
+}
+
+// the variant a union holds, and the range of its value in `bytes`
+fn active_variant<'s>(
+    variants: &'s [Option<SszTypeInfo>],
+    bytes: &[u8],
+) -> Result<(Option<&'s SszTypeInfo>, Range<usize>), DecodeError> {
+    let (&selector, body) = bytes.split_first().ok_or(DecodeError::InvalidByteLength {
+        len: 0,
+        expected: 1,
+    })?;
+    match variants.get(usize::from(selector)) {
+        Some(Some(variant)) => Ok((Some(variant), 1..bytes.len())),
+        Some(None) if body.is_empty() => Ok((None, 1..1)),
+        Some(None) => Err(DecodeError::InvalidByteLength {
+            len: body.len(),
+            expected: 0,
+        }
+        .at_byte(1)),
+        None => Err(DecodeError::UnionSelectorInvalid(selector)),
+    }
+}
+
+fn malformed(path: &str, error: DecodeError, at_byte: usize) -> PathError {
+    PathError::Malformed {
+        path: path.into(),
+        error: error.at_byte(at_byte),
+    }
+}
+
+// splits a path into its steps, each with the prefix of the path up to and including it
+fn parse(path: &str) -> Result<Vec<(Step<'_>, &str)>, PathError> {
+    let syntax = || PathError::Syntax(path.into());
+    let mut steps = Vec::new();
+    if path.is_empty() {
+        return Ok(steps);
+    }
+
+    let mut start = 0;
+    for (i, segment) in path.split('.').enumerate() {
+        let (name, mut indices) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if name.contains(']') {
+            return Err(syntax());
+        }
+        if !name.is_empty() {
+            steps.push((Step::Field(name), &path[..(start + name.len())]));
+        } else if i > 0 || indices.is_empty() {
+            // only the first segment may start with an index
+            return Err(syntax());
+        }
+
+        let mut end = start + name.len();
+        while !indices.is_empty() {
+            let close = indices.find(']').ok_or_else(syntax)?;
+            if !indices.starts_with('[') {
+                return Err(syntax());
+            }
+            let index = indices[1..close].parse().map_err(|_| syntax())?;
+            end += close + 1;
+            steps.push((Step::Index(index), &path[..end]));
+            indices = &indices[(close + 1)..];
+        }
+        start += segment.len() + 1;
+    }
+    Ok(steps)
+}
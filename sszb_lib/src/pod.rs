@@ -0,0 +1,22 @@
+//! What the code derived for `#[ssz(pod)]` containers needs: the bytes of a container that's
+//! plain old data, and the container back from them.
+
+use alloc::vec::Vec;
+
+pub fn bytes_of<T: bytemuck::Pod>(value: &T) -> &[u8] {
+    bytemuck::bytes_of(value)
+}
+
+pub fn slice_bytes<T: bytemuck::Pod>(values: &[T]) -> &[u8] {
+    bytemuck::cast_slice(values)
+}
+
+// bytes has to be exactly one value long
+pub fn read<T: bytemuck::Pod>(bytes: &[u8]) -> T {
+    bytemuck::pod_read_unaligned(bytes)
+}
+
+// bytes has to be a whole number of values long
+pub fn read_slice<T: bytemuck::Pod>(bytes: &[u8]) -> Vec<T> {
+    bytemuck::pod_collect_to_vec(bytes)
+}
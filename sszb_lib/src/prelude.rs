@@ -0,0 +1,20 @@
+//! Everything a type that derives or implements the SSZ traits needs, in one import:
+//!
+//! ```ignore
+//! use sszb::prelude::*;
+//!
+//! #[derive(SszbEncode, SszbDecode)]
+//! struct Checkpoint {
+//!     epoch: u64,
+//!     root: [u8; 32],
+//! }
+//! ```
+//!
+//! The derives, which share their names with the traits, come with the `derive` feature. `Buf`
+//! and `BufMut` are here because the derived impls name them.
+
+pub use crate::dynamic::SszbTypeInfo;
+pub use crate::{DecodeError, EncodeError, SszbDecode, SszbEncode, BYTES_PER_LENGTH_OFFSET};
+pub use bytes::buf::{Buf, BufMut};
+#[cfg(feature = "derive")]
+pub use sszb_derive::{SszbDecode, SszbEncode, SszbTypeInfo};
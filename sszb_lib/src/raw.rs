@@ -0,0 +1,86 @@
+//! Helpers for inspecting serialized SSZ lists without decoding their elements.
+//!
+//! These are the same checks the list decoders perform before touching any element, so they
+//! can be used for cheap admission control (e.g. "how many attestations are in this aggregate?")
+//! and are guaranteed to agree with a full decode on the list's length.
+use crate::{
+    offsets::{check_list_first_offset, read_offset, OffsetTable},
+    DecodeError, SszbDecode, BYTES_PER_LENGTH_OFFSET,
+};
+
+/// Returns the number of items in a serialized list whose elements are static, each
+/// `elem_fixed_len` bytes long.
+///
+/// Errors if `elem_fixed_len` is zero or if `bytes` is not a whole number of elements.
+/// The list maximum is not checked, since it is not known here.
+pub fn peek_list_len_static(bytes: &[u8], elem_fixed_len: usize) -> Result<usize, DecodeError> {
+    let num_items = bytes
+        .len()
+        .checked_div(elem_fixed_len)
+        .ok_or(DecodeError::ZeroLengthItem)?;
+
+    let trailing = bytes.len() % elem_fixed_len;
+    if trailing != 0 {
+        // report the dangling partial element the same way decoding it would
+        return Err(DecodeError::InvalidByteLength {
+            len: trailing,
+            expected: elem_fixed_len,
+        });
+    }
+
+    Ok(num_items)
+}
+
+/// Returns the number of items in a serialized list whose elements are variable-sized.
+///
+/// The count is deduced from the first offset, which must point past the offset table
+/// (i.e. be a non-zero multiple of `BYTES_PER_LENGTH_OFFSET`) and stay within `bytes`, so the
+/// offset table of the returned count always fits in `bytes`.
+/// An empty input is an empty list. The list maximum is not checked, since it is not known here.
+pub fn peek_list_len_variable(bytes: &[u8]) -> Result<usize, DecodeError> {
+    if bytes.is_empty() {
+        return Ok(0);
+    }
+
+    check_list_first_offset(read_offset(bytes)?, bytes.len())
+}
+
+/// Returns the number of items in a serialized list of `T`, with `peek_list_len_static` or
+/// `peek_list_len_variable` depending on whether `T` is static.
+pub fn peek_list_len<T: SszbDecode>(bytes: &[u8]) -> Result<usize, DecodeError> {
+    if T::SSZ_STATIC {
+        peek_list_len_static(bytes, T::SSZ_FIXED_LEN)
+    } else {
+        peek_list_len_variable(bytes)
+    }
+}
+
+/// Iterates over the offset table of a serialized list of variable-sized elements.
+///
+/// The table size is taken from `peek_list_len_variable`; if that fails, its error is the only
+/// item yielded. The offsets after the first are returned as read, without sanitization.
+pub fn iter_offsets(bytes: &[u8]) -> impl Iterator<Item = Result<usize, DecodeError>> + '_ {
+    let (offset_table, err) = match peek_list_len_variable(bytes)
+        .and_then(|num_items| split_offset_table(bytes, num_items))
+    {
+        Ok((offset_table, _)) => (offset_table, None),
+        Err(e) => (&bytes[..0], Some(e)),
+    };
+
+    err.map(Err)
+        .into_iter()
+        .chain(OffsetTable::new(offset_table, bytes.len()).map(|(range, _)| Ok(range.start)))
+}
+
+/// Splits a serialized list of `num_items` variable-sized elements into its offset table and the
+/// elements' bytes.
+///
+/// Errors with `InvalidListFixedBytesLen` instead of panicking if `bytes` is too short to hold
+/// `num_items` offsets, which `peek_list_len_variable` already rules out for the count it returns.
+pub fn split_offset_table(bytes: &[u8], num_items: usize) -> Result<(&[u8], &[u8]), DecodeError> {
+    let table_len = num_items.saturating_mul(BYTES_PER_LENGTH_OFFSET);
+    if table_len > bytes.len() {
+        return Err(DecodeError::InvalidListFixedBytesLen(table_len));
+    }
+    Ok(bytes.split_at(table_len))
+}
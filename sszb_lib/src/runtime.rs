@@ -0,0 +1,285 @@
+//! Lists and vectors whose bounds are only known at runtime, for lengths that come from a preset
+//! or a chain config instead of the type.
+//!
+//! [`RuntimeList`] carries its maximum length and [`RuntimeVector`] its exact one, and both encode
+//! exactly like the list or vector they stand in for. Decoding has to be told the bound, so
+//! neither implements `SszbDecode`: they are decoded with `from_ssz_bytes_with_max` and
+//! `from_ssz_bytes_with_len`, and a container holding one decodes it by hand, from the bytes its
+//! offset points at.
+//!
+//! Both are variable-size values as far as the `SszbEncode` consts go, since the length of a
+//! vector isn't known to its type. A `RuntimeVector` of static items is inlined into a container
+//! by the spec, so it can't stand in for one as a container field: only the bytes of the vector
+//! itself, or of a list of them, match.
+
+use crate::decode::decode_impls::{validate_list, validate_vector, visit_vector_items};
+use crate::encode::{
+    record_item_lens, write_items_cached, write_items_cached_vec, write_items_slice, write_to_slice,
+};
+use crate::vectored::Segments;
+use crate::{
+    check_items_bounds, decode_list_into, expect_len, expect_offset, try_items_bytes_len,
+    write_offset, DecodeError, EncodeError, SszbDecode, SszbEncode, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use bytes::buf::BufMut;
+use core::ops::Deref;
+
+/// An SSZ list of at most `max_len` items, with the maximum chosen at runtime.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RuntimeList<T> {
+    items: Vec<T>,
+    max_len: usize,
+}
+
+impl<T> RuntimeList<T> {
+    /// Fails with `ListTooLong` if there are more than `max_len` items.
+    pub fn new(items: Vec<T>, max_len: usize) -> Result<Self, DecodeError> {
+        if items.len() > max_len {
+            return Err(DecodeError::ListTooLong {
+                len: items.len(),
+                max: max_len,
+            });
+        }
+        Ok(RuntimeList { items, max_len })
+    }
+
+    pub fn empty(max_len: usize) -> Self {
+        RuntimeList {
+            items: Vec::new(),
+            max_len,
+        }
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Fails with `ListTooLong`, leaving the list as it was, if it's already full.
+    pub fn push(&mut self, item: T) -> Result<(), DecodeError> {
+        if self.items.len() == self.max_len {
+            return Err(DecodeError::ListTooLong {
+                len: self.max_len + 1,
+                max: self.max_len,
+            });
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Skips the length check of `new`, for testing what happens to a list past its maximum.
+    #[cfg(feature = "testing")]
+    #[doc(hidden)]
+    pub fn new_unchecked(items: Vec<T>, max_len: usize) -> Self {
+        RuntimeList { items, max_len }
+    }
+
+    fn max_items(&self) -> usize {
+        self.max_len
+    }
+}
+
+impl<T: SszbDecode> RuntimeList<T> {
+    /// Decodes a list of at most `max_len` items, failing with `ListTooLong` before decoding any
+    /// of them if there are more.
+    pub fn from_ssz_bytes_with_max(bytes: &[u8], max_len: usize) -> Result<Self, DecodeError> {
+        let mut items = Vec::new();
+        decode_list_into(bytes, max_len, &mut items)?;
+        Ok(RuntimeList { items, max_len })
+    }
+
+    /// Checks the bytes the way `from_ssz_bytes_with_max` does, without keeping the items.
+    pub fn validate_ssz_bytes_with_max(bytes: &[u8], max_len: usize) -> Result<(), DecodeError> {
+        validate_list::<T>(bytes, max_len)
+    }
+}
+
+impl<T> Deref for RuntimeList<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+/// An SSZ vector of exactly `len()` items, with the length chosen at runtime.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RuntimeVector<T> {
+    items: Vec<T>,
+}
+
+impl<T> RuntimeVector<T> {
+    /// Fails with `VectorLengthMismatch` unless there are exactly `len` items, or with
+    /// `ZeroLengthVector` if `len` is zero, which no vector can be.
+    pub fn new(items: Vec<T>, len: usize) -> Result<Self, DecodeError> {
+        if len == 0 {
+            return Err(DecodeError::ZeroLengthVector);
+        }
+        if items.len() != len {
+            return Err(DecodeError::VectorLengthMismatch {
+                found: items.len(),
+                expected: len,
+            });
+        }
+        Ok(RuntimeVector { items })
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.items
+    }
+
+    // a vector has no maximum, its length is checked when it's built
+    fn max_items(&self) -> usize {
+        usize::MAX
+    }
+}
+
+impl<T: SszbDecode> RuntimeVector<T> {
+    /// Decodes a vector of exactly `len` items.
+    pub fn from_ssz_bytes_with_len(bytes: &[u8], len: usize) -> Result<Self, DecodeError> {
+        let mut items = Vec::with_capacity(len);
+        visit_vector_items(bytes, (T::SSZ_STATIC, T::SSZ_FIXED_LEN), len, |range| {
+            let item =
+                T::from_ssz_bytes(&bytes[range.clone()]).map_err(|e| e.at_byte(range.start))?;
+            items.push(item);
+            Ok(())
+        })?;
+        Ok(RuntimeVector { items })
+    }
+
+    /// Checks the bytes the way `from_ssz_bytes_with_len` does, without keeping the items.
+    pub fn validate_ssz_bytes_with_len(bytes: &[u8], len: usize) -> Result<(), DecodeError> {
+        validate_vector::<T>(bytes, len)
+    }
+}
+
+impl<T> Deref for RuntimeVector<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+// the infallible encoders don't check the maximum of a list, but debug builds assert it
+#[inline]
+fn debug_check_len(len: usize, max: usize) {
+    debug_assert!(
+        len <= max,
+        "RuntimeList holds {} items, more than its maximum of {}",
+        len,
+        max
+    );
+}
+
+// lists and vectors encode their items the same way, a vector just can't be empty
+macro_rules! impl_runtime_encode {
+    ($ty:ident) => {
+        impl<T: SszbEncode> SszbEncode for $ty<T> {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+            const SSZ_MAX_LEN: usize = usize::MAX;
+
+            fn sszb_bytes_len(&self) -> usize {
+                expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+                try_items_bytes_len::<T>(self.len(), self.iter())
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+                check_items_bounds::<T>(self.len(), self.max_items(), self.iter())
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                expect_offset(write_offset(*offset, buf));
+                *offset += self.sszb_bytes_len();
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                self.ssz_write(buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                debug_check_len(self.len(), self.max_items());
+                if T::SSZ_STATIC {
+                    T::ssz_write_packed(self, buf);
+                } else {
+                    let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+                    for item in self.iter() {
+                        item.ssz_write_fixed(offset, buf);
+                    }
+                    for item in self.iter() {
+                        item.ssz_write(buf);
+                    }
+                }
+            }
+
+            fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+                if T::SSZ_STATIC {
+                    T::ssz_write_packed_vectored(self, segments);
+                } else {
+                    let offset = &mut (self.len() * BYTES_PER_LENGTH_OFFSET);
+                    for item in self.iter() {
+                        item.ssz_write_fixed(offset, segments.scratch());
+                    }
+                    for item in self.iter() {
+                        item.ssz_write_vectored(segments);
+                    }
+                }
+            }
+
+            fn ssz_record_lens(&self, lens: &mut Vec<usize>) -> usize {
+                debug_check_len(self.len(), self.max_items());
+                if T::SSZ_STATIC {
+                    self.sszb_bytes_len()
+                } else {
+                    record_item_lens(self.len(), self.iter(), lens)
+                }
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+                if T::SSZ_STATIC {
+                    self.ssz_write(buf);
+                } else {
+                    write_items_cached(self.len(), self.iter(), lens, buf);
+                }
+            }
+
+            fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut Vec<u8>) {
+                if T::SSZ_STATIC {
+                    self.ssz_write(buf);
+                } else {
+                    write_items_cached_vec(self.len(), self.iter(), lens, buf);
+                }
+            }
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                if T::SSZ_STATIC {
+                    write_to_slice(buf, |rest| self.ssz_write(rest))
+                } else {
+                    write_items_slice(self.len(), self.iter(), buf)
+                }
+            }
+        }
+    };
+}
+
+impl_runtime_encode!(RuntimeList);
+impl_runtime_encode!(RuntimeVector);
+
+#[cfg(feature = "std")]
+impl<T: SszbDecode> crate::context::ContextLen for RuntimeList<T> {
+    fn context_len(&self) -> usize {
+        self.items.len()
+    }
+
+    fn peek_context_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+        crate::raw::peek_list_len::<T>(bytes)
+    }
+}
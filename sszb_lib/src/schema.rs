@@ -0,0 +1,226 @@
+//! Fingerprints of SSZ layouts, to catch bytes written with one definition of a type being
+//! decoded with another.
+//!
+//! Every `SszbDecode` type has an `SSZ_SCHEMA` constant, the SHA-256 of a canonical description
+//! of its layout, and derived containers also get it as an inherent `SSZ_SCHEMA_FINGERPRINT`.
+//! The description is built bottom-up:
+//!
+//! - a container is described by its number of fields and the fingerprint of each field's type,
+//!   in order. Field names don't take part, so renaming a field keeps the fingerprint, and
+//!   neither do skipped fields. A field with `#[ssz(encode_as = "...")]` counts as the type it's
+//!   encoded as.
+//! - a list or vector is described by its maximum length or length, and the fingerprint of its
+//!   item type.
+//! - any other type is described by whether it's static, its fixed length and its maximum
+//!   length, so two such types of the same size share a fingerprint.
+//!
+//! A field of a recursive container that refers back to the container, like the children of a
+//! tree node, can't be described by a fingerprint that's still being computed. It's described by
+//! its type as written instead, with the container's own name as `Self`.
+//!
+//! Pin a fingerprint in a test with `assert_schema!`, so that any change to the layout of a
+//! type that's persisted fails loudly.
+
+use alloc::string::String;
+
+// the first 32 bits of the fractional parts of the cube roots of the first 64 primes
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// the first 32 bits of the fractional parts of the square roots of the first 8 primes
+const H: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+// SHA-256, usable in constants
+struct Sha256 {
+    state: [u32; 8],
+    block: [u8; 64],
+    block_len: usize,
+    len: u64,
+}
+
+impl Sha256 {
+    const fn new() -> Self {
+        Sha256 {
+            state: H,
+            block: [0; 64],
+            block_len: 0,
+            len: 0,
+        }
+    }
+
+    const fn update(mut self, bytes: &[u8]) -> Self {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.block[self.block_len] = bytes[i];
+            self.block_len += 1;
+            if self.block_len == 64 {
+                self.state = compress(self.state, &self.block);
+                self.block_len = 0;
+            }
+            i += 1;
+        }
+        self.len += bytes.len() as u64;
+        self
+    }
+
+    const fn finish(self) -> [u8; 32] {
+        let bit_len = self.len * 8;
+        let mut hasher = self.update(&[0x80]);
+        while hasher.block_len != 56 {
+            hasher = hasher.update(&[0]);
+        }
+        hasher = hasher.update(&bit_len.to_be_bytes());
+
+        let mut digest = [0; 32];
+        let mut i = 0;
+        while i < 8 {
+            let word = hasher.state[i].to_be_bytes();
+            digest[4 * i] = word[0];
+            digest[4 * i + 1] = word[1];
+            digest[4 * i + 2] = word[2];
+            digest[4 * i + 3] = word[3];
+            i += 1;
+        }
+        digest
+    }
+}
+
+const fn compress(state: [u32; 8], block: &[u8; 64]) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    let mut i = 0;
+    while i < 16 {
+        w[i] = u32::from_be_bytes([
+            block[4 * i],
+            block[4 * i + 1],
+            block[4 * i + 2],
+            block[4 * i + 3],
+        ]);
+        i += 1;
+    }
+    while i < 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+        i += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    i = 0;
+    while i < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        i += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+// lengths are described as u64s, with unbounded (saturated) ones as u64::MAX, so that the
+// fingerprints are the same on 32 and 64 bit targets
+const fn len_bytes(len: usize) -> [u8; 8] {
+    if len == usize::MAX {
+        u64::MAX.to_le_bytes()
+    } else {
+        (len as u64).to_le_bytes()
+    }
+}
+
+/// The fingerprint of a type described only by its lengths, the default `SSZ_SCHEMA`.
+pub const fn basic(ssz_static: bool, fixed_len: usize, max_len: usize) -> [u8; 32] {
+    Sha256::new()
+        .update(b"basic")
+        .update(&[ssz_static as u8])
+        .update(&len_bytes(fixed_len))
+        .update(&len_bytes(max_len))
+        .finish()
+}
+
+/// The fingerprint of a container with fields of the given fingerprints, in order.
+pub const fn container(fields: &[[u8; 32]]) -> [u8; 32] {
+    let mut hasher = Sha256::new()
+        .update(b"container")
+        .update(&len_bytes(fields.len()));
+    let mut i = 0;
+    while i < fields.len() {
+        hasher = hasher.update(&fields[i]);
+        i += 1;
+    }
+    hasher.finish()
+}
+
+/// The fingerprint of a list of at most `max_len` items with the fingerprint `item`.
+pub const fn list(item: [u8; 32], max_len: usize) -> [u8; 32] {
+    Sha256::new()
+        .update(b"list")
+        .update(&len_bytes(max_len))
+        .update(&item)
+        .finish()
+}
+
+/// The fingerprint of a vector of `len` items with the fingerprint `item`.
+pub const fn vector(item: [u8; 32], len: usize) -> [u8; 32] {
+    Sha256::new()
+        .update(b"vector")
+        .update(&len_bytes(len))
+        .update(&item)
+        .finish()
+}
+
+/// The fingerprint of a field that refers back to the container it's in, described by its type.
+pub const fn recursive(ty: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .update(b"recursive")
+        .update(&len_bytes(ty.len()))
+        .update(ty)
+        .finish()
+}
+
+/// A fingerprint as `0x`-prefixed lowercase hex, the format `assert_schema!` takes.
+pub fn to_hex(fingerprint: &[u8; 32]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(66);
+    hex.push_str("0x");
+    for byte in fingerprint {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+    hex
+}
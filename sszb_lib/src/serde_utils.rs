@@ -0,0 +1,74 @@
+//! Serde helpers that represent a value as its SSZ serialization.
+//!
+//! Annotate a field with `#[serde(with = "sszb::serde_utils::hex")]` (or just
+//! `#[serde(with = "sszb::serde_utils")]`) to (de)serialize it as a `0x`-prefixed hex string,
+//! or with `#[serde(with = "sszb::serde_utils::base64")]` for standard base64.
+use crate::DecodeError;
+use alloc::format;
+use serde::de::Error;
+
+pub use self::hex::{deserialize, serialize};
+
+fn decode_error<E: Error>(e: DecodeError) -> E {
+    E::custom(format!("SSZ decoding failed: {:?}", e))
+}
+
+pub mod hex {
+    use super::decode_error;
+    use crate::{SszbDecode, SszbEncode};
+    use alloc::{format, string::String};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: SszbEncode,
+    {
+        serializer.serialize_str(&format!("0x{}", ::hex::encode(value.to_ssz())))
+    }
+
+    // accepts upper- and lower-case hex digits, but the 0x prefix is mandatory
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: SszbDecode,
+    {
+        let s = String::deserialize(deserializer)?;
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| D::Error::custom("hex string is missing the 0x prefix"))?;
+        let bytes = ::hex::decode(digits)
+            .map_err(|e| D::Error::custom(format!("invalid hex string: {}", e)))?;
+
+        T::from_ssz_bytes(&bytes).map_err(decode_error)
+    }
+}
+
+pub mod base64 {
+    use super::decode_error;
+    use crate::{SszbDecode, SszbEncode};
+    use ::base64::{engine::general_purpose::STANDARD, Engine as _};
+    use alloc::{format, string::String};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: SszbEncode,
+    {
+        serializer.serialize_str(&STANDARD.encode(value.to_ssz()))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: SszbDecode,
+    {
+        let s = String::deserialize(deserializer)?;
+        let bytes = STANDARD
+            .decode(s)
+            .map_err(|e| D::Error::custom(format!("invalid base64 string: {}", e)))?;
+
+        T::from_ssz_bytes(&bytes).map_err(decode_error)
+    }
+}
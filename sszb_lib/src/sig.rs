@@ -1,4 +1,5 @@
-use crate::{DecodeError, SszbDecode, SszbEncode};
+use crate::dynamic::{SszTypeInfo, SszbTypeInfo};
+use crate::{read_contiguous, DecodeError, SszZero, SszbDecode, SszbEncode};
 use bytes::buf::{Buf, BufMut};
 use sigp_bls::{PublicKeyBytes, Signature};
 use tree_hash::TreeHash;
@@ -30,20 +31,12 @@ impl TreeHash for PKBytes {
 }
 
 impl SszbEncode for PKBytes {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        48
-    }
-
-    fn ssz_max_len() -> usize {
-        48
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 48;
+    const SSZ_MAX_LEN: usize = 48;
 
     fn sszb_bytes_len(&self) -> usize {
-        48
+        <Self as SszbEncode>::SSZ_FIXED_LEN
     }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -58,39 +51,48 @@ impl SszbEncode for PKBytes {
 }
 
 impl SszbDecode for PKBytes {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        48
-    }
-
-    fn ssz_max_len() -> usize {
-        48
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 48;
+    const SSZ_MAX_LEN: usize = 48;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
         } else {
-            let res = PublicKeyBytes::deserialize(&fixed_bytes.chunk()[0..48])
-                .map_err(|e| DecodeError::BytesInvalid(format!("{:?}", e)));
-            fixed_bytes.advance(48);
-            Ok(Self(res.unwrap()))
+            let res = read_contiguous(fixed_bytes, 48, PublicKeyBytes::deserialize)
+                .map_err(|_| DecodeError::BlsError("PublicKeyBytes"));
+            Ok(Self(res?))
         }
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PKBytes {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let bytes = <[u8; 48] as arbitrary::Arbitrary>::arbitrary(u)?;
+        PublicKeyBytes::deserialize(&bytes)
+            .map(Self)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct Sig(Signature);
 
+// arbitrary bytes are almost never a valid point, so only the empty signature is generated
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Sig {
+    fn arbitrary(_u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(Signature::empty()))
+    }
+}
+
 impl TreeHash for Sig {
     fn tree_hash_type() -> tree_hash::TreeHashType {
         tree_hash::TreeHashType::Vector
@@ -115,51 +117,34 @@ impl TreeHash for Sig {
 }
 
 impl SszbDecode for Sig {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        96
-    }
-
-    fn ssz_max_len() -> usize {
-        96
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 96;
+    const SSZ_MAX_LEN: usize = 96;
 
     fn ssz_read(
         fixed_bytes: &mut impl Buf,
         _variable_bytes: &mut impl Buf,
     ) -> Result<Self, DecodeError> {
         let len = fixed_bytes.remaining();
-        let expected = <Self as SszbDecode>::ssz_fixed_len();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
 
         if len < expected {
             Err(DecodeError::InvalidByteLength { len, expected })
         } else {
-            let res = Signature::deserialize(&fixed_bytes.chunk()[0..96])
-                .map_err(|e| DecodeError::BytesInvalid(format!("{:?}", e)));
-            fixed_bytes.advance(96);
-            Ok(Self(res.unwrap()))
+            let res = read_contiguous(fixed_bytes, 96, Signature::deserialize)
+                .map_err(|_| DecodeError::BlsError("Signature"));
+            Ok(Self(res?))
         }
     }
 }
 
 impl SszbEncode for Sig {
-    fn is_ssz_static() -> bool {
-        true
-    }
-
-    fn ssz_fixed_len() -> usize {
-        96
-    }
-
-    fn ssz_max_len() -> usize {
-        96
-    }
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 96;
+    const SSZ_MAX_LEN: usize = 96;
 
     fn sszb_bytes_len(&self) -> usize {
-        96
+        <Self as SszbEncode>::SSZ_FIXED_LEN
     }
 
     fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
@@ -172,3 +157,28 @@ impl SszbEncode for Sig {
         buf.put_slice(&self.0.serialize())
     }
 }
+
+impl SszbTypeInfo for PKBytes {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 48)
+    }
+}
+
+impl SszbTypeInfo for Sig {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 96)
+    }
+}
+
+// the empty key and signature, which are serialized as all zeros like any other fixed bytes
+impl SszZero for PKBytes {
+    fn ssz_zero() -> Self {
+        Self(PublicKeyBytes::empty())
+    }
+}
+
+impl SszZero for Sig {
+    fn ssz_zero() -> Self {
+        Self(Signature::empty())
+    }
+}
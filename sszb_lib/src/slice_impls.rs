@@ -0,0 +1,259 @@
+//! `Arc<[T]>` and `Box<[T]>`, the shared and owned slices that caches hand out instead of a `Vec`.
+//!
+//! Both are encoded as SSZ lists of their elements, with no maximum length: their bytes are those
+//! of a `VariableList` of the same elements, whatever its maximum. They go through the same
+//! `collections` functions as the other lists, so static elements are measured without being
+//! visited and written and read in bulk, and a `Box<[T]>` is decoded straight out of the vector
+//! the elements were read into. Being built from those functions, they come with any of the
+//! features that enable a list type.
+
+use crate::{
+    collections::{
+        check_collection_bounds, read_list, read_list_from, read_list_into, read_list_trusted,
+        record_collection_lens, try_collection_bytes_len, write_collection,
+        write_collection_cached, write_collection_cached_vec, write_collection_parallel,
+        write_collection_slice, write_collection_vec, write_collection_vectored, Parallel, Serial,
+        SszCollection,
+    },
+    context::ContextLen,
+    decode::decode_impls::validate_list,
+    expect_len, expect_offset,
+    parallel::{SszbDecodeParallel, SszbEncodeParallel},
+    raw::peek_list_len,
+    vectored::Segments,
+    write_offset, DecodeError, EncodeError, SszReader, SszZero, SszbDecode, SszbEncode,
+    TryFromIter, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use bytes::buf::{Buf, BufMut};
+
+impl<T> TryFromIter<T> for Arc<[T]> {
+    type Error = DecodeError;
+
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+        Ok(iter.collect())
+    }
+}
+
+impl<T> TryFromIter<T> for Box<[T]> {
+    type Error = DecodeError;
+
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+        Ok(iter.collect())
+    }
+}
+
+impl<T> SszCollection<T> for Arc<[T]> {
+    const LIMIT: usize = usize::MAX;
+
+    fn num_items(&self) -> usize {
+        self.len()
+    }
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(&self[..])
+    }
+
+    #[cfg(feature = "rayon")]
+    fn write_parallel(&self, buf: &mut [u8]) -> bool
+    where
+        T: SszbEncode + Sync,
+    {
+        crate::parallel::pool::write_items(self, buf);
+        true
+    }
+
+    fn from_items(items: Vec<T>) -> Result<Self, DecodeError> {
+        Ok(Arc::from(items))
+    }
+}
+
+impl<T> SszCollection<T> for Box<[T]> {
+    const LIMIT: usize = usize::MAX;
+
+    fn num_items(&self) -> usize {
+        self.len()
+    }
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(&self[..])
+    }
+
+    #[cfg(feature = "rayon")]
+    fn write_parallel(&self, buf: &mut [u8]) -> bool
+    where
+        T: SszbEncode + Sync,
+    {
+        crate::parallel::pool::write_items(self, buf);
+        true
+    }
+
+    fn from_items(items: Vec<T>) -> Result<Self, DecodeError> {
+        Ok(items.into_boxed_slice())
+    }
+
+    fn take_items(&mut self) -> Option<Vec<T>> {
+        Some(core::mem::take(self).into_vec())
+    }
+}
+
+macro_rules! impl_ssz_slice {
+    ($pointer:ident) => {
+        impl<T: SszbEncode> SszbEncode for $pointer<[T]> {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+            const SSZ_MAX_LEN: usize = usize::MAX;
+
+            fn sszb_bytes_len(&self) -> usize {
+                expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+                try_collection_bytes_len::<T, Self>(self)
+            }
+
+            // unbounded, so only the items are checked
+            fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+                check_collection_bounds::<T, Self>(self, usize::MAX)
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                expect_offset(write_offset(*offset, buf));
+                *offset += self.sszb_bytes_len();
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                self.ssz_write(buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                write_collection::<T, Self>(self, buf);
+            }
+
+            fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+                write_collection_vectored::<T, Self>(self, segments);
+            }
+
+            fn ssz_record_lens(&self, lens: &mut Vec<usize>) -> usize {
+                record_collection_lens::<T, Self>(self, lens)
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl BufMut) {
+                write_collection_cached::<T, Self>(self, lens, buf);
+            }
+
+            fn ssz_write_vec(&self, buf: &mut Vec<u8>) {
+                write_collection_vec::<T, Self>(self, buf);
+            }
+
+            fn ssz_write_cached_vec(&self, lens: &mut &[usize], buf: &mut Vec<u8>) {
+                write_collection_cached_vec::<T, Self>(self, lens, buf);
+            }
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                write_collection_slice::<T, Self>(self, buf)
+            }
+        }
+
+        impl<T: SszbDecode> SszbDecode for $pointer<[T]> {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+            const SSZ_MAX_LEN: usize = usize::MAX;
+            const SSZ_SCHEMA: [u8; 32] = crate::schema::list(T::SSZ_SCHEMA, usize::MAX);
+
+            fn ssz_read(
+                _fixed_bytes: &mut impl Buf,
+                variable_bytes: &mut impl Buf,
+            ) -> Result<Self, DecodeError> {
+                read_list::<T, Self, Serial>(variable_bytes)
+            }
+
+            fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+                read_list_from::<T, Self>(reader)
+            }
+
+            fn ssz_read_into(
+                &mut self,
+                _fixed_bytes: &mut impl Buf,
+                variable_bytes: &mut impl Buf,
+            ) -> Result<(), DecodeError> {
+                read_list_into::<T, Self>(self, variable_bytes)
+            }
+
+            fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+                validate_list::<T>(bytes, usize::MAX)
+            }
+
+            fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, DecodeError> {
+                read_list_trusted::<T, Self>(bytes)
+            }
+        }
+
+        impl<T: SszbEncode + Sync> SszbEncodeParallel for $pointer<[T]>
+        where
+            Self: Sync,
+        {
+            fn ssz_write_parallel(&self, buf: &mut [u8]) {
+                write_collection_parallel::<T, Self>(self, buf);
+            }
+        }
+
+        impl<T: SszbDecode + Send> SszbDecodeParallel for $pointer<[T]>
+        where
+            Self: Send,
+        {
+            fn ssz_read_parallel(bytes: &[u8]) -> Result<Self, DecodeError> {
+                read_list::<T, Self, Parallel>(&mut &bytes[..])
+            }
+        }
+    };
+}
+
+impl_ssz_slice!(Arc);
+impl_ssz_slice!(Box);
+
+impl<T> SszZero for Arc<[T]> {
+    fn ssz_zero() -> Self {
+        Vec::new().into()
+    }
+}
+
+impl<T> SszZero for Box<[T]> {
+    fn ssz_zero() -> Self {
+        Vec::new().into()
+    }
+}
+
+impl<T: SszbDecode> ContextLen for Arc<[T]> {
+    fn context_len(&self) -> usize {
+        self.len()
+    }
+
+    fn peek_context_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+        peek_list_len::<T>(bytes)
+    }
+}
+
+impl<T: SszbDecode> ContextLen for Box<[T]> {
+    fn context_len(&self) -> usize {
+        self.len()
+    }
+
+    fn peek_context_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+        peek_list_len::<T>(bytes)
+    }
+}
@@ -0,0 +1,324 @@
+use crate::{
+    bitfield_bytes_len, bitlist_bytes_len,
+    collections::{impl_ssz_list, impl_ssz_vector, SszCollection},
+    context::ContextLen,
+    decode::{
+        check_static_len,
+        decode_impls::{
+            bitlist_len, validate_bitvector_bits, EXCESS_BITS, MISSING_LENGTH_BIT, TOO_LONG,
+            WRONG_BYTE_COUNT,
+        },
+        read_contiguous,
+    },
+    dynamic::{SszTypeInfo, SszbTypeInfo},
+    expect_offset, write_offset, DecodeError, EncodeError, SszZero, SszbDecode, SszbEncode,
+    TryFromIter, BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use bytes::buf::{Buf, BufMut};
+use smallvec::ToSmallVec;
+use ssz_types::{BitList, BitVector, Error as SszTypeError, FixedVector, VariableList};
+use typenum::Unsigned;
+
+impl From<SszTypeError> for DecodeError {
+    fn from(error: SszTypeError) -> Self {
+        #[cfg(not(feature = "tiny-errors"))]
+        let error = DecodeError::SszTypes(error);
+        #[cfg(feature = "tiny-errors")]
+        let error = {
+            let _ = error;
+            DecodeError::SszTypes("ssz_types collection")
+        };
+        error
+    }
+}
+
+impl<T, N> TryFromIter<T> for VariableList<T, N>
+where
+    T: SszbDecode,
+    N: Unsigned,
+{
+    type Error = DecodeError;
+
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+        let n = N::USIZE;
+        let iterator = iter.into_iter();
+
+        // Pre-allocate up to `N` elements based on the iterator size hint.
+        let (_, opt_max_len) = iterator.size_hint();
+        let mut l = Self::new(Vec::with_capacity(
+            opt_max_len.map_or(n, |max_len| core::cmp::min(n, max_len)),
+        ))?;
+        for item in iterator {
+            l.push(item).map_err(|_| DecodeError::ListTooLong {
+                len: l.len() + 1,
+                max: n,
+            })?
+        }
+        Ok(l)
+    }
+}
+
+impl<T, N> TryFromIter<T> for FixedVector<T, N>
+where
+    T: SszbDecode,
+    N: Unsigned,
+{
+    type Error = DecodeError;
+
+    fn try_from_iter(iter: impl Iterator<Item = T>) -> Result<Self, Self::Error> {
+        let n = N::USIZE;
+        let iterator = iter.into_iter();
+
+        let (_, opt_max_len) = iterator.size_hint();
+        let mut vec =
+            Vec::with_capacity(opt_max_len.map_or(n, |max_len| core::cmp::min(n, max_len)));
+
+        for item in iterator {
+            // Bail out as soon as the length tries to exceed the limit. This guards against
+            // memory denial-of-service attacks.
+            if vec.len() >= n {
+                return Err(DecodeError::VectorLengthMismatch {
+                    found: vec.len() + 1,
+                    expected: n,
+                });
+            }
+            vec.push(item);
+        }
+        let found = vec.len();
+        Self::new(vec).map_err(|_| DecodeError::VectorLengthMismatch { found, expected: n })
+    }
+}
+
+// describes the bitfield errors that decoding can run into, without allocating
+fn bitfield_error(error: SszTypeError) -> DecodeError {
+    DecodeError::BitfieldError(match error {
+        SszTypeError::ExcessBits => EXCESS_BITS,
+        SszTypeError::MissingLengthInformation => MISSING_LENGTH_BIT,
+        SszTypeError::OutOfBounds { .. } => TOO_LONG,
+        _ => WRONG_BYTE_COUNT,
+    })
+}
+
+impl<N: Unsigned + Clone> SszbEncode for BitVector<N> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = bitfield_bytes_len(N::USIZE);
+    const SSZ_MAX_LEN: usize = bitfield_bytes_len(N::USIZE);
+
+    fn sszb_bytes_len(&self) -> usize {
+        <Self as SszbEncode>::SSZ_FIXED_LEN
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        // ssz_types keeps a byte even for a BitVector<U0>, whose encoding is empty
+        buf.put_slice(&self.as_slice()[..<Self as SszbEncode>::SSZ_FIXED_LEN]);
+    }
+}
+
+impl<N: Unsigned + Clone> SszbDecode for BitVector<N> {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = bitfield_bytes_len(N::USIZE);
+    const SSZ_MAX_LEN: usize = bitfield_bytes_len(N::USIZE);
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = fixed_bytes.remaining();
+        let expected = <Self as SszbDecode>::SSZ_FIXED_LEN;
+
+        if N::USIZE == 0 {
+            Err(DecodeError::ZeroLengthVector)
+        } else if len < expected {
+            Err(DecodeError::InvalidByteLength { len, expected })
+        } else {
+            // only the vector's own bytes are taken, whatever field follows it. the bits of the
+            // last one past N are padding, and have to be clear for the encoding to be the only
+            // one of the value, which is checked before anything is allocated
+            read_contiguous(fixed_bytes, expected, |bytes| {
+                validate_bitvector_bits(bytes, N::USIZE)?;
+                Self::from_bytes(bytes.to_smallvec()).map_err(bitfield_error)
+            })
+        }
+    }
+
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        check_static_len::<Self>(bytes)?;
+        validate_bitvector_bits(bytes, N::USIZE)
+    }
+}
+
+impl<N: Unsigned + Clone> SszbEncode for BitList<N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = bitlist_bytes_len(N::USIZE);
+
+    fn sszb_bytes_len(&self) -> usize {
+        // the bits, plus the length bit that marks where they end
+        self.len() / 8 + 1
+    }
+
+    fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+        if self.len() > N::USIZE {
+            return Err(EncodeError::ListTooLong {
+                len: self.len(),
+                max: N::USIZE,
+            });
+        }
+        Ok(())
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        // the length bit goes right after the last bit, which may take one more byte than the
+        // bits themselves. bits past the end are always zero, so the last byte can just be or'd
+        let len = self.len();
+        let bytes = self.as_slice();
+        buf.put_slice(&bytes[..(len / 8)]);
+        buf.put_u8(bytes.get(len / 8).copied().unwrap_or(0) | (1 << (len % 8)));
+    }
+}
+
+impl<N: Unsigned + Clone> SszbDecode for BitList<N> {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = bitlist_bytes_len(N::USIZE);
+
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = variable_bytes.remaining();
+        read_contiguous(variable_bytes, len, |bytes| {
+            Self::from_bytes(bytes.to_smallvec()).map_err(bitfield_error)
+        })
+    }
+
+    fn validate_ssz_bytes(bytes: &[u8]) -> Result<(), DecodeError> {
+        bitlist_len(bytes, N::USIZE)?;
+        Ok(())
+    }
+}
+
+impl<N: Unsigned> SszbTypeInfo for BitVector<N> {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::Bitvector(N::USIZE)
+    }
+}
+
+impl<N: Unsigned> SszbTypeInfo for BitList<N> {
+    fn ssz_type_info() -> SszTypeInfo {
+        SszTypeInfo::Bitlist(N::USIZE)
+    }
+}
+
+impl<N: Unsigned + Clone> SszZero for BitVector<N> {
+    fn ssz_zero() -> Self {
+        BitVector::new()
+    }
+}
+
+impl<N: Unsigned + Clone> ContextLen for BitList<N> {
+    fn context_len(&self) -> usize {
+        self.len()
+    }
+
+    fn peek_context_len(bytes: &[u8]) -> Result<usize, DecodeError> {
+        bitlist_len(bytes, N::USIZE)
+    }
+}
+
+impl<N: Unsigned + Clone> SszZero for BitList<N> {
+    fn ssz_zero() -> Self {
+        BitList::with_capacity(0).expect("an empty bitlist is never too long")
+    }
+}
+
+impl<T, N: Unsigned> SszCollection<T> for VariableList<T, N> {
+    const LIMIT: usize = N::USIZE;
+
+    fn num_items(&self) -> usize {
+        self.len()
+    }
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(&self[..])
+    }
+
+    #[cfg(feature = "rayon")]
+    fn write_parallel(&self, buf: &mut [u8]) -> bool
+    where
+        T: SszbEncode + Sync,
+    {
+        crate::parallel::pool::write_items(self, buf);
+        true
+    }
+
+    fn from_items(items: Vec<T>) -> Result<Self, DecodeError> {
+        Ok(VariableList::new(items)?)
+    }
+
+    fn take_items(&mut self) -> Option<Vec<T>> {
+        Some(Vec::from(core::mem::replace(self, VariableList::empty())))
+    }
+}
+
+impl<T, N: Unsigned> SszCollection<T> for FixedVector<T, N> {
+    const LIMIT: usize = N::USIZE;
+
+    fn num_items(&self) -> usize {
+        self.len()
+    }
+
+    fn items<'a>(&'a self) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.iter()
+    }
+
+    fn as_slice(&self) -> Option<&[T]> {
+        Some(&self[..])
+    }
+
+    #[cfg(feature = "rayon")]
+    fn write_parallel(&self, buf: &mut [u8]) -> bool
+    where
+        T: SszbEncode + Sync,
+    {
+        crate::parallel::pool::write_items(self, buf);
+        true
+    }
+
+    fn from_items(items: Vec<T>) -> Result<Self, DecodeError> {
+        Ok(FixedVector::new(items)?)
+    }
+
+    fn items_mut(&mut self) -> Option<&mut [T]> {
+        Some(&mut self[..])
+    }
+}
+
+impl_ssz_list!(VariableList, item: []);
+impl_ssz_vector!(FixedVector, item: []);
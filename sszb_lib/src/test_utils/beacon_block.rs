@@ -1,34 +1,31 @@
+//! The bench's `SignedBeaconBlock`, as of Deneb.
+
+use crate::{PKBytes, Sig};
 use alloy_primitives::{Address, FixedBytes, B256, U256};
 use bytes::buf::{Buf, BufMut};
-use itertools::Itertools as _;
 use ssz_types::{BitList, BitVector, FixedVector, VariableList as List};
-use sszb::*;
-use sszb_derive::{SszbDecode, SszbEncode};
-use tree_hash::*;
-use tree_hash_derive::TreeHash;
+use sszb_derive::{SszbDecode, SszbEncode, SszbTypeInfo};
 
 type ByteList<N> = List<u8, N>;
-type ByteVector<N> = FixedVector<u8, N>;
 pub type SignatureBytes = Sig; // ByteVector<typenum::U96>;
 type PublicKeyBytes = PKBytes; // [u8; 48];
 type KZGCommitment = [u8; 48];
-type H32 = [u8; 4];
 type H160 = Address;
 type H256 = B256;
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct SignedBeaconBlock {
     pub message: BeaconBlock,
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct SignedBeaconBlockHeader {
     pub message: BeaconBlockHeader,
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct BeaconBlockHeader {
     pub slot: u64,
     pub proposer_index: u64,
@@ -37,7 +34,7 @@ pub struct BeaconBlockHeader {
     pub body_root: H256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct BeaconBlock {
     pub slot: u64,
     pub proposer_index: u64,
@@ -46,7 +43,7 @@ pub struct BeaconBlock {
     pub body: BeaconBlockBody,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct BeaconBlockBody {
     pub randao_reveal: SignatureBytes,
     pub eth1_data: Eth1Data,
@@ -62,26 +59,26 @@ pub struct BeaconBlockBody {
     pub blob_kzg_commitments: List<KZGCommitment, typenum::U4096>,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct Eth1Data {
     pub deposit_root: H256,
     pub deposit_count: u64,
     pub block_hash: H256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct ProposerSlashing {
     pub signed_header_1: SignedBeaconBlockHeader,
     pub signed_header_2: SignedBeaconBlockHeader,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct Checkpoint {
     pub epoch: u64,
     pub root: H256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct AttestationData {
     pub slot: u64,
     pub index: u64,
@@ -90,27 +87,27 @@ pub struct AttestationData {
     pub target: Checkpoint,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct IndexedAttestation {
     pub attesting_indices: List<u64, typenum::U2048>,
     pub data: AttestationData,
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct AttesterSlashing {
     pub attestation_1: IndexedAttestation,
     pub attestation_2: IndexedAttestation,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct Attestation {
     pub aggregation_bits: BitList<typenum::U2048>,
     pub data: AttestationData,
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct DepositData {
     pub pubkey: PublicKeyBytes,
     pub withdrawal_credentials: H256,
@@ -118,25 +115,25 @@ pub struct DepositData {
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct Deposit {
     pub proof: FixedVector<H256, typenum::U32>,
     pub data: DepositData,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct VoluntaryExit {
     pub epoch: u64,
     pub validator_index: u64,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct SignedVoluntaryExit {
     pub message: VoluntaryExit,
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct SyncAggregate {
     pub sync_committee_bits: BitVector<typenum::U512>,
     pub sync_committee_signature: SignatureBytes,
@@ -144,7 +141,7 @@ pub struct SyncAggregate {
 
 pub type Transaction = ByteList<typenum::U1073741824>;
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct Withdrawal {
     pub index: u64,
     pub validator_index: u64,
@@ -152,7 +149,7 @@ pub struct Withdrawal {
     pub amount: u64,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct ExecutionPayload {
     pub parent_hash: H256,
     pub fee_recipient: H160,
@@ -175,13 +172,13 @@ pub struct ExecutionPayload {
     pub excess_blob_gas: u64,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct SignedBlsToExecutionChange {
     pub message: BlsToExecutionChange,
     pub signature: SignatureBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct BlsToExecutionChange {
     pub validator_index: u64,
     pub from_bls_pubkey: PublicKeyBytes,
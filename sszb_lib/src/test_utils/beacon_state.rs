@@ -1,30 +1,30 @@
+//! The bench's `BeaconState`, as of Deneb, with its large lists and vectors in `ghilhouse` trees.
+
+use crate::PKBytes;
 use alloy_primitives::{Address, B256, U256};
 use bytes::buf::{Buf, BufMut};
 use ghilhouse::{List, Vector};
-use itertools::Itertools as _;
 use ssz_types::{BitVector, FixedVector, VariableList};
-use sszb::*;
-use sszb_derive::{SszbDecode, SszbEncode};
-use tree_hash_derive::TreeHash;
+use sszb_derive::{SszbDecode, SszbEncode, SszbTypeInfo};
 
 type ByteVector<N> = Vector<u8, N>;
 type PublicKeyBytes = PKBytes; //[u8; 48];
 type H32 = ByteVector<typenum::U4>;
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash, Default)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug, Default)]
 pub struct Fork {
     pub previous_version: H32,
     pub current_version: H32,
     pub epoch: u64,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash, Default)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug, Default)]
 pub struct Checkpoint {
     pub epoch: u64,
     pub root: B256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash, Default)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug, Default)]
 pub struct BeaconBlockHeader {
     pub slot: u64,
     pub proposer_index: u64,
@@ -33,14 +33,14 @@ pub struct BeaconBlockHeader {
     pub body_root: B256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash, Default)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug, Default)]
 pub struct Eth1Data {
     pub deposit_root: B256,
     pub deposit_count: u64,
     pub block_hash: B256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct Validator {
     pub pubkey: PublicKeyBytes,
     pub withdrawal_credentials: B256,
@@ -52,13 +52,13 @@ pub struct Validator {
     pub withdrawable_epoch: u64,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct SyncCommittee {
     pub pubkeys: FixedVector<PublicKeyBytes, typenum::U512>,
     pub aggregate_pubkey: PublicKeyBytes,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
 pub struct ExecutionPayloadHeader {
     pub parent_hash: B256,
     pub fee_recipient: Address,
@@ -79,13 +79,14 @@ pub struct ExecutionPayloadHeader {
     pub excess_blob_gas: u64,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash, Default)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug, Default)]
 pub struct HistoricalSummary {
     pub block_summary_root: B256,
     pub state_summary_root: B256,
 }
 
-#[derive(Clone, SszbEncode, SszbDecode, PartialEq, Debug, TreeHash)]
+#[derive(Clone, SszbEncode, SszbDecode, SszbTypeInfo, PartialEq, Debug)]
+#[ssz(parallel)]
 pub struct BeaconState {
     // Versioning
     pub genesis_time: u64,
@@ -95,8 +96,11 @@ pub struct BeaconState {
 
     // History
     pub latest_block_header: BeaconBlockHeader,
+    #[ssz(parallel)]
     pub block_roots: Vector<B256, typenum::U8192>,
+    #[ssz(parallel)]
     pub state_roots: Vector<B256, typenum::U8192>,
+    #[ssz(parallel)]
     pub historical_roots: List<B256, typenum::U16777216>,
 
     // Ethereum 1.0 chain data
@@ -105,17 +109,22 @@ pub struct BeaconState {
     pub eth1_deposit_index: u64,
 
     // Registry
+    #[ssz(parallel)]
     pub validators: List<Validator, typenum::U1099511627776>,
+    #[ssz(parallel)]
     pub balances: List<u64, typenum::U1099511627776>,
 
     // Randomness
+    #[ssz(parallel)]
     pub randao_mixes: Vector<B256, typenum::U65536>,
 
     // Slashings
     pub slashings: Vector<u64, typenum::U8192>,
 
     // Participation (Altair and later)
+    #[ssz(parallel)]
     pub previous_epoch_participation: List<u8, typenum::U1099511627776>,
+    #[ssz(parallel)]
     pub current_epoch_participation: List<u8, typenum::U1099511627776>,
 
     // Finality
@@ -125,6 +134,7 @@ pub struct BeaconState {
     pub finalized_checkpoint: Checkpoint,
 
     // Inactivity
+    #[ssz(parallel)]
     pub inactivity_scores: List<u64, typenum::U1099511627776>,
 
     // Light-client sync committees
@@ -0,0 +1,355 @@
+//! Deterministic fixtures of a realistic shape, for benches and integration tests that shouldn't
+//! depend on files of mainnet data.
+//!
+//! `signed_beacon_block` and `beacon_state` build the bench's Deneb `SignedBeaconBlock` and
+//! `BeaconState` from a seed and a size, and return them with their serialization:
+//!
+//! ```ignore
+//! let (state, bytes) = beacon_state(7, &StateConfig { validators: 1_000 });
+//! assert_eq!(BeaconState::from_ssz_bytes(&bytes)?, state);
+//! ```
+//!
+//! The same seed and size always give the same bytes. Every signature is the empty one, since
+//! random bytes are almost never a valid point, while public keys are random bytes, which are
+//! only checked for their length.
+
+pub mod beacon_block;
+pub mod beacon_state;
+
+pub use beacon_block::SignedBeaconBlock;
+pub use beacon_state::BeaconState;
+
+use crate::{PKBytes, Sig, SszbDecode, SszbEncode};
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, B256, U256};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+
+/// The size of a generated block. The default is about as full as a mainnet block gets.
+#[derive(Clone, Debug)]
+pub struct BlockConfig {
+    /// At most 128.
+    pub attestations: usize,
+    pub transactions: usize,
+    /// The total length of the transactions, spread unevenly over them.
+    pub transaction_bytes: usize,
+}
+
+impl Default for BlockConfig {
+    fn default() -> Self {
+        Self {
+            attestations: 128,
+            transactions: 200,
+            transaction_bytes: 100_000,
+        }
+    }
+}
+
+/// The size of a generated state. The default is a tenth of mainnet's validator count, which
+/// already makes for a state of more than 15MB.
+#[derive(Clone, Debug)]
+pub struct StateConfig {
+    pub validators: usize,
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        Self {
+            validators: 100_000,
+        }
+    }
+}
+
+// splitmix64, which is all a fixture needs to be the same on every platform and every run
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // in 0..n, or 0 if n is 0
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n.max(1)
+    }
+
+    fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0; N];
+        self.fill(&mut bytes);
+        bytes
+    }
+
+    fn root(&mut self) -> B256 {
+        B256::from(self.bytes::<32>())
+    }
+
+    fn roots(&mut self, n: usize) -> Vec<B256> {
+        (0..n).map(|_| self.root()).collect()
+    }
+
+    fn address(&mut self) -> Address {
+        Address::from(self.bytes::<20>())
+    }
+
+    fn pubkey(&mut self) -> PKBytes {
+        PKBytes::from_ssz_bytes(&self.bytes::<48>()).expect("public keys are any 48 bytes")
+    }
+}
+
+fn empty_signature() -> Sig {
+    Sig::from_ssz_bytes(&[0; 96]).expect("the empty signature is all zeros")
+}
+
+/// Generates a `SignedBeaconBlock` of the given size from `seed`, and its serialization.
+pub fn signed_beacon_block(seed: u64, config: &BlockConfig) -> (SignedBeaconBlock, Vec<u8>) {
+    use beacon_block::*;
+
+    let rng = &mut Rng(seed);
+    let slot = 8_000_000 + rng.below(1_000_000);
+    let epoch = slot / 32;
+    let checkpoint = |rng: &mut Rng, epoch| Checkpoint {
+        epoch,
+        root: rng.root(),
+    };
+
+    let attestations = (0..config.attestations)
+        .map(|_| {
+            let committee_len = 64 + rng.below(448) as usize;
+            let mut aggregation_bits = BitList::with_capacity(committee_len).unwrap();
+            for i in 0..committee_len {
+                aggregation_bits.set(i, rng.below(8) != 0).unwrap();
+            }
+            Attestation {
+                aggregation_bits,
+                data: AttestationData {
+                    slot: slot - 1 - rng.below(32),
+                    index: rng.below(64),
+                    beacon_block_root: rng.root(),
+                    source: checkpoint(rng, epoch - 1),
+                    target: checkpoint(rng, epoch),
+                },
+                signature: empty_signature(),
+            }
+        })
+        .collect();
+
+    // each transaction gets up to twice its share of what's left, and the last one the rest
+    let mut remaining = config.transaction_bytes;
+    let transactions = (0..config.transactions)
+        .map(|i| {
+            let left = config.transactions - i;
+            let len = if left == 1 {
+                remaining
+            } else {
+                rng.below((2 * remaining / left) as u64 + 1) as usize
+            };
+            remaining -= len;
+            let mut transaction = alloc::vec![0; len];
+            rng.fill(&mut transaction);
+            Transaction::new(transaction).unwrap()
+        })
+        .collect();
+
+    let withdrawals = (0..16)
+        .map(|i| Withdrawal {
+            index: 50_000_000 + i,
+            validator_index: rng.below(1_000_000),
+            address: rng.address(),
+            amount: rng.below(100_000_000),
+        })
+        .collect();
+
+    let mut sync_committee_bits = BitVector::new();
+    for i in 0..512 {
+        sync_committee_bits.set(i, rng.below(16) != 0).unwrap();
+    }
+
+    let block = SignedBeaconBlock {
+        message: BeaconBlock {
+            slot,
+            proposer_index: rng.below(1_000_000),
+            parent_root: rng.root(),
+            state_root: rng.root(),
+            body: BeaconBlockBody {
+                randao_reveal: empty_signature(),
+                eth1_data: Eth1Data {
+                    deposit_root: rng.root(),
+                    deposit_count: rng.below(2_000_000),
+                    block_hash: rng.root(),
+                },
+                graffiti: FixedBytes::from(rng.bytes::<32>()),
+                proposer_slashings: VariableList::default(),
+                attester_slashings: VariableList::default(),
+                attestations: VariableList::new(attestations).unwrap(),
+                deposits: VariableList::default(),
+                voluntary_exits: VariableList::default(),
+                sync_aggregate: SyncAggregate {
+                    sync_committee_bits,
+                    sync_committee_signature: empty_signature(),
+                },
+                execution_payload: ExecutionPayload {
+                    parent_hash: rng.root(),
+                    fee_recipient: rng.address(),
+                    state_root: rng.root(),
+                    receipts_root: rng.root(),
+                    logs_bloom: FixedBytes::from(rng.bytes::<256>()),
+                    prev_randao: rng.root(),
+                    block_number: 19_000_000 + rng.below(1_000_000),
+                    gas_limit: 30_000_000,
+                    gas_used: rng.below(30_000_000),
+                    timestamp: 1_606_824_023 + slot * 12,
+                    extra_data: VariableList::new(rng.bytes::<12>().to_vec()).unwrap(),
+                    base_fee_per_gas: U256::from(rng.below(100_000_000_000)),
+                    block_hash: rng.root(),
+                    transactions: VariableList::new(transactions).unwrap(),
+                    withdrawals: VariableList::new(withdrawals).unwrap(),
+                    blob_gas_used: 131_072 * rng.below(7),
+                    excess_blob_gas: rng.below(100_000_000),
+                },
+                bls_to_execution_changes: VariableList::default(),
+                blob_kzg_commitments: VariableList::new(
+                    (0..3).map(|_| rng.bytes::<48>()).collect(),
+                )
+                .unwrap(),
+            },
+        },
+        signature: empty_signature(),
+    };
+    let bytes = block.to_ssz();
+    (block, bytes)
+}
+
+/// Generates a `BeaconState` of the given size from `seed`, and its serialization.
+pub fn beacon_state(seed: u64, config: &StateConfig) -> (BeaconState, Vec<u8>) {
+    use beacon_state::*;
+    use ghilhouse::{List, Vector};
+
+    let rng = &mut Rng(seed);
+    let slot = 8_000_000 + rng.below(1_000_000);
+    let epoch = slot / 32;
+    let validators = config.validators;
+    let checkpoint = |rng: &mut Rng, epoch| Checkpoint {
+        epoch,
+        root: rng.root(),
+    };
+    let eth1_data = |rng: &mut Rng| Eth1Data {
+        deposit_root: rng.root(),
+        deposit_count: validators as u64,
+        block_hash: rng.root(),
+    };
+    let sync_committee = |rng: &mut Rng| SyncCommittee {
+        pubkeys: FixedVector::new((0..512).map(|_| rng.pubkey()).collect()).unwrap(),
+        aggregate_pubkey: rng.pubkey(),
+    };
+
+    let mut justification_bits = BitVector::new();
+    for i in 0..4 {
+        justification_bits.set(i, rng.below(2) != 0).unwrap();
+    }
+
+    let state = BeaconState {
+        genesis_time: 1_606_824_023,
+        genesis_validators_root: rng.root(),
+        slot,
+        fork: Fork {
+            previous_version: Vector::new(alloc::vec![3, 0, 0, 0]).unwrap(),
+            current_version: Vector::new(alloc::vec![4, 0, 0, 0]).unwrap(),
+            epoch: epoch - rng.below(epoch),
+        },
+        latest_block_header: BeaconBlockHeader {
+            slot,
+            proposer_index: rng.below(validators as u64),
+            parent_root: rng.root(),
+            state_root: B256::ZERO,
+            body_root: rng.root(),
+        },
+        block_roots: Vector::new(rng.roots(8192)).unwrap(),
+        state_roots: Vector::new(rng.roots(8192)).unwrap(),
+        historical_roots: List::new(rng.roots(758)).unwrap(),
+        eth1_data: eth1_data(rng),
+        eth1_data_votes: List::new((0..rng.below(2048)).map(|_| eth1_data(rng)).collect()).unwrap(),
+        eth1_deposit_index: validators as u64,
+        validators: List::new(
+            (0..validators)
+                .map(|_| {
+                    let activation_epoch = rng.below(epoch);
+                    Validator {
+                        pubkey: rng.pubkey(),
+                        withdrawal_credentials: rng.root(),
+                        effective_balance: 32_000_000_000,
+                        slashed: rng.below(1000) == 0,
+                        activation_eligibility_epoch: activation_epoch.saturating_sub(4),
+                        activation_epoch,
+                        exit_epoch: u64::MAX,
+                        withdrawable_epoch: u64::MAX,
+                    }
+                })
+                .collect(),
+        )
+        .unwrap(),
+        balances: List::new(
+            (0..validators)
+                .map(|_| 32_000_000_000 + rng.below(100_000_000))
+                .collect(),
+        )
+        .unwrap(),
+        randao_mixes: Vector::new(rng.roots(65536)).unwrap(),
+        slashings: Vector::new((0..8192).map(|_| rng.below(4) * 1_000_000_000).collect()).unwrap(),
+        previous_epoch_participation: List::new(
+            (0..validators).map(|_| rng.below(8) as u8).collect(),
+        )
+        .unwrap(),
+        current_epoch_participation: List::new(
+            (0..validators).map(|_| rng.below(8) as u8).collect(),
+        )
+        .unwrap(),
+        justification_bits,
+        previous_justified_checkpoint: checkpoint(rng, epoch - 2),
+        current_justified_checkpoint: checkpoint(rng, epoch - 1),
+        finalized_checkpoint: checkpoint(rng, epoch - 2),
+        inactivity_scores: List::new((0..validators).map(|_| rng.below(4)).collect()).unwrap(),
+        current_sync_committee: sync_committee(rng),
+        next_sync_committee: sync_committee(rng),
+        latest_execution_payload_header: ExecutionPayloadHeader {
+            parent_hash: rng.root(),
+            fee_recipient: rng.address(),
+            state_root: rng.root(),
+            receipts_root: rng.root(),
+            logs_bloom: FixedVector::new(rng.bytes::<256>().to_vec()).unwrap(),
+            prev_randao: rng.root(),
+            block_number: 19_000_000 + rng.below(1_000_000),
+            gas_limit: 30_000_000,
+            gas_used: rng.below(30_000_000),
+            timestamp: 1_606_824_023 + slot * 12,
+            extra_data: VariableList::new(rng.bytes::<12>().to_vec()).unwrap(),
+            base_fee_per_gas: U256::from(rng.below(100_000_000_000)),
+            block_hash: rng.root(),
+            transactions_root: rng.root(),
+            withdrawals_root: rng.root(),
+            blob_gas_used: 131_072 * rng.below(7),
+            excess_blob_gas: rng.below(100_000_000),
+        },
+        next_withdrawal_index: 50_000_000 + rng.below(1_000_000),
+        next_withdrawal_validator_index: rng.below(validators as u64),
+        historical_summaries: List::new(
+            (0..rng.below(512))
+                .map(|_| HistoricalSummary {
+                    block_summary_root: rng.root(),
+                    state_summary_root: rng.root(),
+                })
+                .collect(),
+        )
+        .unwrap(),
+    };
+    let bytes = state.to_ssz();
+    (state, bytes)
+}
@@ -0,0 +1,416 @@
+//! Property testing helpers for SSZ types.
+//!
+//! The strategy constructors take element strategies as arguments, so nested types are built by
+//! composition (e.g. `variable_list::<_, U4>(variable_list::<_, U8>(any::<u8>()))`). Containers
+//! are built by mapping a tuple of field strategies, e.g.
+//! `(any::<u16>(), bit_list::<U8>()).prop_map(|(a, b)| Foo { a, b })`.
+//!
+//! `roundtrip_ssz!` turns a type and a strategy into the standard set of property tests,
+//! `verify_encode_contract` checks the encode impl of a single value, and `check_fragmented`
+//! decodes a fixture out of buffers split into tiny chunks.
+use crate::{
+    decode::check_static_len, offset_bytes, DecodeError, SszbDecode, SszbEncode,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use bytes::Buf;
+use ethereum_types::H256;
+use milhouse::{List as PersistentList, Value, Vector as PersistentVector};
+use proptest::{collection::vec, prelude::*};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use std::fmt::Debug;
+use typenum::Unsigned;
+
+pub use proptest;
+
+#[doc(hidden)]
+pub use paste::paste;
+
+/// Lists of `0..=N` elements drawn from `element`.
+pub fn variable_list<T, N>(
+    element: impl Strategy<Value = T>,
+) -> impl Strategy<Value = VariableList<T, N>>
+where
+    T: Debug,
+    N: Unsigned + Debug,
+{
+    vec(element, 0..=N::USIZE).prop_map(|items| VariableList::new(items).unwrap())
+}
+
+/// Vectors of exactly `N` elements drawn from `element`.
+pub fn fixed_vector<T, N>(
+    element: impl Strategy<Value = T>,
+) -> impl Strategy<Value = FixedVector<T, N>>
+where
+    T: Debug,
+    N: Unsigned + Debug,
+{
+    vec(element, N::USIZE).prop_map(|items| FixedVector::new(items).unwrap())
+}
+
+/// Milhouse lists of `0..=N` elements drawn from `element`.
+pub fn persistent_list<T, N>(
+    element: impl Strategy<Value = T>,
+) -> impl Strategy<Value = PersistentList<T, N>>
+where
+    T: Value + Debug,
+    N: Unsigned + Debug,
+{
+    vec(element, 0..=N::USIZE).prop_map(|items| PersistentList::new(items).unwrap())
+}
+
+/// Milhouse vectors of exactly `N` elements drawn from `element`.
+pub fn persistent_vector<T, N>(
+    element: impl Strategy<Value = T>,
+) -> impl Strategy<Value = PersistentVector<T, N>>
+where
+    T: Value + Debug,
+    N: Unsigned + Debug,
+{
+    vec(element, N::USIZE).prop_map(|items| PersistentVector::new(items).unwrap())
+}
+
+/// Bitlists of a random length in `0..=N` with random bits set.
+pub fn bit_list<N: Unsigned + Clone + Debug>() -> impl Strategy<Value = BitList<N>> {
+    (0..=N::USIZE)
+        .prop_flat_map(|len| vec(any::<bool>(), len))
+        .prop_map(|bits| {
+            let mut bitlist = BitList::with_capacity(bits.len()).unwrap();
+            for (i, bit) in bits.into_iter().enumerate() {
+                bitlist.set(i, bit).unwrap();
+            }
+            bitlist
+        })
+}
+
+/// Bitvectors with random bits set.
+pub fn bit_vector<N: Unsigned + Clone + Debug>() -> impl Strategy<Value = BitVector<N>> {
+    vec(any::<bool>(), N::USIZE).prop_map(|bits| {
+        let mut bitvector = BitVector::new();
+        for (i, bit) in bits.into_iter().enumerate() {
+            bitvector.set(i, bit).unwrap();
+        }
+        bitvector
+    })
+}
+
+pub fn address() -> impl Strategy<Value = Address> {
+    any::<[u8; 20]>().prop_map(Address::from)
+}
+
+pub fn h256() -> impl Strategy<Value = H256> {
+    any::<[u8; 32]>().prop_map(H256::from)
+}
+
+pub fn u256() -> impl Strategy<Value = U256> {
+    any::<[u8; 32]>().prop_map(U256::from_le_bytes)
+}
+
+/// Checks that the encode impl of `value`'s type keeps to the contract of `SszbEncode`, and
+/// panics with the first thing it breaks:
+///
+/// - `ssz_write` writes `sszb_bytes_len()` bytes, which are within `SSZ_MAX_LEN`, and
+///   `try_sszb_bytes_len`, `to_ssz`, `ssz_write_slice` and the cached writes agree with it
+/// - a static type's `ssz_write_fixed` writes its `SSZ_FIXED_LEN` bytes, the same as
+///   `ssz_write`, and leaves the offset alone, and its `ssz_write_variable` writes nothing
+/// - a variable type's `SSZ_FIXED_LEN` is `BYTES_PER_LENGTH_OFFSET`, its `ssz_write_fixed` writes
+///   the offset it's given and advances it by `sszb_bytes_len()`, and its `ssz_write_variable`
+///   writes the same bytes as `ssz_write`
+///
+/// Hand-written impls, containers in particular, can run it over a few values in their tests.
+pub fn verify_encode_contract<T: SszbEncode + Debug>(value: &T) {
+    let len = value.sszb_bytes_len();
+    let mut bytes = Vec::new();
+    value.ssz_write(&mut bytes);
+    assert_eq!(
+        bytes.len(),
+        len,
+        "{:?}: ssz_write wrote a different number of bytes than sszb_bytes_len()",
+        value
+    );
+    assert_eq!(
+        value.try_sszb_bytes_len(),
+        Ok(len),
+        "{:?}: try_sszb_bytes_len() disagrees with sszb_bytes_len()",
+        value
+    );
+    assert!(
+        len <= T::SSZ_MAX_LEN,
+        "{:?}: {} bytes long, past SSZ_MAX_LEN",
+        value,
+        len
+    );
+    assert_eq!(
+        value.to_ssz(),
+        bytes,
+        "{:?}: to_ssz disagrees with ssz_write",
+        value
+    );
+
+    let mut slice = Vec::new();
+    slice.resize(len, 0);
+    assert_eq!(
+        value.ssz_write_slice(&mut slice),
+        len,
+        "{:?}: ssz_write_slice wrote a different number of bytes than sszb_bytes_len()",
+        value
+    );
+    assert_eq!(
+        slice, bytes,
+        "{:?}: ssz_write_slice disagrees with ssz_write",
+        value
+    );
+
+    let mut lens = Vec::new();
+    assert_eq!(
+        value.ssz_record_lens(&mut lens),
+        len,
+        "{:?}: ssz_record_lens disagrees with sszb_bytes_len()",
+        value
+    );
+    let mut cached = Vec::new();
+    let mut unused = lens.as_slice();
+    value.ssz_write_cached(&mut unused, &mut cached);
+    assert_eq!(
+        cached, bytes,
+        "{:?}: ssz_write_cached disagrees with ssz_write",
+        value
+    );
+    assert!(
+        unused.is_empty(),
+        "{:?}: ssz_write_cached left recorded lengths unused",
+        value
+    );
+
+    // as the field of a container whose variable part starts at START
+    const START: usize = 100;
+    let mut offset = START;
+    let mut fixed = Vec::new();
+    value.ssz_write_fixed(&mut offset, &mut fixed);
+    let mut variable = Vec::new();
+    value.ssz_write_variable(&mut variable);
+    if T::SSZ_STATIC {
+        assert_eq!(
+            len,
+            T::SSZ_FIXED_LEN,
+            "{:?}: static, but not SSZ_FIXED_LEN bytes long",
+            value
+        );
+        assert_eq!(
+            fixed, bytes,
+            "{:?}: ssz_write_fixed of a static value has to write all of it",
+            value
+        );
+        assert_eq!(
+            offset, START,
+            "{:?}: ssz_write_fixed of a static value moved the offset",
+            value
+        );
+        assert!(
+            variable.is_empty(),
+            "{:?}: ssz_write_variable of a static value wrote bytes",
+            value
+        );
+    } else {
+        assert_eq!(
+            T::SSZ_FIXED_LEN,
+            BYTES_PER_LENGTH_OFFSET,
+            "{:?}: variable, but its fixed part isn't an offset",
+            value
+        );
+        assert_eq!(
+            fixed,
+            offset_bytes(START),
+            "{:?}: ssz_write_fixed of a variable value has to write the offset it's given",
+            value
+        );
+        assert_eq!(
+            offset,
+            START + len,
+            "{:?}: ssz_write_fixed of a variable value has to advance the offset past it",
+            value
+        );
+        assert_eq!(
+            variable, bytes,
+            "{:?}: ssz_write_variable disagrees with ssz_write",
+            value
+        );
+    }
+}
+
+/// A `Buf` over `bytes` in chunks of `segment_len` bytes, like a `Chain` of that many-byte
+/// segments. Decoding out of one splits every field that's longer than a segment across chunks,
+/// which `remaining()` alone doesn't tell an impl about.
+#[derive(Clone, Debug)]
+pub struct Fragmented<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    segment_len: usize,
+}
+
+impl<'a> Fragmented<'a> {
+    pub fn new(bytes: &'a [u8], segment_len: usize) -> Self {
+        assert!(segment_len > 0, "segments have at least one byte");
+        Fragmented {
+            bytes,
+            position: 0,
+            segment_len,
+        }
+    }
+}
+
+impl Buf for Fragmented<'_> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    // the rest of the segment the position is in, however far into it that is
+    fn chunk(&self) -> &[u8] {
+        let end = (self.position / self.segment_len + 1) * self.segment_len;
+        &self.bytes[self.position..end.min(self.bytes.len())]
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= self.remaining(),
+            "advanced past the end of the buffer"
+        );
+        self.position += cnt;
+    }
+}
+
+/// The segment lengths `check_fragmented` splits its input into.
+pub const SEGMENT_LENS: [usize; 3] = [1, 2, 3];
+
+/// `from_ssz_bytes`, but with `ssz_read` reading `bytes` out of a `Fragmented` buffer of
+/// `segment_len`-byte segments. A value that decodes has to consume all of its bytes.
+pub fn read_fragmented<T: SszbDecode>(bytes: &[u8], segment_len: usize) -> Result<T, DecodeError> {
+    if T::SSZ_STATIC {
+        check_static_len::<T>(bytes)?;
+    }
+
+    let mut buf = Fragmented::new(bytes, segment_len);
+    let mut empty = Fragmented::new(&[], segment_len);
+    let value = if T::SSZ_STATIC {
+        T::ssz_read(&mut buf, &mut empty)?
+    } else {
+        T::ssz_read(&mut empty, &mut buf)?
+    };
+    assert_eq!(
+        buf.remaining(),
+        0,
+        "{}::ssz_read left bytes of a fragmented buffer unread",
+        core::any::type_name::<T>()
+    );
+    Ok(value)
+}
+
+/// Checks that decoding `bytes` out of buffers split into the `SEGMENT_LENS` gives what
+/// `from_ssz_bytes` gives, the same value or the same error, and panics otherwise. Valid bytes
+/// are also decoded over a value with `ssz_read_into`.
+pub fn check_fragmented<T: SszbDecode + PartialEq + Debug>(bytes: &[u8]) {
+    let expected = T::from_ssz_bytes(bytes);
+    for segment_len in SEGMENT_LENS {
+        assert_eq!(
+            read_fragmented::<T>(bytes, segment_len),
+            expected,
+            "decoding {:?} out of {}-byte segments",
+            bytes,
+            segment_len
+        );
+
+        if let Ok(expected) = &expected {
+            let mut value = T::from_ssz_bytes(bytes).unwrap();
+            let mut buf = Fragmented::new(bytes, segment_len);
+            let mut empty = Fragmented::new(&[], segment_len);
+            let result = if T::SSZ_STATIC {
+                value.ssz_read_into(&mut buf, &mut empty)
+            } else {
+                value.ssz_read_into(&mut empty, &mut buf)
+            };
+            assert_eq!(
+                result,
+                Ok(()),
+                "decoding {:?} into a value out of {}-byte segments",
+                bytes,
+                segment_len
+            );
+            assert_eq!(&value, expected);
+        }
+    }
+}
+
+/// Generates the standard SSZ property tests for a type:
+///
+/// - `decode(encode(x)) == x`
+/// - `encode(x).len() == x.sszb_bytes_len()`
+/// - `encode(x)` followed by extra bytes does not decode back to `x`
+/// - the encode and decode impls have the same `SSZ_MAX_LEN`, which `x.sszb_bytes_len()` is within
+/// - the encode impl keeps to the contract `verify_encode_contract` checks
+/// - `encode(x)` decodes the same out of a fragmented buffer, see `check_fragmented`
+///
+/// The tests are placed in a module named after the type (`roundtrip_<type>`), so generic
+/// types need an explicit module name:
+///
+/// ```ignore
+/// roundtrip_ssz!(Checkpoint, checkpoint_strategy());
+/// roundtrip_ssz!(byte_list: VariableList<u8, U32>, variable_list(any::<u8>()));
+/// ```
+#[macro_export]
+macro_rules! roundtrip_ssz {
+    ($ty:ident, $strategy:expr) => {
+        $crate::testing::paste! {
+            $crate::roundtrip_ssz!([<$ty:snake>]: $ty, $strategy);
+        }
+    };
+    ($name:ident: $ty:ty, $strategy:expr) => {
+        $crate::testing::paste! {
+            mod [<roundtrip_ $name>] {
+                #[allow(unused_imports)]
+                use super::*;
+                use $crate::testing::proptest::prelude::*;
+                use $crate::{SszbDecode, SszbEncode};
+
+                $crate::testing::proptest::proptest! {
+                    #[test]
+                    fn encode_decode(item in $strategy) {
+                        let bytes = SszbEncode::to_ssz(&item);
+                        prop_assert_eq!(<$ty as SszbDecode>::from_ssz_bytes(&bytes), Ok(item));
+                    }
+
+                    #[test]
+                    fn bytes_len(item in $strategy) {
+                        prop_assert_eq!(SszbEncode::to_ssz(&item).len(), item.sszb_bytes_len());
+                    }
+
+                    #[test]
+                    fn trailing_bytes(item in $strategy, extra in 1usize..8) {
+                        let mut bytes = SszbEncode::to_ssz(&item);
+                        bytes.resize(bytes.len() + extra, 0);
+                        prop_assert_ne!(<$ty as SszbDecode>::from_ssz_bytes(&bytes), Ok(item));
+                    }
+
+                    #[test]
+                    fn max_len(item in $strategy) {
+                        prop_assert_eq!(
+                            <$ty as SszbEncode>::SSZ_MAX_LEN,
+                            <$ty as SszbDecode>::SSZ_MAX_LEN
+                        );
+                        prop_assert!(item.sszb_bytes_len() <= <$ty as SszbEncode>::SSZ_MAX_LEN);
+                    }
+
+                    #[test]
+                    fn encode_contract(item in $strategy) {
+                        $crate::testing::verify_encode_contract(&item);
+                    }
+
+                    #[test]
+                    fn fragmented(item in $strategy) {
+                        $crate::testing::check_fragmented::<$ty>(&SszbEncode::to_ssz(&item));
+                    }
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,131 @@
+use crate::{
+    expect_len, expect_offset, read_contiguous, vectored::Segments, write_offset, DecodeError,
+    EncodeError, SszbDecode, SszbEncode, BYTES_PER_LENGTH_OFFSET,
+};
+use bytes::buf::{Buf, BufMut};
+
+// ready-made unions, encoded like a derived enum whose variants each hold one value: a selector
+// byte (the variant's index) followed by the value
+macro_rules! union_type {
+    ($(#[$attr: meta])* $name: ident { $($selector: literal => $variant: ident),+ }) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name<$($variant),+> {
+            $($variant($variant)),+
+        }
+
+        impl<$($variant: SszbEncode),+> SszbEncode for $name<$($variant),+> {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+            // the selector, followed by the largest variant
+            const SSZ_MAX_LEN: usize = {
+                let mut max_len: usize = 0;
+                $(
+                    if <$variant as SszbEncode>::SSZ_MAX_LEN > max_len {
+                        max_len = <$variant as SszbEncode>::SSZ_MAX_LEN;
+                    }
+                )+
+                max_len.saturating_add(1)
+            };
+
+            fn sszb_bytes_len(&self) -> usize {
+                expect_len(self.try_sszb_bytes_len(), core::any::type_name::<Self>())
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, EncodeError> {
+                let len = match self {
+                    $(Self::$variant(value) => value.try_sszb_bytes_len()?,)+
+                };
+                len.checked_add(1).ok_or(EncodeError::LengthOverflow)
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), EncodeError> {
+                match self {
+                    $(Self::$variant(value) => value.ssz_check_bounds(),)+
+                }
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+                expect_offset(write_offset(*offset, buf));
+                *offset += self.sszb_bytes_len();
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+                self.ssz_write(buf);
+            }
+
+            fn ssz_write(&self, buf: &mut impl BufMut) {
+                match self {
+                    $(
+                        Self::$variant(value) => {
+                            buf.put_u8($selector);
+                            value.ssz_write(buf);
+                        }
+                    )+
+                }
+            }
+
+            fn ssz_write_vectored<'segments>(&'segments self, segments: &mut Segments<'segments>) {
+                match self {
+                    $(
+                        Self::$variant(value) => {
+                            segments.scratch().put_u8($selector);
+                            value.ssz_write_vectored(segments);
+                        }
+                    )+
+                }
+            }
+        }
+
+        impl<$($variant: SszbDecode),+> SszbDecode for $name<$($variant),+> {
+            const SSZ_STATIC: bool = false;
+            const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+            const SSZ_MAX_LEN: usize = {
+                let mut max_len: usize = 0;
+                $(
+                    if <$variant as SszbDecode>::SSZ_MAX_LEN > max_len {
+                        max_len = <$variant as SszbDecode>::SSZ_MAX_LEN;
+                    }
+                )+
+                max_len.saturating_add(1)
+            };
+
+            fn ssz_read(
+                _fixed_bytes: &mut impl Buf,
+                variable_bytes: &mut impl Buf,
+            ) -> Result<Self, DecodeError> {
+                let len = variable_bytes.remaining();
+                read_contiguous(variable_bytes, len, |bytes| {
+                    let (selector, body) = bytes
+                        .split_first()
+                        .ok_or(DecodeError::InvalidByteLength { len: 0, expected: 1 })?;
+
+                    Ok(match *selector {
+                        $(
+                            $selector => Self::$variant(
+                                <$variant as SszbDecode>::from_ssz_bytes(body)
+                                    .map_err(|e| e.at_byte(1))?,
+                            ),
+                        )+
+                        selector => return Err(DecodeError::UnionSelectorInvalid(selector)),
+                    })
+                })
+            }
+        }
+    };
+}
+
+union_type! {
+    /// An SSZ union of two types, for when declaring an enum isn't worth it.
+    Union2 { 0 => A, 1 => B }
+}
+
+union_type! {
+    /// An SSZ union of three types.
+    Union3 { 0 => A, 1 => B, 2 => C }
+}
+
+union_type! {
+    /// An SSZ union of four types.
+    Union4 { 0 => A, 1 => B, 2 => C, 3 => D }
+}
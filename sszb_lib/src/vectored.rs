@@ -0,0 +1,87 @@
+//! Encoding into a list of segments rather than one contiguous buffer.
+//!
+//! `SszbEncode::ssz_write_vectored` writes fixed fields, offsets and small values into a scratch
+//! buffer, but byte lists and vectors of at least `VECTORED_THRESHOLD` bytes are borrowed from
+//! the value being encoded instead of copied. The segments can be handed to a vectored write
+//! (`Segments::io_slices`, with the `std` feature), or flattened when one buffer is needed:
+//!
+//! ```ignore
+//! let mut segments = Segments::new();
+//! block.ssz_write_vectored(&mut segments);
+//! socket.write_vectored(&segments.io_slices())?;
+//! ```
+use alloc::vec::Vec;
+
+/// Byte lists and vectors at least this long are borrowed rather than copied.
+pub const VECTORED_THRESHOLD: usize = 1024;
+
+/// The output of `ssz_write_vectored`: a scratch buffer, interleaved with slices borrowed from
+/// the encoded value.
+#[derive(Debug, Default, Clone)]
+pub struct Segments<'a> {
+    scratch: Vec<u8>,
+    // each borrowed slice, along with how much had been written to scratch before it
+    borrowed: Vec<(usize, &'a [u8])>,
+}
+
+impl<'a> Segments<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The buffer that everything that isn't borrowed is written into.
+    pub fn scratch(&mut self) -> &mut Vec<u8> {
+        &mut self.scratch
+    }
+
+    /// Appends `bytes` as a segment of its own, without copying it.
+    pub fn push_borrowed(&mut self, bytes: &'a [u8]) {
+        self.borrowed.push((self.scratch.len(), bytes));
+    }
+
+    /// Total length of the encoding.
+    pub fn len(&self) -> usize {
+        let borrowed: usize = self.borrowed.iter().map(|(_, bytes)| bytes.len()).sum();
+        self.scratch.len() + borrowed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of bytes that were copied into the scratch buffer, rather than borrowed.
+    pub fn copied_len(&self) -> usize {
+        self.scratch.len()
+    }
+
+    /// The segments in order, skipping empty ones.
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        let mut start = 0;
+        self.borrowed
+            .iter()
+            .flat_map(move |&(end, bytes)| {
+                let scratch = &self.scratch[start..end];
+                start = end;
+                [scratch, bytes]
+            })
+            .chain(core::iter::once_with(move || {
+                &self.scratch[self.borrowed.last().map_or(0, |&(end, _)| end)..]
+            }))
+            .filter(|segment| !segment.is_empty())
+    }
+
+    /// The segments in order, for `std::io::Write::write_vectored`.
+    #[cfg(feature = "std")]
+    pub fn io_slices(&self) -> Vec<std::io::IoSlice<'_>> {
+        self.iter().map(std::io::IoSlice::new).collect()
+    }
+
+    /// Copies the segments into one buffer, which is the same as `to_ssz()` would have produced.
+    pub fn flatten(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.len());
+        for segment in self.iter() {
+            buf.extend_from_slice(segment);
+        }
+        buf
+    }
+}
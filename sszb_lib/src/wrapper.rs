@@ -0,0 +1,126 @@
+use crate::DecodeError;
+use core::fmt::Debug;
+
+/// Implements `SszbEncode` and `SszbDecode` for a type that converts to and from one that
+/// already has them, encoding it exactly as that type, e.g. `impl_ssz_wrapper!(Slot => u64);`.
+///
+/// It's the impl-side counterpart of deriving on a newtype, for types that can't use the derives
+/// because they only expose their value through conversions. Encoding clones the value into the
+/// inner type with `Clone + Into<Inner>`, so the wrapper should be cheap to clone. Decoding
+/// decodes the inner type and converts it with `TryFrom<Inner>`, which `From<Inner>` provides,
+/// and a conversion that fails is a `DecodeError::BytesInvalid` with the conversion's error (only
+/// naming the wrapper with `tiny-errors`).
+///
+/// The generated impls are subject to the orphan rule like any other, so the macro has to be
+/// invoked in the crate that defines the wrapper.
+#[macro_export]
+macro_rules! impl_ssz_wrapper {
+    ($wrapper: ty => $inner: ty) => {
+        impl $crate::SszbEncode for $wrapper {
+            const SSZ_STATIC: bool = <$inner as $crate::SszbEncode>::SSZ_STATIC;
+            const SSZ_FIXED_LEN: usize = <$inner as $crate::SszbEncode>::SSZ_FIXED_LEN;
+            const SSZ_MAX_LEN: usize = <$inner as $crate::SszbEncode>::SSZ_MAX_LEN;
+
+            fn sszb_bytes_len(&self) -> usize {
+                if <Self as $crate::SszbEncode>::SSZ_STATIC {
+                    return <Self as $crate::SszbEncode>::SSZ_FIXED_LEN;
+                }
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::sszb_bytes_len(&inner)
+            }
+
+            fn try_sszb_bytes_len(&self) -> Result<usize, $crate::EncodeError> {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::try_sszb_bytes_len(&inner)
+            }
+
+            fn ssz_check_bounds(&self) -> Result<(), $crate::EncodeError> {
+                if <Self as $crate::SszbEncode>::SSZ_STATIC {
+                    return Ok(());
+                }
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_check_bounds(&inner)
+            }
+
+            fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl ::bytes::BufMut) {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_write_fixed(&inner, offset, buf)
+            }
+
+            fn ssz_write_variable(&self, buf: &mut impl ::bytes::BufMut) {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_write_variable(&inner, buf)
+            }
+
+            fn ssz_write(&self, buf: &mut impl ::bytes::BufMut) {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_write(&inner, buf)
+            }
+
+            fn ssz_record_lens(&self, lens: &mut $crate::Vec<usize>) -> usize {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_record_lens(&inner, lens)
+            }
+
+            fn ssz_write_cached(&self, lens: &mut &[usize], buf: &mut impl ::bytes::BufMut) {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_write_cached(&inner, lens, buf)
+            }
+
+            fn ssz_write_slice(&self, buf: &mut [u8]) -> usize {
+                let inner: $inner = ::core::clone::Clone::clone(self).into();
+                $crate::SszbEncode::ssz_write_slice(&inner, buf)
+            }
+        }
+
+        impl $crate::SszbDecode for $wrapper {
+            const SSZ_STATIC: bool = <$inner as $crate::SszbDecode>::SSZ_STATIC;
+            const SSZ_FIXED_LEN: usize = <$inner as $crate::SszbDecode>::SSZ_FIXED_LEN;
+            const SSZ_MAX_LEN: usize = <$inner as $crate::SszbDecode>::SSZ_MAX_LEN;
+            const SSZ_SCHEMA: [u8; 32] = <$inner as $crate::SszbDecode>::SSZ_SCHEMA;
+
+            fn ssz_read(
+                fixed_bytes: &mut impl ::bytes::Buf,
+                variable_bytes: &mut impl ::bytes::Buf,
+            ) -> Result<Self, $crate::DecodeError> {
+                $crate::wrap_decoded(<$inner as $crate::SszbDecode>::ssz_read(
+                    fixed_bytes,
+                    variable_bytes,
+                ))
+            }
+
+            fn ssz_read_from(reader: $crate::SszReader<'_>) -> Result<Self, $crate::DecodeError> {
+                $crate::wrap_decoded(<$inner as $crate::SszbDecode>::ssz_read_from(reader))
+            }
+
+            fn ssz_read_trusted(bytes: &[u8]) -> Result<Self, $crate::DecodeError> {
+                $crate::wrap_decoded(<$inner as $crate::SszbDecode>::ssz_read_trusted(bytes))
+            }
+        }
+    };
+}
+
+/// Converts a value decoded as the inner type of `impl_ssz_wrapper!` into the wrapper.
+#[doc(hidden)]
+pub fn wrap_decoded<I, W>(inner: Result<I, DecodeError>) -> Result<W, DecodeError>
+where
+    W: TryFrom<I>,
+    W::Error: Debug,
+{
+    W::try_from(inner?).map_err(|error| {
+        #[cfg(not(feature = "tiny-errors"))]
+        #[allow(deprecated)]
+        let error = DecodeError::BytesInvalid(alloc::format!(
+            "{} can't be built from its decoded value: {:?}",
+            core::any::type_name::<W>(),
+            error
+        ));
+        #[cfg(feature = "tiny-errors")]
+        #[allow(deprecated)]
+        let error = {
+            let _ = error;
+            DecodeError::BytesInvalid(core::any::type_name::<W>())
+        };
+        error
+    })
+}
@@ -0,0 +1,65 @@
+use crate::{Union2, Union3, Union4};
+use alloc::{boxed::Box, sync::Arc};
+
+/// Types with an SSZ zero value: the default value of the type as the SSZ spec defines it, whose
+/// encoding is the canonical all-zero one. Integers are `0`, booleans `false` and byte arrays all
+/// zeros, vectors hold their length of zero values, lists and bitlists are empty, containers are
+/// made of the zero values of their fields, and unions are their first variant holding its zero.
+///
+/// This isn't always `Default`, which a vector doesn't have or a type may define otherwise, so
+/// `#[ssz(zero)]` builds a container's from this instead.
+pub trait SszZero {
+    fn ssz_zero() -> Self;
+}
+
+macro_rules! uint_ssz_zero {
+    ($($type: ident),+) => {
+        $(
+            impl SszZero for $type {
+                fn ssz_zero() -> Self {
+                    0
+                }
+            }
+        )+
+    };
+}
+
+uint_ssz_zero!(u8, u16, u32, u64, u128);
+
+impl SszZero for bool {
+    fn ssz_zero() -> Self {
+        false
+    }
+}
+
+impl<const N: usize> SszZero for [u8; N] {
+    fn ssz_zero() -> Self {
+        [0; N]
+    }
+}
+
+impl<T: SszZero> SszZero for Box<T> {
+    fn ssz_zero() -> Self {
+        Box::new(T::ssz_zero())
+    }
+}
+
+impl<T: SszZero> SszZero for Arc<T> {
+    fn ssz_zero() -> Self {
+        Arc::new(T::ssz_zero())
+    }
+}
+
+macro_rules! union_ssz_zero {
+    ($name: ident { $first: ident $(, $variant: ident)* }) => {
+        impl<$first: SszZero $(, $variant)*> SszZero for $name<$first $(, $variant)*> {
+            fn ssz_zero() -> Self {
+                $name::$first($first::ssz_zero())
+            }
+        }
+    };
+}
+
+union_ssz_zero!(Union2 { A, B });
+union_ssz_zero!(Union3 { A, B, C });
+union_ssz_zero!(Union4 { A, B, C, D });
@@ -0,0 +1,125 @@
+//! Encoding into a buffer that's already big enough shouldn't allocate.
+//!
+//! A counting global allocator tracks the allocations made by the current thread, so this file
+//! holds its own test binary.
+#![cfg(feature = "ssz-types")]
+
+use ssz_types::{BitList, BitVector, VariableList};
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use typenum::{U10, U2048, U4, U512, U64};
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn bit_list(len: usize) -> BitList<U2048> {
+    let mut bits = BitList::with_capacity(len).unwrap();
+    for i in (0..len).step_by(3) {
+        bits.set(i, true).unwrap();
+    }
+    bits
+}
+
+#[test]
+fn test_bitfields_encode_without_allocating() {
+    // lengths on and off byte boundaries, so the length bit sometimes needs a byte of its own
+    let lists = (0..64).map(|i| bit_list(i * 31)).collect::<Vec<_>>();
+    let list = VariableList::<BitList<U2048>, U64>::new(lists).unwrap();
+    let mut vector = BitVector::<U512>::new();
+    vector.set(7, true).unwrap();
+
+    let (len, count) = allocations(|| list.sszb_bytes_len() + vector.sszb_bytes_len());
+    assert_eq!(count, 0);
+
+    let mut buf = vec![0u8; len];
+    let ((), count) = allocations(|| {
+        let mut slice = buf.as_mut_slice();
+        list.ssz_write(&mut slice);
+        vector.ssz_write(&mut slice);
+    });
+    assert_eq!(count, 0);
+
+    let (list_bytes, vector_bytes) = buf.split_at(list.sszb_bytes_len());
+    assert_eq!(
+        <VariableList<BitList<U2048>, U64> as SszbDecode>::from_ssz_bytes(list_bytes),
+        Ok(list)
+    );
+    assert_eq!(
+        <BitVector<U512> as SszbDecode>::from_ssz_bytes(vector_bytes),
+        Ok(vector)
+    );
+}
+
+#[test]
+fn test_decode_errors_without_allocating() {
+    let bytes = [0u8; 40];
+    let (result, count) = allocations(|| VariableList::<u64, U4>::from_ssz_bytes(&bytes));
+    assert_eq!(result, Err(DecodeError::ListTooLong { len: 5, max: 4 }));
+    assert_eq!(count, 0);
+
+    let (result, count) = allocations(|| bool::from_ssz_bytes(&[2]));
+    assert_eq!(result, Err(DecodeError::InvalidBool(2)));
+    assert_eq!(count, 0);
+
+    // only 10 of the 16 bits belong to the vector
+    let (result, count) = allocations(|| BitVector::<U10>::from_ssz_bytes(&[0, 0b1000_0000]));
+    assert!(matches!(result, Err(DecodeError::BitfieldError(_))));
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn test_validate_without_allocating() {
+    let lists = (0..64).map(|i| bit_list(i * 31)).collect::<Vec<_>>();
+    let list = VariableList::<BitList<U2048>, U64>::new(lists).unwrap();
+    let bytes = list.to_ssz();
+
+    // decoding copies each bit list out, validating doesn't
+    let (result, count) = allocations(|| validate_ssz::<VariableList<BitList<U2048>, U64>>(&bytes));
+    assert_eq!(result, Ok(()));
+    assert_eq!(count, 0);
+
+    let bytes = VariableList::<u64, U512>::new((0..512).collect())
+        .unwrap()
+        .to_ssz();
+    let (result, count) = allocations(|| validate_ssz::<VariableList<u64, U512>>(&bytes));
+    assert_eq!(result, Ok(()));
+    assert_eq!(count, 0);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_heapless_decodes_without_allocating() {
+    let items = (0..16u64).collect::<heapless::Vec<u64, 16>>();
+    let bytes = items.to_ssz();
+    let (result, count) = allocations(|| heapless::Vec::<u64, 16>::from_ssz_bytes(&bytes));
+    assert_eq!(result, Ok(items));
+    assert_eq!(count, 0);
+
+    let (result, count) = allocations(|| heapless::Vec::<u64, 8>::from_ssz_bytes(&bytes));
+    assert_eq!(result, Err(DecodeError::ListTooLong { len: 16, max: 8 }));
+    assert_eq!(count, 0);
+}
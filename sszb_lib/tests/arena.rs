@@ -0,0 +1,145 @@
+#![cfg(all(feature = "bumpalo", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::arena::{from_ssz_bytes_in, ArenaList, Bump, SszbDecodeIn};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbDecodeIn, SszbEncode};
+use typenum::{U16, U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+#[derive(Debug, SszbEncode, SszbDecodeIn)]
+struct Attestation<'a> {
+    aggregation_bits: ArenaList<'a, u8, 16>,
+    target: Checkpoint,
+    committees: ArenaList<'a, ArenaList<'a, u16, 4>, 8>,
+    signature: [u8; 96],
+}
+
+// the same layout with heap-allocated lists
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct HeapAttestation {
+    aggregation_bits: VariableList<u8, U16>,
+    target: Checkpoint,
+    committees: VariableList<VariableList<u16, U4>, U8>,
+    signature: [u8; 96],
+}
+
+fn heap_attestation(i: u64) -> HeapAttestation {
+    HeapAttestation {
+        aggregation_bits: VariableList::new(vec![i as u8; (i % 16) as usize]).unwrap(),
+        target: Checkpoint {
+            epoch: i,
+            root: [i as u8 ^ 0x5a; 32],
+        },
+        committees: VariableList::new(
+            (0..(i % 8))
+                .map(|c| VariableList::new(vec![(i + c) as u16; (c % 5) as usize]).unwrap())
+                .collect(),
+        )
+        .unwrap(),
+        signature: [i as u8; 96],
+    }
+}
+
+fn assert_same(attestation: &Attestation, heap: &HeapAttestation) {
+    assert_eq!(
+        &attestation.aggregation_bits[..],
+        &heap.aggregation_bits[..]
+    );
+    assert_eq!(attestation.target, heap.target);
+    assert_eq!(attestation.committees.len(), heap.committees.len());
+    for (committee, heap_committee) in attestation.committees.iter().zip(heap.committees.iter()) {
+        assert_eq!(&committee[..], &heap_committee[..]);
+    }
+    assert_eq!(attestation.signature, heap.signature);
+}
+
+#[test]
+fn test_decode_in_matches_heap_decode() {
+    let arena = Bump::new();
+    for i in 0..20 {
+        let heap = heap_attestation(i);
+        let bytes = heap.to_ssz();
+        let attestation: Attestation = from_ssz_bytes_in(&bytes, &arena).unwrap();
+        assert_same(&attestation, &heap);
+        // and arena lists encode like any other list
+        assert_eq!(attestation.to_ssz(), bytes);
+    }
+}
+
+#[test]
+fn test_arena_reset_and_reuse() {
+    let mut arena = Bump::new();
+    let batch = |offset: u64| {
+        (0..100)
+            .map(|i| heap_attestation(offset + i).to_ssz())
+            .collect::<Vec<_>>()
+    };
+
+    let first = batch(0);
+    let decoded = first
+        .iter()
+        .map(|bytes| Attestation::from_ssz_bytes_in(bytes, &arena).unwrap())
+        .collect::<Vec<_>>();
+    for (i, attestation) in decoded.iter().enumerate() {
+        assert_same(attestation, &heap_attestation(i as u64));
+    }
+    drop(decoded);
+    let allocated = arena.allocated_bytes();
+
+    // a batch of the same shape fits in the memory kept by the reset, and none of the previous
+    // batch's data shows through
+    arena.reset();
+    let second = batch(1000);
+    let decoded = second
+        .iter()
+        .map(|bytes| Attestation::from_ssz_bytes_in(bytes, &arena).unwrap())
+        .collect::<Vec<_>>();
+    for (i, attestation) in decoded.iter().enumerate() {
+        assert_same(attestation, &heap_attestation(1000 + i as u64));
+    }
+    assert!(arena.allocated_bytes() <= allocated);
+}
+
+#[test]
+fn test_decode_in_errors() {
+    let arena = Bump::new();
+
+    let bits = VariableList::<u8, U16>::new(vec![1; 9]).unwrap().to_ssz();
+    assert_eq!(
+        from_ssz_bytes_in::<ArenaList<u8, 8>>(&bits, &arena),
+        Err(DecodeError::ListTooLong { len: 9, max: 8 })
+    );
+
+    // errors inside a field carry the field's position, like the owned derive's do
+    let mut heap = heap_attestation(3);
+    heap.committees = VariableList::new(vec![VariableList::new(vec![1, 2]).unwrap()]).unwrap();
+    let mut bytes = heap.to_ssz();
+    let expected = HeapAttestation::from_ssz_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(
+        Attestation::from_ssz_bytes_in(&bytes[..bytes.len() - 1], &arena).unwrap_err(),
+        expected
+    );
+    bytes.truncate(10);
+    assert_eq!(
+        Attestation::from_ssz_bytes_in(&bytes, &arena).unwrap_err(),
+        HeapAttestation::from_ssz_bytes(&bytes).unwrap_err()
+    );
+}
+
+#[test]
+fn test_arena_list_push() {
+    let arena = Bump::new();
+    let mut list = ArenaList::<u16, 2>::new_in(&arena);
+    assert_eq!(list.push(1), Ok(()));
+    assert_eq!(list.push(2), Ok(()));
+    assert_eq!(list.push(3), Err(3));
+    assert_eq!(list.to_ssz(), [1, 0, 2, 0]);
+    assert_eq!(list.into_bump_slice(), &[1, 2]);
+}
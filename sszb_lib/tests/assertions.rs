@@ -0,0 +1,20 @@
+use bytes::buf::BufMut;
+use sszb::{assert_ssz_fixed_len, assert_ssz_static};
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+assert_ssz_static!(u64);
+assert_ssz_static!(Checkpoint);
+assert_ssz_fixed_len!(Checkpoint, 40);
+assert_ssz_fixed_len!([u8; 96], 96);
+
+#[test]
+fn test_assertions_fail_to_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
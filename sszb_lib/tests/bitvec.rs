@@ -0,0 +1,73 @@
+#![cfg(all(feature = "bitvec", feature = "ssz-types"))]
+
+use bitvec::{array::BitArray, order::Lsb0, vec::BitVec};
+use ssz_types::{BitList, BitVector};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use typenum::{U16, U2048, U24};
+
+// the same bits in both representations, every third bit set
+fn bits(len: usize) -> (BitList<U2048>, BitVec<u8, Lsb0>) {
+    let mut list = BitList::with_capacity(len).unwrap();
+    let mut vec = BitVec::new();
+    for i in 0..len {
+        list.set(i, i % 3 == 0).unwrap();
+        vec.push(i % 3 == 0);
+    }
+    (list, vec)
+}
+
+#[test]
+fn test_bitvec_matches_bitlist() {
+    for len in [0, 1, 7, 8, 9, 15, 16, 17, 1000, 2048] {
+        let (list, vec) = bits(len);
+        let bytes = list.to_ssz();
+        assert_eq!(vec.to_ssz(), bytes, "length {}", len);
+        assert_eq!(vec.sszb_bytes_len(), bytes.len());
+        assert_eq!(BitVec::<u8, Lsb0>::from_ssz_bytes(&bytes), Ok(vec));
+    }
+}
+
+#[test]
+fn test_unaligned_bitvec() {
+    // starting part way into its first byte, with set bits past its end
+    let mut vec = BitVec::<u8, Lsb0>::repeat(true, 20);
+    vec.drain(..3);
+    vec.truncate(9);
+    vec.set(1, false);
+
+    let (mut list, _) = bits(9);
+    for i in 0..9 {
+        list.set(i, i != 1).unwrap();
+    }
+    assert_eq!(vec.to_ssz(), list.to_ssz());
+}
+
+#[test]
+fn test_bitvec_missing_length_bit() {
+    assert_eq!(
+        BitVec::<u8, Lsb0>::from_ssz_bytes(&[]),
+        Err(DecodeError::BitfieldError("its length bit is missing"))
+    );
+    assert_eq!(
+        BitVec::<u8, Lsb0>::from_ssz_bytes(&[0xff, 0]),
+        Err(DecodeError::BitfieldError("its length bit is missing"))
+    );
+}
+
+#[test]
+fn test_bitarray_matches_bitvector() {
+    let mut vector = BitVector::<U16>::new();
+    let mut array = BitArray::<[u8; 2], Lsb0>::ZERO;
+    for i in [0, 5, 8, 15] {
+        vector.set(i, true).unwrap();
+        array.set(i, true);
+    }
+    let bytes = vector.to_ssz();
+    assert_eq!(array.to_ssz(), bytes);
+    assert_eq!(BitArray::<[u8; 2], Lsb0>::from_ssz_bytes(&bytes), Ok(array));
+
+    let vector = BitVector::<U24>::from_bytes(vec![0xff; 3].into()).unwrap();
+    let array = BitArray::<[u8; 3], Lsb0>::new([0xff; 3]);
+    assert_eq!(array.to_ssz(), vector.to_ssz());
+    assert_eq!(<BitArray<[u8; 3], Lsb0> as SszbEncode>::SSZ_FIXED_LEN, 3);
+}
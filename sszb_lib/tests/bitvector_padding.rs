@@ -0,0 +1,67 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{BitVector, VariableList};
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U10, U16, U8};
+
+const EXCESS_BITS: DecodeError = DecodeError::BitfieldError("bits are set past its length");
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Committee {
+    index: u16,
+    bits: BitVector<U10>,
+    epoch: u32,
+    name: VariableList<u8, U8>,
+}
+
+fn bits(set: &[usize]) -> BitVector<U10> {
+    let mut bits = BitVector::new();
+    for &i in set {
+        bits.set(i, true).unwrap();
+    }
+    bits
+}
+
+#[test]
+fn test_padding_bits_are_rejected() {
+    // bits 10 to 15 are padding, the 12th is set
+    let bytes = [0b0000_0001, 0b0000_1000];
+    assert_eq!(BitVector::<U10>::from_ssz_bytes(&bytes), Err(EXCESS_BITS));
+    assert_eq!(validate_ssz::<BitVector<U10>>(&bytes), Err(EXCESS_BITS));
+
+    // the 10th is the vector's last
+    let bytes = [0b0000_0001, 0b0000_0010];
+    let decoded = BitVector::<U10>::from_ssz_bytes(&bytes).unwrap();
+    assert_eq!(decoded, bits(&[0, 9]));
+    assert_eq!(decoded.to_ssz(), bytes);
+
+    // a whole number of bytes has no padding
+    assert!(BitVector::<U16>::from_ssz_bytes(&[0xff, 0xff]).is_ok());
+}
+
+#[test]
+fn test_inner_field() {
+    let committee = Committee {
+        index: 3,
+        bits: bits(&[1, 9]),
+        epoch: 0xffff_ffff,
+        name: VariableList::new(b"ab".to_vec()).unwrap(),
+    };
+    let bytes = committee.to_ssz();
+    // the vector's two bytes, followed by the epoch's, which have every bit set
+    assert_eq!(bytes[2..8], [0b10, 0b10, 0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(Committee::from_ssz_bytes(&bytes), Ok(committee));
+
+    let mut bytes = bytes;
+    bytes[3] |= 0b100;
+    assert_eq!(
+        Committee::from_ssz_bytes(&bytes),
+        Err(EXCESS_BITS.at_byte(2))
+    );
+    assert_eq!(
+        validate_ssz::<Committee>(&bytes),
+        Err(EXCESS_BITS.at_byte(2))
+    );
+}
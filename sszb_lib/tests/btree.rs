@@ -0,0 +1,128 @@
+#![cfg(all(feature = "collections", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::collections::{BTreeMap, BTreeSet};
+use typenum::{U16, U8};
+
+type Name = VariableList<u8, U8>;
+
+fn name(s: &str) -> Name {
+    VariableList::new(s.as_bytes().to_vec()).unwrap()
+}
+
+// the containers map entries are encoded as
+#[derive(SszbEncode)]
+struct StaticEntry {
+    key: u64,
+    value: u32,
+}
+
+#[derive(SszbEncode)]
+struct VariableEntry {
+    key: u32,
+    value: Name,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Config {
+    version: u32,
+    peers: BTreeMap<u32, Name>,
+    flags: BTreeSet<u8>,
+}
+
+fn assert_roundtrip<T: SszbEncode + SszbDecode + PartialEq + std::fmt::Debug>(
+    value: T,
+    expected: &[u8],
+) {
+    assert_eq!(value.to_ssz(), expected);
+    assert_eq!(value.sszb_bytes_len(), expected.len());
+    assert_eq!(T::from_ssz_bytes(expected), Ok(value));
+}
+
+fn assert_unsorted<T: SszbDecode + std::fmt::Debug>(bytes: &[u8]) {
+    let err = T::from_ssz_bytes(bytes).unwrap_err();
+    #[allow(deprecated)]
+    let is_unsorted = matches!(err, DecodeError::BytesInvalid(_));
+    assert!(is_unsorted, "{:?}", err);
+}
+
+#[test]
+fn test_maps_roundtrip() {
+    let map = BTreeMap::from([(3u64, 30u32), (1, 10), (2, 20)]);
+    let entries =
+        Vec::from([(1, 10), (2, 20), (3, 30)].map(|(key, value)| StaticEntry { key, value }));
+    let expected = VariableList::<StaticEntry, U16>::new(entries)
+        .unwrap()
+        .to_ssz();
+    assert_roundtrip(map, &expected);
+
+    let map = BTreeMap::from([(9u32, name("b")), (4, name("aa")), (0, name(""))]);
+    let entries = Vec::from(
+        [(0, ""), (4, "aa"), (9, "b")].map(|(key, value)| VariableEntry {
+            key,
+            value: name(value),
+        }),
+    );
+    let expected = VariableList::<VariableEntry, U16>::new(entries)
+        .unwrap()
+        .to_ssz();
+    assert_roundtrip(map, &expected);
+
+    assert_roundtrip(BTreeMap::<u64, u32>::new(), &[]);
+    assert_roundtrip(BTreeMap::<u32, Name>::new(), &[]);
+}
+
+#[test]
+fn test_sets_roundtrip() {
+    let set = BTreeSet::from([5u16, 1, 3]);
+    assert_roundtrip(set, &[1u16, 3, 5].map(|i| i.to_le_bytes()).concat());
+
+    let set = BTreeSet::from([[2u8, 0], [1, 9]]);
+    assert_roundtrip(set, &[1, 9, 2, 0]);
+    assert_roundtrip(BTreeSet::<u16>::new(), &[]);
+
+    let config = Config {
+        version: 2,
+        peers: BTreeMap::from([(50, name("lh")), (6, name("teku"))]),
+        flags: BTreeSet::from([7, 1]),
+    };
+    let bytes = config.to_ssz();
+    assert_roundtrip(config, &bytes);
+}
+
+#[test]
+fn test_non_canonical_payloads() {
+    // keys out of order, and a key twice
+    for keys in [[2, 1, 3], [1, 1, 2]] {
+        let entries = Vec::from(keys.map(|key| StaticEntry { key, value: 0 }));
+        let bytes = VariableList::<StaticEntry, U16>::new(entries)
+            .unwrap()
+            .to_ssz();
+        assert_unsorted::<BTreeMap<u64, u32>>(&bytes);
+    }
+    for keys in [[7, 3], [3, 3]] {
+        let entries = Vec::from(keys.map(|key| VariableEntry {
+            key,
+            value: name("v"),
+        }));
+        let bytes = VariableList::<VariableEntry, U16>::new(entries)
+            .unwrap()
+            .to_ssz();
+        assert_unsorted::<BTreeMap<u32, Name>>(&bytes);
+    }
+
+    assert_unsorted::<BTreeSet<u16>>(&[3, 0, 1, 0]);
+    assert_unsorted::<BTreeSet<u16>>(&[1, 0, 1, 0]);
+
+    // malformed lists fail the way they would for any list
+    assert_eq!(
+        BTreeSet::<u16>::from_ssz_bytes(&[1, 0, 2]),
+        Err(DecodeError::InvalidByteLength {
+            len: 1,
+            expected: 2
+        })
+    );
+}
@@ -0,0 +1,142 @@
+//! Lists and vectors of byte array newtypes (roots, addresses, `FixedBytes<N>`) are copied to and
+//! from their bytes a run at a time. Each is checked against the same items wrapped in a
+//! container of one field, which goes through the generic item by item path.
+#![cfg(all(
+    feature = "alloy",
+    feature = "ethereum-types",
+    feature = "ssz-types",
+    feature = "milhouse"
+))]
+
+use alloy_primitives::{Address, FixedBytes, B256};
+use bytes::buf::{Buf, BufMut};
+use ethereum_types::{H160, H256, H32};
+use milhouse::{List, Vector};
+use ssz_types::{FixedVector, VariableList};
+use sszb::vectored::Segments;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U1024, U64};
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Generic<T: SszbEncode + SszbDecode> {
+    item: T,
+}
+
+fn items<T: From<[u8; N]>, const N: usize>(count: usize) -> Vec<T> {
+    (0..count)
+        .map(|i| T::from(core::array::from_fn(|j| (i * 7 + j) as u8)))
+        .collect()
+}
+
+fn matches_generic<T, const N: usize>(count: usize)
+where
+    T: SszbEncode + SszbDecode + From<[u8; N]> + Clone + PartialEq + Debug,
+{
+    let items = items::<T, N>(count);
+    let list = VariableList::<T, U1024>::new(items.clone()).unwrap();
+    let generic = VariableList::<Generic<T>, U1024>::new(
+        items
+            .iter()
+            .map(|item| Generic { item: item.clone() })
+            .collect(),
+    )
+    .unwrap();
+
+    let bytes = generic.to_ssz();
+    assert_eq!(bytes.len(), count * N);
+    assert_eq!(list.to_ssz(), bytes);
+    assert_eq!(
+        VariableList::<T, U1024>::from_ssz_bytes(&bytes),
+        Ok(list.clone())
+    );
+
+    // long runs are borrowed by vectored writes
+    let mut segments = Segments::new();
+    list.ssz_write_vectored(&mut segments);
+    assert_eq!(segments.flatten(), bytes);
+
+    // and decoding over an existing list replaces its items
+    let mut decoded = VariableList::<T, U1024>::new(items[..count / 2].to_vec()).unwrap();
+    decoded.decode_into(&bytes).unwrap();
+    assert_eq!(decoded, list);
+
+    // a partial item fails at the same place, the same way
+    if count > 0 {
+        let truncated = &bytes[..bytes.len() - 1];
+        let expected = VariableList::<Generic<T>, U1024>::from_ssz_bytes(truncated).unwrap_err();
+        assert_eq!(
+            VariableList::<T, U1024>::from_ssz_bytes(truncated),
+            Err(expected)
+        );
+    }
+}
+
+#[test]
+fn test_lists() {
+    for count in [0, 1, 2, 33, 1024] {
+        matches_generic::<H256, 32>(count);
+        matches_generic::<H160, 20>(count);
+        matches_generic::<H32, 4>(count);
+        matches_generic::<FixedBytes<32>, 32>(count);
+        matches_generic::<FixedBytes<48>, 48>(count);
+        matches_generic::<Address, 20>(count);
+        matches_generic::<[u8; 32], 32>(count);
+    }
+}
+
+#[test]
+fn test_vectors() {
+    let items = items::<H256, 32>(64);
+    let vector = FixedVector::<H256, U64>::new(items.clone()).unwrap();
+    let generic = FixedVector::<Generic<H256>, U64>::new(
+        items.iter().map(|&item| Generic { item }).collect(),
+    )
+    .unwrap();
+    let bytes = generic.to_ssz();
+    assert_eq!(vector.to_ssz(), bytes);
+    assert_eq!(FixedVector::<H256, U64>::from_ssz_bytes(&bytes), Ok(vector));
+    assert!(FixedVector::<H256, U64>::from_ssz_bytes(&bytes[32..]).is_err());
+}
+
+// the leaves of a tree are gathered into runs, and decoded lists are built from whole runs
+#[test]
+fn test_persistent() {
+    let roots = items::<B256, 32>(1000);
+    let list = List::<B256, U1024>::new(roots.clone()).unwrap();
+    let bytes = VariableList::<B256, U1024>::new(roots.clone())
+        .unwrap()
+        .to_ssz();
+    assert_eq!(ssz::Encode::as_ssz_bytes(&list), bytes);
+    assert_eq!(list.to_ssz(), bytes);
+    assert_eq!(List::<B256, U1024>::from_ssz_bytes(&bytes), Ok(list));
+
+    let addresses = items::<Address, 20>(64);
+    let vector = Vector::<Address, U64>::new(addresses.clone()).unwrap();
+    let bytes = FixedVector::<Address, U64>::new(addresses)
+        .unwrap()
+        .to_ssz();
+    assert_eq!(ssz::Encode::as_ssz_bytes(&vector), bytes);
+    assert_eq!(vector.to_ssz(), bytes);
+    assert_eq!(Vector::<Address, U64>::from_ssz_bytes(&bytes), Ok(vector));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        VariableList::<H256, U1024>::from_ssz_bytes(&[0; 33]),
+        Err(DecodeError::InvalidByteLength {
+            len: 1,
+            expected: 32
+        })
+    );
+    assert_eq!(
+        VariableList::<H256, U64>::from_ssz_bytes(&[0; 65 * 32]),
+        Err(DecodeError::ListTooLong { len: 65, max: 64 })
+    );
+    assert_eq!(
+        VariableList::<FixedBytes<0>, U64>::from_ssz_bytes(&[0; 4]),
+        Err(DecodeError::ZeroLengthItem)
+    );
+}
@@ -0,0 +1,47 @@
+#![cfg(all(feature = "ssz-types", feature = "milhouse"))]
+
+use milhouse::List;
+use ssz_types::{FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use typenum::{U1048576, U32};
+
+const MIB: usize = 1 << 20;
+
+fn payload(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 31 % 251) as u8).collect()
+}
+
+#[test]
+fn test_byte_list_matches_generic_path() {
+    let bytes = payload(MIB);
+    let list = VariableList::<u8, U1048576>::new(bytes.clone()).unwrap();
+    // milhouse lists are trees, so they are still written one byte at a time
+    let persistent = List::<u8, U1048576>::new(bytes.clone()).unwrap();
+
+    let encoded = list.to_ssz();
+    assert_eq!(encoded, bytes);
+    assert_eq!(encoded, persistent.to_ssz());
+    assert_eq!(
+        <VariableList<u8, U1048576> as SszbDecode>::from_ssz_bytes(&encoded),
+        Ok(list)
+    );
+    assert_eq!(
+        <List<u8, U1048576> as SszbDecode>::from_ssz_bytes(&encoded),
+        Ok(persistent)
+    );
+}
+
+#[test]
+fn test_byte_vector_matches_generic_path() {
+    let bytes = payload(32);
+    let vector = FixedVector::<u8, U32>::new(bytes.clone()).unwrap();
+
+    let mut buf = vec![];
+    vector.ssz_write(&mut buf);
+    assert_eq!(buf, bytes);
+    assert_eq!(
+        <FixedVector<u8, U32> as SszbDecode>::from_ssz_bytes(&buf),
+        Ok(vector)
+    );
+    assert!(<FixedVector<u8, U32> as SszbDecode>::from_ssz_bytes(&buf[..31]).is_err());
+}
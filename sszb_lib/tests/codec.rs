@@ -0,0 +1,120 @@
+#![cfg(all(feature = "codec", feature = "ssz-types"))]
+
+use bytes::{BufMut, BytesMut};
+use futures::{SinkExt, StreamExt};
+use ssz_types::VariableList;
+use sszb::codec::{CodecError, SszCodec, DEFAULT_MAX_FRAME_LEN};
+use sszb::SszbEncode;
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+use typenum::{U4096, U4194304};
+
+type Message = VariableList<u8, U4096>;
+
+fn message(len: usize) -> Message {
+    VariableList::new((0..len).map(|i| (i * 7) as u8).collect()).unwrap()
+}
+
+fn frame(message: &Message) -> Vec<u8> {
+    let mut bytes = (message.len() as u32).to_le_bytes().to_vec();
+    bytes.extend(message.to_ssz());
+    bytes
+}
+
+#[tokio::test]
+async fn test_framed_roundtrip() {
+    let (client, server) = tokio::io::duplex(64);
+    let messages = (0..200)
+        .map(|i| message((i * 37) % 3000))
+        .collect::<Vec<_>>();
+
+    let sent = messages.clone();
+    let writer = tokio::spawn(async move {
+        let mut sink = FramedWrite::new(client, SszCodec::<Message>::new());
+        for message in sent {
+            sink.send(message).await.unwrap();
+        }
+    });
+
+    let mut stream = FramedRead::new(server, SszCodec::<Message>::new());
+    for message in &messages {
+        assert_eq!(stream.next().await.unwrap().unwrap(), *message);
+    }
+    writer.await.unwrap();
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_over_long_message_not_sent() {
+    // big enough to hold every frame, since nothing reads until they're all sent
+    let (client, server) = tokio::io::duplex(1024);
+    let mut sink = FramedWrite::new(client, SszCodec::<Message>::with_max_frame_len(100));
+    let mut stream = FramedRead::new(server, SszCodec::<Message>::new());
+
+    sink.send(message(10)).await.unwrap();
+    assert!(matches!(
+        sink.send(message(101)).await,
+        Err(CodecError::FrameTooLarge { len: 101, max: 100 })
+    ));
+    sink.send(message(100)).await.unwrap();
+    drop(sink);
+
+    assert_eq!(stream.next().await.unwrap().unwrap(), message(10));
+    assert_eq!(stream.next().await.unwrap().unwrap(), message(100));
+    assert!(stream.next().await.is_none());
+}
+
+#[test]
+fn test_over_long_frame_skipped() {
+    let mut codec = SszCodec::<Message>::with_max_frame_len(100);
+    let mut bytes = frame(&message(300));
+    bytes.extend(frame(&message(20)));
+
+    // the frames arrive a few bytes at a time
+    let mut src = BytesMut::new();
+    let mut results = vec![];
+    for chunk in bytes.chunks(7) {
+        src.put_slice(chunk);
+        loop {
+            match codec.decode(&mut src) {
+                Ok(Some(message)) => results.push(Ok(message)),
+                Ok(None) => break,
+                Err(error) => results.push(Err(error)),
+            }
+        }
+    }
+
+    assert_eq!(results.len(), 2);
+    assert!(matches!(
+        results[0],
+        Err(CodecError::FrameTooLarge { len: 300, max: 100 })
+    ));
+    assert_eq!(results[1].as_ref().unwrap(), &message(20));
+    assert!(src.is_empty());
+}
+
+#[test]
+fn test_invalid_frame_consumed() {
+    let mut codec = SszCodec::<VariableList<u64, U4096>>::new();
+    let mut src = BytesMut::new();
+    // 3 bytes can't hold whole u64s
+    src.put_slice(&[3, 0, 0, 0, 1, 2, 3]);
+    src.put_slice(&[8, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0]);
+
+    assert!(matches!(codec.decode(&mut src), Err(CodecError::Decode(_))));
+    assert_eq!(codec.decode(&mut src).unwrap().unwrap().to_vec(), [9]);
+}
+
+#[test]
+fn test_max_frame_len() {
+    assert_eq!(SszCodec::<Message>::new().max_frame_len(), 4096);
+    assert_eq!(
+        SszCodec::<VariableList<u64, U4194304>>::new().max_frame_len(),
+        DEFAULT_MAX_FRAME_LEN
+    );
+
+    let mut dst = BytesMut::new();
+    SszCodec::<Message>::new()
+        .encode(message(3), &mut dst)
+        .unwrap();
+    assert_eq!(&dst[..], [3, 0, 0, 0, 0, 7, 14]);
+}
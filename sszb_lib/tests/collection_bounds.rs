@@ -0,0 +1,29 @@
+#![cfg(feature = "ssz-types")]
+
+// every collection asks the same of its items: the sszb traits, and whatever the backing type
+// itself requires. each case derives containers around an item that has nothing more
+
+#[test]
+fn test_ssz_types_bounds() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/bounds/ssz_types.rs");
+    t.pass("tests/ui/bounds/runtime.rs");
+}
+
+#[cfg(feature = "milhouse")]
+#[test]
+fn test_milhouse_bounds() {
+    trybuild::TestCases::new().pass("tests/ui/bounds/milhouse.rs");
+}
+
+#[cfg(feature = "ghilhouse")]
+#[test]
+fn test_ghilhouse_bounds() {
+    trybuild::TestCases::new().pass("tests/ui/bounds/ghilhouse.rs");
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_heapless_bounds() {
+    trybuild::TestCases::new().pass("tests/ui/bounds/heapless.rs");
+}
@@ -0,0 +1,120 @@
+//! Lists and vectors converted between `ssz_types`, `milhouse` and `ghilhouse`: for random items,
+//! all three encode to the same bytes, which `ethereum_ssz` agrees with, and each decodes what
+//! the others encode. The three share their impls in `collections`, and this keeps them honest.
+#![cfg(all(feature = "testing", feature = "ghilhouse"))]
+
+use alloy_primitives::B256;
+use milhouse::{List, Vector};
+use ssz_types::{FixedVector, VariableList};
+use sszb::testing::proptest::prelude::*;
+use sszb::testing::{fixed_vector, variable_list};
+use sszb::{SszList, SszVector, SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{Unsigned, U16, U4, U8};
+
+type Payload = VariableList<u8, U8>;
+
+fn payload() -> impl Strategy<Value = Payload> {
+    variable_list(any::<u8>())
+}
+
+fn root() -> impl Strategy<Value = B256> {
+    any::<[u8; 32]>().prop_map(B256::from)
+}
+
+fn check_lists<T, N>(list: VariableList<T, N>)
+where
+    T: Clone,
+    N: Unsigned,
+    VariableList<T, N>: SszbEncode + SszbDecode + ssz::Encode + SszList<T, N> + PartialEq + Debug,
+    List<T, N>: SszbEncode + SszbDecode + SszList<T, N>,
+    ghilhouse::List<T, N>: SszbEncode + SszbDecode + SszList<T, N>,
+{
+    let bytes = list.to_ssz();
+    assert_eq!(ssz::Encode::as_ssz_bytes(&list), bytes);
+
+    let persistent: List<T, N> = list.convert_list().unwrap();
+    let forked: ghilhouse::List<T, N> = list.convert_list().unwrap();
+    assert_eq!(persistent.to_ssz(), bytes);
+    assert_eq!(forked.to_ssz(), bytes);
+    assert_eq!(persistent.sszb_bytes_len(), bytes.len());
+    assert_eq!(forked.sszb_bytes_len(), bytes.len());
+
+    // each family decodes the others' bytes into the same items
+    let decoded = List::<T, N>::from_ssz_bytes(&forked.to_ssz()).unwrap();
+    assert_eq!(decoded.convert_list(), Ok(list.clone()));
+    let decoded = ghilhouse::List::<T, N>::from_ssz_bytes(&persistent.to_ssz()).unwrap();
+    assert_eq!(decoded.convert_list(), Ok(list.clone()));
+    assert_eq!(
+        VariableList::<T, N>::from_ssz_bytes(&persistent.to_ssz()),
+        Ok(list.clone())
+    );
+
+    // and converting between the trees keeps them too
+    let converted: ghilhouse::List<T, N> = persistent.convert_list().unwrap();
+    assert_eq!(converted.to_ssz(), bytes);
+    assert_eq!(forked.convert_list(), Ok(list));
+}
+
+fn check_vectors<T, N>(vector: FixedVector<T, N>)
+where
+    T: Clone,
+    N: Unsigned,
+    FixedVector<T, N>: SszbEncode + SszbDecode + ssz::Encode + SszVector<T, N> + PartialEq + Debug,
+    Vector<T, N>: SszbEncode + SszbDecode + SszVector<T, N>,
+    ghilhouse::Vector<T, N>: SszbEncode + SszbDecode + SszVector<T, N>,
+{
+    let bytes = vector.to_ssz();
+    assert_eq!(ssz::Encode::as_ssz_bytes(&vector), bytes);
+
+    let persistent: Vector<T, N> = vector.convert_vector().unwrap();
+    let forked: ghilhouse::Vector<T, N> = vector.convert_vector().unwrap();
+    assert_eq!(persistent.to_ssz(), bytes);
+    assert_eq!(forked.to_ssz(), bytes);
+
+    let decoded = Vector::<T, N>::from_ssz_bytes(&forked.to_ssz()).unwrap();
+    assert_eq!(decoded.convert_vector(), Ok(vector.clone()));
+    let decoded = ghilhouse::Vector::<T, N>::from_ssz_bytes(&persistent.to_ssz()).unwrap();
+    assert_eq!(decoded.convert_vector(), Ok(vector.clone()));
+    assert_eq!(
+        FixedVector::<T, N>::from_ssz_bytes(&persistent.to_ssz()),
+        Ok(vector.clone())
+    );
+
+    let converted: ghilhouse::Vector<T, N> = persistent.convert_vector().unwrap();
+    assert_eq!(converted.to_ssz(), bytes);
+    assert_eq!(forked.convert_vector(), Ok(vector));
+}
+
+proptest! {
+    #[test]
+    fn test_static_lists(list in variable_list::<u64, U16>(any::<u64>())) {
+        check_lists(list);
+    }
+
+    #[test]
+    fn test_root_lists(list in variable_list::<B256, U8>(root())) {
+        check_lists(list);
+    }
+
+    #[test]
+    fn test_variable_lists(list in variable_list::<Payload, U8>(payload())) {
+        check_lists(list);
+    }
+
+    #[test]
+    fn test_static_vectors(vector in fixed_vector::<u64, U8>(any::<u64>())) {
+        check_vectors(vector);
+    }
+
+    #[test]
+    fn test_variable_vectors(vector in fixed_vector::<Payload, U4>(payload())) {
+        check_vectors(vector);
+    }
+}
+
+#[test]
+fn test_empty() {
+    check_lists(VariableList::<u64, U16>::empty());
+    check_lists(VariableList::<Payload, U8>::empty());
+}
@@ -0,0 +1,385 @@
+//! The same roundtrip and malformed-input cases, run against the lists and vectors of every
+//! backing crate: `ssz_types`, `milhouse` and `ghilhouse`.
+//!
+//! Encodings are checked against `ethereum_ssz`, and decoding results (values and errors alike)
+//! against `decode_list_into`, which decodes into a plain `Vec` without going through any of the
+//! collection impls.
+#![cfg(feature = "ssz-types")]
+
+use bytes::Buf;
+use ssz::Encode;
+use ssz_types::{FixedVector, VariableList};
+use sszb::{
+    decode_list_into, parallel::SszbEncodeParallel, validate_ssz, vectored::Segments, DecodeError,
+    SszbDecode, SszbEncode,
+};
+use std::fmt::Debug;
+use typenum::{U16, U4, U8, U8192};
+
+type Payload = VariableList<u8, U8>;
+
+fn payload(i: usize) -> Payload {
+    VariableList::new(vec![i as u8; i % 5]).unwrap()
+}
+
+fn static_items(len: usize) -> Vec<u64> {
+    (0..len as u64).map(|i| i * 0x0101).collect()
+}
+
+fn variable_items(len: usize) -> Vec<Payload> {
+    (0..len).map(payload).collect()
+}
+
+// every way of writing a value has to give the same bytes
+fn assert_encodes_to(value: &impl SszbEncodeParallel, expected: &[u8]) {
+    assert_eq!(value.to_ssz(), expected);
+    assert_eq!(value.sszb_bytes_len(), expected.len());
+
+    let mut buf = vec![0u8; expected.len()];
+    value.ssz_write(&mut buf.as_mut_slice());
+    assert_eq!(buf, expected);
+
+    let mut buf = vec![0u8; expected.len()];
+    assert_eq!(value.ssz_write_slice(&mut buf), expected.len());
+    assert_eq!(buf, expected);
+
+    let mut buf = vec![0u8; expected.len()];
+    value.ssz_write_parallel(&mut buf);
+    assert_eq!(buf, expected);
+
+    let mut segments = Segments::new();
+    value.ssz_write_vectored(&mut segments);
+    assert_eq!(segments.flatten(), expected);
+}
+
+// a variable value gets all of its bytes in variable_bytes, here split into two chunks
+fn read_chained<T: SszbDecode>(bytes: &[u8], at: usize) -> Result<T, DecodeError> {
+    let (front, back) = bytes.split_at(at);
+    let mut variable_bytes = front.chain(back);
+    let value = T::ssz_read(&mut &[0u8; 0][..], &mut variable_bytes);
+    assert!(!variable_bytes.has_remaining());
+    value
+}
+
+// the list decoded by `decode`, and the same bytes decoded into a Vec, have to agree on both
+// values and errors, and validation has to accept exactly what they accept
+fn assert_decodes_like_vec<T: SszbDecode + PartialEq + Debug>(
+    bytes: &[u8],
+    max_len: usize,
+    decode: impl Fn(&[u8]) -> Result<Vec<T>, DecodeError>,
+    validate: impl Fn(&[u8]) -> Result<(), DecodeError>,
+) {
+    let mut items = Vec::new();
+    let expected = decode_list_into(bytes, max_len, &mut items).map(|()| items);
+    assert_eq!(validate(bytes).is_ok(), expected.is_ok(), "{:?}", bytes);
+    assert_eq!(decode(bytes), expected, "{:?}", bytes);
+}
+
+// truncations, extensions and single byte changes of a valid encoding
+fn corpus(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut corpus = vec![bytes.to_vec(), Vec::new()];
+    for len in 0..bytes.len() {
+        corpus.push(bytes[..len].to_vec());
+    }
+    for extra in [0, 4, 0xff] {
+        let mut extended = bytes.to_vec();
+        extended.push(extra);
+        corpus.push(extended);
+    }
+    for i in 0..bytes.len() {
+        for value in [
+            0,
+            1,
+            4,
+            0xff,
+            bytes[i].wrapping_add(1),
+            bytes[i].wrapping_sub(1),
+        ] {
+            let mut changed = bytes.to_vec();
+            changed[i] = value;
+            corpus.push(changed);
+        }
+    }
+    corpus
+}
+
+macro_rules! collection_tests {
+    ($backend: ident, $($list: ident)::+, $($vector: ident)::+) => {
+        mod $backend {
+            use super::*;
+            use $($list)::+ as List;
+            use $($vector)::+ as Vector;
+
+            type StaticList = List<u64, U16>;
+            type VariableItemsList = List<Payload, U4>;
+            type StaticVector = Vector<u64, U4>;
+            type VariableItemsVector = Vector<Payload, U4>;
+
+            #[test]
+            fn test_lists_roundtrip() {
+                for len in [0, 1, 3, 16] {
+                    let items = static_items(len);
+                    let list = StaticList::new(items.clone()).unwrap();
+                    let bytes = VariableList::<u64, U16>::new(items).unwrap().as_ssz_bytes();
+                    assert_encodes_to(&list, &bytes);
+                    assert_eq!(StaticList::from_ssz_bytes(&bytes), Ok(list.clone()));
+                    assert_eq!(StaticList::from_ssz_bytes_trusted(&bytes), Ok(list.clone()));
+                    assert_eq!(validate_ssz::<StaticList>(&bytes), Ok(()));
+                    for at in 0..=bytes.len() {
+                        assert_eq!(read_chained(&bytes, at), Ok(list.clone()));
+                    }
+                }
+
+                for len in 0..=4 {
+                    let items = variable_items(len);
+                    let list = VariableItemsList::new(items.clone()).unwrap();
+                    let bytes = VariableList::<Payload, U4>::new(items).unwrap().as_ssz_bytes();
+                    assert_encodes_to(&list, &bytes);
+                    assert_eq!(VariableItemsList::from_ssz_bytes(&bytes), Ok(list.clone()));
+                    assert_eq!(
+                        VariableItemsList::from_ssz_bytes_trusted(&bytes),
+                        Ok(list.clone())
+                    );
+                    assert_eq!(validate_ssz::<VariableItemsList>(&bytes), Ok(()));
+                    for at in 0..=bytes.len() {
+                        assert_eq!(read_chained(&bytes, at), Ok(list.clone()));
+                    }
+                }
+            }
+
+            // integers are copied in bulk where the items are contiguous, and have to come out
+            // little-endian whatever the target
+            #[test]
+            fn test_packed_integers() {
+                let list = List::<u16, U4>::new(vec![0x0102, 0xa0b0]).unwrap();
+                assert_encodes_to(&list, &[0x02, 0x01, 0xb0, 0xa0]);
+                let list = List::<u32, U4>::new(vec![0x01020304]).unwrap();
+                assert_encodes_to(&list, &[0x04, 0x03, 0x02, 0x01]);
+                let list = List::<u64, U4>::new(vec![0x0102030405060708, 1]).unwrap();
+                assert_encodes_to(
+                    &list,
+                    &[8, 7, 6, 5, 4, 3, 2, 1, 1, 0, 0, 0, 0, 0, 0, 0],
+                );
+
+                let vector = Vector::<u32, U4>::new(vec![1, 0x100, 0x10000, 0x1000000]).unwrap();
+                assert_encodes_to(
+                    &vector,
+                    &[1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1],
+                );
+            }
+
+            // the items of a tree are gathered into runs, which have to come out the same
+            // whether a run ends on a packed leaf or part way into one
+            #[test]
+            fn test_packed_runs() {
+                macro_rules! check {
+                    ($ty: ty, $lens: expr) => {
+                        for len in $lens {
+                            let items: Vec<$ty> =
+                                (0..len).map(|i: usize| (i * 0x0103_0507) as $ty).collect();
+                            let list = List::<$ty, U8192>::new(items.clone()).unwrap();
+                            let bytes =
+                                VariableList::<$ty, U8192>::new(items).unwrap().as_ssz_bytes();
+                            assert_encodes_to(&list, &bytes);
+                        }
+                    };
+                }
+                check!(u8, [0, 1, 31, 32, 33, 4095, 4096, 4097, 8000]);
+                check!(u16, [1, 15, 17, 2047, 2048, 2049]);
+                check!(u32, [7, 9, 1023, 1024, 1025]);
+                check!(u64, [3, 4, 5, 511, 512, 513, 1027]);
+            }
+
+            #[test]
+            fn test_vectors_roundtrip() {
+                let items = static_items(4);
+                let vector = StaticVector::new(items.clone()).unwrap();
+                let bytes = FixedVector::<u64, U4>::new(items).unwrap().as_ssz_bytes();
+                assert_encodes_to(&vector, &bytes);
+                assert_eq!(StaticVector::from_ssz_bytes(&bytes), Ok(vector.clone()));
+                assert_eq!(StaticVector::from_ssz_bytes_trusted(&bytes), Ok(vector.clone()));
+                assert_eq!(validate_ssz::<StaticVector>(&bytes), Ok(()));
+
+                let items = variable_items(4);
+                let vector = VariableItemsVector::new(items.clone()).unwrap();
+                let bytes = FixedVector::<Payload, U4>::new(items).unwrap().as_ssz_bytes();
+                assert_encodes_to(&vector, &bytes);
+                assert_eq!(VariableItemsVector::from_ssz_bytes(&bytes), Ok(vector.clone()));
+                assert_eq!(
+                    VariableItemsVector::from_ssz_bytes_trusted(&bytes),
+                    Ok(vector.clone())
+                );
+                assert_eq!(validate_ssz::<VariableItemsVector>(&bytes), Ok(()));
+                for at in 0..=bytes.len() {
+                    assert_eq!(read_chained(&bytes, at), Ok(vector.clone()));
+                }
+            }
+
+            #[test]
+            fn test_malformed_lists() {
+                let bytes = StaticList::new(static_items(5)).unwrap().to_ssz();
+                for input in corpus(&bytes) {
+                    assert_decodes_like_vec(
+                        &input,
+                        16,
+                        |bytes| {
+                            StaticList::from_ssz_bytes(bytes)
+                                .map(|list| list.iter().copied().collect())
+                        },
+                        validate_ssz::<StaticList>,
+                    );
+                }
+                let bytes = VariableItemsList::new(variable_items(4)).unwrap().to_ssz();
+                for input in corpus(&bytes) {
+                    assert_decodes_like_vec(
+                        &input,
+                        4,
+                        |bytes| {
+                            VariableItemsList::from_ssz_bytes(bytes)
+                                .map(|list| list.iter().cloned().collect())
+                        },
+                        validate_ssz::<VariableItemsList>,
+                    );
+                }
+
+                assert_eq!(
+                    StaticList::from_ssz_bytes(&[0; 17 * 8]),
+                    Err(DecodeError::ListTooLong { len: 17, max: 16 })
+                );
+                assert_eq!(
+                    StaticList::from_ssz_bytes_trusted(&[0; 17 * 8]),
+                    Err(DecodeError::ListTooLong { len: 17, max: 16 })
+                );
+                assert_eq!(
+                    StaticList::from_ssz_bytes(&[0; 7]),
+                    Err(DecodeError::InvalidByteLength {
+                        len: 7,
+                        expected: 8
+                    })
+                );
+                // five offsets, all pointing past the offset table
+                let bytes = [20u8, 0, 0, 0].repeat(5);
+                assert_eq!(
+                    VariableItemsList::from_ssz_bytes(&bytes),
+                    Err(DecodeError::ListTooLong { len: 5, max: 4 })
+                );
+                assert_eq!(
+                    VariableItemsList::from_ssz_bytes(&[3, 0, 0, 0, 0, 0]),
+                    Err(DecodeError::InvalidListFixedBytesLen(3))
+                );
+
+                // a first offset claiming 1000 items in a 40 byte list, whose offset table
+                // can't be split off
+                let mut bytes = [0u8; 40];
+                bytes[..4].copy_from_slice(&4000u32.to_le_bytes());
+                let err = DecodeError::OffsetOutOfBounds(4000);
+                assert_eq!(VariableItemsList::from_ssz_bytes(&bytes), Err(err.clone()));
+                assert_eq!(validate_ssz::<VariableItemsList>(&bytes), Err(err.clone()));
+                for at in [0, 2, 20] {
+                    assert_eq!(
+                        read_chained::<VariableItemsList>(&bytes, at),
+                        Err(err.clone())
+                    );
+                }
+                let mut list = VariableItemsList::new(variable_items(2)).unwrap();
+                assert_eq!(list.decode_into(&bytes), Err(err));
+                assert_eq!(
+                    VariableItemsList::from_ssz_bytes_trusted(&bytes),
+                    Err(DecodeError::InvalidListFixedBytesLen(4000))
+                );
+            }
+
+            #[test]
+            fn test_malformed_vectors() {
+                assert_eq!(
+                    StaticVector::ssz_read(&mut &[0u8; 8][..], &mut &[0u8; 0][..]),
+                    Err(DecodeError::VectorLengthMismatch {
+                        found: 1,
+                        expected: 4
+                    })
+                );
+                assert_eq!(
+                    StaticVector::from_ssz_bytes(&[0; 8]),
+                    Err(DecodeError::InvalidByteLength {
+                        len: 8,
+                        expected: 32
+                    })
+                );
+                assert_eq!(
+                    VariableItemsVector::from_ssz_bytes(&[]),
+                    Err(DecodeError::VectorLengthMismatch {
+                        found: 0,
+                        expected: 4
+                    })
+                );
+                assert_eq!(
+                    VariableItemsVector::from_ssz_bytes(&[16, 0, 0, 0, 16, 0, 0, 0]),
+                    Err(DecodeError::InvalidByteLength {
+                        len: 8,
+                        expected: 16
+                    })
+                );
+                assert_eq!(
+                    VariableItemsVector::from_ssz_bytes_trusted(&[4, 0, 0, 0]),
+                    Err(DecodeError::VectorLengthMismatch {
+                        found: 1,
+                        expected: 4
+                    })
+                );
+
+                // every accepted mutation decodes to four items, like the FixedVector it's
+                // checked against, and the rest fail the same way
+                let bytes = VariableItemsVector::new(variable_items(4)).unwrap().to_ssz();
+                for input in corpus(&bytes) {
+                    let decoded = VariableItemsVector::from_ssz_bytes(&input);
+                    let expected = FixedVector::<Payload, U4>::from_ssz_bytes(&input);
+                    match (&decoded, &expected) {
+                        (Ok(decoded), Ok(expected)) => {
+                            assert!(decoded.iter().eq(expected.iter()), "{:?}", input)
+                        }
+                        (Err(decoded), Err(expected)) => assert_eq!(decoded, expected),
+                        _ => panic!("{:?} but FixedVector gave {:?}", decoded, expected),
+                    }
+                    assert_eq!(
+                        validate_ssz::<VariableItemsVector>(&input).is_ok(),
+                        decoded.is_ok()
+                    );
+                }
+            }
+
+            #[test]
+            fn test_decode_into() {
+                let mut list = VariableItemsList::new(variable_items(4)).unwrap();
+                let shorter = VariableItemsList::new(variable_items(2)).unwrap();
+                list.decode_into(&shorter.to_ssz()).unwrap();
+                assert_eq!(list, shorter);
+
+                let mut bytes = VariableItemsList::new(variable_items(3)).unwrap().to_ssz();
+                bytes[4] = 0;
+                let err = VariableItemsList::from_ssz_bytes(&bytes).unwrap_err();
+                assert_eq!(list.decode_into(&bytes), Err(err));
+            }
+        }
+    };
+}
+
+collection_tests!(
+    ssz_types_backend,
+    ssz_types::VariableList,
+    ssz_types::FixedVector
+);
+#[cfg(feature = "milhouse")]
+collection_tests!(milhouse_backend, milhouse::List, milhouse::Vector);
+#[cfg(feature = "ghilhouse")]
+collection_tests!(ghilhouse_backend, ghilhouse::List, ghilhouse::Vector);
+
+// the widest integers, on ssz_types lists alone
+#[test]
+fn test_packed_u128s() {
+    let list = VariableList::<u128, U4>::new(vec![u128::MAX - 1, 0x0102]).unwrap();
+    let mut expected = vec![0xff; 16];
+    expected[0] = 0xfe;
+    expected.extend_from_slice(&[0x02, 0x01]);
+    expected.resize(32, 0);
+    assert_encodes_to(&list, &expected);
+}
@@ -0,0 +1,78 @@
+use bytes::buf::{Buf, BufMut};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Chunk<const N: usize> {
+    data: [u8; N],
+    checksum: u32,
+}
+
+fn chunk<const N: usize>() -> Chunk<N> {
+    Chunk {
+        data: core::array::from_fn(|i| i as u8),
+        checksum: N as u32,
+    }
+}
+
+#[test]
+fn test_const_generic_array() {
+    assert_eq!(<Chunk<4> as SszbEncode>::SSZ_FIXED_LEN, 8);
+    assert_eq!(<Chunk<32> as SszbDecode>::SSZ_FIXED_LEN, 36);
+
+    let small = chunk::<4>();
+    let bytes = small.to_ssz();
+    assert_eq!(bytes, [0, 1, 2, 3, 4, 0, 0, 0]);
+    assert_eq!(Chunk::<4>::from_ssz_bytes(&bytes), Ok(small));
+
+    let large = chunk::<32>();
+    let bytes = large.to_ssz();
+    assert_eq!(bytes.len(), 36);
+    assert_eq!(&bytes[32..], &[32, 0, 0, 0]);
+    assert_eq!(Chunk::<32>::from_ssz_bytes(&bytes), Ok(large));
+
+    // the length is part of the type, so one chunk's encoding doesn't decode as another
+    assert!(Chunk::<32>::from_ssz_bytes(&chunk::<4>().to_ssz()).is_err());
+}
+
+#[cfg(all(feature = "alloy", feature = "ssz-types"))]
+mod fixed_bytes {
+    use super::*;
+    use alloy_primitives::FixedBytes;
+    use ssz_types::VariableList;
+    use typenum::U64;
+
+    #[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+    struct Tagged<const N: usize> {
+        tag: FixedBytes<N>,
+        body: VariableList<u8, U64>,
+        chunk: Chunk<N>,
+    }
+
+    fn tagged<const N: usize>() -> Tagged<N> {
+        Tagged {
+            tag: FixedBytes::repeat_byte(0xab),
+            body: VariableList::new(vec![1, 2, 3]).unwrap(),
+            chunk: chunk(),
+        }
+    }
+
+    #[test]
+    fn test_const_generic_fixed_bytes() {
+        let tag = tagged::<8>();
+        let bytes = tag.to_ssz();
+        // the tag, the body's offset and the chunk, then the body
+        assert_eq!(bytes.len(), 8 + 4 + 12 + 3);
+        assert_eq!(&bytes[8..12], &[24, 0, 0, 0]);
+        assert_eq!(Tagged::<8>::from_ssz_bytes(&bytes), Ok(tag));
+        assert_eq!(
+            Tagged::<8>::decode_field_body(&bytes).unwrap().to_vec(),
+            [1, 2, 3]
+        );
+
+        let tag = tagged::<20>();
+        let bytes = tag.to_ssz();
+        assert_eq!(bytes.len(), 20 + 4 + 24 + 3);
+        assert_eq!(Tagged::<20>::from_ssz_bytes_trusted(&bytes), Ok(tag));
+    }
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::context::{DecodeContext, DecodeWithContext};
+use sszb::{validate_ssz, ByteList, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U1024, U32, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct State {
+    slot: u64,
+    #[ssz(ctx_max = "max_validators")]
+    validators: VariableList<u64, U1024>,
+    #[ssz(ctx_max = "max_graffiti")]
+    graffiti: ByteList<U32>,
+    history: VariableList<u64, U8>,
+}
+
+fn state(validators: usize) -> State {
+    State {
+        slot: 9,
+        validators: VariableList::new(vec![32; validators]).unwrap(),
+        graffiti: ByteList::new(&b"preset"[..]).unwrap(),
+        history: VariableList::new(vec![1, 2, 3]).unwrap(),
+    }
+}
+
+fn mainnet() -> DecodeContext {
+    DecodeContext::new()
+        .with_limit("max_validators", 64)
+        .with_limit("max_graffiti", 32)
+}
+
+fn minimal() -> DecodeContext {
+    DecodeContext::new()
+        .with_limit("max_validators", 8)
+        .with_limit("max_graffiti", 32)
+}
+
+#[test]
+fn test_limit_per_call() {
+    let bytes = state(16).to_ssz();
+    assert_eq!(
+        State::from_ssz_bytes_with_ctx(&bytes, &mainnet()),
+        Ok(state(16))
+    );
+    assert_eq!(
+        State::from_ssz_bytes_with_ctx(&bytes, &minimal()),
+        Err(DecodeError::ListTooLong { len: 16, max: 8 }.at_byte(20))
+    );
+
+    // the limit is checked on the length of the list before it's decoded, ahead of the fields
+    // after it
+    let mut bytes = state(16).to_ssz();
+    bytes.push(0);
+    assert_eq!(
+        State::from_ssz_bytes_with_ctx(&bytes, &minimal()),
+        Err(DecodeError::ListTooLong { len: 16, max: 8 }.at_byte(20))
+    );
+
+    // at the limit is fine
+    let bytes = state(8).to_ssz();
+    assert_eq!(
+        State::from_ssz_bytes_with_ctx(&bytes, &minimal()),
+        Ok(state(8))
+    );
+
+    // limits apply to byte lists as well, below the bound of their type
+    let mut long = state(1);
+    long.graffiti = ByteList::new(vec![0; 32]).unwrap();
+    let ctx = mainnet().with_limit("max_graffiti", 16);
+    assert_eq!(
+        State::from_ssz_bytes_with_ctx(&long.to_ssz(), &ctx),
+        Err(DecodeError::ListTooLong { len: 32, max: 16 }.at_byte(28))
+    );
+}
+
+#[test]
+fn test_missing_limit() {
+    let bytes = state(1).to_ssz();
+    assert_eq!(
+        State::from_ssz_bytes(&bytes),
+        Err(DecodeError::MissingContextLimit("max_validators").at_byte(20))
+    );
+    let partial = DecodeContext::new().with_limit("max_validators", 64);
+    assert_eq!(
+        State::from_ssz_bytes_with_ctx(&bytes, &partial),
+        Err(DecodeError::MissingContextLimit("max_graffiti").at_byte(28))
+    );
+    assert_eq!(
+        validate_ssz::<State>(&bytes),
+        Err(DecodeError::MissingContextLimit("max_validators").at_byte(20))
+    );
+}
+
+#[test]
+fn test_installed_per_thread() {
+    let bytes = state(16).to_ssz();
+    {
+        let _guard = minimal().install();
+        assert_eq!(DecodeContext::current(), Some(minimal()));
+        assert_eq!(
+            State::from_ssz_bytes(&bytes),
+            Err(DecodeError::ListTooLong { len: 16, max: 8 }.at_byte(20))
+        );
+        assert_eq!(
+            validate_ssz::<State>(&bytes),
+            Err(DecodeError::ListTooLong { len: 16, max: 8 }.at_byte(20))
+        );
+
+        // a context for one call replaces the installed one, which is back afterwards
+        assert_eq!(
+            State::from_ssz_bytes_with_ctx(&bytes, &mainnet()),
+            Ok(state(16))
+        );
+        assert_eq!(DecodeContext::current(), Some(minimal()));
+
+        // other threads have their own
+        let other = std::thread::spawn(move || State::from_ssz_bytes(&bytes))
+            .join()
+            .unwrap();
+        assert_eq!(
+            other,
+            Err(DecodeError::MissingContextLimit("max_validators").at_byte(20))
+        );
+    }
+    assert_eq!(DecodeContext::current(), None);
+
+    assert_eq!(
+        mainnet().scope(|| State::from_ssz_bytes(&state(16).to_ssz())),
+        Ok(state(16))
+    );
+}
+
+// the limit applies to every container of a list, and encoding doesn't look at it
+#[test]
+fn test_nested() {
+    let states = VariableList::<State, U8>::new(vec![state(4), state(12)]).unwrap();
+    let bytes = states.to_ssz();
+    assert_eq!(
+        VariableList::<State, U8>::from_ssz_bytes_with_ctx(&bytes, &mainnet()),
+        Ok(states)
+    );
+
+    let err = VariableList::<State, U8>::from_ssz_bytes_with_ctx(&bytes, &minimal()).unwrap_err();
+    assert_eq!(
+        err.root_cause(),
+        &DecodeError::ListTooLong { len: 12, max: 8 }
+    );
+    assert_eq!(err.position(), Some(8 + state(4).sszb_bytes_len() + 20));
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use bytes::Bytes;
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U64;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Header {
+    slot: u64,
+    root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Message {
+    slot: u64,
+    payload: VariableList<u8, U64>,
+    flags: u16,
+}
+
+fn header() -> Header {
+    Header {
+        slot: 5,
+        root: [7; 32],
+    }
+}
+
+fn message() -> Message {
+    Message {
+        slot: 9,
+        payload: VariableList::new(vec![1, 2, 3, 4]).unwrap(),
+        flags: 3,
+    }
+}
+
+#[test]
+fn test_decode_from_bytes() {
+    let mut bytes = Bytes::from(message().to_ssz());
+    assert_eq!(Message::decode_from_buf(&mut bytes), Ok(message()));
+    assert!(!bytes.has_remaining());
+}
+
+#[test]
+fn test_decode_from_chain() {
+    let bytes = message().to_ssz();
+    // split inside the fixed section, between the slot and the payload's offset
+    for at in [0, 3, 8, 11, bytes.len()] {
+        let (front, back) = bytes.split_at(at);
+        let mut chain = front.chain(back);
+        assert_eq!(Message::decode_from_buf(&mut chain), Ok(message()));
+        assert!(!chain.has_remaining());
+    }
+
+    let bytes = header().to_ssz();
+    let (front, back) = bytes.split_at(20);
+    assert_eq!(
+        Header::decode_from_buf(&mut front.chain(back)),
+        Ok(header())
+    );
+}
+
+#[test]
+fn test_sequential_decodes() {
+    let mut bytes = header().to_ssz();
+    bytes.extend(message().to_ssz());
+
+    // the header takes only its own bytes, and the message the rest
+    let (front, back) = bytes.split_at(50);
+    let mut chain = front.chain(back);
+    assert_eq!(Header::decode_from_buf(&mut chain), Ok(header()));
+    assert_eq!(chain.remaining(), message().sszb_bytes_len());
+    assert_eq!(Message::decode_from_buf(&mut chain), Ok(message()));
+    assert!(!chain.has_remaining());
+
+    let mut bytes = &[1u8, 0, 2, 0, 0, 0][..];
+    assert_eq!(u16::decode_from_buf(&mut bytes), Ok(1));
+    assert_eq!(u32::decode_from_buf(&mut bytes), Ok(2));
+    assert_eq!(
+        u8::decode_from_buf(&mut bytes),
+        Err(DecodeError::InvalidByteLength {
+            len: 0,
+            expected: 1
+        })
+    );
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{FixedVector, VariableList};
+use sszb::{decode_list_into, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U1024, U16, U4, U64};
+
+type Blob = VariableList<u8, U1024>;
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    balances: VariableList<u64, U1024>,
+    blobs: VariableList<Blob, U16>,
+    root: [u8; 32],
+}
+
+fn blob(len: usize, fill: u8) -> Blob {
+    VariableList::new(vec![fill; len]).unwrap()
+}
+
+fn block(slot: u64, num_balances: usize, blob_lens: &[usize]) -> Block {
+    Block {
+        slot,
+        balances: VariableList::new((0..num_balances as u64).collect()).unwrap(),
+        blobs: VariableList::new(
+            blob_lens
+                .iter()
+                .enumerate()
+                .map(|(i, &len)| blob(len, i as u8))
+                .collect(),
+        )
+        .unwrap(),
+        root: [slot as u8; 32],
+    }
+}
+
+#[test]
+fn test_decode_into_list_reuses_capacity() {
+    let big: VariableList<u64, U1024> = VariableList::new((0..1000).collect()).unwrap();
+    let small: VariableList<u64, U1024> = VariableList::new(vec![7, 8, 9]).unwrap();
+
+    let mut list = VariableList::<u64, U1024>::from_ssz_bytes(&big.to_ssz()).unwrap();
+    let ptr = list.as_ptr();
+    list.decode_into(&small.to_ssz()).unwrap();
+    assert_eq!(list, small);
+    list.decode_into(&big.to_ssz()).unwrap();
+    assert_eq!(list, big);
+    assert_eq!(list.as_ptr(), ptr);
+
+    list.decode_into(&[]).unwrap();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn test_decode_into_container() {
+    let first = block(1, 900, &[1000, 800]);
+    let second = block(2, 20, &[10, 700, 3]);
+
+    let mut value = Block::from_ssz_bytes(&first.to_ssz()).unwrap();
+    let balances_ptr = value.balances.as_ptr();
+    let blob_ptrs: Vec<_> = value.blobs.iter().map(|blob| blob.as_ptr()).collect();
+
+    value.decode_into(&second.to_ssz()).unwrap();
+    assert_eq!(value, second);
+    // the lists, and the blobs that were already there, kept their allocations
+    assert_eq!(value.balances.as_ptr(), balances_ptr);
+    assert_eq!(value.blobs[0].as_ptr(), blob_ptrs[0]);
+    assert_eq!(value.blobs[1].as_ptr(), blob_ptrs[1]);
+
+    value.decode_into(&first.to_ssz()).unwrap();
+    assert_eq!(value, first);
+    assert_eq!(value.balances.as_ptr(), balances_ptr);
+}
+
+#[test]
+fn test_decode_into_vector() {
+    let first: FixedVector<[u8; 48], U4> = FixedVector::new(vec![[1; 48]; 4]).unwrap();
+    let second: FixedVector<[u8; 48], U4> = FixedVector::new(vec![[2; 48]; 4]).unwrap();
+
+    let mut vector = FixedVector::<[u8; 48], U4>::from_ssz_bytes(&first.to_ssz()).unwrap();
+    let ptr = vector.as_ptr();
+    vector.decode_into(&second.to_ssz()).unwrap();
+    assert_eq!(vector, second);
+    assert_eq!(vector.as_ptr(), ptr);
+
+    // a vector of variable-size items decodes over them too
+    let blobs: FixedVector<Blob, U4> = FixedVector::new(vec![blob(900, 1); 4]).unwrap();
+    let mut vector = FixedVector::<Blob, U4>::from_ssz_bytes(&blobs.to_ssz()).unwrap();
+    let blob_ptr = vector[3].as_ptr();
+    let smaller: FixedVector<Blob, U4> = FixedVector::new(vec![blob(10, 2); 4]).unwrap();
+    vector.decode_into(&smaller.to_ssz()).unwrap();
+    assert_eq!(vector, smaller);
+    assert_eq!(vector[3].as_ptr(), blob_ptr);
+
+    // the items are all checked first, so a bad offset after the first leaves the old ones in
+    // place
+    let mut bytes = blobs.to_ssz();
+    bytes[4..8].copy_from_slice(&[0; 4]);
+    let err = FixedVector::<Blob, U4>::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(vector.decode_into(&bytes), Err(err));
+    assert_eq!(vector, smaller);
+
+    let err = FixedVector::<[u8; 48], U4>::from_ssz_bytes(&[0; 100]).unwrap_err();
+    let mut keys = first.clone();
+    assert_eq!(keys.decode_into(&[0; 100]), Err(err));
+    assert_eq!(keys, first);
+}
+
+#[test]
+fn test_decode_into_vec() {
+    let list: VariableList<Blob, U16> = VariableList::new(vec![blob(3, 1), blob(0, 2)]).unwrap();
+    let bytes = list.to_ssz();
+
+    let mut items = Vec::with_capacity(16);
+    items.push(blob(100, 0));
+    let ptr = items[0].as_ptr();
+    decode_list_into(&bytes, 16, &mut items).unwrap();
+    assert_eq!(items, list.to_vec());
+    assert_eq!(items[0].as_ptr(), ptr);
+    assert_eq!(items.capacity(), 16);
+
+    assert_eq!(
+        decode_list_into(&bytes, 1, &mut items),
+        Err(DecodeError::ListTooLong { len: 2, max: 1 })
+    );
+    assert!(items.is_empty());
+}
+
+#[test]
+fn test_decode_into_errors_match_from_ssz_bytes() {
+    let list: VariableList<Blob, U16> =
+        VariableList::new(vec![blob(3, 1), blob(4, 2), blob(5, 3)]).unwrap();
+    let mut bytes = list.to_ssz();
+    // the third offset points before the second
+    bytes[8] = 0;
+
+    let mut target: VariableList<Blob, U16> =
+        VariableList::new(vec![blob(1, 9), blob(1, 9), blob(1, 9)]).unwrap();
+    let err = VariableList::<Blob, U16>::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(target.decode_into(&bytes), Err(err));
+    // the first blob was already overwritten, so the list was cleared rather than mixing items
+    assert!(target.is_empty());
+
+    let mut balances: VariableList<u64, U64> = VariableList::new(vec![1, 2]).unwrap();
+    assert_eq!(
+        balances.decode_into(&[0; 65 * 8]),
+        Err(DecodeError::ListTooLong { len: 65, max: 64 })
+    );
+    assert!(balances.is_empty());
+
+    // a static value is only overwritten on success
+    let mut slot = 5u64;
+    assert!(slot.decode_into(&[1, 2, 3]).is_err());
+    assert_eq!(slot, 5);
+    slot.decode_into(&6u64.to_ssz()).unwrap();
+    assert_eq!(slot, 6);
+}
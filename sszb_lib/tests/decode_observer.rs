@@ -0,0 +1,122 @@
+#![cfg(all(feature = "metrics", feature = "ssz-types"))]
+
+// failed decodes are reported to the observer once each, with their error and type, and the
+// decodes of their fields and items aren't
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{set_decode_observer, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::sync::Mutex;
+use typenum::{U2, U4};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    votes: VariableList<u8, U2>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+type Blocks = VariableList<Block, U4>;
+
+static REJECTED: Mutex<Vec<(DecodeError, &'static str)>> = Mutex::new(Vec::new());
+
+fn record(error: &DecodeError, type_name: &'static str) {
+    REJECTED.lock().unwrap().push((error.clone(), type_name));
+}
+
+fn rejected() -> Vec<(DecodeError, &'static str)> {
+    std::mem::take(&mut *REJECTED.lock().unwrap())
+}
+
+fn block(slot: u64, votes: usize) -> Vec<u8> {
+    // votes past the maximum of a Block, to decode as one
+    let votes = VariableList::<u8, U4>::new(vec![1; votes]).unwrap();
+    let mut bytes = slot.to_ssz();
+    bytes.extend(12u32.to_ssz());
+    bytes.extend(votes.to_ssz());
+    bytes
+}
+
+// the observer is installed for the whole process, so everything runs in one test
+#[test]
+fn test_observer() {
+    assert!(Block::from_ssz_bytes(&block(1, 3)).is_err());
+    assert_eq!(set_decode_observer(record), Ok(()));
+    assert!(set_decode_observer(record).is_err());
+    assert!(rejected().is_empty());
+
+    // valid payloads aren't reported
+    let checkpoint = Checkpoint {
+        epoch: 3,
+        root: [7; 32],
+    };
+    assert!(Block::from_ssz_bytes(&block(1, 2)).is_ok());
+    assert!(Checkpoint::from_ssz_bytes(&checkpoint.to_ssz()).is_ok());
+    assert!(rejected().is_empty());
+
+    // truncated, bad offset, over the limit
+    let truncated = &checkpoint.to_ssz()[..39];
+    let truncated_err = Checkpoint::from_ssz_bytes(truncated).unwrap_err();
+    let mut bad_offset = block(1, 1);
+    bad_offset[8] = 13;
+    let bad_offset_err = Block::from_ssz_bytes(&bad_offset).unwrap_err();
+    let too_long_err = Block::from_ssz_bytes(&block(1, 3)).unwrap_err();
+    assert_eq!(too_long_err, DecodeError::ListTooLong { len: 3, max: 2 });
+
+    let rejected = rejected();
+    assert_eq!(rejected.len(), 3);
+    assert_eq!(
+        rejected[0],
+        (truncated_err, std::any::type_name::<Checkpoint>())
+    );
+    assert_eq!(
+        rejected[1],
+        (bad_offset_err, std::any::type_name::<Block>())
+    );
+    assert_eq!(rejected[2], (too_long_err, std::any::type_name::<Block>()));
+    assert!(rejected[1].1.ends_with("Block"));
+
+    // a block that fails inside a list is reported once, as the list, with the list's error
+    let blocks = Blocks::new(vec![
+        Block {
+            slot: 1,
+            votes: VariableList::new(vec![1]).unwrap(),
+        },
+        Block {
+            slot: 2,
+            votes: VariableList::new(vec![1, 2]).unwrap(),
+        },
+    ])
+    .unwrap();
+    let mut bytes = blocks.to_ssz();
+    assert!(Blocks::from_ssz_bytes(&bytes).is_ok());
+    // the offset of the first block's votes points into its slot
+    bytes[8 + 8] = 3;
+    let err = Blocks::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(rejected(), vec![(err, std::any::type_name::<Blocks>())]);
+
+    // so are the failures of the other ways to decode a whole value
+    let shared_err = Block::from_ssz_bytes_shared(block(1, 3).into()).unwrap_err();
+    let mut value = Block {
+        slot: 0,
+        votes: VariableList::new(vec![1]).unwrap(),
+    };
+    let into_err = value.decode_into(&block(1, 3)).unwrap_err();
+    let trusted_err = Block::from_ssz_bytes_trusted(&block(1, 1)[..10]).unwrap_err();
+    let static_trusted_err = Checkpoint::from_ssz_bytes_trusted(truncated).unwrap_err();
+    assert_eq!(
+        rejected(),
+        vec![
+            (shared_err, std::any::type_name::<Block>()),
+            (into_err, std::any::type_name::<Block>()),
+            (trusted_err, std::any::type_name::<Block>()),
+            (static_trusted_err, std::any::type_name::<Checkpoint>()),
+        ]
+    );
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{FixedVector, VariableList};
+use sszb::{DecodeError, SszbDecode, SszbDecodeRef, SszbEncode};
+use sszb_derive::{SszbDecode, SszbDecodeRef, SszbEncode};
+use typenum::{U256, U96};
+
+#[derive(PartialEq, Debug, SszbDecodeRef)]
+struct Envelope<'a> {
+    slot: u64,
+    signature: &'a [u8; 96],
+    extra_data: &'a [u8],
+    root: [u8; 32],
+    graffiti: &'a [u8],
+}
+
+// the owned equivalent, used to produce fixtures and to compare errors against
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct OwnedEnvelope {
+    slot: u64,
+    signature: FixedVector<u8, U96>,
+    extra_data: VariableList<u8, U256>,
+    root: [u8; 32],
+    graffiti: VariableList<u8, U256>,
+}
+
+fn owned() -> OwnedEnvelope {
+    OwnedEnvelope {
+        slot: 42,
+        signature: FixedVector::new((0..96).collect()).unwrap(),
+        extra_data: VariableList::new(vec![7; 20]).unwrap(),
+        root: [3; 32],
+        graffiti: VariableList::new(b"sszb".to_vec()).unwrap(),
+    }
+}
+
+fn points_into(bytes: &[u8], field: &[u8]) -> bool {
+    let range = bytes.as_ptr_range();
+    range.contains(&field.as_ptr()) && field.as_ptr_range().end <= range.end
+}
+
+#[test]
+fn test_borrowed_fields_point_into_input() {
+    let owned = owned();
+    let bytes = owned.to_ssz();
+    let envelope = Envelope::from_ssz_bytes_ref(&bytes).unwrap();
+
+    assert_eq!(envelope.slot, 42);
+    assert_eq!(envelope.root, [3; 32]);
+    assert_eq!(&envelope.signature[..], &owned.signature[..]);
+    assert_eq!(envelope.extra_data, &owned.extra_data[..]);
+    assert_eq!(envelope.graffiti, b"sszb");
+
+    assert!(points_into(&bytes, envelope.signature));
+    assert!(points_into(&bytes, envelope.extra_data));
+    assert!(points_into(&bytes, envelope.graffiti));
+}
+
+#[test]
+fn test_validation_matches_owned() {
+    let bytes = owned().to_ssz();
+    // the fixed portion: slot, signature, extra_data offset, root, graffiti offset
+    let fixed_len = 8 + 96 + 4 + 32 + 4;
+
+    for i in 0..fixed_len {
+        for bit in 0..8 {
+            let mut flipped = bytes.clone();
+            flipped[i] ^= 1 << bit;
+            assert_eq!(
+                Envelope::from_ssz_bytes_ref(&flipped).err(),
+                OwnedEnvelope::from_ssz_bytes(&flipped).err(),
+                "flipped bit {} of byte {}",
+                bit,
+                i
+            );
+        }
+    }
+
+    for len in 0..bytes.len() {
+        assert_eq!(
+            Envelope::from_ssz_bytes_ref(&bytes[..len]).err(),
+            OwnedEnvelope::from_ssz_bytes(&bytes[..len]).err(),
+        );
+    }
+}
+
+#[test]
+fn test_fixed_size_view() {
+    let bytes = [1u8; 4];
+    assert_eq!(<&[u8; 4]>::from_ssz_bytes_ref(&bytes), Ok(&bytes));
+    assert_eq!(
+        <&[u8; 4]>::from_ssz_bytes_ref(&bytes[..3]),
+        Err(DecodeError::InvalidByteLength {
+            len: 3,
+            expected: 4
+        })
+    );
+}
@@ -0,0 +1,95 @@
+use bytes::buf::{Buf, BufMut};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+fn check_slot(slot: &u64) -> Result<(), String> {
+    if *slot == u64::MAX {
+        return Err("slot is out of range".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+struct Index(u16);
+
+impl From<Index> for u16 {
+    fn from(index: Index) -> u16 {
+        index.0
+    }
+}
+
+impl From<u16> for Index {
+    fn from(index: u16) -> Index {
+        Index(index)
+    }
+}
+
+// the options that go together, spread over one attribute or several
+#[derive(Debug, PartialEq, SszbEncode, SszbDecode)]
+#[ssz(lenient_decode)]
+#[ssz(validate = "Header::check")]
+struct Header {
+    #[ssz(validate = "check_slot")]
+    slot: u64,
+    #[ssz(encode_as = "u16", validate = "Header::check_index")]
+    index: Index,
+    #[ssz(skip_encode, skip_decode)]
+    cache: u32,
+    #[ssz(skip_encode)]
+    #[ssz(skip_decode)]
+    scratch: Vec<u8>,
+}
+
+impl Header {
+    fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn check_index(index: &Index) -> Result<(), String> {
+        if index.0 > 1000 {
+            return Err("index is out of range".to_string());
+        }
+        Ok(())
+    }
+}
+
+// a field that's only ever written, which is acknowledged
+#[derive(SszbEncode)]
+struct Report {
+    slot: u64,
+    #[ssz(skip_decode, allow_asymmetric_skip)]
+    checksum: u32,
+}
+
+#[test]
+fn test_valid_options() {
+    let header = Header {
+        slot: 1,
+        index: Index(2),
+        cache: 3,
+        scratch: vec![4],
+    };
+    let bytes = header.to_ssz();
+    assert_eq!(bytes, [1, 0, 0, 0, 0, 0, 0, 0, 2, 0]);
+    assert_eq!(
+        Header::from_ssz_bytes(&bytes),
+        Ok(Header {
+            slot: 1,
+            index: Index(2),
+            cache: 0,
+            scratch: vec![],
+        })
+    );
+
+    let report = Report {
+        slot: 1,
+        checksum: 2,
+    };
+    assert_eq!(report.to_ssz(), [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0]);
+}
+
+#[test]
+fn test_invalid_options_fail_to_compile() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/attrs/*.rs");
+}
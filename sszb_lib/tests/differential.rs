@@ -0,0 +1,346 @@
+//! Differential tests against `ethereum_ssz`, the implementation this crate replaces.
+//!
+//! Random values must encode to identical bytes, and every mutation of those bytes (bit flips,
+//! truncations, extensions) must be accepted or rejected by both crates alike, decoding to the
+//! same value when accepted. Error variants are allowed to differ. The bulk decoding of integer
+//! lists is also checked against decoding the same bytes one integer at a time, and
+//! `from_ssz_bytes_trusted` has to decode every accepted input to the same value (and must not
+//! panic on the rest).
+//!
+//! Known, intentional divergences are listed in `KNOWN_DIVERGENCES` and asserted in
+//! `test_known_divergences`, so they can't silently disappear or grow.
+#![cfg(all(feature = "alloy", feature = "ssz-types"))]
+
+use alloy_primitives::{Address, B256, U256};
+use bytes::buf::{Buf, BufMut};
+use proptest::{collection::vec, prelude::*};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U16, U32, U4, U64, U8};
+
+#[derive(
+    Clone, PartialEq, Debug, SszbEncode, SszbDecode, ssz_derive::Encode, ssz_derive::Decode,
+)]
+struct Checkpoint {
+    epoch: u64,
+    root: B256,
+}
+
+#[derive(
+    Clone, PartialEq, Debug, SszbEncode, SszbDecode, ssz_derive::Encode, ssz_derive::Decode,
+)]
+struct Attestation {
+    aggregation_bits: BitList<U64>,
+    slot: u64,
+    target: Checkpoint,
+    indices: VariableList<u64, U16>,
+    signature: FixedVector<u8, U32>,
+}
+
+#[derive(
+    Clone, PartialEq, Debug, SszbEncode, SszbDecode, ssz_derive::Encode, ssz_derive::Decode,
+)]
+struct Block {
+    slot: u64,
+    attestations: VariableList<Attestation, U4>,
+    graffiti: VariableList<u8, U32>,
+}
+
+// generic containers compute their fixed portion from their parameters' lengths
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Envelope<T: SszbEncode + SszbDecode, U: SszbEncode + SszbDecode> {
+    slot: u64,
+    payload: T,
+    root: U,
+    graffiti: VariableList<u8, U32>,
+}
+
+#[derive(
+    Clone, PartialEq, Debug, SszbEncode, SszbDecode, ssz_derive::Encode, ssz_derive::Decode,
+)]
+struct ConcreteEnvelope {
+    slot: u64,
+    payload: VariableList<u16, U8>,
+    root: B256,
+    graffiti: VariableList<u8, U32>,
+}
+
+/// Inputs on which the two crates are known to disagree, as (type, bytes, sszb accepts).
+/// There are none at the moment; add an entry here (with a comment explaining why) rather than
+/// weakening the property tests below.
+const KNOWN_DIVERGENCES: &[(&str, &[u8], bool)] = &[];
+
+fn assert_same_decision<T>(bytes: &[u8])
+where
+    T: SszbDecode + ssz::Decode + PartialEq + Debug,
+{
+    let ours = <T as SszbDecode>::from_ssz_bytes(bytes);
+    let theirs = <T as ssz::Decode>::from_ssz_bytes(bytes);
+    let trusted = <T as SszbDecode>::from_ssz_bytes_trusted(bytes);
+    if let Ok(ours) = &ours {
+        assert_eq!(
+            trusted.as_ref(),
+            Ok(ours),
+            "trusted decode differs for {:?}",
+            bytes
+        );
+    }
+
+    match (ours, theirs) {
+        (Ok(ours), Ok(theirs)) => assert_eq!(ours, theirs, "decoded values differ for {:?}", bytes),
+        (Err(_), Err(_)) => {}
+        (ours, theirs) => panic!(
+            "accept/reject mismatch for {:?}: sszb {:?}, ethereum_ssz {:?}",
+            bytes, ours, theirs
+        ),
+    }
+}
+
+fn assert_equivalent<T>(item: &T)
+where
+    T: SszbEncode + SszbDecode + ssz::Encode + ssz::Decode + PartialEq + Debug,
+{
+    let bytes = SszbEncode::to_ssz(item);
+    assert_eq!(bytes, <T as ssz::Encode>::as_ssz_bytes(item));
+    // to_ssz measures every variable part once up front, ssz_write measures them as it goes
+    let mut written = vec![0u8; item.sszb_bytes_len()];
+    item.ssz_write(&mut written.as_mut_slice());
+    assert_eq!(written, bytes);
+    assert_same_decision::<T>(&bytes);
+
+    // flip every bit of the first 64 bytes, where the fixed portion and offsets live
+    for i in 0..bytes.len().min(64) {
+        for bit in 0..8 {
+            let mut flipped = bytes.clone();
+            flipped[i] ^= 1 << bit;
+            assert_same_decision::<T>(&flipped);
+        }
+    }
+
+    for len in 0..bytes.len() {
+        assert_same_decision::<T>(&bytes[..len]);
+    }
+
+    for extra in [
+        &[0u8][..],
+        &[0, 0, 0, 0],
+        &[0xff],
+        &[1, 2, 3, 4, 5, 6, 7, 8],
+    ] {
+        let mut extended = bytes.clone();
+        extended.extend_from_slice(extra);
+        assert_same_decision::<T>(&extended);
+    }
+}
+
+// integers decode lists in bulk, which has to agree with decoding them one at a time
+fn assert_packed_matches_items<T>(bytes: &[u8])
+where
+    T: SszbDecode + PartialEq + Debug,
+{
+    let items = bytes
+        .chunks(<T as SszbDecode>::SSZ_FIXED_LEN)
+        .map(<T as SszbDecode>::from_ssz_bytes)
+        .collect::<Result<Vec<T>, _>>();
+    match (T::ssz_read_packed(bytes), items) {
+        (Ok(packed), Ok(items)) => assert_eq!(packed, items),
+        (Err(_), Err(_)) => {}
+        (packed, items) => panic!(
+            "packed/item mismatch for {:?}: {:?}, {:?}",
+            bytes, packed, items
+        ),
+    }
+}
+
+fn b256() -> impl Strategy<Value = B256> {
+    any::<[u8; 32]>().prop_map(B256::from)
+}
+
+fn bit_list<N: typenum::Unsigned + Clone>() -> impl Strategy<Value = BitList<N>> {
+    vec(any::<bool>(), 0..=N::USIZE).prop_map(|bits| {
+        let mut bitlist = BitList::with_capacity(bits.len()).unwrap();
+        for (i, bit) in bits.into_iter().enumerate() {
+            bitlist.set(i, bit).unwrap();
+        }
+        bitlist
+    })
+}
+
+fn bit_vector<N: typenum::Unsigned + Clone>() -> impl Strategy<Value = BitVector<N>> {
+    vec(any::<bool>(), N::USIZE).prop_map(|bits| {
+        let mut bitvector = BitVector::new();
+        for (i, bit) in bits.into_iter().enumerate() {
+            bitvector.set(i, bit).unwrap();
+        }
+        bitvector
+    })
+}
+
+fn checkpoint() -> impl Strategy<Value = Checkpoint> {
+    (any::<u64>(), b256()).prop_map(|(epoch, root)| Checkpoint { epoch, root })
+}
+
+fn attestation() -> impl Strategy<Value = Attestation> {
+    (
+        bit_list::<U64>(),
+        any::<u64>(),
+        checkpoint(),
+        vec(any::<u64>(), 0..=16),
+        vec(any::<u8>(), 32),
+    )
+        .prop_map(
+            |(aggregation_bits, slot, target, indices, signature)| Attestation {
+                aggregation_bits,
+                slot,
+                target,
+                indices: VariableList::new(indices).unwrap(),
+                signature: FixedVector::new(signature).unwrap(),
+            },
+        )
+}
+
+fn block() -> impl Strategy<Value = Block> {
+    (
+        any::<u64>(),
+        vec(attestation(), 0..=4),
+        vec(any::<u8>(), 0..=32),
+    )
+        .prop_map(|(slot, attestations, graffiti)| Block {
+            slot,
+            attestations: VariableList::new(attestations).unwrap(),
+            graffiti: VariableList::new(graffiti).unwrap(),
+        })
+}
+
+proptest! {
+    #[test]
+    fn test_uints(a in any::<u8>(), b in any::<u16>(), c in any::<u32>(), d in any::<u64>()) {
+        assert_equivalent(&a);
+        assert_equivalent(&b);
+        assert_equivalent(&c);
+        assert_equivalent(&d);
+    }
+
+    #[test]
+    fn test_bool(b in any::<bool>()) {
+        assert_equivalent(&b);
+    }
+
+    #[test]
+    fn test_fixed_bytes(root in b256(), address in any::<[u8; 20]>(), n in any::<[u8; 32]>()) {
+        assert_equivalent(&root);
+        assert_equivalent(&Address::from(address));
+        assert_equivalent(&U256::from_le_bytes(n));
+    }
+
+    #[test]
+    fn test_bitfields(list in bit_list::<U64>(), vector in bit_vector::<U16>()) {
+        assert_equivalent(&list);
+        assert_equivalent(&vector);
+    }
+
+    #[test]
+    fn test_static_collections(list in vec(any::<u16>(), 0..=32), vector in vec(any::<u64>(), 8)) {
+        assert_equivalent(&VariableList::<u16, U32>::new(list).unwrap());
+        assert_equivalent(&FixedVector::<u64, U8>::new(vector).unwrap());
+    }
+
+    #[test]
+    fn test_packed_collections(
+        bytes in vec(any::<u8>(), 0..=64),
+        halves in vec(any::<u32>(), 0..=16),
+        words in vec(any::<u64>(), 0..=16),
+        wide in vec(any::<u128>(), 0..=4),
+        vector in vec(any::<u32>(), 8),
+    ) {
+        assert_equivalent(&VariableList::<u8, U64>::new(bytes.clone()).unwrap());
+        assert_equivalent(&VariableList::<u32, U16>::new(halves).unwrap());
+        assert_equivalent(&VariableList::<u64, U16>::new(words).unwrap());
+        assert_equivalent(&VariableList::<u128, U4>::new(wide).unwrap());
+        assert_equivalent(&FixedVector::<u32, U8>::new(vector).unwrap());
+
+        for len in 0..=bytes.len() {
+            assert_packed_matches_items::<u8>(&bytes[..len]);
+            assert_packed_matches_items::<u16>(&bytes[..len]);
+            assert_packed_matches_items::<u32>(&bytes[..len]);
+            assert_packed_matches_items::<u64>(&bytes[..len]);
+            assert_packed_matches_items::<u128>(&bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn test_variable_collections(
+        list in vec(vec(any::<u8>(), 0..=8), 0..=4),
+        vector in vec(vec(any::<u8>(), 0..=8), 4),
+    ) {
+        let list = list.into_iter().map(|l| VariableList::<u8, U8>::new(l).unwrap()).collect();
+        let vector = vector.into_iter().map(|l| VariableList::<u8, U8>::new(l).unwrap()).collect();
+        assert_equivalent(&VariableList::<_, U4>::new(list).unwrap());
+        assert_equivalent(&FixedVector::<_, U4>::new(vector).unwrap());
+    }
+
+    #[test]
+    fn test_containers(checkpoint in checkpoint(), attestation in attestation(), block in block()) {
+        assert_equivalent(&checkpoint);
+        assert_equivalent(&attestation);
+        assert_equivalent(&block);
+    }
+
+    #[test]
+    fn test_generic_containers(
+        slot in any::<u64>(),
+        payload in vec(any::<u16>(), 0..=8),
+        root in b256(),
+        graffiti in vec(any::<u8>(), 0..=32),
+    ) {
+        let payload = VariableList::<u16, U8>::new(payload).unwrap();
+        let graffiti = VariableList::<u8, U32>::new(graffiti).unwrap();
+        let concrete = ConcreteEnvelope {
+            slot,
+            payload: payload.clone(),
+            root,
+            graffiti: graffiti.clone(),
+        };
+        let generic = Envelope { slot, payload, root, graffiti };
+        assert_equivalent(&concrete);
+
+        let bytes = <ConcreteEnvelope as ssz::Encode>::as_ssz_bytes(&concrete);
+        assert_eq!(generic.sszb_bytes_len(), bytes.len());
+        assert_eq!(SszbEncode::to_ssz(&generic), bytes);
+        assert_eq!(<Envelope<_, _> as SszbDecode>::from_ssz_bytes(&bytes), Ok(generic));
+    }
+}
+
+#[test]
+fn test_known_divergences() {
+    for (ty, bytes, sszb_accepts) in KNOWN_DIVERGENCES {
+        let (ours, theirs) = match *ty {
+            "Block" => (
+                <Block as SszbDecode>::from_ssz_bytes(bytes).is_ok(),
+                <Block as ssz::Decode>::from_ssz_bytes(bytes).is_ok(),
+            ),
+            _ => panic!("no dispatch for {}", ty),
+        };
+        assert_eq!(ours, *sszb_accepts, "{} on {:?}", ty, bytes);
+        assert_ne!(ours, theirs, "{} on {:?} no longer diverges", ty, bytes);
+    }
+}
+
+#[test]
+fn test_container_first_offset_must_follow_fixed_portion() {
+    // ethereum_ssz rejects an offset that skips over variable bytes, and so must we
+    let block = Block {
+        slot: 1,
+        attestations: VariableList::empty(),
+        graffiti: VariableList::new(vec![1, 2, 3]).unwrap(),
+    };
+    let mut bytes = SszbEncode::to_ssz(&block);
+    // shift both offsets by one, so the attestations stay empty and the graffiti would be read
+    // from the wrong position if the first offset weren't checked
+    bytes[8] += 1;
+    bytes[12] += 1;
+    assert!(<Block as SszbDecode>::from_ssz_bytes(&bytes).is_err());
+    assert_same_decision::<Block>(&bytes);
+}
@@ -0,0 +1,271 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::dynamic::{decode_dynamic, encode_dynamic, SszTypeInfo, SszValue, SszbTypeInfo};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode, SszbTypeInfo};
+use typenum::{U12, U16, U2, U32, U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, SszbTypeInfo)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, SszbTypeInfo)]
+enum Payload {
+    Empty,
+    Number(u16),
+    Pair {
+        tag: u8,
+        values: VariableList<u32, U4>,
+    },
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode, SszbTypeInfo)]
+struct Block {
+    slot: u64,
+    finalized: bool,
+    checkpoint: Checkpoint,
+    extra_data: VariableList<u8, U32>,
+    checkpoints: VariableList<Checkpoint, U8>,
+    names: VariableList<VariableList<u8, U16>, U4>,
+    votes: BitList<U16>,
+    committee: BitVector<U12>,
+    balances: FixedVector<u128, U2>,
+    payload: Payload,
+    #[ssz(skip_encode, skip_decode)]
+    cached_root: u64,
+}
+
+fn checkpoint(epoch: u64) -> Checkpoint {
+    Checkpoint {
+        epoch,
+        root: [epoch as u8; 32],
+    }
+}
+
+fn block() -> Block {
+    let mut votes = BitList::with_capacity(10).unwrap();
+    votes.set(1, true).unwrap();
+    votes.set(9, true).unwrap();
+    let mut committee = BitVector::new();
+    committee.set(0, true).unwrap();
+    committee.set(11, true).unwrap();
+
+    Block {
+        slot: 42,
+        finalized: true,
+        checkpoint: checkpoint(3),
+        extra_data: VariableList::new(b"graffiti".to_vec()).unwrap(),
+        checkpoints: VariableList::new(vec![checkpoint(1), checkpoint(2)]).unwrap(),
+        names: VariableList::new(vec![
+            VariableList::new(b"alice".to_vec()).unwrap(),
+            VariableList::new(vec![]).unwrap(),
+            VariableList::new(b"bob".to_vec()).unwrap(),
+        ])
+        .unwrap(),
+        votes,
+        committee,
+        balances: FixedVector::new(vec![1, u128::MAX]).unwrap(),
+        payload: Payload::Pair {
+            tag: 7,
+            values: VariableList::new(vec![10, 20]).unwrap(),
+        },
+        cached_root: 0,
+    }
+}
+
+fn checkpoint_value(epoch: u64) -> SszValue {
+    SszValue::Container(vec![
+        ("epoch".into(), SszValue::U64(epoch)),
+        ("root".into(), SszValue::Bytes(vec![epoch as u8; 32])),
+    ])
+}
+
+#[test]
+fn test_derived_type_info() {
+    assert_eq!(
+        Checkpoint::ssz_type_info(),
+        SszTypeInfo::Container(vec![
+            ("epoch".into(), SszTypeInfo::Uint(8)),
+            ("root".into(), SszTypeInfo::vector(SszTypeInfo::Uint(1), 32)),
+        ])
+    );
+    assert_eq!(
+        Payload::ssz_type_info(),
+        SszTypeInfo::Union(vec![
+            None,
+            Some(SszTypeInfo::Uint(2)),
+            Some(SszTypeInfo::Container(vec![
+                ("tag".into(), SszTypeInfo::Uint(1)),
+                ("values".into(), SszTypeInfo::list(SszTypeInfo::Uint(4), 4)),
+            ])),
+        ])
+    );
+
+    // the skipped field isn't part of the layout
+    let SszTypeInfo::Container(fields) = Block::ssz_type_info() else {
+        panic!("a struct is a container");
+    };
+    assert_eq!(fields.len(), 10);
+    assert!(fields.iter().all(|(name, _)| name != "cached_root"));
+
+    let schema = Block::ssz_type_info();
+    assert!(!schema.is_static());
+    assert!(Checkpoint::ssz_type_info().is_static());
+    assert_eq!(
+        Checkpoint::ssz_type_info().fixed_len(),
+        <Checkpoint as SszbDecode>::SSZ_FIXED_LEN
+    );
+}
+
+#[test]
+fn test_decode_matches_static_decode() {
+    let block = block();
+    let bytes = block.to_ssz();
+    let decoded = Block::from_ssz_bytes(&bytes).unwrap();
+    let value = decode_dynamic(&Block::ssz_type_info(), &bytes).unwrap();
+
+    assert_eq!(value.field("slot"), Some(&SszValue::U64(decoded.slot)));
+    assert_eq!(
+        value.field("finalized"),
+        Some(&SszValue::Bool(decoded.finalized))
+    );
+    assert_eq!(value.field("checkpoint"), Some(&checkpoint_value(3)));
+    assert_eq!(
+        value.field("extra_data"),
+        Some(&SszValue::Bytes(decoded.extra_data.to_vec()))
+    );
+    assert_eq!(
+        value.field("checkpoints"),
+        Some(&SszValue::List(vec![
+            checkpoint_value(1),
+            checkpoint_value(2)
+        ]))
+    );
+    assert_eq!(
+        value.field("names"),
+        Some(&SszValue::List(
+            decoded
+                .names
+                .iter()
+                .map(|name| SszValue::Bytes(name.to_vec()))
+                .collect()
+        ))
+    );
+    assert_eq!(
+        value.field("votes"),
+        Some(&SszValue::Bitlist(decoded.votes.iter().collect()))
+    );
+    assert_eq!(
+        value.field("committee"),
+        Some(&SszValue::Bitvector(decoded.committee.iter().collect()))
+    );
+    assert_eq!(
+        value.field("balances"),
+        Some(&SszValue::Vector(vec![
+            SszValue::U128(1),
+            SszValue::U128(u128::MAX)
+        ]))
+    );
+    assert_eq!(
+        value.field("payload"),
+        Some(&SszValue::Union {
+            selector: 2,
+            value: Some(Box::new(SszValue::Container(vec![
+                ("tag".into(), SszValue::U8(7)),
+                (
+                    "values".into(),
+                    SszValue::List(vec![SszValue::U32(10), SszValue::U32(20)])
+                ),
+            ]))),
+        })
+    );
+    assert_eq!(value.field("cached_root"), None);
+}
+
+#[test]
+fn test_encode_roundtrip() {
+    let schema = Block::ssz_type_info();
+    for payload in [
+        Payload::Empty,
+        Payload::Number(9),
+        Payload::Pair {
+            tag: 1,
+            values: VariableList::new(vec![]).unwrap(),
+        },
+    ] {
+        let block = Block { payload, ..block() };
+        let bytes = block.to_ssz();
+        let value = decode_dynamic(&schema, &bytes).unwrap();
+
+        let mut reencoded = vec![];
+        encode_dynamic(&schema, &value, &mut reencoded).unwrap();
+        assert_eq!(reencoded, bytes);
+    }
+}
+
+#[test]
+fn test_errors_match_typed_decode() {
+    let schema = Block::ssz_type_info();
+    let bytes = block().to_ssz();
+
+    // every truncation fails the same way as validating, which decoding agrees with, at the
+    // same position
+    for len in 0..bytes.len() {
+        assert_eq!(
+            decode_dynamic(&schema, &bytes[..len]).err(),
+            sszb::validate_ssz::<Block>(&bytes[..len]).err(),
+            "truncated to {} bytes",
+            len
+        );
+    }
+
+    // `finalized` is the byte right after the slot
+    let mut invalid_bool = bytes.clone();
+    invalid_bool[8] = 2;
+    let err = decode_dynamic(&schema, &invalid_bool).unwrap_err();
+    assert_eq!(err.root_cause(), &DecodeError::InvalidBool(2));
+    assert_eq!(Some(err), sszb::validate_ssz::<Block>(&invalid_bool).err());
+
+    // the first offset, of `extra_data`, pointing into the fixed portion
+    let mut bad_offset = bytes.clone();
+    bad_offset[49] = 0;
+    assert_eq!(
+        decode_dynamic(&schema, &bad_offset).err(),
+        sszb::validate_ssz::<Block>(&bad_offset).err()
+    );
+}
+
+#[test]
+fn test_encode_rejects_mismatched_values() {
+    let schema = Checkpoint::ssz_type_info();
+    let mut buf = vec![];
+
+    // the root is one byte short
+    let short_root = SszValue::Container(vec![
+        ("epoch".into(), SszValue::U64(1)),
+        ("root".into(), SszValue::Bytes(vec![0; 31])),
+    ]);
+    assert!(encode_dynamic(&schema, &short_root, &mut buf).is_err());
+
+    // the fields are in the wrong order
+    let swapped = SszValue::Container(vec![
+        ("root".into(), SszValue::Bytes(vec![0; 32])),
+        ("epoch".into(), SszValue::U64(1)),
+    ]);
+    assert!(encode_dynamic(&schema, &swapped, &mut buf).is_err());
+
+    let too_long = SszValue::List(vec![SszValue::U32(0); 5]);
+    assert!(encode_dynamic(
+        &SszTypeInfo::list(SszTypeInfo::Uint(4), 4),
+        &too_long,
+        &mut buf
+    )
+    .is_err());
+
+    // nothing was written for the values that didn't fit
+    assert!(buf.is_empty());
+}
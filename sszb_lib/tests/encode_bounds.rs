@@ -0,0 +1,71 @@
+#![cfg(feature = "testing")]
+
+// the fallible encoders refuse a list past its maximum, wherever it's nested, rather than write
+// bytes that no decoder accepts
+
+use bytes::buf::BufMut;
+use ssz_types::VariableList;
+use sszb::runtime::RuntimeList;
+use sszb::{EncodeError, SszbEncode};
+use sszb_derive::SszbEncode;
+use typenum::U4;
+
+#[derive(SszbEncode)]
+struct Committee {
+    epoch: u64,
+    members: RuntimeList<u64>,
+}
+
+#[derive(SszbEncode)]
+enum Message {
+    Committee(Committee),
+    Epoch(u64),
+}
+
+fn members(len: usize) -> RuntimeList<u64> {
+    RuntimeList::new_unchecked((0..len as u64).collect(), 2)
+}
+
+const TOO_LONG: EncodeError = EncodeError::ListTooLong { len: 3, max: 2 };
+
+#[test]
+fn test_list() {
+    assert_eq!(members(2).ssz_check_bounds(), Ok(()));
+    assert_eq!(members(2).try_to_ssz(), Ok(members(2).to_ssz()));
+
+    assert_eq!(members(3).ssz_check_bounds(), Err(TOO_LONG));
+    assert_eq!(members(3).try_to_ssz(), Err(TOO_LONG));
+
+    // nothing is written
+    let mut buf = vec![];
+    assert_eq!(members(3).try_ssz_write(&mut buf), Err(TOO_LONG));
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_nested() {
+    let committee = |len| Committee {
+        epoch: 1,
+        members: members(len),
+    };
+    assert!(committee(2).try_to_ssz().is_ok());
+    assert_eq!(committee(3).try_to_ssz(), Err(TOO_LONG));
+
+    assert!(Message::Committee(committee(2)).try_to_ssz().is_ok());
+    assert_eq!(Message::Committee(committee(3)).try_to_ssz(), Err(TOO_LONG));
+    assert!(Message::Epoch(1).try_to_ssz().is_ok());
+
+    // the items of a list are checked too, not only its own length
+    let lists = VariableList::<_, U4>::new(vec![members(1), members(3)]).unwrap();
+    assert_eq!(lists.try_to_ssz(), Err(TOO_LONG));
+    assert_eq!(Box::new(committee(3)).try_to_ssz(), Err(TOO_LONG));
+}
+
+// the infallible encoders don't check, but debug builds catch the list
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "more than its maximum of 2")]
+fn test_ssz_write_asserts() {
+    let mut buf = vec![];
+    members(3).ssz_write(&mut buf);
+}
@@ -0,0 +1,146 @@
+#![cfg(all(feature = "ssz-types", feature = "milhouse"))]
+
+use bytes::buf::{Buf, BufMut};
+use milhouse::List;
+use ssz_types::{FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U32, U4, U8};
+
+type Payload = VariableList<u8, U32>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Inner {
+    id: u64,
+    payloads: VariableList<Payload, U4>,
+    tag: [u8; 4],
+    notes: Payload,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Outer {
+    slot: u64,
+    inners: VariableList<Inner, U4>,
+    persistent: List<Payload, U8>,
+    fixed: FixedVector<Payload, U4>,
+    root: [u8; 32],
+}
+
+fn payload(len: usize) -> Payload {
+    VariableList::new((0..len).map(|i| i as u8).collect()).unwrap()
+}
+
+fn inner(id: u64) -> Inner {
+    Inner {
+        id,
+        payloads: VariableList::new((0..id as usize).map(payload).collect()).unwrap(),
+        tag: [id as u8; 4],
+        notes: payload(3 * id as usize),
+    }
+}
+
+fn outer(num_inners: u64) -> Outer {
+    Outer {
+        slot: 99,
+        inners: VariableList::new((0..num_inners).map(inner).collect()).unwrap(),
+        persistent: List::new((0..5).map(|i| payload(i * 2)).collect()).unwrap(),
+        fixed: FixedVector::new((0..4).map(payload).collect()).unwrap(),
+        root: [7; 32],
+    }
+}
+
+// ssz_write measures each variable part as it goes, rather than from a record made up front
+fn written(item: &impl SszbEncode) -> Vec<u8> {
+    let mut buf = vec![0u8; item.sszb_bytes_len()];
+    item.ssz_write(&mut buf.as_mut_slice());
+    buf
+}
+
+#[test]
+fn test_cached_lengths_match_ssz_write() {
+    for num_inners in 0..=4 {
+        let outer = outer(num_inners);
+        let bytes = outer.to_ssz();
+        assert_eq!(bytes, written(&outer));
+
+        let mut lens = Vec::new();
+        assert_eq!(outer.ssz_record_lens(&mut lens), bytes.len());
+
+        let mut buf = vec![1, 2, 3];
+        outer.to_ssz_with_vec(&mut buf);
+        assert_eq!(&buf[3..], &bytes[..]);
+
+        assert_eq!(Outer::from_ssz_bytes(&bytes), Ok(outer));
+    }
+}
+
+#[test]
+fn test_nested_items_match_standalone() {
+    let outer = outer(4);
+    for inner in outer.inners.iter() {
+        assert_eq!(inner.to_ssz(), written(inner));
+    }
+    assert_eq!(outer.inners.to_ssz(), written(&outer.inners));
+    assert_eq!(outer.persistent.to_ssz(), written(&outer.persistent));
+    assert_eq!(outer.fixed.to_ssz(), written(&outer.fixed));
+}
+
+// ssz_write_slice writes each variable part once and back-patches its offset
+fn written_in_one_pass(item: &impl SszbEncode) -> Vec<u8> {
+    // trailing room that has to be left alone
+    let mut buf = vec![0xaa; item.sszb_bytes_len() + 3];
+    let len = item.ssz_write_slice(&mut buf);
+    assert_eq!(len, item.sszb_bytes_len());
+    assert_eq!(&buf[len..], [0xaa; 3]);
+    buf.truncate(len);
+    buf
+}
+
+#[test]
+fn test_single_pass_matches_ssz_write() {
+    for num_inners in 0..=4 {
+        let outer = outer(num_inners);
+        assert_eq!(written_in_one_pass(&outer), written(&outer));
+        for inner in outer.inners.iter() {
+            assert_eq!(written_in_one_pass(inner), written(inner));
+        }
+        assert_eq!(written_in_one_pass(&outer.inners), written(&outer.inners));
+        assert_eq!(
+            written_in_one_pass(&outer.persistent),
+            written(&outer.persistent)
+        );
+        assert_eq!(written_in_one_pass(&outer.fixed), written(&outer.fixed));
+        assert_eq!(written_in_one_pass(&outer.root), written(&outer.root));
+    }
+}
+
+// encode_to_uninit writes into spare capacity that was never zeroed
+fn written_uninit(item: &impl SszbEncode) -> Vec<u8> {
+    let len = item.sszb_bytes_len();
+    let mut buf = Vec::with_capacity(len + 3);
+    let written = item.encode_to_uninit(buf.spare_capacity_mut()).unwrap();
+    assert_eq!(written.len(), len);
+    written.to_vec()
+}
+
+#[test]
+fn test_uninit_matches_to_ssz() {
+    for num_inners in 0..=4 {
+        let outer = outer(num_inners);
+        assert_eq!(written_uninit(&outer), outer.to_ssz());
+        assert_eq!(written_uninit(&outer.inners), outer.inners.to_ssz());
+        assert_eq!(written_uninit(&outer.persistent), outer.persistent.to_ssz());
+        assert_eq!(written_uninit(&outer.root), outer.root.to_ssz());
+    }
+
+    let outer = outer(2);
+    let len = outer.sszb_bytes_len();
+    let mut buf = Vec::<u8>::with_capacity(len - 1);
+    assert_eq!(
+        outer.encode_to_uninit(&mut buf.spare_capacity_mut()[..len - 1]),
+        Err(sszb::EncodeError::BufferTooSmall {
+            len: len - 1,
+            expected: len
+        })
+    );
+}
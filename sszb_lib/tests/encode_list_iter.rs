@@ -0,0 +1,103 @@
+#![cfg(feature = "ssz-types")]
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+
+use ssz_types::VariableList;
+use sszb::{encode_list_iter, list_iter_to_ssz, SszbEncode};
+use typenum::{U1024, U16};
+
+type Item = VariableList<u8, U16>;
+type Items = VariableList<Item, U1024>;
+
+fn item(i: usize) -> Item {
+    VariableList::new(vec![i as u8; i % 7]).unwrap()
+}
+
+fn encode<T: SszbEncode>(
+    items: impl ExactSizeIterator<Item = impl Borrow<T>> + Clone,
+) -> (Vec<u8>, Vec<u8>) {
+    let mut buf = Vec::new();
+    encode_list_iter::<T>(items.clone(), &mut buf);
+    (buf, list_iter_to_ssz::<T>(items))
+}
+
+#[test]
+fn test_static_items() {
+    let list = VariableList::<u64, U1024>::new((0..500).map(|i| i * 3).collect()).unwrap();
+    let expected = list.to_ssz();
+
+    let (written, vec) = encode::<u64>((0..500u64).map(|i| i * 3));
+    assert_eq!(written, expected);
+    assert_eq!(vec, expected);
+
+    // borrowed from a collection
+    let (written, vec) = encode::<u64>(list.iter());
+    assert_eq!(written, expected);
+    assert_eq!(vec, expected);
+}
+
+#[test]
+fn test_variable_items() {
+    let list = Items::new((0..100).map(item).collect()).unwrap();
+    let expected = list.to_ssz();
+
+    let (written, vec) = encode::<Item>((0..100).map(item));
+    assert_eq!(written, expected);
+    assert_eq!(vec, expected);
+
+    // the values of a map, as a response handler would hold them
+    let map: BTreeMap<usize, Item> = (0..100).map(|i| (i, item(i))).collect();
+    let (written, vec) = encode::<Item>(map.values());
+    assert_eq!(written, expected);
+    assert_eq!(vec, expected);
+}
+
+#[test]
+fn test_nested_variable_items() {
+    let list: VariableList<Items, U16> = VariableList::new(
+        (0..5)
+            .map(|n| Items::new((0..n * 3).map(item).collect()).unwrap())
+            .collect(),
+    )
+    .unwrap();
+    let (written, vec) = encode::<Items>(list.iter());
+    assert_eq!(written, list.to_ssz());
+    assert_eq!(vec, list.to_ssz());
+}
+
+#[test]
+fn test_empty_iterators() {
+    assert_eq!(encode::<u32>(core::iter::empty::<u32>()), (vec![], vec![]));
+    assert_eq!(
+        encode::<Item>(core::iter::empty::<Item>()),
+        (vec![], vec![])
+    );
+    assert_eq!(Items::new(vec![]).unwrap().to_ssz(), Vec::<u8>::new());
+}
+
+// claims one element fewer than it yields
+#[derive(Clone)]
+struct Miscounted(std::ops::Range<usize>);
+
+impl Iterator for Miscounted {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        self.0.next().map(item)
+    }
+}
+
+impl ExactSizeIterator for Miscounted {
+    fn len(&self) -> usize {
+        self.0.len() - 1
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "the iterator yielded a different number of elements than its len() of 2"
+)]
+fn test_miscounted_iterator() {
+    list_iter_to_ssz::<Item>(Miscounted(0..3));
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{EncoderPool, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::collections::HashSet;
+use typenum::U256;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Message {
+    id: u64,
+    payload: VariableList<u8, U256>,
+    root: [u8; 32],
+}
+
+fn message(id: u64) -> Message {
+    Message {
+        id,
+        payload: VariableList::new((0..id % 200).map(|i| i as u8).collect()).unwrap(),
+        root: [id as u8; 32],
+    }
+}
+
+#[test]
+fn test_pool_matches_to_ssz() {
+    let pool = EncoderPool::new();
+    // hold on to every result, so each encode has to write past the earlier ones
+    let encoded = (0..100)
+        .map(|id| pool.encode(&message(id)))
+        .collect::<Vec<_>>();
+    for (id, bytes) in encoded.iter().enumerate() {
+        let message = message(id as u64);
+        assert_eq!(&bytes[..], &message.to_ssz()[..]);
+        assert_eq!(Message::from_ssz_bytes(bytes), Ok(message));
+    }
+}
+
+#[test]
+fn test_pool_reuses_allocation() {
+    let pool = EncoderPool::new();
+    let message = message(150);
+    let mut allocations = HashSet::new();
+    for _ in 0..1000 {
+        // each result is dropped before the next encode, so the buffer can be reclaimed
+        let bytes = pool.encode(&message);
+        assert_eq!(&bytes[..], &message.to_ssz()[..]);
+        allocations.insert(bytes.as_ptr());
+    }
+    assert!(allocations.len() <= 2, "{} allocations", allocations.len());
+}
+
+#[test]
+fn test_pool_shared_across_threads() {
+    let pool = EncoderPool::new();
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let pool = pool.clone();
+            scope.spawn(move || {
+                for id in (thread..200).step_by(4) {
+                    let message = message(id);
+                    assert_eq!(&pool.encode(&message)[..], &message.to_ssz()[..]);
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,190 @@
+#![cfg(all(feature = "era", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::era::{
+    E2StoreReader, E2StoreWriter, EraError, COMPRESSED_BEACON_STATE,
+    COMPRESSED_SIGNED_BEACON_BLOCK, HEADER_LEN, SLOT_INDEX, VERSION,
+};
+use sszb::SszbEncode;
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U1024, U64, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    parent_root: [u8; 32],
+    body: VariableList<u8, U1024>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct State {
+    slot: u64,
+    block_roots: VariableList<[u8; 32], U8>,
+}
+
+fn block(slot: u64) -> Block {
+    Block {
+        slot,
+        parent_root: [slot as u8; 32],
+        body: VariableList::new(vec![7; 300]).unwrap(),
+    }
+}
+
+fn state() -> State {
+    State {
+        slot: 2,
+        block_roots: VariableList::new(vec![[1; 32], [2; 32]]).unwrap(),
+    }
+}
+
+type Slots = VariableList<u64, U64>;
+
+fn slots() -> Slots {
+    VariableList::new(vec![1, 2]).unwrap()
+}
+
+// a version, two blocks, a state and an index of the blocks' slots
+fn era_file() -> Vec<u8> {
+    let mut writer = E2StoreWriter::new(Vec::new()).unwrap();
+    writer
+        .write_compressed_entry(COMPRESSED_SIGNED_BEACON_BLOCK, &block(1))
+        .unwrap();
+    writer
+        .write_compressed_entry(COMPRESSED_SIGNED_BEACON_BLOCK, &block(2))
+        .unwrap();
+    writer
+        .write_compressed_entry(COMPRESSED_BEACON_STATE, &state())
+        .unwrap();
+    writer.write_entry(SLOT_INDEX, &slots()).unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn test_era_roundtrip() {
+    let bytes = era_file();
+    // the version entry is a bare header
+    assert_eq!(bytes[..2], VERSION);
+    assert_eq!(bytes[2..HEADER_LEN], [0; 6]);
+
+    let mut reader = E2StoreReader::new(bytes.as_slice()).unwrap();
+    for slot in [1, 2] {
+        assert_eq!(
+            reader
+                .read_compressed_entry::<Block>(COMPRESSED_SIGNED_BEACON_BLOCK)
+                .unwrap(),
+            Some(block(slot))
+        );
+    }
+    assert_eq!(
+        reader
+            .read_compressed_entry::<State>(COMPRESSED_BEACON_STATE)
+            .unwrap(),
+        Some(state())
+    );
+    assert_eq!(
+        reader.read_entry::<Slots>(SLOT_INDEX).unwrap(),
+        Some(slots())
+    );
+    assert!(reader.next_entry().unwrap().is_none());
+
+    // the entries can also be iterated over raw, and the length fields add up
+    let entries = E2StoreReader::new(bytes.as_slice())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let tags = entries.iter().map(|(tag, _)| *tag).collect::<Vec<_>>();
+    assert_eq!(
+        tags,
+        [
+            COMPRESSED_SIGNED_BEACON_BLOCK,
+            COMPRESSED_SIGNED_BEACON_BLOCK,
+            COMPRESSED_BEACON_STATE,
+            SLOT_INDEX
+        ]
+    );
+    let len: usize = entries
+        .iter()
+        .map(|(_, data)| HEADER_LEN + data.len())
+        .sum();
+    assert_eq!(HEADER_LEN + len, bytes.len());
+    assert_eq!(entries[3].1, slots().to_ssz());
+}
+
+#[test]
+fn test_malformed_era_files() {
+    let bytes = era_file();
+
+    let mut corrupted = bytes.clone();
+    corrupted[0] = 0;
+    assert!(matches!(
+        E2StoreReader::new(corrupted.as_slice()),
+        Err(EraError::MissingVersion([0, 0x32]))
+    ));
+
+    assert!(matches!(
+        E2StoreReader::new(&bytes[..5]),
+        Err(EraError::TruncatedHeader { len: 5 })
+    ));
+
+    let mut reader = E2StoreReader::new(&bytes[..HEADER_LEN + 4]).unwrap();
+    assert!(matches!(
+        reader.next_entry(),
+        Err(EraError::TruncatedHeader { len: 4 })
+    ));
+
+    // the first block's length claims more than the file holds
+    let mut corrupted = bytes.clone();
+    corrupted[HEADER_LEN + 2..HEADER_LEN + 6].copy_from_slice(&u32::MAX.to_le_bytes());
+    let mut reader = E2StoreReader::new(corrupted.as_slice()).unwrap();
+    assert!(matches!(
+        reader.next_entry(),
+        Err(EraError::TruncatedEntry { expected, .. }) if expected == u32::MAX as usize
+    ));
+
+    let mut corrupted = bytes.clone();
+    corrupted[HEADER_LEN + 7] = 1;
+    let mut reader = E2StoreReader::new(corrupted.as_slice()).unwrap();
+    assert!(matches!(
+        reader.next_entry(),
+        Err(EraError::ReservedBytesSet([0, 1]))
+    ));
+
+    let mut reader = E2StoreReader::new(bytes.as_slice()).unwrap();
+    assert!(matches!(
+        reader.read_compressed_entry::<State>(COMPRESSED_BEACON_STATE),
+        Err(EraError::UnexpectedType {
+            found: COMPRESSED_SIGNED_BEACON_BLOCK,
+            expected: COMPRESSED_BEACON_STATE
+        })
+    ));
+
+    // a block entry that isn't snappy framing
+    let mut writer = E2StoreWriter::new(Vec::new()).unwrap();
+    writer
+        .write_raw_entry(COMPRESSED_SIGNED_BEACON_BLOCK, b"not snappy")
+        .unwrap();
+    let bytes = writer.into_inner();
+    let mut reader = E2StoreReader::new(bytes.as_slice()).unwrap();
+    assert!(matches!(
+        reader.read_compressed_entry::<Block>(COMPRESSED_SIGNED_BEACON_BLOCK),
+        Err(EraError::InvalidCompression(_))
+    ));
+
+    // a state that decompresses to more than a state can hold
+    let mut writer = E2StoreWriter::new(Vec::new()).unwrap();
+    writer
+        .write_compressed_entry(COMPRESSED_BEACON_STATE, &block(3))
+        .unwrap();
+    let bytes = writer.into_inner();
+    let mut reader = E2StoreReader::new(bytes.as_slice()).unwrap();
+    assert!(matches!(
+        reader.read_compressed_entry::<State>(COMPRESSED_BEACON_STATE),
+        Err(EraError::EntryTooLarge { .. })
+    ));
+
+    assert!(matches!(
+        E2StoreReader::new(&[0u8; 0][..]),
+        Err(EraError::TruncatedHeader { len: 0 })
+    ));
+}
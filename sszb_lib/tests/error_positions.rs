@@ -0,0 +1,109 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U32, U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Vote {
+    validator: u64,
+    approve: bool,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Ballot {
+    round: u64,
+    votes: VariableList<Vote, U8>,
+    memo: VariableList<u8, U32>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Ledger {
+    epoch: u64,
+    ballots: VariableList<Ballot, U4>,
+}
+
+// ledger fixed portion: epoch (8) + ballots offset (4)
+const BALLOTS_START: usize = 12;
+// ballot fixed portion: round (8) + votes offset (4) + memo offset (4)
+const VOTES_START: usize = 16;
+const MEMO_OFFSET_AT: usize = 12;
+const VOTE_LEN: usize = 9;
+
+fn ledger() -> Ledger {
+    let ballot = |round: u64| Ballot {
+        round,
+        votes: VariableList::new(
+            (0..3)
+                .map(|validator| Vote {
+                    validator,
+                    approve: validator % 2 == 0,
+                })
+                .collect(),
+        )
+        .unwrap(),
+        memo: VariableList::new(vec![7; round as usize]).unwrap(),
+    };
+    Ledger {
+        epoch: 5,
+        ballots: VariableList::new(vec![ballot(1), ballot(2), ballot(3)]).unwrap(),
+    }
+}
+
+fn ballot_start(bytes: &[u8], ballot: usize) -> usize {
+    let at = BALLOTS_START + ballot * sszb::BYTES_PER_LENGTH_OFFSET;
+    BALLOTS_START + sszb::offsets::read_offset(&bytes[at..]).unwrap()
+}
+
+#[test]
+fn test_invalid_element_position() {
+    let mut bytes = ledger().to_ssz();
+    // turn the `approve` flag of the third vote in the second ballot into a 2
+    let vote_start = ballot_start(&bytes, 1) + VOTES_START + 2 * VOTE_LEN;
+    bytes[vote_start + 8] = 2;
+
+    let err = Ledger::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(err.root_cause(), &DecodeError::InvalidBool(2));
+    assert!((vote_start..vote_start + VOTE_LEN).contains(&err.position().unwrap()));
+    assert_eq!(err.position(), Some(vote_start + 8));
+}
+
+#[test]
+fn test_invalid_field_offset_position() {
+    let mut bytes = ledger().to_ssz();
+    // point the memo of the third ballot past the end of the input, which makes the votes
+    // before it longer than the ballot itself
+    let offset_at = ballot_start(&bytes, 2) + MEMO_OFFSET_AT;
+    bytes[offset_at + 3] = 0xff;
+
+    let err = Ledger::from_ssz_bytes(&bytes).unwrap_err();
+    assert!(matches!(
+        err.root_cause(),
+        DecodeError::InvalidByteLength { .. }
+    ));
+    assert_eq!(err.position(), Some(offset_at));
+}
+
+#[test]
+fn test_decreasing_list_offset_position() {
+    let mut bytes = ledger().to_ssz();
+    // make the third ballot start before the second one
+    let offset_at = BALLOTS_START + 2 * sszb::BYTES_PER_LENGTH_OFFSET;
+    bytes[offset_at..offset_at + 4].copy_from_slice(&12u32.to_le_bytes());
+
+    let err = Ledger::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        err,
+        DecodeError::OffsetsAreDecreasing(12).at_byte(offset_at)
+    );
+}
+
+#[test]
+fn test_at_byte_accumulates() {
+    let err = DecodeError::ZeroLengthItem.at_byte(3).at_byte(10);
+    assert_eq!(err.position(), Some(13));
+    assert_eq!(err.root_cause(), &DecodeError::ZeroLengthItem);
+    assert_eq!(DecodeError::ZeroLengthItem.position(), None);
+}
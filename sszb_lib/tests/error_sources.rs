@@ -0,0 +1,50 @@
+#![cfg(feature = "milhouse")]
+
+use milhouse::List;
+use sszb::{ssz_decode_variable_length_items, DecodeError};
+use std::error::Error;
+use typenum::U2;
+
+// three items for a list of at most two, built without the length check that decoding a list
+// does first, so that it's milhouse that refuses them
+fn overflow_milhouse_list() -> DecodeError {
+    let mut bytes = Vec::new();
+    for offset in [12u32, 20, 28] {
+        bytes.extend_from_slice(&offset.to_le_bytes());
+    }
+    for item in [1u64, 2, 3] {
+        bytes.extend_from_slice(&item.to_le_bytes());
+    }
+    let (offsets, mut items) = bytes.split_at(12);
+    ssz_decode_variable_length_items::<u64, List<u64, U2>>(offsets, &mut items).unwrap_err()
+}
+
+#[test]
+fn test_milhouse_error_is_the_source() {
+    let error = overflow_milhouse_list();
+    assert!(matches!(error, DecodeError::Milhouse(_)), "{:?}", error);
+    assert!(error
+        .source()
+        .and_then(|source| source.downcast_ref::<milhouse::Error>())
+        .is_some());
+
+    // the error survives a trip through anyhow, context and all
+    let report = anyhow::Error::new(error.clone()).context("decoding the validator registry");
+    assert_eq!(report.downcast_ref::<DecodeError>(), Some(&error));
+    assert!(report
+        .chain()
+        .any(|cause| cause.downcast_ref::<milhouse::Error>().is_some()));
+}
+
+#[test]
+fn test_positions_chain_to_the_error() {
+    let error = overflow_milhouse_list().at_byte(40);
+    let report = anyhow::Error::new(error);
+    let causes = report.chain().collect::<Vec<_>>();
+    assert_eq!(causes.len(), 3);
+    assert!(matches!(
+        causes[1].downcast_ref::<DecodeError>(),
+        Some(DecodeError::Milhouse(_))
+    ));
+    assert!(causes[2].downcast_ref::<milhouse::Error>().is_some());
+}
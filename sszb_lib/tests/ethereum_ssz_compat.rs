@@ -0,0 +1,110 @@
+#![cfg(all(feature = "ethereum-ssz", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U16, U4};
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(compat = "ethereum_ssz")]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(compat = "ethereum_ssz")]
+struct Attestation {
+    slot: u64,
+    target: Checkpoint,
+    indices: VariableList<u64, U16>,
+}
+
+// a type that's still derived with ssz_derive, holding the migrated ones
+#[derive(Clone, PartialEq, Debug, ssz_derive::Encode, ssz_derive::Decode)]
+struct Block {
+    slot: u64,
+    checkpoint: Checkpoint,
+    attestations: VariableList<Attestation, U4>,
+}
+
+fn checkpoint(epoch: u64) -> Checkpoint {
+    Checkpoint {
+        epoch,
+        root: [epoch as u8; 32],
+    }
+}
+
+fn attestation(slot: u64, num_indices: u64) -> Attestation {
+    Attestation {
+        slot,
+        target: checkpoint(slot / 32),
+        indices: VariableList::new((0..num_indices).collect()).unwrap(),
+    }
+}
+
+// both trait surfaces have to agree on the bytes and on each other's output
+fn assert_compatible<T>(value: T)
+where
+    T: SszbEncode + SszbDecode + ssz::Encode + ssz::Decode + PartialEq + Debug,
+{
+    let bytes = <T as SszbEncode>::to_ssz(&value);
+    assert_eq!(ssz::Encode::as_ssz_bytes(&value), bytes);
+    assert_eq!(ssz::Encode::ssz_bytes_len(&value), bytes.len());
+    assert_eq!(
+        <T as ssz::Encode>::is_ssz_fixed_len(),
+        <T as SszbEncode>::SSZ_STATIC
+    );
+    assert_eq!(
+        <T as ssz::Decode>::ssz_fixed_len(),
+        <T as SszbDecode>::SSZ_FIXED_LEN
+    );
+    assert_eq!(<T as ssz::Decode>::from_ssz_bytes(&bytes).unwrap(), value);
+    assert_eq!(<T as SszbDecode>::from_ssz_bytes(&bytes).unwrap(), value);
+}
+
+#[test]
+fn test_both_trait_surfaces_agree() {
+    assert_compatible(checkpoint(7));
+    assert_compatible(attestation(100, 5));
+    assert_compatible(attestation(0, 0));
+
+    // nested in a container that only ethereum_ssz knows how to encode
+    let block = Block {
+        slot: 9,
+        checkpoint: checkpoint(1),
+        attestations: VariableList::new(vec![attestation(64, 3), attestation(65, 0)]).unwrap(),
+    };
+    let bytes = ssz::Encode::as_ssz_bytes(&block);
+    assert_eq!(
+        <Block as ssz::Decode>::from_ssz_bytes(&bytes).unwrap(),
+        block
+    );
+}
+
+#[test]
+fn test_errors_convert() {
+    assert_eq!(
+        <Checkpoint as ssz::Decode>::from_ssz_bytes(&[0; 39]),
+        Err(ssz::DecodeError::InvalidByteLength {
+            len: 39,
+            expected: 40
+        })
+    );
+
+    // an offset pointing back into the fixed portion
+    let mut bytes = <Attestation as SszbEncode>::to_ssz(&attestation(1, 2));
+    bytes[48..52].copy_from_slice(&4u32.to_le_bytes());
+    assert!(<Attestation as ssz::Decode>::from_ssz_bytes(&bytes).is_err());
+
+    // errors ethereum_ssz has no variant for are described in full
+    let mut bytes = <Attestation as SszbEncode>::to_ssz(&attestation(1, 16));
+    bytes.extend_from_slice(&16u64.to_le_bytes());
+    let error = <Attestation as SszbDecode>::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        <Attestation as ssz::Decode>::from_ssz_bytes(&bytes),
+        Err(ssz::DecodeError::BytesInvalid(error.to_string()))
+    );
+}
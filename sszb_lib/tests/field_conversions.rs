@@ -0,0 +1,137 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{validate_ssz, vectored::Segments, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::time::Duration;
+use typenum::U32;
+
+type Name = VariableList<u8, U32>;
+
+// a Duration is encoded as a whole number of milliseconds
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Millis {
+    millis: u64,
+}
+
+impl From<Duration> for Millis {
+    fn from(duration: Duration) -> Self {
+        Millis {
+            millis: duration.as_millis() as u64,
+        }
+    }
+}
+
+impl TryFrom<Millis> for Duration {
+    type Error = ();
+
+    fn try_from(millis: Millis) -> Result<Self, ()> {
+        Ok(Duration::from_millis(millis.millis))
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct Timeout {
+    id: u32,
+    #[ssz(encode_as = "Millis")]
+    after: Duration,
+    name: Name,
+    #[ssz(encode_as = "u16")]
+    retries: u8,
+}
+
+// the same layout, without the conversions
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RawTimeout {
+    id: u32,
+    after: u64,
+    name: Name,
+    retries: u16,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, SszbEncode, SszbDecode)]
+struct Level {
+    #[ssz(encode_as = "u16")]
+    level: u8,
+}
+
+fn timeout() -> Timeout {
+    Timeout {
+        id: 7,
+        after: Duration::from_millis(1500),
+        name: VariableList::new(b"connect".to_vec()).unwrap(),
+        retries: 3,
+    }
+}
+
+#[test]
+fn test_conversions_roundtrip() {
+    let bytes = timeout().to_ssz();
+    // the lengths are those of the encoded types
+    assert_eq!(
+        <Timeout as SszbEncode>::SSZ_MAX_LEN,
+        <RawTimeout as SszbEncode>::SSZ_MAX_LEN
+    );
+    assert_eq!(<Level as SszbEncode>::SSZ_FIXED_LEN, 2);
+    assert_eq!(timeout().sszb_bytes_len(), bytes.len());
+    assert_eq!(
+        bytes,
+        RawTimeout {
+            id: 7,
+            after: 1500,
+            name: VariableList::new(b"connect".to_vec()).unwrap(),
+            retries: 3,
+        }
+        .to_ssz()
+    );
+
+    assert_eq!(Timeout::from_ssz_bytes(&bytes), Ok(timeout()));
+    assert_eq!(Timeout::from_ssz_bytes_trusted(&bytes), Ok(timeout()));
+    assert_eq!(validate_ssz::<Timeout>(&bytes), Ok(()));
+    assert_eq!(
+        Timeout::decode_field_after(&bytes),
+        Ok(Duration::from_millis(1500))
+    );
+
+    let mut decoded = Timeout {
+        after: Duration::ZERO,
+        ..timeout()
+    };
+    decoded.decode_into(&bytes).unwrap();
+    assert_eq!(decoded, timeout());
+
+    let mut buf = vec![0u8; bytes.len()];
+    assert_eq!(timeout().ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(buf, bytes);
+    let value = timeout();
+    let mut segments = Segments::new();
+    value.ssz_write_vectored(&mut segments);
+    assert_eq!(segments.flatten(), bytes);
+
+    let level = Level { level: 200 };
+    assert_eq!(level.to_ssz(), 200u16.to_ssz());
+    assert_eq!(Level::from_ssz_bytes(&level.to_ssz()), Ok(level));
+}
+
+// conversion failures are reported as BytesInvalid
+#[allow(deprecated)]
+#[test]
+fn test_failed_conversions() {
+    let err = Level::from_ssz_bytes(&300u16.to_ssz()).unwrap_err();
+    let expected = DecodeError::BytesInvalid(
+        "field `level` can't be converted from its `u16` encoding".to_string(),
+    );
+    assert_eq!(err.root_cause(), &expected);
+    assert_eq!(validate_ssz::<Level>(&300u16.to_ssz()), Err(err));
+
+    // the error points at the field that failed to convert, after the fixed portion's id, after
+    // and name offset
+    let mut bytes = timeout().to_ssz();
+    bytes[16..18].copy_from_slice(&256u16.to_le_bytes());
+    let err = Timeout::from_ssz_bytes(&bytes).unwrap_err();
+    assert!(matches!(err.root_cause(), DecodeError::BytesInvalid(_)));
+    assert_eq!(err.position(), Some(16));
+    assert_eq!(validate_ssz::<Timeout>(&bytes), Err(err.clone()));
+    assert_eq!(timeout().decode_into(&bytes), Err(err));
+}
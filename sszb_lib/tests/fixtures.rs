@@ -0,0 +1,149 @@
+#![cfg(feature = "test-utils")]
+
+use bytes::Bytes;
+use sszb::test_utils::{
+    beacon_state, signed_beacon_block, BeaconState, BlockConfig, SignedBeaconBlock, StateConfig,
+};
+use sszb::{validate_ssz, SszbDecode, SszbEncode};
+
+fn assert_roundtrip<T: SszbEncode + SszbDecode + PartialEq + std::fmt::Debug>(
+    value: &T,
+    bytes: &[u8],
+) {
+    assert_eq!(value.sszb_bytes_len(), bytes.len());
+    assert_eq!(value.to_ssz(), bytes);
+    assert_eq!(validate_ssz::<T>(bytes), Ok(()));
+    assert_eq!(T::from_ssz_bytes(bytes).as_ref(), Ok(value));
+    assert_eq!(T::from_ssz_bytes_trusted(bytes).as_ref(), Ok(value));
+    assert_eq!(
+        T::decode_from_buf(&mut Bytes::copy_from_slice(bytes)).as_ref(),
+        Ok(value)
+    );
+
+    let mut buf = vec![0; bytes.len()];
+    assert_eq!(value.ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(buf, bytes);
+}
+
+#[test]
+fn test_blocks_at_several_sizes() {
+    for (attestations, transactions, transaction_bytes) in [
+        (0, 0, 0),
+        (1, 1, 100),
+        (16, 50, 10_000),
+        (128, 1000, 1 << 20),
+    ] {
+        let config = BlockConfig {
+            attestations,
+            transactions,
+            transaction_bytes,
+        };
+        let (block, bytes) = signed_beacon_block(attestations as u64, &config);
+        assert_roundtrip(&block, &bytes);
+
+        let payload = &block.message.body.execution_payload;
+        assert_eq!(block.message.body.attestations.len(), attestations);
+        assert_eq!(payload.transactions.len(), transactions);
+        assert_eq!(
+            payload
+                .transactions
+                .iter()
+                .map(|tx| tx.len())
+                .sum::<usize>(),
+            transaction_bytes
+        );
+    }
+}
+
+#[test]
+fn test_states_at_several_sizes() {
+    for validators in [0, 1, 1000, 20_000] {
+        let (state, bytes) = beacon_state(validators as u64, &StateConfig { validators });
+        assert_roundtrip(&state, &bytes);
+        assert_eq!(state.validators.len(), validators);
+        assert_eq!(state.balances.len(), validators);
+
+        // and a field deep inside can be found without decoding the rest
+        assert_eq!(BeaconState::decode_field_slot(&bytes), Ok(state.slot));
+    }
+}
+
+#[test]
+fn test_fixtures_are_deterministic() {
+    let config = BlockConfig {
+        attestations: 4,
+        transactions: 4,
+        transaction_bytes: 1000,
+    };
+    let (_, first) = signed_beacon_block(7, &config);
+    let (_, again) = signed_beacon_block(7, &config);
+    let (_, other) = signed_beacon_block(8, &config);
+    assert_eq!(first, again);
+    assert_ne!(first, other);
+
+    let config = StateConfig { validators: 10 };
+    assert_eq!(beacon_state(7, &config).1, beacon_state(7, &config).1);
+    assert_ne!(beacon_state(7, &config).1, beacon_state(8, &config).1);
+}
+
+#[test]
+fn test_nested_field_decode() {
+    let (block, bytes) = signed_beacon_block(3, &BlockConfig::default());
+    let message = SignedBeaconBlock::field_range(&bytes, 0).unwrap();
+    assert_eq!(
+        sszb::test_utils::beacon_block::BeaconBlock::decode_field_proposer_index(&bytes[message]),
+        Ok(block.message.proposer_index)
+    );
+}
+
+#[test]
+fn test_decode_into_reuses_fixture_buffers() {
+    let big = BlockConfig {
+        attestations: 16,
+        transactions: 50,
+        transaction_bytes: 10_000,
+    };
+    let small = BlockConfig {
+        attestations: 2,
+        transactions: 5,
+        transaction_bytes: 100,
+    };
+    let (first, first_bytes) = signed_beacon_block(1, &big);
+    let (second, second_bytes) = signed_beacon_block(2, &small);
+
+    let mut block = SignedBeaconBlock::from_ssz_bytes(&first_bytes).unwrap();
+    let payload = &block.message.body.execution_payload;
+    let transactions = payload.transactions.as_ptr();
+
+    block.decode_into(&second_bytes).unwrap();
+    assert_eq!(block, second);
+    assert_eq!(
+        block.message.body.execution_payload.transactions.as_ptr(),
+        transactions
+    );
+
+    block.decode_into(&first_bytes).unwrap();
+    assert_eq!(block, first);
+    assert_eq!(
+        block.message.body.execution_payload.transactions.as_ptr(),
+        transactions
+    );
+
+    let config = StateConfig { validators: 100 };
+    let (first, first_bytes) = beacon_state(1, &config);
+    let (second, second_bytes) = beacon_state(2, &config);
+
+    let mut state = BeaconState::from_ssz_bytes(&first_bytes).unwrap();
+    let pubkeys = state.current_sync_committee.pubkeys.as_ptr();
+    state.decode_into(&second_bytes).unwrap();
+    assert_eq!(state, second);
+    // the sync committee is a static container, and its vector of keys is decoded over
+    assert_eq!(state.current_sync_committee.pubkeys.as_ptr(), pubkeys);
+
+    // a failed decode leaves a valid value behind, which the next one decodes over
+    assert!(state
+        .decode_into(&first_bytes[..first_bytes.len() - 1])
+        .is_err());
+    state.decode_into(&first_bytes).unwrap();
+    assert_eq!(state, first);
+}
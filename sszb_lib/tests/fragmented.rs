@@ -0,0 +1,182 @@
+//! Every decode impl, fed its bytes out of buffers of 1, 2 and 3-byte chunks. A `Buf` may split a
+//! value anywhere, which `remaining()` doesn't reveal, so an impl that indexes `chunk()` for a
+//! whole field reads garbage or panics here.
+#![cfg(feature = "testing")]
+
+use alloy_primitives::{Address, FixedBytes, U256};
+use bytes::buf::{Buf, BufMut};
+use ethereum_types::{H160, H256};
+use milhouse::List;
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::testing::{check_fragmented, read_fragmented, Fragmented};
+use sszb::{ByteList, DecodeError, SszbDecode, SszbEncode, Union2};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U16, U32, U4, U8};
+
+type Bytes8 = VariableList<u8, U8>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: H256,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Header {
+    slot: u64,
+    proposer: Address,
+    graffiti: [u8; 32],
+    target: Checkpoint,
+    balance: U256,
+    bits: BitVector<U16>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    header: Header,
+    extra_data: Bytes8,
+    attesters: BitList<U32>,
+    indices: VariableList<u64, U16>,
+    names: VariableList<Bytes8, U4>,
+    message: Message,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum Message {
+    None,
+    Checkpoint(Checkpoint),
+    Data(Bytes8),
+}
+
+fn header() -> Header {
+    let mut bits = BitVector::new();
+    bits.set(3, true).unwrap();
+    Header {
+        slot: 0x0102_0304_0506_0708,
+        proposer: Address::repeat_byte(0xaa),
+        graffiti: [0x47; 32],
+        target: Checkpoint {
+            epoch: 9,
+            root: H256::repeat_byte(0x11),
+        },
+        balance: U256::from(0xdead_beef_u64) << 100,
+        bits,
+    }
+}
+
+fn block(message: Message) -> Block {
+    let mut attesters = BitList::with_capacity(20).unwrap();
+    attesters.set(19, true).unwrap();
+    Block {
+        header: header(),
+        extra_data: Bytes8::new(vec![1, 2, 3]).unwrap(),
+        attesters,
+        indices: VariableList::new(vec![u64::MAX, 0, 0x1234_5678]).unwrap(),
+        names: VariableList::new(vec![
+            Bytes8::new(vec![5; 7]).unwrap(),
+            Bytes8::new(vec![]).unwrap(),
+            Bytes8::new(vec![6]).unwrap(),
+        ])
+        .unwrap(),
+        message,
+    }
+}
+
+fn fragmented<T: SszbEncode + SszbDecode + PartialEq + Debug>(value: T) {
+    let bytes = value.to_ssz();
+    check_fragmented::<T>(&bytes);
+    assert_eq!(T::from_ssz_bytes(&bytes), Ok(value));
+}
+
+#[test]
+fn test_segments() {
+    let bytes: Vec<u8> = (0..8).collect();
+    let mut buf = Fragmented::new(&bytes, 3);
+    assert_eq!(buf.chunk(), [0, 1, 2]);
+    buf.advance(1);
+    assert_eq!(buf.chunk(), [1, 2]);
+    buf.advance(2);
+    assert_eq!(buf.chunk(), [3, 4, 5]);
+    assert_eq!(buf.get_u32_le(), u32::from_le_bytes([3, 4, 5, 6]));
+    assert_eq!(buf.chunk(), [7]);
+    buf.advance(1);
+    assert!(buf.chunk().is_empty());
+}
+
+#[test]
+fn test_basic_types() {
+    fragmented(0xabu8);
+    fragmented(0x0102u16);
+    fragmented(0x0102_0304u32);
+    fragmented(0x0102_0304_0506_0708u64);
+    fragmented(u128::MAX - 7);
+    fragmented(true);
+    fragmented([0x42u8; 32]);
+    fragmented(Address::repeat_byte(0x0f));
+    fragmented(FixedBytes::<4>::from([1, 2, 3, 4]));
+    fragmented(U256::MAX - U256::from(12));
+    fragmented(H160::repeat_byte(0x0e));
+    fragmented(H256::repeat_byte(0x0d));
+}
+
+#[test]
+fn test_collections() {
+    fragmented(header().bits);
+    fragmented(block(Message::None).attesters);
+    fragmented(FixedVector::<u16, U4>::new(vec![1, 2, 3, 4]).unwrap());
+    fragmented(VariableList::<u32, U8>::new(vec![7, 8, 9]).unwrap());
+    fragmented(block(Message::None).names);
+    fragmented(FixedVector::<Bytes8, U4>::default());
+    fragmented(List::<u64, U16>::new(vec![3, 4, 5]).unwrap());
+    fragmented(ByteList::<U32>::new(&b"fragmented"[..]).unwrap());
+    fragmented(bytes::Bytes::from_static(b"shared"));
+}
+
+#[test]
+fn test_containers_and_unions() {
+    fragmented(header());
+    fragmented(block(Message::None));
+    fragmented(block(Message::Checkpoint(header().target)));
+    fragmented(block(Message::Data(Bytes8::new(vec![9; 8]).unwrap())));
+    fragmented(Union2::<u64, Bytes8>::A(77));
+    fragmented(Union2::<u64, Bytes8>::B(Bytes8::new(vec![1, 2]).unwrap()));
+}
+
+#[cfg(feature = "bls")]
+#[test]
+fn test_bls() {
+    let pubkey = sszb::PKBytes::from_ssz_bytes(&[0xc0; 48]).unwrap();
+    fragmented(pubkey);
+}
+
+// invalid input fails the same way, at the same position, however it's split
+#[test]
+fn test_errors() {
+    let bytes = block(Message::None).to_ssz();
+
+    // every prefix is missing something
+    for len in 0..bytes.len() {
+        check_fragmented::<Block>(&bytes[..len]);
+    }
+    check_fragmented::<Header>(&header().to_ssz()[1..]);
+
+    // and so is a bad byte almost anywhere
+    for i in 0..bytes.len() {
+        let mut bytes = bytes.clone();
+        bytes[i] ^= 0xff;
+        check_fragmented::<Block>(&bytes);
+    }
+
+    check_fragmented::<bool>(&[2]);
+    check_fragmented::<Message>(&[3, 0]);
+    check_fragmented::<BitList<U8>>(&[0x00]);
+    check_fragmented::<[u8; 4]>(&[1, 2, 3]);
+    assert_eq!(
+        read_fragmented::<u64>(&[1, 2, 3], 1),
+        Err(DecodeError::InvalidByteLength {
+            len: 3,
+            expected: 8
+        })
+    );
+}
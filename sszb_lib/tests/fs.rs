@@ -0,0 +1,80 @@
+#![cfg(all(feature = "mmap", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::fs::{from_ssz_file, FileError, SszFile};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbDecodeRef, SszbEncode};
+use std::path::PathBuf;
+use typenum::{U1024, U65536};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Archive {
+    slot: u64,
+    roots: VariableList<[u8; 32], U65536>,
+    extra_data: VariableList<u8, U1024>,
+}
+
+#[derive(SszbDecodeRef)]
+struct ArchiveRef<'a> {
+    slot: u64,
+    roots: VariableList<[u8; 32], U65536>,
+    extra_data: &'a [u8],
+}
+
+// removed again when dropped, even if the test fails
+struct Fixture(PathBuf);
+
+impl Fixture {
+    fn new(name: &str, bytes: &[u8]) -> Self {
+        let path = std::env::temp_dir().join(format!("sszb-{}-{}.ssz", name, std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        Fixture(path)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn archive() -> Archive {
+    Archive {
+        slot: 12,
+        roots: VariableList::new((0..5000).map(|i| [i as u8; 32]).collect()).unwrap(),
+        extra_data: VariableList::new(b"graffiti".to_vec()).unwrap(),
+    }
+}
+
+#[test]
+fn test_decode_mapped_file() {
+    let bytes = archive().to_ssz();
+    let fixture = Fixture::new("archive", &bytes);
+
+    let decoded: Archive = from_ssz_file(&fixture.0).unwrap();
+    assert_eq!(decoded, Archive::from_ssz_bytes(&bytes).unwrap());
+    assert_eq!(decoded, archive());
+
+    let file = SszFile::open(&fixture.0).unwrap();
+    assert_eq!(file.bytes(), bytes);
+    let view = file.decode_ref::<ArchiveRef>().unwrap();
+    assert_eq!(view.slot, 12);
+    assert_eq!(view.roots.len(), 5000);
+    assert_eq!(view.extra_data, b"graffiti");
+}
+
+#[test]
+fn test_file_errors() {
+    let missing = std::env::temp_dir().join("sszb-missing-file.ssz");
+    match from_ssz_file::<Archive>(&missing) {
+        Err(FileError::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::NotFound),
+        result => panic!("expected an io error, got {:?}", result),
+    }
+
+    let fixture = Fixture::new("truncated", &archive().to_ssz()[..10]);
+    assert!(matches!(
+        from_ssz_file::<Archive>(&fixture.0),
+        Err(FileError::Decode(DecodeError::InvalidByteLength { .. }))
+    ));
+}
@@ -0,0 +1,102 @@
+#![cfg(all(feature = "alloy", feature = "ethereum-types", feature = "ssz-types"))]
+
+use alloy_primitives::{Address, U256};
+use ethereum_types::H256;
+use ssz_types::{FixedVector, VariableList};
+use sszb::{DecodeError, SszbDecode};
+
+type ByteList = VariableList<u8, typenum::U8>;
+
+#[test]
+fn test_short_fixed_size_inputs() {
+    let bytes = [1u8; 31];
+    assert_eq!(
+        <[u8; 32] as SszbDecode>::from_ssz_bytes(&bytes),
+        Err(DecodeError::InvalidByteLength {
+            len: 31,
+            expected: 32
+        })
+    );
+    assert_eq!(
+        <H256 as SszbDecode>::from_ssz_bytes(&bytes),
+        Err(DecodeError::InvalidByteLength {
+            len: 31,
+            expected: 32
+        })
+    );
+    assert_eq!(
+        <U256 as SszbDecode>::from_ssz_bytes(&bytes),
+        Err(DecodeError::InvalidByteLength {
+            len: 31,
+            expected: 32
+        })
+    );
+    assert_eq!(
+        <Address as SszbDecode>::from_ssz_bytes(&bytes[..19]),
+        Err(DecodeError::InvalidByteLength {
+            len: 19,
+            expected: 20
+        })
+    );
+}
+
+#[test]
+fn test_decreasing_offsets() {
+    // two items, but the second offset points before the first
+    let bytes = [8, 0, 0, 0, 7, 0, 0, 0, 1, 2];
+    assert_eq!(
+        <VariableList<ByteList, typenum::U4> as SszbDecode>::from_ssz_bytes(&bytes),
+        Err(DecodeError::OffsetsAreDecreasing(7).at_byte(4))
+    );
+}
+
+#[test]
+fn test_offset_past_items() {
+    // the second offset points beyond the end of the input
+    let bytes = [8, 0, 0, 0, 40, 0, 0, 0, 1, 2];
+    assert_eq!(
+        <VariableList<ByteList, typenum::U4> as SszbDecode>::from_ssz_bytes(&bytes),
+        Err(DecodeError::OffsetOutOfBounds(40).at_byte(4))
+    );
+}
+
+#[test]
+fn test_short_variable_vector() {
+    // a vector of four variable-size items needs at least a 16 byte offset table
+    let bytes = [16, 0, 0, 0, 16, 0, 0, 0];
+    assert_eq!(
+        <FixedVector<ByteList, typenum::U4> as SszbDecode>::from_ssz_bytes(&bytes),
+        Err(DecodeError::InvalidByteLength {
+            len: 8,
+            expected: 16
+        })
+    );
+}
+
+#[test]
+fn test_inflated_first_offset() {
+    // the first offset claims 1000 items, but the list is only 40 bytes long
+    let mut bytes = [0u8; 40];
+    bytes[..4].copy_from_slice(&4000u32.to_le_bytes());
+
+    assert_eq!(
+        sszb::iter_ssz_list::<ByteList>(&bytes, 4000).err(),
+        Some(DecodeError::OffsetOutOfBounds(4000))
+    );
+    assert_eq!(
+        sszb::decode_list_into(&bytes, 4000, &mut Vec::<ByteList>::new()),
+        Err(DecodeError::OffsetOutOfBounds(4000))
+    );
+    assert_eq!(
+        sszb::raw::iter_offsets(&bytes).collect::<Vec<_>>(),
+        [Err(DecodeError::OffsetOutOfBounds(4000))]
+    );
+    assert_eq!(
+        sszb::raw::split_offset_table(&bytes, 1000),
+        Err(DecodeError::InvalidListFixedBytesLen(4000))
+    );
+    assert_eq!(
+        sszb::raw::split_offset_table(&bytes, usize::MAX),
+        Err(DecodeError::InvalidListFixedBytesLen(usize::MAX))
+    );
+}
@@ -0,0 +1,280 @@
+//! Exact bytes for every built-in impl and a few derived containers.
+//!
+//! Round trips can't catch a mistake that encoding and decoding make alike, such as the wrong
+//! endianness or offsets counted from the wrong place, so each value here is checked against
+//! hard-coded bytes, worked out from the SSZ spec and its examples. Where ethereum_ssz implements
+//! the same type, its encoding is checked against them too. A change to the wire format fails
+//! here first.
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode, Union2};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U10, U1024, U128, U16, U2, U256, U3, U4, U8};
+
+// the value encodes to exactly `bytes`, and `bytes` decode to exactly the value
+fn golden<T: SszbEncode + SszbDecode + PartialEq + Debug>(value: T, bytes: &[u8]) {
+    assert_eq!(value.to_ssz(), bytes, "encoding {:?}", value);
+    assert_eq!(value.sszb_bytes_len(), bytes.len(), "length of {:?}", value);
+    assert_eq!(T::from_ssz_bytes(bytes), Ok(value));
+}
+
+fn cross_checked<T: SszbEncode + SszbDecode + ssz::Encode + PartialEq + Debug>(
+    value: T,
+    bytes: &[u8],
+) {
+    assert_eq!(
+        ssz::Encode::as_ssz_bytes(&value),
+        bytes,
+        "ethereum_ssz encoding {:?}",
+        value
+    );
+    golden(value, bytes);
+}
+
+#[test]
+fn test_uints() {
+    cross_checked(0xabu8, &[0xab]);
+    cross_checked(0x0102u16, &[0x02, 0x01]);
+    cross_checked(0x0102_0304u32, &[0x04, 0x03, 0x02, 0x01]);
+    cross_checked(
+        0x0102_0304_0506_0708u64,
+        &[0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01],
+    );
+    cross_checked(u64::MAX, &[0xff; 8]);
+    golden(
+        0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10u128,
+        &[
+            0x10, 0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a, 0x09, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03,
+            0x02, 0x01,
+        ],
+    );
+}
+
+#[test]
+fn test_bools() {
+    cross_checked(false, &[0x00]);
+    cross_checked(true, &[0x01]);
+}
+
+#[test]
+fn test_fixed_bytes() {
+    golden([0x01u8, 0x02, 0x03, 0x04], &[0x01, 0x02, 0x03, 0x04]);
+    let root: Vec<u8> = (0..32).collect();
+    cross_checked(<[u8; 32]>::try_from(root.as_slice()).unwrap(), &root);
+}
+
+#[cfg(feature = "alloy")]
+#[test]
+fn test_alloy() {
+    use alloy_primitives::{FixedBytes, U256 as Uint256};
+
+    let mut bytes = [0u8; 32];
+    bytes[..2].copy_from_slice(&[0x02, 0x01]);
+    golden(Uint256::from(0x0102u64), &bytes);
+    golden(
+        FixedBytes::<4>::from([0x01, 0x02, 0x03, 0x04]),
+        &[0x01, 0x02, 0x03, 0x04],
+    );
+}
+
+fn bitvector<N: typenum::Unsigned + Clone>(set: &[usize]) -> BitVector<N> {
+    let mut bits = BitVector::new();
+    for &i in set {
+        bits.set(i, true).unwrap();
+    }
+    bits
+}
+
+fn bitlist<N: typenum::Unsigned + Clone>(len: usize, set: &[usize]) -> BitList<N> {
+    let mut bits = BitList::with_capacity(len).unwrap();
+    for &i in set {
+        bits.set(i, true).unwrap();
+    }
+    bits
+}
+
+#[test]
+fn test_bitvectors() {
+    cross_checked(bitvector::<U8>(&[0, 7]), &[0b1000_0001]);
+    // the last six bits of the second byte are padding
+    cross_checked(bitvector::<U10>(&[0, 9]), &[0b0000_0001, 0b0000_0010]);
+    cross_checked(bitvector::<U16>(&[]), &[0x00, 0x00]);
+}
+
+#[test]
+fn test_bitlists() {
+    // nothing but the delimiter
+    cross_checked(bitlist::<U8>(0, &[]), &[0b0000_0001]);
+    cross_checked(bitlist::<U8>(3, &[0, 2]), &[0b0000_1101]);
+    // eight bits fill the first byte, so the delimiter starts a byte of its own
+    cross_checked(bitlist::<U8>(8, &[0, 1, 2, 3, 4, 5, 6, 7]), &[0xff, 0x01]);
+    cross_checked(bitlist::<U16>(9, &[8]), &[0x00, 0b0000_0011]);
+}
+
+type Bytes4 = VariableList<u8, U4>;
+
+#[test]
+fn test_vectors() {
+    cross_checked(
+        FixedVector::<u16, U3>::new(vec![1, 2, 3]).unwrap(),
+        &[0x01, 0x00, 0x02, 0x00, 0x03, 0x00],
+    );
+    // two offsets, then the items they point to
+    cross_checked(
+        FixedVector::<Bytes4, U2>::new(vec![
+            Bytes4::new(vec![0x01, 0x02]).unwrap(),
+            Bytes4::new(vec![0x03]).unwrap(),
+        ])
+        .unwrap(),
+        &[8, 0, 0, 0, 10, 0, 0, 0, 0x01, 0x02, 0x03],
+    );
+}
+
+#[test]
+fn test_lists() {
+    cross_checked(VariableList::<u32, U4>::new(vec![]).unwrap(), &[]);
+    cross_checked(
+        VariableList::<u32, U4>::new(vec![0x0102_0304, 0x0506_0708]).unwrap(),
+        &[0x04, 0x03, 0x02, 0x01, 0x08, 0x07, 0x06, 0x05],
+    );
+
+    type Lists = VariableList<Bytes4, U4>;
+    cross_checked(Lists::new(vec![]).unwrap(), &[]);
+    cross_checked(
+        Lists::new(vec![Bytes4::new(vec![0xaa, 0xbb]).unwrap()]).unwrap(),
+        &[4, 0, 0, 0, 0xaa, 0xbb],
+    );
+    // an empty item ends where it starts
+    cross_checked(
+        Lists::new(vec![
+            Bytes4::new(vec![0x01]).unwrap(),
+            Bytes4::new(vec![]).unwrap(),
+            Bytes4::new(vec![0x02, 0x03]).unwrap(),
+        ])
+        .unwrap(),
+        &[12, 0, 0, 0, 13, 0, 0, 0, 13, 0, 0, 0, 0x01, 0x02, 0x03],
+    );
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[cfg_attr(feature = "ethereum-ssz", ssz(compat = "ethereum_ssz"))]
+struct FixedTestStruct {
+    a: u8,
+    b: u64,
+    c: u32,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[cfg_attr(feature = "ethereum-ssz", ssz(compat = "ethereum_ssz"))]
+struct VarTestStruct {
+    a: u16,
+    b: VariableList<u16, U1024>,
+    c: u8,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[cfg_attr(feature = "ethereum-ssz", ssz(compat = "ethereum_ssz"))]
+struct ComplexTestStruct {
+    a: u16,
+    b: VariableList<u16, U128>,
+    c: u8,
+    d: VariableList<u8, U256>,
+    e: VarTestStruct,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[cfg_attr(feature = "ethereum-ssz", ssz(compat = "ethereum_ssz"))]
+struct OnlyVariable {
+    data: VariableList<u8, U16>,
+}
+
+const FIXED_BYTES: [u8; 13] = [
+    0xab, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01, 0xef, 0xbe, 0xad, 0xde,
+];
+
+fn fixed() -> FixedTestStruct {
+    FixedTestStruct {
+        a: 0xab,
+        b: 0x0102_0304_0506_0708,
+        c: 0xdead_beef,
+    }
+}
+
+// the fixed part is a, b's offset and c, 7 bytes
+const VAR_BYTES: [u8; 13] = [
+    0xcd, 0xab, 7, 0, 0, 0, 0xff, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00,
+];
+
+fn var() -> VarTestStruct {
+    VarTestStruct {
+        a: 0xabcd,
+        b: VariableList::new(vec![1, 2, 3]).unwrap(),
+        c: 0xff,
+    }
+}
+
+// a, b's offset, c, d's offset and e's offset take up the first 15 bytes, then b (4 bytes),
+// d (6) and e (13) follow in order
+fn complex_bytes() -> Vec<u8> {
+    let mut bytes = vec![0xbb, 0xaa, 15, 0, 0, 0, 0xff, 19, 0, 0, 0, 25, 0, 0, 0];
+    bytes.extend_from_slice(&[0x22, 0x11, 0x44, 0x33]);
+    bytes.extend_from_slice(b"foobar");
+    bytes.extend_from_slice(&VAR_BYTES);
+    bytes
+}
+
+fn complex() -> ComplexTestStruct {
+    ComplexTestStruct {
+        a: 0xaabb,
+        b: VariableList::new(vec![0x1122, 0x3344]).unwrap(),
+        c: 0xff,
+        d: VariableList::new(b"foobar".to_vec()).unwrap(),
+        e: var(),
+    }
+}
+
+fn only_variable(data: &[u8]) -> OnlyVariable {
+    OnlyVariable {
+        data: VariableList::new(data.to_vec()).unwrap(),
+    }
+}
+
+#[test]
+fn test_containers() {
+    golden(fixed(), &FIXED_BYTES);
+    golden(var(), &VAR_BYTES);
+    golden(complex(), &complex_bytes());
+    // the offset is all there is of an empty field
+    golden(only_variable(&[]), &[4, 0, 0, 0]);
+    golden(only_variable(&[0x01, 0x02]), &[4, 0, 0, 0, 0x01, 0x02]);
+
+    // static containers are packed like any static item
+    let mut bytes = FIXED_BYTES.to_vec();
+    bytes.extend_from_slice(&FIXED_BYTES);
+    golden(
+        VariableList::<FixedTestStruct, U4>::new(vec![fixed(), fixed()]).unwrap(),
+        &bytes,
+    );
+}
+
+#[cfg(feature = "ethereum-ssz")]
+#[test]
+fn test_containers_ethereum_ssz() {
+    assert_eq!(ssz::Encode::as_ssz_bytes(&fixed()), FIXED_BYTES);
+    assert_eq!(ssz::Encode::as_ssz_bytes(&var()), VAR_BYTES);
+    assert_eq!(ssz::Encode::as_ssz_bytes(&complex()), complex_bytes());
+    assert_eq!(ssz::Encode::as_ssz_bytes(&only_variable(&[])), [4, 0, 0, 0]);
+}
+
+#[test]
+fn test_unions() {
+    // the selector, then the value
+    golden(Union2::<u16, Bytes4>::A(0x0102), &[0x00, 0x02, 0x01]);
+    golden(
+        Union2::<u16, Bytes4>::B(Bytes4::new(vec![0x09]).unwrap()),
+        &[0x01, 0x09],
+    );
+}
@@ -0,0 +1,103 @@
+#![cfg(all(feature = "heapless", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U4, U8};
+
+type Name = VariableList<u8, U8>;
+
+fn name(s: &str) -> Name {
+    VariableList::new(s.as_bytes().to_vec()).unwrap()
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Peers {
+    version: u32,
+    ports: heapless::Vec<u16, 4>,
+    names: heapless::Vec<Name, 4>,
+}
+
+// heapless vectors encode exactly like lists of the same maximum length
+fn assert_like_list<T>(items: Vec<T>)
+where
+    T: SszbEncode + SszbDecode + Clone + PartialEq + std::fmt::Debug,
+    VariableList<T, U4>: SszbEncode,
+{
+    let expected = VariableList::<T, U4>::new(items.clone()).unwrap().to_ssz();
+    let vec = heapless::Vec::<T, 4>::from_iter(items);
+    assert_eq!(vec.to_ssz(), expected);
+    assert_eq!(vec.sszb_bytes_len(), expected.len());
+    assert_eq!(heapless::Vec::<T, 4>::from_ssz_bytes(&expected), Ok(vec));
+    assert_eq!(validate_ssz::<heapless::Vec<T, 4>>(&expected), Ok(()));
+}
+
+#[test]
+fn test_full_and_empty_roundtrip() {
+    assert_like_list::<u64>(vec![1, 2, 3, 4]);
+    assert_like_list::<u64>(vec![]);
+    assert_like_list::<Name>(vec![name("a"), name(""), name("bcd"), name("efgh")]);
+    assert_like_list::<Name>(vec![]);
+
+    let peers = Peers {
+        version: 1,
+        ports: heapless::Vec::from_slice(&[9000, 9001]).unwrap(),
+        names: heapless::Vec::from_iter([name("lh"), name("teku")]),
+    };
+    let bytes = peers.to_ssz();
+    assert_eq!(Peers::from_ssz_bytes(&bytes), Ok(peers));
+}
+
+#[test]
+fn test_over_capacity() {
+    let bytes = VariableList::<u64, U8>::new(vec![0; 5]).unwrap().to_ssz();
+    let expected = Err(DecodeError::ListTooLong { len: 5, max: 4 });
+    assert_eq!(heapless::Vec::<u64, 4>::from_ssz_bytes(&bytes), expected);
+    assert_eq!(
+        validate_ssz::<heapless::Vec<u64, 4>>(&bytes),
+        expected.map(drop)
+    );
+
+    let names = vec![name("x"); 5];
+    let bytes = VariableList::<Name, U8>::new(names).unwrap().to_ssz();
+    let expected = Err(DecodeError::ListTooLong { len: 5, max: 4 });
+    assert_eq!(heapless::Vec::<Name, 4>::from_ssz_bytes(&bytes), expected);
+    assert_eq!(
+        validate_ssz::<heapless::Vec<Name, 4>>(&bytes),
+        expected.map(drop)
+    );
+
+    // a vector with no room at all only takes the empty list
+    assert_eq!(
+        heapless::Vec::<u8, 0>::from_ssz_bytes(&[1]),
+        Err(DecodeError::ListTooLong { len: 1, max: 0 })
+    );
+    assert_eq!(
+        heapless::Vec::<u8, 0>::from_ssz_bytes(&[]),
+        Ok(heapless::Vec::new())
+    );
+}
+
+#[test]
+fn test_malformed_lists() {
+    assert_eq!(
+        heapless::Vec::<u16, 4>::from_ssz_bytes(&[1, 0, 2]),
+        Err(DecodeError::InvalidByteLength {
+            len: 1,
+            expected: 2
+        })
+    );
+
+    // the second offset points before the first
+    let mut bytes = VariableList::<Name, U4>::new(vec![name("a"), name("b")])
+        .unwrap()
+        .to_ssz();
+    bytes[4] = 7;
+    let expected = validate_ssz::<VariableList<Name, U4>>(&bytes);
+    assert!(expected.is_err());
+    assert_eq!(
+        heapless::Vec::<Name, 4>::from_ssz_bytes(&bytes).map(drop),
+        expected
+    );
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::BufMut;
+use sszb::{EncodeError, SszbEncode, Union2};
+use sszb_derive::SszbEncode;
+
+// a value that reports more than half of usize::MAX bytes, so that any two of them together
+// overflow. nothing is ever written, the lengths are measured first
+struct Huge;
+
+impl SszbEncode for Huge {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = 4;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        usize::MAX / 2 + 1
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, _buf: &mut impl BufMut) {
+        unreachable!("the length is checked first")
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {
+        unreachable!("the length is checked first")
+    }
+
+    fn ssz_write(&self, _buf: &mut impl BufMut) {
+        unreachable!("the length is checked first")
+    }
+}
+
+#[derive(SszbEncode)]
+struct Pair {
+    slot: u64,
+    a: Huge,
+    b: Huge,
+}
+
+#[derive(SszbEncode)]
+enum Message {
+    Empty,
+    Pair(Pair),
+    Fields { a: Huge, b: Huge },
+}
+
+#[test]
+fn test_fits() {
+    // a single one still fits, with its offset
+    let huge = Huge;
+    assert_eq!(huge.try_sszb_bytes_len(), Ok(usize::MAX / 2 + 1));
+    assert_eq!(Message::Empty.try_sszb_bytes_len(), Ok(1));
+    assert_eq!(<u64 as SszbEncode>::try_ssz_max_len(), Ok(8));
+}
+
+#[test]
+fn test_containers() {
+    let pair = Pair {
+        slot: 1,
+        a: Huge,
+        b: Huge,
+    };
+    assert_eq!(pair.try_sszb_bytes_len(), Err(EncodeError::LengthOverflow));
+    assert_eq!(pair.try_to_ssz(), Err(EncodeError::LengthOverflow));
+    assert_eq!(
+        pair.try_ssz_write(&mut Vec::<u8>::new()),
+        Err(EncodeError::LengthOverflow)
+    );
+
+    let message = Message::Fields { a: Huge, b: Huge };
+    assert_eq!(
+        message.try_sszb_bytes_len(),
+        Err(EncodeError::LengthOverflow)
+    );
+    let message = Message::Pair(pair);
+    assert_eq!(message.try_to_ssz(), Err(EncodeError::LengthOverflow));
+}
+
+#[test]
+fn test_lists_and_unions() {
+    let list: Box<[Huge]> = vec![Huge, Huge].into_boxed_slice();
+    assert_eq!(list.try_sszb_bytes_len(), Err(EncodeError::LengthOverflow));
+    assert_eq!(
+        vec![Huge].into_boxed_slice().try_sszb_bytes_len(),
+        Ok(usize::MAX / 2 + 5)
+    );
+
+    // the selector is one byte too many
+    struct Largest;
+    impl SszbEncode for Largest {
+        const SSZ_STATIC: bool = false;
+        const SSZ_FIXED_LEN: usize = 4;
+        const SSZ_MAX_LEN: usize = usize::MAX;
+
+        fn sszb_bytes_len(&self) -> usize {
+            usize::MAX
+        }
+
+        fn ssz_write_fixed(&self, _offset: &mut usize, _buf: &mut impl BufMut) {}
+        fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+        fn ssz_write(&self, _buf: &mut impl BufMut) {}
+    }
+    let union: Union2<Largest, u8> = Union2::A(Largest);
+    assert_eq!(union.try_sszb_bytes_len(), Err(EncodeError::LengthOverflow));
+}
+
+#[test]
+fn test_max_len() {
+    assert_eq!(
+        <Pair as SszbEncode>::try_ssz_max_len(),
+        Err(EncodeError::LengthOverflow)
+    );
+    assert_eq!(
+        <Box<[u8]> as SszbEncode>::try_ssz_max_len(),
+        Err(EncodeError::LengthOverflow)
+    );
+    assert_eq!(<[u8; 32] as SszbEncode>::try_ssz_max_len(), Ok(32));
+}
+
+#[test]
+fn test_buffer_too_small() {
+    let mut array = [0u8; 4];
+    assert_eq!(
+        7u64.try_ssz_write(&mut array.as_mut_slice()),
+        Err(EncodeError::BufferTooSmall {
+            len: 4,
+            expected: 8
+        })
+    );
+    // nothing was written
+    assert_eq!(array, [0; 4]);
+
+    let mut buf = Vec::new();
+    assert_eq!(7u64.try_ssz_write(&mut buf), Ok(()));
+    assert_eq!(buf, 7u64.to_ssz());
+}
+
+#[test]
+#[should_panic(
+    expected = "SSZ length overflow: the encoding of Pair is longer than usize::MAX bytes"
+)]
+fn test_panicking_len() {
+    let pair = Pair {
+        slot: 1,
+        a: Huge,
+        b: Huge,
+    };
+    pair.sszb_bytes_len();
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn test_codec() {
+    use sszb::codec::{CodecError, SszCodec};
+    use tokio_util::codec::Encoder;
+
+    let mut codec = SszCodec::<Pair>::with_max_frame_len(1024);
+    let mut dst = bytes::BytesMut::new();
+    let pair = Pair {
+        slot: 1,
+        a: Huge,
+        b: Huge,
+    };
+    assert!(matches!(
+        codec.encode(pair, &mut dst),
+        Err(CodecError::Encode(EncodeError::LengthOverflow))
+    ));
+    assert!(dst.is_empty());
+}
+
+#[cfg(feature = "era")]
+#[test]
+fn test_era() {
+    use sszb::era::{E2StoreWriter, EraError, COMPRESSED_BEACON_STATE};
+
+    let mut out = Vec::new();
+    let mut writer = E2StoreWriter::new(&mut out).unwrap();
+    let pair = Pair {
+        slot: 1,
+        a: Huge,
+        b: Huge,
+    };
+    assert!(matches!(
+        writer.write_compressed_entry(COMPRESSED_BEACON_STATE, &pair),
+        Err(EraError::Encode(EncodeError::LengthOverflow))
+    ));
+}
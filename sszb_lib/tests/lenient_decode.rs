@@ -0,0 +1,144 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U32;
+
+type Name = VariableList<u8, U32>;
+
+fn name(s: &str) -> Name {
+    VariableList::new(s.as_bytes().to_vec()).unwrap()
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(lenient_decode)]
+struct Profile {
+    id: u64,
+    name: Name,
+    flags: u16,
+}
+
+// Profile, as extended by a newer schema
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct ProfileV2 {
+    id: u64,
+    name: Name,
+    flags: u16,
+    bio: Name,
+    karma: u32,
+}
+
+// a newer schema that only appended a static field
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct ProfileV3 {
+    id: u64,
+    name: Name,
+    flags: u16,
+    karma: u32,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(lenient_decode)]
+struct Header {
+    slot: u64,
+    root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct HeaderV2 {
+    slot: u64,
+    root: [u8; 32],
+    proposer: u64,
+}
+
+fn profile() -> Profile {
+    Profile {
+        id: 3,
+        name: name("alice"),
+        flags: 0x0101,
+    }
+}
+
+#[test]
+fn test_lenient_decode_ignores_appended_fields() {
+    let bytes = ProfileV2 {
+        id: 3,
+        name: name("alice"),
+        flags: 0x0101,
+        bio: name("likes ssz"),
+        karma: 7,
+    }
+    .to_ssz();
+    assert_eq!(Profile::from_ssz_bytes_lenient(&bytes), Ok(profile()));
+    // the strict decode expects the variable data right after its own fixed fields
+    assert_eq!(
+        Profile::from_ssz_bytes(&bytes)
+            .unwrap_err()
+            .root_cause()
+            .clone(),
+        DecodeError::OffsetSkipsVariableBytes(22)
+    );
+
+    let bytes = ProfileV3 {
+        id: 3,
+        name: name("alice"),
+        flags: 0x0101,
+        karma: 7,
+    }
+    .to_ssz();
+    assert_eq!(Profile::from_ssz_bytes_lenient(&bytes), Ok(profile()));
+    assert!(Profile::from_ssz_bytes(&bytes).is_err());
+
+    let header = HeaderV2 {
+        slot: 1,
+        root: [2; 32],
+        proposer: 9,
+    };
+    let expected = Header {
+        slot: 1,
+        root: [2; 32],
+    };
+    assert_eq!(
+        Header::from_ssz_bytes_lenient(&header.to_ssz()),
+        Ok(expected)
+    );
+    assert_eq!(
+        Header::from_ssz_bytes(&header.to_ssz()),
+        Err(DecodeError::InvalidByteLength {
+            len: 48,
+            expected: 40
+        })
+    );
+}
+
+#[test]
+fn test_lenient_decode_of_current_schema() {
+    assert_eq!(
+        Profile::from_ssz_bytes_lenient(&profile().to_ssz()),
+        Ok(profile())
+    );
+}
+
+#[test]
+fn test_lenient_decode_checks_known_offsets() {
+    let mut bytes = profile().to_ssz();
+    // the name's offset points into the id
+    bytes[8] = 3;
+    let err = Profile::from_ssz_bytes_lenient(&bytes).unwrap_err();
+    assert_eq!(err.position(), Some(8));
+    assert_eq!(err.root_cause(), &DecodeError::OffsetIntoFixedPortion(3));
+
+    bytes[8] = 200;
+    let err = Profile::from_ssz_bytes_lenient(&bytes).unwrap_err();
+    assert_eq!(err.root_cause(), &DecodeError::OffsetOutOfBounds(200));
+
+    assert_eq!(
+        Profile::from_ssz_bytes_lenient(&bytes[..10]),
+        Err(DecodeError::InvalidByteLength {
+            len: 10,
+            expected: 14
+        })
+    );
+}
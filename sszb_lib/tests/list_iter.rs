@@ -0,0 +1,92 @@
+#![cfg(feature = "ssz-types")]
+
+use ssz_types::VariableList;
+use sszb::{iter_ssz_list, DecodeError, SszbDecode, SszbEncode};
+use typenum::{U1024, U16};
+
+type Item = VariableList<u8, U16>;
+type Items = VariableList<Item, U1024>;
+
+fn items() -> Items {
+    VariableList::new(
+        (0..100)
+            .map(|i| VariableList::new(vec![i as u8; i % 7]).unwrap())
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn write_offset(bytes: &mut [u8], index: usize, offset: usize) {
+    bytes[(index * 4)..(index * 4 + 4)].copy_from_slice(&(offset as u32).to_le_bytes());
+}
+
+#[test]
+fn test_iter_static_list() {
+    let list = VariableList::<u64, U1024>::new((0..500).map(|i| i * 3).collect()).unwrap();
+    let bytes = list.to_ssz();
+    let iterated = iter_ssz_list::<u64>(&bytes, 1024)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(iterated, list.to_vec());
+
+    assert_eq!(
+        iter_ssz_list::<u64>(&bytes, 499).err(),
+        Some(DecodeError::ListTooLong { len: 500, max: 499 })
+    );
+    assert_eq!(
+        iter_ssz_list::<u64>(&bytes[..13], 1024).err(),
+        Some(DecodeError::InvalidByteLength {
+            len: 5,
+            expected: 8
+        })
+    );
+    assert_eq!(iter_ssz_list::<u64>(&[], 1024).unwrap().count(), 0);
+}
+
+#[test]
+fn test_iter_variable_list() {
+    let list = items();
+    let bytes = list.to_ssz();
+    let iterated = iter_ssz_list::<Item>(&bytes, 1024)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(iterated, list.to_vec());
+
+    assert_eq!(
+        iter_ssz_list::<Item>(&bytes, 99).err(),
+        Some(DecodeError::ListTooLong { len: 100, max: 99 })
+    );
+    assert_eq!(iter_ssz_list::<Item>(&[], 1024).unwrap().count(), 0);
+}
+
+#[test]
+fn test_bad_offset_is_an_element_error() {
+    let mut bytes = items().to_ssz();
+    // make item 50 end past the end of the list
+    write_offset(&mut bytes, 51, bytes.len() + 1);
+
+    let results = iter_ssz_list::<Item>(&bytes, 1024)
+        .unwrap()
+        .collect::<Vec<_>>();
+    // the items before it decode, then iteration stops at the bad offset
+    assert_eq!(results.len(), 51);
+    assert!(results[..50].iter().all(Result::is_ok));
+    let err = results[50].clone().unwrap_err();
+    assert_eq!(Err(err), Items::from_ssz_bytes(&bytes));
+}
+
+#[test]
+fn test_bad_element_is_an_element_error() {
+    let mut bytes = items().to_ssz();
+    // make item 10 longer than its maximum of 16 bytes, at the expense of item 11
+    let start = u32::from_le_bytes(bytes[40..44].try_into().unwrap()) as usize;
+    write_offset(&mut bytes, 11, start + 17);
+    let next = u32::from_le_bytes(bytes[48..52].try_into().unwrap()) as usize;
+    write_offset(&mut bytes, 12, next.max(start + 17));
+
+    let mut iter = iter_ssz_list::<Item>(&bytes, 1024).unwrap();
+    let first_error = iter.by_ref().find_map(Result::err).unwrap();
+    assert_eq!(Err(first_error), Items::from_ssz_bytes(&bytes));
+}
@@ -0,0 +1,168 @@
+//! Hand-written impls only provide `ssz_read`, and get `from_ssz_bytes` from the trait.
+
+use bytes::buf::{Buf, BufMut};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Point {
+    x: u16,
+    y: u16,
+}
+
+impl SszbEncode for Point {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 4;
+    const SSZ_MAX_LEN: usize = 4;
+
+    fn sszb_bytes_len(&self) -> usize {
+        4
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_u16_le(self.x);
+        buf.put_u16_le(self.y);
+    }
+}
+
+impl SszbDecode for Point {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 4;
+    const SSZ_MAX_LEN: usize = 4;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        _variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        if fixed_bytes.remaining() < 4 {
+            return Err(DecodeError::InvalidByteLength {
+                len: fixed_bytes.remaining(),
+                expected: 4,
+            });
+        }
+        Ok(Point {
+            x: fixed_bytes.get_u16_le(),
+            y: fixed_bytes.get_u16_le(),
+        })
+    }
+}
+
+// a UTF-8 string of up to 16 bytes
+#[derive(PartialEq, Debug, Clone)]
+struct Name(String);
+
+impl SszbEncode for Name {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = sszb::BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = 16;
+
+    fn sszb_bytes_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        buf.put_slice(&offset.to_le_bytes()[0..sszb::BYTES_PER_LENGTH_OFFSET]);
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(self.0.as_bytes());
+    }
+}
+
+impl SszbDecode for Name {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = sszb::BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = 16;
+
+    fn ssz_read(
+        _fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        let len = variable_bytes.remaining();
+        if len > 16 {
+            return Err(DecodeError::ListTooLong { len, max: 16 });
+        }
+        let name = core::str::from_utf8(&variable_bytes.chunk()[..len])
+            .map_err(|_| DecodeError::CollectionBuildError("Name"))?
+            .to_owned();
+        variable_bytes.advance(len);
+        Ok(Name(name))
+    }
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Player {
+    position: Point,
+    name: Name,
+    score: u32,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Match {
+    players: [u8; 2],
+    home: Player,
+    away: Player,
+}
+
+fn player(name: &str, score: u32) -> Player {
+    Player {
+        position: Point { x: 3, y: 4 },
+        name: Name(name.to_owned()),
+        score,
+    }
+}
+
+#[test]
+fn test_manual_impls_use_default_from_ssz_bytes() {
+    let point = Point { x: 1, y: 0x0203 };
+    assert_eq!(point.to_ssz(), [1, 0, 3, 2]);
+    assert_eq!(Point::from_ssz_bytes(&[1, 0, 3, 2]), Ok(point));
+    // a static value has to span exactly its fixed length
+    assert_eq!(
+        Point::from_ssz_bytes(&[1, 0, 3, 2, 0]),
+        Err(DecodeError::InvalidByteLength {
+            len: 5,
+            expected: 4
+        })
+    );
+
+    assert_eq!(Name::from_ssz_bytes(b"alice"), Ok(Name("alice".to_owned())));
+    assert_eq!(
+        Name::from_ssz_bytes(&[b'a'; 17]),
+        Err(DecodeError::ListTooLong { len: 17, max: 16 })
+    );
+}
+
+#[test]
+fn test_manual_impls_in_derived_containers() {
+    let value = Match {
+        players: [1, 2],
+        home: player("alice", 7),
+        away: player("", 0),
+    };
+    let bytes = value.to_ssz();
+    assert_eq!(Match::from_ssz_bytes(&bytes), Ok(value));
+
+    // the container's own fixed portion (point, name offset, score) is checked up front
+    assert_eq!(
+        Player::from_ssz_bytes(&[0; 11]),
+        Err(DecodeError::InvalidByteLength {
+            len: 11,
+            expected: 12
+        })
+    );
+    let bytes = player("bob", 1).to_ssz();
+    assert_eq!(&bytes[12..], b"bob");
+    assert_eq!(Player::from_ssz_bytes(&bytes), Ok(player("bob", 1)));
+}
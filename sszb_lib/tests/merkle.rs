@@ -0,0 +1,117 @@
+#![cfg(feature = "test-utils")]
+
+use sszb::dynamic::{SszTypeInfo, SszbTypeInfo};
+use sszb::merkle::{gindex, GindexError};
+use sszb::test_utils::beacon_block::BeaconBlockBody;
+use sszb::test_utils::BeaconState;
+
+// the light client constants of the consensus specs, from Altair to Deneb
+#[test]
+fn test_light_client_gindices() {
+    let state = BeaconState::ssz_type_info();
+    assert_eq!(gindex(&state, &["finalized_checkpoint", "root"]), Ok(105));
+    assert_eq!(gindex(&state, &["current_sync_committee"]), Ok(54));
+    assert_eq!(gindex(&state, &["next_sync_committee"]), Ok(55));
+
+    let body = BeaconBlockBody::ssz_type_info();
+    assert_eq!(gindex(&body, &["execution_payload"]), Ok(25));
+    // KZG_COMMITMENT_INCLUSION_PROOF_DEPTH is 17, the depth of the first commitment
+    let commitment = gindex(&body, &["blob_kzg_commitments", "0"]).unwrap();
+    assert_eq!(commitment, 27 * 2 * 4096);
+    assert_eq!(63 - commitment.leading_zeros(), 17);
+    assert_eq!(
+        gindex(&body, &["blob_kzg_commitments", "4095"]),
+        Ok(commitment + 4095)
+    );
+}
+
+#[test]
+fn test_list_items_and_lengths() {
+    let state = BeaconState::ssz_type_info();
+
+    // the validators are the 12th of 28 fields, in a list of up to 2^40 containers of 8 fields
+    let validators = 32 + 11;
+    let validator = validators * 2 * (1 << 40) + 12;
+    assert_eq!(gindex(&state, &["validators", "12"]), Ok(validator));
+    assert_eq!(
+        gindex(&state, &["validators", "12", "pubkey"]),
+        Ok(validator * 8)
+    );
+    assert_eq!(
+        gindex(&state, &["validators", "12", "slashed"]),
+        Ok(validator * 8 + 3)
+    );
+    assert_eq!(
+        gindex(&state, &["validators", "__len__"]),
+        Ok(validators * 2 + 1)
+    );
+
+    // four balances share a chunk, and there are 2^38 chunks
+    let balances = 32 + 12;
+    assert_eq!(
+        gindex(&state, &["balances", "5"]),
+        Ok(balances * 2 * (1 << 38) + 1)
+    );
+    // 8192 roots of a chunk each
+    assert_eq!(
+        gindex(&state, &["block_roots", "100"]),
+        Ok((32 + 5) * 8192 + 100)
+    );
+    // four bits fit in a single chunk
+    assert_eq!(gindex(&state, &["justification_bits", "3"]), Ok(32 + 17));
+}
+
+#[test]
+fn test_unions_and_bitlists() {
+    let schema = SszTypeInfo::Container(vec![
+        ("bits".into(), SszTypeInfo::Bitlist(2048)),
+        (
+            "payload".into(),
+            SszTypeInfo::Union(vec![None, Some(SszTypeInfo::list(SszTypeInfo::Uint(8), 4))]),
+        ),
+    ]);
+    assert_eq!(gindex(&schema, &["bits", "__len__"]), Ok(5));
+    // 2048 bits are 8 chunks, under the length mix-in
+    assert_eq!(gindex(&schema, &["bits", "300"]), Ok(2 * 2 * 8 + 1));
+    assert_eq!(gindex(&schema, &["payload", "__selector__"]), Ok(7));
+    assert_eq!(gindex(&schema, &["payload", "0"]), Ok(6));
+    assert_eq!(gindex(&schema, &["payload", "1", "__len__"]), Ok(13));
+    // four u64s fill the one chunk
+    assert_eq!(gindex(&schema, &["payload", "1", "3"]), Ok(12));
+}
+
+#[test]
+fn test_invalid_paths() {
+    let state = BeaconState::ssz_type_info();
+    assert_eq!(gindex(&state, &[]), Ok(1));
+    assert_eq!(
+        gindex(&state, &["finalised_checkpoint"]),
+        Err(GindexError::UnknownField("finalised_checkpoint".into()))
+    );
+    assert_eq!(
+        gindex(&state, &["slot", "0"]),
+        Err(GindexError::NotComposite("0".into()))
+    );
+    assert_eq!(
+        gindex(&state, &["block_roots", "8192"]),
+        Err(GindexError::IndexOutOfRange {
+            index: 8192,
+            len: 8192
+        })
+    );
+    assert_eq!(
+        gindex(&state, &["block_roots", "__len__"]),
+        Err(GindexError::NoSuchNode("__len__".into()))
+    );
+    assert_eq!(
+        gindex(&state, &["validators", "first"]),
+        Err(GindexError::InvalidIndex("first".into()))
+    );
+
+    // a list of lists of up to 2^40 items each is too deep for a u64
+    let deep = SszTypeInfo::list(
+        SszTypeInfo::list(SszTypeInfo::list(SszTypeInfo::Bool, 1 << 40), 1 << 20),
+        1 << 20,
+    );
+    assert_eq!(gindex(&deep, &["0", "0", "0"]), Err(GindexError::Overflow));
+}
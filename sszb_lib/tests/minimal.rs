@@ -0,0 +1,41 @@
+//! Only uses what's available with `--no-default-features`, so it runs under any feature set:
+//! `cargo test -p sszb --no-default-features --test minimal`
+use bytes::buf::{Buf, BufMut};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Primitives {
+    a: u8,
+    b: u16,
+    c: u32,
+    d: u64,
+    e: u128,
+    f: bool,
+    g: [u8; 32],
+}
+
+#[test]
+fn test_minimal_roundtrip() {
+    let item = Primitives {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+        e: 5,
+        f: true,
+        g: [6; 32],
+    };
+
+    let bytes = item.to_ssz();
+    assert_eq!(bytes.len(), <Primitives as SszbEncode>::SSZ_FIXED_LEN);
+    assert_eq!(bytes.len(), item.sszb_bytes_len());
+    assert_eq!(<Primitives as SszbDecode>::from_ssz_bytes(&bytes), Ok(item));
+    assert_eq!(
+        <Primitives as SszbDecode>::from_ssz_bytes(&bytes[1..]),
+        Err(DecodeError::InvalidByteLength {
+            len: bytes.len() - 1,
+            expected: bytes.len()
+        })
+    );
+}
@@ -0,0 +1,156 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use bytes::{Bytes, BytesMut};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::{U16, U4};
+
+// static, so it's written in a single pass and can't be monomorphized (see
+// tests/ui/monomorphize_static.rs)
+#[derive(Clone, PartialEq, Debug, Default, SszbEncode, SszbDecode)]
+struct Header {
+    slot: u64,
+    proposer: u32,
+    root: [u8; 32],
+}
+
+#[derive(Clone, PartialEq, Debug, Default, SszbEncode, SszbDecode)]
+#[ssz(monomorphize = "slices")]
+struct Body {
+    header: Header,
+    graffiti: VariableList<u8, U16>,
+    flags: u16,
+    headers: VariableList<Header, U4>,
+    names: VariableList<VariableList<u8, U16>, U4>,
+}
+
+// holds a monomorphized container among regular fields
+#[derive(Clone, PartialEq, Debug, Default, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    body: Body,
+    bodies: VariableList<Body, U4>,
+}
+
+// the same layout, derived the regular way
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RegularBody {
+    header: Header,
+    graffiti: VariableList<u8, U16>,
+    flags: u16,
+    headers: VariableList<Header, U4>,
+    names: VariableList<VariableList<u8, U16>, U4>,
+}
+
+fn header(i: u64) -> Header {
+    Header {
+        slot: i,
+        proposer: !(i as u32),
+        root: [i as u8; 32],
+    }
+}
+
+fn body(i: u64) -> (Body, RegularBody) {
+    let graffiti = VariableList::new(vec![i as u8; i as usize % 16]).unwrap();
+    let names = VariableList::new(vec![
+        VariableList::new(b"alice".to_vec()).unwrap(),
+        VariableList::new(vec![]).unwrap(),
+    ])
+    .unwrap();
+    let headers = VariableList::new((0..i % 5).map(header).collect()).unwrap();
+    (
+        Body {
+            header: header(i),
+            graffiti: graffiti.clone(),
+            flags: i as u16,
+            headers: headers.clone(),
+            names: names.clone(),
+        },
+        RegularBody {
+            header: header(i),
+            graffiti,
+            flags: i as u16,
+            headers,
+            names,
+        },
+    )
+}
+
+// every way of writing and reading the container has to agree with the regular derive
+fn assert_same_encoding<M, R>(value: M, regular: &R)
+where
+    M: SszbEncode + SszbDecode + PartialEq + Debug + Default,
+    R: SszbEncode + SszbDecode,
+{
+    let bytes = regular.to_ssz();
+    assert_eq!(value.to_ssz(), bytes);
+    assert_eq!(value.sszb_bytes_len(), bytes.len());
+
+    // to_ssz_with_vec writes straight into the Vec it's given, after what it already holds
+    let mut appended = vec![0xaa; 3];
+    value.to_ssz_with_vec(&mut appended);
+    assert_eq!(&appended[..3], &[0xaa; 3]);
+    assert_eq!(&appended[3..], &bytes[..]);
+
+    let mut vec = vec![];
+    value.ssz_write(&mut vec);
+    assert_eq!(vec, bytes);
+    let mut bytes_mut = BytesMut::new();
+    value.ssz_write(&mut bytes_mut);
+    assert_eq!(&bytes_mut[..], &bytes[..]);
+
+    let mut buf = vec![0xaa; bytes.len() + 1];
+    assert_eq!(value.ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(&buf[..bytes.len()], &bytes[..]);
+
+    assert_eq!(M::from_ssz_bytes(&bytes).as_ref(), Ok(&value));
+    assert_eq!(
+        M::decode_from_buf(&mut Bytes::from(bytes.clone())).as_ref(),
+        Ok(&value)
+    );
+    let mut decoded = M::default();
+    decoded.decode_into(&bytes).unwrap();
+    assert_eq!(decoded, value);
+
+    // and fail the same way, at the same position
+    for len in 0..bytes.len() {
+        assert_eq!(
+            M::from_ssz_bytes(&bytes[..len]).err(),
+            R::from_ssz_bytes(&bytes[..len]).err(),
+            "truncated to {} bytes",
+            len
+        );
+    }
+}
+
+#[test]
+fn test_variable_container() {
+    for i in [0, 3, 4, 9] {
+        let (value, regular) = body(i);
+        assert_same_encoding(value, &regular);
+    }
+}
+
+#[test]
+fn test_nested_in_regular_container() {
+    let block = Block {
+        slot: 7,
+        body: body(3).0,
+        bodies: VariableList::new(vec![body(0).0, body(4).0]).unwrap(),
+    };
+    let bytes = block.to_ssz();
+    assert_eq!(Block::from_ssz_bytes(&bytes).as_ref(), Ok(&block));
+    let mut bytes_mut = BytesMut::new();
+    block.ssz_write(&mut bytes_mut);
+    assert_eq!(&bytes_mut[..], &bytes[..]);
+
+    let mut vec = vec![];
+    block.ssz_write(&mut vec);
+    assert_eq!(vec, bytes);
+    let mut buf = vec![0; bytes.len()];
+    assert_eq!(block.ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(buf, bytes);
+}
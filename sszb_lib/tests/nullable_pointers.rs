@@ -0,0 +1,160 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::sync::Arc;
+use typenum::U64;
+
+type Transactions = VariableList<u8, U64>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Payload {
+    block_hash: [u8; 32],
+    transactions: Transactions,
+}
+
+// Union[None, Payload], the SSZ spelling of an optional payload, held behind a pointer or inline
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum SharedPayload {
+    None,
+    Some(Arc<Payload>),
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum InlinePayload {
+    None,
+    Some(Payload),
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum MaybeTransactions {
+    None,
+    Some(Box<Transactions>),
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum InlineTransactions {
+    None,
+    Some(Transactions),
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    payload: SharedPayload,
+    transactions: MaybeTransactions,
+    parent: Arc<InlinePayload>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct InlineBlock {
+    slot: u64,
+    payload: InlinePayload,
+    transactions: InlineTransactions,
+    parent: InlinePayload,
+}
+
+fn payload(len: usize) -> Payload {
+    Payload {
+        block_hash: [len as u8; 32],
+        transactions: Transactions::new(vec![7; len]).unwrap(),
+    }
+}
+
+fn block(payload_len: Option<usize>, transactions_len: Option<usize>) -> (Block, InlineBlock) {
+    let transactions = |len: usize| Transactions::new(vec![3; len]).unwrap();
+    let shared = Block {
+        slot: 9,
+        payload: match payload_len {
+            Some(len) => SharedPayload::Some(Arc::new(payload(len))),
+            None => SharedPayload::None,
+        },
+        transactions: match transactions_len {
+            Some(len) => MaybeTransactions::Some(Box::new(transactions(len))),
+            None => MaybeTransactions::None,
+        },
+        parent: Arc::new(InlinePayload::Some(payload(2))),
+    };
+    let inline = InlineBlock {
+        slot: 9,
+        payload: match payload_len {
+            Some(len) => InlinePayload::Some(payload(len)),
+            None => InlinePayload::None,
+        },
+        transactions: match transactions_len {
+            Some(len) => InlineTransactions::Some(transactions(len)),
+            None => InlineTransactions::None,
+        },
+        parent: InlinePayload::Some(payload(2)),
+    };
+    (shared, inline)
+}
+
+#[test]
+fn test_optional_pointer() {
+    let inner = payload(5);
+    let shared = SharedPayload::Some(Arc::new(payload(5)));
+    assert_eq!(shared.sszb_bytes_len(), inner.sszb_bytes_len() + 1);
+    assert_eq!(shared.to_ssz(), InlinePayload::Some(payload(5)).to_ssz());
+    assert_eq!(shared.to_ssz()[1..], inner.to_ssz());
+
+    let decoded = SharedPayload::from_ssz_bytes(&shared.to_ssz()).unwrap();
+    assert_eq!(decoded, shared);
+    // a new allocation, owned by nothing else
+    match (&decoded, &shared) {
+        (SharedPayload::Some(decoded), SharedPayload::Some(shared)) => {
+            assert!(!Arc::ptr_eq(decoded, shared));
+            assert_eq!(Arc::strong_count(decoded), 1);
+        }
+        _ => unreachable!(),
+    }
+
+    assert_eq!(SharedPayload::None.to_ssz(), [0]);
+    assert_eq!(SharedPayload::from_ssz_bytes(&[0]), Ok(SharedPayload::None));
+}
+
+#[test]
+fn test_pointer_to_optional() {
+    for value in [InlinePayload::None, InlinePayload::Some(payload(3))] {
+        let bytes = value.to_ssz();
+        let shared = Arc::new(value);
+        assert_eq!(shared.sszb_bytes_len(), bytes.len());
+        assert_eq!(shared.to_ssz(), bytes);
+        assert_eq!(Arc::<InlinePayload>::from_ssz_bytes(&bytes), Ok(shared));
+    }
+}
+
+#[test]
+fn test_optional_fields() {
+    // absent, present and empty, present and full
+    for len in [None, Some(0), Some(40)] {
+        let (shared, inline) = block(len, len);
+        let bytes = inline.to_ssz();
+        assert_eq!(shared.to_ssz(), bytes);
+        assert_eq!(shared.sszb_bytes_len(), bytes.len());
+
+        let mut slice = vec![0; bytes.len()];
+        assert_eq!(shared.ssz_write_slice(&mut slice), bytes.len());
+        assert_eq!(slice, bytes);
+
+        assert_eq!(Block::from_ssz_bytes(&bytes), Ok(shared));
+        assert_eq!(validate_ssz::<Block>(&bytes), Ok(()));
+    }
+}
+
+#[test]
+fn test_error_positions() {
+    // the payload's transactions start at a bad offset, reported at the same byte whether the
+    // payload is behind an Arc or not
+    let (_, inline) = block(Some(4), None);
+    let mut bytes = inline.to_ssz();
+    let payload_start = u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize;
+    // past the selector and the block hash
+    bytes[payload_start + 1 + 32] = 40;
+    let err = InlineBlock::from_ssz_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, DecodeError::AtByte { .. }));
+    assert_eq!(Block::from_ssz_bytes(&bytes), Err(err.clone()));
+    assert_eq!(validate_ssz::<Block>(&bytes), Err(err));
+}
@@ -0,0 +1,162 @@
+#![cfg(all(feature = "ssz-types", target_pointer_width = "64"))]
+
+use bytes::buf::BufMut;
+use ssz_types::VariableList;
+use sszb::{
+    check_offset, expect_offset, offset_bytes, write_offset, EncodeError, SszbEncode,
+    BYTES_PER_LENGTH_OFFSET,
+};
+use sszb_derive::SszbEncode;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use typenum::U4;
+
+const GIB: usize = 1 << 30;
+
+// claims to be 3 GiB long without holding, or writing, any of it, so that the offsets after it
+// can be pushed past u32::MAX without allocating that much
+struct Huge;
+
+impl SszbEncode for Huge {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        3 * GIB
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        expect_offset(write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, _buf: &mut impl BufMut) {}
+}
+
+// the third field would start 6 GiB in
+#[derive(SszbEncode)]
+struct Archive {
+    first: Huge,
+    count: u64,
+    second: Huge,
+    third: Huge,
+}
+
+fn archive() -> Archive {
+    Archive {
+        first: Huge,
+        count: 7,
+        second: Huge,
+        third: Huge,
+    }
+}
+
+fn huge_list() -> VariableList<Huge, U4> {
+    VariableList::new(vec![Huge, Huge, Huge]).unwrap()
+}
+
+#[test]
+fn test_offsets_up_to_u32_max() {
+    assert_eq!(check_offset(12), 12);
+    assert_eq!(check_offset(u32::MAX as usize), u32::MAX);
+    assert_eq!(offset_bytes(0x0102_0304), [4, 3, 2, 1]);
+}
+
+#[test]
+fn test_write_offset() {
+    let mut buf = Vec::<u8>::new();
+    assert_eq!(write_offset(0, &mut buf), Ok(()));
+    assert_eq!(write_offset(u32::MAX as usize, &mut buf), Ok(()));
+    assert_eq!(buf, [0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+
+    // one past u32::MAX is an error, and nothing is written
+    let offset = u32::MAX as usize + 1;
+    assert_eq!(
+        write_offset(offset, &mut buf),
+        Err(EncodeError::OffsetOverflow { offset })
+    );
+    assert_eq!(buf.len(), 8);
+
+    // a slice takes the bytes the same way
+    let mut full = [0u8; 4];
+    let mut slice = &mut full[..];
+    assert_eq!(write_offset(0x0102_0304, &mut slice), Ok(()));
+    assert_eq!(full, [4, 3, 2, 1]);
+}
+
+#[test]
+#[should_panic(expected = "SSZ offset overflow")]
+fn test_expect_offset_past_u32_max() {
+    expect_offset(write_offset(u32::MAX as usize + 1, &mut Vec::<u8>::new()));
+}
+
+#[test]
+#[should_panic(expected = "SSZ offset overflow")]
+fn test_offset_past_u32_max() {
+    check_offset(u32::MAX as usize + 1);
+}
+
+#[test]
+#[should_panic(expected = "SSZ offset overflow")]
+fn test_container_offset_overflow() {
+    archive().ssz_write(&mut Vec::<u8>::new());
+}
+
+#[test]
+#[should_panic(expected = "SSZ offset overflow")]
+fn test_list_offset_overflow() {
+    huge_list().ssz_write(&mut Vec::<u8>::new());
+}
+
+#[test]
+fn test_overflow_is_caught_before_writing() {
+    // the lengths are measured up front, so nothing is written (or allocated) before the panic
+    let mut buf = vec![];
+    let result = catch_unwind(AssertUnwindSafe(|| archive().to_ssz_with_vec(&mut buf)));
+    assert!(result.is_err());
+    assert!(buf.is_empty());
+
+    let result = catch_unwind(AssertUnwindSafe(|| huge_list().to_ssz_with_vec(&mut buf)));
+    assert!(result.is_err());
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_fallible_encoders_return_offset_overflow() {
+    // the third field starts after the fixed part and the first two
+    let offset = 20 + 6 * GIB;
+    assert_eq!(
+        archive().try_to_ssz(),
+        Err(EncodeError::OffsetOverflow { offset })
+    );
+    let mut buf = Vec::<u8>::new();
+    assert_eq!(
+        archive().try_ssz_write(&mut buf),
+        Err(EncodeError::OffsetOverflow { offset })
+    );
+    assert!(buf.is_empty());
+
+    // and the third item after the offsets and the first two
+    let offset = 12 + 6 * GIB;
+    assert_eq!(
+        huge_list().try_to_ssz(),
+        Err(EncodeError::OffsetOverflow { offset })
+    );
+    assert_eq!(
+        huge_list().try_ssz_write(&mut buf),
+        Err(EncodeError::OffsetOverflow { offset })
+    );
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_parts_up_to_u32_max() {
+    // two parts of 3 GiB are fine, as the second one starts before u32::MAX
+    let list = VariableList::<Huge, U4>::new(vec![Huge, Huge]).unwrap();
+    assert_eq!(list.ssz_check_bounds(), Ok(()));
+    let mut buf = Vec::<u8>::new();
+    list.ssz_write(&mut buf);
+    assert_eq!(buf, [8, 0, 0, 0, 8, 0, 0, 0xc0]);
+}
@@ -0,0 +1,113 @@
+//! Lists of variable-size items check their whole offset table before decoding any item, and
+//! reject the same malformed tables with the same errors whichever backend they decode into.
+#![cfg(all(feature = "ssz-types", feature = "milhouse", feature = "ghilhouse"))]
+
+use milhouse::List;
+use ssz_types::VariableList;
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use typenum::{U1, U8};
+
+type Item = VariableList<u8, U1>;
+
+// three items of one byte: offsets 12, 13 and 14, then the items
+fn list() -> Vec<u8> {
+    let items = (1..=3).map(|i| Item::new(vec![i]).unwrap()).collect();
+    VariableList::<Item, U8>::new(items).unwrap().to_ssz()
+}
+
+fn write_offset(bytes: &mut [u8], i: usize, offset: u32) {
+    bytes[(i * 4)..(i * 4 + 4)].copy_from_slice(&offset.to_le_bytes());
+}
+
+fn assert_rejected(bytes: &[u8], err: DecodeError) {
+    let expected = Err(err);
+    assert_eq!(VariableList::<Item, U8>::from_ssz_bytes(bytes), expected);
+    assert_eq!(
+        List::<Item, U8>::from_ssz_bytes(bytes).map(drop),
+        expected.clone().map(drop)
+    );
+    assert_eq!(
+        ghilhouse::List::<Item, U8>::from_ssz_bytes(bytes).map(drop),
+        expected.clone().map(drop)
+    );
+    assert_eq!(
+        validate_ssz::<VariableList<Item, U8>>(bytes),
+        expected.clone().map(drop)
+    );
+
+    // decoding over an existing list goes through its own path, which has to agree
+    let mut decoded = VariableList::<Item, U8>::empty();
+    assert_eq!(decoded.decode_into(bytes), expected.map(drop));
+}
+
+#[test]
+fn test_well_formed() {
+    let bytes = list();
+    assert_eq!(bytes, [12, 0, 0, 0, 13, 0, 0, 0, 14, 0, 0, 0, 1, 2, 3]);
+    assert!(VariableList::<Item, U8>::from_ssz_bytes(&bytes).is_ok());
+    assert!(List::<Item, U8>::from_ssz_bytes(&bytes).is_ok());
+    assert!(ghilhouse::List::<Item, U8>::from_ssz_bytes(&bytes).is_ok());
+}
+
+#[test]
+fn test_first_offset() {
+    let mut unaligned = list();
+    write_offset(&mut unaligned, 0, 10);
+    assert_rejected(&unaligned, DecodeError::InvalidListFixedBytesLen(10));
+
+    let mut past_end = list();
+    write_offset(&mut past_end, 0, 16);
+    assert_rejected(&past_end, DecodeError::OffsetOutOfBounds(16));
+}
+
+#[test]
+fn test_offset_before_previous() {
+    let mut bytes = list();
+    write_offset(&mut bytes, 2, 12);
+    write_offset(&mut bytes, 1, 14);
+    assert_rejected(&bytes, DecodeError::OffsetsAreDecreasing(12).at_byte(8));
+}
+
+// an offset into the table is necessarily before the first offset, which is where the table ends
+#[test]
+fn test_offset_into_table() {
+    let mut bytes = list();
+    write_offset(&mut bytes, 1, 4);
+    assert_rejected(&bytes, DecodeError::OffsetsAreDecreasing(4).at_byte(4));
+
+    let mut bytes = list();
+    write_offset(&mut bytes, 2, 0);
+    assert_rejected(&bytes, DecodeError::OffsetsAreDecreasing(0).at_byte(8));
+}
+
+#[test]
+fn test_offset_past_end() {
+    let mut bytes = list();
+    write_offset(&mut bytes, 2, 16);
+    assert_rejected(&bytes, DecodeError::OffsetOutOfBounds(16).at_byte(8));
+
+    let mut bytes = list();
+    write_offset(&mut bytes, 1, u32::MAX);
+    assert_rejected(
+        &bytes,
+        DecodeError::OffsetOutOfBounds(u32::MAX as usize).at_byte(4),
+    );
+}
+
+// a bad offset is reported even when an item before it is invalid, since no item is decoded
+// until the whole table has been checked
+#[test]
+fn test_table_checked_before_items() {
+    let mut bytes = list();
+    // the first item takes two bytes, more than its maximum of one, and the last offset is bad
+    write_offset(&mut bytes, 1, 14);
+    write_offset(&mut bytes, 2, 13);
+    assert_rejected(&bytes, DecodeError::OffsetsAreDecreasing(13).at_byte(8));
+
+    // with the table intact, the item is to blame
+    write_offset(&mut bytes, 2, 14);
+    assert_rejected(
+        &bytes,
+        DecodeError::ListTooLong { len: 2, max: 1 }.at_byte(12),
+    );
+}
@@ -0,0 +1,256 @@
+use bytes::buf::Buf;
+use sszb::offsets::{
+    check_first_offset, check_list_first_offset, check_next_offset, check_offset_past_fixed,
+    offset_span, read_offset, read_offset_from_buf,
+};
+use sszb::DecodeError;
+
+#[test]
+fn test_read_offset() {
+    assert_eq!(read_offset(&[1, 2, 0, 0]), Ok(0x0201));
+    // only the first four bytes are read
+    assert_eq!(
+        read_offset(&[0xff, 0xff, 0xff, 0xff, 9]),
+        Ok(u32::MAX as usize)
+    );
+    assert_eq!(
+        read_offset(&[1, 2, 3]),
+        Err(DecodeError::InvalidLengthPrefix {
+            len: 3,
+            expected: 4
+        })
+    );
+
+    let mut buf = &[8, 0, 0, 0, 12, 0][..];
+    assert_eq!(read_offset_from_buf(&mut buf), Ok(8));
+    assert_eq!(buf.remaining(), 2);
+    // a short read leaves the buffer where it was
+    assert_eq!(
+        read_offset_from_buf(&mut buf),
+        Err(DecodeError::InvalidLengthPrefix {
+            len: 2,
+            expected: 4
+        })
+    );
+    assert_eq!(buf, [12, 0]);
+}
+
+#[test]
+fn test_first_offset() {
+    assert_eq!(check_first_offset(12, 12, 20), Ok(12));
+    // a container whose only variable field is empty
+    assert_eq!(check_first_offset(12, 12, 12), Ok(12));
+
+    assert_eq!(
+        check_first_offset(8, 12, 20),
+        Err(DecodeError::OffsetIntoFixedPortion(8))
+    );
+    assert_eq!(
+        check_first_offset(16, 12, 20),
+        Err(DecodeError::OffsetSkipsVariableBytes(16))
+    );
+    // pointing past the end is skipping bytes too, the first offset is only ever one value
+    assert_eq!(
+        check_first_offset(40, 12, 20),
+        Err(DecodeError::OffsetSkipsVariableBytes(40))
+    );
+    assert_eq!(
+        check_first_offset(12, 12, 8),
+        Err(DecodeError::OffsetOutOfBounds(12))
+    );
+}
+
+#[test]
+fn test_offset_past_fixed() {
+    assert_eq!(check_offset_past_fixed(12, 12, 20), Ok(12));
+    assert_eq!(check_offset_past_fixed(16, 12, 20), Ok(16));
+    assert_eq!(check_offset_past_fixed(20, 12, 20), Ok(20));
+
+    assert_eq!(
+        check_offset_past_fixed(11, 12, 20),
+        Err(DecodeError::OffsetIntoFixedPortion(11))
+    );
+    assert_eq!(
+        check_offset_past_fixed(21, 12, 20),
+        Err(DecodeError::OffsetOutOfBounds(21))
+    );
+}
+
+#[test]
+fn test_next_offset() {
+    assert_eq!(check_next_offset(16, 12, 20), Ok(16));
+    // an empty value between the two
+    assert_eq!(check_next_offset(12, 12, 20), Ok(12));
+    assert_eq!(check_next_offset(20, 12, 20), Ok(20));
+
+    assert_eq!(
+        check_next_offset(8, 12, 20),
+        Err(DecodeError::OffsetsAreDecreasing(8))
+    );
+    assert_eq!(
+        check_next_offset(24, 12, 20),
+        Err(DecodeError::OffsetOutOfBounds(24))
+    );
+    assert_eq!(
+        check_next_offset(usize::MAX, 12, 20),
+        Err(DecodeError::OffsetOutOfBounds(usize::MAX))
+    );
+}
+
+#[test]
+fn test_list_first_offset() {
+    // three offsets, then the items
+    assert_eq!(check_list_first_offset(12, 20), Ok(3));
+    // the items can all be empty
+    assert_eq!(check_list_first_offset(12, 12), Ok(3));
+
+    assert_eq!(
+        check_list_first_offset(6, 20),
+        Err(DecodeError::InvalidListFixedBytesLen(6))
+    );
+    assert_eq!(
+        check_list_first_offset(0, 20),
+        Err(DecodeError::InvalidListFixedBytesLen(0))
+    );
+    assert_eq!(
+        check_list_first_offset(4000, 40),
+        Err(DecodeError::OffsetOutOfBounds(4000))
+    );
+}
+
+#[test]
+fn test_offset_span() {
+    assert_eq!(offset_span(12, 20), Ok(8));
+    assert_eq!(offset_span(12, 12), Ok(0));
+    assert_eq!(
+        offset_span(12, 8),
+        Err(DecodeError::OffsetsAreDecreasing(8))
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_helpers() {
+    assert_eq!(sszb::read_offset_from_slice(&[4, 0, 0, 0]), Ok(4));
+
+    // the first offset of a container
+    assert_eq!(sszb::sanitize_offset(12, None, 20, Some(12)), Ok(12));
+    assert_eq!(
+        sszb::sanitize_offset(8, None, 20, Some(12)),
+        Err(DecodeError::OffsetIntoFixedPortion(8))
+    );
+    assert_eq!(
+        sszb::sanitize_offset(16, None, 20, Some(12)),
+        Err(DecodeError::OffsetSkipsVariableBytes(16))
+    );
+    // the ones after it
+    assert_eq!(sszb::sanitize_offset(16, Some(12), 20, Some(12)), Ok(16));
+    assert_eq!(
+        sszb::sanitize_offset(8, Some(12), 20, Some(4)),
+        Err(DecodeError::OffsetsAreDecreasing(8))
+    );
+    assert_eq!(
+        sszb::sanitize_offset(24, Some(12), 20, Some(12)),
+        Err(DecodeError::OffsetOutOfBounds(24))
+    );
+    assert_eq!(
+        sszb::sanitize_offset(24, None, 20, None),
+        Err(DecodeError::OffsetOutOfBounds(24))
+    );
+}
+
+#[cfg(feature = "ssz-types")]
+mod decoders {
+    use super::*;
+    use bytes::buf::BufMut;
+    use ssz_types::VariableList;
+    use sszb::{validate_ssz, SszbDecode, SszbEncode};
+    use sszb_derive::{SszbDecode, SszbEncode};
+    use typenum::U8;
+
+    type Bytes = VariableList<u8, U8>;
+
+    #[derive(Debug, PartialEq, SszbEncode, SszbDecode)]
+    struct Pair {
+        slot: u32,
+        a: Bytes,
+        b: Bytes,
+    }
+
+    fn pair() -> Vec<u8> {
+        Pair {
+            slot: 1,
+            a: Bytes::new(vec![2, 3]).unwrap(),
+            b: Bytes::new(vec![4]).unwrap(),
+        }
+        .to_ssz()
+    }
+
+    // every way of reading a container reports the same bad offset at the same byte
+    fn assert_rejected(bytes: &[u8], err: DecodeError) {
+        assert_eq!(Pair::from_ssz_bytes(bytes), Err(err.clone()));
+        assert_eq!(validate_ssz::<Pair>(bytes), Err(err.clone()));
+        assert_eq!(
+            sszb::container_field_ranges(bytes, &[(true, 4), (false, 4), (false, 4)]),
+            Err(err)
+        );
+    }
+
+    #[test]
+    fn test_container_offsets() {
+        let bytes = pair();
+        assert_eq!(bytes, [1, 0, 0, 0, 12, 0, 0, 0, 14, 0, 0, 0, 2, 3, 4]);
+
+        let mut into_fixed = bytes.clone();
+        into_fixed[4] = 8;
+        assert_rejected(
+            &into_fixed,
+            DecodeError::OffsetIntoFixedPortion(8).at_byte(4),
+        );
+
+        let mut skipping = bytes.clone();
+        skipping[4] = 13;
+        assert_rejected(
+            &skipping,
+            DecodeError::OffsetSkipsVariableBytes(13).at_byte(4),
+        );
+
+        let mut decreasing = bytes.clone();
+        decreasing[8] = 11;
+        assert_rejected(
+            &decreasing,
+            DecodeError::OffsetsAreDecreasing(11).at_byte(8),
+        );
+    }
+
+    #[test]
+    fn test_list_offsets() {
+        type Lists = VariableList<Bytes, U8>;
+        let list = Lists::new(vec![
+            Bytes::new(vec![1]).unwrap(),
+            Bytes::new(vec![2]).unwrap(),
+        ])
+        .unwrap()
+        .to_ssz();
+        assert_eq!(list, [8, 0, 0, 0, 9, 0, 0, 0, 1, 2]);
+
+        let mut unaligned = list.clone();
+        unaligned[0] = 6;
+        let err = DecodeError::InvalidListFixedBytesLen(6);
+        assert_eq!(Lists::from_ssz_bytes(&unaligned), Err(err.clone()));
+        assert_eq!(validate_ssz::<Lists>(&unaligned), Err(err.clone()));
+        assert_eq!(sszb::raw::peek_list_len_variable(&unaligned), Err(err));
+
+        let mut out_of_bounds = list.clone();
+        out_of_bounds[4] = 11;
+        let err = DecodeError::OffsetOutOfBounds(11).at_byte(4);
+        assert_eq!(Lists::from_ssz_bytes(&out_of_bounds), Err(err.clone()));
+        assert_eq!(validate_ssz::<Lists>(&out_of_bounds), Err(err));
+
+        let mut decreasing = list;
+        decreasing[4] = 7;
+        let err = DecodeError::OffsetsAreDecreasing(7).at_byte(4);
+        assert_eq!(Lists::from_ssz_bytes(&decreasing), Err(err.clone()));
+        assert_eq!(validate_ssz::<Lists>(&decreasing), Err(err));
+    }
+}
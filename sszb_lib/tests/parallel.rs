@@ -0,0 +1,203 @@
+#![cfg(feature = "ssz-types")]
+
+// the parallel paths give the same values, bytes and errors as the serial ones, on the rayon
+// pool with the `rayon` feature and on the calling thread without it
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{FixedVector, VariableList};
+use sszb::{
+    parallel::{SszbDecodeParallel, SszbEncodeParallel, PARALLEL_THRESHOLD},
+    DecodeError, SszbDecode, SszbEncode,
+};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U4096, U64, U65536};
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Vote {
+    validator: u64,
+    approve: bool,
+}
+
+const VOTE_LEN: usize = 9;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(parallel)]
+struct Registry {
+    epoch: u64,
+    #[ssz(parallel)]
+    votes: Votes,
+    roots: FixedVector<[u8; 32], U4096>,
+    #[ssz(parallel)]
+    messages: Messages,
+    checkpoint: Vote,
+}
+
+type Votes = VariableList<Vote, U65536>;
+type Messages = VariableList<VariableList<u8, U64>, U4096>;
+
+fn votes() -> Votes {
+    VariableList::new(
+        (0..10_000)
+            .map(|validator| Vote {
+                validator,
+                approve: validator % 3 == 0,
+            })
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn messages() -> Messages {
+    VariableList::new(
+        (0..2_000)
+            .map(|i| VariableList::new(vec![i as u8; 40]).unwrap())
+            .collect(),
+    )
+    .unwrap()
+}
+
+fn read_offset(bytes: &[u8], i: usize) -> usize {
+    sszb::offsets::read_offset(&bytes[(i * sszb::BYTES_PER_LENGTH_OFFSET)..]).unwrap()
+}
+
+fn write_offset(bytes: &mut [u8], i: usize, offset: usize) {
+    let at = i * sszb::BYTES_PER_LENGTH_OFFSET;
+    bytes[at..(at + 4)].copy_from_slice(&(offset as u32).to_le_bytes());
+}
+
+#[test]
+fn test_static_list_matches_serial() {
+    let votes = votes();
+    let bytes = votes.to_ssz();
+    assert!(bytes.len() >= PARALLEL_THRESHOLD);
+
+    assert_eq!(Votes::from_ssz_bytes_parallel(&bytes), Ok(votes));
+    // `ssz_read_packed` is the serial path
+    for len in [
+        bytes.len() - 1,
+        bytes.len() - VOTE_LEN,
+        PARALLEL_THRESHOLD + 1,
+    ] {
+        assert_eq!(
+            Votes::from_ssz_bytes_parallel(&bytes[..len]).map(|votes| votes.to_vec()),
+            Vote::ssz_read_packed(&bytes[..len])
+        );
+        assert_eq!(
+            Votes::from_ssz_bytes_parallel(&bytes[..len]),
+            Votes::from_ssz_bytes(&bytes[..len])
+        );
+    }
+}
+
+#[test]
+fn test_static_list_first_error_wins() {
+    let mut bytes = votes().to_ssz();
+    // two invalid bools in different runs, the first one has to be reported no matter which
+    // run is decoded first
+    bytes[9_000 * VOTE_LEN + 8] = 2;
+    bytes[3_000 * VOTE_LEN + 8] = 2;
+
+    let err = Votes::from_ssz_bytes_parallel(&bytes).unwrap_err();
+    assert_eq!(Err(err.clone()), Vote::ssz_read_packed(&bytes));
+    assert_eq!(Err(err.clone()), Votes::from_ssz_bytes(&bytes));
+    assert_eq!(err.position(), Some(3_000 * VOTE_LEN + 8));
+}
+
+#[test]
+fn test_variable_list_matches_serial() {
+    let messages = messages();
+    let bytes = messages.to_ssz();
+    assert!(bytes.len() >= PARALLEL_THRESHOLD);
+
+    assert_eq!(Messages::from_ssz_bytes_parallel(&bytes), Ok(messages));
+}
+
+#[test]
+fn test_variable_list_first_error_wins() {
+    let mut bytes = messages().to_ssz();
+    // make item 100 longer than its limit of 64 bytes, and make item 1499 end before it starts
+    let item_start = read_offset(&bytes, 100);
+    let last_start = read_offset(&bytes, 1_500);
+    write_offset(&mut bytes, 101, item_start + 70);
+    write_offset(&mut bytes, 1_500, item_start);
+
+    // the offset table is checked in full before any item is decoded, so the bad offset wins
+    assert_eq!(
+        Messages::from_ssz_bytes_parallel(&bytes),
+        Err(DecodeError::OffsetsAreDecreasing(item_start).at_byte(1_500 * 4))
+    );
+    assert_eq!(
+        Messages::from_ssz_bytes_parallel(&bytes),
+        Messages::from_ssz_bytes(&bytes)
+    );
+
+    // with the table intact, the first bad item is reported
+    write_offset(&mut bytes, 1_500, last_start);
+    let err = Messages::from_ssz_bytes_parallel(&bytes).unwrap_err();
+    assert_eq!(Err(err.clone()), Messages::from_ssz_bytes(&bytes));
+    assert_eq!(
+        err.root_cause(),
+        &DecodeError::ListTooLong { len: 70, max: 64 }
+    );
+    assert_eq!(err.position(), Some(item_start));
+}
+
+fn assert_parallel_write<T: SszbEncodeParallel>(item: &T) {
+    let mut buf = vec![0u8; item.sszb_bytes_len()];
+    item.ssz_write_parallel(&mut buf);
+    assert_eq!(buf, item.to_ssz());
+}
+
+#[test]
+fn test_parallel_write_matches_serial() {
+    let registry = Registry {
+        epoch: 3,
+        votes: votes(),
+        roots: FixedVector::new((0..4096).map(|i| [i as u8; 32]).collect()).unwrap(),
+        messages: messages(),
+        checkpoint: Vote {
+            validator: 1,
+            approve: true,
+        },
+    };
+    assert!(registry.sszb_bytes_len() >= PARALLEL_THRESHOLD);
+
+    assert_parallel_write(&registry.votes);
+    assert_parallel_write(&registry.roots);
+    assert_parallel_write(&registry.messages);
+    assert_parallel_write(&registry);
+    assert_eq!(
+        Registry::from_ssz_bytes_parallel(&registry.to_ssz()),
+        Ok(registry)
+    );
+}
+
+#[test]
+fn test_parallel_container_first_error_wins() {
+    let registry = Registry {
+        epoch: 3,
+        votes: votes(),
+        roots: FixedVector::new(vec![[0; 32]; 4096]).unwrap(),
+        messages: messages(),
+        checkpoint: Vote {
+            validator: 1,
+            approve: true,
+        },
+    };
+    let mut bytes = registry.to_ssz();
+    // an invalid bool in the votes, and one in the checkpoint, which is read first since it's in
+    // the fixed section
+    let votes_start = read_offset(&bytes[8..], 0);
+    bytes[votes_start + 3_000 * VOTE_LEN + 8] = 2;
+    let checkpoint_end = 8 + 4 + 4096 * 32 + 4 + VOTE_LEN;
+    bytes[checkpoint_end - 1] = 2;
+
+    let err = Registry::from_ssz_bytes_parallel(&bytes).unwrap_err();
+    assert_eq!(Err(err.clone()), Registry::from_ssz_bytes(&bytes));
+    assert_eq!(err.position(), Some(checkpoint_end - 1));
+    // a truncated container fails the same way too
+    assert_eq!(
+        Registry::from_ssz_bytes_parallel(&bytes[..100]),
+        Registry::from_ssz_bytes(&bytes[..100])
+    );
+}
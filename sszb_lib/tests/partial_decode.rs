@@ -0,0 +1,136 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U16;
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct Body {
+    graffiti: [u8; 32],
+    deposits: VariableList<u64, U16>,
+    exits: VariableList<bool, U16>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Header {
+    slot: u64,
+    proposer_index: u64,
+    body: Body,
+    parent_root: [u8; 32],
+    flags: VariableList<bool, U16>,
+}
+
+// the fixed portion: slot, proposer_index, body offset, parent_root, flags offset
+const FIXED_LEN: usize = 8 + 8 + 4 + 32 + 4;
+
+fn header() -> Header {
+    Header {
+        slot: 1234,
+        proposer_index: 7,
+        body: Body {
+            graffiti: [9; 32],
+            deposits: VariableList::new(vec![1, 2, 3]).unwrap(),
+            exits: VariableList::new(vec![true, false]).unwrap(),
+        },
+        parent_root: [5; 32],
+        flags: VariableList::new(vec![false, true, true]).unwrap(),
+    }
+}
+
+#[test]
+fn test_fields_match_full_decode() {
+    let bytes = header().to_ssz();
+    let header = Header::from_ssz_bytes(&bytes).unwrap();
+
+    assert_eq!(Header::decode_field_slot(&bytes), Ok(header.slot));
+    assert_eq!(
+        Header::decode_field_proposer_index(&bytes),
+        Ok(header.proposer_index)
+    );
+    assert_eq!(Header::decode_field_body(&bytes), Ok(header.body.clone()));
+    assert_eq!(
+        Header::decode_field_parent_root(&bytes),
+        Ok(header.parent_root)
+    );
+    assert_eq!(Header::decode_field_flags(&bytes), Ok(header.flags));
+
+    assert_eq!(Header::field_range(&bytes, 0), Ok(0..8));
+    assert_eq!(Header::field_range(&bytes, 3), Ok(20..52));
+    assert_eq!(
+        Header::field_range(&bytes, 2),
+        Ok(FIXED_LEN..(FIXED_LEN + header.body.sszb_bytes_len()))
+    );
+    assert_eq!(
+        Header::field_range(&bytes, 4),
+        Ok((FIXED_LEN + header.body.sszb_bytes_len())..bytes.len())
+    );
+    assert!(Header::field_range(&bytes, 5).is_err());
+}
+
+#[test]
+fn test_nested_field() {
+    let bytes = header().to_ssz();
+    let body = Header::field_range(&bytes, 2).unwrap();
+
+    assert_eq!(
+        Body::decode_field_deposits(&bytes[body.clone()]),
+        Ok(VariableList::new(vec![1, 2, 3]).unwrap())
+    );
+    assert_eq!(Body::decode_field_graffiti(&bytes[body]), Ok([9; 32]));
+}
+
+#[test]
+fn test_corruption_elsewhere() {
+    let header = header();
+    let bytes = header.to_ssz();
+
+    // the last flag is no longer a valid boolean
+    let mut bad_flag = bytes.clone();
+    *bad_flag.last_mut().unwrap() = 2;
+    assert!(Header::from_ssz_bytes(&bad_flag).is_err());
+    assert_eq!(Header::decode_field_slot(&bad_flag), Ok(header.slot));
+    assert_eq!(
+        Header::decode_field_body(&bad_flag),
+        Ok(header.body.clone())
+    );
+    assert_eq!(
+        Header::decode_field_parent_root(&bad_flag),
+        Ok(header.parent_root)
+    );
+
+    // the first offset inside the body points back into its fixed portion
+    let mut bad_body = bytes.clone();
+    bad_body[FIXED_LEN + 32] = 0;
+    assert!(Header::from_ssz_bytes(&bad_body).is_err());
+    assert!(Header::decode_field_body(&bad_body).is_err());
+    assert_eq!(Header::decode_field_slot(&bad_body), Ok(header.slot));
+    assert_eq!(Header::decode_field_flags(&bad_body), Ok(header.flags));
+}
+
+#[test]
+fn test_errors_match_full_decode() {
+    let bytes = header().to_ssz();
+
+    // the body offset skips a byte of the variable portion
+    let mut bad_offset = bytes.clone();
+    bad_offset[16] += 1;
+    assert_eq!(
+        Header::decode_field_body(&bad_offset).err(),
+        Header::from_ssz_bytes(&bad_offset).err()
+    );
+    assert_eq!(
+        Header::decode_field_body(&bad_offset),
+        Err(DecodeError::OffsetSkipsVariableBytes(FIXED_LEN + 1).at_byte(16))
+    );
+
+    // too short to hold the fixed portion
+    assert_eq!(
+        Header::decode_field_slot(&bytes[..FIXED_LEN - 1]),
+        Err(DecodeError::InvalidByteLength {
+            len: FIXED_LEN - 1,
+            expected: FIXED_LEN
+        })
+    );
+}
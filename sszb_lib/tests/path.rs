@@ -0,0 +1,233 @@
+#![cfg(feature = "test-utils")]
+
+use sszb::dynamic::SszTypeInfo;
+use sszb::path::{get_raw, get_raw_with_schema, get_typed, PathError};
+use sszb::test_utils::beacon_block::{Checkpoint, Transaction};
+use sszb::test_utils::{signed_beacon_block, BlockConfig, SignedBeaconBlock};
+use sszb::{SszbDecode, SszbEncode};
+
+fn block() -> (SignedBeaconBlock, Vec<u8>) {
+    let config = BlockConfig {
+        attestations: 8,
+        transactions: 10,
+        transaction_bytes: 5_000,
+    };
+    signed_beacon_block(3, &config)
+}
+
+#[test]
+fn test_static_leaves() {
+    let (block, bytes) = block();
+    let message = &block.message;
+
+    assert_eq!(
+        get_typed::<SignedBeaconBlock, u64>(&bytes, "message.slot"),
+        Ok(message.slot)
+    );
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, "message.slot"),
+        Ok(&message.slot.to_ssz()[..])
+    );
+
+    let target = "message.body.attestations[3].data.target";
+    assert_eq!(
+        get_typed::<SignedBeaconBlock, Checkpoint>(&bytes, target).as_ref(),
+        Ok(&message.body.attestations[3].data.target)
+    );
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, &format!("{}.root", target)),
+        Ok(&message.body.attestations[3].data.target.root[..])
+    );
+}
+
+#[test]
+fn test_variable_leaves() {
+    let (block, bytes) = block();
+    let transactions = &block.message.body.execution_payload.transactions;
+
+    for (i, transaction) in transactions.iter().enumerate() {
+        let path = format!("message.body.execution_payload.transactions[{}]", i);
+        assert_eq!(
+            get_raw::<SignedBeaconBlock>(&bytes, &path),
+            Ok(&transaction[..])
+        );
+        assert_eq!(
+            get_typed::<SignedBeaconBlock, Transaction>(&bytes, &path).as_ref(),
+            Ok(transaction)
+        );
+    }
+
+    // a byte of a transaction is an item like any other
+    let (i, transaction) = transactions
+        .iter()
+        .enumerate()
+        .find(|(_, transaction)| transaction.len() > 1)
+        .unwrap();
+    let path = format!("message.body.execution_payload.transactions[{}][1]", i);
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, &path),
+        Ok(&transaction[1..2])
+    );
+
+    // the whole object, and whole lists
+    assert_eq!(get_raw::<SignedBeaconBlock>(&bytes, ""), Ok(&bytes[..]));
+    let attestations = get_raw::<SignedBeaconBlock>(&bytes, "message.body.attestations").unwrap();
+    assert_eq!(attestations, &block.message.body.attestations.to_ssz()[..]);
+}
+
+#[test]
+fn test_indices_out_of_range() {
+    let (block, bytes) = block();
+    let len = block.message.body.execution_payload.transactions.len();
+
+    // past the transactions this block has, but within the list's maximum
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, "message.body.execution_payload.transactions[10].x"),
+        Err(PathError::IndexOutOfRange {
+            path: "message.body.execution_payload.transactions[10]".into(),
+            index: 10,
+            len,
+        })
+    );
+    // past the maximum, no block has it
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, "message.body.blob_kzg_commitments[4096]"),
+        Err(PathError::NotInSchema(
+            "message.body.blob_kzg_commitments[4096]".into()
+        ))
+    );
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, "message.parent_root[32]"),
+        Err(PathError::NotInSchema("message.parent_root[32]".into()))
+    );
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, "message.parent_root[31]"),
+        Ok(&block.message.parent_root[31..])
+    );
+}
+
+#[test]
+fn test_paths_not_in_schema() {
+    let (_, bytes) = block();
+    for (path, error) in [
+        ("message.bdy.graffiti", "message.bdy"),
+        ("message.slot.epoch", "message.slot.epoch"),
+        ("message[0]", "message[0]"),
+        (
+            "message.body.attestations.len",
+            "message.body.attestations.len",
+        ),
+    ] {
+        assert_eq!(
+            get_raw::<SignedBeaconBlock>(&bytes, path),
+            Err(PathError::NotInSchema(error.into())),
+            "{}",
+            path
+        );
+    }
+
+    for path in [
+        "message..slot",
+        "message.slot[",
+        "message.slot[x]",
+        "a]",
+        "[1]b",
+        ".",
+    ] {
+        assert_eq!(
+            get_raw::<SignedBeaconBlock>(&bytes, path),
+            Err(PathError::Syntax(path.into())),
+            "{}",
+            path
+        );
+    }
+}
+
+#[test]
+fn test_malformed_bytes() {
+    let (_, mut bytes) = block();
+    // the message starts after its offset and the signature, and the body's offset follows the
+    // slot, proposer index and two roots
+    let body_offset_at = 4 + 96 + 8 + 8 + 32 + 32;
+    bytes[body_offset_at..(body_offset_at + 4)].copy_from_slice(&3u32.to_le_bytes());
+
+    let error = SignedBeaconBlock::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(error.position(), Some(body_offset_at));
+    assert_eq!(
+        get_raw::<SignedBeaconBlock>(&bytes, "message.body.graffiti"),
+        Err(PathError::Malformed {
+            path: "message.body".into(),
+            error,
+        })
+    );
+    // fields before the bad offset can still be found
+    assert!(get_raw::<SignedBeaconBlock>(&bytes, "signature").is_ok());
+
+    // a truncated static object
+    let checkpoint = Checkpoint {
+        epoch: 1,
+        root: Default::default(),
+    }
+    .to_ssz();
+    assert_eq!(
+        get_raw::<Checkpoint>(&checkpoint[..39], "epoch"),
+        Err(PathError::Malformed {
+            path: "".into(),
+            error: Checkpoint::from_ssz_bytes(&checkpoint[..39]).unwrap_err(),
+        })
+    );
+}
+
+#[test]
+fn test_unions() {
+    let schema = SszTypeInfo::Container(vec![
+        ("slot".into(), SszTypeInfo::Uint(8)),
+        (
+            "payload".into(),
+            SszTypeInfo::Union(vec![
+                None,
+                Some(SszTypeInfo::list(SszTypeInfo::Uint(2), 4)),
+                Some(SszTypeInfo::Container(vec![(
+                    "root".into(),
+                    SszTypeInfo::vector(SszTypeInfo::Uint(1), 4),
+                )])),
+            ]),
+        ),
+    ]);
+
+    // the offset of the union, then its selector and a list of two u16s
+    let mut bytes = vec![7, 0, 0, 0, 0, 0, 0, 0, 12, 0, 0, 0, 1];
+    bytes.extend_from_slice(&[5, 0, 6, 0]);
+    assert_eq!(
+        get_raw_with_schema(&schema, &bytes, "payload[1]"),
+        Ok(&[6, 0][..])
+    );
+    assert_eq!(
+        get_raw_with_schema(&schema, &bytes, "payload"),
+        Ok(&bytes[12..])
+    );
+    assert_eq!(
+        get_raw_with_schema(&schema, &bytes, "payload.root"),
+        Err(PathError::NotInSchema("payload.root".into()))
+    );
+
+    let mut container = bytes[..12].to_vec();
+    container.extend_from_slice(&[2, 9, 8, 7, 6]);
+    assert_eq!(
+        get_raw_with_schema(&schema, &container, "payload.root[2]"),
+        Ok(&[7][..])
+    );
+
+    let mut empty = bytes[..12].to_vec();
+    empty.push(0);
+    assert_eq!(
+        get_raw_with_schema(&schema, &empty, "payload.root"),
+        Err(PathError::EmptyVariant("payload".into()))
+    );
+
+    empty[12] = 3;
+    assert!(matches!(
+        get_raw_with_schema(&schema, &empty, "payload.root"),
+        Err(PathError::Malformed { path, .. }) if path == "payload"
+    ));
+}
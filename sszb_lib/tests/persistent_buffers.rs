@@ -0,0 +1,83 @@
+#![cfg(all(feature = "ssz-types", feature = "milhouse"))]
+
+use bytes::Buf;
+use milhouse::{List, Vector};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use typenum::{U16, U4, U64};
+
+type Transaction = VariableList<u8, U16>;
+
+fn transactions() -> Vec<Transaction> {
+    (0..4)
+        .map(|i| VariableList::new(vec![i as u8; i * 3]).unwrap())
+        .collect()
+}
+
+// a variable value gets all of its bytes in variable_bytes, here split into two chunks at `at`
+fn read_variable_chained<T: SszbDecode>(bytes: &[u8], at: usize) -> Result<T, DecodeError> {
+    let (front, back) = bytes.split_at(at);
+    let mut variable_bytes = front.chain(back);
+    let value = T::ssz_read(&mut &[0u8; 0][..], &mut variable_bytes);
+    assert!(!variable_bytes.has_remaining());
+    value
+}
+
+#[test]
+fn test_lists_from_chained_buffers() {
+    let list = List::<u64, U64>::try_from_iter(0..20).unwrap();
+    let bytes = list.to_ssz();
+    for at in 0..=bytes.len() {
+        assert_eq!(read_variable_chained(&bytes, at), Ok(list.clone()));
+    }
+
+    let list = List::<Transaction, U4>::try_from_iter(transactions().into_iter()).unwrap();
+    let bytes = list.to_ssz();
+    // splits inside the offsets, between them and the items, and inside the items
+    for at in 0..=bytes.len() {
+        assert_eq!(read_variable_chained(&bytes, at), Ok(list.clone()));
+    }
+    assert_eq!(List::from_ssz_bytes(&bytes), Ok(list));
+}
+
+#[test]
+fn test_vectors_from_chained_buffers() {
+    let vector = Vector::<Transaction, U4>::try_from_iter(transactions().into_iter()).unwrap();
+    let bytes = vector.to_ssz();
+    for at in 0..=bytes.len() {
+        assert_eq!(read_variable_chained(&bytes, at), Ok(vector.clone()));
+    }
+
+    // a static vector takes only its own bytes from fixed_bytes, leaving the rest for what follows
+    let vector = Vector::<u64, U4>::try_from_iter(10..14).unwrap();
+    let mut bytes = vector.to_ssz();
+    bytes.extend(7u32.to_ssz());
+    for at in 0..=bytes.len() {
+        let (front, back) = bytes.split_at(at);
+        let mut fixed_bytes = front.chain(back);
+        let decoded = Vector::<u64, U4>::ssz_read(&mut fixed_bytes, &mut &[0u8; 0][..]);
+        assert_eq!(decoded, Ok(vector.clone()));
+        assert_eq!(u32::ssz_read(&mut fixed_bytes, &mut &[0u8; 0][..]), Ok(7));
+    }
+}
+
+#[test]
+fn test_chained_errors_match_slices() {
+    let list = List::<Transaction, U4>::try_from_iter(transactions().into_iter()).unwrap();
+    let mut bytes = list.to_ssz();
+    // the second offset points before the first
+    bytes[4] = 0;
+    let err = List::<Transaction, U4>::from_ssz_bytes(&bytes).unwrap_err();
+    for at in 0..=bytes.len() {
+        assert_eq!(
+            read_variable_chained::<List<Transaction, U4>>(&bytes, at),
+            Err(err.clone())
+        );
+    }
+
+    let bytes = List::<u64, U64>::try_from_iter(0..20).unwrap().to_ssz();
+    assert_eq!(
+        read_variable_chained::<List<u64, U4>>(&bytes, 12),
+        Err(DecodeError::ListTooLong { len: 20, max: 4 })
+    );
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "milhouse")]
+
+use milhouse::{List, Vector};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use typenum::{U1048576, U64};
+
+// built the slow way, one push at a time
+fn pushed<T: milhouse::Value>(items: &[T]) -> List<T, U1048576> {
+    let mut list = List::empty();
+    for item in items {
+        list.push(item.clone()).unwrap();
+    }
+    list.apply_updates().unwrap();
+    list
+}
+
+#[test]
+fn test_decoded_lists_match_pushed() {
+    // lengths around the packing of u64s (4 to a leaf) and u8s (32 to a leaf)
+    for len in [0, 1, 3, 4, 5, 31, 32, 33, 1000, 70_000] {
+        let items = (0..len as u64).map(|i| i * 3).collect::<Vec<_>>();
+        let list = pushed(&items);
+        let decoded = List::<u64, U1048576>::from_ssz_bytes(&list.to_ssz()).unwrap();
+        assert_eq!(decoded, list);
+        assert_eq!(decoded, List::try_from_iter(items.iter().copied()).unwrap());
+        assert_eq!(decoded.iter().copied().collect::<Vec<_>>(), items);
+
+        let bytes = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+        let decoded = List::<u8, U1048576>::from_ssz_bytes(&bytes).unwrap();
+        assert_eq!(decoded, pushed(&bytes));
+    }
+}
+
+#[test]
+fn test_decoded_vectors_match_built() {
+    let items = (0..64u64).collect::<Vec<_>>();
+    let vector = Vector::<u64, U64>::try_from_iter(items.iter().copied()).unwrap();
+    let decoded = Vector::<u64, U64>::from_ssz_bytes(&vector.to_ssz()).unwrap();
+    assert_eq!(decoded, vector);
+}
+
+// the items are decoded a few kilobytes at a time, and an invalid one is still reported where it
+// is, in whichever run it falls
+#[test]
+fn test_invalid_item_position() {
+    for position in [0, 4095, 4096, 9_000] {
+        let mut bytes = vec![1u8; 10_000];
+        bytes[position] = 2;
+        let err = List::<bool, U1048576>::from_ssz_bytes(&bytes).unwrap_err();
+        assert_eq!(err.root_cause(), &DecodeError::InvalidBool(2));
+        assert_eq!(err.position(), Some(position));
+    }
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "ssz-types")]
+
+// placeholder fields keep their place in the encoding, but what's written there is their
+// placeholder, and what's read back is too
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{validate_ssz, vectored::Segments, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U4, U8};
+
+type Bits = VariableList<u8, U8>;
+type Legacy = VariableList<u8, U4>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Attestation {
+    slot: u64,
+    // a cached signature, always written as zeros
+    #[ssz(placeholder)]
+    signature: [u8; 32],
+    bits: Bits,
+    // a deprecated field that other implementations still expect
+    #[ssz(placeholder = "legacy_root")]
+    legacy: Legacy,
+}
+
+fn legacy_root() -> Legacy {
+    VariableList::new(vec![0xaa, 0xbb]).unwrap()
+}
+
+// the same layout, with every field encoded
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RawAttestation {
+    slot: u64,
+    signature: [u8; 32],
+    bits: Bits,
+    legacy: Legacy,
+}
+
+fn attestation() -> Attestation {
+    Attestation {
+        slot: 3,
+        signature: [9; 32],
+        bits: VariableList::new(vec![1, 2, 3]).unwrap(),
+        legacy: VariableList::new(vec![4]).unwrap(),
+    }
+}
+
+fn raw(signature: [u8; 32], legacy: Legacy) -> RawAttestation {
+    RawAttestation {
+        slot: 3,
+        signature,
+        bits: VariableList::new(vec![1, 2, 3]).unwrap(),
+        legacy,
+    }
+}
+
+#[test]
+fn test_encode_placeholders() {
+    let bytes = attestation().to_ssz();
+    let expected = raw([0; 32], legacy_root()).to_ssz();
+
+    // the layout and the offsets are those of the fully encoded container
+    assert_eq!(
+        <Attestation as SszbEncode>::SSZ_MAX_LEN,
+        <RawAttestation as SszbEncode>::SSZ_MAX_LEN
+    );
+    assert_eq!(attestation().sszb_bytes_len(), expected.len());
+    assert_eq!(bytes, expected);
+    assert_eq!(bytes[8..40], [0; 32]);
+    assert_eq!(bytes[40..44], 48u32.to_le_bytes());
+    assert_eq!(bytes[44..48], 51u32.to_le_bytes());
+    assert_eq!(bytes[51..], [0xaa, 0xbb]);
+
+    let mut buf = vec![0u8; bytes.len()];
+    assert_eq!(attestation().ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(buf, bytes);
+    let value = attestation();
+    let mut segments = Segments::new();
+    value.ssz_write_vectored(&mut segments);
+    assert_eq!(segments.flatten(), bytes);
+}
+
+#[test]
+fn test_decode_placeholders() {
+    // whatever is in their place, placeholder fields decode as their placeholder
+    let bytes = raw([5; 32], VariableList::new(vec![6, 7, 8]).unwrap()).to_ssz();
+    let decoded = Attestation {
+        signature: [0; 32],
+        legacy: legacy_root(),
+        ..attestation()
+    };
+    assert_eq!(Attestation::from_ssz_bytes(&bytes), Ok(decoded));
+    assert_eq!(
+        Attestation::from_ssz_bytes_trusted(&bytes),
+        Ok(Attestation {
+            signature: [0; 32],
+            legacy: legacy_root(),
+            ..attestation()
+        })
+    );
+    assert_eq!(validate_ssz::<Attestation>(&bytes), Ok(()));
+    assert_eq!(Attestation::decode_field_signature(&bytes), Ok([0; 32]));
+    assert_eq!(Attestation::decode_field_legacy(&bytes), Ok(legacy_root()));
+
+    let mut value = attestation();
+    value.decode_into(&bytes).unwrap();
+    assert_eq!(value.signature, [0; 32]);
+    assert_eq!(value.legacy, legacy_root());
+
+    // and a round trip gives back the placeholders
+    let roundtrip = Attestation::from_ssz_bytes(&attestation().to_ssz()).unwrap();
+    assert_eq!(roundtrip.signature, [0; 32]);
+    assert_eq!(roundtrip.legacy, legacy_root());
+    assert_eq!(roundtrip.bits, attestation().bits);
+}
+
+// the bytes in a placeholder's place still have to decode as the field's type
+#[test]
+fn test_invalid_placeholder_bytes() {
+    let mut bytes = raw([0; 32], legacy_root()).to_ssz();
+    bytes.extend([1, 2, 3]);
+    let err = Attestation::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        err.root_cause(),
+        &DecodeError::ListTooLong { len: 5, max: 4 }
+    );
+    assert_eq!(err.position(), Some(51));
+    assert_eq!(validate_ssz::<Attestation>(&bytes), Err(err));
+}
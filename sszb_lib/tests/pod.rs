@@ -0,0 +1,185 @@
+#![cfg(all(feature = "pod", feature = "ssz-types"))]
+
+use bytemuck::{Pod, Zeroable};
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use typenum::U8;
+
+#[derive(Clone, Copy, PartialEq, Debug, Pod, Zeroable, SszbEncode, SszbDecode)]
+#[repr(C)]
+#[ssz(pod)]
+struct TrackingRecord {
+    slot: u64,
+    index: u64,
+    root: [u8; 32],
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Pod, Zeroable, SszbEncode, SszbDecode)]
+#[repr(C)]
+#[ssz(pod)]
+struct Mixed {
+    a: u32,
+    b: u16,
+    c: [u8; 2],
+    d: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Pod, Zeroable, SszbEncode, SszbDecode)]
+#[repr(C)]
+#[ssz(pod)]
+struct Nested {
+    record: TrackingRecord,
+    mixed: Mixed,
+    epoch: u64,
+}
+
+// plain old data, but without #[ssz(pod)], so a container holding it has to fall back
+#[derive(Clone, Copy, PartialEq, Debug, Pod, Zeroable, SszbEncode, SszbDecode)]
+#[repr(C)]
+struct Plain {
+    x: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Pod, Zeroable, SszbEncode, SszbDecode)]
+#[repr(C)]
+#[ssz(pod)]
+struct HoldsPlain {
+    plain: Plain,
+    y: u64,
+}
+
+// the same layouts, derived the regular way
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RegularRecord {
+    slot: u64,
+    index: u64,
+    root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RegularMixed {
+    a: u32,
+    b: u16,
+    c: [u8; 2],
+    d: u64,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RegularNested {
+    record: RegularRecord,
+    mixed: RegularMixed,
+    epoch: u64,
+}
+
+fn record(i: u64) -> (TrackingRecord, RegularRecord) {
+    let root = [i as u8 ^ 0x5a; 32];
+    (
+        TrackingRecord {
+            slot: i * 0x0102_0304_0506,
+            index: !i,
+            root,
+        },
+        RegularRecord {
+            slot: i * 0x0102_0304_0506,
+            index: !i,
+            root,
+        },
+    )
+}
+
+fn mixed(i: u32) -> (Mixed, RegularMixed) {
+    (
+        Mixed {
+            a: i * 0x0102_0304,
+            b: i as u16 ^ 0xbeef,
+            c: [i as u8, 0xff],
+            d: u64::MAX - i as u64,
+        },
+        RegularMixed {
+            a: i * 0x0102_0304,
+            b: i as u16 ^ 0xbeef,
+            c: [i as u8, 0xff],
+            d: u64::MAX - i as u64,
+        },
+    )
+}
+
+// the pod container has to write every byte the regular derive does, and read them back
+fn assert_same_encoding<P, R>(pod: P, regular: &R)
+where
+    P: SszbEncode + SszbDecode + PartialEq + Debug,
+    R: SszbEncode,
+{
+    let bytes = regular.to_ssz();
+    assert_eq!(pod.to_ssz(), bytes);
+    assert_eq!(pod.sszb_bytes_len(), bytes.len());
+
+    let mut buf = vec![0xaa; bytes.len() + 1];
+    assert_eq!(pod.ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(&buf[..bytes.len()], &bytes[..]);
+
+    assert_eq!(P::from_ssz_bytes(&bytes), Ok(pod));
+    assert!(P::from_ssz_bytes(&bytes[1..]).is_err());
+}
+
+#[test]
+fn test_pod_matches_regular_derive() {
+    for i in [0, 1, 7, 1000] {
+        let (pod, regular) = record(i);
+        assert_same_encoding(pod, &regular);
+
+        let (pod, regular) = mixed(i as u32);
+        assert_same_encoding(pod, &regular);
+
+        let ((record, regular_record), (mixed, regular_mixed)) = (record(i), mixed(i as u32));
+        let nested = Nested {
+            record,
+            mixed,
+            epoch: i,
+        };
+        let regular = RegularNested {
+            record: regular_record,
+            mixed: regular_mixed,
+            epoch: i,
+        };
+        assert_same_encoding(nested, &regular);
+    }
+}
+
+#[test]
+fn test_pod_lists_match_regular_derive() {
+    let (pods, regulars): (Vec<_>, Vec<_>) = (0..5).map(record).unzip();
+    let pods = VariableList::<TrackingRecord, U8>::new(pods).unwrap();
+    let regulars = VariableList::<RegularRecord, U8>::new(regulars).unwrap();
+
+    let bytes = regulars.to_ssz();
+    assert_eq!(pods.to_ssz(), bytes);
+    assert_eq!(
+        VariableList::<TrackingRecord, U8>::from_ssz_bytes(&bytes),
+        Ok(pods)
+    );
+    assert!(VariableList::<TrackingRecord, U8>::from_ssz_bytes(&bytes[1..]).is_err());
+}
+
+#[test]
+fn test_pod_layouts() {
+    let little_endian = cfg!(target_endian = "little");
+    assert_eq!(<TrackingRecord as SszbEncode>::SSZ_POD, little_endian);
+    assert_eq!(<Mixed as SszbDecode>::SSZ_POD, little_endian);
+    assert_eq!(<Nested as SszbEncode>::SSZ_POD, little_endian);
+    assert_eq!(<Nested as SszbEncode>::SSZ_FIXED_LEN, 48 + 16 + 8);
+
+    // a field that isn't known to be plain old data falls back to the regular code
+    assert!(!<HoldsPlain as SszbEncode>::SSZ_POD);
+    assert!(!<HoldsPlain as SszbDecode>::SSZ_POD);
+    let value = HoldsPlain {
+        plain: Plain { x: 3 },
+        y: 4,
+    };
+    let bytes = [3u64.to_le_bytes(), 4u64.to_le_bytes()].concat();
+    assert_eq!(value.to_ssz(), bytes);
+    assert_eq!(HoldsPlain::from_ssz_bytes(&bytes), Ok(value));
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "ssz-types")]
+
+use ssz_types::VariableList;
+use sszb::raw::{iter_offsets, peek_list_len_static, peek_list_len_variable};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+
+type ByteList = VariableList<u8, typenum::U4>;
+type NestedList = VariableList<ByteList, typenum::U3>;
+
+fn nested(lens: &[usize]) -> NestedList {
+    NestedList::new(
+        lens.iter()
+            .map(|len| ByteList::new(vec![7u8; *len]).unwrap())
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_peek_empty_lists() {
+    assert_eq!(peek_list_len_static(&[], 8), Ok(0));
+    assert_eq!(peek_list_len_variable(&[]), Ok(0));
+    assert_eq!(iter_offsets(&[]).count(), 0);
+}
+
+#[test]
+fn test_peek_max_length_lists() {
+    let list = VariableList::<u16, typenum::U4>::new(vec![1, 2, 3, 4]).unwrap();
+    assert_eq!(peek_list_len_static(&list.to_ssz(), 2), Ok(4));
+
+    let list = nested(&[0, 2, 4]);
+    let bytes = list.to_ssz();
+    assert_eq!(peek_list_len_variable(&bytes), Ok(3));
+    assert_eq!(
+        iter_offsets(&bytes).collect::<Result<Vec<_>, _>>(),
+        Ok(vec![12, 12, 14])
+    );
+    assert_eq!(
+        <NestedList as SszbDecode>::from_ssz_bytes(&bytes).unwrap(),
+        list
+    );
+}
+
+#[test]
+fn test_peek_static_errors() {
+    assert_eq!(
+        peek_list_len_static(&[0; 5], 0),
+        Err(DecodeError::ZeroLengthItem)
+    );
+    assert_eq!(
+        peek_list_len_static(&[0; 5], 2),
+        Err(DecodeError::InvalidByteLength {
+            len: 1,
+            expected: 2
+        })
+    );
+}
+
+#[test]
+fn test_peek_malformed_first_offset() {
+    // too short to hold an offset
+    assert_eq!(
+        peek_list_len_variable(&[4, 0]),
+        Err(DecodeError::InvalidLengthPrefix {
+            len: 2,
+            expected: 4
+        })
+    );
+    // not a multiple of the offset size
+    assert_eq!(
+        peek_list_len_variable(&[5, 0, 0, 0, 0]),
+        Err(DecodeError::InvalidListFixedBytesLen(5))
+    );
+    // zero items but non-empty bytes
+    assert_eq!(
+        peek_list_len_variable(&[0, 0, 0, 0]),
+        Err(DecodeError::InvalidListFixedBytesLen(0))
+    );
+    // claims more offsets than there are bytes
+    assert_eq!(
+        peek_list_len_variable(&[40, 0, 0, 0, 1, 2, 3, 4]),
+        Err(DecodeError::OffsetOutOfBounds(40))
+    );
+
+    let mut offsets = iter_offsets(&[40, 0, 0, 0, 1, 2, 3, 4]);
+    assert_eq!(
+        offsets.next(),
+        Some(Err(DecodeError::OffsetOutOfBounds(40)))
+    );
+    assert_eq!(offsets.next(), None);
+}
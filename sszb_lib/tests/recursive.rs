@@ -0,0 +1,140 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode, BYTES_PER_LENGTH_OFFSET};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::sync::Arc;
+use typenum::U16;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct TreeNode {
+    value: u64,
+    children: VariableList<Box<TreeNode>, U16>,
+}
+
+// the same layout under another name
+#[derive(SszbEncode, SszbDecode)]
+struct Node {
+    value: u64,
+    children: VariableList<Box<Self>, U16>,
+}
+
+mod external {
+    use sszb_derive::{SszbDecode, SszbEncode};
+
+    #[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+    pub struct Address {
+        pub hi: u64,
+        pub lo: u32,
+    }
+}
+
+// not recursive: the field's type only shares the container's name
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Address {
+    inner: external::Address,
+    nonce: u64,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum Expr {
+    Num(u64),
+    Neg(Box<Expr>),
+    Add { left: Box<Expr>, right: Box<Expr> },
+    Shared(Arc<Expr>),
+}
+
+fn node(value: u64, children: Vec<TreeNode>) -> TreeNode {
+    TreeNode {
+        value,
+        children: VariableList::new(children.into_iter().map(Box::new).collect()).unwrap(),
+    }
+}
+
+fn assert_roundtrip<T: SszbEncode + SszbDecode + PartialEq + std::fmt::Debug>(value: T) {
+    let bytes = value.to_ssz();
+    assert_eq!(value.sszb_bytes_len(), bytes.len());
+    assert_eq!(T::from_ssz_bytes(&bytes), Ok(value));
+    assert_eq!(sszb::validate_ssz::<T>(&bytes), Ok(()));
+}
+
+#[test]
+fn test_tree_roundtrip() {
+    // a leaf is its value and the offset of its empty list of children
+    let leaf = node(7, vec![]);
+    let mut expected = 7u64.to_le_bytes().to_vec();
+    expected.extend_from_slice(&12u32.to_le_bytes());
+    assert_eq!(leaf.to_ssz(), expected);
+
+    let tree = node(
+        1,
+        vec![
+            node(2, vec![node(4, vec![]), node(5, vec![])]),
+            node(3, vec![]),
+        ],
+    );
+    assert_roundtrip(tree);
+}
+
+#[test]
+fn test_union_roundtrip() {
+    let expr = Expr::Add {
+        left: Box::new(Expr::Num(1)),
+        right: Box::new(Expr::Neg(Box::new(Expr::Shared(Arc::new(Expr::Num(2)))))),
+    };
+    assert_roundtrip(expr);
+
+    assert_eq!(
+        Expr::Neg(Box::new(Expr::Num(9))).to_ssz(),
+        [&[1, 0][..], &9u64.to_le_bytes()].concat()
+    );
+}
+
+#[test]
+fn test_recursive_lengths() {
+    assert!(!<TreeNode as SszbDecode>::SSZ_STATIC);
+    assert_eq!(
+        <TreeNode as SszbDecode>::SSZ_FIXED_LEN,
+        BYTES_PER_LENGTH_OFFSET
+    );
+    assert_eq!(<TreeNode as SszbDecode>::SSZ_MAX_LEN, usize::MAX);
+    assert_eq!(<TreeNode as SszbEncode>::SSZ_MAX_LEN, usize::MAX);
+    assert_eq!(<Expr as SszbDecode>::SSZ_MAX_LEN, usize::MAX);
+    assert_eq!(<Expr as SszbEncode>::SSZ_MAX_LEN, usize::MAX);
+
+    #[allow(deprecated)]
+    let max_len = <TreeNode as SszbDecode>::ssz_max_len();
+    assert_eq!(max_len, usize::MAX);
+}
+
+#[test]
+fn test_same_named_field_type() {
+    assert!(<Address as SszbDecode>::SSZ_STATIC);
+    assert_eq!(<Address as SszbDecode>::SSZ_FIXED_LEN, 20);
+    assert_eq!(<Address as SszbEncode>::SSZ_MAX_LEN, 20);
+
+    let address = Address {
+        inner: external::Address { hi: 1, lo: 2 },
+        nonce: 3,
+    };
+    assert_eq!(
+        address.to_ssz(),
+        [
+            &1u64.to_le_bytes()[..],
+            &2u32.to_le_bytes(),
+            &3u64.to_le_bytes()
+        ]
+        .concat()
+    );
+    assert_roundtrip(address);
+}
+
+#[test]
+fn test_recursive_schema() {
+    // the field referring back to the container is described with the container as `Self`
+    assert_eq!(
+        TreeNode::SSZ_SCHEMA_FINGERPRINT,
+        Node::SSZ_SCHEMA_FINGERPRINT
+    );
+}
@@ -0,0 +1,195 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::BufMut;
+use ssz_types::{FixedVector, VariableList};
+use sszb::runtime::{RuntimeList, RuntimeVector};
+use sszb::{DecodeError, SszbEncode};
+use sszb_derive::SszbEncode;
+use typenum::{U1024, U2, U4, U8};
+
+// a minimal and a mainnet-like maximum for the same list
+const SMALL_MAX: usize = 4;
+const LARGE_MAX: usize = 1024;
+
+type Name = VariableList<u8, U8>;
+
+fn name(s: &str) -> Name {
+    VariableList::new(s.as_bytes().to_vec()).unwrap()
+}
+
+#[derive(SszbEncode)]
+struct Runtime {
+    id: u32,
+    names: RuntimeList<Name>,
+}
+
+#[derive(SszbEncode)]
+struct Typed {
+    id: u32,
+    names: VariableList<Name, U4>,
+}
+
+#[test]
+fn test_round_trip_under_both_maximums() {
+    for max_len in [SMALL_MAX, LARGE_MAX] {
+        let list = RuntimeList::new(vec![1u64, 2, 3, 4], max_len).unwrap();
+        let bytes = list.to_ssz();
+        assert_eq!(
+            bytes,
+            VariableList::<u64, U4>::from(vec![1, 2, 3, 4]).to_ssz()
+        );
+        assert_eq!(
+            RuntimeList::<u64>::from_ssz_bytes_with_max(&bytes, max_len),
+            Ok(list)
+        );
+
+        let names = RuntimeList::new(vec![name("a"), name("bc")], max_len).unwrap();
+        let bytes = names.to_ssz();
+        assert_eq!(
+            RuntimeList::<Name>::from_ssz_bytes_with_max(&bytes, max_len),
+            Ok(names)
+        );
+        assert_eq!(
+            RuntimeList::<Name>::validate_ssz_bytes_with_max(&bytes, max_len),
+            Ok(())
+        );
+    }
+
+    let long = RuntimeList::new((0..LARGE_MAX as u16).collect(), LARGE_MAX).unwrap();
+    let bytes = long.to_ssz();
+    assert_eq!(
+        bytes,
+        VariableList::<u16, U1024>::from((0..LARGE_MAX as u16).collect::<Vec<_>>()).to_ssz()
+    );
+    assert_eq!(
+        RuntimeList::<u16>::from_ssz_bytes_with_max(&bytes, LARGE_MAX),
+        Ok(long)
+    );
+}
+
+#[test]
+fn test_decode_enforces_the_runtime_maximum() {
+    let bytes = RuntimeList::new(vec![0u32; 5], LARGE_MAX).unwrap().to_ssz();
+    let too_long = DecodeError::ListTooLong {
+        len: 5,
+        max: SMALL_MAX,
+    };
+    assert_eq!(
+        RuntimeList::<u32>::from_ssz_bytes_with_max(&bytes, SMALL_MAX),
+        Err(too_long.clone())
+    );
+    assert_eq!(
+        RuntimeList::<u32>::validate_ssz_bytes_with_max(&bytes, SMALL_MAX),
+        Err(too_long)
+    );
+    assert_eq!(
+        RuntimeList::<u32>::from_ssz_bytes_with_max(&bytes, 5).map(|list| list.len()),
+        Ok(5)
+    );
+
+    let names = RuntimeList::new(vec![name("a"); 5], LARGE_MAX).unwrap();
+    assert_eq!(
+        RuntimeList::<Name>::from_ssz_bytes_with_max(&names.to_ssz(), SMALL_MAX),
+        Err(DecodeError::ListTooLong {
+            len: 5,
+            max: SMALL_MAX
+        })
+    );
+}
+
+#[test]
+fn test_construction_enforces_the_runtime_maximum() {
+    assert_eq!(
+        RuntimeList::new(vec![0u8; 5], SMALL_MAX),
+        Err(DecodeError::ListTooLong {
+            len: 5,
+            max: SMALL_MAX
+        })
+    );
+
+    let mut list = RuntimeList::empty(SMALL_MAX);
+    for i in 0..SMALL_MAX {
+        list.push(i as u8).unwrap();
+    }
+    assert_eq!(
+        list.push(0),
+        Err(DecodeError::ListTooLong {
+            len: 5,
+            max: SMALL_MAX
+        })
+    );
+    assert_eq!(list.max_len(), SMALL_MAX);
+    assert_eq!(list.into_inner(), [0, 1, 2, 3]);
+}
+
+#[test]
+fn test_vectors() {
+    let vector = RuntimeVector::new(vec![1u16, 2, 3], 3).unwrap();
+    let bytes = vector.to_ssz();
+    assert_eq!(bytes, [1, 0, 2, 0, 3, 0]);
+    assert_eq!(
+        RuntimeVector::<u16>::from_ssz_bytes_with_len(&bytes, 3),
+        Ok(vector)
+    );
+    assert_eq!(
+        RuntimeVector::<u16>::from_ssz_bytes_with_len(&bytes, 2),
+        Err(DecodeError::InvalidByteLength {
+            len: 6,
+            expected: 4
+        })
+    );
+    assert_eq!(
+        RuntimeVector::<u16>::validate_ssz_bytes_with_len(&bytes, 3),
+        Ok(())
+    );
+
+    let names = RuntimeVector::new(vec![name("a"), name("bc")], 2).unwrap();
+    let bytes = names.to_ssz();
+    assert_eq!(
+        bytes,
+        FixedVector::<Name, U2>::from(vec![name("a"), name("bc")]).to_ssz()
+    );
+    assert_eq!(
+        RuntimeVector::<Name>::from_ssz_bytes_with_len(&bytes, 2),
+        Ok(names)
+    );
+
+    assert_eq!(
+        RuntimeVector::new(vec![1u8, 2], 3),
+        Err(DecodeError::VectorLengthMismatch {
+            found: 2,
+            expected: 3
+        })
+    );
+    assert_eq!(
+        RuntimeVector::<u8>::new(vec![], 0),
+        Err(DecodeError::ZeroLengthVector)
+    );
+    assert_eq!(
+        RuntimeVector::<u8>::from_ssz_bytes_with_len(&[], 0),
+        Err(DecodeError::ZeroLengthVector)
+    );
+}
+
+#[test]
+fn test_container_field() {
+    let names = vec![name("alice"), name("bob")];
+    let runtime = Runtime {
+        id: 7,
+        names: RuntimeList::new(names.clone(), SMALL_MAX).unwrap(),
+    };
+    let typed = Typed {
+        id: 7,
+        names: VariableList::new(names).unwrap(),
+    };
+    assert_eq!(runtime.to_ssz(), typed.to_ssz());
+    assert_eq!(runtime.sszb_bytes_len(), typed.sszb_bytes_len());
+
+    // decoded by hand, from the bytes the field's offset points at
+    let bytes = runtime.to_ssz();
+    assert_eq!(&bytes[4..8], 8u32.to_le_bytes());
+    assert_eq!(
+        RuntimeList::<Name>::from_ssz_bytes_with_max(&bytes[8..], SMALL_MAX),
+        Ok(runtime.names)
+    );
+}
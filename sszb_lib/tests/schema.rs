@@ -0,0 +1,120 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{assert_schema, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+// the same layout under other names
+#[derive(SszbEncode, SszbDecode)]
+struct Justified {
+    slot: u64,
+    block_root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct CachedCheckpoint {
+    epoch: u64,
+    #[ssz(skip_encode, skip_decode)]
+    hits: u32,
+    root: [u8; 32],
+}
+
+#[derive(SszbEncode, SszbDecode)]
+struct ExtendedCheckpoint {
+    epoch: u64,
+    root: [u8; 32],
+    finalized: bool,
+}
+
+#[derive(SszbEncode, SszbDecode)]
+struct ReorderedCheckpoint {
+    root: [u8; 32],
+    epoch: u64,
+}
+
+#[derive(SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    checkpoints: VariableList<Checkpoint, U4>,
+}
+
+#[derive(SszbEncode, SszbDecode)]
+struct LongerBlock {
+    slot: u64,
+    checkpoints: VariableList<Checkpoint, U8>,
+}
+
+#[derive(SszbEncode, SszbDecode)]
+struct ExtendedBlock {
+    slot: u64,
+    checkpoints: VariableList<ExtendedCheckpoint, U4>,
+}
+
+#[test]
+fn test_pinned_fingerprints() {
+    assert_schema!(
+        Checkpoint,
+        "0x710f23c02ffeddaa6780e6d366359c8a9541eaffa47c2448e14ab9676bd945f3"
+    );
+    assert_schema!(
+        Block,
+        "0xd5ec7132f6b0ce672250228c3927a650df469c71733fb33527e537027e7cd13a"
+    );
+    assert_eq!(
+        Checkpoint::SSZ_SCHEMA_FINGERPRINT,
+        <Checkpoint as SszbDecode>::SSZ_SCHEMA
+    );
+}
+
+#[test]
+#[should_panic(expected = "the SSZ layout of `ExtendedCheckpoint` changed")]
+fn test_changed_fingerprint_panics() {
+    assert_schema!(
+        ExtendedCheckpoint,
+        "0x710f23c02ffeddaa6780e6d366359c8a9541eaffa47c2448e14ab9676bd945f3"
+    );
+}
+
+#[test]
+fn test_fingerprints_follow_the_layout() {
+    // field names and skipped fields don't take part
+    assert_eq!(
+        Justified::SSZ_SCHEMA_FINGERPRINT,
+        Checkpoint::SSZ_SCHEMA_FINGERPRINT
+    );
+    assert_eq!(
+        CachedCheckpoint::SSZ_SCHEMA_FINGERPRINT,
+        Checkpoint::SSZ_SCHEMA_FINGERPRINT
+    );
+    let cached = CachedCheckpoint {
+        epoch: 3,
+        hits: 0,
+        root: [1; 32],
+    };
+    assert_eq!(
+        CachedCheckpoint::from_ssz_bytes(&cached.to_ssz()),
+        Ok(cached)
+    );
+
+    // adding or reordering fields does, and so does a list bound or a nested container
+    for fingerprint in [
+        ExtendedCheckpoint::SSZ_SCHEMA_FINGERPRINT,
+        ReorderedCheckpoint::SSZ_SCHEMA_FINGERPRINT,
+    ] {
+        assert_ne!(fingerprint, Checkpoint::SSZ_SCHEMA_FINGERPRINT);
+    }
+    for fingerprint in [
+        LongerBlock::SSZ_SCHEMA_FINGERPRINT,
+        ExtendedBlock::SSZ_SCHEMA_FINGERPRINT,
+    ] {
+        assert_ne!(fingerprint, Block::SSZ_SCHEMA_FINGERPRINT);
+    }
+}
@@ -0,0 +1,73 @@
+#![cfg(all(feature = "serde", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use serde::{Deserialize, Serialize};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    roots: VariableList<u16, typenum::U4>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Envelope {
+    #[serde(with = "sszb::serde_utils::hex")]
+    hex: Checkpoint,
+    #[serde(with = "sszb::serde_utils::base64")]
+    base64: Checkpoint,
+    #[serde(with = "sszb::serde_utils")]
+    slot: u64,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Slot(#[serde(with = "sszb::serde_utils::hex")] u32);
+
+fn checkpoint() -> Checkpoint {
+    Checkpoint {
+        epoch: 3,
+        roots: VariableList::new(vec![1, 2]).unwrap(),
+    }
+}
+
+#[test]
+fn test_roundtrip_json() {
+    let envelope = Envelope {
+        hex: checkpoint(),
+        base64: checkpoint(),
+        slot: 1,
+    };
+
+    let json = serde_json::to_string(&envelope).unwrap();
+    assert_eq!(
+        json,
+        r#"{"hex":"0x03000000000000000c00000001000200","base64":"AwAAAAAAAAAMAAAAAQACAA==","slot":"0x0100000000000000"}"#
+    );
+    assert_eq!(serde_json::from_str::<Envelope>(&json).unwrap(), envelope);
+}
+
+#[test]
+fn test_hex_case_insensitive() {
+    assert_eq!(
+        serde_json::from_str::<Slot>(r#""0xabcdef01""#).unwrap(),
+        Slot(0x01efcdab)
+    );
+    assert_eq!(
+        serde_json::from_str::<Slot>(r#""0xABCDEF01""#).unwrap(),
+        Slot(0x01efcdab)
+    );
+}
+
+#[test]
+fn test_hex_errors() {
+    // odd number of digits
+    assert!(serde_json::from_str::<Slot>(r#""0xabcdef0""#).is_err());
+    // missing prefix
+    assert!(serde_json::from_str::<Slot>(r#""abcdef01""#).is_err());
+
+    // the inner decode error is surfaced
+    let err = serde_json::from_str::<Slot>(r#""0xabcd""#).unwrap_err();
+    assert!(err.to_string().contains("InvalidByteLength"));
+}
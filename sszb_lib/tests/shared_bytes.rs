@@ -0,0 +1,169 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use bytes::Bytes;
+use ssz_types::VariableList;
+use sszb::{validate_ssz, ByteList, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::ops::Range;
+use typenum::{U1024, U32};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Payload {
+    block_number: u64,
+    extra_data: ByteList<U32>,
+    fee_recipient: [u8; 20],
+    transaction: Bytes,
+    logs: VariableList<u8, U1024>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Envelope {
+    slot: u64,
+    payload: Payload,
+}
+
+fn payload() -> Payload {
+    Payload {
+        block_number: 12,
+        extra_data: ByteList::new(&b"sszb"[..]).unwrap(),
+        fee_recipient: [3; 20],
+        transaction: Bytes::from((0..200).map(|i| i as u8).collect::<Vec<_>>()),
+        logs: VariableList::new(vec![9; 40]).unwrap(),
+    }
+}
+
+fn range(bytes: &[u8]) -> Range<usize> {
+    let start = bytes.as_ptr() as usize;
+    start..start + bytes.len()
+}
+
+// the field's bytes lie within the input's allocation, at the position its encoding is at
+fn assert_aliases(field: &[u8], input: &[u8], at: usize) {
+    let input = range(input);
+    let field = range(field);
+    assert!(input.start <= field.start && field.end <= input.end);
+    assert_eq!(field.start - input.start, at);
+}
+
+#[test]
+fn test_fields_share_the_input() {
+    let value = payload();
+    let input = Bytes::from(value.to_ssz());
+    let decoded = Payload::from_ssz_bytes_shared(input.clone()).unwrap();
+    assert_eq!(decoded, value);
+
+    // block_number, two offsets, fee_recipient and the logs' offset come first
+    let extra_data_at = 8 + 4 + 20 + 4 + 4;
+    assert_aliases(&decoded.extra_data, &input, extra_data_at);
+    assert_aliases(&decoded.transaction, &input, extra_data_at + 4);
+
+    // everything else is still a copy
+    assert!(!range(&input).contains(&(decoded.logs.as_ptr() as usize)));
+    assert_eq!(Payload::from_ssz_bytes(&input), Ok(decoded));
+}
+
+#[test]
+fn test_fields_outlive_the_input() {
+    let value = payload();
+    let input = Bytes::from(value.to_ssz());
+    let allocation = range(&input);
+    let decoded = Payload::from_ssz_bytes_shared(input).unwrap();
+
+    // the fields keep the allocation alive after the input is gone
+    assert!(allocation.contains(&(decoded.transaction.as_ptr() as usize)));
+    assert_eq!(decoded, value);
+    assert_eq!(decoded.to_ssz(), value.to_ssz());
+}
+
+#[test]
+fn test_nested_containers() {
+    let envelope = Envelope {
+        slot: 4,
+        payload: payload(),
+    };
+    let input = Bytes::from(envelope.to_ssz());
+    let decoded = Envelope::from_ssz_bytes_shared(input.clone()).unwrap();
+    assert_eq!(decoded, envelope);
+    // the payload starts after slot and its offset
+    assert_aliases(&decoded.payload.extra_data, &input, 12 + 40);
+}
+
+#[test]
+fn test_standalone() {
+    let input = Bytes::from_static(b"some transaction");
+    let decoded = Bytes::from_ssz_bytes_shared(input.clone()).unwrap();
+    assert_eq!(decoded.as_ptr(), input.as_ptr());
+    assert_eq!(decoded, input);
+
+    // reading out of a Bytes buffer shares it too
+    let mut buf = input.clone();
+    let decoded = ByteList::<U32>::ssz_read(&mut Bytes::new(), &mut buf).unwrap();
+    assert_eq!(decoded.as_ptr(), input.as_ptr());
+    assert!(!buf.has_remaining());
+
+    // and a plain slice is copied
+    let decoded = Bytes::from_ssz_bytes(&input).unwrap();
+    assert_ne!(decoded.as_ptr(), input.as_ptr());
+    assert_eq!(decoded, input);
+}
+
+#[test]
+fn test_same_bytes_as_lists() {
+    let bytes = b"extra".to_vec();
+    let list = VariableList::<u8, U32>::new(bytes.clone()).unwrap();
+    assert_eq!(
+        ByteList::<U32>::new(bytes.clone()).unwrap().to_ssz(),
+        list.to_ssz()
+    );
+    assert_eq!(Bytes::from(bytes.clone()).to_ssz(), list.to_ssz());
+    assert_eq!(
+        <ByteList<U32> as SszbDecode>::SSZ_SCHEMA,
+        <VariableList<u8, U32> as SszbDecode>::SSZ_SCHEMA
+    );
+
+    let value = payload();
+    let mut buf = vec![0; value.sszb_bytes_len()];
+    assert_eq!(value.ssz_write_slice(&mut buf), buf.len());
+    assert_eq!(buf, value.to_ssz());
+}
+
+#[test]
+fn test_errors() {
+    let too_long = Bytes::from(vec![0; 33]);
+    let expected = DecodeError::ListTooLong { len: 33, max: 32 };
+    assert_eq!(
+        ByteList::<U32>::new(too_long.clone()),
+        Err(expected.clone())
+    );
+    assert_eq!(
+        ByteList::<U32>::from_ssz_bytes_shared(too_long.clone()),
+        Err(expected.clone())
+    );
+    assert_eq!(
+        ByteList::<U32>::from_ssz_bytes(&too_long),
+        Err(expected.clone())
+    );
+    assert_eq!(validate_ssz::<ByteList<U32>>(&too_long), Err(expected));
+
+    // the same errors at the same positions as from_ssz_bytes
+    let mut bytes = payload().to_ssz();
+    bytes[8] = 0xff;
+    let input = Bytes::from(bytes.clone());
+    let err = Payload::from_ssz_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, DecodeError::AtByte { .. }));
+    assert_eq!(Payload::from_ssz_bytes_shared(input), Err(err));
+
+    // a static type still has to be exactly its length
+    assert_eq!(
+        u64::from_ssz_bytes_shared(Bytes::from_static(&[0; 9])),
+        Err(DecodeError::InvalidByteLength {
+            len: 9,
+            expected: 8
+        })
+    );
+    assert_eq!(
+        u64::from_ssz_bytes_shared(Bytes::from_static(&[1, 0, 0, 0, 0, 0, 0, 0])),
+        Ok(1)
+    );
+}
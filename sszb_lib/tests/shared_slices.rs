@@ -0,0 +1,102 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::sync::Arc;
+use typenum::{U1024, U16, U8};
+
+type Transaction = VariableList<u8, U16>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct CachedBlock {
+    slot: u64,
+    transactions: Arc<[u8]>,
+    indices: Arc<[u64]>,
+    roots: Box<[[u8; 32]]>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    transactions: VariableList<u8, U1024>,
+    indices: VariableList<u64, U1024>,
+    roots: VariableList<[u8; 32], U8>,
+}
+
+fn round_trip<T: SszbEncode + SszbDecode + PartialEq + std::fmt::Debug>(value: &T, bytes: &[u8]) {
+    assert_eq!(value.to_ssz(), bytes);
+    assert_eq!(value.sszb_bytes_len(), bytes.len());
+    assert_eq!(T::from_ssz_bytes(bytes).as_ref(), Ok(value));
+    assert_eq!(validate_ssz::<T>(bytes), Ok(()));
+}
+
+#[test]
+fn test_standalone() {
+    let bytes: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let list = VariableList::<u8, U1024>::new(bytes.clone()).unwrap();
+    round_trip(&Arc::<[u8]>::from(bytes.clone()), &list.to_ssz());
+    round_trip(&bytes.into_boxed_slice(), &list.to_ssz());
+
+    let indices: Vec<u64> = (0..50).map(|i| i * 1000).collect();
+    let list = VariableList::<u64, U1024>::new(indices.clone()).unwrap();
+    round_trip(&Arc::<[u64]>::from(indices), &list.to_ssz());
+
+    // empty slices are empty lists
+    round_trip(&Arc::<[u64]>::from(vec![]), &[]);
+}
+
+#[test]
+fn test_variable_elements() {
+    let transactions: Vec<Transaction> = (0..10)
+        .map(|i| Transaction::new(vec![i as u8; i]).unwrap())
+        .collect();
+    let list = VariableList::<Transaction, U16>::new(transactions.clone()).unwrap();
+    round_trip(
+        &Arc::<[Transaction]>::from(transactions.clone()),
+        &list.to_ssz(),
+    );
+    round_trip(&transactions.into_boxed_slice(), &list.to_ssz());
+
+    // the offsets are checked as a list's would be
+    let mut bytes = list.to_ssz();
+    bytes[4] = 3;
+    assert_eq!(
+        Arc::<[Transaction]>::from_ssz_bytes(&bytes),
+        VariableList::<Transaction, U16>::from_ssz_bytes(&bytes).map(|list| list.to_vec().into())
+    );
+    assert_eq!(
+        Arc::<[Transaction]>::from_ssz_bytes(&bytes),
+        Err(DecodeError::OffsetsAreDecreasing(3).at_byte(4))
+    );
+}
+
+#[test]
+fn test_container_fields() {
+    let cached = CachedBlock {
+        slot: 7,
+        transactions: Arc::from(vec![1, 2, 3]),
+        indices: Arc::from(vec![4, 5]),
+        roots: vec![[6; 32], [7; 32]].into_boxed_slice(),
+    };
+    let block = Block {
+        slot: 7,
+        transactions: VariableList::new(vec![1, 2, 3]).unwrap(),
+        indices: VariableList::new(vec![4, 5]).unwrap(),
+        roots: VariableList::new(vec![[6; 32], [7; 32]]).unwrap(),
+    };
+    let bytes = block.to_ssz();
+    round_trip(&cached, &bytes);
+    assert_eq!(Block::from_ssz_bytes(&cached.to_ssz()), Ok(block));
+}
+
+#[test]
+fn test_decode_into() {
+    let mut roots: Box<[[u8; 32]]> = vec![[1; 32]; 3].into_boxed_slice();
+    let bytes = VariableList::<[u8; 32], U8>::new(vec![[2; 32]; 5])
+        .unwrap()
+        .to_ssz();
+    roots.decode_into(&bytes).unwrap();
+    assert_eq!(&roots[..], &[[2; 32]; 5][..]);
+}
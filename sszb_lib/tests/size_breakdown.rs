@@ -0,0 +1,133 @@
+#![cfg(all(feature = "introspection", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::introspection::size_breakdown_table;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U1024, U16};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Millis(u64);
+
+impl From<Millis> for u64 {
+    fn from(millis: Millis) -> Self {
+        millis.0
+    }
+}
+
+impl From<u64> for Millis {
+    fn from(millis: u64) -> Self {
+        Millis(millis)
+    }
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct State {
+    slot: u64,
+    balances: VariableList<u64, U1024>,
+    #[ssz(skip_encode, skip_decode)]
+    cache: Option<u64>,
+    finalized: Checkpoint,
+    #[ssz(encode_as = "u64")]
+    uptime: Millis,
+    graffiti: VariableList<u8, U16>,
+    history: VariableList<Checkpoint, U16>,
+}
+
+fn checkpoint(epoch: u64) -> Checkpoint {
+    Checkpoint {
+        epoch,
+        root: [epoch as u8; 32],
+    }
+}
+
+fn state() -> State {
+    State {
+        slot: 100,
+        balances: VariableList::new(vec![32; 10]).unwrap(),
+        cache: Some(7),
+        finalized: checkpoint(3),
+        uptime: Millis(1_000),
+        graffiti: VariableList::new(b"breakdown".to_vec()).unwrap(),
+        history: VariableList::new(vec![checkpoint(1), checkpoint(2)]).unwrap(),
+    }
+}
+
+fn sum(breakdown: &[(&str, usize)]) -> usize {
+    breakdown.iter().map(|(_, len)| len).sum()
+}
+
+#[test]
+fn test_static_breakdown() {
+    let breakdown = checkpoint(1).ssz_size_breakdown();
+    assert_eq!(breakdown, [("epoch", 8), ("root", 32)]);
+    assert_eq!(sum(&breakdown), checkpoint(1).sszb_bytes_len());
+}
+
+#[test]
+fn test_variable_breakdown() {
+    let state = state();
+    let breakdown = state.ssz_size_breakdown();
+    // variable fields are charged their offset on top of their bytes, and the skipped field
+    // isn't there at all
+    assert_eq!(
+        breakdown,
+        [
+            ("slot", 8),
+            ("balances", 4 + 80),
+            ("finalized", 40),
+            ("uptime", 8),
+            ("graffiti", 4 + 9),
+            ("history", 4 + 80),
+        ]
+    );
+    assert_eq!(sum(&breakdown), state.sszb_bytes_len());
+    assert_eq!(sum(&breakdown), state.to_ssz().len());
+
+    let empty = State {
+        balances: VariableList::empty(),
+        graffiti: VariableList::empty(),
+        history: VariableList::empty(),
+        ..state
+    };
+    assert_eq!(sum(&empty.ssz_size_breakdown()), empty.to_ssz().len());
+}
+
+#[test]
+fn test_table() {
+    let table = size_breakdown_table(&state().ssz_size_breakdown());
+    let lines: Vec<_> = table.lines().collect();
+    assert_eq!(lines.len(), 8);
+    assert!(lines[0].starts_with("field"));
+    // largest first, ties in field order
+    let names: Vec<_> = lines[1..]
+        .iter()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "balances",
+            "history",
+            "finalized",
+            "graffiti",
+            "slot",
+            "uptime",
+            "total"
+        ]
+    );
+    assert!(lines[7].contains(" 237 "));
+    assert!(lines[7].ends_with("100.0%"));
+
+    assert_eq!(
+        size_breakdown_table(&[]).lines().last(),
+        Some("total            0    0.0%")
+    );
+}
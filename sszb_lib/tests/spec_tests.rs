@@ -0,0 +1,385 @@
+//! Runner for the `ssz_generic` suite of ethereum/consensus-spec-tests.
+//!
+//! Point `CONSENSUS_SPEC_TESTS_DIR` at an extracted copy of the test vectors (the directory that
+//! holds `tests/`) to run it; without the variable the runner is skipped.
+//!
+//! Valid cases must decode, re-encode to the same bytes and match `value.yaml`. Invalid cases must
+//! be rejected with an `Err`, and no case may panic. Cases whose type has no dispatch entry are
+//! counted as skipped. `ssz_static` can be added per fork the same way, with a dispatch function
+//! keyed on the container name and a walk over `tests/<preset>/<fork>/ssz_static`.
+#![cfg(all(feature = "alloy", feature = "ssz-types"))]
+
+use alloy_primitives::U256;
+use bytes::buf::{Buf, BufMut};
+use serde_yaml::{Mapping, Value};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use std::fs;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+const SPEC_TESTS_DIR_ENV: &str = "CONSENSUS_SPEC_TESTS_DIR";
+
+const SSZ_GENERIC_HANDLERS: &[&str] = &[
+    "uints",
+    "boolean",
+    "bitvector",
+    "bitlist",
+    "basic_vector",
+    "containers",
+];
+
+type Runner = fn(&Path, bool) -> Result<(), String>;
+
+/// Converts a decoded value into the shape used by the `value.yaml` files.
+trait SpecValue {
+    fn spec_value(&self) -> Value;
+}
+
+macro_rules! uint_spec_value {
+    ($($type: ty),*) => {
+        $(
+            impl SpecValue for $type {
+                fn spec_value(&self) -> Value {
+                    Value::String(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+uint_spec_value!(u8, u16, u32, u64, u128, U256);
+
+impl SpecValue for bool {
+    fn spec_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+// bitfields are written out as the hex of their serialization
+fn hex_spec_value(bytes: &[u8]) -> Value {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Value::String(format!("0x{}", digits))
+}
+
+impl<N: typenum::Unsigned + Clone> SpecValue for BitVector<N> {
+    fn spec_value(&self) -> Value {
+        hex_spec_value(&self.to_ssz())
+    }
+}
+
+impl<N: typenum::Unsigned + Clone> SpecValue for BitList<N> {
+    fn spec_value(&self) -> Value {
+        hex_spec_value(&self.to_ssz())
+    }
+}
+
+impl<T: SpecValue, N: typenum::Unsigned> SpecValue for VariableList<T, N> {
+    fn spec_value(&self) -> Value {
+        Value::Sequence(self.iter().map(SpecValue::spec_value).collect())
+    }
+}
+
+impl<T: SpecValue, N: typenum::Unsigned> SpecValue for FixedVector<T, N> {
+    fn spec_value(&self) -> Value {
+        Value::Sequence(self.iter().map(SpecValue::spec_value).collect())
+    }
+}
+
+macro_rules! spec_container {
+    ($name: ident { $($field: ident),* }) => {
+        impl SpecValue for $name {
+            fn spec_value(&self) -> Value {
+                let mut map = Mapping::new();
+                $(
+                    map.insert(Value::from(stringify!($field)), self.$field.spec_value());
+                )*
+                Value::Mapping(map)
+            }
+        }
+    };
+}
+
+// container definitions from the ssz_generic format description
+#[allow(non_snake_case)]
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct SingleFieldTestStruct {
+    A: u8,
+}
+
+#[allow(non_snake_case)]
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct SmallTestStruct {
+    A: u16,
+    B: u16,
+}
+
+#[allow(non_snake_case)]
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct FixedTestStruct {
+    A: u8,
+    B: u64,
+    C: u32,
+}
+
+#[allow(non_snake_case)]
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct VarTestStruct {
+    A: u16,
+    B: VariableList<u16, typenum::U1024>,
+    C: u8,
+}
+
+#[allow(non_snake_case)]
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct ComplexTestStruct {
+    A: u16,
+    B: VariableList<u16, typenum::U128>,
+    C: u8,
+    D: VariableList<u8, typenum::U256>,
+    E: VarTestStruct,
+    F: FixedVector<FixedTestStruct, typenum::U4>,
+    G: FixedVector<VarTestStruct, typenum::U2>,
+}
+
+#[allow(non_snake_case)]
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct BitsStruct {
+    A: BitList<typenum::U5>,
+    B: BitVector<typenum::U2>,
+    C: BitVector<typenum::U1>,
+    D: BitList<typenum::U6>,
+    E: BitVector<typenum::U8>,
+}
+
+spec_container!(SingleFieldTestStruct { A });
+spec_container!(SmallTestStruct { A, B });
+spec_container!(FixedTestStruct { A, B, C });
+spec_container!(VarTestStruct { A, B, C });
+spec_container!(ComplexTestStruct {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G
+});
+spec_container!(BitsStruct { A, B, C, D, E });
+
+// compares our value against value.yaml, where numbers may be written as ints or strings and
+// byte lists as hex strings
+fn same_value(ours: &Value, expected: &Value) -> bool {
+    match (ours, expected) {
+        (Value::String(a), Value::Number(b)) => *a == b.to_string(),
+        (Value::Sequence(a), Value::String(b)) if b.starts_with("0x") => {
+            hex_spec_value(
+                &a.iter()
+                    .map(|byte| match byte {
+                        Value::String(s) => s.parse::<u8>().ok(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<u8>>>()
+                    .unwrap_or_default(),
+            ) == Value::String(b.to_lowercase())
+        }
+        (Value::Sequence(a), Value::Sequence(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_value(a, b))
+        }
+        (Value::Mapping(a), Value::Mapping(b)) => {
+            a.len() == b.len()
+                && b.iter()
+                    .all(|(key, b)| a.get(key).is_some_and(|a| same_value(a, b)))
+        }
+        (Value::String(a), Value::String(b)) => a.eq_ignore_ascii_case(b),
+        (a, b) => a == b,
+    }
+}
+
+fn read_ssz_snappy(path: &Path) -> Result<Vec<u8>, String> {
+    let compressed = fs::read(path).map_err(|e| format!("reading {:?}: {}", path, e))?;
+    snap::raw::Decoder::new()
+        .decompress_vec(&compressed)
+        .map_err(|e| format!("decompressing {:?}: {}", path, e))
+}
+
+fn run_case<T>(dir: &Path, valid: bool) -> Result<(), String>
+where
+    T: SszbEncode + SszbDecode + SpecValue + Debug,
+{
+    let bytes = read_ssz_snappy(&dir.join("serialized.ssz_snappy"))?;
+    let decoded = <T as SszbDecode>::from_ssz_bytes(&bytes);
+
+    if !valid {
+        return match decoded {
+            Ok(value) => Err(format!("invalid input accepted as {:?}", value)),
+            Err(_) => Ok(()),
+        };
+    }
+
+    let value = decoded.map_err(|e| format!("valid input rejected: {:?}", e))?;
+    if value.to_ssz() != bytes {
+        return Err(format!("{:?} re-encodes to different bytes", value));
+    }
+
+    let yaml = fs::read_to_string(dir.join("value.yaml")).map_err(|e| e.to_string())?;
+    let expected: Value = serde_yaml::from_str(&yaml).map_err(|e| e.to_string())?;
+    if !same_value(&value.spec_value(), &expected) {
+        return Err(format!("decoded {:?}, expected {:?}", value, expected));
+    }
+
+    Ok(())
+}
+
+fn runner<T>() -> Option<Runner>
+where
+    T: SszbEncode + SszbDecode + SpecValue + Debug,
+{
+    Some(run_case::<T> as Runner)
+}
+
+// maps the length in a case name to a typenum type, for the lengths the suite uses
+macro_rules! sized {
+    ($len: expr, |$N: ident| $ty: ty) => {
+        match $len {
+            // zero-length vectors and bitvectors only appear as invalid cases
+            "0" => {
+                type $N = typenum::U0;
+                runner::<$ty>()
+            }
+            "1" => {
+                type $N = typenum::U1;
+                runner::<$ty>()
+            }
+            "2" => {
+                type $N = typenum::U2;
+                runner::<$ty>()
+            }
+            "3" => {
+                type $N = typenum::U3;
+                runner::<$ty>()
+            }
+            "4" => {
+                type $N = typenum::U4;
+                runner::<$ty>()
+            }
+            "5" => {
+                type $N = typenum::U5;
+                runner::<$ty>()
+            }
+            "8" => {
+                type $N = typenum::U8;
+                runner::<$ty>()
+            }
+            "16" => {
+                type $N = typenum::U16;
+                runner::<$ty>()
+            }
+            "31" => {
+                type $N = typenum::U31;
+                runner::<$ty>()
+            }
+            "512" => {
+                type $N = typenum::U512;
+                runner::<$ty>()
+            }
+            "513" => {
+                type $N = typenum::U513;
+                runner::<$ty>()
+            }
+            _ => None,
+        }
+    };
+}
+
+fn ssz_generic_runner(handler: &str, case: &str) -> Option<Runner> {
+    let mut parts = case.split('_');
+
+    match handler {
+        "uints" => match parts.nth(1)? {
+            "8" => runner::<u8>(),
+            "16" => runner::<u16>(),
+            "32" => runner::<u32>(),
+            "64" => runner::<u64>(),
+            "128" => runner::<u128>(),
+            "256" => runner::<U256>(),
+            _ => None,
+        },
+        "boolean" => runner::<bool>(),
+        "bitvector" => sized!(parts.nth(1)?, |N| BitVector<N>),
+        "bitlist" => sized!(parts.nth(1)?, |N| BitList<N>),
+        "basic_vector" => {
+            let elem = parts.nth(1)?;
+            let len = parts.next()?;
+            match elem {
+                "bool" => sized!(len, |N| FixedVector<bool, N>),
+                "uint8" => sized!(len, |N| FixedVector<u8, N>),
+                "uint16" => sized!(len, |N| FixedVector<u16, N>),
+                "uint32" => sized!(len, |N| FixedVector<u32, N>),
+                "uint64" => sized!(len, |N| FixedVector<u64, N>),
+                "uint128" => sized!(len, |N| FixedVector<u128, N>),
+                "uint256" => sized!(len, |N| FixedVector<U256, N>),
+                _ => None,
+            }
+        }
+        "containers" => match parts.next()? {
+            "SingleFieldTestStruct" => runner::<SingleFieldTestStruct>(),
+            "SmallTestStruct" => runner::<SmallTestStruct>(),
+            "FixedTestStruct" => runner::<FixedTestStruct>(),
+            "VarTestStruct" => runner::<VarTestStruct>(),
+            "ComplexTestStruct" => runner::<ComplexTestStruct>(),
+            "BitsStruct" => runner::<BitsStruct>(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[test]
+fn test_ssz_generic() {
+    let Ok(root) = std::env::var(SPEC_TESTS_DIR_ENV) else {
+        eprintln!("{} is not set, skipping ssz_generic", SPEC_TESTS_DIR_ENV);
+        return;
+    };
+    let suite = Path::new(&root).join("tests/general/phase0/ssz_generic");
+
+    let mut passed = 0;
+    let mut skipped = vec![];
+    let mut failures = vec![];
+
+    for handler in SSZ_GENERIC_HANDLERS {
+        for (kind, valid) in [("valid", true), ("invalid", false)] {
+            let Ok(cases) = fs::read_dir(suite.join(handler).join(kind)) else {
+                continue;
+            };
+
+            for case in cases {
+                let case = case.expect("failed to read case directory").path();
+                let name = case.file_name().unwrap().to_string_lossy().into_owned();
+                let id = format!("{}/{}/{}", handler, kind, name);
+
+                let Some(run) = ssz_generic_runner(handler, &name) else {
+                    skipped.push(id);
+                    continue;
+                };
+
+                match catch_unwind(AssertUnwindSafe(|| run(&case, valid))) {
+                    Ok(Ok(())) => passed += 1,
+                    Ok(Err(e)) => failures.push(format!("{}: {}", id, e)),
+                    Err(_) => failures.push(format!("{}: panicked", id)),
+                }
+            }
+        }
+    }
+
+    eprintln!(
+        "ssz_generic: {} passed, {} failed, {} skipped",
+        passed,
+        failures.len(),
+        skipped.len()
+    );
+    assert!(passed > 0, "no ssz_generic cases found under {:?}", suite);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,53 @@
+#![cfg(all(feature = "ethereum-types", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ethereum_types::H256;
+use ssz_types::VariableList;
+use sszb::{EncodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U8;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: H256,
+}
+
+#[test]
+fn test_to_ssz_array() {
+    let slot: u64 = 0x0102030405060708;
+    let bytes: [u8; 8] = slot.to_ssz_array();
+    assert_eq!(bytes, [8, 7, 6, 5, 4, 3, 2, 1]);
+
+    let root = H256::repeat_byte(0xab);
+    let bytes: [u8; 32] = root.to_ssz_array();
+    assert_eq!(bytes.as_slice(), root.as_bytes());
+
+    let checkpoint = Checkpoint { epoch: 3, root };
+    let bytes: [u8; 40] = checkpoint.to_ssz_array();
+    assert_eq!(bytes.as_slice(), checkpoint.to_ssz());
+    assert_eq!(Checkpoint::from_ssz_bytes(&bytes), Ok(checkpoint));
+}
+
+#[test]
+fn test_try_to_ssz_array() {
+    assert_eq!(5u16.try_to_ssz_array::<2>(), Ok([5, 0]));
+    assert_eq!(
+        5u16.try_to_ssz_array::<4>(),
+        Err(EncodeError::LengthMismatch {
+            len: 2,
+            expected: 4
+        })
+    );
+
+    // a variable value only fits an array of its exact length
+    let list = VariableList::<u8, U8>::new(vec![1, 2, 3]).unwrap();
+    assert_eq!(list.try_to_ssz_array::<3>(), Ok([1, 2, 3]));
+    assert_eq!(
+        list.try_to_ssz_array::<8>(),
+        Err(EncodeError::LengthMismatch {
+            len: 3,
+            expected: 8
+        })
+    );
+}
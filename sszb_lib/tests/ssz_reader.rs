@@ -0,0 +1,316 @@
+#![cfg(feature = "ssz-types")]
+
+// impls built on SszReader, and the errors it raises. Derived containers decode through it too,
+// so a hand-written impl laid out like one has to fail the same way, at the same positions
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{read_through_reader, DecodeError, SszReader, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct DerivedRecord {
+    id: u32,
+    name: VariableList<u8, U8>,
+    tag: u16,
+    tail: VariableList<u16, U4>,
+}
+
+// the same layout, read by hand
+#[derive(PartialEq, Debug)]
+struct Record {
+    id: u32,
+    name: VariableList<u8, U8>,
+    tag: u16,
+    tail: VariableList<u16, U4>,
+}
+
+const RECORD_FIXED_LEN: usize = 4 + 4 + 2 + 4;
+
+impl SszbDecode for Record {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = sszb::BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = <DerivedRecord as SszbDecode>::SSZ_MAX_LEN;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        read_through_reader(fixed_bytes, variable_bytes)
+    }
+
+    fn ssz_read_from(reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        let mut reader = reader.with_fixed_len(RECORD_FIXED_LEN)?;
+        let id = reader.read_fixed()?;
+        reader.read_offset()?;
+        let tag = reader.read_fixed()?;
+        reader.read_offset()?;
+        let name = reader.read_variable()?;
+        let tail = reader.read_variable()?;
+        reader.finish()?;
+        Ok(Record {
+            id,
+            name,
+            tag,
+            tail,
+        })
+    }
+}
+
+// a byte, and where the reader it was read from started within the input
+#[derive(PartialEq, Debug)]
+struct Located {
+    byte: u8,
+    at: usize,
+}
+
+impl SszbDecode for Located {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 1;
+    const SSZ_MAX_LEN: usize = 1;
+
+    fn ssz_read(
+        fixed_bytes: &mut impl Buf,
+        variable_bytes: &mut impl Buf,
+    ) -> Result<Self, DecodeError> {
+        read_through_reader(fixed_bytes, variable_bytes)
+    }
+
+    fn ssz_read_from(mut reader: SszReader<'_>) -> Result<Self, DecodeError> {
+        let at = reader.base();
+        let byte = reader.read_fixed_bytes(1)?[0];
+        reader.finish()?;
+        Ok(Located { byte, at })
+    }
+}
+
+#[derive(PartialEq, Debug, SszbDecode)]
+struct Inner {
+    list: VariableList<u8, U4>,
+    located: Located,
+}
+
+#[derive(PartialEq, Debug, SszbDecode)]
+struct Outer {
+    slot: u64,
+    located: Located,
+    inner: Inner,
+}
+
+fn record() -> DerivedRecord {
+    DerivedRecord {
+        id: 7,
+        name: VariableList::new(b"alice".to_vec()).unwrap(),
+        tag: 3,
+        tail: VariableList::new(vec![1, 2]).unwrap(),
+    }
+}
+
+// the record's bytes with the offset at `at` replaced
+fn with_offset(at: usize, offset: u32) -> Vec<u8> {
+    let mut bytes = record().to_ssz();
+    bytes[at..(at + 4)].copy_from_slice(&offset.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn test_manual_impl_matches_derive() {
+    let bytes = record().to_ssz();
+    let decoded = Record::from_ssz_bytes(&bytes).unwrap();
+    let DerivedRecord {
+        id,
+        name,
+        tag,
+        tail,
+    } = record();
+    assert_eq!(
+        decoded,
+        Record {
+            id,
+            name,
+            tag,
+            tail
+        }
+    );
+
+    // and fails the same way on every cut and every bad offset
+    for len in 0..bytes.len() {
+        assert_eq!(
+            Record::from_ssz_bytes(&bytes[..len]).err(),
+            DerivedRecord::from_ssz_bytes(&bytes[..len]).err(),
+            "truncated to {} bytes",
+            len
+        );
+    }
+    for (at, offset) in [(4, 0), (4, 15), (4, 200), (10, 13), (10, 14), (10, 200)] {
+        let bytes = with_offset(at, offset);
+        assert_eq!(
+            Record::from_ssz_bytes(&bytes).err(),
+            DerivedRecord::from_ssz_bytes(&bytes).err(),
+            "offset {} at byte {}",
+            offset,
+            at
+        );
+    }
+}
+
+#[test]
+fn test_offset_errors() {
+    // the first offset has to end the fixed section exactly
+    assert_eq!(
+        Record::from_ssz_bytes(&with_offset(4, 10)),
+        Err(DecodeError::OffsetIntoFixedPortion(10).at_byte(4))
+    );
+    assert_eq!(
+        Record::from_ssz_bytes(&with_offset(4, 15)),
+        Err(DecodeError::OffsetSkipsVariableBytes(15).at_byte(4))
+    );
+
+    // the ones after it are blamed for the length of the value they end
+    let name_start = RECORD_FIXED_LEN as u32;
+    assert_eq!(
+        Record::from_ssz_bytes(&with_offset(10, name_start - 1)),
+        Err(DecodeError::OffsetsAreDecreasing(13).at_byte(10))
+    );
+    let bytes = with_offset(10, 200);
+    assert_eq!(
+        Record::from_ssz_bytes(&bytes),
+        Err(DecodeError::InvalidByteLength {
+            len: 200 - RECORD_FIXED_LEN,
+            expected: bytes.len() - RECORD_FIXED_LEN,
+        }
+        .at_byte(10))
+    );
+}
+
+#[test]
+fn test_value_errors_are_positioned() {
+    // a name longer than its maximum is an error of the list, at its start
+    let mut bytes = with_offset(10, RECORD_FIXED_LEN as u32 + 9);
+    bytes.splice(RECORD_FIXED_LEN..RECORD_FIXED_LEN, [0; 4]);
+    assert_eq!(
+        Record::from_ssz_bytes(&bytes),
+        Err(DecodeError::ListTooLong { len: 9, max: 8 }.at_byte(RECORD_FIXED_LEN))
+    );
+
+    // as is an odd number of bytes for a list of u16s, after the name
+    let mut bytes = record().to_ssz();
+    bytes.push(0);
+    assert_eq!(
+        Record::from_ssz_bytes(&bytes).unwrap_err().position(),
+        Some(RECORD_FIXED_LEN + 5)
+    );
+}
+
+#[test]
+fn test_reader_errors() {
+    let bytes: [u8; 6] = [1, 2, 3, 4, 5, 6];
+
+    assert_eq!(
+        SszReader::new(&bytes).with_fixed_len(7).err(),
+        Some(DecodeError::InvalidByteLength {
+            len: 6,
+            expected: 7
+        })
+    );
+
+    let mut reader = SszReader::new(&bytes).with_fixed_len(6).unwrap();
+    assert_eq!(reader.read_fixed::<u32>(), Ok(0x0403_0201));
+    assert_eq!(reader.fixed_position(), 4);
+    assert_eq!(
+        reader.read_fixed::<u32>(),
+        Err(DecodeError::InvalidByteLength {
+            len: 2,
+            expected: 4
+        }
+        .at_byte(4))
+    );
+    assert_eq!(
+        reader.read_offset(),
+        Err(DecodeError::InvalidLengthPrefix {
+            len: 2,
+            expected: 4
+        }
+        .at_byte(4))
+    );
+
+    // bytes left over in either section
+    let mut reader = SszReader::new(&bytes);
+    reader.read_fixed_bytes(4).unwrap();
+    assert_eq!(
+        reader.finish(),
+        Err(DecodeError::InvalidByteLength {
+            len: 6,
+            expected: 4
+        })
+    );
+    let mut reader = SszReader::new(&bytes).with_fixed_len(2).unwrap();
+    reader.read_fixed_bytes(2).unwrap();
+    assert_eq!(reader.take_variable(3), Ok(&bytes[2..5]));
+    assert_eq!(reader.variable_position(), 5);
+    assert_eq!(
+        reader.take_variable(2),
+        Err(DecodeError::InvalidByteLength {
+            len: 1,
+            expected: 2
+        }
+        .at_byte(5))
+    );
+    assert_eq!(
+        reader.finish(),
+        Err(DecodeError::InvalidByteLength {
+            len: 6,
+            expected: 5
+        })
+    );
+}
+
+#[test]
+#[should_panic(expected = "variable values are read with read_offset and read_variable")]
+fn test_read_fixed_of_variable_value() {
+    let _ = SszReader::new(&[0u8; 4]).read_fixed::<VariableList<u8, U4>>();
+}
+
+#[test]
+#[should_panic(expected = "the fixed section has to be read before the variable one")]
+fn test_read_variable_too_early() {
+    let bytes = record().to_ssz();
+    let mut reader = SszReader::new(&bytes)
+        .with_fixed_len(RECORD_FIXED_LEN)
+        .unwrap();
+    reader.read_fixed::<u32>().unwrap();
+    reader.read_offset().unwrap();
+    let _ = reader.read_variable::<VariableList<u8, U8>>();
+}
+
+#[test]
+fn test_base_positions() {
+    // slot, located, the inner offset, then the inner list's offset, located and list
+    let mut bytes = vec![0; 8];
+    bytes.push(1);
+    bytes.extend_from_slice(&13u32.to_le_bytes());
+    bytes.extend_from_slice(&5u32.to_le_bytes());
+    bytes.push(2);
+    bytes.extend_from_slice(&[3, 4]);
+
+    let outer = Outer::from_ssz_bytes(&bytes).unwrap();
+    assert_eq!(outer.located, Located { byte: 1, at: 8 });
+    assert_eq!(outer.inner.located, Located { byte: 2, at: 17 });
+    assert_eq!(outer.inner.list[..], [3, 4]);
+
+    // a reader's base is only its position, errors stay relative to it until they travel up
+    let reader = SszReader::new(&bytes[13..]).with_base(13);
+    assert_eq!(reader.base(), 13);
+    assert_eq!(
+        Inner::ssz_read_from(reader).unwrap().located,
+        Located { byte: 2, at: 17 }
+    );
+    assert_eq!(
+        Inner::ssz_read_from(SszReader::new(&bytes[13..16]).with_base(13)),
+        Err(DecodeError::InvalidByteLength {
+            len: 3,
+            expected: 5
+        })
+    );
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{stream_ssz_to, SszbEncode};
+use sszb_derive::SszbEncode;
+use std::io::{self, Write};
+use typenum::{U1048576, U16777216, U64};
+
+#[derive(SszbEncode)]
+struct Validator {
+    pubkey: [u8; 48],
+    balance: u64,
+    graffiti: VariableList<u8, U64>,
+}
+
+#[derive(SszbEncode)]
+struct State {
+    slot: u64,
+    validators: VariableList<Validator, U1048576>,
+    balances: VariableList<u64, U1048576>,
+    blob: VariableList<u8, U16777216>,
+}
+
+fn state() -> State {
+    State {
+        slot: 42,
+        validators: VariableList::new(
+            (0..20_000)
+                .map(|i| Validator {
+                    pubkey: [i as u8; 48],
+                    balance: i * 1_000,
+                    graffiti: VariableList::new(vec![i as u8; (i % 40) as usize]).unwrap(),
+                })
+                .collect(),
+        )
+        .unwrap(),
+        balances: VariableList::new((0..200_000).collect()).unwrap(),
+        blob: VariableList::new((0..3_000_000).map(|i| i as u8).collect()).unwrap(),
+    }
+}
+
+// records every write, and the largest one made
+#[derive(Default)]
+struct Recorder {
+    bytes: Vec<u8>,
+    largest_write: usize,
+    flushed: bool,
+}
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.largest_write = self.largest_write.max(buf.len());
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushed = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stream_matches_to_ssz() {
+    let state = state();
+    let expected = state.to_ssz();
+    assert!(expected.len() > 5_000_000);
+
+    for scratch_capacity in [1, 100, 4096, 64 * 1024] {
+        let mut recorder = Recorder::default();
+        let written = stream_ssz_to(&state, &mut recorder, scratch_capacity).unwrap();
+        assert_eq!(written, expected.len() as u64);
+        assert!(recorder.bytes == expected);
+        assert!(recorder.flushed);
+    }
+}
+
+#[test]
+fn test_stream_scratch_bounded() {
+    let state = state();
+    let mut recorder = Recorder::default();
+    stream_ssz_to(&state.balances, &mut recorder, 4096).unwrap();
+    // nothing bigger than the scratch buffer was buffered
+    assert!(recorder.largest_write <= 4096);
+    assert_eq!(recorder.bytes, state.balances.to_ssz());
+
+    // the blob is written as it is rather than through the scratch buffer
+    let mut recorder = Recorder::default();
+    stream_ssz_to(&state, &mut recorder, 4096).unwrap();
+    assert_eq!(recorder.largest_write, state.blob.len());
+}
+
+struct Failing(usize);
+
+impl Write for Failing {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.0 < buf.len() {
+            return Err(io::Error::new(io::ErrorKind::Other, "disk full"));
+        }
+        self.0 -= buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stream_write_error() {
+    let error = stream_ssz_to(&state(), Failing(10_000), 1024).unwrap_err();
+    assert_eq!(error.to_string(), "disk full");
+}
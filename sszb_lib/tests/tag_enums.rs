@@ -0,0 +1,87 @@
+use bytes::buf::{Buf, BufMut};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(PartialEq, Debug, Clone, Copy, SszbEncode, SszbDecode)]
+#[ssz(enum_behaviour = "tag")]
+enum DomainType {
+    Proposer,
+    Attester,
+    Randao,
+}
+
+// explicit discriminants, with gaps, and implicit ones following them
+#[derive(PartialEq, Debug, Clone, Copy, SszbEncode, SszbDecode)]
+#[ssz(enum_behaviour = "tag")]
+enum Status {
+    Pending = 2,
+    Active,
+    Exited = 10,
+    Slashed = 255,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Duty {
+    domain: DomainType,
+    status: Status,
+    epoch: u64,
+}
+
+#[test]
+fn test_tags() {
+    assert_eq!(DomainType::Proposer.to_ssz(), [0]);
+    assert_eq!(DomainType::Randao.to_ssz(), [2]);
+    assert_eq!(Status::Pending.to_ssz(), [2]);
+    assert_eq!(Status::Active.to_ssz(), [3]);
+    assert_eq!(Status::Exited.to_ssz(), [10]);
+    assert_eq!(Status::Slashed.to_ssz(), [255]);
+
+    for status in [
+        Status::Pending,
+        Status::Active,
+        Status::Exited,
+        Status::Slashed,
+    ] {
+        assert_eq!(Status::from_ssz_bytes(&[status as u8]), Ok(status));
+    }
+
+    const { assert!(<Status as SszbEncode>::SSZ_STATIC) };
+    assert_eq!(<Status as SszbDecode>::SSZ_FIXED_LEN, 1);
+}
+
+#[test]
+fn test_tags_in_containers() {
+    let duty = Duty {
+        domain: DomainType::Attester,
+        status: Status::Exited,
+        epoch: 4,
+    };
+    let bytes = duty.to_ssz();
+    assert_eq!(bytes, [1, 10, 4, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(Duty::from_ssz_bytes(&bytes), Ok(duty));
+}
+
+#[test]
+fn test_unknown_tags() {
+    for tag in [0, 1, 4, 9, 11, 254] {
+        assert_eq!(
+            Status::from_ssz_bytes(&[tag]),
+            Err(DecodeError::InvalidEnumTag(tag))
+        );
+    }
+    assert_eq!(
+        DomainType::from_ssz_bytes(&[3]),
+        Err(DecodeError::InvalidEnumTag(3))
+    );
+    assert_eq!(
+        DomainType::from_ssz_bytes(&[]),
+        Err(DecodeError::InvalidByteLength {
+            len: 0,
+            expected: 1
+        })
+    );
+
+    let err = Duty::from_ssz_bytes(&[1, 7, 4, 0, 0, 0, 0, 0, 0, 0]).unwrap_err();
+    assert_eq!(err.position(), Some(1));
+    assert_eq!(err.root_cause(), &DecodeError::InvalidEnumTag(7));
+}
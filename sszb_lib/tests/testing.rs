@@ -0,0 +1,224 @@
+#![cfg(feature = "testing")]
+
+use bytes::buf::{Buf, BufMut};
+use milhouse::{List, Vector};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::roundtrip_ssz;
+use sszb::testing::proptest::prelude::*;
+use sszb::testing::*;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U16, U32, U4, U64, U8};
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: ethereum_types::H256,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Attestation {
+    aggregation_bits: BitList<U64>,
+    slot: u64,
+    target: Checkpoint,
+    indices: VariableList<u64, U16>,
+}
+
+// static vectors between variable fields write their items in full, and leave the offset of the
+// fields after them alone
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Committees {
+    name: VariableList<u8, U8>,
+    balances: FixedVector<u64, U4>,
+    indices: VariableList<u64, U16>,
+    roots: Vector<u64, U8>,
+    bits: BitVector<U16>,
+    names: FixedVector<VariableList<u8, U8>, U4>,
+}
+
+fn checkpoint() -> impl Strategy<Value = Checkpoint> {
+    (any::<u64>(), h256()).prop_map(|(epoch, root)| Checkpoint { epoch, root })
+}
+
+fn attestation() -> impl Strategy<Value = Attestation> {
+    (
+        bit_list::<U64>(),
+        any::<u64>(),
+        checkpoint(),
+        variable_list(any::<u64>()),
+    )
+        .prop_map(|(aggregation_bits, slot, target, indices)| Attestation {
+            aggregation_bits,
+            slot,
+            target,
+            indices,
+        })
+}
+
+fn committees() -> impl Strategy<Value = Committees> {
+    (
+        variable_list(any::<u8>()),
+        fixed_vector(any::<u64>()),
+        variable_list(any::<u64>()),
+        persistent_vector(any::<u64>()),
+        bit_vector::<U16>(),
+        fixed_vector(variable_list(any::<u8>())),
+    )
+        .prop_map(|(name, balances, indices, roots, bits, names)| Committees {
+            name,
+            balances,
+            indices,
+            roots,
+            bits,
+            names,
+        })
+}
+
+roundtrip_ssz!(u8, any::<u8>());
+roundtrip_ssz!(u16, any::<u16>());
+roundtrip_ssz!(u32, any::<u32>());
+roundtrip_ssz!(u64, any::<u64>());
+roundtrip_ssz!(bool, any::<bool>());
+roundtrip_ssz!(bytes32: [u8; 32], any::<[u8; 32]>());
+roundtrip_ssz!(address: alloy_primitives::Address, address());
+roundtrip_ssz!(h256: ethereum_types::H256, h256());
+roundtrip_ssz!(u256: alloy_primitives::U256, u256());
+roundtrip_ssz!(bit_list: BitList<U64>, bit_list());
+roundtrip_ssz!(bit_vector: BitVector<U16>, bit_vector());
+roundtrip_ssz!(variable_list: VariableList<u16, U32>, variable_list(any::<u16>()));
+roundtrip_ssz!(
+    nested_list: VariableList<VariableList<u8, U8>, U4>,
+    variable_list(variable_list(any::<u8>()))
+);
+roundtrip_ssz!(fixed_vector: FixedVector<u32, U8>, fixed_vector(any::<u32>()));
+roundtrip_ssz!(
+    variable_vector: FixedVector<VariableList<u8, U8>, U4>,
+    fixed_vector(variable_list(any::<u8>()))
+);
+roundtrip_ssz!(persistent_list: List<u64, U32>, persistent_list(any::<u64>()));
+roundtrip_ssz!(persistent_vector: Vector<u64, U8>, persistent_vector(any::<u64>()));
+roundtrip_ssz!(
+    shared_slice: std::sync::Arc<[u16]>,
+    prop::collection::vec(any::<u16>(), 0..8).prop_map(Into::into)
+);
+roundtrip_ssz!(boxed: Box<Checkpoint>, checkpoint().prop_map(Box::new));
+roundtrip_ssz!(
+    union: sszb::Union2<u16, VariableList<u8, U8>>,
+    prop_oneof![
+        any::<u16>().prop_map(sszb::Union2::A),
+        variable_list(any::<u8>()).prop_map(sszb::Union2::B),
+    ]
+);
+roundtrip_ssz!(Checkpoint, checkpoint());
+roundtrip_ssz!(Attestation, attestation());
+roundtrip_ssz!(Committees, committees());
+
+// a hand-written wrapper that writes its offset, but forgets to advance it past its bytes
+#[derive(Debug)]
+struct Forgetful(VariableList<u8, U8>);
+
+impl SszbEncode for Forgetful {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = 4;
+    const SSZ_MAX_LEN: usize = 8;
+
+    fn sszb_bytes_len(&self) -> usize {
+        self.0.sszb_bytes_len()
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        sszb::expect_offset(sszb::write_offset(*offset, buf));
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.0.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        self.0.ssz_write(buf);
+    }
+}
+
+#[derive(Debug, SszbEncode)]
+struct HoldsForgetful {
+    forgetful: Forgetful,
+    after: VariableList<u8, U8>,
+}
+
+#[test]
+#[should_panic(expected = "ssz_write_fixed of a variable value has to advance the offset past it")]
+fn test_contract_violation() {
+    verify_encode_contract(&Forgetful(VariableList::new(vec![1, 2]).unwrap()));
+}
+
+// the offset of `after` would point into the bytes of `forgetful`
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "SSZ offset accounting")]
+fn test_derived_fields_are_checked() {
+    let value = HoldsForgetful {
+        forgetful: Forgetful(VariableList::new(vec![1, 2]).unwrap()),
+        after: VariableList::new(vec![3]).unwrap(),
+    };
+    value.ssz_write(&mut Vec::<u8>::new());
+}
+
+// a value as long as its type allows has to be exactly `SSZ_MAX_LEN` long, the same on both sides
+fn assert_max_len<T: SszbEncode + SszbDecode>(value: &T) {
+    assert_eq!(
+        <T as SszbEncode>::SSZ_MAX_LEN,
+        <T as SszbDecode>::SSZ_MAX_LEN
+    );
+    assert_eq!(value.sszb_bytes_len(), <T as SszbEncode>::SSZ_MAX_LEN);
+    assert_eq!(value.to_ssz().len(), <T as SszbDecode>::SSZ_MAX_LEN);
+}
+
+fn full_bit_list<N: typenum::Unsigned + Clone>() -> BitList<N> {
+    let mut bits = BitList::with_capacity(N::USIZE).unwrap();
+    for i in 0..N::USIZE {
+        bits.set(i, true).unwrap();
+    }
+    bits
+}
+
+#[test]
+fn test_max_len_of_maximal_values() {
+    let bytes = VariableList::<u8, U8>::new(vec![0xff; 8]).unwrap();
+    let lists = VariableList::<_, U4>::new(vec![bytes.clone(); 4]).unwrap();
+    assert_max_len(&bytes);
+    assert_max_len(&VariableList::<u16, U32>::new(vec![7; 32]).unwrap());
+    // each variable item has an offset as well as its bytes
+    assert_max_len(&lists);
+    assert_eq!(
+        <VariableList<VariableList<u8, U8>, U4> as SszbDecode>::SSZ_MAX_LEN,
+        4 * (4 + 8)
+    );
+    assert_max_len(&FixedVector::<_, U4>::new(vec![bytes; 4]).unwrap());
+    assert_max_len(&FixedVector::<u32, U8>::new(vec![1; 8]).unwrap());
+    assert_max_len(&VariableList::<_, U4>::new(vec![lists; 4]).unwrap());
+
+    // the length bit takes a byte of its own once a multiple of 8 bits are set
+    assert_max_len(&full_bit_list::<U64>());
+    assert_eq!(<BitList<U64> as SszbDecode>::SSZ_MAX_LEN, 9);
+    assert_max_len(&full_bit_list::<typenum::U5>());
+    assert_max_len(&full_bit_list::<typenum::U0>());
+    assert_max_len(&BitVector::<U16>::new());
+
+    assert_max_len(&List::<u64, U32>::new(vec![3; 32]).unwrap());
+    assert_max_len(&Vector::<u64, U8>::new(vec![3; 8]).unwrap());
+
+    // a container counts the offsets of its variable fields
+    assert_max_len(&Attestation {
+        aggregation_bits: full_bit_list(),
+        slot: 1,
+        target: Checkpoint {
+            epoch: 2,
+            root: Default::default(),
+        },
+        indices: VariableList::new(vec![4; 16]).unwrap(),
+    });
+    assert_eq!(
+        <Attestation as SszbEncode>::SSZ_MAX_LEN,
+        (4 + 9) + 8 + 40 + (4 + 16 * 8)
+    );
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "tiny-errors")]
+
+// with `tiny-errors`, decode errors are plain data: no messages, nothing allocated
+
+use bytes::buf::{Buf, BufMut};
+use sszb::{ByteList, DecodeError, DecodeErrorKind, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U2, U4};
+
+const _: () = {
+    const fn assert_copy<T: Copy>() {}
+    assert_copy::<DecodeError>();
+};
+
+fn nonzero_slot(slot: &u64) -> Result<(), &'static str> {
+    if *slot == 0 {
+        return Err("slot must not be zero");
+    }
+    Ok(())
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    #[ssz(validate = "nonzero_slot")]
+    slot: u64,
+    #[ssz(encode_as = "u16")]
+    proposer: u8,
+    votes: ByteList<U2>,
+}
+
+fn block(slot: u64, proposer: u8, votes: usize) -> Vec<u8> {
+    // votes past the maximum of a Block, to decode as one
+    let votes = ByteList::<U4>::new(vec![1; votes]).unwrap();
+    let mut bytes = slot.to_ssz();
+    bytes.extend((proposer as u16).to_ssz());
+    bytes.extend(14u32.to_ssz());
+    bytes.extend(votes.to_ssz());
+    bytes
+}
+
+#[test]
+fn test_structured_errors() {
+    assert!(Block::from_ssz_bytes(&block(1, 2, 2)).is_ok());
+
+    // an error raised on its own keeps its fields
+    let list = ByteList::<U4>::new(vec![1; 3]).unwrap().to_ssz();
+    let error = ByteList::<U2>::from_ssz_bytes(&list).unwrap_err();
+    assert_eq!(error, DecodeError::ListTooLong { len: 3, max: 2 });
+    assert_eq!(error.position(), None);
+    assert_eq!(error.to_string(), "ListTooLong { len: 3, max: 2 }");
+
+    // and where it was raised keeps its kind
+    let error = Block::from_ssz_bytes(&block(1, 2, 3)).unwrap_err();
+    assert_eq!(
+        error,
+        DecodeError::AtByte {
+            at_byte: 14,
+            kind: DecodeErrorKind::ListTooLong
+        }
+    );
+    assert_eq!(error.position(), Some(14));
+    assert_eq!(error.kind(), DecodeErrorKind::ListTooLong);
+    assert_eq!(error.root_cause(), &error);
+}
+
+// errors that are messages without the feature name what was rejected instead
+#[allow(deprecated)]
+#[test]
+fn test_invalid_values() {
+    assert_eq!(
+        Block::from_ssz_bytes(&block(0, 2, 1)),
+        Err(DecodeError::BytesInvalid("field `slot` of `Block`"))
+    );
+
+    let mut bytes = block(1, 2, 1);
+    bytes[9] = 1;
+    assert_eq!(
+        Block::from_ssz_bytes(&bytes),
+        Err(DecodeError::AtByte {
+            at_byte: 8,
+            kind: DecodeErrorKind::BytesInvalid
+        })
+    );
+    assert_eq!(
+        sszb::invalid_value("field `proposer` can't be converted from its `u16` encoding"),
+        DecodeError::BytesInvalid("field `proposer` can't be converted from its `u16` encoding")
+    );
+}
@@ -0,0 +1,146 @@
+#![cfg(all(feature = "tracing", feature = "ssz-types"))]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use typenum::{U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 4],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Attestation {
+    target: Checkpoint,
+    flags: VariableList<bool, U8>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    attestations: VariableList<Attestation, U4>,
+}
+
+type Span = (&'static str, Vec<(&'static str, String)>);
+
+// records the name and fields of every span created, in order
+#[derive(Clone, Default)]
+struct Collector(Arc<Mutex<Vec<Span>>>);
+
+struct Fields(Vec<(&'static str, String)>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push((field.name(), value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((field.name(), format!("{:?}", value)));
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields(Vec::new());
+        span.record(&mut fields);
+        let mut spans = self.0.lock().unwrap();
+        spans.push((span.metadata().name(), fields.0));
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn collect_spans(f: impl FnOnce()) -> Vec<Span> {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), f);
+    let spans = collector.0.lock().unwrap().clone();
+    spans
+}
+
+fn span(name: &'static str, fields: &[(&'static str, &str)]) -> Span {
+    (
+        name,
+        fields
+            .iter()
+            .map(|(field, value)| (*field, value.to_string()))
+            .collect(),
+    )
+}
+
+fn attestation(epoch: u64, num_flags: usize) -> Attestation {
+    Attestation {
+        target: Checkpoint {
+            epoch,
+            root: [1, 2, 3, 4],
+        },
+        flags: VariableList::new(vec![true; num_flags]).unwrap(),
+    }
+}
+
+#[test]
+fn test_decode_spans() {
+    let block = Block {
+        slot: 9,
+        attestations: VariableList::new(vec![attestation(1, 2), attestation(2, 0)]).unwrap(),
+    };
+    let bytes = block.to_ssz();
+
+    let spans = collect_spans(|| assert_eq!(Block::from_ssz_bytes(&bytes), Ok(block)));
+    assert_eq!(
+        spans,
+        vec![
+            span("ssz_decode", &[("ty", "Block"), ("len", "54")]),
+            span("ssz_decode_list", &[("items", "2"), ("len", "42")]),
+            span("ssz_decode", &[("ty", "Attestation"), ("len", "18")]),
+            span("ssz_decode", &[("ty", "Checkpoint"), ("len", "12")]),
+            span("ssz_decode_list", &[("items", "2"), ("len", "2")]),
+            // the second attestation's flags are empty, so there are no list bytes to decode
+            span("ssz_decode", &[("ty", "Attestation"), ("len", "16")]),
+            span("ssz_decode", &[("ty", "Checkpoint"), ("len", "12")]),
+        ]
+    );
+}
+
+#[test]
+fn test_encode_spans() {
+    let checkpoint = Checkpoint {
+        epoch: 1,
+        root: [0; 4],
+    };
+    let spans = collect_spans(|| {
+        checkpoint.to_ssz();
+    });
+    assert_eq!(spans, vec![span("ssz_encode", &[("ty", "Checkpoint")])]);
+
+    let block = Block {
+        slot: 9,
+        attestations: VariableList::new(vec![attestation(1, 2)]).unwrap(),
+    };
+    let spans = collect_spans(|| {
+        block.to_ssz();
+    });
+    assert_eq!(spans[0], span("ssz_encode", &[("ty", "Block")]));
+    assert!(spans.contains(&span("ssz_encode", &[("ty", "Attestation")])));
+    assert!(spans.contains(&span("ssz_encode", &[("ty", "Checkpoint")])));
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U64;
+
+type Body = VariableList<u8, U64>;
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct BlockV1 {
+    slot: u64,
+    parent_root: [u8; 32],
+    body: Body,
+}
+
+// a later fork adds a field
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct BlockV2 {
+    slot: u64,
+    parent_root: [u8; 32],
+    body: Body,
+    blob_count: u32,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(enum_behaviour = "transparent")]
+enum BlockAny {
+    V1(BlockV1),
+    V2(BlockV2),
+}
+
+#[derive(SszbEncode)]
+struct SignedBlockAny {
+    message: BlockAny,
+    signature: [u8; 8],
+}
+
+#[derive(SszbEncode)]
+struct SignedBlockV2 {
+    message: BlockV2,
+    signature: [u8; 8],
+}
+
+#[derive(SszbEncode)]
+#[ssz(enum_behaviour = "transparent")]
+enum Root {
+    Block([u8; 32]),
+    State([u8; 32]),
+}
+
+#[derive(SszbEncode)]
+#[ssz(enum_behaviour = "transparent")]
+enum Number {
+    Small(u32),
+    Large(u64),
+}
+
+fn v1() -> BlockV1 {
+    BlockV1 {
+        slot: 7,
+        parent_root: [1; 32],
+        body: VariableList::new(vec![4, 5, 6]).unwrap(),
+    }
+}
+
+fn v2() -> BlockV2 {
+    BlockV2 {
+        slot: 7,
+        parent_root: [1; 32],
+        body: VariableList::new(vec![4, 5, 6]).unwrap(),
+        blob_count: 3,
+    }
+}
+
+#[test]
+fn test_encodes_without_selector() {
+    assert_eq!(BlockAny::V1(v1()).to_ssz(), v1().to_ssz());
+    assert_eq!(BlockAny::V2(v2()).to_ssz(), v2().to_ssz());
+    assert_eq!(BlockAny::V2(v2()).sszb_bytes_len(), v2().sszb_bytes_len());
+
+    let signed = SignedBlockAny {
+        message: BlockAny::V2(v2()),
+        signature: [9; 8],
+    };
+    let expected = SignedBlockV2 {
+        message: v2(),
+        signature: [9; 8],
+    };
+    assert_eq!(signed.to_ssz(), expected.to_ssz());
+}
+
+#[test]
+fn test_decodes_the_given_variant() {
+    let v1_bytes = v1().to_ssz();
+    let v2_bytes = v2().to_ssz();
+
+    assert_eq!(
+        BlockAny::from_ssz_bytes_for_variant(0, &v1_bytes),
+        Ok(BlockAny::V1(v1()))
+    );
+    match BlockAny::from_ssz_bytes_for_variant(1, &v2_bytes).unwrap() {
+        BlockAny::V2(block) => {
+            assert_eq!(block.slot, 7);
+            assert_eq!(block.body.to_vec(), [4, 5, 6]);
+            assert_eq!(block.blob_count, 3);
+        }
+        block => panic!("decoded the wrong variant: {:?}", block),
+    }
+    assert_eq!(
+        BlockAny::from_ssz_bytes_for_variant_name("V2", &v2_bytes),
+        Ok(BlockAny::V2(v2()))
+    );
+
+    // each layout only decodes as its own variant
+    assert!(BlockAny::from_ssz_bytes_for_variant(1, &v1_bytes).is_err());
+    assert!(BlockAny::from_ssz_bytes_for_variant_name("V1", &v2_bytes).is_err());
+}
+
+#[test]
+fn test_invalid_variants() {
+    let bytes = v1().to_ssz();
+    assert_eq!(
+        BlockAny::from_ssz_bytes_for_variant(2, &bytes),
+        Err(DecodeError::InvalidVariantIndex(2))
+    );
+    assert_eq!(
+        BlockAny::from_ssz_bytes_for_variant_name("V3", &bytes),
+        Err(DecodeError::InvalidVariantName)
+    );
+}
+
+#[test]
+fn test_transparent_lengths() {
+    assert!(!<BlockAny as SszbEncode>::SSZ_STATIC);
+    assert_eq!(
+        <BlockAny as SszbEncode>::SSZ_MAX_LEN,
+        <BlockV2 as SszbEncode>::SSZ_MAX_LEN
+    );
+
+    // variants of the same length keep the enum static, so it's laid out like them
+    assert!(<Root as SszbEncode>::SSZ_STATIC);
+    assert_eq!(<Root as SszbEncode>::SSZ_FIXED_LEN, 32);
+    assert_eq!(Root::State([2; 32]).to_ssz(), [2; 32]);
+    assert_eq!(Root::Block([3; 32]).sszb_bytes_len(), 32);
+
+    assert!(!<Number as SszbEncode>::SSZ_STATIC);
+    assert_eq!(Number::Small(1).to_ssz(), [1, 0, 0, 0]);
+    assert_eq!(Number::Large(1).sszb_bytes_len(), 8);
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U4, U8};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Inner {
+    a: u16,
+    list: VariableList<u8, U8>,
+    b: u32,
+    tail: VariableList<u16, U4>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Outer {
+    slot: u64,
+    inner: Inner,
+    root: [u8; 4],
+    extra: VariableList<u8, U8>,
+}
+
+const INNER_FIXED_LEN: usize = 2 + 4 + 4 + 4;
+const OUTER_FIXED_LEN: usize = 8 + 4 + 4 + 4;
+
+fn inner() -> Inner {
+    Inner {
+        a: 0x0102,
+        list: VariableList::new(vec![1, 2, 3]).unwrap(),
+        b: 0x0304_0506,
+        tail: VariableList::new(vec![7, 8]).unwrap(),
+    }
+}
+
+fn outer() -> Outer {
+    Outer {
+        slot: 9,
+        inner: inner(),
+        root: [4; 4],
+        extra: VariableList::new(vec![5, 6]).unwrap(),
+    }
+}
+
+#[test]
+fn test_truncated_fixed_portion() {
+    let bytes = inner().to_ssz();
+    for len in 0..INNER_FIXED_LEN {
+        let expected = Err(DecodeError::InvalidByteLength {
+            len,
+            expected: INNER_FIXED_LEN,
+        });
+        assert_eq!(Inner::from_ssz_bytes(&bytes[..len]), expected);
+        // ssz_read itself checks the length, rather than relying on from_ssz_bytes
+        assert_eq!(Inner::ssz_read(&mut &[][..], &mut &bytes[..len]), expected);
+    }
+
+    let bytes = outer().to_ssz();
+    for len in 0..OUTER_FIXED_LEN {
+        assert_eq!(
+            Outer::from_ssz_bytes(&bytes[..len]),
+            Err(DecodeError::InvalidByteLength {
+                len,
+                expected: OUTER_FIXED_LEN,
+            })
+        );
+    }
+}
+
+#[test]
+fn test_truncated_nested_container() {
+    // the inner container cut short inside its fixed portion, with the offset of the field
+    // after it moved up so that the outer container's layout is still consistent
+    let inner_bytes = inner().to_ssz();
+    for len in 0..INNER_FIXED_LEN {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&9u64.to_le_bytes());
+        bytes.extend_from_slice(&(OUTER_FIXED_LEN as u32).to_le_bytes());
+        bytes.extend_from_slice(&[4; 4]);
+        bytes.extend_from_slice(&((OUTER_FIXED_LEN + len) as u32).to_le_bytes());
+        bytes.extend_from_slice(&inner_bytes[..len]);
+        bytes.extend_from_slice(&[5, 6]);
+
+        assert_eq!(
+            Outer::from_ssz_bytes(&bytes),
+            Err(DecodeError::InvalidByteLength {
+                len,
+                expected: INNER_FIXED_LEN,
+            }
+            .at_byte(OUTER_FIXED_LEN))
+        );
+        assert!(sszb::validate_ssz::<Outer>(&bytes).is_err());
+    }
+}
+
+#[test]
+fn test_truncated_variable_portion() {
+    // every cut inside the variable portion either fails or decodes to a shorter last list,
+    // and none of them panic
+    let bytes = outer().to_ssz();
+    for len in OUTER_FIXED_LEN..bytes.len() {
+        if let Ok(value) = Outer::from_ssz_bytes(&bytes[..len]) {
+            assert_eq!(value.inner, inner());
+            assert!(value.extra.len() < 2);
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Checkpoint {
+    #[ssz(encode_as = "u64 + 1")]
+    epoch: u64,
+    root: [u8; 32],
+}
+
+fn main() {}
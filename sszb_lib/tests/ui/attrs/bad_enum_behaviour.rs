@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+#[ssz(enum_behaviour = "untagged")]
+enum Message {
+    Ping(u64),
+    Pong(u64),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct State {
+    slot: u64,
+    #[ssz(parallel)]
+    balances: Vec<u64>,
+}
+
+fn main() {}
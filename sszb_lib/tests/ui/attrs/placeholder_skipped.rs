@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Block {
+    slot: u64,
+    #[ssz(placeholder, skip_encode, skip_decode)]
+    signature: [u8; 96],
+}
+
+fn main() {}
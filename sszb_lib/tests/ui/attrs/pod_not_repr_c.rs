@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+#[ssz(pod)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct State {
+    slot: u64,
+    #[ssz(skip_encode, skip_decode, ctx_max = "max_validators")]
+    validators: Vec<u64>,
+}
+
+fn main() {}
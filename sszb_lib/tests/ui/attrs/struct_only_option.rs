@@ -0,0 +1,10 @@
+use sszb_derive::SszbDecode;
+
+#[derive(SszbDecode)]
+#[ssz(zero)]
+enum Message {
+    None,
+    Ping(u64),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+#[ssz(enum_behaviour = "tag")]
+enum Fork {
+    Phase0 = 255,
+    Altair,
+}
+
+fn main() {}
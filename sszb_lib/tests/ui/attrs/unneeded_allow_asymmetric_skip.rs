@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Checkpoint {
+    epoch: u64,
+    #[ssz(skip_encode, skip_decode, allow_asymmetric_skip)]
+    root: [u8; 32],
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+enum Message {
+    None,
+    #[ssz(skip_encode)]
+    Ping(u64),
+}
+
+fn main() {}
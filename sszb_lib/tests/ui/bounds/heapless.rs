@@ -0,0 +1,23 @@
+// an item that implements the sszb traits and nothing else in a heapless vector
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U8;
+
+#[derive(SszbEncode, SszbDecode)]
+struct Item {
+    value: u64,
+    data: VariableList<u8, U8>,
+}
+
+#[derive(SszbEncode, SszbDecode)]
+struct Holder {
+    list: heapless::Vec<Item, 8>,
+}
+
+fn main() {
+    let holder = Holder::from_ssz_bytes(&[]);
+    assert!(holder.is_err());
+    let _ = heapless::Vec::<Item, 8>::new().to_ssz();
+}
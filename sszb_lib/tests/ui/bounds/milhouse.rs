@@ -0,0 +1,27 @@
+// milhouse's trees only hold items that implement its `Value` trait, which the item derives the
+// parts of. Beyond that, it only needs the sszb traits
+use bytes::buf::{Buf, BufMut};
+use milhouse::{List, Vector};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode, SszbTypeInfo};
+use tree_hash_derive::TreeHash;
+use typenum::{U4, U8};
+
+#[derive(Debug, Clone, PartialEq, TreeHash, SszbEncode, SszbDecode, SszbTypeInfo)]
+struct Item {
+    value: u64,
+    flag: bool,
+}
+
+#[derive(SszbEncode, SszbDecode, SszbTypeInfo)]
+struct Holder {
+    list: List<Item, U8>,
+    vector: Vector<Item, U4>,
+}
+
+fn main() {
+    let holder = Holder::from_ssz_bytes(&[]);
+    assert!(holder.is_err());
+    let _ = <Holder as sszb::dynamic::SszbTypeInfo>::ssz_type_info();
+    let _ = List::<Item, U8>::empty().to_ssz();
+}
@@ -0,0 +1,26 @@
+// an item that implements the sszb traits and nothing else in sszb's own runtime-bounded lists
+// and vectors
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::runtime::{RuntimeList, RuntimeVector};
+use sszb::SszbEncode;
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U8;
+
+#[derive(SszbEncode, SszbDecode)]
+struct Item {
+    value: u64,
+    data: VariableList<u8, U8>,
+}
+
+#[derive(SszbEncode)]
+struct Holder {
+    list: RuntimeList<Item>,
+    vector: RuntimeVector<Item>,
+}
+
+fn main() {
+    let list = RuntimeList::<Item>::from_ssz_bytes_with_max(&[], 4).unwrap();
+    assert!(RuntimeVector::<Item>::from_ssz_bytes_with_len(&[], 4).is_err());
+    let _ = list.to_ssz();
+}
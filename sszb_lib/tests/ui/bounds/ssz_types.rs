@@ -0,0 +1,27 @@
+// an item that implements the sszb traits and nothing else, not even Clone, in each of the
+// ssz_types collections
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{FixedVector, VariableList};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode, SszbTypeInfo};
+use typenum::{U4, U8};
+
+#[derive(SszbEncode, SszbDecode, SszbTypeInfo)]
+struct Item {
+    value: u64,
+    data: VariableList<u8, U8>,
+}
+
+#[derive(SszbEncode, SszbDecode, SszbTypeInfo)]
+struct Holder {
+    list: VariableList<Item, U8>,
+    vector: FixedVector<Item, U4>,
+    nested: VariableList<FixedVector<Item, U4>, U4>,
+}
+
+fn main() {
+    let holder = Holder::from_ssz_bytes(&[]);
+    assert!(holder.is_err());
+    let _ = <Holder as sszb::dynamic::SszbTypeInfo>::ssz_type_info();
+    let _ = VariableList::<Item, U8>::new(vec![]).unwrap().to_ssz();
+}
@@ -0,0 +1,11 @@
+use bytes::buf::BufMut;
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+#[ssz(monomorphize = "slices")]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+fn main() {}
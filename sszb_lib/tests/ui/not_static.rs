@@ -0,0 +1,13 @@
+use bytes::buf::BufMut;
+use sszb::Union2;
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Message {
+    slot: u64,
+    body: Union2<u64, u32>,
+}
+
+sszb::assert_ssz_static!(Message);
+
+fn main() {}
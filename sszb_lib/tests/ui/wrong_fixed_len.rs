@@ -0,0 +1,13 @@
+use bytes::buf::BufMut;
+use sszb_derive::SszbEncode;
+
+#[derive(SszbEncode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 32],
+}
+
+sszb::assert_ssz_fixed_len!(Checkpoint, 40);
+sszb::assert_ssz_fixed_len!(Checkpoint, 32);
+
+fn main() {}
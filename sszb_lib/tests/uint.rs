@@ -0,0 +1,64 @@
+#![cfg(feature = "alloy")]
+
+use alloy_primitives::{U128, U160, U256, U512, U64};
+use bytes::Buf;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+
+// encodes to the value's little-endian bytes, decodes back, and rejects every shorter input
+fn assert_uint<T: SszbEncode + SszbDecode + PartialEq + core::fmt::Debug>(
+    value: T,
+    le_bytes: &[u8],
+) {
+    assert_eq!(<T as SszbEncode>::SSZ_FIXED_LEN, le_bytes.len());
+    assert_eq!(value.to_ssz(), le_bytes);
+    assert_eq!(T::from_ssz_bytes(le_bytes), Ok(value));
+    for len in 0..le_bytes.len() {
+        assert_eq!(
+            T::from_ssz_bytes(&le_bytes[..len]),
+            Err(DecodeError::InvalidByteLength {
+                len,
+                expected: le_bytes.len()
+            })
+        );
+    }
+}
+
+#[test]
+fn test_uints() {
+    // U256 is encoded exactly as before, as its 32 little-endian bytes
+    let value = U256::from_be_bytes([0xab; 32]) - U256::from(7);
+    assert_uint(value, &value.to_le_bytes::<32>());
+    assert_uint(U256::MAX, &[0xff; 32]);
+    assert_uint(U128::from(0x0102_0304u64), &0x0102_0304u128.to_le_bytes());
+
+    assert_uint(
+        U64::from(0x0102_0304_0506_0708u64),
+        &0x0102_0304_0506_0708u64.to_le_bytes(),
+    );
+    // U160 is stored in three limbs, but only its 20 bytes are encoded
+    let value = U160::from_be_bytes([0x11; 20]);
+    assert_uint(value, &[0x11; 20]);
+    assert_uint(U160::MAX, &[0xff; 20]);
+    let value = U512::from(1u64) << 500;
+    let mut le_bytes = [0u8; 64];
+    le_bytes[62] = 0x10;
+    assert_uint(value, &le_bytes);
+}
+
+#[test]
+fn test_uints_from_chained_buffers() {
+    let value = U160::from_be_bytes([0x5a; 20]) + U160::from(3);
+    let mut bytes = value.to_ssz();
+    bytes.extend(value.to_ssz());
+    for at in 0..=bytes.len() {
+        let (front, back) = bytes.split_at(at);
+        let mut fixed_bytes = front.chain(back);
+        for _ in 0..2 {
+            assert_eq!(
+                U160::ssz_read(&mut fixed_bytes, &mut &[0u8; 0][..]),
+                Ok(value)
+            );
+        }
+        assert!(!fixed_bytes.has_remaining());
+    }
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "ssz-types")]
+
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode, Union2, Union3, Union4};
+use typenum::U16;
+
+type Bytes16 = VariableList<u8, U16>;
+
+fn bytes16(len: usize) -> Bytes16 {
+    VariableList::new((0..len).map(|i| i as u8).collect()).unwrap()
+}
+
+#[test]
+fn test_union_roundtrips() {
+    let values: Vec<Union3<u64, Bytes16, [u8; 4]>> = vec![
+        Union3::A(7),
+        Union3::B(bytes16(0)),
+        Union3::B(bytes16(16)),
+        Union3::C([9, 8, 7, 6]),
+    ];
+    for value in values {
+        let bytes = value.to_ssz();
+        assert_eq!(bytes.len(), value.sszb_bytes_len());
+        assert_eq!(Union3::from_ssz_bytes(&bytes), Ok(value));
+    }
+
+    // as a variable item of a list, behind an offset
+    let list = VariableList::<Union2<bool, Bytes16>, U16>::new(vec![
+        Union2::A(true),
+        Union2::B(bytes16(3)),
+        Union2::A(false),
+    ])
+    .unwrap();
+    assert_eq!(
+        VariableList::<Union2<bool, Bytes16>, U16>::from_ssz_bytes(&list.to_ssz()),
+        Ok(list)
+    );
+}
+
+#[test]
+fn test_union_matches_hand_encoding() {
+    let value: Union4<u8, u16, Bytes16, u32> = Union4::C(bytes16(3));
+    assert_eq!(value.to_ssz(), [2, 0, 1, 2]);
+
+    let value: Union4<u8, u16, Bytes16, u32> = Union4::D(0x01020304);
+    assert_eq!(value.to_ssz(), [3, 4, 3, 2, 1]);
+    assert_eq!(
+        Union4::<u8, u16, Bytes16, u32>::from_ssz_bytes(&[1, 0x34, 0x12]),
+        Ok(Union4::B(0x1234))
+    );
+}
+
+#[test]
+fn test_union_lengths() {
+    type U = Union2<u64, Bytes16>;
+    assert!(!<U as SszbEncode>::SSZ_STATIC);
+    assert!(!<U as SszbDecode>::is_ssz_static());
+    assert_eq!(
+        <U as SszbEncode>::SSZ_FIXED_LEN,
+        sszb::BYTES_PER_LENGTH_OFFSET
+    );
+    assert_eq!(<U as SszbEncode>::SSZ_MAX_LEN, 1 + 16);
+    assert_eq!(<Union2<u64, u16> as SszbDecode>::ssz_max_len(), 1 + 8);
+}
+
+#[test]
+fn test_union_invalid_bytes() {
+    type U = Union2<u64, Bytes16>;
+    assert_eq!(
+        U::from_ssz_bytes(&[]),
+        Err(DecodeError::InvalidByteLength {
+            len: 0,
+            expected: 1
+        })
+    );
+    assert_eq!(
+        U::from_ssz_bytes(&[2, 0, 0, 0, 0, 0, 0, 0, 0]),
+        Err(DecodeError::UnionSelectorInvalid(2))
+    );
+
+    // errors in the value are reported after the selector
+    let err = U::from_ssz_bytes(&[0, 1, 2, 3]).unwrap_err();
+    assert_eq!(err.position(), Some(1));
+    assert_eq!(
+        err.root_cause(),
+        &DecodeError::InvalidByteLength {
+            len: 3,
+            expected: 8
+        }
+    );
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::VariableList;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U64;
+
+type Payload = VariableList<u8, U64>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum Msg {
+    Ping {
+        seq: u64,
+    },
+    Data {
+        id: [u8; 32],
+        payload: Payload,
+        flags: u16,
+        extra: Payload,
+    },
+    Raw(Payload),
+}
+
+// the same layouts, written out as separate structs behind a selector
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Ping {
+    seq: u64,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Data {
+    id: [u8; 32],
+    payload: Payload,
+    flags: u16,
+    extra: Payload,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+enum Maybe {
+    None,
+    Some(u64),
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Envelope {
+    slot: u64,
+    msg: Msg,
+    maybe: Maybe,
+}
+
+fn payload(len: usize) -> Payload {
+    VariableList::new((0..len).map(|i| i as u8).collect()).unwrap()
+}
+
+fn data() -> (Msg, Data) {
+    let msg = Msg::Data {
+        id: [3; 32],
+        payload: payload(5),
+        flags: 0x0102,
+        extra: payload(2),
+    };
+    let data = Data {
+        id: [3; 32],
+        payload: payload(5),
+        flags: 0x0102,
+        extra: payload(2),
+    };
+    (msg, data)
+}
+
+fn with_selector(selector: u8, body: Vec<u8>) -> Vec<u8> {
+    let mut bytes = vec![selector];
+    bytes.extend(body);
+    bytes
+}
+
+#[test]
+fn test_struct_variants_match_separate_structs() {
+    let msg = Msg::Ping { seq: 42 };
+    let bytes = with_selector(0, Ping { seq: 42 }.to_ssz());
+    assert_eq!(msg.to_ssz(), bytes);
+    assert_eq!(msg.sszb_bytes_len(), bytes.len());
+    assert_eq!(Msg::from_ssz_bytes(&bytes), Ok(msg));
+
+    let (msg, data) = data();
+    let bytes = with_selector(1, data.to_ssz());
+    assert_eq!(msg.to_ssz(), bytes);
+    assert_eq!(msg.sszb_bytes_len(), bytes.len());
+    assert_eq!(Msg::from_ssz_bytes(&bytes), Ok(msg));
+
+    let msg = Msg::Raw(payload(7));
+    let bytes = with_selector(2, payload(7).to_ssz());
+    assert_eq!(msg.to_ssz(), bytes);
+    assert_eq!(Msg::from_ssz_bytes(&bytes), Ok(msg));
+}
+
+#[test]
+fn test_unions_in_containers() {
+    for (msg, maybe) in [
+        (Msg::Ping { seq: 1 }, Maybe::None),
+        (data().0, Maybe::Some(9)),
+        (Msg::Raw(payload(0)), Maybe::None),
+    ] {
+        let envelope = Envelope {
+            slot: 5,
+            msg,
+            maybe,
+        };
+        let bytes = envelope.to_ssz();
+        assert_eq!(bytes.len(), envelope.sszb_bytes_len());
+        assert_eq!(Envelope::from_ssz_bytes(&bytes), Ok(envelope));
+    }
+
+    assert_eq!(Maybe::None.to_ssz(), [0]);
+    assert_eq!(Maybe::Some(1).to_ssz(), [1, 1, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn test_union_lengths() {
+    assert!(!<Msg as SszbEncode>::SSZ_STATIC);
+    assert_eq!(<Msg as SszbDecode>::SSZ_FIXED_LEN, 4);
+    // the data variant is the largest
+    assert_eq!(
+        <Msg as SszbEncode>::SSZ_MAX_LEN,
+        1 + <Data as SszbEncode>::SSZ_MAX_LEN
+    );
+    assert_eq!(<Maybe as SszbDecode>::SSZ_MAX_LEN, 9);
+}
+
+#[test]
+fn test_invalid_unions() {
+    assert_eq!(
+        Msg::from_ssz_bytes(&[]),
+        Err(DecodeError::InvalidByteLength {
+            len: 0,
+            expected: 1
+        })
+    );
+    assert_eq!(
+        Msg::from_ssz_bytes(&[3, 0]),
+        Err(DecodeError::UnionSelectorInvalid(3))
+    );
+
+    // the empty variant has no body, and static variants don't take trailing bytes
+    assert_eq!(
+        Maybe::from_ssz_bytes(&[0, 0]).unwrap_err().position(),
+        Some(1)
+    );
+    let mut bytes = Msg::Ping { seq: 1 }.to_ssz();
+    bytes.push(0);
+    assert!(Msg::from_ssz_bytes(&bytes).is_err());
+
+    // the variant's offsets are checked like a container's, with positions counted from the
+    // selector
+    let (msg, _) = data();
+    let mut bytes = msg.to_ssz();
+    bytes[33] += 1;
+    let err = Msg::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(err.position(), Some(33));
+    assert_eq!(err.root_cause(), &DecodeError::OffsetSkipsVariableBytes(43));
+}
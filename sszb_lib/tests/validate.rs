@@ -0,0 +1,162 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U10, U2, U32, U4, U64, U8};
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct Checkpoint {
+    epoch: u64,
+    root: [u8; 4],
+}
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct Attestation {
+    bits: BitList<U64>,
+    target: Checkpoint,
+    flags: VariableList<bool, U8>,
+    committee: BitVector<U10>,
+}
+
+#[derive(PartialEq, Debug, Clone, SszbEncode, SszbDecode)]
+struct Block {
+    slot: u64,
+    attestations: VariableList<Attestation, U4>,
+    graffiti: VariableList<u8, U32>,
+    extra: FixedVector<VariableList<u16, U4>, U2>,
+    finalized: bool,
+}
+
+fn attestation(num_bits: usize, num_flags: usize) -> Attestation {
+    let mut bits = BitList::with_capacity(num_bits).unwrap();
+    for i in (0..num_bits).step_by(2) {
+        bits.set(i, true).unwrap();
+    }
+    let mut committee = BitVector::new();
+    committee.set(9, true).unwrap();
+    Attestation {
+        bits,
+        target: Checkpoint {
+            epoch: 3,
+            root: [1, 2, 3, 4],
+        },
+        flags: VariableList::new((0..num_flags).map(|i| i % 3 == 0).collect()).unwrap(),
+        committee,
+    }
+}
+
+fn block() -> Block {
+    Block {
+        slot: 12,
+        attestations: VariableList::new(vec![attestation(9, 2), attestation(0, 0)]).unwrap(),
+        graffiti: VariableList::new(b"sszb".to_vec()).unwrap(),
+        extra: FixedVector::new(vec![
+            VariableList::new(vec![1, 2]).unwrap(),
+            VariableList::empty(),
+        ])
+        .unwrap(),
+        finalized: true,
+    }
+}
+
+// truncations, extensions and single byte changes of a valid encoding, which between them break
+// every length and offset in it
+fn corpus(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut corpus = vec![bytes.to_vec(), Vec::new()];
+    for len in 0..bytes.len() {
+        corpus.push(bytes[..len].to_vec());
+    }
+    for extra in [0, 1, 0xff] {
+        let mut extended = bytes.to_vec();
+        extended.push(extra);
+        corpus.push(extended);
+    }
+    for i in 0..bytes.len() {
+        for value in [
+            0,
+            1,
+            2,
+            4,
+            0x80,
+            0xff,
+            bytes[i].wrapping_add(1),
+            bytes[i].wrapping_sub(1),
+        ] {
+            let mut changed = bytes.to_vec();
+            changed[i] = value;
+            corpus.push(changed);
+        }
+    }
+    corpus
+}
+
+// validation accepts exactly what decoding accepts, and fails the same way, except that the
+// wording of bitfield errors comes from ssz_types when decoding
+fn assert_validates_like_decode<T: SszbDecode>(bytes: &[u8]) -> usize {
+    let mut accepted = 0;
+    for input in corpus(bytes) {
+        match (validate_ssz::<T>(&input), T::from_ssz_bytes(&input)) {
+            (Ok(()), Ok(_)) => accepted += 1,
+            (Err(validate_err), Err(decode_err)) => {
+                if !matches!(decode_err.root_cause(), DecodeError::BitfieldError(_)) {
+                    assert_eq!(validate_err, decode_err, "{:?}", input);
+                }
+            }
+            (validated, decoded) => panic!(
+                "validate_ssz gave {:?} but decoding gave {:?} for {:?}",
+                validated,
+                decoded.map(drop),
+                input
+            ),
+        }
+    }
+    accepted
+}
+
+#[test]
+fn test_validate_matches_decode() {
+    let accepted = assert_validates_like_decode::<Block>(&block().to_ssz());
+    // some changes still decode, e.g. to the slot or the graffiti
+    assert!(accepted > 1);
+
+    assert_validates_like_decode::<Attestation>(&attestation(17, 5).to_ssz());
+    assert_validates_like_decode::<Checkpoint>(&block().attestations[0].target.to_ssz());
+    assert_validates_like_decode::<VariableList<Attestation, U4>>(&block().attestations.to_ssz());
+    assert_validates_like_decode::<FixedVector<VariableList<u16, U4>, U2>>(&block().extra.to_ssz());
+    assert_validates_like_decode::<VariableList<BitList<U10>, U4>>(
+        &VariableList::<BitList<U10>, U4>::new(vec![BitList::with_capacity(8).unwrap()])
+            .unwrap()
+            .to_ssz(),
+    );
+}
+
+#[test]
+fn test_validate_errors() {
+    let mut bytes = block().to_ssz();
+    assert_eq!(validate_ssz::<Block>(&bytes), Ok(()));
+
+    // finalized, after the slot and three offsets, isn't a boolean any more
+    bytes[20] = 2;
+    let err = validate_ssz::<Block>(&bytes).unwrap_err();
+    assert_eq!(err.root_cause(), &DecodeError::InvalidBool(2));
+    assert_eq!(err.position(), Some(20));
+
+    assert_eq!(
+        validate_ssz::<VariableList<u64, U4>>(&[0; 40]),
+        Err(DecodeError::ListTooLong { len: 5, max: 4 })
+    );
+    assert_eq!(
+        validate_ssz::<BitList<U8>>(&[0, 0]),
+        Err(DecodeError::BitfieldError("its length bit is missing"))
+    );
+    assert_eq!(
+        validate_ssz::<BitList<U8>>(&[0, 0b10]),
+        Err(DecodeError::BitfieldError("it exceeds its maximum length"))
+    );
+    assert_eq!(
+        validate_ssz::<BitVector<U10>>(&[0, 0b100]),
+        Err(DecodeError::BitfieldError("bits are set past its length"))
+    );
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "ssz-types")]
+
+use bytes::buf::{Buf, BufMut};
+use bytes::Bytes;
+use ssz_types::VariableList;
+use sszb::{validate_ssz, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use std::fmt;
+use typenum::{U16, U4};
+
+mod checks {
+    use super::*;
+
+    pub fn nonzero_slot(block: &Block) -> Result<(), String> {
+        if block.slot == 0 {
+            return Err("slot must not be zero".into());
+        }
+        Ok(())
+    }
+
+    pub struct TooManyVotes(pub usize);
+
+    impl fmt::Display for TooManyVotes {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{} votes for a committee of 8", self.0)
+        }
+    }
+
+    pub fn committee_votes(votes: &VariableList<u8, U16>) -> Result<(), TooManyVotes> {
+        if votes.len() > 8 {
+            return Err(TooManyVotes(votes.len()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(validate = "checks::nonzero_slot")]
+struct Block {
+    slot: u64,
+    #[ssz(validate = "checks::committee_votes")]
+    votes: VariableList<u8, U16>,
+    proposer: u32,
+}
+
+// a static container with a hook, in a list
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(validate = "Header::check")]
+struct Header {
+    slot: u64,
+    parent_slot: u64,
+}
+
+impl Header {
+    fn check(&self) -> Result<(), String> {
+        if self.parent_slot >= self.slot {
+            return Err(format!(
+                "parent slot {} isn't before {}",
+                self.parent_slot, self.slot
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn block(slot: u64, votes: usize) -> Block {
+    Block {
+        slot,
+        votes: VariableList::new(vec![1; votes]).unwrap(),
+        proposer: 7,
+    }
+}
+
+fn assert_invalid(err: &DecodeError, text: &str) {
+    #[allow(deprecated)]
+    let DecodeError::BytesInvalid(message) = err.root_cause() else {
+        panic!("expected the hook's error, got {:?}", err);
+    };
+    assert!(
+        message.contains(text),
+        "{:?} doesn't mention {:?}",
+        message,
+        text
+    );
+}
+
+#[test]
+fn test_valid_value_round_trips() {
+    let block = block(5, 3);
+    let bytes = block.to_ssz();
+    assert_eq!(Block::from_ssz_bytes(&bytes).as_ref(), Ok(&block));
+    assert_eq!(validate_ssz::<Block>(&bytes), Ok(()));
+}
+
+#[test]
+fn test_container_hook_rejects_zero_slot() {
+    // encoding is unaffected by the hooks
+    let bytes = block(0, 3).to_ssz();
+
+    let err = Block::from_ssz_bytes(&bytes).unwrap_err();
+    assert_invalid(&err, "slot must not be zero");
+    assert_invalid(&err, "`Block`");
+
+    // every other way of decoding runs the hook too
+    assert_eq!(validate_ssz::<Block>(&bytes), Err(err.clone()));
+    assert_eq!(
+        Block::decode_from_buf(&mut Bytes::from(bytes.clone())),
+        Err(err.clone())
+    );
+    assert_eq!(Block::from_ssz_bytes_trusted(&bytes), Err(err.clone()));
+    let mut target = block(9, 1);
+    assert_eq!(target.decode_into(&bytes), Err(err));
+}
+
+#[test]
+fn test_field_hook_converts_custom_error() {
+    let bytes = block(5, 9).to_ssz();
+    let err = Block::from_ssz_bytes(&bytes).unwrap_err();
+    assert_invalid(&err, "9 votes for a committee of 8");
+    assert_invalid(&err, "field `votes` of `Block`");
+    assert_eq!(validate_ssz::<Block>(&bytes), Err(err));
+}
+
+#[test]
+fn test_hooks_run_for_list_items() {
+    let headers: VariableList<Header, U4> = VariableList::new(vec![
+        Header {
+            slot: 2,
+            parent_slot: 1,
+        },
+        Header {
+            slot: 2,
+            parent_slot: 2,
+        },
+    ])
+    .unwrap();
+    let bytes = headers.to_ssz();
+
+    let err = VariableList::<Header, U4>::from_ssz_bytes(&bytes).unwrap_err();
+    assert_invalid(&err, "parent slot 2 isn't before 2");
+    // the second header is at fault
+    assert!(matches!(err, DecodeError::AtByte { at_byte: 16, .. }));
+    assert_eq!(validate_ssz::<VariableList<Header, U4>>(&bytes), Err(err));
+
+    assert!(Header::from_ssz_bytes(&bytes[..16]).is_ok());
+}
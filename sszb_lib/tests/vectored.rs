@@ -0,0 +1,94 @@
+#![cfg(all(feature = "ssz-types", feature = "milhouse"))]
+
+use bytes::buf::{Buf, BufMut};
+use milhouse::List;
+use ssz_types::{FixedVector, VariableList};
+use sszb::{vectored::Segments, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U1048576, U16, U2048, U32};
+
+type Transaction = VariableList<u8, U1048576>;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Payload {
+    block_number: u64,
+    extra_data: VariableList<u8, U32>,
+    logs_bloom: FixedVector<u8, U2048>,
+    transactions: VariableList<Transaction, U16>,
+    blobs: List<Transaction, U16>,
+    fee_recipient: [u8; 20],
+}
+
+fn bytes(len: usize, seed: u8) -> Vec<u8> {
+    (0..len).map(|i| (i as u8).wrapping_mul(seed)).collect()
+}
+
+fn payload(tx_lens: &[usize]) -> Payload {
+    let txs = tx_lens
+        .iter()
+        .enumerate()
+        .map(|(i, len)| VariableList::new(bytes(*len, i as u8 + 1)).unwrap())
+        .collect::<Vec<_>>();
+    Payload {
+        block_number: 17,
+        extra_data: VariableList::new(bytes(20, 3)).unwrap(),
+        logs_bloom: FixedVector::new(bytes(2048, 5)).unwrap(),
+        transactions: VariableList::new(txs.clone()).unwrap(),
+        blobs: List::new(txs).unwrap(),
+        fee_recipient: [9; 20],
+    }
+}
+
+fn points_into(value: &[u8], segment: &[u8]) -> bool {
+    value.as_ptr_range() == segment.as_ptr_range()
+}
+
+#[test]
+fn test_flattened_segments_match_to_ssz() {
+    for tx_lens in [
+        &[][..],
+        &[0],
+        &[10, 20_000],
+        &[1023, 1024, 1025],
+        &[300_000, 0, 5, 70_000],
+    ] {
+        let payload = payload(tx_lens);
+        let mut segments = Segments::new();
+        payload.ssz_write_vectored(&mut segments);
+
+        let bytes = payload.to_ssz();
+        assert_eq!(segments.len(), bytes.len());
+        assert_eq!(segments.flatten(), bytes);
+        assert_eq!(
+            segments
+                .io_slices()
+                .iter()
+                .map(|slice| slice.len())
+                .sum::<usize>(),
+            bytes.len()
+        );
+        assert_eq!(Payload::from_ssz_bytes(&segments.flatten()), Ok(payload));
+    }
+}
+
+#[test]
+fn test_long_byte_fields_are_borrowed() {
+    let payload = payload(&[10, 300_000, 70_000]);
+    let mut segments = Segments::new();
+    payload.ssz_write_vectored(&mut segments);
+
+    // the two long transactions, in both lists, and the bloom are borrowed, the rest is copied
+    let borrowed = 2 * (300_000 + 70_000) + 2048;
+    assert_eq!(segments.copied_len(), payload.sszb_bytes_len() - borrowed);
+
+    let all = segments.iter().collect::<Vec<_>>();
+    assert!(all
+        .iter()
+        .any(|segment| points_into(&payload.logs_bloom, segment)));
+    assert!(all
+        .iter()
+        .any(|segment| points_into(&payload.transactions[1], segment)));
+    assert!(!all
+        .iter()
+        .any(|segment| points_into(&payload.transactions[0], segment)));
+}
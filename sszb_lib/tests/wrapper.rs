@@ -0,0 +1,177 @@
+use bytes::buf::{Buf, BufMut};
+use foreign::{Epoch, Graffiti, Slot};
+use sszb::{impl_ssz_wrapper, ByteList, DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::U32;
+
+// stands in for a crate that only exposes its types through conversions
+mod foreign {
+    use sszb::ByteList;
+    use typenum::U32;
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct Slot(u64);
+
+    impl From<u64> for Slot {
+        fn from(slot: u64) -> Self {
+            Slot(slot)
+        }
+    }
+
+    impl From<Slot> for u64 {
+        fn from(slot: Slot) -> Self {
+            slot.0
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct Epoch(u64);
+
+    #[derive(Debug)]
+    pub struct EpochTooLarge(pub u64);
+
+    impl Epoch {
+        pub const MAX: u64 = 1 << 40;
+    }
+
+    impl TryFrom<u64> for Epoch {
+        type Error = EpochTooLarge;
+
+        fn try_from(epoch: u64) -> Result<Self, EpochTooLarge> {
+            if epoch > Epoch::MAX {
+                return Err(EpochTooLarge(epoch));
+            }
+            Ok(Epoch(epoch))
+        }
+    }
+
+    impl From<Epoch> for u64 {
+        fn from(epoch: Epoch) -> Self {
+            epoch.0
+        }
+    }
+
+    // UTF-8 of at most 32 bytes
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct Graffiti(String);
+
+    impl Graffiti {
+        pub fn new(graffiti: &str) -> Self {
+            assert!(graffiti.len() <= 32);
+            Graffiti(graffiti.to_string())
+        }
+    }
+
+    impl TryFrom<ByteList<U32>> for Graffiti {
+        type Error = std::string::FromUtf8Error;
+
+        fn try_from(bytes: ByteList<U32>) -> Result<Self, Self::Error> {
+            String::from_utf8(bytes.to_vec()).map(Graffiti)
+        }
+    }
+
+    impl From<Graffiti> for ByteList<U32> {
+        fn from(graffiti: Graffiti) -> Self {
+            ByteList::new(graffiti.0.into_bytes()).unwrap()
+        }
+    }
+}
+
+impl_ssz_wrapper!(Slot => u64);
+impl_ssz_wrapper!(Epoch => u64);
+impl_ssz_wrapper!(Graffiti => ByteList<U32>);
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct Block {
+    slot: Slot,
+    graffiti: Graffiti,
+    epoch: Epoch,
+}
+
+// the same layout, with the inner types
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+struct RawBlock {
+    slot: u64,
+    graffiti: ByteList<U32>,
+    epoch: u64,
+}
+
+fn block() -> Block {
+    Block {
+        slot: Slot::from(12),
+        graffiti: Graffiti::new("wrapped"),
+        epoch: Epoch::try_from(3).unwrap(),
+    }
+}
+
+fn raw_block(epoch: u64, graffiti: &[u8]) -> RawBlock {
+    RawBlock {
+        slot: 12,
+        graffiti: ByteList::new(graffiti.to_vec()).unwrap(),
+        epoch,
+    }
+}
+
+#[test]
+fn test_same_bytes_as_inner() {
+    assert_eq!(Slot::from(7).to_ssz(), 7u64.to_ssz());
+    assert_eq!(Slot::from_ssz_bytes(&7u64.to_ssz()), Ok(Slot::from(7)));
+    assert_eq!(
+        Graffiti::new("abc").to_ssz(),
+        ByteList::<U32>::new(&b"abc"[..]).unwrap().to_ssz()
+    );
+    const { assert!(<Slot as SszbEncode>::SSZ_STATIC) };
+    const { assert!(!<Graffiti as SszbEncode>::SSZ_STATIC) };
+    assert_eq!(
+        <Graffiti as SszbDecode>::SSZ_SCHEMA,
+        <ByteList<U32> as SszbDecode>::SSZ_SCHEMA
+    );
+
+    // and as fields, with the offsets of the inner types
+    let bytes = raw_block(3, b"wrapped").to_ssz();
+    assert_eq!(block().to_ssz(), bytes);
+    assert_eq!(block().sszb_bytes_len(), bytes.len());
+    let mut buf = vec![0; bytes.len()];
+    assert_eq!(block().ssz_write_slice(&mut buf), bytes.len());
+    assert_eq!(buf, bytes);
+    assert_eq!(Block::from_ssz_bytes(&bytes), Ok(block()));
+    assert_eq!(Block::from_ssz_bytes_trusted(&bytes), Ok(block()));
+    assert_eq!(
+        <Block as SszbDecode>::SSZ_SCHEMA,
+        <RawBlock as SszbDecode>::SSZ_SCHEMA
+    );
+}
+
+// conversion failures are reported as BytesInvalid
+#[allow(deprecated)]
+#[test]
+fn test_failed_conversions() {
+    let err = Epoch::from_ssz_bytes(&(Epoch::MAX + 1).to_ssz()).unwrap_err();
+    assert!(
+        matches!(&err, DecodeError::BytesInvalid(message) if message.contains("EpochTooLarge")),
+        "{:?}",
+        err
+    );
+
+    // inside a container, the error is at the field
+    let err = Block::from_ssz_bytes(&raw_block(Epoch::MAX + 1, b"").to_ssz()).unwrap_err();
+    assert_eq!(err.position(), Some(12));
+    assert!(matches!(err.root_cause(), DecodeError::BytesInvalid(_)));
+
+    let err = Block::from_ssz_bytes(&raw_block(3, &[0xff, 0xfe]).to_ssz()).unwrap_err();
+    assert_eq!(err.position(), Some(20));
+    assert!(
+        matches!(err.root_cause(), DecodeError::BytesInvalid(message) if message.contains("Utf8")),
+        "{:?}",
+        err
+    );
+
+    // errors of the inner type are passed on as they are
+    assert_eq!(
+        Slot::from_ssz_bytes(&[0; 7]),
+        Err(DecodeError::InvalidByteLength {
+            len: 7,
+            expected: 8
+        })
+    );
+}
@@ -0,0 +1,171 @@
+#![cfg(all(debug_assertions, feature = "ssz-types"))]
+
+// the derived encoders check, in debug builds, that what they write is as long as their lengths
+// say. the impls below get their lengths wrong on purpose, the way a drifted length formula would
+
+use bytes::buf::BufMut;
+use ssz_types::VariableList;
+use sszb::{stream_ssz_to, SszbEncode};
+use sszb_derive::SszbEncode;
+use typenum::U16;
+
+// claims 8 bytes, writes 4
+struct Short(u32);
+
+impl SszbEncode for Short {
+    const SSZ_STATIC: bool = true;
+    const SSZ_FIXED_LEN: usize = 8;
+    const SSZ_MAX_LEN: usize = 8;
+
+    fn sszb_bytes_len(&self) -> usize {
+        8
+    }
+
+    fn ssz_write_fixed(&self, _offset: &mut usize, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write_variable(&self, _buf: &mut impl BufMut) {}
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_u32_le(self.0);
+    }
+}
+
+// measures its bytes, but writes a trailing zero after them
+struct Padded(Vec<u8>);
+
+impl SszbEncode for Padded {
+    const SSZ_STATIC: bool = false;
+    const SSZ_FIXED_LEN: usize = sszb::BYTES_PER_LENGTH_OFFSET;
+    const SSZ_MAX_LEN: usize = usize::MAX;
+
+    fn sszb_bytes_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn ssz_write_fixed(&self, offset: &mut usize, buf: &mut impl BufMut) {
+        sszb::expect_offset(sszb::write_offset(*offset, buf));
+        *offset += self.sszb_bytes_len();
+    }
+
+    fn ssz_write_variable(&self, buf: &mut impl BufMut) {
+        self.ssz_write(buf);
+    }
+
+    fn ssz_write(&self, buf: &mut impl BufMut) {
+        buf.put_slice(&self.0);
+        buf.put_u8(0);
+    }
+}
+
+#[derive(SszbEncode)]
+struct Header {
+    slot: u64,
+    short: Short,
+}
+
+#[derive(SszbEncode)]
+struct Block {
+    header: Header,
+    parent: u64,
+}
+
+#[derive(SszbEncode)]
+struct Message {
+    id: u32,
+    body: Padded,
+}
+
+#[derive(SszbEncode)]
+enum Payload {
+    Empty,
+    Body(Padded),
+}
+
+#[derive(SszbEncode)]
+struct Consistent {
+    id: u32,
+    body: VariableList<u8, U16>,
+}
+
+#[test]
+#[should_panic(
+    expected = "SSZ length mismatch: Header::ssz_write wrote 12 bytes, but its lengths add up to 16"
+)]
+fn test_static_container() {
+    Header {
+        slot: 1,
+        short: Short(2),
+    }
+    .to_ssz();
+}
+
+#[test]
+#[should_panic(expected = "SSZ length mismatch: Header::ssz_write_fixed wrote 12 bytes")]
+fn test_static_field() {
+    let block = Block {
+        header: Header {
+            slot: 1,
+            short: Short(2),
+        },
+        parent: 3,
+    };
+    block.ssz_write(&mut Vec::new());
+}
+
+#[test]
+#[should_panic(
+    expected = "SSZ length mismatch: Message::ssz_write wrote 10 bytes, but its lengths add up to 9"
+)]
+fn test_variable_field() {
+    let message = Message {
+        id: 1,
+        body: Padded(vec![2]),
+    };
+    message.ssz_write(&mut Vec::new());
+}
+
+#[test]
+#[should_panic(expected = "SSZ length mismatch: Message::ssz_write_cached")]
+fn test_variable_field_cached() {
+    Message {
+        id: 1,
+        body: Padded(vec![2]),
+    }
+    .to_ssz();
+}
+
+#[test]
+#[should_panic(
+    expected = "SSZ length mismatch: Payload::ssz_write wrote 3 bytes, but its lengths add up to 2"
+)]
+fn test_union() {
+    Payload::Body(Padded(vec![2])).ssz_write(&mut Vec::new());
+}
+
+#[test]
+#[should_panic(expected = "::ssz_write wrote 6 bytes, but its lengths add up to 5")]
+fn test_list() {
+    let list = VariableList::<Padded, U16>::new(vec![Padded(vec![2])]).unwrap();
+    list.ssz_write(&mut Vec::new());
+}
+
+#[test]
+fn test_consistent_impls() {
+    let value = Consistent {
+        id: 1,
+        body: VariableList::new(vec![2, 3]).unwrap(),
+    };
+    assert_eq!(value.to_ssz(), [1, 0, 0, 0, 8, 0, 0, 0, 2, 3]);
+    let mut buf = [0u8; 10];
+    value.ssz_write(&mut buf.as_mut_slice());
+    assert_eq!(Payload::Empty.to_ssz(), [0]);
+
+    // a stream can't tell how much was written, so it isn't checked rather than failing
+    let message = Message {
+        id: 1,
+        body: Padded(vec![2]),
+    };
+    assert_eq!(stream_ssz_to(&message, Vec::new(), 64).unwrap(), 10);
+}
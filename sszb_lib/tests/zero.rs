@@ -0,0 +1,96 @@
+#![cfg(all(feature = "alloy", feature = "ssz-types"))]
+
+use alloy_primitives::{Address, B256};
+use bytes::buf::{Buf, BufMut};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::{ByteList, SszZero, SszbDecode, SszbEncode, Union2};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U12, U2, U3, U4, U8};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Millis(u64);
+
+impl From<Millis> for u64 {
+    fn from(millis: Millis) -> Self {
+        millis.0
+    }
+}
+
+impl From<u64> for Millis {
+    fn from(millis: u64) -> Self {
+        Millis(millis)
+    }
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(zero)]
+struct Checkpoint {
+    epoch: u64,
+    root: B256,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(zero)]
+struct Header {
+    slot: u64,
+    active: bool,
+    checkpoint: Checkpoint,
+    roots: FixedVector<B256, U2>,
+    bits: BitVector<U12>,
+    extra: VariableList<u64, U8>,
+    attesters: BitList<U8>,
+    choice: Union2<u32, ByteList<U4>>,
+    #[ssz(encode_as = "u64")]
+    duration: Millis,
+    #[ssz(skip_encode, skip_decode)]
+    cache: Option<u64>,
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+#[ssz(zero)]
+struct Generic<T: SszbEncode + SszbDecode> {
+    item: T,
+}
+
+#[test]
+fn test_container_zero() {
+    let zero = Header::ssz_zero();
+    assert_eq!(zero.checkpoint, Checkpoint::ssz_zero());
+    assert_eq!(zero.roots.len(), 2);
+    assert_eq!(zero.choice, Union2::A(0));
+    assert_eq!(zero.cache, None);
+
+    // the fixed part is 8 + 1 + 40 + 64 + 2 bytes of zeros, three offsets and the duration, then
+    // the empty list, the empty bitlist's length bit and the union's selector and u32
+    let mut expected = vec![0; 115];
+    expected.extend_from_slice(&135u32.to_le_bytes());
+    expected.extend_from_slice(&135u32.to_le_bytes());
+    expected.extend_from_slice(&136u32.to_le_bytes());
+    expected.extend_from_slice(&[0; 8]);
+    expected.extend_from_slice(&[0x01]);
+    expected.extend_from_slice(&[0; 5]);
+
+    let bytes = zero.to_ssz();
+    assert_eq!(bytes, expected);
+    assert_eq!(Header::from_ssz_bytes(&bytes), Ok(zero));
+    assert_eq!(<Header as SszZero>::ssz_zero(), Header::ssz_zero());
+}
+
+#[test]
+fn test_built_in_zeros() {
+    assert_eq!(<u64 as SszZero>::ssz_zero().to_ssz(), [0; 8]);
+    assert_eq!(<bool as SszZero>::ssz_zero().to_ssz(), [0]);
+    assert_eq!(<[u8; 3] as SszZero>::ssz_zero().to_ssz(), [0; 3]);
+    assert_eq!(Address::ssz_zero().to_ssz(), [0; 20]);
+    assert_eq!(BitVector::<U12>::ssz_zero().to_ssz(), [0; 2]);
+    assert_eq!(BitList::<U8>::ssz_zero().to_ssz(), [0x01]);
+    assert!(ByteList::<U4>::ssz_zero().to_ssz().is_empty());
+    assert_eq!(Generic::<FixedVector<u16, U3>>::ssz_zero().to_ssz(), [0; 6]);
+    assert_eq!(
+        Generic::<FixedVector<Checkpoint, U2>>::ssz_zero().to_ssz(),
+        [0; 80]
+    );
+    assert!(Generic::<VariableList<Checkpoint, U2>>::ssz_zero()
+        .item
+        .is_empty());
+}
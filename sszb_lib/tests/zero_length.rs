@@ -0,0 +1,262 @@
+#![cfg(feature = "testing")]
+
+// every collection at a length of zero. lists of no items are fine, and only the empty list
+// encodes as one, while the consensus specs make vectors and bit vectors of no items illegal:
+// they encode as the spec says, but no bytes decode as one
+
+use alloy_primitives::FixedBytes;
+use bytes::buf::{Buf, BufMut};
+use milhouse::{List, Vector};
+use ssz_types::{BitList, BitVector, FixedVector, VariableList};
+use sszb::dynamic::{decode_dynamic, SszTypeInfo};
+use sszb::roundtrip_ssz;
+use sszb::testing::proptest::prelude::*;
+use sszb::testing::*;
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+use typenum::{U0, U4};
+
+type Bytes = VariableList<u8, U4>;
+
+// the encode and decode impls of a type have to agree on its layout
+macro_rules! assert_lens_agree {
+    ($($ty: ty),* $(,)?) => {
+        $(
+            assert_eq!(
+                <$ty as SszbEncode>::SSZ_STATIC,
+                <$ty as SszbDecode>::SSZ_STATIC,
+                "{}",
+                stringify!($ty)
+            );
+            assert_eq!(
+                <$ty as SszbEncode>::SSZ_FIXED_LEN,
+                <$ty as SszbDecode>::SSZ_FIXED_LEN,
+                "{}",
+                stringify!($ty)
+            );
+            assert_eq!(
+                <$ty as SszbEncode>::SSZ_MAX_LEN,
+                <$ty as SszbDecode>::SSZ_MAX_LEN,
+                "{}",
+                stringify!($ty)
+            );
+        )*
+    };
+}
+
+// every way of decoding a vector of no items rejects it
+fn assert_rejected<T: SszbDecode + std::fmt::Debug>(bytes: &[u8]) {
+    assert_eq!(
+        T::from_ssz_bytes(bytes).unwrap_err().root_cause(),
+        &DecodeError::ZeroLengthVector
+    );
+    assert_eq!(
+        T::validate_ssz_bytes(bytes).unwrap_err().root_cause(),
+        &DecodeError::ZeroLengthVector
+    );
+    assert_eq!(
+        T::from_ssz_bytes_trusted(bytes).unwrap_err().root_cause(),
+        &DecodeError::ZeroLengthVector
+    );
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct EmptyLists {
+    slot: u64,
+    items: VariableList<u64, U0>,
+    names: VariableList<Bytes, U0>,
+    bits: BitList<U0>,
+    tree: List<u64, U0>,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct EmptyVectors {
+    slot: u64,
+    bits: BitVector<U0>,
+    roots: FixedVector<u64, U0>,
+    names: FixedVector<Bytes, U0>,
+    items: VariableList<u64, U0>,
+}
+
+#[derive(Clone, PartialEq, Debug, SszbEncode, SszbDecode)]
+struct EmptyBitsFirst {
+    bits: BitVector<U0>,
+    items: VariableList<u64, U4>,
+}
+
+roundtrip_ssz!(list: VariableList<u64, U0>, variable_list(any::<u64>()));
+roundtrip_ssz!(
+    list_of_lists: VariableList<Bytes, U0>,
+    variable_list(variable_list(any::<u8>()))
+);
+roundtrip_ssz!(persistent_list: List<u64, U0>, persistent_list(any::<u64>()));
+roundtrip_ssz!(
+    persistent_list_of_lists: List<Bytes, U0>,
+    persistent_list(variable_list(any::<u8>()))
+);
+roundtrip_ssz!(bit_list: BitList<U0>, bit_list());
+
+#[test]
+fn test_lens_agree() {
+    assert_lens_agree!(
+        VariableList<u64, U0>,
+        VariableList<Bytes, U0>,
+        FixedVector<u64, U0>,
+        FixedVector<Bytes, U0>,
+        List<u64, U0>,
+        List<Bytes, U0>,
+        Vector<u64, U0>,
+        Vector<Bytes, U0>,
+        BitList<U0>,
+        BitVector<U0>,
+        [u8; 0],
+        FixedBytes<0>,
+        EmptyLists,
+        EmptyVectors,
+    );
+    #[cfg(feature = "ghilhouse")]
+    assert_lens_agree!(
+        ghilhouse::List<u64, U0>,
+        ghilhouse::List<Bytes, U0>,
+        ghilhouse::Vector<u64, U0>,
+        ghilhouse::Vector<Bytes, U0>,
+    );
+
+    // a vector of static items takes up no bytes, and one of variable items just its offset
+    assert_eq!(<FixedVector<u64, U0> as SszbEncode>::SSZ_FIXED_LEN, 0);
+    assert_eq!(<Vector<u64, U0> as SszbEncode>::SSZ_FIXED_LEN, 0);
+    assert_eq!(<BitVector<U0> as SszbEncode>::SSZ_FIXED_LEN, 0);
+    assert!(!<FixedVector<Bytes, U0> as SszbEncode>::SSZ_STATIC);
+    assert_eq!(<FixedVector<Bytes, U0> as SszbEncode>::SSZ_MAX_LEN, 0);
+    // a bit list of no bits is just its length bit
+    assert_eq!(<BitList<U0> as SszbEncode>::SSZ_MAX_LEN, 1);
+    assert_eq!(<EmptyVectors as SszbEncode>::SSZ_FIXED_LEN, 8 + 4 + 4);
+}
+
+#[test]
+fn test_empty_lists() {
+    assert!(VariableList::<u64, U0>::empty().to_ssz().is_empty());
+    assert_eq!(BitList::<U0>::with_capacity(0).unwrap().to_ssz(), [1]);
+
+    // any item is one too many
+    let too_long = DecodeError::ListTooLong { len: 1, max: 0 };
+    assert_eq!(
+        VariableList::<u64, U0>::from_ssz_bytes(&[0; 8]),
+        Err(too_long.clone())
+    );
+    assert_eq!(
+        VariableList::<Bytes, U0>::from_ssz_bytes(&[4, 0, 0, 0]),
+        Err(too_long.clone())
+    );
+    assert_eq!(
+        VariableList::<Bytes, U0>::validate_ssz_bytes(&[4, 0, 0, 0]),
+        Err(too_long)
+    );
+    assert_eq!(
+        List::<u64, U0>::from_ssz_bytes(&[0; 16]),
+        Err(DecodeError::ListTooLong { len: 2, max: 0 })
+    );
+    assert!(BitList::<U0>::from_ssz_bytes(&[2]).is_err());
+
+    let value = EmptyLists {
+        slot: 3,
+        items: VariableList::empty(),
+        names: VariableList::empty(),
+        bits: BitList::with_capacity(0).unwrap(),
+        tree: List::empty(),
+    };
+    let bytes = value.to_ssz();
+    assert_eq!(bytes.len(), 8 + 4 * 4 + 1);
+    assert_eq!(EmptyLists::from_ssz_bytes(&bytes), Ok(value));
+}
+
+#[test]
+fn test_empty_vectors_are_rejected() {
+    let mut roots = FixedVector::<u64, U0>::new(vec![]).unwrap();
+    let names = FixedVector::<Bytes, U0>::new(vec![]).unwrap();
+    assert!(roots.to_ssz().is_empty());
+    assert!(names.to_ssz().is_empty());
+    // ssz_types keeps a byte for the bits, which isn't part of the encoding
+    assert!(BitVector::<U0>::new().to_ssz().is_empty());
+    assert!([0u8; 0].to_ssz().is_empty());
+
+    for bytes in [&[][..], &[0][..], &[4, 0, 0, 0][..]] {
+        assert_rejected::<FixedVector<Bytes, U0>>(bytes);
+        assert_rejected::<Vector<Bytes, U0>>(bytes);
+        #[cfg(feature = "ghilhouse")]
+        assert_rejected::<ghilhouse::Vector<Bytes, U0>>(bytes);
+    }
+    // static ones are rejected for their length first, unless there are no bytes at all
+    assert_rejected::<FixedVector<u64, U0>>(&[]);
+    assert_rejected::<Vector<u64, U0>>(&[]);
+    #[cfg(feature = "ghilhouse")]
+    assert_rejected::<ghilhouse::Vector<u64, U0>>(&[]);
+    assert_rejected::<BitVector<U0>>(&[]);
+    assert_rejected::<[u8; 0]>(&[]);
+    assert!(FixedVector::<u64, U0>::from_ssz_bytes(&[0; 8]).is_err());
+    assert!(BitVector::<U0>::from_ssz_bytes(&[0]).is_err());
+    assert!(FixedBytes::<0>::from_ssz_bytes(&[]).is_err());
+
+    // decoding into an existing vector rejects it too
+    assert_eq!(
+        roots.decode_into(&[]).unwrap_err().root_cause(),
+        &DecodeError::ZeroLengthVector
+    );
+}
+
+#[test]
+fn test_containers_of_empty_vectors() {
+    let value = EmptyVectors {
+        slot: 7,
+        bits: BitVector::new(),
+        roots: FixedVector::new(vec![]).unwrap(),
+        names: FixedVector::new(vec![]).unwrap(),
+        items: VariableList::empty(),
+    };
+
+    // the container encodes fine, but can't be decoded, its bit vector being the first field
+    // to fail
+    let bytes = value.to_ssz();
+    assert_eq!(bytes, [7, 0, 0, 0, 0, 0, 0, 0, 16, 0, 0, 0, 16, 0, 0, 0]);
+    let error = EmptyVectors::from_ssz_bytes(&bytes).unwrap_err();
+    assert_eq!(error.root_cause(), &DecodeError::ZeroLengthVector);
+    assert_eq!(error.position(), Some(8));
+    assert!(EmptyVectors::validate_ssz_bytes(&bytes).is_err());
+}
+
+// ssz_types keeps a byte in memory for a BitVector<U0>, which mustn't count towards its
+// length, or the offset of the field after it would point one byte too far
+#[test]
+fn test_empty_bit_vector_before_variable_field() {
+    let value = EmptyBitsFirst {
+        bits: BitVector::new(),
+        items: VariableList::new(vec![1, 2]).unwrap(),
+    };
+    assert_eq!(value.bits.sszb_bytes_len(), 0);
+    assert_eq!(value.sszb_bytes_len(), 4 + 16);
+
+    let bytes = value.to_ssz();
+    assert_eq!(&bytes[..4], [4, 0, 0, 0]);
+    assert_eq!(EmptyBitsFirst::field_range(&bytes, 1), Ok(4..20));
+    assert_eq!(EmptyBitsFirst::decode_field_items(&bytes), Ok(value.items));
+}
+
+#[test]
+fn test_empty_schemas() {
+    for schema in [
+        SszTypeInfo::vector(SszTypeInfo::Uint(8), 0),
+        SszTypeInfo::vector(SszTypeInfo::Uint(1), 0),
+        SszTypeInfo::vector(SszTypeInfo::list(SszTypeInfo::Uint(1), 4), 0),
+        SszTypeInfo::Bitvector(0),
+    ] {
+        assert_eq!(
+            decode_dynamic(&schema, &[]).unwrap_err().root_cause(),
+            &DecodeError::ZeroLengthVector,
+            "{:?}",
+            schema
+        );
+    }
+    assert_eq!(SszTypeInfo::Bitvector(0).fixed_len(), 0);
+    assert!(decode_dynamic(&SszTypeInfo::list(SszTypeInfo::Uint(8), 0), &[]).is_ok());
+    assert!(decode_dynamic(&SszTypeInfo::Bitlist(0), &[1]).is_ok());
+}
@@ -0,0 +1,28 @@
+//! A `#![no_std]` consumer of `sszb`, built with `default-features = false`.
+//!
+//! Build it on its own so that the `std` feature isn't unified in from the rest of the
+//! workspace, e.g. `cargo build -p sszb_no_std --target wasm32-unknown-unknown`.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256};
+use bytes::buf::{Buf, BufMut};
+use sszb::{DecodeError, SszbDecode, SszbEncode};
+use sszb_derive::{SszbDecode, SszbEncode};
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct LightClientHeader {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub fee_recipient: Address,
+    pub parent_root: B256,
+    pub state_root: [u8; 32],
+    pub finalized: bool,
+}
+
+pub fn roundtrip(header: &LightClientHeader) -> Result<LightClientHeader, DecodeError> {
+    let bytes: Vec<u8> = header.to_ssz();
+    <LightClientHeader as SszbDecode>::from_ssz_bytes(&bytes)
+}
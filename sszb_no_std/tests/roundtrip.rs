@@ -0,0 +1,23 @@
+use alloy_primitives::{Address, B256};
+use sszb::{SszbDecode, SszbEncode};
+use sszb_no_std::{roundtrip, LightClientHeader};
+
+#[test]
+fn test_roundtrip() {
+    let header = LightClientHeader {
+        slot: 7,
+        proposer_index: 42,
+        fee_recipient: Address::repeat_byte(1),
+        parent_root: B256::repeat_byte(2),
+        state_root: [3; 32],
+        finalized: true,
+    };
+
+    assert_eq!(
+        <LightClientHeader as SszbEncode>::SSZ_FIXED_LEN,
+        8 + 8 + 20 + 32 + 32 + 1
+    );
+    assert_eq!(header.to_ssz().len(), header.sszb_bytes_len());
+    assert_eq!(roundtrip(&header), Ok(header));
+    assert!(<LightClientHeader as SszbDecode>::from_ssz_bytes(&[0; 10]).is_err());
+}
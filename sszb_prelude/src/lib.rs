@@ -0,0 +1,21 @@
+//! A consumer of `sszb` that imports nothing but `sszb::prelude::*`, with the `derive` feature
+//! in place of a dependency on `sszb_derive`, to keep the prelude enough on its own.
+
+use sszb::prelude::*;
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Checkpoint {
+    pub epoch: u64,
+    pub root: [u8; 32],
+}
+
+#[derive(PartialEq, Debug, SszbEncode, SszbDecode)]
+pub struct Vote {
+    pub source: Checkpoint,
+    pub target: Checkpoint,
+    pub signature: [u8; 96],
+}
+
+pub fn roundtrip<T: SszbEncode + SszbDecode>(value: &T) -> Result<T, DecodeError> {
+    T::from_ssz_bytes(&value.to_ssz())
+}
@@ -0,0 +1,42 @@
+use sszb::prelude::*;
+use sszb_prelude::{roundtrip, Checkpoint, Vote};
+
+#[test]
+fn test_roundtrip() {
+    let vote = Vote {
+        source: Checkpoint {
+            epoch: 1,
+            root: [1; 32],
+        },
+        target: Checkpoint {
+            epoch: 2,
+            root: [2; 32],
+        },
+        signature: [3; 96],
+    };
+
+    assert_eq!(<Vote as SszbEncode>::SSZ_FIXED_LEN, 2 * (8 + 32) + 96);
+    assert_eq!(vote.to_ssz().len(), vote.sszb_bytes_len());
+    assert_eq!(roundtrip(&vote), Ok(vote));
+    assert!(matches!(
+        Vote::from_ssz_bytes(&[0; BYTES_PER_LENGTH_OFFSET]),
+        Err(DecodeError::InvalidByteLength { .. })
+    ));
+}
+
+// the earlier trait names are the same traits
+#[test]
+fn test_old_names() {
+    fn encode<T: sszb::SszEncode>(value: &T) -> Vec<u8> {
+        sszb::SszEncode::to_ssz(value)
+    }
+    fn decode<T: sszb::SszDecode>(bytes: &[u8]) -> Result<T, DecodeError> {
+        <T as SszbDecode>::from_ssz_bytes(bytes)
+    }
+
+    let checkpoint = Checkpoint {
+        epoch: 5,
+        root: [9; 32],
+    };
+    assert_eq!(decode::<Checkpoint>(&encode(&checkpoint)), Ok(checkpoint));
+}